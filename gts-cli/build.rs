@@ -0,0 +1,16 @@
+//! Generates the gRPC service from `proto/` when the `grpc` feature is on.
+
+#[cfg(feature = "grpc")]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Vendored so building the feature needs no system `protoc`
+    let mut config = prost_build::Config::new();
+    config.protoc_executable(protoc_bin_vendored::protoc_bin_path()?);
+    tonic_prost_build::configure()
+        .build_client(true)
+        .build_server(true)
+        .compile_with_config(config, &["proto/gts/v1/gts.proto"], &["proto"])?;
+    Ok(())
+}
+
+#[cfg(not(feature = "grpc"))]
+fn main() {}
@@ -0,0 +1,300 @@
+//! Error envelope returned by the HTTP server.
+//!
+//! Operation results (`/validate-id`, `/cast`, ...) keep their own `ok`/`error`
+//! fields as defined by the GTS spec. Failures of the request itself —
+//! malformed bodies or query strings, unknown routes, unusable server state —
+//! and failed lookups and validations of the registry endpoints
+//! (`/entities/{id}`, `/schemas`, `/instances`, `/validate`) are reported as
+//! `{code, message, details[], gts_id?}` with a 4xx/5xx status, so clients can
+//! branch on `code`.
+
+use axum::{
+    Json,
+    extract::{
+        FromRequest, FromRequestParts, Path, Query, Request,
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+    },
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+use gts::{GtsError, RetiredIdsError, StoreError};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Machine-readable error code of an [`ApiError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiErrorCode {
+    /// The request body, query string or path could not be decoded.
+    BadRequest,
+    /// A GTS identifier is syntactically invalid.
+    InvalidGtsId,
+    /// A GTS wildcard or query pattern is invalid.
+    InvalidPattern,
+    /// A GTS identifier was retired and must not be reused.
+    RetiredId,
+    /// The route or a referenced entity does not exist.
+    NotFound,
+    /// An entity or schema is malformed (missing or wrong kind of ID).
+    InvalidEntity,
+    /// Validation against a schema or reference failed.
+    ValidationFailed,
+    /// An instance could not be cast to the target schema.
+    CastFailed,
+    /// The server failed to process the request.
+    Internal,
+}
+
+impl ApiErrorCode {
+    /// HTTP status used for this code.
+    #[must_use]
+    pub fn status(self) -> StatusCode {
+        match self {
+            Self::BadRequest | Self::InvalidGtsId | Self::InvalidPattern => StatusCode::BAD_REQUEST,
+            Self::RetiredId => StatusCode::GONE,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::InvalidEntity | Self::ValidationFailed | Self::CastFailed => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Self::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Error body returned by the server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiError {
+    pub code: ApiErrorCode,
+    pub message: String,
+    /// Additional human-readable details (e.g. the failing segment).
+    pub details: Vec<String>,
+    /// The GTS ID the error is about, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gts_id: Option<String>,
+    /// Overrides [`ApiErrorCode::status`], e.g. to keep an extractor's status.
+    #[serde(skip)]
+    status: Option<StatusCode>,
+}
+
+impl ApiError {
+    #[must_use]
+    pub fn new(code: ApiErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: Vec::new(),
+            gts_id: None,
+            status: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_gts_id(mut self, gts_id: impl Into<String>) -> Self {
+        self.gts_id = Some(gts_id.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.details.push(detail.into());
+        self
+    }
+
+    /// HTTP status of the response.
+    #[must_use]
+    pub fn status(&self) -> StatusCode {
+        self.status.unwrap_or_else(|| self.code.status())
+    }
+
+    /// Error for requests that match no route.
+    #[must_use]
+    pub fn route_not_found(path: &str) -> Self {
+        Self::new(ApiErrorCode::NotFound, format!("No route for '{path}'"))
+    }
+
+    fn rejection(status: StatusCode, body_text: String) -> Self {
+        let mut error = Self::new(ApiErrorCode::BadRequest, body_text);
+        error.status = Some(status);
+        error
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status(), Json(self)).into_response()
+    }
+}
+
+impl From<&GtsError> for ApiError {
+    fn from(err: &GtsError) -> Self {
+        match err {
+            GtsError::Segment {
+                num,
+                segment,
+                cause,
+                ..
+            } => Self::new(ApiErrorCode::InvalidGtsId, err.to_string())
+                .with_detail(format!("segment #{num} '{segment}': {cause}")),
+            GtsError::Id { id, cause } => Self::new(ApiErrorCode::InvalidGtsId, err.to_string())
+                .with_detail(cause.clone())
+                .with_gts_id(id.clone()),
+            GtsError::Wildcard { pattern, cause } => {
+                Self::new(ApiErrorCode::InvalidPattern, err.to_string())
+                    .with_detail(cause.clone())
+                    .with_gts_id(pattern.clone())
+            }
+            GtsError::Field(cause) => Self::new(ApiErrorCode::InvalidGtsId, err.to_string())
+                .with_detail(cause.to_string()),
+        }
+    }
+}
+
+impl From<&StoreError> for ApiError {
+    fn from(err: &StoreError) -> Self {
+        let message = err.to_string();
+        match err {
+            StoreError::ObjectNotFound(id)
+            | StoreError::SchemaNotFound(id)
+            | StoreError::EntityNotFound(id)
+            | StoreError::SchemaForInstanceNotFound(id)
+            | StoreError::NoPatternMatches(id) => {
+                Self::new(ApiErrorCode::NotFound, message).with_gts_id(id.clone())
+            }
+            StoreError::CastFromSchemaNotAllowed(id) => {
+                Self::new(ApiErrorCode::CastFailed, message).with_gts_id(id.clone())
+            }
+            StoreError::InvalidEntity | StoreError::InvalidSchemaId => {
+                Self::new(ApiErrorCode::InvalidEntity, message)
+            }
+            StoreError::ValidationError(_) | StoreError::InvalidRef(_) => {
+                Self::new(ApiErrorCode::ValidationFailed, message)
+            }
+            StoreError::InvalidPattern(_) => Self::new(ApiErrorCode::InvalidPattern, message),
+            StoreError::RetiredIds(RetiredIdsError::Reused { id, reason, date }) => {
+                Self::new(ApiErrorCode::RetiredId, message)
+                    .with_detail(format!("Retired on {date}: {reason}"))
+                    .with_gts_id(id.clone())
+            }
+            StoreError::RetiredIds(_) => Self::new(ApiErrorCode::InvalidEntity, message),
+            StoreError::Storage(_) => Self::new(ApiErrorCode::Internal, message),
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self::rejection(rejection.status(), rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::rejection(rejection.status(), rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        Self::rejection(rejection.status(), rejection.body_text())
+    }
+}
+
+/// [`Json`] extractor that rejects with an [`ApiError`].
+pub struct ApiJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        Ok(Self(value))
+    }
+}
+
+/// [`Query`] extractor that rejects with an [`ApiError`].
+pub struct ApiQuery<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state).await?;
+        Ok(Self(value))
+    }
+}
+
+/// [`Path`] extractor that rejects with an [`ApiError`].
+pub struct ApiPath<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ApiPath<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Path(value) = Path::<T>::from_request_parts(parts, state).await?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_serialization() {
+        let error = ApiError::new(ApiErrorCode::NotFound, "missing")
+            .with_gts_id("gts.x.core.events.type.v1~")
+            .with_detail("checked the store");
+        assert_eq!(error.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "not_found",
+                "message": "missing",
+                "details": ["checked the store"],
+                "gts_id": "gts.x.core.events.type.v1~"
+            })
+        );
+
+        let bare = serde_json::to_value(ApiError::new(ApiErrorCode::Internal, "boom")).unwrap();
+        assert_eq!(bare["details"], serde_json::json!([]));
+        assert!(bare.get("gts_id").is_none());
+    }
+
+    #[test]
+    fn test_api_error_from_gts_error() {
+        let err = gts::GtsID::new("gts.x.core").unwrap_err();
+        let api: ApiError = (&err).into();
+        assert_eq!(api.code, ApiErrorCode::InvalidGtsId);
+        assert_eq!(api.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(api.details.len(), 1);
+
+        let err = gts::GtsWildcard::new("gts.x.*.core*").unwrap_err();
+        assert_eq!(ApiError::from(&err).code, ApiErrorCode::InvalidPattern);
+    }
+
+    #[test]
+    fn test_api_error_from_store_error() {
+        let api = ApiError::from(&StoreError::SchemaNotFound("gts.x.a.b.c.v1~".to_owned()));
+        assert_eq!(api.code, ApiErrorCode::NotFound);
+        assert_eq!(api.gts_id.as_deref(), Some("gts.x.a.b.c.v1~"));
+
+        let api = ApiError::from(&StoreError::ValidationError("bad".to_owned()));
+        assert_eq!(api.code, ApiErrorCode::ValidationFailed);
+        assert_eq!(api.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            ApiError::from(&StoreError::InvalidSchemaId).code,
+            ApiErrorCode::InvalidEntity
+        );
+    }
+}
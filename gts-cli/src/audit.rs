@@ -0,0 +1,608 @@
+//! `gts audit`: every repository check in one pass.
+//!
+//! Runs documentation validation, store lint, reference integrity, the
+//! generated-schema sync check and backward compatibility against an earlier
+//! git ref, all on one loaded store, and folds the outcome into a single exit
+//! code (see [`AuditReport::exit_code`]). With a time budget, checks that have
+//! not started when it runs out are skipped and the run fails.
+
+use anyhow::{Context, Result, bail};
+use gts::{GtsFileNameEncoding, GtsOps, PartitionKey, check_store_evolution};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::gen_schemas::check_generated_schemas;
+
+/// Exit code bit of a failing `docs` check
+pub const EXIT_DOCS: i32 = 4;
+/// Exit code bit of a failing `lint` check
+pub const EXIT_LINT: i32 = 8;
+/// Exit code bit of a failing `refs` check
+pub const EXIT_REFS: i32 = 16;
+/// Exit code bit of a failing `sync` check
+pub const EXIT_SYNC: i32 = 32;
+/// Exit code bit of a failing `compat` check
+pub const EXIT_COMPAT: i32 = 64;
+/// Exit code bit set when the time budget skipped a check
+pub const EXIT_TIME_BUDGET: i32 = 128;
+
+/// Findings listed per check in the table; JSON lists all of them
+const TABLE_FINDINGS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditStatus {
+    Pass,
+    Fail,
+    Skipped,
+}
+
+impl AuditStatus {
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Fail => "fail",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditCheck {
+    pub name: &'static str,
+    pub status: AuditStatus,
+    pub message: String,
+    /// One line per finding
+    pub findings: Vec<String>,
+    pub elapsed_ms: u64,
+    /// Skipped because the time budget ran out
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub over_budget: bool,
+}
+
+impl AuditCheck {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: AuditStatus::Pass,
+            message: message.into(),
+            findings: Vec::new(),
+            elapsed_ms: 0,
+            over_budget: false,
+        }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>, findings: Vec<String>) -> Self {
+        Self {
+            name,
+            status: AuditStatus::Fail,
+            message: message.into(),
+            findings,
+            elapsed_ms: 0,
+            over_budget: false,
+        }
+    }
+
+    fn skipped(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: AuditStatus::Skipped,
+            message: message.into(),
+            findings: Vec::new(),
+            elapsed_ms: 0,
+            over_budget: false,
+        }
+    }
+
+    /// Pass without findings, fail with them
+    fn from_findings(name: &'static str, findings: Vec<String>, ok: impl Into<String>) -> Self {
+        if findings.is_empty() {
+            Self::pass(name, ok)
+        } else {
+            Self::fail(name, format!("{} finding(s)", findings.len()), findings)
+        }
+    }
+
+    /// Exit code bit of this check when it fails
+    #[must_use]
+    pub fn exit_bit(&self) -> i32 {
+        match self.name {
+            "docs" => EXIT_DOCS,
+            "lint" => EXIT_LINT,
+            "refs" => EXIT_REFS,
+            "sync" => EXIT_SYNC,
+            "compat" => EXIT_COMPAT,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditReport {
+    pub ok: bool,
+    /// 0 when every check passed, otherwise the OR of [`EXIT_DOCS`],
+    /// [`EXIT_LINT`], [`EXIT_REFS`], [`EXIT_SYNC`] and [`EXIT_COMPAT`] for the
+    /// failed checks and [`EXIT_TIME_BUDGET`] if a check was skipped for time
+    pub exit_code: i32,
+    pub elapsed_ms: u64,
+    pub checks: Vec<AuditCheck>,
+}
+
+impl AuditReport {
+    #[must_use]
+    pub fn new(checks: Vec<AuditCheck>, elapsed: Duration) -> Self {
+        let exit_code = checks.iter().fold(0, |code, check| {
+            if check.over_budget {
+                code | EXIT_TIME_BUDGET
+            } else if check.status == AuditStatus::Fail {
+                code | check.exit_bit()
+            } else {
+                code
+            }
+        });
+        Self {
+            ok: exit_code == 0,
+            exit_code,
+            elapsed_ms: millis(elapsed),
+            checks,
+        }
+    }
+
+    /// Plain-text table, with the first findings below each failing row
+    #[must_use]
+    pub fn to_table(&self) -> String {
+        let width = self.checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+        let mut out = format!(
+            "{:<8}{:<width$}  {:>8}  MESSAGE\n",
+            "STATUS", "CHECK", "TIME"
+        );
+        for check in &self.checks {
+            let _ = writeln!(
+                out,
+                "{:<8}{:<width$}  {:>6}ms  {}",
+                check.status.label(),
+                check.name,
+                check.elapsed_ms,
+                check.message
+            );
+            for finding in check.findings.iter().take(TABLE_FINDINGS) {
+                let _ = writeln!(out, "{:<8}{:<width$}  {:>8}  - {finding}", "", "", "");
+            }
+            if check.findings.len() > TABLE_FINDINGS {
+                let _ = writeln!(
+                    out,
+                    "{:<8}{:<width$}  {:>8}  ... and {} more",
+                    "",
+                    "",
+                    "",
+                    check.findings.len() - TABLE_FINDINGS
+                );
+            }
+        }
+        let _ = writeln!(out, "exit code {} in {}ms", self.exit_code, self.elapsed_ms);
+        out
+    }
+}
+
+/// Returned by `gts audit` when a check failed, carrying the exit code
+#[derive(Debug)]
+pub struct AuditFailed {
+    pub exit_code: i32,
+    pub failed: Vec<&'static str>,
+}
+
+impl std::fmt::Display for AuditFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "audit failed (exit code {}): {}",
+            self.exit_code,
+            self.failed.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for AuditFailed {}
+
+/// What `gts audit` looks at
+#[derive(Debug, Clone, Default)]
+pub struct AuditOptions {
+    /// Documentation roots for the validator; missing roots are ignored
+    pub docs: Vec<PathBuf>,
+    /// Vendor every GTS ID in the docs must use
+    pub vendor: Option<String>,
+    /// The store directory, also read at `since` for the compatibility check
+    pub store: Option<String>,
+    pub config: Option<String>,
+    pub require_owner: Vec<String>,
+    pub encoding: GtsFileNameEncoding,
+    /// Root of the annotated Rust sources
+    pub source: PathBuf,
+    pub exclude: Vec<String>,
+    /// Git ref whose schemas the current ones must stay compatible with
+    pub since: Option<String>,
+    /// Keeps the validator cache and the store snapshots of `since` between runs
+    pub cache_dir: Option<PathBuf>,
+    pub time_budget: Option<Duration>,
+}
+
+/// Run every check against `ops`, whose store is loaded from `options.store`
+#[must_use]
+pub fn run_audit(ops: &GtsOps, options: &AuditOptions) -> AuditReport {
+    type Check<'a> = (&'static str, Box<dyn Fn() -> AuditCheck + 'a>);
+    let checks: [Check<'_>; 5] = [
+        ("docs", Box::new(|| check_docs(options))),
+        ("lint", Box::new(|| check_lint(ops, options))),
+        ("refs", Box::new(|| check_refs(ops))),
+        ("sync", Box::new(|| check_sync(options))),
+        ("compat", Box::new(|| check_compat(ops, options))),
+    ];
+
+    let start = Instant::now();
+    let results = checks
+        .iter()
+        .map(|(name, check)| {
+            if let Some(budget) = options.time_budget
+                && start.elapsed() >= budget
+            {
+                let mut check = AuditCheck::skipped(
+                    name,
+                    format!("time budget of {}s exhausted", budget.as_secs_f64()),
+                );
+                check.over_budget = true;
+                return check;
+            }
+            let check_start = Instant::now();
+            let mut check = check();
+            check.elapsed_ms = millis(check_start.elapsed());
+            check
+        })
+        .collect();
+    AuditReport::new(results, start.elapsed())
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+fn check_docs(options: &AuditOptions) -> AuditCheck {
+    const NAME: &str = "docs";
+    let paths: Vec<PathBuf> = options
+        .docs
+        .iter()
+        .filter(|p| p.exists())
+        .cloned()
+        .collect();
+    if paths.is_empty() {
+        return AuditCheck::skipped(NAME, "no documentation paths found");
+    }
+    let mut fs_config = gts_validator::FsSourceConfig::default();
+    fs_config.paths = paths;
+    fs_config.cache = options
+        .cache_dir
+        .as_ref()
+        .map(|dir| dir.join("validator.json"));
+    let mut config = gts_validator::ValidationConfig::default();
+    if let Some(vendor) = &options.vendor {
+        config.vendor_policy = gts_validator::VendorPolicy::MustMatch(vendor.clone());
+    }
+    let report = match gts_validator::validate_fs(&fs_config, &config) {
+        Ok(report) => report,
+        Err(e) => return AuditCheck::fail(NAME, format!("{e:#}"), Vec::new()),
+    };
+    let findings = report
+        .validation_errors
+        .iter()
+        .filter(|e| e.fails_run())
+        .map(gts_validator::ValidationError::format_human_readable)
+        .chain(
+            report
+                .scan_errors
+                .iter()
+                .map(gts_validator::ScanError::format_human_readable),
+        )
+        .collect();
+    AuditCheck::from_findings(
+        NAME,
+        findings,
+        format!("{} file(s) valid", report.scanned_files),
+    )
+}
+
+fn check_lint(ops: &GtsOps, options: &AuditOptions) -> AuditCheck {
+    const NAME: &str = "lint";
+    let result = ops.lint_store_with(&options.require_owner, options.encoding);
+    if !result.error.is_empty() {
+        return AuditCheck::fail(NAME, result.error, Vec::new());
+    }
+    let findings = result
+        .file_name_mismatches
+        .iter()
+        .map(|m| format!("{}: file name does not match {}", m.path, m.entity_id))
+        .chain(
+            result
+                .missing_owners
+                .iter()
+                .map(|id| format!("{id}: missing x-gts-owner")),
+        )
+        .chain(
+            result
+                .invalid_metadata
+                .iter()
+                .map(|issue| format!("{}: {}", issue.id, issue.error)),
+        )
+        .collect();
+    AuditCheck::from_findings(NAME, findings, "store is clean")
+}
+
+fn check_refs(ops: &GtsOps) -> AuditCheck {
+    const NAME: &str = "refs";
+    let report = match ops.store.partition(&PartitionKey::Vendor) {
+        Ok(partitioning) => partitioning.report,
+        Err(e) => return AuditCheck::fail(NAME, e.to_string(), Vec::new()),
+    };
+    let findings = report
+        .dangling
+        .iter()
+        .map(|r| format!("{} ({}): unresolved reference to {}", r.from, r.path, r.to))
+        .collect();
+    AuditCheck::from_findings(NAME, findings, "every reference resolves")
+}
+
+fn check_sync(options: &AuditOptions) -> AuditCheck {
+    const NAME: &str = "sync";
+    let status = match check_generated_schemas(
+        &options.source.to_string_lossy(),
+        None,
+        &options.exclude,
+        options.encoding,
+    ) {
+        Ok(status) => status,
+        Err(e) => return AuditCheck::skipped(NAME, format!("{e:#}")),
+    };
+    let findings = status
+        .unparsable
+        .iter()
+        .map(|s| format!("{s}: annotation could not be parsed"))
+        .chain(status.missing.iter().map(|f| format!("{f}: missing")))
+        .chain(status.stale.iter().map(|f| format!("{f}: stale")))
+        .collect();
+    AuditCheck::from_findings(
+        NAME,
+        findings,
+        format!(
+            "{} annotated struct(s), generated schemas in sync",
+            status.annotations
+        ),
+    )
+}
+
+fn check_compat(ops: &GtsOps, options: &AuditOptions) -> AuditCheck {
+    const NAME: &str = "compat";
+    let Some(since) = &options.since else {
+        return AuditCheck::skipped(NAME, "no --since ref");
+    };
+    let Some(store) = &options.store else {
+        return AuditCheck::skipped(NAME, "no schema path configured");
+    };
+    let previous = match snapshot_at(Path::new(store), since, options.cache_dir.as_deref()) {
+        Ok(previous) => previous,
+        Err(e) => return AuditCheck::fail(NAME, format!("{e:#}"), Vec::new()),
+    };
+    let old = GtsOps::new(
+        Some(vec![previous.dir.to_string_lossy().into_owned()]),
+        options.config.clone(),
+        0,
+    );
+    drop(previous);
+
+    let report = check_store_evolution(&old.store, &ops.store);
+    let findings = report.incompatible().map(ToString::to_string).collect();
+    AuditCheck::from_findings(
+        NAME,
+        findings,
+        format!(
+            "{} schema evolution(s) backward compatible with {since}",
+            report.evolutions.len()
+        ),
+    )
+}
+
+/// The store directory as of a git ref, extracted to disk
+pub struct Snapshot {
+    pub dir: PathBuf,
+    /// Removed on drop; snapshots in the cache directory are kept
+    temporary: bool,
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+/// Extract the files under `store` at git ref `since`, reusing a snapshot of
+/// the same commit in `cache_dir`
+///
+/// # Errors
+/// Returns an error if `since` does not resolve to a commit or git fails.
+pub fn snapshot_at(store: &Path, since: &str, cache_dir: Option<&Path>) -> Result<Snapshot> {
+    let commit = git(
+        store,
+        &["rev-parse", "--verify", &format!("{since}^{{commit}}")],
+    )
+    .with_context(|| format!("cannot resolve git ref '{since}'"))?;
+    let commit = String::from_utf8_lossy(&commit).trim().to_owned();
+    let snapshot = match cache_dir {
+        Some(cache_dir) => Snapshot {
+            dir: cache_dir.join("refs").join(&commit),
+            temporary: false,
+        },
+        None => Snapshot {
+            dir: std::env::temp_dir().join(format!("gts-audit-{}-{commit}", std::process::id())),
+            temporary: true,
+        },
+    };
+    if snapshot.dir.is_dir() && !snapshot.temporary {
+        return Ok(snapshot);
+    }
+
+    // Extract next to the final directory so an interrupted run leaves no
+    // partial snapshot behind in the cache
+    let partial = snapshot.dir.with_extension("partial");
+    let _ = fs::remove_dir_all(&partial);
+    let files = git(store, &["ls-tree", "-r", "--name-only", &commit, "--", "."])?;
+    for file in String::from_utf8_lossy(&files).lines() {
+        let content = git(store, &["show", &format!("{commit}:./{file}")])?;
+        let target = partial.join(file);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, content)?;
+    }
+    fs::create_dir_all(&partial)?;
+    fs::rename(&partial, &snapshot.dir)
+        .with_context(|| format!("cannot write {}", snapshot.dir.display()))?;
+    Ok(snapshot)
+}
+
+/// Stdout of `git <args>` run in `dir`
+fn git(dir: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("cannot run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gts::test_support::schema_json;
+    use tempfile::TempDir;
+
+    const USER: &str = "gts.x.app.entities.user.v1~";
+
+    fn git_in(dir: &Path, args: &[&str]) {
+        git(dir, args).unwrap();
+    }
+
+    #[test]
+    fn test_exit_code_matrix() {
+        let mut over_budget = AuditCheck::skipped("compat", "time budget exhausted");
+        over_budget.over_budget = true;
+        let report = AuditReport::new(
+            vec![
+                AuditCheck::fail("docs", "1 finding(s)", vec!["a".to_owned()]),
+                AuditCheck::pass("lint", "store is clean"),
+                AuditCheck::fail("sync", "1 finding(s)", vec!["b".to_owned()]),
+                AuditCheck::skipped("refs", "not run"),
+                over_budget,
+            ],
+            Duration::ZERO,
+        );
+        assert!(!report.ok);
+        assert_eq!(report.exit_code, EXIT_DOCS | EXIT_SYNC | EXIT_TIME_BUDGET);
+
+        let report = AuditReport::new(
+            vec![AuditCheck::skipped("compat", "no --since ref")],
+            Duration::ZERO,
+        );
+        assert!(report.ok);
+        assert_eq!(report.exit_code, 0);
+    }
+
+    #[test]
+    fn test_run_audit_time_budget() {
+        let dir = TempDir::new().unwrap();
+        let ops = GtsOps::new(None, None, 0);
+        let report = run_audit(
+            &ops,
+            &AuditOptions {
+                source: dir.path().to_path_buf(),
+                time_budget: Some(Duration::ZERO),
+                ..AuditOptions::default()
+            },
+        );
+        assert_eq!(report.exit_code, EXIT_TIME_BUDGET);
+        assert!(
+            report
+                .checks
+                .iter()
+                .all(|c| c.status == AuditStatus::Skipped && c.over_budget)
+        );
+    }
+
+    #[test]
+    fn test_run_audit_compat_since_ref() {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path();
+        let store = repo.join("schemas");
+        fs::create_dir(&store).unwrap();
+        let file = store.join("gts.x.app.entities.user.v1~.schema.json");
+        fs::write(&file, schema_json(USER, &["id"]).to_string()).unwrap();
+        git_in(repo, &["init", "-q"]);
+        git_in(repo, &["add", "."]);
+        git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@example.com",
+                "commit",
+                "-q",
+                "-m",
+                "schemas",
+            ],
+        );
+        // A new required property breaks readers of older instances
+        fs::write(&file, schema_json(USER, &["id", "name"]).to_string()).unwrap();
+
+        let store = store.to_string_lossy().into_owned();
+        let ops = GtsOps::new(Some(vec![store.clone()]), None, 0);
+        let cache = repo.join("cache");
+        let options = AuditOptions {
+            docs: vec![repo.join("docs")],
+            store: Some(store),
+            source: repo.join("src"),
+            since: Some("HEAD".to_owned()),
+            cache_dir: Some(cache.clone()),
+            ..AuditOptions::default()
+        };
+        let report = run_audit(&ops, &options);
+
+        let status = |name: &str| {
+            report
+                .checks
+                .iter()
+                .find(|c| c.name == name)
+                .map(|c| c.status)
+        };
+        assert_eq!(status("docs"), Some(AuditStatus::Skipped));
+        assert_eq!(status("lint"), Some(AuditStatus::Pass));
+        assert_eq!(status("refs"), Some(AuditStatus::Pass));
+        assert_eq!(status("compat"), Some(AuditStatus::Fail));
+        assert_eq!(report.exit_code, EXIT_COMPAT);
+        assert!(report.to_table().contains("gts.x.app.entities.user.v1~"));
+        // The snapshot of HEAD is kept for the next run
+        assert_eq!(fs::read_dir(cache.join("refs")).unwrap().count(), 1);
+        assert_eq!(run_audit(&ops, &options).exit_code, EXIT_COMPAT);
+    }
+}
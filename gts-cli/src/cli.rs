@@ -1,10 +1,26 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
-use gts::GtsOps;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use gts::{GtsFileNameEncoding, GtsOps, check_store_evolution};
 use std::io::Write;
 
-use crate::gen_schemas::generate_schemas_from_rust;
+use crate::audit::{AuditFailed, AuditOptions, AuditStatus, run_audit, snapshot_at};
+use crate::completion::GtsCompletionServer;
+use crate::coverage::{CoverageOptions, run_coverage};
+use crate::doctor::{DoctorOptions, run_doctor};
+use crate::export::{infra_manifest, partition_store, write_infra_manifest, write_partitions};
+use crate::gen_schemas::{
+    GenerateOptions, SchemaIdFilter, clean_generated_schemas, find_schema_collisions,
+    generate_schemas_from_rust,
+};
+use crate::import::{ImportMapping, ImportOptions, run_import};
+use crate::lint::{LintOptions, run_lint};
+use crate::new_schema::run_new_schema;
+use crate::progress::{ProgressFormat, ProgressReporter};
+use crate::project_config::ProjectConfig;
+use crate::replay::{render_replay_report, run_replay};
+use crate::report::{ReportFormat, write_cast_report, write_diff_report};
 use crate::server::GtsHttpServer;
+use crate::server_config::ServerConfig;
 
 #[derive(Parser)]
 #[command(name = "gts")]
@@ -22,6 +38,16 @@ pub struct Cli {
     #[arg(long)]
     pub path: Option<String>,
 
+    /// Project config file; by default `gts.toml` is looked up in the current
+    /// directory and its ancestors
+    #[arg(long)]
+    pub project_config: Option<String>,
+
+    /// How GTS IDs are encoded in generated and checked schema file names
+    /// (default: raw)
+    #[arg(long, value_name = "raw|percent|hashed")]
+    pub file_name_encoding: Option<GtsFileNameEncoding>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -72,12 +98,59 @@ pub enum Commands {
         #[arg(long)]
         gts_id: String,
     },
+    /// Diff two instances of the same type property by property
+    DiffInstances {
+        #[arg(long)]
+        id_a: String,
+        #[arg(long)]
+        id_b: String,
+    },
+    /// Diff two versions of a schema and classify the change as major, minor
+    /// or patch
+    Diff {
+        /// Old version: a schema file or a schema ID in the store
+        old_schema: String,
+        /// New version: a schema file or a schema ID in the store
+        new_schema: String,
+        /// Also write a Markdown (or HTML for `.html`) report to this file
+        #[arg(long)]
+        report: Option<String>,
+    },
+    /// Merge the ~ chain of a schema into one schema, reporting conflicts
+    FlattenChain {
+        #[arg(long)]
+        schema_id: String,
+    },
+    /// Generate random valid instances of a schema (e.g. as test fixtures)
+    GenExamples {
+        #[arg(long)]
+        schema_id: String,
+        #[arg(long, default_value = "10")]
+        count: usize,
+        /// The same seed always yields the same examples
+        #[arg(long, default_value = "0")]
+        seed: u64,
+    },
+    /// Generate instances of a schema that each violate one constraint,
+    /// annotated with the expected violation
+    GenInvalidExamples {
+        #[arg(long)]
+        schema_id: String,
+        #[arg(long, default_value = "10")]
+        count: usize,
+        /// The same seed always yields the same examples
+        #[arg(long, default_value = "0")]
+        seed: u64,
+    },
     /// Check compatibility between two schemas
     Compatibility {
         #[arg(long)]
         old_schema_id: String,
         #[arg(long)]
         new_schema_id: String,
+        /// Also write a Markdown (or HTML for `.html`) report to this file
+        #[arg(long)]
+        report: Option<String>,
     },
     /// Cast an instance or schema to a target schema
     Cast {
@@ -85,6 +158,9 @@ pub enum Commands {
         from_id: String,
         #[arg(long)]
         to_schema_id: String,
+        /// Also write a Markdown (or HTML for `.html`) report to this file
+        #[arg(long)]
+        report: Option<String>,
     },
     /// Query entities using an expression
     Query {
@@ -92,6 +168,9 @@ pub enum Commands {
         expr: String,
         #[arg(long, default_value = "100")]
         limit: usize,
+        /// Explain why each result matched
+        #[arg(long)]
+        explain: bool,
     },
     /// Get attribute value from a GTS entity
     Attr {
@@ -103,13 +182,82 @@ pub enum Commands {
         #[arg(long, default_value = "100")]
         limit: usize,
     },
+    /// Expand a wildcard pattern into all matching IDs in the store
+    ExpandPattern {
+        #[arg(long)]
+        pattern: String,
+        /// Report an error if the pattern matches no IDs
+        #[arg(long)]
+        require_match: bool,
+    },
+    /// Check that file names match the embedded `$id`/`id` of each entity
+    /// and that schema ownership metadata is well-formed
+    LintStore {
+        /// Require `x-gts-owner` on schemas matching this wildcard pattern
+        /// (repeatable; default: `[lint] require-owner` of gts.toml)
+        #[arg(long, value_name = "PATTERN")]
+        require_owner: Vec<String>,
+    },
+    /// Show store statistics: entity counts, last load time and load errors
+    StoreInfo,
+    /// Run the ID conformance vectors against this build; fails if any disagrees
+    Conformance {
+        /// Directory of YAML vector files
+        #[arg(long, default_value = "conformance/vectors")]
+        vectors: String,
+    },
+    /// Diagnose common setup problems: project/GTS config, schema loading,
+    /// annotations and generated schemas, server port and gts crate versions
+    Doctor {
+        /// Root of the Rust sources with `#[struct_to_gts_schema]` annotations
+        #[arg(long, default_value = ".")]
+        source: String,
+        /// Server address whose port should be free
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        #[arg(long, default_value = "8000")]
+        port: u16,
+        #[arg(long, value_enum, default_value_t = DoctorFormat::Table)]
+        format: DoctorFormat,
+    },
+    /// Export data derived from the store
+    Export {
+        #[command(subcommand)]
+        target: ExportTarget,
+    },
+    /// Interactively create a new schema (and optionally a Rust struct skeleton)
+    NewSchema {
+        /// Directory to write the `.schema.json` file to
+        #[arg(long, default_value = ".")]
+        out: String,
+        /// Also write an annotated `#[struct_to_gts_schema]` struct skeleton to this file
+        #[arg(long)]
+        rust: Option<String>,
+    },
     /// Start the GTS HTTP server
     Server {
         #[arg(long, default_value = "127.0.0.1")]
         host: String,
         #[arg(long, default_value = "8000")]
         port: u16,
+        /// YAML/JSON file with `path` and `config`, re-read on SIGHUP; unset
+        /// settings fall back to the global `--path`/`--config`
+        #[arg(long)]
+        server_config: Option<String>,
+        /// Also re-read `--server-config` every N seconds
+        #[arg(long, value_name = "SECS", requires = "server_config")]
+        reload_interval: Option<u64>,
+        /// Files loaded in parallel while the store loads in the background
+        /// (default: number of CPUs)
+        #[arg(long, value_name = "N")]
+        load_concurrency: Option<usize>,
+        /// Also serve the gRPC API on this port
+        #[cfg(feature = "grpc")]
+        #[arg(long, value_name = "PORT")]
+        grpc_port: Option<u16>,
     },
+    /// Serve line-oriented JSON-RPC (`validate_text`, `complete_id`) on stdin/stdout for editors
+    CompletionServer,
     /// Generate `OpenAPI` specification
     OpenapiSpec {
         #[arg(long)]
@@ -131,8 +279,335 @@ pub enum Commands {
         /// Example: --exclude "tests/*" --exclude "examples/*"
         #[arg(long, action = clap::ArgAction::Append)]
         exclude: Vec<String>,
+        /// Only generate schemas whose ID matches one of these GTS wildcard patterns
+        /// (can be specified multiple times). Example: --only 'gts.x.core.events.*'
+        #[arg(long, action = clap::ArgAction::Append)]
+        only: Vec<String>,
+        /// Skip schemas whose ID matches one of these GTS wildcard patterns
+        /// (can be specified multiple times)
+        #[arg(long, action = clap::ArgAction::Append)]
+        skip_pattern: Vec<String>,
+        /// Write progress events to stderr (`json`: one JSON object per line)
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        progress: Option<ProgressFormat>,
+        /// Write nothing; fail if any schema file would be added, changed or removed
+        #[arg(long)]
+        check: bool,
+    },
+    /// Remove generated schema files that no annotated struct emits any more
+    Clean(CleanArgs),
+    /// Report payload-like structs without `#[struct_to_gts_schema]` and
+    /// schemas in the store without an annotated struct
+    Coverage(CoverageArgs),
+    /// Convert `OpenAPI` components or a directory of JSON Schemas into GTS
+    /// schemas, reporting constructs that cannot be carried over
+    Import(ImportArgs),
+    /// Validate GTS identifiers in .md/.rst/.adoc/.json/.yaml/.yml files
+    Validate(ValidateArgs),
+    /// Lint GTS identifiers in .md/.rst/.adoc/.json/.yaml/.yml files: the checks of
+    /// `validate` plus style rules, with a suggested fix for each finding
+    Lint(LintArgs),
+    /// Run docs validation, store lint, reference integrity, the generated
+    /// schema sync check and compatibility with `--since` in one pass; the
+    /// exit code ORs 4 (docs), 8 (lint), 16 (refs), 32 (sync), 64 (compat)
+    /// and 128 (time budget exhausted)
+    Audit(AuditArgs),
+    /// Validate historical payloads against a candidate schema version and
+    /// summarize the failure rate per error type
+    Replay(ReplayArgs),
+    /// Pair the schemas of a directory with an earlier version by GTS ID and
+    /// fail if a changed schema or new minor version breaks backward
+    /// compatibility, or a schema was removed
+    CompatCheck(CompatCheckArgs),
+    /// Fail if annotated structs of more than one crate under `--source` emit
+    /// the same schema ID, naming the crates and whether the content diverges
+    CheckCollisions(CheckCollisionsArgs),
+}
+
+#[derive(clap::Args)]
+pub struct CoverageArgs {
+    /// Source directory or file to scan
+    #[arg(long)]
+    source: String,
+    /// Schemas to match against the annotations (default: the global `--path`)
+    #[arg(long)]
+    store: Option<String>,
+    /// Exclude patterns (can be specified multiple times); defaults to the
+    /// `generate-from-rust` excludes of the project config
+    #[arg(long, action = clap::ArgAction::Append)]
+    exclude: Vec<String>,
+    /// Report every unannotated struct deriving `Serialize`/`Deserialize`,
+    /// not only those named like events and configs
+    #[arg(long)]
+    all_serde: bool,
+    /// Fail if fewer than this percentage of payload-like structs are annotated
+    #[arg(long, value_name = "PERCENT")]
+    min_coverage: Option<f64>,
+}
+
+#[derive(clap::Args)]
+pub struct AuditArgs {
+    /// Documentation roots to validate (repeatable); missing ones are ignored
+    #[arg(long = "docs", value_name = "PATH", default_values_t = ["docs".to_owned()])]
+    docs: Vec<String>,
+    /// Vendor every GTS ID in the docs must use
+    #[arg(long)]
+    vendor: Option<String>,
+    /// Root of the Rust sources with `#[struct_to_gts_schema]` annotations
+    #[arg(long, default_value = ".")]
+    source: String,
+    /// Git ref whose schemas must stay backward compatible (skipped if unset)
+    #[arg(long, value_name = "REF")]
+    since: Option<String>,
+    /// Keep the validator cache and `--since` store snapshots here between runs
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<String>,
+    /// Skip the checks not started after this many seconds (the run fails)
+    #[arg(long, value_name = "SECS")]
+    time_budget: Option<u64>,
+    #[arg(long, value_enum, default_value_t = DoctorFormat::Table)]
+    format: DoctorFormat,
+}
+
+#[derive(clap::Args)]
+pub struct ReplayArgs {
+    /// Candidate schema file; its `gts://` references resolve to the global `--path`
+    #[arg(long, value_name = "FILE")]
+    schema: String,
+    /// Directory or file of payloads (`.json`, or `.jsonl`/`.ndjson` with one
+    /// payload per line), or `-` for JSONL on stdin
+    #[arg(long, value_name = "PATH")]
+    data: String,
+    /// Print a Markdown summary instead of the JSON result, or write it to
+    /// FILE (HTML for `.html`)
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "-")]
+    report: Option<String>,
+    /// Fail if more than this percentage of the payloads fails
+    #[arg(long, value_name = "PERCENT")]
+    max_failure_rate: Option<f64>,
+}
+
+#[derive(clap::Args)]
+pub struct CompatCheckArgs {
+    /// Schema directory to check (default: the global `--path`)
+    #[arg(long, value_name = "DIR")]
+    dir: Option<String>,
+    /// Earlier version of the schemas: `git:<REF>` for `--dir` at a git ref,
+    /// or another directory
+    #[arg(long, value_name = "git:REF|DIR")]
+    against: String,
+}
+
+#[derive(clap::Args)]
+pub struct CleanArgs {
+    /// Source directory or file to scan for annotated structs
+    #[arg(long)]
+    source: String,
+    /// Output directory the schemas were generated into (optional: uses
+    /// paths from macro if not specified)
+    #[arg(long)]
+    output: Option<String>,
+    /// Exclude patterns (can be specified multiple times); defaults to the
+    /// `generate-from-rust` excludes of the project config
+    #[arg(long, action = clap::ArgAction::Append)]
+    exclude: Vec<String>,
+    /// List the files that would be removed without removing them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(clap::Args)]
+pub struct CheckCollisionsArgs {
+    /// Workspace directory to scan
+    #[arg(long)]
+    source: String,
+    /// Exclude patterns (can be specified multiple times); defaults to the
+    /// `generate-from-rust` excludes of the project config
+    #[arg(long, action = clap::ArgAction::Append)]
+    exclude: Vec<String>,
+}
+
+#[derive(clap::Args)]
+pub struct ImportArgs {
+    /// `OpenAPI` 3 document (YAML/JSON) or directory of JSON Schema files
+    #[arg(long)]
+    from: String,
+    /// Vendor of the generated IDs
+    #[arg(long)]
+    vendor: String,
+    /// Package of the generated IDs
+    #[arg(long)]
+    package: String,
+    /// Namespace of the generated IDs
+    #[arg(long, default_value = "_")]
+    namespace: String,
+    /// YAML/JSON file with `ids` (schema name -> GTS ID) and the `version`
+    /// of generated IDs
+    #[arg(long)]
+    mapping: Option<String>,
+    /// Directory to write the GTS schemas to (default: the global `--path`)
+    #[arg(long)]
+    out: Option<String>,
+    /// Replace existing schema files
+    #[arg(long)]
+    overwrite: bool,
+}
+
+#[derive(clap::Args)]
+pub struct ValidateArgs {
+    /// Files or directories to scan (default: `paths` of the validator
+    /// config, else `.`)
+    #[arg(value_name = "PATH")]
+    paths: Vec<String>,
+    /// Exclude patterns (glob, can be specified multiple times)
+    #[arg(long, short = 'e', action = clap::ArgAction::Append)]
+    exclude: Vec<String>,
+    /// Vendor every GTS ID must use (example vendors are always accepted)
+    #[arg(long, conflicts_with = "allow_vendors")]
+    vendor: Option<String>,
+    /// Comma-separated vendors GTS IDs may use
+    #[arg(long, value_name = "VENDORS", value_delimiter = ',')]
+    allow_vendors: Vec<String>,
+    /// Without a vendor policy, list the vendor of every GTS ID found, with
+    /// its count and the first place it appears
+    #[arg(long, conflicts_with_all = ["vendor", "allow_vendors"])]
+    list_vendors: bool,
+    /// Which Markdown strings are checked: well-formed IDs only (default), every
+    /// `gts.*` string, or every `gts.*` string only in files where well-formed
+    /// matching missed one
+    #[arg(long, value_enum)]
+    discovery_mode: Option<DiscoveryModeArg>,
+    /// Severity of a rule, as `CODE=SEVERITY` (repeatable), e.g. `vendor_mismatch=warning`
+    #[arg(long = "rule", value_name = "CODE=SEVERITY", value_parser = parse_rule_override)]
+    rules: Vec<(gts_validator::RuleId, gts_validator::Severity)>,
+    /// Demote findings of heuristic discovery whose confidence (0-100) is
+    /// below N to `info`
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=100))]
+    min_confidence: Option<u8>,
+    #[arg(long, value_enum, default_value_t = ValidateFormat::Human)]
+    format: ValidateFormat,
+    /// Write progress events to stderr (`json`: one JSON object per line)
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    progress: Option<ProgressFormat>,
+}
+
+/// Parse a `--rule CODE=SEVERITY` value of `gts validate`
+fn parse_rule_override(
+    value: &str,
+) -> std::result::Result<(gts_validator::RuleId, gts_validator::Severity), String> {
+    let (code, severity) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected CODE=SEVERITY, got '{value}'"))?;
+    Ok((code.parse()?, severity.parse()?))
+}
+
+/// `--discovery-mode` of `gts validate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiscoveryModeArg {
+    Strict,
+    Heuristic,
+    Auto,
+}
+
+impl From<DiscoveryModeArg> for gts_validator::DiscoveryMode {
+    fn from(mode: DiscoveryModeArg) -> Self {
+        match mode {
+            DiscoveryModeArg::Strict => Self::StrictSpecOnly,
+            DiscoveryModeArg::Heuristic => Self::Heuristic,
+            DiscoveryModeArg::Auto => Self::Auto,
+        }
+    }
+}
+
+/// Output format of `gts validate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ValidateFormat {
+    Human,
+    Json,
+    Sarif,
+}
+
+#[derive(clap::Args)]
+pub struct LintArgs {
+    /// Files or directories to scan
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+    /// Exclude patterns (glob, can be specified multiple times)
+    #[arg(long, short = 'e', action = clap::ArgAction::Append)]
+    exclude: Vec<String>,
+    /// Vendor every GTS ID must use (example vendors are always accepted)
+    #[arg(long, conflicts_with = "allow_vendors")]
+    vendor: Option<String>,
+    /// Comma-separated vendors GTS IDs may use
+    #[arg(long, value_name = "VENDORS", value_delimiter = ',')]
+    allow_vendors: Vec<String>,
+    /// Which Markdown strings are checked; by default every `gts.*` string, so
+    /// that malformed IDs get a suggestion too
+    #[arg(long, value_enum, default_value_t = DiscoveryModeArg::Heuristic)]
+    discovery_mode: DiscoveryModeArg,
+    /// A deprecated vendor and its replacement, as `OLD=NEW` (repeatable)
+    #[arg(long = "deprecated-vendor", value_name = "OLD=NEW", value_parser = parse_vendor_rename)]
+    deprecated_vendors: Vec<(String, String)>,
+    /// Warn on segment versions without a minor number (`v1` instead of `v1.0`)
+    #[arg(long)]
+    require_minor: bool,
+    /// Fail on warnings as well as errors
+    #[arg(long)]
+    deny_warnings: bool,
+    #[arg(long, value_enum, default_value_t = LintFormat::Human)]
+    format: LintFormat,
+}
+
+/// Parse a `--deprecated-vendor OLD=NEW` value of `gts lint`
+fn parse_vendor_rename(value: &str) -> std::result::Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((old, new)) if !old.is_empty() && !new.is_empty() => {
+            Ok((old.to_owned(), new.to_owned()))
+        }
+        _ => Err(format!("expected OLD=NEW, got '{value}'")),
+    }
+}
+
+/// Output format of `gts lint`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LintFormat {
+    Human,
+    Json,
+}
+
+#[derive(Subcommand)]
+pub enum ExportTarget {
+    /// Emit trait values of selected instances as an infrastructure manifest
+    Infra {
+        /// YAML/JSON file with `types` (GTS wildcard patterns) and `traits` (trait -> output key)
+        #[arg(long)]
+        trait_map: String,
+        /// Write the manifest to this file (`.yaml`/`.yml` for YAML, otherwise JSON)
+        /// instead of printing JSON to stdout
+        #[arg(long)]
+        out: Option<String>,
     },
+    /// Split the store per vendor (or per named pattern) into bundles
+    Partition {
+        /// Named partition as NAME=PATTERN (repeatable); one partition per vendor if omitted
+        #[arg(long = "pattern", value_name = "NAME=PATTERN")]
+        patterns: Vec<String>,
+        /// Only write the bundles of these partitions
+        #[arg(long)]
+        only: Vec<String>,
+        /// Directory receiving `<partition>.bundle.json` files and `partition-report.json`
+        #[arg(long)]
+        out: String,
+    },
+}
+
+/// Output format of `gts doctor` and `gts audit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DoctorFormat {
+    Table,
+    Json,
 }
+
 /// Run the CLI application
 ///
 /// # Errors
@@ -161,58 +636,112 @@ pub async fn run_with_cli(cli: Cli) -> Result<()> {
         _ => tracing::Level::DEBUG,
     };
 
-    // Only initialize logging if not already initialized (for testing)
+    // Only initialize logging if not already initialized (for testing). Logs
+    // go to stderr so they never mix into the JSON commands print on stdout
     let _ = tracing_subscriber::fmt()
         .with_max_level(log_level)
         .with_target(false)
+        .with_writer(std::io::stderr)
         .try_init();
 
+    // Doctor reports a broken project config instead of failing on it
+    if let Commands::Doctor {
+        source,
+        host,
+        port,
+        format,
+    } = &cli.command
+    {
+        return doctor(&cli, source, host, *port, *format);
+    }
+    // The validator scans files; it needs no store, only its own config
+    if let Commands::Validate(args) = &cli.command {
+        return validate(
+            args,
+            cli.project_config.as_deref().map(std::path::Path::new),
+        );
+    }
+    if let Commands::Lint(args) = &cli.command {
+        return lint(args);
+    }
+
     run_command(cli).await
 }
 
 /// Execute a command with the given CLI configuration
-async fn run_command(cli: Cli) -> Result<()> {
+#[allow(clippy::too_many_lines)]
+async fn run_command(mut cli: Cli) -> Result<()> {
+    // Command-line flags win over the project config
+    let project = ProjectConfig::resolve(
+        cli.project_config.as_deref().map(std::path::Path::new),
+        &std::env::current_dir()?,
+    )?;
+    cli.path = cli.path.or(project.path);
+    cli.config = cli.config.or(project.config);
+    cli.file_name_encoding = cli.file_name_encoding.or(project.file_name_encoding);
+    let file_name_encoding = cli.file_name_encoding.unwrap_or_default();
+
+    let cli_settings = ServerConfig {
+        path: cli.path.clone(),
+        config: cli.config.clone(),
+    };
+
     // Parse path into Vec<String>
     let path = cli.path.map(|p| vec![p]);
 
-    // Create GtsOps
-    let mut ops = GtsOps::new(path, cli.config, cli.verbose as usize);
+    // The server loads its store in the background once it is listening
+    let load_now = !matches!(cli.command, Commands::Server { .. });
+    let load_path = path.clone().filter(|_| load_now);
+    let mut ops = GtsOps::new(load_path, cli.config, cli.verbose as usize);
+    add_retired_ids(&mut ops, project.retired_ids.as_deref())?;
+    if let Some(file) = &project.redaction {
+        ops.redaction = gts::load_redaction(std::path::Path::new(file))?;
+    }
 
     match cli.command {
-        Commands::Server { host, port } => {
-            println!("starting the server @ http://{host}:{port}");
-            if cli.verbose == 0 {
-                println!("use --verbose to see server logs");
-            }
-            let server = GtsHttpServer::new(ops, host.clone(), port, cli.verbose);
+        Commands::Server {
+            host,
+            port,
+            server_config,
+            reload_interval,
+            load_concurrency,
+            #[cfg(feature = "grpc")]
+            grpc_port,
+        } => {
+            print_server_banner(&host, port, cli.verbose);
+            let concurrency = load_concurrency.unwrap_or_else(default_load_concurrency);
+            let server = match server_config {
+                Some(file) => reloading_server(
+                    std::path::PathBuf::from(file),
+                    cli_settings,
+                    reload_interval,
+                    (host, port),
+                    concurrency,
+                    ops,
+                    cli.verbose,
+                )?,
+                None => background_server(ops, path, (host, port), concurrency, cli.verbose),
+            };
+            #[cfg(feature = "grpc")]
+            let server = match grpc_port {
+                Some(grpc_port) => server.with_grpc(grpc_port),
+                None => server,
+            };
             server.run().await?;
         }
-        Commands::OpenapiSpec { out, host, port } => {
-            let server = GtsHttpServer::new(ops, host, port, cli.verbose);
-            let spec = server.openapi_spec();
-            std::fs::write(&out, serde_json::to_string_pretty(&spec)?)?;
-            let result = serde_json::json!({
-                "ok": true,
-                "out": out
-            });
-            println!("{}", serde_json::to_string_pretty(&result)?);
-        }
-        Commands::ValidateId { gts_id } => {
-            let result = GtsOps::validate_id(&gts_id);
-            print_result(&result)?;
+        Commands::CompletionServer => {
+            GtsCompletionServer::new(ops).run()?;
         }
-        Commands::ParseId { gts_id } => {
-            let result = GtsOps::parse_id(&gts_id);
-            print_result(&result)?;
+        Commands::OpenapiSpec { out, host, port } => {
+            write_openapi_spec(&GtsHttpServer::new(ops, host, port, cli.verbose), &out)?;
         }
+        // ID operations need no store
+        Commands::ValidateId { gts_id } => print_result(&GtsOps::validate_id(&gts_id))?,
+        Commands::ParseId { gts_id } => print_result(&GtsOps::parse_id(&gts_id))?,
         Commands::MatchIdPattern { pattern, candidate } => {
-            let result = GtsOps::match_id_pattern(&candidate, &pattern);
-            print_result(&result)?;
-        }
-        Commands::Uuid { gts_id, scope: _ } => {
-            let result = GtsOps::uuid(&gts_id);
-            print_result(&result)?;
+            print_result(&GtsOps::match_id_pattern(&candidate, &pattern))?;
         }
+        Commands::Uuid { gts_id, scope: _ } => print_result(&GtsOps::uuid(&gts_id))?,
         Commands::ValidateInstance { gts_id } => {
             let result = ops.validate_instance(&gts_id);
             print_result(&result)?;
@@ -232,19 +761,39 @@ async fn run_command(cli: Cli) -> Result<()> {
         Commands::Compatibility {
             old_schema_id,
             new_schema_id,
+            report,
         } => {
             let result = ops.compatibility(&old_schema_id, &new_schema_id);
+            if let Some(path) = report {
+                write_cast_report(
+                    &path,
+                    "GTS compatibility report",
+                    std::slice::from_ref(&result),
+                )?;
+            }
             print_result(&result)?;
         }
         Commands::Cast {
             from_id,
             to_schema_id,
+            report,
         } => {
             let result = ops.cast(&from_id, &to_schema_id);
+            if let Some(path) = report {
+                write_cast_report(&path, "GTS cast report", std::slice::from_ref(&result))?;
+            }
             print_result(&result)?;
         }
-        Commands::Query { expr, limit } => {
-            let result = ops.query(&expr, limit);
+        Commands::Query {
+            expr,
+            limit,
+            explain,
+        } => {
+            let result = if explain {
+                ops.query_explained(&expr, limit)
+            } else {
+                ops.query(&expr, limit)
+            };
             print_result(&result)?;
         }
         Commands::Attr { gts_with_path } => {
@@ -255,18 +804,563 @@ async fn run_command(cli: Cli) -> Result<()> {
             let result = ops.get_entities(limit);
             print_result(&result)?;
         }
+        Commands::DiffInstances { id_a, id_b } => {
+            let result = ops.diff_instances(&id_a, &id_b);
+            print_result(&result)?;
+        }
+        Commands::Diff {
+            old_schema,
+            new_schema,
+            report,
+        } => {
+            let old = load_schema(&mut ops.store, &old_schema)?;
+            let new = load_schema(&mut ops.store, &new_schema)?;
+            let diff = gts::diff_schemas(&old, &new);
+            if let Some(path) = report {
+                write_diff_report(&path, &old_schema, &new_schema, &diff)?;
+            }
+            print_result(&diff)?;
+        }
+        Commands::FlattenChain { schema_id } => {
+            let result = ops.flatten_chain(&schema_id);
+            print_result(&result)?;
+        }
+        Commands::GenExamples {
+            schema_id,
+            count,
+            seed,
+        } => {
+            let result = ops.generate_examples(&schema_id, count, seed);
+            print_result(&result)?;
+        }
+        Commands::GenInvalidExamples {
+            schema_id,
+            count,
+            seed,
+        } => {
+            let result = ops.generate_invalid_examples(&schema_id, count, seed);
+            print_result(&result)?;
+        }
+        Commands::ExpandPattern {
+            pattern,
+            require_match,
+        } => {
+            let result = ops.expand_pattern(&pattern, require_match);
+            print_result(&result)?;
+        }
+        Commands::LintStore { mut require_owner } => {
+            if require_owner.is_empty() {
+                require_owner = project.lint.require_owner;
+            }
+            print_result(&ops.lint_store_with(&require_owner, file_name_encoding))?;
+        }
+        Commands::StoreInfo => print_result(&ops.health())?,
+        Commands::Conformance { vectors } => run_conformance(&vectors)?,
+        Commands::Doctor { .. } | Commands::Validate(_) | Commands::Lint(_) => {
+            unreachable!("handled before the project config is loaded")
+        }
+        Commands::Export { target } => export(&mut ops, target)?,
+        Commands::NewSchema { out, rust } => {
+            let result = run_new_schema(&ops, &out, rust.as_deref(), file_name_encoding)?;
+            print_result(&result)?;
+        }
         Commands::GenerateFromRust {
             source,
             output,
             exclude,
+            only,
+            skip_pattern,
+            progress,
+            check,
         } => {
-            generate_schemas_from_rust(&source, output.as_deref(), &exclude, cli.verbose)?;
+            let id_filter = SchemaIdFilter::new(&only, &skip_pattern)?;
+            let exclude = if exclude.is_empty() {
+                project.generate_from_rust.exclude
+            } else {
+                exclude
+            };
+            let options = GenerateOptions {
+                output,
+                exclude,
+                id_filter,
+                retired: ops.store.retired_ids().clone(),
+                encoding: file_name_encoding,
+                check,
+                verbose: cli.verbose,
+            };
+            let report = generate_schemas_from_rust(
+                &source,
+                &options,
+                &mut ProgressReporter::new("generate-from-rust", progress),
+            )?;
+            if check && !report.is_up_to_date() {
+                anyhow::bail!(
+                    "Generated schemas are out of date: {} to add, {} to change, {} to remove; \
+                     run generate-from-rust without --check",
+                    report.added.len(),
+                    report.changed.len(),
+                    report.removed.len()
+                );
+            }
+        }
+        Commands::Clean(args) => {
+            let exclude = if args.exclude.is_empty() {
+                project.generate_from_rust.exclude
+            } else {
+                args.exclude
+            };
+            print_result(&clean_generated_schemas(
+                &args.source,
+                args.output.as_deref(),
+                &exclude,
+                file_name_encoding,
+                args.dry_run,
+            )?)?;
+        }
+        Commands::Coverage(args) => {
+            coverage(args, project.generate_from_rust.exclude, cli_settings)?;
+        }
+        Commands::Import(args) => import(args, cli_settings.path, file_name_encoding, &ops)?,
+        Commands::Replay(args) => replay(&ops.store, &args)?,
+        Commands::CompatCheck(args) => compat_check(&args, cli_settings)?,
+        Commands::CheckCollisions(args) => {
+            check_collisions(args, project.generate_from_rust.exclude)?;
+        }
+        Commands::Audit(args) => audit(
+            &ops,
+            &args,
+            AuditOptions {
+                store: cli_settings.path,
+                config: cli_settings.config,
+                require_owner: project.lint.require_owner,
+                encoding: file_name_encoding,
+                exclude: project.generate_from_rust.exclude,
+                ..AuditOptions::default()
+            },
+        )?,
+    }
+
+    Ok(())
+}
+
+/// Print the conformance report for the vectors in `dir`, failing if any vector fails
+fn run_conformance(dir: &str) -> Result<()> {
+    let report = gts::run_conformance(std::path::Path::new(dir))?;
+    print_result(&report)?;
+    if !report.ok {
+        anyhow::bail!("{} conformance vector(s) failed", report.failures.len());
+    }
+    Ok(())
+}
+
+/// Run `gts doctor` and print its report, failing if any check failed
+fn doctor(cli: &Cli, source: &str, host: &str, port: u16, format: DoctorFormat) -> Result<()> {
+    let report = run_doctor(&DoctorOptions {
+        project_config: cli.project_config.as_ref().map(std::path::PathBuf::from),
+        cwd: std::env::current_dir()?,
+        path: cli.path.clone(),
+        config: cli.config.clone(),
+        file_name_encoding: cli.file_name_encoding,
+        source: std::path::PathBuf::from(source),
+        host: host.to_owned(),
+        port,
+    });
+    match format {
+        DoctorFormat::Table => print!("{}", report.to_table()),
+        DoctorFormat::Json => print_result(&report)?,
+    }
+    let failed = report
+        .checks
+        .iter()
+        .filter(|c| c.status == crate::doctor::CheckStatus::Fail)
+        .count();
+    if failed > 0 {
+        anyhow::bail!("{failed} doctor check(s) failed");
+    }
+    Ok(())
+}
+
+/// Run `gts audit` and print its report, failing with the audit's exit code
+fn audit(ops: &GtsOps, args: &AuditArgs, options: AuditOptions) -> Result<()> {
+    let report = run_audit(
+        ops,
+        &AuditOptions {
+            docs: args.docs.iter().map(std::path::PathBuf::from).collect(),
+            vendor: args.vendor.clone(),
+            source: std::path::PathBuf::from(&args.source),
+            since: args.since.clone(),
+            cache_dir: args.cache_dir.as_ref().map(std::path::PathBuf::from),
+            time_budget: args.time_budget.map(std::time::Duration::from_secs),
+            ..options
+        },
+    );
+    match args.format {
+        DoctorFormat::Table => print!("{}", report.to_table()),
+        DoctorFormat::Json => print_result(&report)?,
+    }
+    if !report.ok {
+        return Err(AuditFailed {
+            exit_code: report.exit_code,
+            failed: report
+                .checks
+                .iter()
+                .filter(|c| c.status == AuditStatus::Fail || c.over_budget)
+                .map(|c| c.name)
+                .collect(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Run `gts coverage` and print its report, failing below `--min-coverage`
+fn coverage(
+    args: CoverageArgs,
+    default_exclude: Vec<String>,
+    settings: ServerConfig,
+) -> Result<()> {
+    let exclude = if args.exclude.is_empty() {
+        default_exclude
+    } else {
+        args.exclude
+    };
+    let report = run_coverage(&CoverageOptions {
+        source: std::path::PathBuf::from(args.source),
+        store: args.store.or(settings.path),
+        config: settings.config,
+        exclude,
+        all_serde: args.all_serde,
+    })?;
+    print_result(&report)?;
+    if let Some(min) = args.min_coverage
+        && report.coverage_percent < min
+    {
+        anyhow::bail!(
+            "Annotation coverage {}% is below --min-coverage {min}%",
+            report.coverage_percent
+        );
+    }
+    Ok(())
+}
+
+/// The schema in the file `source` or, if there is none, the store's schema
+/// with that ID, its references resolved from the store
+fn load_schema(store: &mut gts::GtsStore, source: &str) -> Result<serde_json::Value> {
+    let path = std::path::Path::new(source);
+    let schema = if path.is_file() {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {source}"))?;
+        serde_json::from_str(&text).with_context(|| format!("Invalid JSON in {source}"))?
+    } else {
+        store.get_schema_content(source)?
+    };
+    Ok(store.resolve_schema_refs(&schema))
+}
+
+/// Run `gts replay` and print its report, failing above `--max-failure-rate`
+fn replay(store: &gts::GtsStore, args: &ReplayArgs) -> Result<()> {
+    let text = std::fs::read_to_string(&args.schema)
+        .with_context(|| format!("Failed to read {}", args.schema))?;
+    let schema: serde_json::Value =
+        serde_json::from_str(&text).with_context(|| format!("Invalid JSON in {}", args.schema))?;
+    let report = run_replay(store, &schema, std::path::Path::new(&args.data))?;
+    match args.report.as_deref() {
+        Some("-") => print!("{}", render_replay_report(&report, ReportFormat::Markdown)),
+        Some(path) => {
+            let path = std::path::Path::new(path);
+            std::fs::write(
+                path,
+                render_replay_report(&report, ReportFormat::from_path(path)),
+            )?;
+            print_result(&report)?;
+        }
+        None => print_result(&report)?,
+    }
+    if let Some(max) = args.max_failure_rate
+        && report.failure_rate > max
+    {
+        anyhow::bail!(
+            "Failure rate {}% is above --max-failure-rate {max}%",
+            report.failure_rate
+        );
+    }
+    Ok(())
+}
+
+/// Run `gts compat-check` and print its report, failing with every
+/// incompatible evolution
+fn compat_check(args: &CompatCheckArgs, settings: ServerConfig) -> Result<()> {
+    let Some(dir) = args.dir.clone().or(settings.path) else {
+        anyhow::bail!("compat-check needs --dir or the global --path");
+    };
+    let load = |dir: &std::path::Path| {
+        GtsOps::new(
+            Some(vec![dir.to_string_lossy().into_owned()]),
+            settings.config.clone(),
+            0,
+        )
+    };
+    let old = match args.against.strip_prefix("git:") {
+        Some(git_ref) => load(&snapshot_at(std::path::Path::new(&dir), git_ref, None)?.dir),
+        None if std::path::Path::new(&args.against).is_dir() => {
+            load(std::path::Path::new(&args.against))
+        }
+        None => anyhow::bail!(
+            "--against {} is neither git:<REF> nor a directory",
+            args.against
+        ),
+    };
+    let new = load(std::path::Path::new(&dir));
+
+    let report = check_store_evolution(&old.store, &new.store);
+    print_result(&report)?;
+    let incompatible: Vec<String> = report
+        .incompatible()
+        .map(|evolution| format!("  - {evolution}"))
+        .collect();
+    if !incompatible.is_empty() {
+        anyhow::bail!(
+            "{} incompatible schema evolution(s) against {}:\n{}",
+            incompatible.len(),
+            args.against,
+            incompatible.join("\n")
+        );
+    }
+    Ok(())
+}
+
+fn check_collisions(args: CheckCollisionsArgs, default_exclude: Vec<String>) -> Result<()> {
+    let exclude = if args.exclude.is_empty() {
+        default_exclude
+    } else {
+        args.exclude
+    };
+    let collisions = find_schema_collisions(&args.source, &exclude)?;
+    print_result(&collisions)?;
+    if !collisions.is_empty() {
+        let lines: Vec<String> = collisions.iter().map(|c| format!("  - {c}")).collect();
+        anyhow::bail!(
+            "{} schema ID(s) emitted by more than one crate:\n{}",
+            lines.len(),
+            lines.join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Run `gts validate` and print its report, failing if the report is not ok
+/// Run `gts validate`. Its defaults come from `[validator]` of the
+/// `--project-config` file, else from the nearest `.gtsvalidator.toml` or
+/// `gts.toml`; command-line flags win.
+fn validate(args: &ValidateArgs, project_config: Option<&std::path::Path>) -> Result<()> {
+    let file_config = match project_config {
+        Some(file) => ProjectConfig::load(file)?.validator,
+        None => gts_validator::ConfigFile::resolve(None, &std::env::current_dir()?)?,
+    };
+    let mut fs_config = gts_validator::FsSourceConfig::default();
+    let mut config = gts_validator::ValidationConfig::default();
+    file_config.apply(&mut fs_config, &mut config);
+
+    if !args.paths.is_empty() {
+        fs_config.paths = args.paths.iter().map(std::path::PathBuf::from).collect();
+    } else if fs_config.paths.is_empty() {
+        fs_config.paths = vec![std::path::PathBuf::from(".")];
+    }
+    if !args.exclude.is_empty() {
+        fs_config.exclude.clone_from(&args.exclude);
+    }
+    if let Some(vendor) = &args.vendor {
+        config.vendor_policy = gts_validator::VendorPolicy::MustMatch(vendor.clone());
+    } else if !args.allow_vendors.is_empty() {
+        config.vendor_policy = gts_validator::VendorPolicy::AllowList(args.allow_vendors.clone());
+    }
+    if let Some(mode) = args.discovery_mode {
+        config.discovery_mode = mode.into();
+    }
+    config.rule_overrides = args.rules.iter().copied().collect();
+    config.min_confidence = args.min_confidence;
+    config.collect_vendors = args.list_vendors;
+
+    let mut progress = ProgressReporter::new("validate", args.progress);
+    let report = gts_validator::validate_fs_with_progress(
+        &fs_config,
+        &config,
+        &gts_validator::Host::os(),
+        &mut |p| progress.report(p.phase.into(), p.file, p.files_done, p.files_total),
+    )?;
+    let mut stdout = std::io::stdout();
+    match args.format {
+        ValidateFormat::Human => gts_validator::output::write_human(&report, &mut stdout)?,
+        ValidateFormat::Json => gts_validator::output::write_json(&report, &mut stdout)?,
+        ValidateFormat::Sarif => gts_validator::output::write_sarif(&report, &mut stdout)?,
+    }
+    if !report.ok {
+        anyhow::bail!(
+            "{} validation error(s), {} scan error(s)",
+            report.failing_errors_count(),
+            report.scan_errors.len()
+        );
+    }
+    Ok(())
+}
+
+/// Run `gts lint` and print its diagnostics, failing on errors (and on
+/// warnings with `--deny-warnings`)
+fn lint(args: &LintArgs) -> Result<()> {
+    let report = run_lint(&LintOptions {
+        paths: args.paths.iter().map(std::path::PathBuf::from).collect(),
+        exclude: args.exclude.clone(),
+        vendor_policy: match &args.vendor {
+            Some(vendor) => gts_validator::VendorPolicy::MustMatch(vendor.clone()),
+            None if !args.allow_vendors.is_empty() => {
+                gts_validator::VendorPolicy::AllowList(args.allow_vendors.clone())
+            }
+            None => gts_validator::VendorPolicy::Any,
+        },
+        discovery_mode: args.discovery_mode.into(),
+        deprecated_vendors: args.deprecated_vendors.iter().cloned().collect(),
+        require_minor: args.require_minor,
+    })?;
+    match args.format {
+        LintFormat::Human => print!("{}", report.to_human()),
+        LintFormat::Json => print_result(&report)?,
+    }
+    if !report.passes(args.deny_warnings) {
+        anyhow::bail!(
+            "{} error(s), {} warning(s), {} scan error(s)",
+            report.count(gts_validator::Severity::Error),
+            report.count(gts_validator::Severity::Warning),
+            report.scan_errors.len()
+        );
+    }
+    Ok(())
+}
+
+/// Import external schemas into `--out`, by default the store directory
+fn import(
+    args: ImportArgs,
+    store: Option<String>,
+    encoding: GtsFileNameEncoding,
+    ops: &GtsOps,
+) -> Result<()> {
+    let Some(out) = args.out.or(store) else {
+        anyhow::bail!("No output directory: pass --out or set --path");
+    };
+    let mapping = match &args.mapping {
+        Some(file) => ImportMapping::load(std::path::Path::new(file))?,
+        None => ImportMapping::default(),
+    };
+    print_result(&run_import(&ImportOptions {
+        from: std::path::PathBuf::from(args.from),
+        vendor: args.vendor,
+        package: args.package,
+        namespace: args.namespace,
+        mapping,
+        out: std::path::PathBuf::from(out),
+        encoding,
+        overwrite: args.overwrite,
+        retired: ops.store.retired_ids().clone(),
+    })?)
+}
+
+/// Add the project's retired-ID registry, if it names one, to the store
+fn add_retired_ids(ops: &mut GtsOps, file: Option<&str>) -> Result<()> {
+    if let Some(file) = file {
+        let retired = gts::load_retired_ids(std::path::Path::new(file))?;
+        ops.store.add_retired_ids(retired);
+    }
+    Ok(())
+}
+
+/// Write the server's `OpenAPI` spec to `out`
+fn export(ops: &mut GtsOps, target: ExportTarget) -> Result<()> {
+    match target {
+        ExportTarget::Infra { trait_map, out } => {
+            let entries = infra_manifest(ops, &trait_map)?;
+            match out {
+                Some(out) => print_result(&write_infra_manifest(&out, &entries)?),
+                None => print_result(&entries),
+            }
         }
+        ExportTarget::Partition {
+            patterns,
+            only,
+            out,
+        } => print_result(&write_partitions(
+            &out,
+            &partition_store(ops, &patterns)?,
+            &only,
+        )?),
     }
+}
 
+fn write_openapi_spec(server: &GtsHttpServer, out: &str) -> Result<()> {
+    let spec = server.openapi_spec();
+    std::fs::write(out, serde_json::to_string_pretty(&spec)?)?;
+    let result = serde_json::json!({
+        "ok": true,
+        "out": out
+    });
+    println!("{}", serde_json::to_string_pretty(&result)?);
     Ok(())
 }
 
+/// Server whose store settings come from `file`, re-read on `SIGHUP` and
+/// every `reload_interval` seconds; the redaction and retired IDs of the
+/// project come from `project`
+fn reloading_server(
+    file: std::path::PathBuf,
+    cli_settings: ServerConfig,
+    reload_interval: Option<u64>,
+    (host, port): (String, u16),
+    load_concurrency: usize,
+    project: GtsOps,
+    verbose: u8,
+) -> Result<GtsHttpServer> {
+    let current = ServerConfig::load(&file)?.or(&cli_settings);
+    let mut ops = GtsOps::new(None, current.config.clone(), verbose as usize);
+    ops.redaction = project.redaction;
+    ops.store
+        .add_retired_ids(project.store.configured_retired_ids().clone());
+    let mut server = GtsHttpServer::new(ops, host, port, verbose);
+    if let Some(path) = &current.path {
+        server = server.with_background_load(vec![path.clone()], load_concurrency);
+    }
+    Ok(server.with_config_reload(
+        file,
+        cli_settings,
+        current,
+        reload_interval.map(std::time::Duration::from_secs),
+    ))
+}
+
+fn print_server_banner(host: &str, port: u16, verbose: u8) {
+    println!("starting the server @ http://{host}:{port}");
+    if verbose == 0 {
+        println!("use --verbose to see server logs");
+    }
+}
+
+/// Server loading the store from `path` once it is listening
+fn background_server(
+    ops: GtsOps,
+    path: Option<Vec<String>>,
+    (host, port): (String, u16),
+    load_concurrency: usize,
+    verbose: u8,
+) -> GtsHttpServer {
+    let server = GtsHttpServer::new(ops, host, port, verbose);
+    match path {
+        Some(path) => server.with_background_load(path, load_concurrency),
+        None => server,
+    }
+}
+
+/// Files loaded in parallel by the server: one per CPU
+fn default_load_concurrency() -> usize {
+    std::thread::available_parallelism().map_or(4, std::num::NonZeroUsize::get)
+}
+
 fn print_result<T: serde::Serialize>(value: &T) -> Result<()> {
     let stdout = std::io::stdout();
     let mut handle = stdout.lock();
@@ -349,6 +1443,13 @@ mod tests {
             "tests/*",
             "--exclude",
             "examples/*",
+            "--only",
+            "gts.x.core.events.*",
+            "--skip-pattern",
+            "gts.x.core.events.type.v1~x.core.audit.*",
+            "--progress",
+            "json",
+            "--check",
         ];
         let cli = Cli::try_parse_from(args).unwrap();
 
@@ -357,10 +1458,21 @@ mod tests {
                 source,
                 output,
                 exclude,
+                only,
+                skip_pattern,
+                progress,
+                check,
             } => {
                 assert_eq!(source, "/src/path");
                 assert_eq!(output, Some("/out/path".to_owned()));
                 assert_eq!(exclude, vec!["tests/*", "examples/*"]);
+                assert_eq!(only, vec!["gts.x.core.events.*"]);
+                assert_eq!(
+                    skip_pattern,
+                    vec!["gts.x.core.events.type.v1~x.core.audit.*"]
+                );
+                assert_eq!(progress, Some(ProgressFormat::Json));
+                assert!(check);
             }
             _ => panic!("Expected GenerateFromRust command"),
         }
@@ -372,7 +1484,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
-            Commands::Server { host, port } => {
+            Commands::Server { host, port, .. } => {
                 assert_eq!(host, "0.0.0.0");
                 assert_eq!(port, 3000);
             }
@@ -499,7 +1611,9 @@ mod tests {
             Commands::Compatibility {
                 old_schema_id,
                 new_schema_id,
+                report,
             } => {
+                assert!(report.is_none());
                 assert_eq!(old_schema_id, "test:schema:v1");
                 assert_eq!(new_schema_id, "test:schema:v2");
             }
@@ -523,6 +1637,7 @@ mod tests {
             Commands::Cast {
                 from_id,
                 to_schema_id,
+                ..
             } => {
                 assert_eq!(from_id, "test:schema:instance:v1");
                 assert_eq!(to_schema_id, "test:schema:v2");
@@ -531,15 +1646,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_parse_cast_with_report() {
+        let args = vec![
+            "gts",
+            "cast",
+            "--from-id",
+            "test:schema:instance:v1",
+            "--to-schema-id",
+            "test:schema:v2",
+            "--report",
+            "cast.html",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::Cast { report, .. } => {
+                assert_eq!(report, Some("cast.html".to_owned()));
+            }
+            _ => panic!("Expected Cast command"),
+        }
+    }
+
     #[test]
     fn test_cli_parse_query() {
         let args = vec!["gts", "query", "--expr", "test:*", "--limit", "25"];
         let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
-            Commands::Query { expr, limit } => {
+            Commands::Query {
+                expr,
+                limit,
+                explain,
+            } => {
                 assert_eq!(expr, "test:*");
                 assert_eq!(limit, 25);
+                assert!(!explain);
             }
             _ => panic!("Expected Query command"),
         }
@@ -551,9 +1693,14 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
-            Commands::Query { expr, limit } => {
+            Commands::Query {
+                expr,
+                limit,
+                explain,
+            } => {
                 assert_eq!(expr, "test:*");
                 assert_eq!(limit, 100);
+                assert!(!explain);
             }
             _ => panic!("Expected Query command"),
         }
@@ -590,6 +1737,152 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_parse_expand_pattern() {
+        let args = vec![
+            "gts",
+            "expand-pattern",
+            "--pattern",
+            "gts.x.core.events.*",
+            "--require-match",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::ExpandPattern {
+                pattern,
+                require_match,
+            } => {
+                assert_eq!(pattern, "gts.x.core.events.*");
+                assert!(require_match);
+            }
+            _ => panic!("Expected ExpandPattern command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_lint_store() {
+        let args = vec!["gts", "--path", "/tmp/gts", "lint-store"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        assert!(
+            matches!(cli.command, Commands::LintStore { ref require_owner } if require_owner.is_empty())
+        );
+
+        let cli =
+            Cli::try_parse_from(["gts", "lint-store", "--require-owner", "gts.x.core.*"]).unwrap();
+        assert!(
+            matches!(cli.command, Commands::LintStore { require_owner } if require_owner == ["gts.x.core.*"])
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_store_info() {
+        let args = vec!["gts", "--path", "/tmp/gts", "store-info"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        assert!(matches!(cli.command, Commands::StoreInfo));
+    }
+
+    #[test]
+    fn test_cli_parse_conformance() {
+        let cli = Cli::try_parse_from(["gts", "conformance"]).unwrap();
+        assert!(
+            matches!(cli.command, Commands::Conformance { vectors } if vectors == "conformance/vectors")
+        );
+
+        let cli = Cli::try_parse_from(["gts", "conformance", "--vectors", "spec/vectors"]).unwrap();
+        assert!(
+            matches!(cli.command, Commands::Conformance { vectors } if vectors == "spec/vectors")
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_export_infra() {
+        let args = vec![
+            "gts",
+            "export",
+            "infra",
+            "--trait-map",
+            "map.yaml",
+            "--out",
+            "infra.yaml",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::Export {
+                target: ExportTarget::Infra { trait_map, out },
+            } => {
+                assert_eq!(trait_map, "map.yaml");
+                assert_eq!(out, Some("infra.yaml".to_owned()));
+            }
+            _ => panic!("Expected Export command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_export_partition() {
+        let cli = Cli::try_parse_from([
+            "gts",
+            "export",
+            "partition",
+            "--pattern",
+            "acme=gts.x.core.events.type.v1~acme.*",
+            "--only",
+            "acme",
+            "--out",
+            "bundles",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Commands::Export {
+                target:
+                    ExportTarget::Partition {
+                        patterns,
+                        only,
+                        out,
+                    },
+            } => {
+                assert_eq!(patterns, ["acme=gts.x.core.events.type.v1~acme.*"]);
+                assert_eq!(only, ["acme"]);
+                assert_eq!(out, "bundles");
+            }
+            _ => panic!("Expected Export command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_new_schema() {
+        let args = vec![
+            "gts",
+            "new-schema",
+            "--out",
+            "schemas",
+            "--rust",
+            "src/event.rs",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::NewSchema { out, rust } => {
+                assert_eq!(out, "schemas");
+                assert_eq!(rust, Some("src/event.rs".to_owned()));
+            }
+            _ => panic!("Expected NewSchema command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_completion_server() {
+        let args = vec!["gts", "--path", "/tmp/gts", "completion-server"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        assert_eq!(cli.path, Some("/tmp/gts".to_owned()));
+        assert!(matches!(cli.command, Commands::CompletionServer));
+    }
+
     #[test]
     fn test_cli_parse_openapi_spec() {
         let args = vec![
@@ -639,10 +1932,18 @@ mod tests {
                 source,
                 output,
                 exclude,
+                only,
+                skip_pattern,
+                progress,
+                check,
             } => {
                 assert_eq!(source, "/src/path");
                 assert_eq!(output, None);
                 assert!(exclude.is_empty());
+                assert!(only.is_empty());
+                assert!(skip_pattern.is_empty());
+                assert_eq!(progress, None);
+                assert!(!check);
             }
             _ => panic!("Expected GenerateFromRust command"),
         }
@@ -654,7 +1955,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
-            Commands::Server { host, port } => {
+            Commands::Server { host, port, .. } => {
                 assert_eq!(host, "127.0.0.1");
                 assert_eq!(port, 8000);
             }
@@ -662,6 +1963,118 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_parse_diff_instances() {
+        let args = vec!["gts", "diff-instances", "--id-a", "a", "--id-b", "b"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::DiffInstances { id_a, id_b } => {
+                assert_eq!(id_a, "a");
+                assert_eq!(id_b, "b");
+            }
+            _ => panic!("Expected DiffInstances command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_flatten_chain() {
+        let args = vec![
+            "gts",
+            "flatten-chain",
+            "--schema-id",
+            "gts.x.core.events.type.v1~",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::FlattenChain { schema_id } => {
+                assert_eq!(schema_id, "gts.x.core.events.type.v1~");
+            }
+            _ => panic!("Expected FlattenChain command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_gen_examples() {
+        let args = vec![
+            "gts",
+            "gen-examples",
+            "--schema-id",
+            "gts.x.core.events.type.v1~",
+            "--seed",
+            "42",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::GenExamples {
+                schema_id,
+                count,
+                seed,
+            } => {
+                assert_eq!(schema_id, "gts.x.core.events.type.v1~");
+                assert_eq!(count, 10);
+                assert_eq!(seed, 42);
+            }
+            _ => panic!("Expected GenExamples command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_gen_invalid_examples() {
+        let args = vec![
+            "gts",
+            "gen-invalid-examples",
+            "--schema-id",
+            "gts.x.core.events.type.v1~",
+            "--count",
+            "3",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::GenInvalidExamples {
+                schema_id,
+                count,
+                seed,
+            } => {
+                assert_eq!(schema_id, "gts.x.core.events.type.v1~");
+                assert_eq!(count, 3);
+                assert_eq!(seed, 0);
+            }
+            _ => panic!("Expected GenInvalidExamples command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_server_reload() {
+        let args = vec![
+            "gts",
+            "server",
+            "--server-config",
+            "server.yaml",
+            "--reload-interval",
+            "30",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::Server {
+                server_config,
+                reload_interval,
+                ..
+            } => {
+                assert_eq!(server_config.as_deref(), Some("server.yaml"));
+                assert_eq!(reload_interval, Some(30));
+            }
+            _ => panic!("Expected Server command"),
+        }
+
+        // Polling needs a config file to poll
+        assert!(Cli::try_parse_from(vec!["gts", "server", "--reload-interval", "30"]).is_err());
+    }
+
     #[test]
     fn test_cli_multiple_verbose_flags() {
         let args = vec!["gts", "-vvv", "validate-id", "--gts-id", "test:schema:v1"];
@@ -692,4 +2105,126 @@ mod tests {
 
         assert_eq!(cli.path, Some("/path/to/data".to_owned()));
     }
+
+    #[test]
+    fn test_cli_parse_import() {
+        let args = vec![
+            "gts",
+            "--file-name-encoding",
+            "percent",
+            "import",
+            "--from",
+            "openapi.yaml",
+            "--vendor",
+            "x",
+            "--package",
+            "imported",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        assert_eq!(cli.file_name_encoding, Some(GtsFileNameEncoding::Percent));
+        match cli.command {
+            Commands::Import(args) => {
+                assert_eq!(args.from, "openapi.yaml");
+                assert_eq!(args.namespace, "_");
+                assert!(args.out.is_none() && !args.overwrite);
+            }
+            _ => panic!("Expected Import command"),
+        }
+        assert!(Cli::try_parse_from(["gts", "import", "--from", "a.yaml"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_audit() {
+        match Cli::try_parse_from(["gts", "audit"]).unwrap().command {
+            Commands::Audit(args) => {
+                assert_eq!(args.docs, vec!["docs"]);
+                assert_eq!(args.source, ".");
+                assert!(args.since.is_none() && args.time_budget.is_none());
+                assert_eq!(args.format, DoctorFormat::Table);
+            }
+            _ => panic!("Expected Audit command"),
+        }
+
+        let args = [
+            "gts",
+            "audit",
+            "--docs",
+            "docs",
+            "--docs",
+            "modules",
+            "--since",
+            "origin/main",
+            "--cache-dir",
+            ".gts-cache",
+            "--time-budget",
+            "300",
+            "--format",
+            "json",
+        ];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Audit(args) => {
+                assert_eq!(args.docs, vec!["docs", "modules"]);
+                assert_eq!(args.since.as_deref(), Some("origin/main"));
+                assert_eq!(args.cache_dir.as_deref(), Some(".gts-cache"));
+                assert_eq!(args.time_budget, Some(300));
+                assert_eq!(args.format, DoctorFormat::Json);
+            }
+            _ => panic!("Expected Audit command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_validate() {
+        let cli = Cli::try_parse_from(["gts", "validate"]).unwrap();
+        match cli.command {
+            Commands::Validate(args) => {
+                // Defaults come from the validator config file, else `.` and strict
+                assert!(args.paths.is_empty());
+                assert_eq!(args.discovery_mode, None);
+                assert_eq!(args.format, ValidateFormat::Human);
+                assert!(args.vendor.is_none() && args.allow_vendors.is_empty());
+            }
+            _ => panic!("Expected Validate command"),
+        }
+
+        let args = vec![
+            "gts",
+            "validate",
+            "docs",
+            "schemas",
+            "--exclude",
+            "target/*",
+            "--allow-vendors",
+            "x,acme",
+            "--discovery-mode",
+            "heuristic",
+            "--format",
+            "sarif",
+        ];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Validate(args) => {
+                assert_eq!(args.paths, vec!["docs", "schemas"]);
+                assert_eq!(args.exclude, vec!["target/*"]);
+                assert_eq!(args.allow_vendors, vec!["x", "acme"]);
+                assert_eq!(args.discovery_mode, Some(DiscoveryModeArg::Heuristic));
+                assert_eq!(args.format, ValidateFormat::Sarif);
+            }
+            _ => panic!("Expected Validate command"),
+        }
+        match Cli::try_parse_from(["gts", "validate", "--discovery-mode", "auto"])
+            .unwrap()
+            .command
+        {
+            Commands::Validate(args) => {
+                assert_eq!(args.discovery_mode, Some(DiscoveryModeArg::Auto));
+                assert_eq!(args.progress, None);
+            }
+            _ => panic!("Expected Validate command"),
+        }
+        assert!(
+            Cli::try_parse_from(["gts", "validate", "--vendor", "x", "--allow-vendors", "y"])
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,366 @@
+//! Minimal line-oriented JSON-RPC 2.0 server for editor integrations.
+//!
+//! Each request is a single JSON object on its own line; each response is
+//! written as a single line. Supported methods:
+//!
+//! - `validate_text` — `{"text": "..."}`: find GTS ID candidates in free text and
+//!   validate each one
+//! - `complete_id` — `{"prefix": "gts.x.", "limit": 50}`: list IDs from the loaded
+//!   store starting with the prefix
+
+use anyhow::Result;
+use gts::GtsOps;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::io::{BufRead, Write};
+use std::sync::LazyLock;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+const DEFAULT_COMPLETION_LIMIT: usize = 50;
+
+/// Candidate regex: anything starting with `gts.` (optionally `gts://gts.`) made of
+/// ID characters. Intentionally permissive — validation is done by `GtsOps::validate_id`.
+static GTS_CANDIDATE_PATTERN: LazyLock<Regex> =
+    LazyLock::new(
+        || match Regex::new(r"(?:gts://)?\bgts\.[A-Za-z0-9_*.~-]*") {
+            Ok(regex) => regex,
+            Err(err) => panic!("Invalid candidate regex: {err}"),
+        },
+    );
+
+#[derive(Debug, Deserialize)]
+struct ValidateTextParams {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompleteIdParams {
+    #[serde(default)]
+    prefix: String,
+    limit: Option<usize>,
+}
+
+/// A GTS ID candidate found by `validate_text`.
+///
+/// `line` and `column` are 0-based, `column` counts characters;
+/// `start`/`end` are byte offsets into the submitted text.
+#[derive(Debug, Clone, Serialize)]
+pub struct GtsTextIdResult {
+    pub id: String,
+    pub line: usize,
+    pub column: usize,
+    pub start: usize,
+    pub end: usize,
+    pub valid: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GtsCompletionItem {
+    pub id: String,
+    pub is_schema: bool,
+}
+
+pub struct GtsCompletionServer {
+    ops: GtsOps,
+}
+
+impl GtsCompletionServer {
+    #[must_use]
+    pub fn new(ops: GtsOps) -> Self {
+        Self { ops }
+    }
+
+    /// Serve requests from stdin until EOF, writing responses to stdout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading stdin or writing stdout fails
+    pub fn run(&self) -> Result<()> {
+        let stdin = std::io::stdin();
+        let stdout = std::io::stdout();
+        self.serve(stdin.lock(), stdout.lock())
+    }
+
+    /// Serve requests from `reader` until EOF, writing one response line per request
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from `reader` or writing to `writer` fails
+    pub fn serve<R: BufRead, W: Write>(&self, reader: R, mut writer: W) -> Result<()> {
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_line(&line) {
+                serde_json::to_writer(&mut writer, &response)?;
+                writeln!(writer)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle a single request line; returns `None` for notifications (no `id`)
+    #[must_use]
+    pub fn handle_line(&self, line: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(e) => {
+                return Some(error_response(
+                    &Value::Null,
+                    PARSE_ERROR,
+                    &format!("Parse error: {e}"),
+                ));
+            }
+        };
+
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                &id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Invalid request: missing 'method'",
+            ));
+        };
+        let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+        let result = self.dispatch(method, params);
+        let id = id?;
+        Some(match result {
+            Ok(value) => json!({"jsonrpc": "2.0", "id": id, "result": value}),
+            Err((code, message)) => error_response(&id, code, &message),
+        })
+    }
+
+    fn dispatch(&self, method: &str, params: Value) -> Result<Value, (i64, String)> {
+        match method {
+            "validate_text" => {
+                let params: ValidateTextParams = parse_params(params)?;
+                Ok(json!({ "ids": validate_text(&params.text) }))
+            }
+            "complete_id" => {
+                let params: CompleteIdParams = parse_params(params)?;
+                let items = self.complete_id(
+                    &params.prefix,
+                    params.limit.unwrap_or(DEFAULT_COMPLETION_LIMIT),
+                );
+                Ok(json!({ "count": items.len(), "items": items }))
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {method}"))),
+        }
+    }
+
+    fn complete_id(&self, prefix: &str, limit: usize) -> Vec<GtsCompletionItem> {
+        let mut items: Vec<GtsCompletionItem> = self
+            .ops
+            .get_entities(usize::MAX)
+            .entities
+            .into_iter()
+            .filter(|e| e.id.starts_with(prefix))
+            .map(|e| GtsCompletionItem {
+                id: e.id,
+                is_schema: e.is_schema,
+            })
+            .collect();
+        items.sort_by(|a, b| a.id.cmp(&b.id));
+        items.truncate(limit);
+        items
+    }
+}
+
+/// Find GTS ID candidates in `text` and validate each one
+#[must_use]
+pub fn validate_text(text: &str) -> Vec<GtsTextIdResult> {
+    let mut results = Vec::new();
+    let mut line_offset = 0;
+    for (line_no, line) in text.split('\n').enumerate() {
+        for m in GTS_CANDIDATE_PATTERN.find_iter(line) {
+            // Trailing dots are sentence punctuation, not part of the ID
+            let raw = m.as_str().trim_end_matches('.');
+            let id = raw.strip_prefix("gts://").unwrap_or(raw);
+            let id_start = m.start() + (raw.len() - id.len());
+            let validation = GtsOps::validate_id(id);
+            results.push(GtsTextIdResult {
+                id: id.to_owned(),
+                line: line_no,
+                column: line[..id_start].chars().count(),
+                start: line_offset + id_start,
+                end: line_offset + id_start + id.len(),
+                valid: validation.valid,
+                error: validation.error,
+            });
+        }
+        line_offset += line.len() + 1;
+    }
+    results
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, format!("Invalid params: {e}")))
+}
+
+fn error_response(id: &Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message}
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn empty_server() -> GtsCompletionServer {
+        GtsCompletionServer::new(GtsOps::new(None, None, 0))
+    }
+
+    fn server_with_schemas() -> (TempDir, GtsCompletionServer) {
+        let dir = TempDir::new().unwrap();
+        for id in [
+            "gts.x.core.events.event.v1~",
+            "gts.x.core.events.topic.v1~",
+            "gts.y.other.pkg.thing.v1~",
+        ] {
+            let schema = json!({
+                "$id": format!("gts://{id}"),
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object"
+            });
+            let file = dir.path().join(format!("{id}.schema.json"));
+            fs::write(file, serde_json::to_string(&schema).unwrap()).unwrap();
+        }
+        let path = vec![dir.path().to_string_lossy().into_owned()];
+        let server = GtsCompletionServer::new(GtsOps::new(Some(path), None, 0));
+        (dir, server)
+    }
+
+    #[test]
+    fn test_validate_text_finds_valid_and_invalid_ids() {
+        let text = "First gts.x.core.events.event.v1~.\nthen gts.X.bad";
+        let results = validate_text(text);
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0].id, "gts.x.core.events.event.v1~");
+        assert!(results[0].valid);
+        assert_eq!(results[0].line, 0);
+        assert_eq!(results[0].column, 6);
+        assert_eq!(&text[results[0].start..results[0].end], results[0].id);
+
+        assert_eq!(results[1].id, "gts.X.bad");
+        assert!(!results[1].valid);
+        assert!(!results[1].error.is_empty());
+        assert_eq!(results[1].line, 1);
+        assert_eq!(results[1].column, 5);
+    }
+
+    #[test]
+    fn test_validate_text_strips_uri_prefix() {
+        let results = validate_text(r#""$ref": "gts://gts.x.core.events.event.v1~""#);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "gts.x.core.events.event.v1~");
+        assert!(results[0].valid);
+    }
+
+    #[test]
+    fn test_handle_validate_text_request() {
+        let server = empty_server();
+        let response = server
+            .handle_line(
+                r#"{"jsonrpc":"2.0","id":1,"method":"validate_text","params":{"text":"gts.x.core.events.event.v1~"}}"#,
+            )
+            .unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["ids"][0]["valid"], true);
+    }
+
+    #[test]
+    fn test_handle_complete_id_request() {
+        let (_dir, server) = server_with_schemas();
+        let response = server
+            .handle_line(
+                r#"{"jsonrpc":"2.0","id":"a","method":"complete_id","params":{"prefix":"gts.x.core."}}"#,
+            )
+            .unwrap();
+        assert_eq!(response["id"], "a");
+        assert_eq!(response["result"]["count"], 2);
+        assert_eq!(
+            response["result"]["items"][0]["id"],
+            "gts.x.core.events.event.v1~"
+        );
+        assert_eq!(response["result"]["items"][0]["is_schema"], true);
+    }
+
+    #[test]
+    fn test_handle_complete_id_respects_limit() {
+        let (_dir, server) = server_with_schemas();
+        let response = server
+            .handle_line(
+                r#"{"jsonrpc":"2.0","id":2,"method":"complete_id","params":{"prefix":"gts.","limit":1}}"#,
+            )
+            .unwrap();
+        assert_eq!(response["result"]["count"], 1);
+    }
+
+    #[test]
+    fn test_handle_errors() {
+        let server = empty_server();
+
+        let response = server.handle_line("not json").unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let response = server.handle_line(r#"{"jsonrpc":"2.0","id":1}"#).unwrap();
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+
+        let response = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"nope"}"#)
+            .unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"validate_text","params":{}}"#)
+            .unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_notification_has_no_response() {
+        let server = empty_server();
+        let response = server
+            .handle_line(r#"{"jsonrpc":"2.0","method":"validate_text","params":{"text":"gts.x"}}"#);
+        assert!(response.is_none());
+    }
+
+    #[test]
+    fn test_serve_writes_one_line_per_request() {
+        let server = empty_server();
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"validate_text","params":{"text":""}}"#,
+            "\n\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"complete_id","params":{}}"#,
+            "\n"
+        );
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["id"], 1);
+        assert_eq!(lines[1]["id"], 2);
+    }
+}
@@ -0,0 +1,339 @@
+//! `gts coverage`: adoption of `#[struct_to_gts_schema]` across a code base.
+//!
+//! Reports the Rust structs that look like event or config payloads but carry
+//! no annotation, and the schemas in the store that no annotated struct
+//! generates. A struct looks like a payload if it derives `Serialize` or
+//! `Deserialize` and its name ends with one of [`PAYLOAD_NAME_SUFFIXES`]
+//! (or, with `all_serde`, whatever its name).
+
+use anyhow::{Result, bail};
+use gts::GtsOps;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+use crate::gen_schemas::{
+    annotated_schema_ids, has_ignore_directive, is_in_auto_ignored_dir, should_exclude_path,
+};
+
+/// Struct name endings of typical event and config payloads
+pub const PAYLOAD_NAME_SUFFIXES: &[&str] = &[
+    "Event",
+    "Command",
+    "Message",
+    "Notification",
+    "Payload",
+    "Config",
+    "Configuration",
+    "Settings",
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct CoverageOptions {
+    /// Source directory or file to scan
+    pub source: PathBuf,
+    /// Store path whose schemas are matched against the annotations
+    pub store: Option<String>,
+    /// GTS config for loading the store
+    pub config: Option<String>,
+    pub exclude: Vec<String>,
+    /// Report every unannotated struct deriving serde traits, whatever its name
+    pub all_serde: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AnnotatedStruct {
+    pub file: String,
+    pub struct_name: String,
+    /// `None` if the attribute could not be parsed
+    pub schema_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnannotatedStruct {
+    pub file: String,
+    pub line: usize,
+    pub struct_name: String,
+    /// Why the struct looks like a payload
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CoverageReport {
+    /// Annotated structs among annotated and payload-like structs, in percent
+    pub coverage_percent: f64,
+    pub annotated: Vec<AnnotatedStruct>,
+    pub unannotated: Vec<UnannotatedStruct>,
+    /// Schemas in the store without an annotated struct; absent without a store
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schemas_without_type: Option<Vec<String>>,
+}
+
+/// Scan the sources (and the store, if any) for annotation coverage
+///
+/// # Errors
+///
+/// Returns an error if the source path does not exist
+pub fn run_coverage(options: &CoverageOptions) -> Result<CoverageReport> {
+    if !options.source.exists() {
+        bail!("Source path does not exist: {}", options.source.display());
+    }
+
+    let scanner = StructScanner::new()?;
+    let mut report = CoverageReport::default();
+    for entry in WalkDir::new(&options.source)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("rs")
+            || should_exclude_path(path, &options.exclude)
+            || is_in_auto_ignored_dir(path)
+        {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        if has_ignore_directive(&content) {
+            continue;
+        }
+
+        let file = path.display().to_string();
+        for (struct_name, schema_id) in annotated_schema_ids(&content)? {
+            report.annotated.push(AnnotatedStruct {
+                file: file.clone(),
+                struct_name,
+                schema_id,
+            });
+        }
+        for (line, struct_name, reasons) in scanner.unannotated(&content, options.all_serde) {
+            report.unannotated.push(UnannotatedStruct {
+                file: file.clone(),
+                line,
+                struct_name,
+                reasons,
+            });
+        }
+    }
+
+    let total = report.annotated.len() + report.unannotated.len();
+    report.coverage_percent = if total == 0 {
+        100.0
+    } else {
+        #[allow(clippy::cast_precision_loss)]
+        let percent = report.annotated.len() as f64 * 100.0 / total as f64;
+        (percent * 10.0).round() / 10.0
+    };
+
+    if let Some(store) = &options.store {
+        let generated: BTreeSet<&str> = report
+            .annotated
+            .iter()
+            .filter_map(|a| a.schema_id.as_deref())
+            .collect();
+        let ops = GtsOps::new(Some(vec![store.clone()]), options.config.clone(), 0);
+        let orphans: BTreeSet<String> = ops
+            .store
+            .items()
+            .filter(|(id, entity)| entity.is_schema && !generated.contains(id.as_str()))
+            .map(|(id, _)| id.clone())
+            .collect();
+        report.schemas_without_type = Some(orphans.into_iter().collect());
+    }
+    Ok(report)
+}
+
+/// Finds struct definitions and the attributes in front of them
+struct StructScanner {
+    structs: Regex,
+    derives: Regex,
+    serde_traits: Regex,
+}
+
+impl StructScanner {
+    fn new() -> Result<Self> {
+        Ok(Self {
+            structs: Regex::new(r"^\s*(?:pub(?:\s*\([^)]*\))?\s+)?struct\s+([A-Za-z_]\w*)")?,
+            derives: Regex::new(r"#\[derive\(([^\]]*)\)\]")?,
+            serde_traits: Regex::new(r"\b(Serialize|Deserialize)\b")?,
+        })
+    }
+
+    /// `(line, name, reasons)` of the unannotated payload-like structs in `content`
+    fn unannotated(&self, content: &str, all_serde: bool) -> Vec<(usize, String, Vec<String>)> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut found = Vec::new();
+        for (idx, line) in lines.iter().enumerate() {
+            let Some(cap) = self.structs.captures(line) else {
+                continue;
+            };
+            let attributes = attributes_before(&lines, idx);
+            if attributes.contains("struct_to_gts_schema") {
+                continue;
+            }
+            let mut reasons: Vec<String> = self
+                .derives
+                .captures_iter(&attributes)
+                .flat_map(|derive| {
+                    self.serde_traits
+                        .captures_iter(derive.get(1).map_or("", |m| m.as_str()))
+                        .map(|c| format!("derives {}", &c[1]))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            if reasons.is_empty() {
+                continue;
+            }
+            let name = &cap[1];
+            match PAYLOAD_NAME_SUFFIXES.iter().find(|s| name.ends_with(*s)) {
+                Some(suffix) => reasons.push(format!("name ends with {suffix}")),
+                None if all_serde => {}
+                None => continue,
+            }
+            found.push((idx + 1, name.to_owned(), reasons));
+        }
+        found
+    }
+}
+
+/// Attribute lines (possibly spanning several lines) and comments directly
+/// above line `idx`, joined
+fn attributes_before(lines: &[&str], idx: usize) -> String {
+    let mut start = idx;
+    // Unclosed `]` seen walking upwards: inside a multi-line attribute
+    let mut depth = 0usize;
+    for i in (0..idx).rev() {
+        let trimmed = lines[i].trim();
+        if depth == 0 && trimmed.starts_with("//") {
+            start = i;
+            continue;
+        }
+        if depth == 0 && !trimmed.ends_with(']') {
+            break;
+        }
+        let closes = trimmed.matches(']').count();
+        let opens = trimmed.matches('[').count();
+        depth = (depth + closes).saturating_sub(opens);
+        start = i;
+    }
+    lines[start..idx].join("\n")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SOURCE: &str = r#"
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug)]
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.core.events.order.v1~",
+    description = "Order",
+    properties = "id"
+)]
+pub struct OrderEvent {
+    pub id: String,
+}
+
+/// Emitted on every refund
+#[derive(Debug, Clone,
+    Serialize, Deserialize)]
+pub struct RefundEvent {
+    pub id: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct CacheKey(String);
+
+#[derive(Debug)]
+pub struct ServerConfig {
+    pub port: u16,
+}
+"#;
+
+    fn project() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), SOURCE).unwrap();
+        fs::create_dir_all(dir.path().join("schemas")).unwrap();
+        for id in [
+            "gts.x.core.events.order.v1~",
+            "gts.x.core.events.legacy.v1~",
+        ] {
+            fs::write(
+                dir.path().join(format!("schemas/{id}.schema.json")),
+                format!(
+                    r#"{{"$id": "gts://{id}", "$schema": "http://json-schema.org/draft-07/schema#"}}"#
+                ),
+            )
+            .unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        let dir = project();
+        let report = run_coverage(&CoverageOptions {
+            source: dir.path().join("src"),
+            store: Some(dir.path().join("schemas").display().to_string()),
+            ..CoverageOptions::default()
+        })
+        .unwrap();
+
+        assert_eq!(report.annotated.len(), 1);
+        assert_eq!(
+            report.annotated[0].schema_id.as_deref(),
+            Some("gts.x.core.events.order.v1~")
+        );
+        let names: Vec<&str> = report
+            .unannotated
+            .iter()
+            .map(|s| s.struct_name.as_str())
+            .collect();
+        assert_eq!(names, ["RefundEvent"]);
+        assert_eq!(report.unannotated[0].line, 19);
+        assert_eq!(
+            report.unannotated[0].reasons,
+            [
+                "derives Serialize",
+                "derives Deserialize",
+                "name ends with Event"
+            ]
+        );
+        assert!((report.coverage_percent - 50.0).abs() < f64::EPSILON);
+        assert_eq!(
+            report.schemas_without_type,
+            Some(vec!["gts.x.core.events.legacy.v1~".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_coverage_all_serde() {
+        let dir = project();
+        let report = run_coverage(&CoverageOptions {
+            source: dir.path().join("src"),
+            all_serde: true,
+            ..CoverageOptions::default()
+        })
+        .unwrap();
+
+        let names: Vec<&str> = report
+            .unannotated
+            .iter()
+            .map(|s| s.struct_name.as_str())
+            .collect();
+        assert_eq!(names, ["RefundEvent", "CacheKey"]);
+        assert!(report.schemas_without_type.is_none());
+    }
+}
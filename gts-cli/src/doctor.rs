@@ -0,0 +1,508 @@
+//! `gts doctor`: diagnose common project setup problems.
+//!
+//! Each check reports `pass`, `warn` or `fail` with a remediation hint; the
+//! run fails if any check fails. Checks never stop at the first problem, so a
+//! broken `gts.toml` still lets the remaining checks run on command-line flags.
+
+use anyhow::Result;
+use gts::{GtsFileNameEncoding, GtsOps};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+
+use crate::gen_schemas::check_generated_schemas;
+use crate::project_config::{PROJECT_CONFIG_FILE, ProjectConfig};
+
+/// Crates published from this repository, expected to share one version
+pub const GTS_CRATES: &[&str] = &[
+    "gts",
+    "gts-cli",
+    "gts-id",
+    "gts-macros",
+    "gts-macros-cli",
+    "gts-validator",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Warn => "warn",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DoctorCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// How to fix a warning or failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DoctorReport {
+    /// No check failed (warnings are allowed)
+    pub ok: bool,
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    #[must_use]
+    pub fn new(checks: Vec<DoctorCheck>) -> Self {
+        Self {
+            ok: checks.iter().all(|c| c.status != CheckStatus::Fail),
+            checks,
+        }
+    }
+
+    /// Plain-text table, with hints below the failing and warning rows
+    #[must_use]
+    pub fn to_table(&self) -> String {
+        let width = self.checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+        let mut out = format!("{:<8}{:<width$}  MESSAGE\n", "STATUS", "CHECK");
+        for check in &self.checks {
+            let _ = writeln!(
+                out,
+                "{:<8}{:<width$}  {}",
+                check.status.label(),
+                check.name,
+                check.message
+            );
+            if let Some(hint) = &check.hint {
+                let _ = writeln!(out, "{:<8}{:<width$}  hint: {hint}", "", "");
+            }
+        }
+        out
+    }
+}
+
+/// What `gts doctor` looks at
+#[derive(Debug, Clone, Default)]
+pub struct DoctorOptions {
+    /// Explicit `--project-config`, else `gts.toml` is discovered from `cwd`
+    pub project_config: Option<PathBuf>,
+    pub cwd: PathBuf,
+    /// `--path`/`--config` given on the command line
+    pub path: Option<String>,
+    pub config: Option<String>,
+    /// `--file-name-encoding` given on the command line
+    pub file_name_encoding: Option<GtsFileNameEncoding>,
+    /// Root of the annotated Rust sources and of the `Cargo.lock` lookup
+    pub source: PathBuf,
+    pub host: String,
+    pub port: u16,
+}
+
+/// Run every check
+#[must_use]
+pub fn run_doctor(options: &DoctorOptions) -> DoctorReport {
+    let (project_check, project) =
+        check_project_config(options.project_config.as_deref(), &options.cwd);
+    // Command-line flags win over the project config, as for every command
+    let path = options.path.clone().or(project.path);
+    let config = options.config.clone().or(project.config);
+    let encoding = options
+        .file_name_encoding
+        .or(project.file_name_encoding)
+        .unwrap_or_default();
+    DoctorReport::new(vec![
+        project_check,
+        check_gts_config(config.as_deref()),
+        check_schemas(path.as_deref(), config),
+        check_annotations(
+            &options.source,
+            &project.generate_from_rust.exclude,
+            encoding,
+        ),
+        check_server_port(&options.host, options.port),
+        check_crate_versions(&options.source),
+    ])
+}
+
+fn check_project_config(explicit: Option<&Path>, cwd: &Path) -> (DoctorCheck, ProjectConfig) {
+    const NAME: &str = "project config";
+    let Some(file) = explicit
+        .map(Path::to_path_buf)
+        .or_else(|| ProjectConfig::discover(cwd))
+    else {
+        return (
+            DoctorCheck::warn(
+                NAME,
+                format!("no {PROJECT_CONFIG_FILE} found"),
+                format!(
+                    "create {PROJECT_CONFIG_FILE} with `path = \"schemas\"` to stop repeating --path"
+                ),
+            ),
+            ProjectConfig::default(),
+        );
+    };
+    match ProjectConfig::load(&file) {
+        Ok(project) => (
+            DoctorCheck::pass(NAME, format!("loaded {}", file.display())),
+            project,
+        ),
+        Err(e) => (
+            DoctorCheck::fail(
+                NAME,
+                format!("{e:#}"),
+                "fix the file; known keys are `path`, `config`, `file-name-encoding`, \
+                 `[generate-from-rust] exclude` and `[lint] require-owner`",
+            ),
+            ProjectConfig::default(),
+        ),
+    }
+}
+
+fn check_gts_config(config: Option<&str>) -> DoctorCheck {
+    const NAME: &str = "gts config";
+    let Some(config) = config else {
+        return DoctorCheck::pass(NAME, "not set, using defaults");
+    };
+    let text = match fs::read_to_string(config) {
+        Ok(text) => text,
+        Err(e) => {
+            return DoctorCheck::fail(
+                NAME,
+                format!("cannot read {config}: {e}"),
+                "point --config (or `config` in gts.toml) at an existing file",
+            );
+        }
+    };
+    match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&text) {
+        Ok(_) => DoctorCheck::pass(NAME, format!("loaded {config}")),
+        // The store silently falls back to defaults on such a file
+        Err(e) => DoctorCheck::fail(
+            NAME,
+            format!("{config} is not a JSON object: {e}"),
+            "fix the JSON; until then the default config is used",
+        ),
+    }
+}
+
+fn check_schemas(path: Option<&str>, config: Option<String>) -> DoctorCheck {
+    const NAME: &str = "schemas";
+    let Some(path) = path else {
+        return DoctorCheck::warn(
+            NAME,
+            "no schema path configured",
+            "pass --path or set `path` in gts.toml",
+        );
+    };
+    if !Path::new(path).exists() {
+        return DoctorCheck::fail(
+            NAME,
+            format!("{path} does not exist"),
+            "create the directory or fix --path / `path` in gts.toml",
+        );
+    }
+    let health = GtsOps::new(Some(vec![path.to_owned()]), config, 0).health();
+    match health.load_errors.first() {
+        None => DoctorCheck::pass(
+            NAME,
+            format!(
+                "{} schema(s), {} instance(s) loaded from {path}",
+                health.schemas, health.instances
+            ),
+        ),
+        Some(first) => DoctorCheck::fail(
+            NAME,
+            format!(
+                "{} file(s) failed to load, first: {}: {}",
+                health.load_errors.len(),
+                first.path,
+                first.message
+            ),
+            "run `gts store-info` for the full list and fix or remove the files",
+        ),
+    }
+}
+
+fn check_annotations(
+    source: &Path,
+    exclude: &[String],
+    encoding: GtsFileNameEncoding,
+) -> DoctorCheck {
+    const NAME: &str = "annotations";
+    let status = match check_generated_schemas(&source.to_string_lossy(), None, exclude, encoding) {
+        Ok(status) => status,
+        Err(e) => {
+            return DoctorCheck::warn(
+                NAME,
+                format!("{e:#}"),
+                "run doctor from the project root or pass --source",
+            );
+        }
+    };
+    if let Some(first) = status.unparsable.first() {
+        return DoctorCheck::fail(
+            NAME,
+            format!(
+                "{} annotation(s) could not be parsed, first: {first}",
+                status.unparsable.len()
+            ),
+            "give #[struct_to_gts_schema] string literals for dir_path and schema_id, and a base",
+        );
+    }
+    let out_of_sync = status.missing.len() + status.stale.len();
+    if out_of_sync > 0 {
+        let first = status.stale.first().or(status.missing.first());
+        return DoctorCheck::fail(
+            NAME,
+            format!(
+                "{out_of_sync} generated schema(s) out of sync ({} stale, {} missing), first: {}",
+                status.stale.len(),
+                status.missing.len(),
+                first.map_or("", String::as_str)
+            ),
+            format!("run `gts generate-from-rust --source {}`", source.display()),
+        );
+    }
+    DoctorCheck::pass(
+        NAME,
+        format!(
+            "{} annotated struct(s), generated schemas in sync",
+            status.annotations
+        ),
+    )
+}
+
+fn check_server_port(host: &str, port: u16) -> DoctorCheck {
+    const NAME: &str = "server port";
+    match TcpListener::bind((host, port)) {
+        Ok(_) => DoctorCheck::pass(NAME, format!("{host}:{port} is free")),
+        Err(e) => DoctorCheck::warn(
+            NAME,
+            format!("cannot bind {host}:{port}: {e}"),
+            "stop the process using the port or start the server with --port",
+        ),
+    }
+}
+
+/// The `Cargo.lock` in `source` or its nearest ancestor
+fn find_cargo_lock(source: &Path) -> Option<PathBuf> {
+    let start = source.canonicalize().ok()?;
+    start
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Versions of the [`GTS_CRATES`] locked in a `Cargo.lock`
+fn locked_gts_versions(lock: &str) -> Result<BTreeMap<String, BTreeSet<String>>> {
+    #[derive(serde::Deserialize)]
+    struct Lock {
+        #[serde(default)]
+        package: Vec<Package>,
+    }
+    #[derive(serde::Deserialize)]
+    struct Package {
+        name: String,
+        version: String,
+    }
+
+    let lock: Lock = toml::from_str(lock)?;
+    let mut versions: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for package in lock.package {
+        if GTS_CRATES.contains(&package.name.as_str()) {
+            versions
+                .entry(package.name)
+                .or_default()
+                .insert(package.version);
+        }
+    }
+    Ok(versions)
+}
+
+fn check_crate_versions(source: &Path) -> DoctorCheck {
+    const NAME: &str = "crate versions";
+    let Some(lock_file) = find_cargo_lock(source) else {
+        return DoctorCheck::warn(
+            NAME,
+            "no Cargo.lock found",
+            "run `cargo generate-lockfile` in the workspace",
+        );
+    };
+    let versions = match fs::read_to_string(&lock_file)
+        .map_err(anyhow::Error::from)
+        .and_then(|text| locked_gts_versions(&text))
+    {
+        Ok(versions) => versions,
+        Err(e) => {
+            return DoctorCheck::warn(
+                NAME,
+                format!("cannot read {}: {e:#}", lock_file.display()),
+                "run `cargo generate-lockfile` in the workspace",
+            );
+        }
+    };
+    let distinct: BTreeSet<&String> = versions.values().flatten().collect();
+    if distinct.len() > 1 {
+        let listing = versions
+            .iter()
+            .map(|(name, v)| format!("{name} {}", v.iter().cloned().collect::<Vec<_>>().join("/")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return DoctorCheck::fail(
+            NAME,
+            format!("mixed gts crate versions: {listing}"),
+            "depend on the same version of every gts crate and run `cargo update`",
+        );
+    }
+    match distinct.first() {
+        Some(version) => DoctorCheck::pass(
+            NAME,
+            format!("{} gts crate(s) at {version}", versions.len()),
+        ),
+        None => DoctorCheck::pass(NAME, "no gts crates in Cargo.lock"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_locked_gts_versions() {
+        let lock = r#"
+version = 4
+
+[[package]]
+name = "gts"
+version = "0.8.4"
+
+[[package]]
+name = "gts-id"
+version = "0.8.3"
+
+[[package]]
+name = "serde"
+version = "1.0.0"
+"#;
+        let versions = locked_gts_versions(lock).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(versions["gts-id"].contains("0.8.3"));
+
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Cargo.lock"), lock).unwrap();
+        let check = check_crate_versions(dir.path());
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.message.contains("gts-id 0.8.3"), "{}", check.message);
+    }
+
+    #[test]
+    fn test_gts_config_check() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("gts.config.json");
+        fs::write(&file, "[1, 2]").unwrap();
+        let file = file.to_string_lossy();
+        assert_eq!(check_gts_config(Some(&file)).status, CheckStatus::Fail);
+        fs::write(dir.path().join("gts.config.json"), "{}").unwrap();
+        assert_eq!(check_gts_config(Some(&file)).status, CheckStatus::Pass);
+        assert_eq!(check_gts_config(None).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn test_annotations_out_of_sync() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("lib.rs"),
+            r#"
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.app.entities.user.v1~",
+    description = "User",
+    properties = "id"
+)]
+pub struct UserV1 {
+    pub id: GtsInstanceId,
+}
+"#,
+        )
+        .unwrap();
+        let check = check_annotations(dir.path(), &[], GtsFileNameEncoding::Raw);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.message.contains("1 missing"), "{}", check.message);
+    }
+
+    #[test]
+    fn test_run_doctor_report() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(PROJECT_CONFIG_FILE), "store = 1\n").unwrap();
+        let schemas = dir.path().join("schemas");
+        fs::create_dir(&schemas).unwrap();
+        let report = run_doctor(&DoctorOptions {
+            cwd: dir.path().to_path_buf(),
+            path: Some(schemas.to_string_lossy().into_owned()),
+            source: dir.path().to_path_buf(),
+            host: "127.0.0.1".to_owned(),
+            port: 0,
+            ..DoctorOptions::default()
+        });
+
+        assert!(!report.ok);
+        let status = |name: &str| {
+            report
+                .checks
+                .iter()
+                .find(|c| c.name == name)
+                .map(|c| c.status)
+        };
+        assert_eq!(status("project config"), Some(CheckStatus::Fail));
+        assert_eq!(status("schemas"), Some(CheckStatus::Pass));
+        assert_eq!(status("annotations"), Some(CheckStatus::Pass));
+        assert_eq!(status("server port"), Some(CheckStatus::Pass));
+
+        let table = report.to_table();
+        assert!(table.starts_with("STATUS"), "{table}");
+        assert!(table.contains("hint: fix the file"), "{table}");
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][0]["status"], "fail");
+    }
+}
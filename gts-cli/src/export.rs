@@ -0,0 +1,280 @@
+//! `gts export` targets.
+//!
+//! `export infra` turns schema traits into a provisioning manifest: the trait
+//! map selects instance types and names the traits to emit, and the manifest
+//! is written as JSON or YAML depending on the output file extension.
+//!
+//! `export partition` splits the store per vendor (or per named pattern) and
+//! writes one `<partition>.bundle.json` per partition next to a
+//! `partition-report.json` listing the references no bundle resolves.
+//! Instances in the bundles are redacted with the store's redaction rules.
+
+use anyhow::{Context, Result, bail};
+use gts::{
+    GtsOps, GtsPartitioning, PartitionKey, PartitionReport, TraitManifestEntry, TraitMap,
+    build_trait_manifest,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Output format of a manifest file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestFormat {
+    Json,
+    Yaml,
+}
+
+impl ManifestFormat {
+    /// `.yaml`/`.yml` files get YAML, everything else JSON
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                Self::Yaml
+            }
+            _ => Self::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportInfraResult {
+    pub ok: bool,
+    pub entries: usize,
+    pub out: String,
+}
+
+/// Build the trait manifest for the map in `trait_map_path`
+///
+/// # Errors
+///
+/// Returns an error if the trait map cannot be read or parsed, or the manifest
+/// cannot be built from the store
+pub fn infra_manifest(ops: &mut GtsOps, trait_map_path: &str) -> Result<Vec<TraitManifestEntry>> {
+    let text = fs::read_to_string(trait_map_path)
+        .with_context(|| format!("Failed to read trait map {trait_map_path}"))?;
+    let map = TraitMap::parse(&text)?;
+    Ok(build_trait_manifest(&mut ops.store, &map)?)
+}
+
+/// Write `entries` to `out`, as YAML or JSON depending on its extension
+///
+/// # Errors
+///
+/// Returns an error if the manifest cannot be serialized or written
+pub fn write_infra_manifest(
+    out: &str,
+    entries: &[TraitManifestEntry],
+) -> Result<ExportInfraResult> {
+    let path = Path::new(out);
+    let content = match ManifestFormat::from_path(path) {
+        ManifestFormat::Yaml => serde_saphyr::to_string(&entries)?,
+        ManifestFormat::Json => serde_json::to_string_pretty(entries)? + "\n",
+    };
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(ExportInfraResult {
+        ok: true,
+        entries: entries.len(),
+        out: out.to_owned(),
+    })
+}
+
+/// File name of the partition report in the output directory
+pub const PARTITION_REPORT_FILE: &str = "partition-report.json";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportPartitionResult {
+    pub ok: bool,
+    /// Written bundle files
+    pub bundles: Vec<String>,
+    pub report: PartitionReport,
+}
+
+/// Partition the store per vendor, or by `NAME=PATTERN` specs if any, with
+/// the instances redacted by `ops.redaction`
+///
+/// # Errors
+///
+/// Returns an error if a spec is not `NAME=PATTERN` or a pattern is invalid
+pub fn partition_store(ops: &GtsOps, specs: &[String]) -> Result<GtsPartitioning> {
+    let key = if specs.is_empty() {
+        PartitionKey::Vendor
+    } else {
+        PartitionKey::Patterns(
+            specs
+                .iter()
+                .map(|spec| match spec.split_once('=') {
+                    Some((name, pattern)) if !name.is_empty() => {
+                        Ok((name.to_owned(), pattern.to_owned()))
+                    }
+                    _ => bail!("Invalid partition '{spec}', expected NAME=PATTERN"),
+                })
+                .collect::<Result<_>>()?,
+        )
+    };
+    let mut partitioning = ops.store.partition(&key)?;
+    for bundle in &mut partitioning.bundles {
+        bundle
+            .instances
+            .values_mut()
+            .for_each(|instance| ops.redaction.redact(instance));
+    }
+    Ok(partitioning)
+}
+
+/// Write the bundles (only those named in `only`, if any) and the report to `out_dir`
+///
+/// # Errors
+///
+/// Returns an error if `only` names an unknown partition or a file cannot be written
+pub fn write_partitions(
+    out_dir: &str,
+    partitioning: &GtsPartitioning,
+    only: &[String],
+) -> Result<ExportPartitionResult> {
+    if let Some(unknown) = only
+        .iter()
+        .find(|name| !partitioning.bundles.iter().any(|b| &b.partition == *name))
+    {
+        bail!("Unknown partition: {unknown}");
+    }
+    let dir = Path::new(out_dir);
+    fs::create_dir_all(dir)?;
+    let mut bundles = Vec::new();
+    for bundle in &partitioning.bundles {
+        if !only.is_empty() && !only.contains(&bundle.partition) {
+            continue;
+        }
+        let path = dir.join(format!("{}.bundle.json", bundle.partition));
+        fs::write(&path, serde_json::to_string_pretty(bundle)? + "\n")?;
+        bundles.push(path.display().to_string());
+    }
+    fs::write(
+        dir.join(PARTITION_REPORT_FILE),
+        serde_json::to_string_pretty(&partitioning.report)? + "\n",
+    )?;
+    Ok(ExportPartitionResult {
+        ok: partitioning.report.ok,
+        bundles,
+        report: partitioning.report.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn ops_with_topic(dir: &Path) -> GtsOps {
+        let schema = json!({
+            "$id": "gts://gts.x.core.events.topic.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "x-gts-traits-schema": {
+                "type": "object",
+                "properties": {"retention": {"type": "string", "default": "P30D"}}
+            }
+        });
+        let instance = json!({"id": "gts.x.core.events.topic.v1~x.app._.orders.v1"});
+        let store_dir = dir.join("store");
+        fs::create_dir_all(&store_dir).unwrap();
+        fs::write(
+            store_dir.join("gts.x.core.events.topic.v1~.schema.json"),
+            schema.to_string(),
+        )
+        .unwrap();
+        fs::write(store_dir.join("orders.instance.json"), instance.to_string()).unwrap();
+        GtsOps::new(
+            Some(vec![store_dir.to_string_lossy().into_owned()]),
+            None,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_manifest_format_from_path() {
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("infra.YML")),
+            ManifestFormat::Yaml
+        );
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("infra.json")),
+            ManifestFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_export_infra_yaml() {
+        let dir = TempDir::new().unwrap();
+        let mut ops = ops_with_topic(dir.path());
+        let map_path = dir.path().join("map.yaml");
+        fs::write(
+            &map_path,
+            "types: [\"gts.x.core.events.topic.*\"]\ntraits:\n  retention: retention_period\n",
+        )
+        .unwrap();
+
+        let entries = infra_manifest(&mut ops, map_path.to_str().unwrap()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].settings["retention_period"], json!("P30D"));
+
+        let out = dir.path().join("out/infra.yaml");
+        let result = write_infra_manifest(out.to_str().unwrap(), &entries).unwrap();
+        assert_eq!(result.entries, 1);
+        let written = fs::read_to_string(&out).unwrap();
+        assert!(written.contains("retention_period: P30D"));
+        assert!(written.contains("gts.x.core.events.topic.v1~x.app._.orders.v1"));
+    }
+
+    #[test]
+    fn test_export_partition_by_vendor() {
+        let dir = TempDir::new().unwrap();
+        let ops = ops_with_topic(dir.path());
+        let partitioning = partition_store(&ops, &[]).unwrap();
+        let out = dir.path().join("bundles");
+        let result =
+            write_partitions(out.to_str().unwrap(), &partitioning, &["x".to_owned()]).unwrap();
+        assert!(result.ok);
+        assert_eq!(result.bundles.len(), 1);
+
+        let bundle: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(out.join("x.bundle.json")).unwrap()).unwrap();
+        assert_eq!(bundle["partition"], "x");
+        assert!(bundle["schemas"]["gts.x.core.events.topic.v1~"].is_object());
+        assert!(out.join(PARTITION_REPORT_FILE).exists());
+
+        assert!(
+            write_partitions(out.to_str().unwrap(), &partitioning, &["acme".to_owned()]).is_err()
+        );
+        assert!(partition_store(&ops, &["=gts.x.*".to_owned()]).is_err());
+    }
+
+    #[test]
+    fn test_export_partition_redacts_instances() {
+        let dir = TempDir::new().unwrap();
+        let mut ops = ops_with_topic(dir.path());
+        ops.redaction = gts::GtsRedaction::parse("redact:\n  - path: $.token\n").unwrap();
+        let instance = json!({"id": "gts.x.core.events.topic.v1~x.app._.audit.v1", "token": "t0p"});
+        assert!(ops.add_entity(&instance, false).ok);
+
+        let partitioning = partition_store(&ops, &[]).unwrap();
+        let bundle = &partitioning.bundles[0];
+        assert_eq!(
+            bundle.instances["gts.x.core.events.topic.v1~x.app._.audit.v1"]["token"],
+            json!(gts::DEFAULT_REDACTION_MASK)
+        );
+    }
+
+    #[test]
+    fn test_export_infra_missing_map() {
+        let dir = TempDir::new().unwrap();
+        let mut ops = ops_with_topic(dir.path());
+        let err = infra_manifest(&mut ops, "/nonexistent/map.yaml").unwrap_err();
+        assert!(err.to_string().contains("Failed to read trait map"));
+    }
+}
@@ -1,11 +1,18 @@
 use anyhow::{Result, bail};
-use gts::{GtsInstanceId, GtsSchemaId};
+use gts::gts::GTS_URI_PREFIX;
+use gts::{
+    GtsFileNameEncoding, GtsID, GtsInstanceId, GtsRetiredIds, GtsSchemaId, GtsWildcard,
+    SCHEMA_FILE_SUFFIX,
+};
 use regex::Regex;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::progress::{ProgressPhase, ProgressReporter};
+
 /// Directories that are automatically ignored (e.g., trybuild `compile_fail` tests)
 const AUTO_IGNORE_DIRS: &[&str] = &["compile_fail"];
 
@@ -46,26 +53,126 @@ enum BaseAttr {
     Parent(String),
 }
 
+/// Selects which schema IDs are generated (`--only` / `--skip-pattern`).
+///
+/// A schema is generated if it matches at least one `only` pattern (or no
+/// `only` patterns are given) and matches none of the `skip` patterns.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaIdFilter {
+    only: Vec<GtsWildcard>,
+    skip: Vec<GtsWildcard>,
+}
+
+impl SchemaIdFilter {
+    /// Build a filter from GTS wildcard patterns such as `gts.x.core.events.*`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any pattern is not a valid GTS wildcard.
+    pub fn new(only: &[String], skip: &[String]) -> Result<Self> {
+        let parse = |patterns: &[String], flag: &str| -> Result<Vec<GtsWildcard>> {
+            patterns
+                .iter()
+                .map(|p| {
+                    GtsWildcard::new(p)
+                        .map_err(|e| anyhow::anyhow!("Invalid {flag} pattern '{p}': {e}"))
+                })
+                .collect()
+        };
+        Ok(Self {
+            only: parse(only, "--only")?,
+            skip: parse(skip, "--skip-pattern")?,
+        })
+    }
+
+    /// Whether no patterns were given (every schema is generated).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.skip.is_empty()
+    }
+
+    /// Whether the schema with `schema_id` should be generated.
+    #[must_use]
+    pub fn allows(&self, schema_id: &str) -> bool {
+        let Ok(id) = GtsID::new(schema_id) else {
+            // Unparseable IDs cannot match a pattern
+            return self.only.is_empty();
+        };
+        (self.only.is_empty() || self.only.iter().any(|p| id.wildcard_match(p)))
+            && !self.skip.iter().any(|p| id.wildcard_match(p))
+    }
+}
+
+/// What `generate-from-rust` did to the schema files, or would do with `--check`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenerationReport {
+    /// Schema files created
+    pub added: Vec<String>,
+    /// Schema files whose content was replaced
+    pub changed: Vec<String>,
+    /// Schema files already up to date, left untouched
+    pub unchanged: usize,
+    /// Schema files of IDs no annotated struct emits any more
+    pub removed: Vec<String>,
+}
+
+impl GenerationReport {
+    /// Whether the files on disk already matched the sources.
+    #[must_use]
+    pub fn is_up_to_date(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Settings of [`generate_schemas_from_rust`].
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptions {
+    /// Output directory override
+    pub output: Option<String>,
+    /// Patterns to exclude (supports simple glob matching)
+    pub exclude: Vec<String>,
+    /// Schema ID patterns selecting which schemas are generated
+    pub id_filter: SchemaIdFilter,
+    /// IDs that must not be generated again
+    pub retired: GtsRetiredIds,
+    /// How schema IDs are encoded in the generated file names
+    pub encoding: GtsFileNameEncoding,
+    /// Only compare the generated schemas with the files on disk
+    pub check: bool,
+    /// Verbosity level (0 = normal, 1+ = show skipped files)
+    pub verbose: u8,
+}
+
 /// Generate GTS schemas from Rust source code with `#[struct_to_gts_schema]` annotations
 ///
+/// Files whose content would not change are not rewritten, so their mtimes
+/// stay put. Without an `id_filter`, schema files left in the output
+/// directories for IDs that no annotated struct under `source` emits any more
+/// are removed. With `check`, nothing is written or removed; the report says
+/// what would be.
+///
 /// # Arguments
 /// * `source` - Source directory or file to scan
-/// * `output` - Optional output directory override
-/// * `exclude_patterns` - Patterns to exclude (supports simple glob matching)
-/// * `verbose` - Verbosity level (0 = normal, 1+ = show skipped files)
+/// * `options` - Output, filters and mode of the run
+/// * `progress` - Receives an event per Rust file, before it is processed
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The source path does not exist
 /// - The output path is outside the source repository
+/// - A schema ID is retired
+/// - Structs of two crates emit the same schema ID
 /// - File I/O operations fail
 pub fn generate_schemas_from_rust(
     source: &str,
-    output: Option<&str>,
-    exclude_patterns: &[String],
-    verbose: u8,
-) -> Result<()> {
+    options: &GenerateOptions,
+    progress: &mut ProgressReporter,
+) -> Result<GenerationReport> {
+    let output = options.output.as_deref();
+    let exclude_patterns = options.exclude.as_slice();
+    let id_filter = &options.id_filter;
+    let (check, verbose) = (options.check, options.verbose);
     println!("Scanning Rust source files in: {source}");
 
     let source_path = Path::new(source);
@@ -73,23 +180,41 @@ pub fn generate_schemas_from_rust(
         bail!("Source path does not exist: {source}");
     }
 
+    // Two crates writing the same ID would overwrite each other's schema file
+    let collisions: Vec<String> = find_schema_collisions(source, exclude_patterns)?
+        .into_iter()
+        .filter(|collision| id_filter.allows(&collision.schema_id))
+        .map(|collision| format!("  - {collision}"))
+        .collect();
+    if !collisions.is_empty() {
+        bail!(
+            "{} schema ID(s) emitted by more than one crate:\n{}",
+            collisions.len(),
+            collisions.join("\n")
+        );
+    }
+
     // Canonicalize source path to detect path traversal attempts
     let source_canonical = source_path.canonicalize()?;
 
-    let mut schemas_generated = 0;
+    let mut report = GenerationReport::default();
+    let mut generated = BTreeSet::new();
+    let mut schemas_filtered = 0;
     let mut files_scanned = 0;
     let mut files_skipped = 0;
 
-    // Walk through all .rs files
-    for entry in WalkDir::new(source_path)
+    // Collect all .rs files first so progress can report a total
+    let files: Vec<PathBuf> = WalkDir::new(source_path)
         .follow_links(true)
         .into_iter()
         .filter_map(Result::ok)
-    {
-        let path = entry.path();
-        if path.extension().and_then(|s| s.to_str()) != Some("rs") {
-            continue;
-        }
+        .filter(|entry| entry.path().extension().and_then(|s| s.to_str()) == Some("rs"))
+        .map(walkdir::DirEntry::into_path)
+        .collect();
+    progress.report(ProgressPhase::Discover, None, 0, files.len());
+
+    for (index, path) in files.iter().enumerate() {
+        progress.report(ProgressPhase::Scan, Some(path), index, files.len());
 
         // Check if path should be excluded
         if should_exclude_path(path, exclude_patterns) {
@@ -133,30 +258,398 @@ pub fn generate_schemas_from_rust(
             }
 
             // Parse the file and extract schema information
-            let results = extract_and_generate_schemas(&content, output, &source_canonical, path)?;
-            schemas_generated += results.len();
-            for (schema_id, file_path) in results {
-                println!("  Generated schema: {schema_id} @ {file_path}");
+            let results = extract_schemas(
+                &content,
+                output,
+                &source_canonical,
+                path,
+                id_filter,
+                &options.retired,
+                options.encoding,
+            )?;
+            for (schema_id, schema) in results {
+                let Some((output_path, schema)) = schema else {
+                    schemas_filtered += 1;
+                    if verbose > 0 {
+                        println!("  Filtered out: {schema_id}");
+                    }
+                    continue;
+                };
+                let file_path = output_path.display().to_string();
+                let text = serde_json::to_string_pretty(&schema)?;
+                let existing = fs::read_to_string(&output_path).ok();
+                if existing.as_deref() == Some(text.as_str()) {
+                    report.unchanged += 1;
+                    if verbose > 0 {
+                        println!("  Unchanged schema: {schema_id} @ {file_path}");
+                    }
+                } else {
+                    if !check {
+                        if let Some(parent) = output_path.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        fs::write(&output_path, text)?;
+                    }
+                    let verb = if check { "Stale" } else { "Generated" };
+                    println!("  {verb} schema: {schema_id} @ {file_path}");
+                    if existing.is_some() {
+                        report.changed.push(file_path);
+                    } else {
+                        report.added.push(file_path);
+                    }
+                }
+                generated.insert(output_path);
             }
         }
     }
 
+    // A filtered run does not regenerate everything, so it cannot tell
+    // which files are left over
+    if id_filter.is_empty() {
+        let dirs: BTreeSet<&Path> = generated.iter().filter_map(|p| p.parent()).collect();
+        for orphan in orphaned_schema_files(source_path, dirs, 1, &generated)? {
+            if !check {
+                fs::remove_file(&orphan)?;
+            }
+            let verb = if check { "Orphaned" } else { "Removed" };
+            println!("  {verb} schema: {}", orphan.display());
+            report.removed.push(orphan.display().to_string());
+        }
+    }
+
+    progress.report(ProgressPhase::Done, None, files.len(), files.len());
+
     println!("\nSummary:");
     println!("  Files scanned: {files_scanned}");
     println!("  Files skipped: {files_skipped}");
-    println!("  Schemas generated: {schemas_generated}");
+    println!("  Schemas added: {}", report.added.len());
+    println!("  Schemas changed: {}", report.changed.len());
+    println!("  Schemas unchanged: {}", report.unchanged);
+    println!("  Schemas removed: {}", report.removed.len());
+    if !id_filter.is_empty() {
+        println!("  Schemas filtered out: {schemas_filtered}");
+    }
 
-    if schemas_generated == 0 {
+    if generated.is_empty() && schemas_filtered == 0 {
         println!(
-            "\n- No schemas found. Make sure your structs are annotated with `#[struct_to_gts_schema(...)]`"
+            "\n- No schemas found. Make sure your structs are annotated with `#[struct_to_gts_schema(...)]` or `#[derive(GtsSchema)]`"
         );
     }
 
-    Ok(())
+    Ok(report)
+}
+
+/// Schema files in `dirs`, up to `max_depth` levels down, that are not in
+/// `expected` and that no annotated struct under `source_path` emits, skipped
+/// files included. Files without a GTS `$id` are never considered generated.
+fn orphaned_schema_files<'a>(
+    source_path: &Path,
+    dirs: impl IntoIterator<Item = &'a Path>,
+    max_depth: usize,
+    expected: &BTreeSet<PathBuf>,
+) -> Result<Vec<PathBuf>> {
+    let mut emitted = HashSet::new();
+    for entry in WalkDir::new(source_path)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().and_then(|s| s.to_str()) == Some("rs"))
+    {
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        for annotated in find_annotated_structs(&content).unwrap_or_default() {
+            if let Some(attrs) = annotated.attrs {
+                emitted.insert(attrs.schema_id);
+            }
+        }
+    }
+
+    let mut orphans = Vec::new();
+    for dir in dirs.into_iter().filter(|dir| dir.is_dir()) {
+        for entry in WalkDir::new(dir).min_depth(1).max_depth(max_depth) {
+            let path = entry?.into_path();
+            if expected.contains(&path)
+                || !path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with(SCHEMA_FILE_SUFFIX))
+            {
+                continue;
+            }
+            let id = fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+                .and_then(|schema| {
+                    schema
+                        .get("$id")?
+                        .as_str()?
+                        .strip_prefix(GTS_URI_PREFIX)
+                        .map(str::to_owned)
+                });
+            if id.is_some_and(|id| !emitted.contains(&id)) {
+                orphans.push(path);
+            }
+        }
+    }
+    Ok(orphans)
+}
+
+/// Schema files `gts clean` removed, or would remove with `--dry-run`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CleanReport {
+    pub dry_run: bool,
+    pub removed: Vec<String>,
+}
+
+/// Remove the generated schema files that no annotated struct under `source`
+/// emits any more, e.g. after a struct was deleted or its schema ID renamed
+///
+/// With `output`, every schema file below that directory is considered;
+/// otherwise only the directories the annotations still generate into are,
+/// as a directory whose last struct is gone cannot be located. Files without
+/// a GTS `$id`, or with one still emitted by a struct (excluded ones
+/// included), are kept. With `dry_run` nothing is removed.
+///
+/// # Errors
+///
+/// Returns an error if the source path does not exist or a file cannot be
+/// removed
+pub fn clean_generated_schemas(
+    source: &str,
+    output: Option<&str>,
+    exclude_patterns: &[String],
+    encoding: GtsFileNameEncoding,
+    dry_run: bool,
+) -> Result<CleanReport> {
+    let source_path = Path::new(source);
+    if !source_path.exists() {
+        bail!("Source path does not exist: {source}");
+    }
+
+    let expected: BTreeSet<PathBuf> = scan_annotated_structs(source_path, exclude_patterns)?
+        .into_iter()
+        .filter_map(|(path, annotated)| {
+            let attrs = annotated.attrs?;
+            Some(AnnotatedStruct::output_path(
+                &attrs,
+                output,
+                source_path,
+                &path,
+                encoding,
+            ))
+        })
+        .collect();
+    let orphans = if let Some(output) = output {
+        orphaned_schema_files(source_path, [Path::new(output)], usize::MAX, &expected)?
+    } else {
+        let dirs: BTreeSet<&Path> = expected.iter().filter_map(|p| p.parent()).collect();
+        orphaned_schema_files(source_path, dirs, 1, &expected)?
+    };
+
+    let mut report = CleanReport {
+        dry_run,
+        removed: Vec::new(),
+    };
+    for orphan in orphans {
+        if !dry_run {
+            fs::remove_file(&orphan)?;
+        }
+        report.removed.push(orphan.display().to_string());
+    }
+    Ok(report)
+}
+
+/// Generated schemas compared with the annotated Rust sources
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaSyncStatus {
+    /// Annotated structs found
+    pub annotations: usize,
+    /// Annotated structs whose attribute could not be parsed, as `file: Struct`
+    pub unparsable: Vec<String>,
+    /// Schema files that `generate-from-rust` would create
+    pub missing: Vec<String>,
+    /// Schema files whose content differs from what `generate-from-rust` would write
+    pub stale: Vec<String>,
+}
+
+/// Compare the schemas `generate-from-rust` would write with the files on
+/// disk, without writing anything
+///
+/// # Errors
+///
+/// Returns an error if the source path does not exist
+pub fn check_generated_schemas(
+    source: &str,
+    output: Option<&str>,
+    exclude_patterns: &[String],
+    encoding: GtsFileNameEncoding,
+) -> Result<SchemaSyncStatus> {
+    let source_path = Path::new(source);
+    if !source_path.exists() {
+        bail!("Source path does not exist: {source}");
+    }
+
+    let mut status = SchemaSyncStatus::default();
+    // Like generation, a later struct with the same output path wins
+    let mut expected = BTreeMap::new();
+    for (path, annotated) in scan_annotated_structs(source_path, exclude_patterns)? {
+        status.annotations += 1;
+        let Some(attrs) = &annotated.attrs else {
+            status
+                .unparsable
+                .push(format!("{}: {}", path.display(), annotated.struct_name));
+            continue;
+        };
+        expected.insert(
+            AnnotatedStruct::output_path(attrs, output, source_path, &path, encoding),
+            annotated.schema(attrs),
+        );
+    }
+
+    for (output_path, schema) in expected {
+        match fs::read_to_string(&output_path) {
+            Err(_) => status.missing.push(output_path.display().to_string()),
+            Ok(text) if serde_json::from_str::<serde_json::Value>(&text).ok() != Some(schema) => {
+                status.stale.push(output_path.display().to_string());
+            }
+            Ok(_) => {}
+        }
+    }
+    Ok(status)
+}
+
+/// A struct emitting a schema ID, located by crate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaSource {
+    /// Directory of the nearest `Cargo.toml` with a `[package]`, or the
+    /// scanned source if there is none
+    pub crate_dir: String,
+    /// `file: Struct`
+    pub location: String,
+}
+
+/// A schema ID emitted by structs of more than one crate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaCollision {
+    pub schema_id: String,
+    /// Whether the structs generate different schemas
+    pub diverging: bool,
+    pub sources: Vec<SchemaSource>,
+}
+
+impl std::fmt::Display for SchemaCollision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let content = if self.diverging {
+            "diverging content"
+        } else {
+            "same content"
+        };
+        let sources: Vec<String> = self
+            .sources
+            .iter()
+            .map(|s| format!("{} ({})", s.crate_dir, s.location))
+            .collect();
+        write!(f, "{} ({content}): {}", self.schema_id, sources.join(", "))
+    }
+}
+
+/// Schema IDs that structs of two or more crates under `source` emit, so
+/// every crate would write its own schema file for the same ID
+///
+/// # Errors
+///
+/// Returns an error if the source path does not exist
+pub fn find_schema_collisions(
+    source: &str,
+    exclude_patterns: &[String],
+) -> Result<Vec<SchemaCollision>> {
+    let source_path = Path::new(source);
+    if !source_path.exists() {
+        bail!("Source path does not exist: {source}");
+    }
+
+    let mut crate_dirs: HashMap<PathBuf, PathBuf> = HashMap::new();
+    let mut emitted: BTreeMap<String, Vec<(SchemaSource, serde_json::Value)>> = BTreeMap::new();
+    for (path, annotated) in scan_annotated_structs(source_path, exclude_patterns)? {
+        let Some(attrs) = &annotated.attrs else {
+            continue;
+        };
+        let dir = path.parent().unwrap_or(source_path).to_path_buf();
+        let crate_dir = crate_dirs.entry(dir).or_insert_with_key(|dir| {
+            crate_dir_of(dir).unwrap_or_else(|| source_path.to_path_buf())
+        });
+        emitted.entry(attrs.schema_id.clone()).or_default().push((
+            SchemaSource {
+                crate_dir: crate_dir.display().to_string(),
+                location: format!("{}: {}", path.display(), annotated.struct_name),
+            },
+            annotated.schema(attrs),
+        ));
+    }
+
+    Ok(emitted
+        .into_iter()
+        .filter(|(_, sources)| {
+            sources
+                .iter()
+                .any(|(source, _)| source.crate_dir != sources[0].0.crate_dir)
+        })
+        .map(|(schema_id, sources)| SchemaCollision {
+            schema_id,
+            diverging: sources.iter().any(|(_, schema)| *schema != sources[0].1),
+            sources: sources.into_iter().map(|(source, _)| source).collect(),
+        })
+        .collect())
+}
+
+/// The nearest directory from `dir` up with a package `Cargo.toml`
+fn crate_dir_of(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|ancestor| {
+            fs::read_to_string(ancestor.join("Cargo.toml"))
+                .is_ok_and(|manifest| manifest.contains("[package]"))
+        })
+        .map(Path::to_path_buf)
+}
+
+/// The annotated structs of the Rust files under `source_path` that
+/// generation does not skip
+fn scan_annotated_structs(
+    source_path: &Path,
+    exclude_patterns: &[String],
+) -> Result<Vec<(PathBuf, AnnotatedStruct)>> {
+    let mut found = Vec::new();
+    for entry in WalkDir::new(source_path)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("rs")
+            || should_exclude_path(path, exclude_patterns)
+            || is_in_auto_ignored_dir(path)
+        {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        if has_ignore_directive(&content) {
+            continue;
+        }
+        found.extend(
+            find_annotated_structs(&content)?
+                .into_iter()
+                .map(|annotated| (path.to_path_buf(), annotated)),
+        );
+    }
+    Ok(found)
 }
 
 /// Check if a path matches any of the exclude patterns
-fn should_exclude_path(path: &Path, patterns: &[String]) -> bool {
+#[must_use]
+pub fn should_exclude_path(path: &Path, patterns: &[String]) -> bool {
     let path_str = path.to_string_lossy();
 
     for pattern in patterns {
@@ -187,7 +680,8 @@ fn matches_glob_pattern(path: &str, pattern: &str) -> bool {
 }
 
 /// Check if path is in an auto-ignored directory (e.g., `compile_fail`)
-fn is_in_auto_ignored_dir(path: &Path) -> bool {
+#[must_use]
+pub fn is_in_auto_ignored_dir(path: &Path) -> bool {
     path.components().any(|component| {
         if let Some(name) = component.as_os_str().to_str() {
             AUTO_IGNORE_DIRS.contains(&name)
@@ -198,7 +692,8 @@ fn is_in_auto_ignored_dir(path: &Path) -> bool {
 }
 
 /// Check if file content starts with the gts:ignore directive
-fn has_ignore_directive(content: &str) -> bool {
+#[must_use]
+pub fn has_ignore_directive(content: &str) -> bool {
     // Check first few lines for the directive
     for line in content.lines().take(10) {
         let trimmed = line.trim();
@@ -262,64 +757,435 @@ fn parse_macro_attrs(attr_body: &str) -> Option<MacroAttrs> {
     })
 }
 
-/// Extract schema metadata from Rust source and generate JSON files
-/// Returns a vector of (`schema_id`, `file_path`) tuples for each generated schema
-fn extract_and_generate_schemas(
-    content: &str,
-    output_override: Option<&str>,
-    source_root: &Path,
-    source_file: &Path,
-) -> Result<Vec<(String, String)>> {
+/// A `#[struct_to_gts_schema(...)]` annotated or `#[derive(GtsSchema)]`
+/// struct, or an annotated enum, found in a source file
+struct AnnotatedStruct {
+    struct_name: String,
+    /// `None` if the attribute could not be parsed
+    attrs: Option<MacroAttrs>,
+    field_types: HashMap<String, String>,
+    /// `description`/`format` of properties from `#[gts(...)]` field attributes
+    property_overrides: HashMap<String, serde_json::Map<String, serde_json::Value>>,
+    /// The variants, for an enum
+    variants: Option<EnumVariants>,
+}
+
+/// The variants of an annotated enum
+struct EnumVariants {
+    /// Serde tag of an enum with data variants (or a `#[serde(tag)]`);
+    /// `None` for a string enum
+    tag: Option<String>,
+    variants: Vec<EnumVariant>,
+}
+
+/// A variant of an annotated enum
+struct EnumVariant {
+    /// Serialized name
+    name: String,
+    /// Serialized names and types of the fields
+    fields: Vec<(String, String)>,
+}
+
+impl AnnotatedStruct {
+    /// Where the schema of the struct is written
+    fn output_path(
+        attrs: &MacroAttrs,
+        output_override: Option<&str>,
+        source_root: &Path,
+        source_file: &Path,
+        encoding: GtsFileNameEncoding,
+    ) -> PathBuf {
+        // e.g., "gts.x.core.events.type.v1~" -> "gts.x.core.events.type.v1~.schema.json",
+        // or "gts.x.core.events.type.v1%7E.schema.json" with percent encoding
+        let schema_file_rel = format!(
+            "{}/{}",
+            attrs.dir_path,
+            encoding.schema_file_name(&attrs.schema_id)
+        );
+
+        if let Some(output_dir) = output_override {
+            // Use CLI-provided output directory
+            Path::new(output_dir).join(&schema_file_rel)
+        } else {
+            // Use path from macro (relative to source file's directory)
+            let source_dir = source_file.parent().unwrap_or(source_root);
+            source_dir.join(&schema_file_rel)
+        }
+    }
+
+    fn schema(&self, attrs: &MacroAttrs) -> serde_json::Value {
+        if let Some(variants) = &self.variants {
+            return build_enum_schema(
+                &attrs.schema_id,
+                &self.struct_name,
+                attrs.description.as_deref(),
+                variants,
+            );
+        }
+        let mut schema = build_json_schema(
+            &attrs.schema_id,
+            &self.struct_name,
+            attrs.description.as_deref(),
+            attrs.properties.as_deref(),
+            &attrs.base,
+            &self.field_types,
+        );
+        let properties = match attrs.base {
+            BaseAttr::IsBase => schema.get_mut("properties"),
+            BaseAttr::Parent(_) => schema.pointer_mut("/allOf/1/properties"),
+        };
+        if let Some(properties) = properties.and_then(serde_json::Value::as_object_mut) {
+            for (name, overrides) in &self.property_overrides {
+                if let Some(property) = properties
+                    .get_mut(name)
+                    .and_then(serde_json::Value::as_object_mut)
+                {
+                    property.extend(overrides.clone());
+                }
+            }
+        }
+        schema
+    }
+}
+
+/// Names and schema IDs (`None` if the attribute cannot be parsed) of the
+/// annotated structs in Rust source
+///
+/// # Errors
+///
+/// Returns an error if the annotation pattern fails to compile
+pub fn annotated_schema_ids(content: &str) -> Result<Vec<(String, Option<String>)>> {
+    Ok(find_annotated_structs(content)?
+        .into_iter()
+        .map(|annotated| {
+            let schema_id = annotated.attrs.map(|attrs| attrs.schema_id);
+            (annotated.struct_name, schema_id)
+        })
+        .collect())
+}
+
+/// Find the annotated structs in Rust source
+fn find_annotated_structs(content: &str) -> Result<Vec<AnnotatedStruct>> {
     // Match #[struct_to_gts_schema(...)] followed by struct definition
     // Captures: (1) attribute body, (2) struct name, (3) optional generics, (4) struct body or semicolon for unit structs
     let re = Regex::new(
-        r"(?s)#\[struct_to_gts_schema\(([^)]+)\)\]\s*(?:#\[[^\]]+\]\s*)*(?:pub\s+)?struct\s+(\w+)(?:<([^>]+)>)?\s*(?:\{([^}]*)\}|;)",
+        r"(?s)#\[struct_to_gts_schema\(((?:[^()]|\([^()]*\))+)\)\]\s*(?:#\[[^\]]+\]\s*)*(?:pub\s+)?struct\s+(\w+)(?:<([^>]+)>)?\s*(?:\{([^}]*)\}|;)",
     )?;
 
     // Pre-compile field regex outside the loop
     let field_re = Regex::new(r"(?m)^\s*(?:pub\s+)?(\w+)\s*:\s*([^,\n]+)")?;
 
-    let mut results = Vec::new();
+    Ok(re
+        .captures_iter(content)
+        .map(|cap| {
+            let struct_body = cap.get(4).map_or("", |m| m.as_str());
+            // Parse struct fields
+            let field_types = field_re
+                .captures_iter(struct_body)
+                .map(|field_cap| {
+                    let field_type = field_cap[2].trim().trim_end_matches(',');
+                    (field_cap[1].to_owned(), field_type.to_owned())
+                })
+                .collect();
+            AnnotatedStruct {
+                struct_name: cap[2].to_owned(),
+                attrs: parse_macro_attrs(&cap[1]),
+                field_types,
+                property_overrides: HashMap::new(),
+                variants: None,
+            }
+        })
+        .chain(find_derived_structs(content)?)
+        .chain(find_annotated_enums(content)?)
+        .collect())
+}
+
+/// Split `s` at the commas outside brackets, dropping empty pieces
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let (mut depth, mut start) = (0i32, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' | ']' | '}' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                pieces.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    pieces.push(&s[start..]);
+    pieces
+        .into_iter()
+        .filter(|p| !p.trim().is_empty())
+        .collect()
+}
 
+/// Split the leading attributes and comments off an enum variant or field
+fn split_leading_attrs(mut s: &str) -> (String, &str) {
+    let mut attrs = String::new();
+    loop {
+        s = s.trim_start();
+        if s.starts_with("//") {
+            s = s.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if s.starts_with("#[") {
+            let mut depth = 0;
+            let end = s
+                .char_indices()
+                .find_map(|(i, c)| {
+                    match c {
+                        '[' => depth += 1,
+                        ']' => depth -= 1,
+                        _ => return None,
+                    }
+                    (depth == 0).then_some(i + 1)
+                })
+                .unwrap_or(s.len());
+            attrs.push_str(&s[..end]);
+            s = &s[end..];
+        } else {
+            return (attrs, s.trim_end());
+        }
+    }
+}
+
+/// The serialized name of an enum variant under a serde `rename_all` rule
+fn rename_variant(variant: &str, rule: &str) -> String {
+    let snake: String = variant
+        .char_indices()
+        .flat_map(|(i, c)| {
+            let separator = (i > 0 && c.is_uppercase()).then_some('_');
+            separator.into_iter().chain(c.to_lowercase())
+        })
+        .collect();
+    match rule {
+        "lowercase" => variant.to_lowercase(),
+        "UPPERCASE" => variant.to_uppercase(),
+        "camelCase" => {
+            let mut chars = variant.chars();
+            chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        "snake_case" => snake,
+        "SCREAMING_SNAKE_CASE" => snake.to_uppercase(),
+        "kebab-case" => snake.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake.to_uppercase().replace('_', "-"),
+        _ => variant.to_owned(),
+    }
+}
+
+/// Find the `#[struct_to_gts_schema(...)]` enums in Rust source: string enums
+/// if all variants are units, otherwise tagged like the macro tags them
+fn find_annotated_enums(content: &str) -> Result<Vec<AnnotatedStruct>> {
+    // Captures: (1) attribute body, (2) other attributes, (3) enum name; the body follows
+    let re = Regex::new(
+        r"(?s)#\[struct_to_gts_schema\(((?:[^()]|\([^()]*\))+)\)\]\s*((?:#\[[^\]]+\]\s*)*)(?:pub(?:\([^)]*\))?\s+)?enum\s+(\w+)\s*\{",
+    )?;
+    let serde_re = |key: &str| Regex::new(&format!(r#"#\[serde\([^\]]*\b{key}\s*=\s*"([^"]+)""#));
+    let (tag_re, rename_all_re, rename_re) = (
+        serde_re("tag")?,
+        serde_re("rename_all")?,
+        serde_re("rename")?,
+    );
+    let variant_re = Regex::new(r"(?s)^(\w+)\s*(?:\{(.*)\})?$")?;
+    let field_re = Regex::new(r"(?s)^(?:pub(?:\([^)]*\))?\s+)?(\w+)\s*:\s*(.+)$")?;
+
+    let mut found = Vec::new();
     for cap in re.captures_iter(content) {
-        let attr_body = &cap[1];
-        let struct_name = &cap[2];
-        let _generics = cap.get(3).map(|m| m.as_str());
-        let struct_body = cap.get(4).map_or("", |m| m.as_str());
+        let Some(whole) = cap.get(0) else {
+            continue;
+        };
+        // The enum body runs to the matching closing brace
+        let rest = &content[whole.end()..];
+        let mut depth = 1;
+        let body_len = rest
+            .char_indices()
+            .find_map(|(i, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                (depth == 0).then_some(i)
+            })
+            .unwrap_or(rest.len());
+
+        let enum_attrs = &cap[2];
+        let rename_all = rename_all_re.captures(enum_attrs).map(|c| c[1].to_owned());
+        let mut variants = Vec::new();
+        for piece in split_top_level(&rest[..body_len]) {
+            let (attrs, variant) = split_leading_attrs(piece);
+            let Some(variant) = variant_re.captures(variant) else {
+                continue;
+            };
+            let name = rename_re.captures(&attrs).map_or_else(
+                || rename_variant(&variant[1], rename_all.as_deref().unwrap_or_default()),
+                |c| c[1].to_owned(),
+            );
+            let fields = split_top_level(variant.get(2).map_or("", |m| m.as_str()))
+                .into_iter()
+                .filter_map(|field| {
+                    let (attrs, field) = split_leading_attrs(field);
+                    let field = field_re.captures(field)?;
+                    let name = rename_re
+                        .captures(&attrs)
+                        .map_or_else(|| field[1].to_owned(), |c| c[1].to_owned());
+                    Some((name, field[2].trim().to_owned()))
+                })
+                .collect::<Vec<_>>();
+            variants.push(EnumVariant { name, fields });
+        }
+        let has_data = variants.iter().any(|v| !v.fields.is_empty());
+        let tag = tag_re
+            .captures(enum_attrs)
+            .map(|c| c[1].to_owned())
+            .or_else(|| has_data.then(|| "type".to_owned()));
+
+        found.push(AnnotatedStruct {
+            struct_name: cap[3].to_owned(),
+            attrs: parse_macro_attrs(&cap[1]),
+            field_types: HashMap::new(),
+            property_overrides: HashMap::new(),
+            variants: Some(EnumVariants { tag, variants }),
+        });
+    }
+    Ok(found)
+}
 
-        // Parse macro attributes
-        let Some(attrs) = parse_macro_attrs(attr_body) else {
+/// Find the `#[derive(GtsSchema)]` structs in Rust source: every field is a
+/// property unless marked `#[gts(skip)]`
+fn find_derived_structs(content: &str) -> Result<Vec<AnnotatedStruct>> {
+    // Captures: (1) attributes, (2) struct name, (3) struct body
+    let re = Regex::new(
+        r"(?s)((?:#\[[^\]]+\]\s*)+)(?:pub\s+)?struct\s+(\w+)(?:<[^>]+>)?\s*(?:\{([^}]*)\}|;)",
+    )?;
+    let derive_re = Regex::new(r"#\[derive\([^\]]*\bGtsSchema\b")?;
+    let gts_attr_re = Regex::new(r"#\[gts\(([^\]]*)\)\]")?;
+    let serde_rename_re = Regex::new(r#"#\[serde\([^\]]*\brename\s*=\s*"([^"]+)""#)?;
+    let field_re = Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?(\w+)\s*:\s*([^,\n]+)")?;
+    let string_re = |key: &str| Regex::new(&format!(r#"\b{key}\s*=\s*"([^"]*)""#));
+    let (rename_re, description_re, format_re) = (
+        string_re("rename")?,
+        string_re("description")?,
+        string_re("format")?,
+    );
+    let skip_re = Regex::new(r"\bskip\b")?;
+
+    let mut found = Vec::new();
+    for cap in re.captures_iter(content) {
+        let attrs = &cap[1];
+        if !derive_re.is_match(attrs) {
+            continue;
+        }
+        let Some(gts_attr) = gts_attr_re.captures(attrs) else {
             continue;
         };
 
-        // Convert schema_id to filename-safe format
-        // e.g., "gts.x.core.events.type.v1~" -> "gts.x.core.events.type.v1~"
-        let schema_file_rel = format!("{}/{}.schema.json", attrs.dir_path, attrs.schema_id);
+        let mut field_types = HashMap::new();
+        let mut property_overrides = HashMap::new();
+        // Attribute lines collect until the field they annotate
+        let mut pending = String::new();
+        for line in cap.get(3).map_or("", |m| m.as_str()).lines() {
+            if line.trim_start().starts_with("#[") {
+                pending.push_str(line);
+                continue;
+            }
+            let Some(field) = field_re.captures(line) else {
+                continue;
+            };
+            let field_attrs: Vec<&str> = gts_attr_re
+                .captures_iter(&pending)
+                .filter_map(|c| c.get(1).map(|m| m.as_str()))
+                .collect();
+            if !field_attrs.iter().any(|a| skip_re.is_match(a)) {
+                let lookup = |re: &Regex| {
+                    field_attrs
+                        .iter()
+                        .find_map(|a| re.captures(a).map(|c| c[1].to_owned()))
+                };
+                let name = lookup(&rename_re)
+                    .or_else(|| serde_rename_re.captures(&pending).map(|c| c[1].to_owned()))
+                    .unwrap_or_else(|| field[1].to_owned());
+                let overrides: serde_json::Map<String, serde_json::Value> = [
+                    ("description", lookup(&description_re)),
+                    ("format", lookup(&format_re)),
+                ]
+                .into_iter()
+                .filter_map(|(key, value)| Some((key.to_owned(), value?.into())))
+                .collect();
+                if !overrides.is_empty() {
+                    property_overrides.insert(name.clone(), overrides);
+                }
+                let field_type = field[2].trim().trim_end_matches(',');
+                field_types.insert(name, field_type.to_owned());
+            }
+            pending.clear();
+        }
+
+        found.push(AnnotatedStruct {
+            struct_name: cap[2].to_owned(),
+            attrs: parse_macro_attrs(&gts_attr[1]),
+            field_types,
+            property_overrides,
+            variants: None,
+        });
+    }
+    Ok(found)
+}
 
-        // Determine output path
-        let output_path = if let Some(output_dir) = output_override {
-            // Use CLI-provided output directory
-            Path::new(output_dir).join(&schema_file_rel)
-        } else {
-            // Use path from macro (relative to source file's directory)
-            let source_dir = source_file.parent().unwrap_or(source_root);
-            source_dir.join(&schema_file_rel)
-        };
+/// A schema built from an annotated struct, and the file it belongs in
+type ExtractedSchema = (PathBuf, serde_json::Value);
 
-        // Security check: ensure output path doesn't escape source repository
-        let output_canonical = if output_path.exists() {
-            output_path.canonicalize()?
-        } else {
-            // For non-existent files, canonicalize the parent directory
-            let parent = output_path.parent().unwrap_or(Path::new("."));
-            fs::create_dir_all(parent)?;
-            let parent_canonical = parent.canonicalize()?;
-            let file_name = output_path
-                .file_name()
-                .ok_or_else(|| anyhow::anyhow!("Invalid output path: no file name"))?;
-            parent_canonical.join(file_name)
+/// Extract schema metadata from Rust source and build the JSON schemas
+/// Returns a vector of (`schema_id`, (`output_path`, `schema`)) tuples for each
+/// annotated struct; the schema is `None` for schemas rejected by `id_filter`
+fn extract_schemas(
+    content: &str,
+    output_override: Option<&str>,
+    source_root: &Path,
+    source_file: &Path,
+    id_filter: &SchemaIdFilter,
+    retired: &GtsRetiredIds,
+    encoding: GtsFileNameEncoding,
+) -> Result<Vec<(String, Option<ExtractedSchema>)>> {
+    let mut results = Vec::new();
+
+    for annotated in find_annotated_structs(content)? {
+        let struct_name = &annotated.struct_name;
+        let Some(attrs) = &annotated.attrs else {
+            continue;
         };
 
+        if !id_filter.allows(&attrs.schema_id) {
+            results.push((attrs.schema_id.clone(), None));
+            continue;
+        }
+        if let Err(e) = retired.check(&attrs.schema_id) {
+            bail!("{}: {struct_name}: {e}", source_file.display());
+        }
+
+        let output_path = AnnotatedStruct::output_path(
+            attrs,
+            output_override,
+            source_root,
+            source_file,
+            encoding,
+        );
+
+        // Security check: ensure output path doesn't escape source repository.
+        // Missing directories are resolved from their nearest existing
+        // ancestor, so nothing is created before the check passes
+        let existing = output_path
+            .ancestors()
+            .find(|ancestor| ancestor.exists())
+            .unwrap_or(Path::new("."));
+        let output_canonical = existing
+            .canonicalize()?
+            .join(output_path.strip_prefix(existing).unwrap_or(&output_path));
+
         // Check if output path is within source repository
         if !output_canonical.starts_with(source_root) {
             bail!(
@@ -333,35 +1199,10 @@ fn extract_and_generate_schemas(
             );
         }
 
-        // Parse struct fields
-        let mut field_types = HashMap::new();
-
-        for field_cap in field_re.captures_iter(struct_body) {
-            let field_name = &field_cap[1];
-            let field_type = field_cap[2].trim().trim_end_matches(',');
-            field_types.insert(field_name.to_owned(), field_type.to_owned());
-        }
-
-        // Build JSON schema
-        let schema = build_json_schema(
-            &attrs.schema_id,
-            struct_name,
-            attrs.description.as_deref(),
-            attrs.properties.as_deref(),
-            &attrs.base,
-            &field_types,
-        );
-
-        // Create parent directories
-        if let Some(parent) = output_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-
-        // Write schema file
-        fs::write(&output_path, serde_json::to_string_pretty(&schema)?)?;
-
-        // Add to results (schema_id, file_path)
-        results.push((attrs.schema_id, output_path.display().to_string()));
+        results.push((
+            attrs.schema_id.clone(),
+            Some((output_path, annotated.schema(attrs))),
+        ));
     }
 
     Ok(results)
@@ -461,6 +1302,63 @@ fn build_json_schema(
     }
 }
 
+/// Build the JSON Schema of an annotated enum: a string enum, or a `oneOf`
+/// of one object per variant with the tag as `discriminator`
+fn build_enum_schema(
+    schema_id: &str,
+    enum_name: &str,
+    description: Option<&str>,
+    enum_variants: &EnumVariants,
+) -> serde_json::Value {
+    use serde_json::json;
+
+    let mut s = json!({
+        "$id": format!("gts://{schema_id}"),
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": enum_name,
+    });
+    if let Some(desc) = description {
+        s["description"] = json!(desc);
+    }
+
+    let Some(tag) = &enum_variants.tag else {
+        s["type"] = json!("string");
+        s["enum"] = enum_variants
+            .variants
+            .iter()
+            .map(|variant| json!(variant.name))
+            .collect();
+        return s;
+    };
+
+    let branches: Vec<serde_json::Value> = enum_variants
+        .variants
+        .iter()
+        .map(|variant| {
+            let mut properties = serde_json::Map::new();
+            properties.insert(tag.clone(), json!({ "const": variant.name }));
+            let mut required = vec![tag.clone()];
+            for (field, field_type) in &variant.fields {
+                let (is_required, json_type_info) = rust_type_to_json_schema(field_type);
+                properties.insert(field.clone(), json_type_info);
+                if is_required {
+                    required.push(field.clone());
+                }
+            }
+            json!({
+                "type": "object",
+                "additionalProperties": false,
+                "properties": properties,
+                "required": required
+            })
+        })
+        .collect();
+    s["type"] = json!("object");
+    s["discriminator"] = json!({ "propertyName": tag });
+    s["oneOf"] = json!(branches);
+    s
+}
+
 /// Derive parent schema ID from child schema ID
 /// e.g., "gts.x.core.events.type.v1~x.core.audit.event.v1~" -> "gts.x.core.events.type.v1~"
 fn derive_parent_schema_id(schema_id: &str) -> String {
@@ -790,7 +1688,7 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_and_generate_schemas_single_struct() {
+    fn test_extract_schemas_single_struct() {
         use std::fs;
         use tempfile::TempDir;
 
@@ -816,12 +1714,15 @@ pub struct Person {
 "#;
         fs::write(&test_file, content).unwrap();
 
-        // Call extract_and_generate_schemas
-        let results = extract_and_generate_schemas(
+        // Call extract_schemas
+        let results = extract_schemas(
             content,
             Some(temp_path.to_str().unwrap()),
             &temp_path,
             &test_file,
+            &SchemaIdFilter::default(),
+            &GtsRetiredIds::new(),
+            GtsFileNameEncoding::Raw,
         )
         .unwrap();
 
@@ -829,14 +1730,15 @@ pub struct Person {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].0, "gts.x.test.person.v1~");
 
-        // Verify schema file was created
-        let schema_path = Path::new(&results[0].1);
-        assert!(schema_path.exists());
+        // Verify the schema path; nothing is written yet
+        let (schema_path, schema) = results[0].1.as_ref().unwrap();
+        assert_eq!(
+            *schema_path,
+            temp_path.join("schemas/gts.x.test.person.v1~.schema.json")
+        );
+        assert!(!schema_path.exists());
 
         // Verify schema content
-        let schema_content = fs::read_to_string(schema_path).unwrap();
-        let schema: serde_json::Value = serde_json::from_str(&schema_content).unwrap();
-
         assert_eq!(schema["$id"], "gts://gts.x.test.person.v1~");
         assert_eq!(schema["title"], "Person");
         assert_eq!(schema["type"], "object");
@@ -847,7 +1749,138 @@ pub struct Person {
     }
 
     #[test]
-    fn test_extract_and_generate_schemas_with_parent() {
+    fn test_find_annotated_structs_with_parent_field() {
+        let content = r#"
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = EnvelopeV1(payload),
+    schema_id = "gts.x.test.envelope.v1~x.test._.order.v1~",
+    description = "Order payload",
+    properties = "order_id"
+)]
+pub struct OrderV1 {
+    pub order_id: String,
+}
+"#;
+        let found = find_annotated_structs(content).unwrap();
+        assert_eq!(found.len(), 1);
+        let attrs = found[0].attrs.as_ref().unwrap();
+        assert!(matches!(attrs.base, BaseAttr::Parent(ref p) if p == "EnvelopeV1"));
+    }
+
+    #[test]
+    fn test_find_annotated_enums() {
+        let content = r#"
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.status.v1~",
+    description = "Order status"
+)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusV1 {
+    /// Not shipped yet
+    Pending,
+    InTransit,
+    #[serde(rename = "done")]
+    Delivered,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.shape.v1~",
+    description = "Shapes"
+)]
+pub enum ShapeV1 {
+    Circle { radius: f64 },
+    Rectangle {
+        width: u32,
+        label: Option<String>,
+    },
+    Empty,
+}
+"#;
+        let found = find_annotated_structs(content).unwrap();
+        assert_eq!(found.len(), 2);
+
+        let status = found[0].schema(found[0].attrs.as_ref().unwrap());
+        assert_eq!(status["type"], "string");
+        assert_eq!(
+            status["enum"],
+            serde_json::json!(["pending", "in_transit", "done"])
+        );
+        assert_eq!(status["title"], "StatusV1");
+
+        let shape = found[1].schema(found[1].attrs.as_ref().unwrap());
+        assert_eq!(
+            shape["discriminator"],
+            serde_json::json!({"propertyName": "type"})
+        );
+        assert_eq!(
+            shape["oneOf"][1],
+            serde_json::json!({
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "type": {"const": "Rectangle"},
+                    "width": {"type": "integer"},
+                    "label": {"type": ["string", "null"]}
+                },
+                "required": ["type", "width"]
+            })
+        );
+        assert_eq!(
+            shape["oneOf"][2]["properties"],
+            serde_json::json!({"type": {"const": "Empty"}})
+        );
+    }
+
+    #[test]
+    fn test_find_derived_structs_applies_field_attrs() {
+        let content = r#"
+/// A user
+#[derive(Debug, Serialize, Deserialize, GtsSchema)]
+#[gts(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.user.v1~",
+    description = "A derived user"
+)]
+pub struct UserV1 {
+    pub id: GtsInstanceId,
+    #[gts(format = "email", description = "Login address")]
+    pub email: String,
+    #[serde(rename = "displayName")]
+    pub display_name: Option<String>,
+    #[serde(skip)]
+    #[gts(skip)]
+    pub cache: Option<String>,
+}
+"#;
+        let found = find_annotated_structs(content).unwrap();
+        assert_eq!(found.len(), 1);
+        let attrs = found[0].attrs.as_ref().unwrap();
+        assert_eq!(attrs.schema_id, "gts.x.test.user.v1~");
+
+        let schema = found[0].schema(attrs);
+        let mut names: Vec<_> = schema["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        names.sort_unstable();
+        assert_eq!(names, ["displayName", "email", "id"]);
+        assert_eq!(schema["properties"]["email"]["format"], "email");
+        assert_eq!(
+            schema["properties"]["email"]["description"],
+            "Login address"
+        );
+    }
+
+    #[test]
+    fn test_extract_schemas_with_parent() {
         use std::fs;
         use tempfile::TempDir;
 
@@ -883,12 +1916,15 @@ pub struct ChildEvent {
 "#;
         fs::write(&test_file, content).unwrap();
 
-        // Call extract_and_generate_schemas
-        let results = extract_and_generate_schemas(
+        // Call extract_schemas
+        let results = extract_schemas(
             content,
             Some(temp_path.to_str().unwrap()),
             &temp_path,
             &test_file,
+            &SchemaIdFilter::default(),
+            &GtsRetiredIds::new(),
+            GtsFileNameEncoding::Raw,
         )
         .unwrap();
 
@@ -906,18 +1942,12 @@ pub struct ChildEvent {
             .unwrap();
 
         // Verify base schema
-        let base_schema_path = Path::new(&base_result.1);
-        assert!(base_schema_path.exists());
-        let base_schema: serde_json::Value =
-            serde_json::from_str(&fs::read_to_string(base_schema_path).unwrap()).unwrap();
+        let (_, base_schema) = base_result.1.as_ref().unwrap();
         assert_eq!(base_schema["title"], "BaseEvent");
         assert!(base_schema["properties"]["id"].is_object());
 
         // Verify child schema
-        let child_schema_path = Path::new(&child_result.1);
-        assert!(child_schema_path.exists());
-        let child_schema: serde_json::Value =
-            serde_json::from_str(&fs::read_to_string(child_schema_path).unwrap()).unwrap();
+        let (_, child_schema) = child_result.1.as_ref().unwrap();
         assert_eq!(child_schema["title"], "ChildEvent (extends BaseEvent)");
 
         // Verify allOf structure with parent reference
@@ -1030,9 +2060,12 @@ pub struct ChildEvent {
         // Call with exclude pattern
         let result = generate_schemas_from_rust(
             temp_path.to_str().unwrap(),
-            None,
-            &["test_*.rs".to_owned()],
-            1, // verbose
+            &GenerateOptions {
+                exclude: vec!["test_*.rs".to_owned()],
+                verbose: 1,
+                ..GenerateOptions::default()
+            },
+            &mut ProgressReporter::new("generate-from-rust", None),
         );
 
         assert!(result.is_ok());
@@ -1051,18 +2084,311 @@ pub struct ChildEvent {
 
         let result = generate_schemas_from_rust(
             temp_path.to_str().unwrap(),
-            None,
-            &[],
-            1, // verbose
+            &GenerateOptions {
+                verbose: 1,
+                ..GenerateOptions::default()
+            },
+            &mut ProgressReporter::new("generate-from-rust", None),
         );
 
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_schema_id_filter() {
+        let all = SchemaIdFilter::default();
+        assert!(all.is_empty());
+        assert!(all.allows("gts.x.core.events.type.v1~"));
+
+        let filter = SchemaIdFilter::new(
+            &["gts.x.core.events.*".to_owned()],
+            &["gts.x.core.events.type.v1~x.core.audit.*".to_owned()],
+        )
+        .unwrap();
+        assert!(filter.allows("gts.x.core.events.type.v1~"));
+        assert!(filter.allows("gts.x.core.events.type.v1~x.core.login.event.v1~"));
+        assert!(!filter.allows("gts.x.core.events.type.v1~x.core.audit.event.v1~"));
+        assert!(!filter.allows("gts.x.core.modules.module.v1~"));
+        assert!(!filter.allows("not-a-gts-id"));
+
+        let skip_only = SchemaIdFilter::new(&[], &["gts.x.core.modules.*".to_owned()]).unwrap();
+        assert!(skip_only.allows("gts.x.core.events.type.v1~"));
+        assert!(!skip_only.allows("gts.x.core.modules.module.v1~"));
+    }
+
+    #[test]
+    fn test_schema_id_filter_invalid_pattern() {
+        let err = SchemaIdFilter::new(&["x.core.*".to_owned()], &[]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Invalid --only pattern 'x.core.*'")
+        );
+    }
+
+    #[test]
+    fn test_extract_schemas_filtered() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().canonicalize().unwrap();
+        let test_file = temp_path.join("test.rs");
+        let content = r#"
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.core.events.type.v1~",
+    description = "Event"
+)]
+pub struct Event {
+    pub id: GtsInstanceId,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.core.modules.module.v1~",
+    description = "Module"
+)]
+pub struct Module {
+    pub id: GtsInstanceId,
+}
+"#;
+        let filter = SchemaIdFilter::new(&["gts.x.core.events.*".to_owned()], &[]).unwrap();
+        let results = extract_schemas(
+            content,
+            Some(temp_path.to_str().unwrap()),
+            &temp_path,
+            &test_file,
+            &filter,
+            &GtsRetiredIds::new(),
+            GtsFileNameEncoding::Raw,
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "gts.x.core.events.type.v1~");
+        assert!(results[0].1.is_some());
+        assert_eq!(
+            results[1],
+            ("gts.x.core.modules.module.v1~".to_owned(), None)
+        );
+        assert!(
+            !temp_path
+                .join("schemas/gts.x.core.modules.module.v1~.schema.json")
+                .exists()
+        );
+
+        let mut retired = GtsRetiredIds::new();
+        retired.insert(gts::RetiredIdEntry {
+            id: "gts.x.core.modules.module.v1~".to_owned(),
+            reason: "Split into plugins".to_owned(),
+            date: "2025-11-02".to_owned(),
+        });
+        let err = extract_schemas(
+            content,
+            Some(temp_path.to_str().unwrap()),
+            &temp_path,
+            &test_file,
+            &SchemaIdFilter::default(),
+            &retired,
+            GtsFileNameEncoding::Raw,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Module: GTS ID 'gts.x.core.modules.module.v1~' was retired on 2025-11-02 (Split into plugins)"));
+    }
+
+    #[test]
+    fn test_generated_file_names_follow_encoding() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().canonicalize().unwrap();
+        fs::write(
+            temp_path.join("event.rs"),
+            r#"
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.core.events.type.v1~",
+    description = "Event"
+)]
+pub struct Event {
+    pub id: GtsInstanceId,
+}
+"#,
+        )
+        .unwrap();
+        let source = temp_path.to_str().unwrap();
+
+        generate_schemas_from_rust(
+            source,
+            &GenerateOptions {
+                encoding: GtsFileNameEncoding::Percent,
+                ..GenerateOptions::default()
+            },
+            &mut ProgressReporter::new("generate-from-rust", None),
+        )
+        .unwrap();
+        assert!(
+            temp_path
+                .join("schemas/gts.x.core.events.type.v1%7E.schema.json")
+                .exists()
+        );
+        assert!(
+            !temp_path
+                .join("schemas/gts.x.core.events.type.v1~.schema.json")
+                .exists()
+        );
+
+        let status =
+            check_generated_schemas(source, None, &[], GtsFileNameEncoding::Percent).unwrap();
+        assert!(status.missing.is_empty() && status.stale.is_empty());
+        // The raw names the check would expect were never written
+        let status = check_generated_schemas(source, None, &[], GtsFileNameEncoding::Raw).unwrap();
+        assert_eq!(status.missing.len(), 1);
+    }
+
+    #[test]
+    fn test_generate_schemas_from_rust_is_incremental() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().canonicalize().unwrap();
+        let event = |description: &str| {
+            format!(
+                r#"
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.core.events.type.v1~",
+    description = "{description}"
+)]
+pub struct Event {{
+    pub id: GtsInstanceId,
+}}
+"#
+            )
+        };
+        fs::write(temp_path.join("event.rs"), event("Event")).unwrap();
+        let source = temp_path.to_str().unwrap();
+        let generate = |check| {
+            generate_schemas_from_rust(
+                source,
+                &GenerateOptions {
+                    check,
+                    ..GenerateOptions::default()
+                },
+                &mut ProgressReporter::new("generate-from-rust", None),
+            )
+            .unwrap()
+        };
+        let schema_path = temp_path.join("schemas/gts.x.core.events.type.v1~.schema.json");
+
+        let report = generate(false);
+        assert_eq!(report.added, [schema_path.display().to_string()]);
+        let modified = fs::metadata(&schema_path).unwrap().modified().unwrap();
+
+        // Nothing changed: the file is left alone
+        let report = generate(false);
+        assert!(report.is_up_to_date());
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(
+            fs::metadata(&schema_path).unwrap().modified().unwrap(),
+            modified
+        );
+
+        // A changed source is reported by a check, but not written
+        fs::write(temp_path.join("event.rs"), event("Any event")).unwrap();
+        let orphan = temp_path.join("schemas/gts.x.core.events.old.v1~.schema.json");
+        fs::write(
+            &orphan,
+            r#"{"$id": "gts://gts.x.core.events.old.v1~", "type": "object"}"#,
+        )
+        .unwrap();
+        let unrelated = temp_path.join("schemas/notes.schema.json");
+        fs::write(&unrelated, r#"{"type": "object"}"#).unwrap();
+        let report = generate(true);
+        assert_eq!(report.changed, [schema_path.display().to_string()]);
+        assert_eq!(report.removed, [orphan.display().to_string()]);
+        assert!(
+            !fs::read_to_string(&schema_path)
+                .unwrap()
+                .contains("Any event")
+        );
+        assert!(orphan.exists());
+
+        let report = generate(false);
+        assert_eq!(report.changed.len(), 1);
+        assert!(
+            fs::read_to_string(&schema_path)
+                .unwrap()
+                .contains("Any event")
+        );
+        assert!(!orphan.exists());
+        assert!(unrelated.exists());
+        assert!(generate(true).is_up_to_date());
+    }
+
+    #[test]
+    fn test_clean_generated_schemas_without_output() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().canonicalize().unwrap();
+        let event = |schema_id: &str| {
+            format!(
+                r#"
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "{schema_id}",
+    description = "Event"
+)]
+pub struct Event {{
+    pub id: GtsInstanceId,
+}}
+"#
+            )
+        };
+        fs::write(temp_path.join("a.rs"), event("gts.x.core.events.a.v1~")).unwrap();
+        fs::write(temp_path.join("b.rs"), event("gts.x.core.events.b.v1~")).unwrap();
+        let source = temp_path.to_str().unwrap();
+        generate_schemas_from_rust(
+            source,
+            &GenerateOptions::default(),
+            &mut ProgressReporter::new("generate-from-rust", None),
+        )
+        .unwrap();
+
+        // b.rs is renamed to c.rs with a new ID; the schema of b is left over
+        fs::remove_file(temp_path.join("b.rs")).unwrap();
+        fs::write(temp_path.join("c.rs"), event("gts.x.core.events.c.v1~")).unwrap();
+        let orphan = temp_path.join("schemas/gts.x.core.events.b.v1~.schema.json");
+
+        let report =
+            clean_generated_schemas(source, None, &[], GtsFileNameEncoding::Raw, true).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.removed, [orphan.display().to_string()]);
+        assert!(orphan.exists());
+
+        let report =
+            clean_generated_schemas(source, None, &[], GtsFileNameEncoding::Raw, false).unwrap();
+        assert_eq!(report.removed, [orphan.display().to_string()]);
+        assert!(!orphan.exists());
+        assert!(
+            temp_path
+                .join("schemas/gts.x.core.events.a.v1~.schema.json")
+                .exists()
+        );
+    }
+
     #[test]
     fn test_generate_schemas_from_rust_nonexistent_path() {
-        let result =
-            generate_schemas_from_rust("/nonexistent/path/that/does/not/exist", None, &[], 0);
+        let result = generate_schemas_from_rust(
+            "/nonexistent/path/that/does/not/exist",
+            &GenerateOptions::default(),
+            &mut ProgressReporter::new("generate-from-rust", None),
+        );
 
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("does not exist"));
@@ -0,0 +1,152 @@
+//! gRPC service next to the REST API (`grpc` feature).
+//!
+//! [`GtsGrpcService`] implements `proto/gts/v1/gts.proto` on top of the same
+//! [`AppState`] as the REST handlers, so both APIs see one store, its
+//! background load and config reloads.
+
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tonic::transport::server::TcpIncoming;
+use tonic::{Request, Response, Status};
+
+use crate::server::AppState;
+
+/// Generated protobuf messages, client and server.
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+pub mod proto {
+    tonic::include_proto!("gts.v1");
+}
+
+use proto::gts_service_server::{GtsService, GtsServiceServer};
+use proto::{
+    EffectiveTraitsRequest, EffectiveTraitsResponse, GetSchemaRequest, GetSchemaResponse,
+    QueryRequest, QueryResponse, ValidateInstanceRequest, ValidateInstanceResponse,
+};
+
+/// Results returned by `Query` when the request sets no limit, as on `/query`.
+const DEFAULT_QUERY_LIMIT: usize = 100;
+
+pub struct GtsGrpcService {
+    state: AppState,
+}
+
+impl GtsGrpcService {
+    #[must_use]
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// The tonic service, ready to be added to a `tonic::transport::Server`.
+    #[must_use]
+    pub fn into_server(self) -> GtsServiceServer<Self> {
+        GtsServiceServer::new(self)
+    }
+
+    fn lock_ops(&self) -> Result<std::sync::MutexGuard<'_, gts::GtsOps>, Status> {
+        self.state
+            .ops
+            .lock()
+            .map_err(|_| Status::internal("Server state corrupted"))
+    }
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<String, Status> {
+    serde_json::to_string(value).map_err(|e| Status::internal(e.to_string()))
+}
+
+#[tonic::async_trait]
+impl GtsService for GtsGrpcService {
+    async fn get_schema(
+        &self,
+        request: Request<GetSchemaRequest>,
+    ) -> Result<Response<GetSchemaResponse>, Status> {
+        let gts_id = request.into_inner().gts_id;
+        let result = self.lock_ops()?.get_entity(&gts_id);
+        let response = match result.content {
+            Some(content) if result.is_schema => GetSchemaResponse {
+                ok: true,
+                id: result.id,
+                content_json: to_json(&content)?,
+                owner: result.metadata.and_then(|m| m.owner),
+                error: String::new(),
+            },
+            Some(_) => GetSchemaResponse {
+                id: result.id,
+                error: format!("Entity '{gts_id}' is not a schema"),
+                ..GetSchemaResponse::default()
+            },
+            None => GetSchemaResponse {
+                error: result.error,
+                ..GetSchemaResponse::default()
+            },
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn query(
+        &self,
+        request: Request<QueryRequest>,
+    ) -> Result<Response<QueryResponse>, Status> {
+        let request = request.into_inner();
+        let limit = match usize::try_from(request.limit) {
+            Ok(0) | Err(_) => DEFAULT_QUERY_LIMIT,
+            Ok(limit) => limit,
+        };
+        let result = self.lock_ops()?.query(&request.expr, limit);
+        let count = |n: usize| u32::try_from(n).unwrap_or(u32::MAX);
+        Ok(Response::new(QueryResponse {
+            count: count(result.count),
+            limit: count(result.limit),
+            results_json: result
+                .results
+                .iter()
+                .map(to_json)
+                .collect::<Result<_, _>>()?,
+            error: result.error,
+        }))
+    }
+
+    async fn validate_instance(
+        &self,
+        request: Request<ValidateInstanceRequest>,
+    ) -> Result<Response<ValidateInstanceResponse>, Status> {
+        let instance_id = request.into_inner().instance_id;
+        let result = self.lock_ops()?.validate_instance(&instance_id);
+        Ok(Response::new(ValidateInstanceResponse {
+            id: result.id,
+            ok: result.ok,
+            error: result.error,
+        }))
+    }
+
+    async fn effective_traits(
+        &self,
+        request: Request<EffectiveTraitsRequest>,
+    ) -> Result<Response<EffectiveTraitsResponse>, Status> {
+        let schema_id = request.into_inner().schema_id;
+        let result = self.lock_ops()?.effective_traits(&schema_id);
+        Ok(Response::new(EffectiveTraitsResponse {
+            id: result.id,
+            ok: result.ok,
+            traits_json: if result.ok {
+                to_json(&Value::Object(result.traits))?
+            } else {
+                String::new()
+            },
+            error: result.error,
+        }))
+    }
+}
+
+/// Serve the gRPC API on `listener` until the process exits.
+///
+/// # Errors
+///
+/// Returns an error if the server fails while serving requests.
+pub async fn serve(state: AppState, listener: TcpListener) -> anyhow::Result<()> {
+    tonic::transport::Server::builder()
+        .add_service(GtsGrpcService::new(state).into_server())
+        .serve_with_incoming(TcpIncoming::from(listener))
+        .await?;
+    Ok(())
+}
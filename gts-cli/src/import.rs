@@ -0,0 +1,811 @@
+//! `gts import`: convert existing JSON Schemas into GTS schemas.
+//!
+//! The source is either an `OpenAPI` 3 document, whose `components.schemas`
+//! are imported, or a directory of JSON Schema files (`.json`, `.yaml`,
+//! `.yml`). Schemas are named by their component name, or by their path
+//! relative to the directory without the extension (`common/address`).
+//!
+//! Each schema gets the GTS ID the mapping file assigns to its name, else
+//! `gts.<vendor>.<package>.<namespace>.<name>.<version>~` with the name turned
+//! into a segment token (`OrderItem` -> `order_item`). `$ref`s between
+//! imported schemas are rewritten to `gts://` form and `OpenAPI`'s `nullable`
+//! and `example` to their JSON Schema equivalents. Everything else that has
+//! no GTS equivalent is kept or dropped as reported in [`ImportResult::issues`],
+//! never silently.
+//!
+//! ```yaml
+//! version: v2                           # of generated IDs, default v1
+//! ids:
+//!   Order: gts.acme.shop._.order.v1~
+//!   common/address: gts.acme.shop._.address.v1~
+//! ```
+
+use anyhow::{Context, Result, bail};
+use gts::gts::GTS_URI_PREFIX;
+use gts::{GtsFileNameEncoding, GtsID, GtsRetiredIds};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// `$schema` of schemas imported without one (and from `OpenAPI` 3.0)
+const DRAFT_07: &str = "http://json-schema.org/draft-07/schema#";
+/// `$schema` of schemas imported from `OpenAPI` 3.1
+const DRAFT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+const COMPONENT_REF_PREFIX: &str = "#/components/schemas/";
+
+/// Keywords whose value maps names to subschemas
+const SCHEMA_MAP_KEYWORDS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "definitions",
+    "$defs",
+    "dependentSchemas",
+];
+/// Keywords whose value is an array of subschemas
+const SCHEMA_ARRAY_KEYWORDS: &[&str] = &["allOf", "anyOf", "oneOf", "prefixItems"];
+/// Keywords whose value is a subschema (`items` may also be an array)
+const SCHEMA_KEYWORDS: &[&str] = &[
+    "items",
+    "additionalItems",
+    "additionalProperties",
+    "not",
+    "if",
+    "then",
+    "else",
+    "contains",
+    "propertyNames",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+];
+
+/// `--mapping` file: GTS IDs of the imported schemas
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportMapping {
+    /// Version of the generated IDs (default `v1`)
+    pub version: Option<String>,
+    /// Schema name -> GTS schema ID
+    #[serde(default)]
+    pub ids: BTreeMap<String, String>,
+}
+
+impl ImportMapping {
+    /// Parse a YAML/JSON mapping file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid mapping
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read mapping {}", path.display()))?;
+        serde_saphyr::from_str(&text).with_context(|| format!("Invalid mapping {}", path.display()))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// `OpenAPI` document or directory of JSON Schema files
+    pub from: PathBuf,
+    pub vendor: String,
+    pub package: String,
+    pub namespace: String,
+    pub mapping: ImportMapping,
+    /// Directory the GTS schemas are written to
+    pub out: PathBuf,
+    pub encoding: GtsFileNameEncoding,
+    /// Replace existing schema files instead of refusing to write
+    pub overwrite: bool,
+    /// IDs that must not be imported
+    pub retired: GtsRetiredIds,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportedSchema {
+    pub name: String,
+    pub schema_id: String,
+    pub path: String,
+}
+
+/// A construct that could not be carried over as is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportIssue {
+    pub name: String,
+    /// JSON path of the construct in the source schema
+    pub json_path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportResult {
+    /// Whether every schema was imported without issues
+    pub ok: bool,
+    pub imported: Vec<ImportedSchema>,
+    pub issues: Vec<ImportIssue>,
+}
+
+/// Convert the source schemas and write them to `options.out`
+///
+/// Nothing is written if any target file exists (unless `overwrite`).
+///
+/// # Errors
+///
+/// Returns an error if the source cannot be read, the mapping names unknown
+/// schemas or assigns invalid, duplicate or retired IDs, or a file cannot be written
+pub fn run_import(options: &ImportOptions) -> Result<ImportResult> {
+    let (kind, schemas) = read_sources(&options.from)?;
+    let ids = assign_ids(&schemas, options)?;
+    let mut converter = Converter::new(kind, &ids, &schemas);
+
+    let mut outputs = Vec::new();
+    for source in &schemas {
+        let (Some(schema_id), Some(content)) = (ids.get(&source.name), converter.convert(source))
+        else {
+            continue;
+        };
+        options
+            .retired
+            .check(schema_id)
+            .with_context(|| format!("Cannot import '{}'", source.name))?;
+        let path = options
+            .out
+            .join(options.encoding.schema_file_name(schema_id));
+        if path.exists() && !options.overwrite {
+            bail!(
+                "Refusing to overwrite existing file: {} (pass --overwrite to replace it)",
+                path.display()
+            );
+        }
+        outputs.push((source, schema_id, path, content));
+    }
+
+    fs::create_dir_all(&options.out)?;
+    let mut imported = Vec::new();
+    for (source, schema_id, path, content) in outputs {
+        fs::write(
+            &path,
+            format!("{}\n", serde_json::to_string_pretty(&content)?),
+        )?;
+        imported.push(ImportedSchema {
+            name: source.name.clone(),
+            schema_id: schema_id.clone(),
+            path: path.to_string_lossy().into_owned(),
+        });
+    }
+    let issues = converter.issues;
+    Ok(ImportResult {
+        ok: issues.is_empty(),
+        imported,
+        issues,
+    })
+}
+
+/// Where the source schemas come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceKind {
+    /// `components.schemas` of an `OpenAPI` 3.0 or 3.1 (`v3_1`) document
+    OpenApi {
+        v3_1: bool,
+    },
+    Directory,
+}
+
+struct SourceSchema {
+    name: String,
+    /// File relative to the source directory, for directory imports
+    file: Option<PathBuf>,
+    content: Value,
+}
+
+impl SourceSchema {
+    /// The schema's own `$id`, without an empty fragment
+    fn uri(&self) -> Option<&str> {
+        self.content
+            .get("$id")
+            .and_then(Value::as_str)
+            .map(|id| id.trim_end_matches('#'))
+    }
+}
+
+fn read_sources(from: &Path) -> Result<(SourceKind, Vec<SourceSchema>)> {
+    if from.is_dir() {
+        return read_directory(from).map(|schemas| (SourceKind::Directory, schemas));
+    }
+    let document = parse_file(from)?;
+    let Some(version) = document.get("openapi").and_then(Value::as_str) else {
+        bail!(
+            "{} is not an OpenAPI 3 document; pass a directory to import JSON Schema files",
+            from.display()
+        );
+    };
+    if !version.starts_with("3.") {
+        bail!("Unsupported OpenAPI version {version}, expected 3.x");
+    }
+    let components = document
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let schemas = components
+        .into_iter()
+        .map(|(name, content)| SourceSchema {
+            name,
+            file: None,
+            content,
+        })
+        .collect();
+    Ok((
+        SourceKind::OpenApi {
+            v3_1: version.starts_with("3.1"),
+        },
+        schemas,
+    ))
+}
+
+fn read_directory(dir: &Path) -> Result<Vec<SourceSchema>> {
+    let mut schemas = Vec::new();
+    for entry in WalkDir::new(dir)
+        .follow_links(true)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some(stem) = [".schema.json", ".json", ".yaml", ".yml"]
+            .into_iter()
+            .find_map(|ext| file_name.strip_suffix(ext))
+        else {
+            continue;
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let file = path.strip_prefix(dir).unwrap_or(path).to_path_buf();
+        let mut name: Vec<String> = file
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        name.push(stem.to_owned());
+        schemas.push(SourceSchema {
+            name: name.join("/"),
+            file: Some(file),
+            content: parse_file(path)?,
+        });
+    }
+    Ok(schemas)
+}
+
+fn parse_file(path: &Path) -> Result<Value> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let is_yaml = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("yaml") || e.eq_ignore_ascii_case("yml"));
+    if is_yaml {
+        serde_saphyr::from_str(&text).with_context(|| format!("Invalid YAML in {}", path.display()))
+    } else {
+        serde_json::from_str(&text).with_context(|| format!("Invalid JSON in {}", path.display()))
+    }
+}
+
+/// Schema name -> GTS ID, from the mapping or generated
+fn assign_ids(
+    schemas: &[SourceSchema],
+    options: &ImportOptions,
+) -> Result<HashMap<String, String>> {
+    if let Some(unknown) = options
+        .mapping
+        .ids
+        .keys()
+        .find(|name| !schemas.iter().any(|s| &s.name == *name))
+    {
+        bail!("Mapping assigns an ID to unknown schema '{unknown}'");
+    }
+    let version = options.mapping.version.as_deref().unwrap_or("v1");
+    let mut ids = HashMap::new();
+    let mut names_by_id: HashMap<String, &str> = HashMap::new();
+    for schema in schemas {
+        let id = match options.mapping.ids.get(&schema.name) {
+            Some(id) => id.strip_prefix(GTS_URI_PREFIX).unwrap_or(id).to_owned(),
+            None => format!(
+                "gts.{}.{}.{}.{}.{version}~",
+                options.vendor,
+                options.package,
+                options.namespace,
+                type_token(&schema.name)
+            ),
+        };
+        let parsed = GtsID::new(&id)
+            .with_context(|| format!("Invalid GTS ID '{id}' for schema '{}'", schema.name))?;
+        if !parsed.is_type() {
+            bail!(
+                "'{id}' assigned to schema '{}' is not a schema ID (must end with ~)",
+                schema.name
+            );
+        }
+        if let Some(other) = names_by_id.insert(id.clone(), &schema.name) {
+            bail!(
+                "Schemas '{other}' and '{}' both map to {id}; assign one of them an ID in the mapping",
+                schema.name
+            );
+        }
+        ids.insert(schema.name.clone(), id);
+    }
+    Ok(ids)
+}
+
+/// Segment token for a schema name: `OrderItem` -> `order_item`,
+/// `common/postal-address` -> `common_postal_address`
+fn type_token(name: &str) -> String {
+    let mut token = String::with_capacity(name.len());
+    let mut after_word_char = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if after_word_char {
+                token.push('_');
+            }
+            token.push(c.to_ascii_lowercase());
+            after_word_char = false;
+        } else if c.is_ascii_lowercase() || c.is_ascii_digit() {
+            token.push(c);
+            after_word_char = true;
+        } else {
+            if !token.ends_with('_') {
+                token.push('_');
+            }
+            after_word_char = false;
+        }
+    }
+    if !token.starts_with(|c: char| c.is_ascii_lowercase() || c == '_') {
+        token.insert(0, '_');
+    }
+    token
+}
+
+/// Rewrites source schemas into GTS schemas, collecting issues
+struct Converter<'a> {
+    kind: SourceKind,
+    ids: &'a HashMap<String, String>,
+    /// Source file -> schema name, for directory imports
+    files: HashMap<PathBuf, String>,
+    /// Original `$id` -> schema name
+    uris: HashMap<String, String>,
+    issues: Vec<ImportIssue>,
+}
+
+impl<'a> Converter<'a> {
+    fn new(kind: SourceKind, ids: &'a HashMap<String, String>, schemas: &[SourceSchema]) -> Self {
+        Self {
+            kind,
+            ids,
+            files: schemas
+                .iter()
+                .filter_map(|s| Some((s.file.clone()?, s.name.clone())))
+                .collect(),
+            uris: schemas
+                .iter()
+                .filter_map(|s| Some((s.uri()?.to_owned(), s.name.clone())))
+                .collect(),
+            issues: Vec::new(),
+        }
+    }
+
+    fn issue(&mut self, source: &SourceSchema, json_path: &str, message: String) {
+        self.issues.push(ImportIssue {
+            name: source.name.clone(),
+            json_path: json_path.to_owned(),
+            message,
+        });
+    }
+
+    /// The GTS schema for `source`; `None` if it cannot be imported
+    fn convert(&mut self, source: &SourceSchema) -> Option<Value> {
+        let ids = self.ids;
+        let schema_id = ids.get(&source.name)?;
+        let Value::Object(mut body) = source.content.clone() else {
+            self.issue(
+                source,
+                "$",
+                "schema is not an object and was not imported".to_owned(),
+            );
+            return None;
+        };
+        body.remove("$id");
+        let dialect = match (body.remove("$schema"), self.kind) {
+            (Some(Value::String(dialect)), _) => dialect,
+            (_, SourceKind::OpenApi { v3_1: true }) => DRAFT_2020_12.to_owned(),
+            _ => DRAFT_07.to_owned(),
+        };
+        self.node(&mut body, source, "$");
+
+        let mut schema = Map::new();
+        schema.insert(
+            "$id".to_owned(),
+            json!(format!("{GTS_URI_PREFIX}{schema_id}")),
+        );
+        schema.insert("$schema".to_owned(), json!(dialect));
+        schema.extend(body);
+        Some(Value::Object(schema))
+    }
+
+    fn node(&mut self, schema: &mut Map<String, Value>, source: &SourceSchema, path: &str) {
+        if path != "$"
+            && let Some(id) = schema.remove("$id")
+        {
+            self.issue(
+                source,
+                &format!("{path}.$id"),
+                format!("nested `$id` {id} was removed; references to it are not rewritten"),
+            );
+        }
+        if let Some(Value::String(reference)) = schema.get_mut("$ref")
+            && let Some(rewritten) = self.reference(reference, source, path)
+        {
+            *reference = rewritten;
+        }
+        for keyword in ["$dynamicRef", "$recursiveRef"] {
+            if schema.contains_key(keyword) {
+                self.issue(
+                    source,
+                    &format!("{path}.{keyword}"),
+                    format!("`{keyword}` has no GTS equivalent and was kept as is"),
+                );
+            }
+        }
+        if matches!(self.kind, SourceKind::OpenApi { .. }) {
+            self.openapi_keywords(schema, source, path);
+        }
+
+        for (keyword, value) in schema.iter_mut() {
+            let keyword = keyword.as_str();
+            match value {
+                Value::Object(subschemas) if SCHEMA_MAP_KEYWORDS.contains(&keyword) => {
+                    for (name, subschema) in subschemas.iter_mut() {
+                        self.subschema(subschema, source, &format!("{path}.{keyword}.{name}"));
+                    }
+                }
+                Value::Array(subschemas)
+                    if SCHEMA_ARRAY_KEYWORDS.contains(&keyword) || keyword == "items" =>
+                {
+                    for (index, subschema) in subschemas.iter_mut().enumerate() {
+                        self.subschema(subschema, source, &format!("{path}.{keyword}[{index}]"));
+                    }
+                }
+                _ if SCHEMA_KEYWORDS.contains(&keyword) => {
+                    self.subschema(value, source, &format!("{path}.{keyword}"));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn subschema(&mut self, value: &mut Value, source: &SourceSchema, path: &str) {
+        if let Value::Object(schema) = value {
+            self.node(schema, source, path);
+        }
+    }
+
+    /// Replace `OpenAPI`-only keywords by JSON Schema ones where possible
+    fn openapi_keywords(
+        &mut self,
+        schema: &mut Map<String, Value>,
+        source: &SourceSchema,
+        path: &str,
+    ) {
+        if schema.remove("nullable") == Some(Value::Bool(true)) {
+            match schema.get_mut("type") {
+                Some(ty @ Value::String(_)) => *ty = json!([ty.take(), "null"]),
+                Some(Value::Array(types)) => {
+                    if !types.contains(&json!("null")) {
+                        types.push(json!("null"));
+                    }
+                }
+                _ => self.issue(
+                    source,
+                    &format!("{path}.nullable"),
+                    "`nullable` without `type` cannot be expressed and was dropped; \
+                     the schema no longer accepts null"
+                        .to_owned(),
+                ),
+            }
+        }
+        if let Some(example) = schema.remove("example") {
+            schema.entry("examples").or_insert_with(|| json!([example]));
+        }
+        for keyword in ["discriminator", "xml"] {
+            if schema.remove(keyword).is_some() {
+                self.issue(
+                    source,
+                    &format!("{path}.{keyword}"),
+                    format!("OpenAPI `{keyword}` has no JSON Schema equivalent and was dropped"),
+                );
+            }
+        }
+    }
+
+    /// The rewritten `$ref`, or `None` to keep it as is
+    fn reference(&mut self, reference: &str, source: &SourceSchema, path: &str) -> Option<String> {
+        if reference.starts_with(GTS_URI_PREFIX) {
+            return None;
+        }
+        let target = match self.kind {
+            SourceKind::OpenApi { .. } => match reference.strip_prefix(COMPONENT_REF_PREFIX) {
+                Some(name) if !name.contains('/') => Ok(name.replace("~1", "/").replace("~0", "~")),
+                Some(_) => Err("points inside another component"),
+                None if reference.starts_with('#') => Err("points outside `components.schemas`"),
+                None => Err("is an external reference"),
+            },
+            SourceKind::Directory => {
+                let (location, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+                if location.is_empty() {
+                    // Local to the schema, valid as is
+                    return None;
+                }
+                match self.resolve_location(location, source) {
+                    Some(name) if fragment.is_empty() || fragment == "/" => Ok(name),
+                    Some(_) => Err("points inside another schema"),
+                    None => Err("does not resolve to an imported schema"),
+                }
+            }
+        };
+        let rewritten = target.and_then(|name| {
+            self.ids
+                .get(&name)
+                .map(|id| format!("{GTS_URI_PREFIX}{id}"))
+                .ok_or("does not resolve to an imported schema")
+        });
+        match rewritten {
+            Ok(rewritten) => Some(rewritten),
+            Err(reason) => {
+                self.issue(
+                    source,
+                    &format!("{path}.$ref"),
+                    format!("`$ref` {reference} {reason} and was kept as is"),
+                );
+                None
+            }
+        }
+    }
+
+    /// Name of the schema a `$ref` location (URI or relative file) points to
+    fn resolve_location(&self, location: &str, source: &SourceSchema) -> Option<String> {
+        if let Some(name) = self.uris.get(location) {
+            return Some(name.clone());
+        }
+        if location.contains("://") {
+            return None;
+        }
+        if let Some(base) = source.uri()
+            && let Some(dir_end) = base.rfind('/')
+            && let Some(name) = self.uris.get(&format!("{}{location}", &base[..=dir_end]))
+        {
+            return Some(name.clone());
+        }
+        let dir = source.file.as_deref()?.parent().unwrap_or(Path::new(""));
+        let mut file = PathBuf::new();
+        for component in dir.join(location).components() {
+            match component {
+                Component::ParentDir => {
+                    file.pop();
+                }
+                Component::Normal(part) => file.push(part),
+                _ => {}
+            }
+        }
+        self.files.get(&file).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn import_options(from: &Path, out: &Path) -> ImportOptions {
+        ImportOptions {
+            from: from.to_path_buf(),
+            vendor: "acme".to_owned(),
+            package: "imported".to_owned(),
+            namespace: "_".to_owned(),
+            out: out.to_path_buf(),
+            ..ImportOptions::default()
+        }
+    }
+
+    fn read(path: &str) -> Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_type_token() {
+        assert_eq!(type_token("OrderItem"), "order_item");
+        assert_eq!(type_token("common/postal-address"), "common_postal_address");
+        assert_eq!(type_token("v2Event"), "v2_event");
+        assert_eq!(type_token("3dModel"), "_3d_model");
+    }
+
+    #[test]
+    fn test_import_openapi_components() {
+        let dir = TempDir::new().unwrap();
+        let spec = dir.path().join("openapi.yaml");
+        fs::write(
+            &spec,
+            r"openapi: 3.0.3
+info: {title: Shop, version: '1'}
+components:
+  schemas:
+    Order:
+      type: object
+      discriminator: {propertyName: kind}
+      properties:
+        id: {type: string, example: o-1}
+        note: {type: string, nullable: true}
+        items:
+          type: array
+          items: {$ref: '#/components/schemas/OrderItem'}
+        owner: {$ref: '#/components/responses/Owner'}
+    OrderItem:
+      type: object
+      properties:
+        sku: {type: string}
+",
+        )
+        .unwrap();
+        let out = dir.path().join("schemas");
+        let mut options = import_options(&spec, &out);
+        options
+            .mapping
+            .ids
+            .insert("Order".to_owned(), "gts.acme.shop._.order.v1~".to_owned());
+
+        let result = run_import(&options).unwrap();
+        assert!(!result.ok);
+        assert_eq!(result.imported.len(), 2);
+        assert_eq!(result.imported[0].schema_id, "gts.acme.shop._.order.v1~");
+        assert_eq!(
+            result.imported[1].schema_id,
+            "gts.acme.imported._.order_item.v1~"
+        );
+
+        let order = read(&result.imported[0].path);
+        assert_eq!(order["$id"], "gts://gts.acme.shop._.order.v1~");
+        assert_eq!(order["$schema"], DRAFT_07);
+        assert_eq!(
+            order["properties"]["items"]["items"]["$ref"],
+            "gts://gts.acme.imported._.order_item.v1~"
+        );
+        assert_eq!(
+            order["properties"]["note"]["type"],
+            json!(["string", "null"])
+        );
+        assert_eq!(order["properties"]["id"]["examples"], json!(["o-1"]));
+        assert!(order.get("discriminator").is_none());
+
+        let paths: Vec<&str> = result.issues.iter().map(|i| i.json_path.as_str()).collect();
+        assert_eq!(paths, ["$.discriminator", "$.properties.owner.$ref"]);
+
+        // The imported schemas load into a store
+        let mut ops = gts::GtsOps::new(Some(vec![out.to_string_lossy().into_owned()]), None, 0);
+        assert!(ops.validate_schema("gts.acme.shop._.order.v1~").ok);
+
+        assert!(run_import(&options).is_err());
+        options.overwrite = true;
+        assert!(run_import(&options).is_ok());
+
+        options.retired.insert(gts::RetiredIdEntry {
+            id: "gts.acme.shop._.order.v1~".to_owned(),
+            reason: "Moved to the orders package".to_owned(),
+            date: "2025-11-02".to_owned(),
+        });
+        let err = run_import(&options).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("retired on 2025-11-02"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_import_directory_rewrites_file_refs() {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir_all(src.join("common")).unwrap();
+        fs::write(
+            src.join("order.schema.json"),
+            r##"{
+  "$id": "https://example.com/schemas/order.schema.json",
+  "type": "object",
+  "properties": {
+    "ship_to": {"$ref": "common/address.json"},
+    "bill_to": {"$ref": "https://example.com/schemas/common/address.json"},
+    "lines": {"type": "array", "items": {"$ref": "#/$defs/line"}},
+    "zip": {"$ref": "common/address.json#/properties/zip"}
+  },
+  "$defs": {"line": {"type": "object"}}
+}"##,
+        )
+        .unwrap();
+        fs::write(
+            src.join("common/address.json"),
+            r#"{"$id": "https://example.com/schemas/common/address.json", "type": "object"}"#,
+        )
+        .unwrap();
+        fs::write(src.join("notes.txt"), "not a schema").unwrap();
+
+        let out = dir.path().join("out");
+        let mut options = import_options(&src, &out);
+        options.encoding = GtsFileNameEncoding::Percent;
+        options.mapping.version = Some("v2".to_owned());
+        let result = run_import(&options).unwrap();
+
+        let names: Vec<&str> = result.imported.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["common/address", "order"]);
+        assert!(
+            result.imported[1]
+                .path
+                .ends_with("gts.acme.imported._.order.v2%7E.schema.json")
+        );
+        let order = read(&result.imported[1].path);
+        let address = "gts://gts.acme.imported._.common_address.v2~";
+        assert_eq!(order["properties"]["ship_to"]["$ref"], address);
+        assert_eq!(order["properties"]["bill_to"]["$ref"], address);
+        assert_eq!(
+            order["properties"]["lines"]["items"]["$ref"],
+            "#/$defs/line"
+        );
+        assert_eq!(
+            order["properties"]["zip"]["$ref"],
+            "common/address.json#/properties/zip"
+        );
+        assert_eq!(result.issues.len(), 1);
+        assert!(
+            result.issues[0]
+                .message
+                .contains("points inside another schema")
+        );
+    }
+
+    #[test]
+    fn test_import_rejects_bad_mappings() {
+        let dir = TempDir::new().unwrap();
+        let spec = dir.path().join("openapi.json");
+        fs::write(
+            &spec,
+            r#"{"openapi": "3.1.0", "components": {"schemas": {"A": {}, "B": {}}}}"#,
+        )
+        .unwrap();
+        let out = dir.path().join("out");
+
+        let mut options = import_options(&spec, &out);
+        options
+            .mapping
+            .ids
+            .insert("C".to_owned(), "gts.acme.x._.c.v1~".to_owned());
+        let err = run_import(&options).unwrap_err();
+        assert!(err.to_string().contains("unknown schema 'C'"));
+
+        let mut options = import_options(&spec, &out);
+        options
+            .mapping
+            .ids
+            .insert("B".to_owned(), "gts.acme.imported._.a.v1~".to_owned());
+        let err = run_import(&options).unwrap_err();
+        assert!(err.to_string().contains("both map to"));
+
+        let mut options = import_options(&spec, &out);
+        options.vendor = "Acme".to_owned();
+        assert!(run_import(&options).is_err());
+        assert!(!out.exists());
+
+        let result = run_import(&import_options(&spec, &out)).unwrap();
+        assert!(result.ok);
+        assert_eq!(read(&result.imported[0].path)["$schema"], DRAFT_2020_12);
+    }
+}
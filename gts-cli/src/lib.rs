@@ -2,9 +2,29 @@
 pub mod logging;
 
 // Other modules needed by CLI
+pub mod api_error;
+pub mod audit;
+pub mod completion;
+pub mod coverage;
+pub mod doctor;
+pub mod export;
 pub mod gen_schemas;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod import;
+pub mod lint;
+pub mod new_schema;
+pub mod progress;
+pub mod project_config;
+pub mod replay;
+pub mod report;
 pub mod server;
+pub mod server_config;
 
 // Re-export CLI types and functions for testing
 pub mod cli;
-pub use cli::{Cli, Commands, run_with_cli};
+pub use cli::{
+    AuditArgs, CheckCollisionsArgs, CleanArgs, Cli, Commands, CompatCheckArgs, CoverageArgs,
+    DiscoveryModeArg, DoctorFormat, ExportTarget, ImportArgs, LintArgs, LintFormat, ReplayArgs,
+    ValidateArgs, ValidateFormat, run_with_cli,
+};
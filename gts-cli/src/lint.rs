@@ -0,0 +1,517 @@
+//! `gts lint`: the findings of `gts validate` plus style rules, as rustc-style
+//! diagnostics with a suggested identifier.
+//!
+//! Engine findings keep their rule code and severity. Style rules report
+//! warnings on IDs that are valid but not in the preferred form:
+//!
+//! - `deprecated_vendor`: the vendor of a segment was renamed
+//!   (`--deprecated-vendor OLD=NEW`)
+//! - `missing_minor_version`: a segment version has no minor number
+//!   (`--require-minor`)
+//!
+//! The engine also reports, as `expected_type_id`, instance IDs in `$ref` and
+//! `x-gts-ref` values, which must name a type and end with `~`. Malformed IDs
+//! are suggested the fix of [`gts_validator::suggest_fix`].
+
+use anyhow::Result;
+use gts::GtsID;
+use gts_validator::{
+    DiscoveryMode, FsSourceConfig, IdOccurrence, RuleId, ScanError, Severity, ValidationConfig,
+    ValidationError, VendorPolicy,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// Code of the `deprecated_vendor` style rule
+pub const DEPRECATED_VENDOR: &str = "deprecated_vendor";
+/// Code of the `missing_minor_version` style rule
+pub const MISSING_MINOR_VERSION: &str = "missing_minor_version";
+
+/// JSON paths whose values must be type IDs
+const TYPE_REF_PATHS: &[&str] = &["$..$ref", "$..x-gts-ref"];
+
+#[derive(Debug, Clone, Default)]
+pub struct LintOptions {
+    pub paths: Vec<PathBuf>,
+    pub exclude: Vec<String>,
+    pub vendor_policy: VendorPolicy,
+    pub discovery_mode: DiscoveryMode,
+    /// Deprecated vendor -> its replacement
+    pub deprecated_vendors: BTreeMap<String, String>,
+    /// Every segment version must have a minor number (`v1.0`, not `v1`)
+    pub require_minor: bool,
+}
+
+impl LintOptions {
+    /// Whether a style rule needs every scanned ID
+    fn has_style_rules(&self) -> bool {
+        self.require_minor || !self.deprecated_vendors.is_empty()
+    }
+}
+
+/// One finding of `gts lint`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintDiagnostic {
+    /// Rule code, e.g. `invalid_id` or `deprecated_vendor`
+    pub code: String,
+    pub severity: Severity,
+    pub message: String,
+    pub file: PathBuf,
+    /// Line number (1-indexed); 0 if unknown
+    pub line: usize,
+    /// Column number (1-indexed, in characters); 0 if unknown
+    pub column: usize,
+    /// The string as written
+    pub raw_value: String,
+    /// The string to write instead, if a fix is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LintReport {
+    pub files_scanned: usize,
+    pub diagnostics: Vec<LintDiagnostic>,
+    pub scan_errors: Vec<ScanError>,
+}
+
+impl LintReport {
+    #[must_use]
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+
+    /// Whether the run passes: no errors or scan errors, and no warnings
+    /// with `deny_warnings`
+    #[must_use]
+    pub fn passes(&self, deny_warnings: bool) -> bool {
+        self.scan_errors.is_empty()
+            && self.count(Severity::Error) == 0
+            && (!deny_warnings || self.count(Severity::Warning) == 0)
+    }
+
+    /// rustc-style diagnostics, each under its source line when the file can
+    /// be read, followed by a summary line
+    #[must_use]
+    pub fn to_human(&self) -> String {
+        let mut sources: HashMap<PathBuf, Option<String>> = HashMap::new();
+        let mut out = String::new();
+        for diagnostic in &self.diagnostics {
+            let source = sources
+                .entry(diagnostic.file.clone())
+                .or_insert_with(|| std::fs::read_to_string(&diagnostic.file).ok());
+            let source_line = source
+                .as_deref()
+                .and_then(|s| s.lines().nth(diagnostic.line.checked_sub(1)?));
+            render_diagnostic(&mut out, diagnostic, source_line);
+        }
+        for scan_error in &self.scan_errors {
+            let _ = writeln!(out, "error: {}", scan_error.message);
+            let _ = writeln!(out, "  --> {}\n", scan_error.file.display());
+        }
+        let _ = writeln!(
+            out,
+            "{} error(s), {} warning(s), {} scan error(s) in {} file(s)",
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+            self.scan_errors.len(),
+            self.files_scanned
+        );
+        out
+    }
+}
+
+/// Append one diagnostic to `out`, with carets under its raw value in
+/// `source_line`
+fn render_diagnostic(out: &mut String, d: &LintDiagnostic, source_line: Option<&str>) {
+    let label = match d.severity {
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+        _ => "error",
+    };
+    let _ = writeln!(out, "{label}[{}]: {}", d.code, d.message);
+    let gutter = " ".repeat(d.line.to_string().len());
+    if d.line == 0 {
+        let _ = writeln!(out, "{gutter}--> {}", d.file.display());
+    } else {
+        let _ = writeln!(
+            out,
+            "{gutter}--> {}:{}:{}",
+            d.file.display(),
+            d.line,
+            d.column
+        );
+    }
+    if let Some(line) = source_line.filter(|_| d.column > 0) {
+        // Keep tabs so the carets line up with the source line
+        let indent: String = line
+            .chars()
+            .take(d.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let carets = "^".repeat(d.raw_value.chars().count().max(1));
+        let _ = writeln!(out, "{gutter} |");
+        let _ = writeln!(out, "{} | {line}", d.line);
+        let _ = writeln!(out, "{gutter} | {indent}{carets}");
+    }
+    if let Some(suggestion) = &d.suggestion {
+        let _ = writeln!(out, "{gutter} = help: use `{suggestion}`");
+    }
+    out.push('\n');
+}
+
+/// Run the validator and the style rules over `options.paths`
+///
+/// # Errors
+/// Returns an error if the files cannot be listed.
+pub fn run_lint(options: &LintOptions) -> Result<LintReport> {
+    let mut fs_config = FsSourceConfig::default();
+    fs_config.paths.clone_from(&options.paths);
+    fs_config.exclude.clone_from(&options.exclude);
+
+    let mut config = ValidationConfig::default();
+    config.vendor_policy = options.vendor_policy.clone();
+    config.discovery_mode = options.discovery_mode.clone();
+    config.type_ref_paths = TYPE_REF_PATHS.iter().map(|&p| p.to_owned()).collect();
+    let report = gts_validator::validate_fs(&fs_config, &config)?;
+
+    let mut diagnostics: Vec<LintDiagnostic> = report
+        .validation_errors
+        .iter()
+        .map(|error| engine_diagnostic(error, &options.vendor_policy))
+        .collect();
+
+    if options.has_style_rules() {
+        // Every ID is needed, including the ones the user's policy rejects
+        config.vendor_policy = VendorPolicy::Any;
+        config.collect_ids = true;
+        let sightings = gts_validator::validate_fs(&fs_config, &config)?;
+        for occurrence in &sightings.ids {
+            diagnostics.extend(style_diagnostics(options, occurrence));
+        }
+    }
+
+    diagnostics.sort_by(|a, b| {
+        (&a.file, a.line, a.column, &a.code).cmp(&(&b.file, b.line, b.column, &b.code))
+    });
+    Ok(LintReport {
+        files_scanned: report.scanned_files,
+        diagnostics,
+        scan_errors: report.scan_errors,
+    })
+}
+
+/// A diagnostic for a finding of the validator, with a fix if one is known
+fn engine_diagnostic(error: &ValidationError, policy: &VendorPolicy) -> LintDiagnostic {
+    let respelled = |fixed: String| {
+        GtsID::new(&fixed)
+            .is_ok()
+            .then(|| respell(&error.raw_value, &error.normalized_id, &fixed))
+    };
+    let suggestion = match error.rule() {
+        // The policy applies to the vendor of the first segment only
+        RuleId::VendorMismatch => match policy.for_path(&error.file) {
+            VendorPolicy::MustMatch(vendor) => {
+                respelled(replace_first_vendor(&error.normalized_id, vendor))
+            }
+            VendorPolicy::AllowList(vendors) if vendors.len() == 1 => {
+                respelled(replace_first_vendor(&error.normalized_id, &vendors[0]))
+            }
+            _ => None,
+        },
+        RuleId::ExpectedTypeId => respelled(format!("{}~", error.normalized_id)),
+        RuleId::InvalidId => gts_validator::suggest_fix(error),
+        _ => None,
+    };
+    LintDiagnostic {
+        code: error.rule().code().to_owned(),
+        severity: error.severity,
+        message: error.error.clone(),
+        file: error.file.clone(),
+        line: error.line,
+        column: error.column,
+        raw_value: error.raw_value.clone(),
+        suggestion,
+    }
+}
+
+/// Warnings of the style rules for one scanned ID
+fn style_diagnostics(options: &LintOptions, occurrence: &IdOccurrence) -> Vec<LintDiagnostic> {
+    let id = occurrence.id.as_str();
+    let Ok(parsed) = GtsID::new(id) else {
+        return vec![];
+    };
+    let warning = |code: &str, message: String, fixed: &str| LintDiagnostic {
+        code: code.to_owned(),
+        severity: Severity::Warning,
+        message,
+        file: occurrence.file.clone(),
+        line: occurrence.line,
+        column: occurrence.column,
+        raw_value: occurrence.raw_value.clone(),
+        suggestion: Some(respell(&occurrence.raw_value, id, fixed)),
+    };
+    let segments: Vec<_> = parsed
+        .gts_id_segments
+        .iter()
+        .filter(|s| !s.is_uuid_tail && !s.is_wildcard)
+        .collect();
+    let mut out = vec![];
+
+    let mut renamed: Vec<(&String, &String)> = segments
+        .iter()
+        .filter_map(|s| options.deprecated_vendors.get_key_value(&s.vendor))
+        .collect();
+    renamed.sort();
+    renamed.dedup();
+    for (old, new) in renamed {
+        let fixed = replace_vendors(id, |vendor| (vendor == old).then_some(new.as_str()));
+        out.push(warning(
+            DEPRECATED_VENDOR,
+            format!("Vendor '{old}' is deprecated, use '{new}'"),
+            &fixed,
+        ));
+    }
+
+    if options.require_minor {
+        let mut versions: Vec<String> = segments
+            .iter()
+            .filter(|s| s.ver_minor.is_none())
+            .map(|s| format!("'v{}'", s.ver_major))
+            .collect();
+        versions.dedup();
+        if !versions.is_empty() {
+            out.push(warning(
+                MISSING_MINOR_VERSION,
+                format!("No minor version in {}", versions.join(", ")),
+                &add_minor_versions(id),
+            ));
+        }
+    }
+    out
+}
+
+/// `raw` with `normalized` replaced by `fixed`, keeping e.g. a `gts://` prefix
+fn respell(raw: &str, normalized: &str, fixed: &str) -> String {
+    if !normalized.is_empty() && raw.contains(normalized) {
+        raw.replacen(normalized, fixed, 1)
+    } else {
+        fixed.to_owned()
+    }
+}
+
+/// Apply `edit` to the `.`-separated tokens of every segment of `id` that
+/// has a vendor (not a UUID tail or wildcard)
+fn edit_segments(id: &str, mut edit: impl FnMut(&mut Vec<String>)) -> String {
+    let Some(rest) = id.strip_prefix("gts.") else {
+        return id.to_owned();
+    };
+    let pieces: Vec<String> = rest
+        .split('~')
+        .map(|piece| {
+            let mut tokens: Vec<String> = piece.split('.').map(str::to_owned).collect();
+            if tokens.len() >= 5 && !piece.contains('*') {
+                edit(&mut tokens);
+            }
+            tokens.join(".")
+        })
+        .collect();
+    format!("gts.{}", pieces.join("~"))
+}
+
+/// `id` with the vendor of each segment replaced where `rename` returns one
+fn replace_vendors<'a>(id: &str, rename: impl Fn(&str) -> Option<&'a str>) -> String {
+    edit_segments(id, |tokens| {
+        if let Some(new) = rename(&tokens[0]) {
+            new.clone_into(&mut tokens[0]);
+        }
+    })
+}
+
+/// `id` with the vendor of its first segment replaced by `vendor`
+fn replace_first_vendor(id: &str, vendor: &str) -> String {
+    let mut first = true;
+    edit_segments(id, |tokens| {
+        if std::mem::take(&mut first) {
+            vendor.clone_into(&mut tokens[0]);
+        }
+    })
+}
+
+/// `id` with `.0` added to every segment version without a minor number
+fn add_minor_versions(id: &str) -> String {
+    edit_segments(id, |tokens| {
+        if tokens.len() == 5 {
+            tokens.push("0".to_owned());
+        }
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn lint(dir: &TempDir, options: LintOptions) -> LintReport {
+        run_lint(&LintOptions {
+            paths: vec![dir.path().to_path_buf()],
+            ..options
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_edit_segments() {
+        let id = "gts.acme.core.pkg.base.v1~x.app.pkg.item.v2.1";
+        assert_eq!(
+            replace_vendors(id, |v| (v == "acme").then_some("acmecorp")),
+            "gts.acmecorp.core.pkg.base.v1~x.app.pkg.item.v2.1"
+        );
+        assert_eq!(
+            replace_first_vendor(id, "y"),
+            "gts.y.core.pkg.base.v1~x.app.pkg.item.v2.1"
+        );
+        assert_eq!(
+            add_minor_versions(id),
+            "gts.acme.core.pkg.base.v1.0~x.app.pkg.item.v2.1"
+        );
+        assert_eq!(
+            add_minor_versions("gts.x.core.pkg.base.v1~"),
+            "gts.x.core.pkg.base.v1.0~"
+        );
+    }
+
+    #[test]
+    fn test_lint_engine_findings_with_suggestions() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("doc.md"),
+            "# Types\n\nSee `gts.x.core.pkg.my-type.v1~` here.\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("schema.json"),
+            r#"{"$ref": "gts://gts.x.core.pkg.base.v1~x.core.pkg.item.v1"}"#,
+        )
+        .unwrap();
+
+        let report = lint(
+            &dir,
+            LintOptions {
+                discovery_mode: DiscoveryMode::Heuristic,
+                ..LintOptions::default()
+            },
+        );
+        assert_eq!(report.files_scanned, 2);
+        assert_eq!(report.count(Severity::Error), 2, "{report:#?}");
+        assert!(!report.passes(false));
+
+        let invalid = report
+            .diagnostics
+            .iter()
+            .find(|d| d.code == "invalid_id")
+            .unwrap();
+        assert_eq!(
+            invalid.suggestion.as_deref(),
+            Some("gts.x.core.pkg.my_type.v1~")
+        );
+        let type_ref = report
+            .diagnostics
+            .iter()
+            .find(|d| d.code == "expected_type_id")
+            .unwrap();
+        assert_eq!(
+            type_ref.suggestion.as_deref(),
+            Some("gts://gts.x.core.pkg.base.v1~x.core.pkg.item.v1~")
+        );
+
+        let human = report.to_human();
+        assert!(human.contains("error[invalid_id]: "), "{human}");
+        assert!(human.contains("doc.md:3:6\n"), "{human}");
+        assert!(
+            human.contains("3 | See `gts.x.core.pkg.my-type.v1~` here.\n  |      ^^^^^"),
+            "{human}"
+        );
+        assert!(
+            human.contains("  = help: use `gts.x.core.pkg.my_type.v1~`"),
+            "{human}"
+        );
+        assert!(human.ends_with("2 error(s), 0 warning(s), 0 scan error(s) in 2 file(s)\n"));
+    }
+
+    #[test]
+    fn test_lint_style_rules() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("doc.md"),
+            "Uses `gts.oldco.core.pkg.order.v1~` and `gts.newco.core.pkg.item.v1.2~`.\n",
+        )
+        .unwrap();
+
+        let report = lint(
+            &dir,
+            LintOptions {
+                vendor_policy: VendorPolicy::MustMatch("newco".to_owned()),
+                deprecated_vendors: BTreeMap::from([("oldco".to_owned(), "newco".to_owned())]),
+                require_minor: true,
+                ..LintOptions::default()
+            },
+        );
+        let codes: Vec<(&str, Severity)> = report
+            .diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.severity))
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                (DEPRECATED_VENDOR, Severity::Warning),
+                (MISSING_MINOR_VERSION, Severity::Warning),
+                ("vendor_mismatch", Severity::Error),
+            ]
+        );
+        for diagnostic in &report.diagnostics {
+            assert_eq!(diagnostic.raw_value, "gts.oldco.core.pkg.order.v1~");
+        }
+        assert_eq!(
+            report.diagnostics[0].suggestion.as_deref(),
+            Some("gts.newco.core.pkg.order.v1~")
+        );
+        assert_eq!(
+            report.diagnostics[1].suggestion.as_deref(),
+            Some("gts.oldco.core.pkg.order.v1.0~")
+        );
+        assert_eq!(
+            report.diagnostics[2].suggestion.as_deref(),
+            Some("gts.newco.core.pkg.order.v1~")
+        );
+    }
+
+    #[test]
+    fn test_lint_warnings_pass_unless_denied() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("doc.md"),
+            "Uses `gts.x.core.pkg.order.v1~`.\n",
+        )
+        .unwrap();
+
+        let report = lint(
+            &dir,
+            LintOptions {
+                require_minor: true,
+                ..LintOptions::default()
+            },
+        );
+        assert_eq!(report.count(Severity::Warning), 1);
+        assert!(report.passes(false));
+        assert!(!report.passes(true));
+    }
+}
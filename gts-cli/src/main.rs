@@ -10,15 +10,35 @@
     clippy::expect_used
 )]
 
+mod api_error;
+mod audit;
 mod cli;
+mod completion;
+mod coverage;
+mod doctor;
+mod export;
 mod gen_schemas;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod import;
+mod lint;
 mod logging;
+mod new_schema;
+mod progress;
+mod project_config;
+mod replay;
+mod report;
 mod server;
+mod server_config;
 
 #[tokio::main]
 async fn main() {
     if let Err(e) = cli::run().await {
         eprintln!("Error: {e}");
-        std::process::exit(1);
+        // `gts audit` encodes the failed checks in its exit code
+        let code = e
+            .downcast_ref::<audit::AuditFailed>()
+            .map_or(1, |failed| failed.exit_code);
+        std::process::exit(code);
     }
 }
@@ -0,0 +1,691 @@
+//! Interactive `gts new-schema` wizard.
+//!
+//! Prompts for the ID segment tokens, an optional parent schema picked from the
+//! loaded store, properties and traits, then writes `<id>.schema.json` and, on
+//! request, a `#[struct_to_gts_schema]` Rust struct skeleton.
+
+use anyhow::{Result, bail};
+use gts::{GtsFileNameEncoding, GtsID, GtsOps};
+use gts_id::{is_valid_segment_token, parse_u32_exact};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+/// JSON Schema types offered for properties and trait definitions
+const PROPERTY_TYPES: &[&str] = &["string", "integer", "number", "boolean", "object", "array"];
+
+/// Rust keywords that need a raw identifier when used as field names
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "yield",
+];
+
+/// A property collected by the wizard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewSchemaProperty {
+    pub name: String,
+    pub json_type: String,
+    pub description: String,
+    pub required: bool,
+}
+
+/// Everything the wizard collects for a new schema
+#[derive(Debug, Clone, Default)]
+pub struct NewSchemaSpec {
+    pub vendor: String,
+    pub package: String,
+    pub namespace: String,
+    pub type_name: String,
+    pub version: String,
+    /// Parent schema ID (ending with `~`), or `None` for a base schema
+    pub parent: Option<String>,
+    pub description: String,
+    pub properties: Vec<NewSchemaProperty>,
+    /// Base schemas: trait name -> JSON type (`x-gts-traits-schema`).
+    /// Derived schemas: trait name -> value (`x-gts-traits`).
+    pub traits: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewSchemaResult {
+    pub ok: bool,
+    pub schema_id: String,
+    pub schema_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rust_path: Option<String>,
+}
+
+impl NewSchemaSpec {
+    /// The segment contributed by this schema, e.g. `x.core.events.event.v1~`
+    #[must_use]
+    pub fn segment(&self) -> String {
+        format!(
+            "{}.{}.{}.{}.{}~",
+            self.vendor, self.package, self.namespace, self.type_name, self.version
+        )
+    }
+
+    /// Full schema ID including the parent chain
+    #[must_use]
+    pub fn schema_id(&self) -> String {
+        match &self.parent {
+            Some(parent) => format!("{parent}{}", self.segment()),
+            None => format!("gts.{}", self.segment()),
+        }
+    }
+
+    /// Build the JSON Schema document; derived schemas extend the parent via `allOf`
+    #[must_use]
+    pub fn to_schema(&self) -> Value {
+        let mut properties = Map::new();
+        for prop in &self.properties {
+            let mut def = json!({ "type": prop.json_type });
+            if !prop.description.is_empty() {
+                def["description"] = json!(prop.description);
+            }
+            properties.insert(prop.name.clone(), def);
+        }
+        let required: Vec<&str> = self
+            .properties
+            .iter()
+            .filter(|p| p.required)
+            .map(|p| p.name.as_str())
+            .collect();
+
+        let mut body = json!({ "type": "object", "properties": properties });
+        if !required.is_empty() {
+            body["required"] = json!(required);
+        }
+
+        let mut schema = json!({
+            "$id": format!("gts://{}", self.schema_id()),
+            "$schema": "http://json-schema.org/draft-07/schema#",
+        });
+        if !self.description.is_empty() {
+            schema["description"] = json!(self.description);
+        }
+
+        match &self.parent {
+            None => {
+                if let (Some(schema_obj), Some(body_obj)) =
+                    (schema.as_object_mut(), body.as_object())
+                {
+                    schema_obj.extend(body_obj.clone());
+                }
+                if !self.traits.is_empty() {
+                    let trait_props: Map<String, Value> = self
+                        .traits
+                        .iter()
+                        .map(|(name, ty)| (name.clone(), json!({ "type": ty })))
+                        .collect();
+                    schema["x-gts-traits-schema"] =
+                        json!({ "type": "object", "properties": trait_props });
+                }
+            }
+            Some(parent) => {
+                if !self.traits.is_empty() {
+                    body["x-gts-traits"] = Value::Object(self.traits.clone());
+                }
+                schema["type"] = json!("object");
+                schema["allOf"] = json!([{ "$ref": format!("gts://{parent}") }, body]);
+            }
+        }
+        schema
+    }
+
+    /// Rust struct skeleton annotated with `#[struct_to_gts_schema]`
+    #[must_use]
+    pub fn to_rust_skeleton(&self, dir_path: &str) -> String {
+        let base = match &self.parent {
+            None => "true".to_owned(),
+            Some(parent) => parent_struct_name(parent),
+        };
+        let properties: Vec<&str> = self.properties.iter().map(|p| p.name.as_str()).collect();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "use gts_macros::struct_to_gts_schema;\n");
+        if self.parent.is_some() {
+            let _ = writeln!(
+                out,
+                "// `{base}` is derived from the parent schema ID; adjust it to the parent's Rust type."
+            );
+        }
+        let _ = writeln!(out, "#[derive(Debug)]");
+        let _ = writeln!(out, "#[struct_to_gts_schema(");
+        let _ = writeln!(out, "    dir_path = {},", quoted(dir_path));
+        let _ = writeln!(out, "    base = {base},");
+        let _ = writeln!(out, "    schema_id = {},", quoted(&self.schema_id()));
+        let _ = writeln!(out, "    description = {},", quoted(&self.description));
+        let _ = writeln!(out, "    properties = {}", quoted(&properties.join(",")));
+        let _ = writeln!(out, ")]");
+        let _ = writeln!(
+            out,
+            "pub struct {} {{",
+            struct_name(&self.type_name, &self.version)
+        );
+        for prop in &self.properties {
+            if !prop.description.is_empty() {
+                let _ = writeln!(out, "    /// {}", prop.description);
+            }
+            let ty = rust_type(&prop.json_type);
+            let ty = if prop.required {
+                ty.to_owned()
+            } else {
+                format!("Option<{ty}>")
+            };
+            let _ = writeln!(out, "    pub {}: {ty},", rust_field_name(&prop.name));
+        }
+        let _ = writeln!(out, "}}");
+        out
+    }
+}
+
+/// Run the wizard, reading answers from `reader` and writing prompts to `writer`
+///
+/// Parent candidates are the schemas currently loaded in `ops`.
+///
+/// # Errors
+///
+/// Returns an error if the input ends before the wizard is complete or if
+/// reading/writing fails
+pub fn prompt_schema_spec<R: BufRead, W: Write>(
+    ops: &GtsOps,
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<NewSchemaSpec> {
+    let mut prompter = Prompter { reader, writer };
+    let mut spec = NewSchemaSpec::default();
+
+    loop {
+        spec.vendor = prompter.ask_token("Vendor", None)?;
+        spec.package = prompter.ask_token("Package", None)?;
+        spec.namespace = prompter.ask_token("Namespace", Some("_"))?;
+        spec.type_name = prompter.ask_token("Type", None)?;
+        spec.version = prompter.ask_version()?;
+        spec.parent = prompter.ask_parent(&parent_candidates(ops))?;
+        match GtsID::new(&spec.schema_id()) {
+            Ok(_) => break,
+            Err(e) => writeln!(prompter.writer, "Invalid schema ID: {e}")?,
+        }
+    }
+    writeln!(prompter.writer, "Schema ID: {}", spec.schema_id())?;
+
+    spec.description = prompter.ask("Description", Some(""))?;
+    spec.properties = prompter.ask_properties()?;
+    spec.traits = if spec.parent.is_some() {
+        prompter.ask_trait_values()?
+    } else {
+        prompter.ask_trait_definitions()?
+    };
+    Ok(spec)
+}
+
+/// Run the wizard on stdin/stderr and write the resulting files
+///
+/// # Errors
+///
+/// Returns an error if the wizard is aborted or the files cannot be written
+pub fn run_new_schema(
+    ops: &GtsOps,
+    out_dir: &str,
+    rust_out: Option<&str>,
+    encoding: GtsFileNameEncoding,
+) -> Result<NewSchemaResult> {
+    let stdin = std::io::stdin();
+    let mut reader = stdin.lock();
+    let mut writer = std::io::stderr();
+    let spec = prompt_schema_spec(ops, &mut reader, &mut writer)?;
+    ops.store.retired_ids().check(&spec.schema_id())?;
+    write_new_schema(&spec, out_dir, rust_out, encoding)
+}
+
+/// Write `<schema_id>.schema.json`, with the ID encoded per `encoding`, into
+/// `out_dir` and optionally the Rust skeleton
+///
+/// # Errors
+///
+/// Returns an error if the schema file already exists or a file cannot be written
+pub fn write_new_schema(
+    spec: &NewSchemaSpec,
+    out_dir: &str,
+    rust_out: Option<&str>,
+    encoding: GtsFileNameEncoding,
+) -> Result<NewSchemaResult> {
+    let schema_id = spec.schema_id();
+    let schema_path = Path::new(out_dir).join(encoding.schema_file_name(&schema_id));
+    if schema_path.exists() {
+        bail!(
+            "Refusing to overwrite existing file: {}",
+            schema_path.display()
+        );
+    }
+    fs::create_dir_all(out_dir)?;
+    fs::write(
+        &schema_path,
+        format!("{}\n", serde_json::to_string_pretty(&spec.to_schema())?),
+    )?;
+
+    if let Some(rust_path) = rust_out {
+        if let Some(parent) = Path::new(rust_path).parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(rust_path, spec.to_rust_skeleton(out_dir))?;
+    }
+
+    Ok(NewSchemaResult {
+        ok: true,
+        schema_id,
+        schema_path: schema_path.to_string_lossy().into_owned(),
+        rust_path: rust_out.map(str::to_owned),
+    })
+}
+
+struct Prompter<'a, R, W> {
+    reader: &'a mut R,
+    writer: &'a mut W,
+}
+
+impl<R: BufRead, W: Write> Prompter<'_, R, W> {
+    fn ask(&mut self, label: &str, default: Option<&str>) -> Result<String> {
+        loop {
+            match default {
+                Some(d) if !d.is_empty() => write!(self.writer, "{label} [{d}]: ")?,
+                _ => write!(self.writer, "{label}: ")?,
+            }
+            self.writer.flush()?;
+
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                bail!("Unexpected end of input while reading '{label}'");
+            }
+            let answer = line.trim();
+            if !answer.is_empty() {
+                return Ok(answer.to_owned());
+            }
+            if let Some(d) = default {
+                return Ok(d.to_owned());
+            }
+            writeln!(self.writer, "A value is required")?;
+        }
+    }
+
+    fn ask_yes_no(&mut self, label: &str, default: bool) -> Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        loop {
+            let answer = self.ask(&format!("{label} ({hint})"), Some(""))?;
+            match answer.to_ascii_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => writeln!(self.writer, "Please answer 'y' or 'n'")?,
+            }
+        }
+    }
+
+    fn ask_token(&mut self, label: &str, default: Option<&str>) -> Result<String> {
+        loop {
+            let token = self.ask(label, default)?;
+            if is_valid_segment_token(&token) {
+                return Ok(token);
+            }
+            writeln!(
+                self.writer,
+                "Invalid token '{token}': must start with [a-z_] followed by [a-z0-9_]"
+            )?;
+        }
+    }
+
+    fn ask_version(&mut self) -> Result<String> {
+        loop {
+            let answer = self.ask("Version", Some("v1"))?;
+            let version = if answer.starts_with('v') {
+                answer
+            } else {
+                format!("v{answer}")
+            };
+            if is_valid_version(&version) {
+                return Ok(version);
+            }
+            writeln!(
+                self.writer,
+                "Invalid version '{version}': expected v<major> or v<major>.<minor>"
+            )?;
+        }
+    }
+
+    fn ask_parent(&mut self, candidates: &[String]) -> Result<Option<String>> {
+        if candidates.is_empty() {
+            writeln!(self.writer, "No schemas loaded; creating a base schema")?;
+            return Ok(None);
+        }
+        writeln!(self.writer, "Parent schemas:")?;
+        for (i, id) in candidates.iter().enumerate() {
+            writeln!(self.writer, "  {}) {id}", i + 1)?;
+        }
+        loop {
+            let answer = self.ask("Parent (number or ID, empty for a base schema)", Some(""))?;
+            if answer.is_empty() {
+                return Ok(None);
+            }
+            if let Ok(n) = answer.parse::<usize>()
+                && let Some(id) = n.checked_sub(1).and_then(|i| candidates.get(i))
+            {
+                return Ok(Some(id.clone()));
+            }
+            let id = answer.strip_prefix("gts://").unwrap_or(&answer);
+            if candidates.iter().any(|c| c == id) {
+                return Ok(Some(id.to_owned()));
+            }
+            writeln!(self.writer, "Unknown parent '{answer}'")?;
+        }
+    }
+
+    fn ask_properties(&mut self) -> Result<Vec<NewSchemaProperty>> {
+        let mut properties: Vec<NewSchemaProperty> = Vec::new();
+        loop {
+            let name = self.ask("Property name (empty to finish)", Some(""))?;
+            if name.is_empty() {
+                return Ok(properties);
+            }
+            if !is_valid_property_name(&name) {
+                writeln!(
+                    self.writer,
+                    "Invalid property name '{name}': use letters, digits and '_'"
+                )?;
+                continue;
+            }
+            if properties.iter().any(|p| p.name == name) {
+                writeln!(self.writer, "Property '{name}' already defined")?;
+                continue;
+            }
+            let json_type = self.ask_type(&format!("  Type of '{name}'"))?;
+            let description = self.ask("  Description", Some(""))?;
+            let required = self.ask_yes_no("  Required", true)?;
+            properties.push(NewSchemaProperty {
+                name,
+                json_type,
+                description,
+                required,
+            });
+        }
+    }
+
+    fn ask_type(&mut self, label: &str) -> Result<String> {
+        let label = format!("{label} ({})", PROPERTY_TYPES.join("/"));
+        loop {
+            let answer = self.ask(&label, Some("string"))?;
+            if PROPERTY_TYPES.contains(&answer.as_str()) {
+                return Ok(answer);
+            }
+            writeln!(self.writer, "Unknown type '{answer}'")?;
+        }
+    }
+
+    /// Base schemas declare which traits derived schemas may set
+    fn ask_trait_definitions(&mut self) -> Result<Map<String, Value>> {
+        let mut traits = Map::new();
+        loop {
+            let name = self.ask("Trait name (empty to finish)", Some(""))?;
+            if name.is_empty() {
+                return Ok(traits);
+            }
+            let json_type = self.ask_type(&format!("  Type of trait '{name}'"))?;
+            traits.insert(name, Value::String(json_type));
+        }
+    }
+
+    /// Derived schemas set trait values; values that parse as JSON keep their type
+    fn ask_trait_values(&mut self) -> Result<Map<String, Value>> {
+        let mut traits = Map::new();
+        loop {
+            let answer = self.ask("Trait (name=value, empty to finish)", Some(""))?;
+            if answer.is_empty() {
+                return Ok(traits);
+            }
+            let Some((name, value)) = answer.split_once('=') else {
+                writeln!(self.writer, "Expected name=value")?;
+                continue;
+            };
+            let value = value.trim();
+            let value = serde_json::from_str(value).unwrap_or_else(|_| json!(value));
+            traits.insert(name.trim().to_owned(), value);
+        }
+    }
+}
+
+fn parent_candidates(ops: &GtsOps) -> Vec<String> {
+    let mut ids: Vec<String> = ops
+        .get_entities(usize::MAX)
+        .entities
+        .into_iter()
+        .filter(|e| e.is_schema)
+        .map(|e| e.id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+fn is_valid_version(version: &str) -> bool {
+    let Some(rest) = version.strip_prefix('v') else {
+        return false;
+    };
+    match rest.split_once('.') {
+        Some((major, minor)) => {
+            parse_u32_exact(major).is_some() && parse_u32_exact(minor).is_some()
+        }
+        None => parse_u32_exact(rest).is_some(),
+    }
+}
+
+fn is_valid_property_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// `event` + `v1.2` -> `EventV1`
+fn struct_name(type_name: &str, version: &str) -> String {
+    let major = version
+        .trim_start_matches('v')
+        .split('.')
+        .next()
+        .unwrap_or_default();
+    let mut name: String = type_name
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect();
+    let _ = write!(name, "V{major}");
+    name
+}
+
+/// Struct name guessed from the last segment of the parent schema ID
+fn parent_struct_name(parent: &str) -> String {
+    let last = parent
+        .trim_end_matches('~')
+        .rsplit('~')
+        .next()
+        .unwrap_or_default();
+    let tokens: Vec<&str> = last.trim_start_matches("gts.").split('.').collect();
+    match tokens.as_slice() {
+        [_, _, _, type_name, version @ ..] => struct_name(type_name, &version.join(".")),
+        _ => "Parent".to_owned(),
+    }
+}
+
+fn rust_type(json_type: &str) -> &'static str {
+    match json_type {
+        "integer" => "i64",
+        "number" => "f64",
+        "boolean" => "bool",
+        "object" => "serde_json::Value",
+        "array" => "Vec<serde_json::Value>",
+        _ => "String",
+    }
+}
+
+/// Quote `value` as a string literal (JSON escaping is valid Rust here)
+fn quoted(value: &str) -> String {
+    Value::String(value.to_owned()).to_string()
+}
+
+fn rust_field_name(name: &str) -> String {
+    if RUST_KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_owned()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run_wizard(ops: &GtsOps, input: &str) -> Result<NewSchemaSpec> {
+        let mut reader = input.as_bytes();
+        let mut prompts = Vec::new();
+        prompt_schema_spec(ops, &mut reader, &mut prompts)
+    }
+
+    fn ops_with_base_schema() -> (TempDir, GtsOps) {
+        let dir = TempDir::new().unwrap();
+        let schema = json!({
+            "$id": "gts://gts.x.core.events.event.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object"
+        });
+        fs::write(
+            dir.path().join("gts.x.core.events.event.v1~.schema.json"),
+            schema.to_string(),
+        )
+        .unwrap();
+        let path = vec![dir.path().to_string_lossy().into_owned()];
+        (dir, GtsOps::new(Some(path), None, 0))
+    }
+
+    #[test]
+    fn test_wizard_base_schema() {
+        let ops = GtsOps::new(None, None, 0);
+        let input = "acme\nbilling\n\ninvoice\n\nAn invoice\n\
+                     total\nnumber\nInvoice total\n\n\
+                     note\n\n\nn\n\n\
+                     retention\nstring\n\n";
+        let spec = run_wizard(&ops, input).unwrap();
+
+        assert_eq!(spec.schema_id(), "gts.acme.billing._.invoice.v1~");
+        assert!(spec.parent.is_none());
+        assert_eq!(spec.properties.len(), 2);
+        assert_eq!(spec.properties[0].json_type, "number");
+        assert!(spec.properties[0].required);
+        assert_eq!(spec.properties[1].json_type, "string");
+        assert!(!spec.properties[1].required);
+
+        let schema = spec.to_schema();
+        assert_eq!(schema["$id"], "gts://gts.acme.billing._.invoice.v1~");
+        assert_eq!(schema["properties"]["total"]["type"], "number");
+        assert_eq!(schema["required"], json!(["total"]));
+        assert_eq!(
+            schema["x-gts-traits-schema"]["properties"]["retention"]["type"],
+            "string"
+        );
+    }
+
+    #[test]
+    fn test_wizard_derived_schema_picks_parent_from_store() {
+        let (_dir, ops) = ops_with_base_schema();
+        let input = "acme\napp\n_\norder_placed\nv2.1\n1\n\n\
+                     order_id\nstring\n\ny\n\n\
+                     retention=P30D\nmaxRetries=3\n\n";
+        let spec = run_wizard(&ops, input).unwrap();
+
+        assert_eq!(
+            spec.schema_id(),
+            "gts.x.core.events.event.v1~acme.app._.order_placed.v2.1~"
+        );
+        let schema = spec.to_schema();
+        assert_eq!(
+            schema["allOf"][0]["$ref"],
+            "gts://gts.x.core.events.event.v1~"
+        );
+        assert_eq!(schema["allOf"][1]["x-gts-traits"]["retention"], "P30D");
+        assert_eq!(schema["allOf"][1]["x-gts-traits"]["maxRetries"], 3);
+
+        let rust = spec.to_rust_skeleton("schemas");
+        assert!(rust.contains("base = EventV1,"));
+        assert!(rust.contains("pub struct OrderPlacedV2 {"));
+        assert!(rust.contains("pub order_id: String,"));
+    }
+
+    #[test]
+    fn test_wizard_reprompts_invalid_answers() {
+        let ops = GtsOps::new(None, None, 0);
+        let input = "Acme\nacme\npkg\nns\nthing\nversion1\n1\n\n\n\n";
+        let spec = run_wizard(&ops, input).unwrap();
+        assert_eq!(spec.schema_id(), "gts.acme.pkg.ns.thing.v1~");
+    }
+
+    #[test]
+    fn test_wizard_fails_on_eof() {
+        let ops = GtsOps::new(None, None, 0);
+        let err = run_wizard(&ops, "acme\n").unwrap_err();
+        assert!(err.to_string().contains("Unexpected end of input"));
+    }
+
+    #[test]
+    fn test_write_new_schema_writes_files() {
+        let dir = TempDir::new().unwrap();
+        let out_dir = dir.path().join("schemas").to_string_lossy().into_owned();
+        let rust_path = dir
+            .path()
+            .join("src/thing.rs")
+            .to_string_lossy()
+            .into_owned();
+        let spec = NewSchemaSpec {
+            vendor: "acme".to_owned(),
+            package: "pkg".to_owned(),
+            namespace: "_".to_owned(),
+            type_name: "thing".to_owned(),
+            version: "v1".to_owned(),
+            properties: vec![NewSchemaProperty {
+                name: "type".to_owned(),
+                json_type: "string".to_owned(),
+                description: String::new(),
+                required: true,
+            }],
+            ..NewSchemaSpec::default()
+        };
+
+        let result =
+            write_new_schema(&spec, &out_dir, Some(&rust_path), GtsFileNameEncoding::Raw).unwrap();
+        assert!(result.ok);
+        let written: Value =
+            serde_json::from_str(&fs::read_to_string(&result.schema_path).unwrap()).unwrap();
+        assert_eq!(written["$id"], "gts://gts.acme.pkg._.thing.v1~");
+        let rust = fs::read_to_string(&rust_path).unwrap();
+        assert!(rust.contains("base = true,"));
+        assert!(rust.contains("pub r#type: String,"));
+
+        assert!(write_new_schema(&spec, &out_dir, None, GtsFileNameEncoding::Raw).is_err());
+
+        let result = write_new_schema(&spec, &out_dir, None, GtsFileNameEncoding::Percent).unwrap();
+        assert!(
+            result
+                .schema_path
+                .ends_with("gts.acme.pkg._.thing.v1%7E.schema.json")
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Machine-readable progress of long-running commands.
+//!
+//! With `--progress json`, `validate` and `generate-from-rust` write one JSON
+//! object per line to stderr as they go, so IDE tasks and build UIs can show
+//! a progress bar and the file being processed:
+//!
+//! ```text
+//! {"command":"validate","phase":"discover","done":0,"total":120,"elapsed_ms":4}
+//! {"command":"validate","phase":"scan","file":"docs/a.md","done":0,"total":120,"elapsed_ms":4}
+//! {"command":"validate","phase":"scan","file":"docs/b.md","done":1,"total":120,"elapsed_ms":9,"eta_ms":595}
+//! {"command":"validate","phase":"done","done":120,"total":120,"elapsed_ms":610,"eta_ms":0}
+//! ```
+//!
+//! `done` counts the files finished before `file`; `eta_ms` extrapolates the
+//! time spent so far and is left out until a file has finished.
+
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+
+/// `--progress` of long-running commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    /// Newline-delimited JSON events on stderr
+    Json,
+}
+
+/// Stage of a command reported by a progress event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressPhase {
+    /// The files to process are known
+    Discover,
+    /// `file` is being processed
+    Scan,
+    Done,
+}
+
+impl From<gts_validator::ScanPhase> for ProgressPhase {
+    fn from(phase: gts_validator::ScanPhase) -> Self {
+        match phase {
+            gts_validator::ScanPhase::Discover => Self::Discover,
+            gts_validator::ScanPhase::Done => Self::Done,
+            _ => Self::Scan,
+        }
+    }
+}
+
+/// A progress event, one line of `--progress json` output
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressEvent<'a> {
+    pub command: &'a str,
+    pub phase: ProgressPhase,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub done: usize,
+    pub total: usize,
+    pub elapsed_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_ms: Option<u64>,
+}
+
+/// Writes the progress events of one command; silent without `--progress`
+pub struct ProgressReporter {
+    command: &'static str,
+    started: Instant,
+    out: Option<Box<dyn Write>>,
+}
+
+impl ProgressReporter {
+    /// Reporter of `command` for `--progress format`, writing to stderr
+    #[must_use]
+    pub fn new(command: &'static str, format: Option<ProgressFormat>) -> Self {
+        let out = format.map(|ProgressFormat::Json| Box::new(std::io::stderr()) as Box<dyn Write>);
+        Self::with_output(command, out)
+    }
+
+    /// Reporter of `command` writing JSON events to `out`, if any
+    #[must_use]
+    pub fn with_output(command: &'static str, out: Option<Box<dyn Write>>) -> Self {
+        Self {
+            command,
+            started: Instant::now(),
+            out,
+        }
+    }
+
+    /// Report `phase` with `done` of `total` files finished. Progress is best
+    /// effort: a failed write does not fail the command.
+    pub fn report(&mut self, phase: ProgressPhase, file: Option<&Path>, done: usize, total: usize) {
+        let Some(out) = &mut self.out else {
+            return;
+        };
+        let elapsed_ms = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        let event = ProgressEvent {
+            command: self.command,
+            phase,
+            file: file.map(|file| file.display().to_string()),
+            done,
+            total,
+            elapsed_ms,
+            eta_ms: eta_ms(elapsed_ms, done, total),
+        };
+        if let Ok(line) = serde_json::to_string(&event) {
+            let _ = writeln!(out, "{line}");
+            let _ = out.flush();
+        }
+    }
+}
+
+/// Time left at the pace so far, once a file has finished
+fn eta_ms(elapsed_ms: u64, done: usize, total: usize) -> Option<u64> {
+    let done = u64::try_from(done).ok()?;
+    let left = u64::try_from(total).ok()?.saturating_sub(done);
+    elapsed_ms.saturating_mul(left).checked_div(done)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A writer whose output the test can read back
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_reporter_writes_json_lines() {
+        let buffer = Buffer::default();
+        let mut progress =
+            ProgressReporter::with_output("validate", Some(Box::new(buffer.clone())));
+        progress.report(ProgressPhase::Discover, None, 0, 2);
+        progress.report(ProgressPhase::Scan, Some(Path::new("docs/a.md")), 0, 2);
+        progress.report(ProgressPhase::Done, None, 2, 2);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["phase"], "discover");
+        assert_eq!(events[1]["file"], "docs/a.md");
+        assert!(events[1].get("eta_ms").is_none());
+        assert_eq!(events[2]["eta_ms"], 0);
+        assert_eq!(events[2]["command"], "validate");
+    }
+
+    #[test]
+    fn test_eta_extrapolates_pace() {
+        assert_eq!(eta_ms(100, 0, 10), None);
+        assert_eq!(eta_ms(100, 1, 10), Some(900));
+        assert_eq!(eta_ms(100, 10, 10), Some(0));
+    }
+}
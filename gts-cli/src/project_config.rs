@@ -0,0 +1,198 @@
+//! Project configuration (`gts.toml`).
+//!
+//! Like Cargo, the CLI looks for `gts.toml` in the current directory and its
+//! ancestors, unless `--project-config` names a file. Values from the file
+//! are defaults: flags given on the command line always win. Relative paths
+//! are resolved against the directory containing `gts.toml`.
+//!
+//! ```toml
+//! path = "schemas"             # --path
+//! config = "gts.config.json"   # --config
+//! file-name-encoding = "percent"   # --file-name-encoding: raw, percent or hashed
+//! retired-ids = "retired.yaml"     # IDs that must never be reused
+//! redaction = "redaction.yaml"     # instance values masked in all output
+//!
+//! [generate-from-rust]
+//! exclude = ["tests/*"]        # --exclude, used when none is given
+//!
+//! [lint]
+//! require-owner = ["gts.x.core.*"]   # lint-store --require-owner
+//!
+//! [validator]
+//! paths = ["docs"]             # gts validate, see gts_validator::ConfigFile
+//! vendor = "x"
+//! ```
+
+use anyhow::{Context, Result};
+use gts::GtsFileNameEncoding;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name looked up by [`ProjectConfig::discover`].
+pub const PROJECT_CONFIG_FILE: &str = "gts.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ProjectConfig {
+    /// Path to json and schema files or directories
+    pub path: Option<String>,
+    /// Path to the GTS config JSON
+    pub config: Option<String>,
+    /// How GTS IDs are encoded in schema file names
+    pub file_name_encoding: Option<GtsFileNameEncoding>,
+    /// YAML/JSON registry of retired IDs, checked by store loading and generators
+    pub retired_ids: Option<String>,
+    /// YAML/JSON redaction rules for instance values in command and server output
+    pub redaction: Option<String>,
+    #[serde(default)]
+    pub generate_from_rust: GenerateFromRustConfig,
+    #[serde(default)]
+    pub lint: LintConfig,
+    /// Defaults of `gts validate` (and the `gts-validator` binary)
+    #[serde(default)]
+    pub validator: gts_validator::ConfigFile,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenerateFromRustConfig {
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct LintConfig {
+    /// Schema ID patterns whose schemas must declare `x-gts-owner`
+    #[serde(default)]
+    pub require_owner: Vec<String>,
+}
+
+impl ProjectConfig {
+    /// Find `gts.toml` in `start` or the nearest ancestor
+    #[must_use]
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Parse a `gts.toml`, resolving relative paths against its directory
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid project config
+    pub fn load(file: &Path) -> Result<Self> {
+        let text = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read project config {}", file.display()))?;
+        let mut cfg: Self = toml::from_str(&text)
+            .with_context(|| format!("Invalid project config {}", file.display()))?;
+
+        let base = file.parent().unwrap_or_else(|| Path::new(""));
+        let resolve = |p: &mut Option<String>| {
+            if let Some(value) = p.as_mut()
+                && Path::new(value.as_str()).is_relative()
+            {
+                *value = base.join(&*value).to_string_lossy().into_owned();
+            }
+        };
+        resolve(&mut cfg.path);
+        resolve(&mut cfg.config);
+        resolve(&mut cfg.retired_ids);
+        resolve(&mut cfg.redaction);
+        cfg.validator.resolve_paths(base);
+        Ok(cfg)
+    }
+
+    /// Load `explicit` if given, else the discovered `gts.toml`, else defaults
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the selected file cannot be loaded
+    pub fn resolve(explicit: Option<&Path>, cwd: &Path) -> Result<Self> {
+        match explicit
+            .map(Path::to_path_buf)
+            .or_else(|| Self::discover(cwd))
+        {
+            Some(file) => Self::load(&file),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_discover_walks_up() {
+        let dir = TempDir::new().unwrap();
+        let nested = dir.path().join("a/b");
+        fs::create_dir_all(&nested).unwrap();
+        assert!(ProjectConfig::discover(&nested).is_none_or(|f| !f.starts_with(dir.path())));
+
+        fs::write(dir.path().join(PROJECT_CONFIG_FILE), "path = \"schemas\"\n").unwrap();
+        assert_eq!(
+            ProjectConfig::discover(&nested),
+            Some(dir.path().join(PROJECT_CONFIG_FILE))
+        );
+    }
+
+    #[test]
+    fn test_load_resolves_relative_paths() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join(PROJECT_CONFIG_FILE);
+        fs::write(
+            &file,
+            "path = \"schemas\"\nconfig = \"/etc/gts.config.json\"\n\n[generate-from-rust]\nexclude = [\"tests/*\"]\n\n[lint]\nrequire-owner = [\"gts.x.core.*\"]\n",
+        )
+        .unwrap();
+
+        let cfg = ProjectConfig::load(&file).unwrap();
+        assert_eq!(
+            cfg.path,
+            Some(dir.path().join("schemas").to_string_lossy().into_owned())
+        );
+        assert_eq!(cfg.config.as_deref(), Some("/etc/gts.config.json"));
+        assert_eq!(cfg.generate_from_rust.exclude, vec!["tests/*"]);
+        assert_eq!(cfg.lint.require_owner, vec!["gts.x.core.*"]);
+
+        fs::write(&file, "[validator]\npaths = [\"docs\"]\n").unwrap();
+        let cfg = ProjectConfig::load(&file).unwrap();
+        assert_eq!(cfg.validator.paths, vec![dir.path().join("docs")]);
+
+        fs::write(&file, "file-name-encoding = \"hashed\"\n").unwrap();
+        let cfg = ProjectConfig::load(&file).unwrap();
+        assert_eq!(cfg.file_name_encoding, Some(GtsFileNameEncoding::Hashed));
+        fs::write(
+            &file,
+            "retired-ids = \"retired.yaml\"\nredaction = \"redaction.yaml\"\n",
+        )
+        .unwrap();
+        let cfg = ProjectConfig::load(&file).unwrap();
+        let in_dir = |name: &str| Some(dir.path().join(name).to_string_lossy().into_owned());
+        assert_eq!(cfg.retired_ids, in_dir("retired.yaml"));
+        assert_eq!(cfg.redaction, in_dir("redaction.yaml"));
+        fs::write(&file, "file-name-encoding = \"base64\"\n").unwrap();
+        assert!(ProjectConfig::load(&file).is_err());
+
+        fs::write(&file, "store = \"schemas\"\n").unwrap();
+        assert!(ProjectConfig::load(&file).is_err());
+    }
+
+    #[test]
+    fn test_resolve_explicit_file() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("custom.toml");
+        fs::write(&file, "path = \"/data\"\n").unwrap();
+
+        let cfg = ProjectConfig::resolve(Some(&file), dir.path()).unwrap();
+        assert_eq!(cfg.path.as_deref(), Some("/data"));
+        assert!(
+            ProjectConfig::resolve(Some(&dir.path().join("missing.toml")), dir.path()).is_err()
+        );
+    }
+}
@@ -0,0 +1,411 @@
+//! `gts replay`: a candidate schema version against historical payloads.
+//!
+//! Validates every payload of a corpus the way [`GtsStore::validate_instance`]
+//! would against the candidate schema, whose `gts://` references resolve to
+//! the store, and counts the failing payloads per error type, so the blast
+//! radius of a schema change is known before the schema is published.
+//!
+//! The corpus is a directory, a file or `-` (stdin). `.jsonl`/`.ndjson` files
+//! and stdin hold one payload per line, other `.json` files one payload each.
+//! Payloads that are not JSON are counted as `unparsable` and left out of the
+//! rates.
+
+use anyhow::{Context, Result, bail};
+use gts::{GtsStore, XGtsRefValidator};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::report::{ReportFormat, escape_html};
+
+/// Failures of each error type kept as samples
+pub const MAX_SAMPLES: usize = 3;
+
+/// Error type of `x-gts-ref` violations
+const X_GTS_REF: &str = "x-gts-ref";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReplaySample {
+    /// File, and line for JSONL payloads (`events.jsonl:12`)
+    pub source: String,
+    pub instance_path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorTypeSummary {
+    /// Failing schema keyword (`required`, `type`, ...) or `x-gts-ref`
+    pub error_type: String,
+    /// Payloads with at least one error of this type
+    pub payloads: usize,
+    /// `payloads` among the parsed payloads, in percent
+    pub rate: f64,
+    pub samples: Vec<ReplaySample>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReplayReport {
+    /// `$id` of the candidate schema, if it has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_id: Option<String>,
+    /// Parsed payloads
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    /// `failed` among `total`, in percent
+    pub failure_rate: f64,
+    /// Payloads that are not JSON
+    pub unparsable: usize,
+    /// Most frequent first
+    pub error_types: Vec<ErrorTypeSummary>,
+}
+
+/// Validate the payloads of `data` against `schema`, resolving its references
+/// in `store`
+///
+/// # Errors
+///
+/// Returns an error if the schema does not compile or the corpus cannot be read
+pub fn run_replay(store: &GtsStore, schema: &Value, data: &Path) -> Result<ReplayReport> {
+    let mut replay = Replay {
+        validator: store.instance_validator(schema)?,
+        schema,
+        report: ReplayReport {
+            schema_id: schema.get("$id").and_then(Value::as_str).map(|id| {
+                id.strip_prefix(gts::gts::GTS_URI_PREFIX)
+                    .unwrap_or(id)
+                    .to_owned()
+            }),
+            ..ReplayReport::default()
+        },
+        by_type: BTreeMap::new(),
+    };
+
+    if data == Path::new("-") {
+        replay.lines("-", std::io::stdin().lock())?;
+    } else if data.is_dir() {
+        for entry in WalkDir::new(data)
+            .follow_links(true)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+        {
+            replay.file(entry.path())?;
+        }
+    } else if data.is_file() {
+        replay.file(data)?;
+    } else {
+        bail!("Payload corpus does not exist: {}", data.display());
+    }
+    Ok(replay.finish())
+}
+
+struct Replay<'a> {
+    validator: jsonschema::Validator,
+    schema: &'a Value,
+    report: ReplayReport,
+    /// Failing payloads and samples per error type
+    by_type: BTreeMap<String, (usize, Vec<ReplaySample>)>,
+}
+
+impl Replay<'_> {
+    fn file(&mut self, path: &Path) -> Result<()> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        let source = path.display().to_string();
+        if extension.eq_ignore_ascii_case("jsonl") || extension.eq_ignore_ascii_case("ndjson") {
+            let file = fs::File::open(path).with_context(|| format!("Failed to read {source}"))?;
+            self.lines(&source, std::io::BufReader::new(file))
+        } else if extension.eq_ignore_ascii_case("json") {
+            let text =
+                fs::read_to_string(path).with_context(|| format!("Failed to read {source}"))?;
+            self.payload(source, &text);
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn lines(&mut self, source: &str, reader: impl BufRead) -> Result<()> {
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read {source}"))?;
+            if !line.trim().is_empty() {
+                self.payload(format!("{source}:{}", index + 1), &line);
+            }
+        }
+        Ok(())
+    }
+
+    fn payload(&mut self, source: String, text: &str) {
+        let payload: Value = match serde_json::from_str(text) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::warn!("Skipping {source}: {e}");
+                self.report.unparsable += 1;
+                return;
+            }
+        };
+        self.report.total += 1;
+
+        let mut errors: BTreeMap<String, ReplaySample> = BTreeMap::new();
+        for error in self.validator.iter_errors(&payload) {
+            let schema_path = error.schema_path().to_string();
+            let error_type = schema_path.rsplit('/').next().unwrap_or_default();
+            errors.entry(error_type.to_owned()).or_insert(ReplaySample {
+                source: source.clone(),
+                instance_path: error.instance_path().to_string(),
+                message: error.to_string(),
+            });
+        }
+        if let Some(error) = XGtsRefValidator::new()
+            .validate_instance(&payload, self.schema, "")
+            .into_iter()
+            .next()
+        {
+            errors.entry(X_GTS_REF.to_owned()).or_insert(ReplaySample {
+                source,
+                instance_path: error.field_path,
+                message: error.reason,
+            });
+        }
+
+        if errors.is_empty() {
+            self.report.passed += 1;
+            return;
+        }
+        self.report.failed += 1;
+        for (error_type, sample) in errors {
+            let (payloads, samples) = self.by_type.entry(error_type).or_default();
+            *payloads += 1;
+            if samples.len() < MAX_SAMPLES {
+                samples.push(sample);
+            }
+        }
+    }
+
+    fn finish(self) -> ReplayReport {
+        let Self {
+            mut report,
+            by_type,
+            ..
+        } = self;
+        let total = report.total;
+        report.failure_rate = percent(report.failed, total);
+        report.error_types = by_type
+            .into_iter()
+            .map(|(error_type, (payloads, samples))| ErrorTypeSummary {
+                error_type,
+                payloads,
+                rate: percent(payloads, total),
+                samples,
+            })
+            .collect();
+        // Stable: ties stay sorted by error type
+        report
+            .error_types
+            .sort_by_key(|summary| std::cmp::Reverse(summary.payloads));
+        report
+    }
+}
+
+/// `part` of `total` in percent, rounded to one decimal
+fn percent(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let percent = part as f64 * 100.0 / total as f64;
+    (percent * 10.0).round() / 10.0
+}
+
+/// Render `report` as a Markdown or HTML document
+#[must_use]
+pub fn render_replay_report(report: &ReplayReport, format: ReportFormat) -> String {
+    let title = match &report.schema_id {
+        Some(id) => format!("Replay of `{id}`"),
+        None => "Replay".to_owned(),
+    };
+    let summary = [
+        ("payloads", report.total.to_string()),
+        ("passed", report.passed.to_string()),
+        (
+            "failed",
+            format!("{} ({}%)", report.failed, report.failure_rate),
+        ),
+        ("unparsable", report.unparsable.to_string()),
+    ];
+    let mut out = String::new();
+    match format {
+        ReportFormat::Markdown => {
+            let _ = writeln!(out, "# {title}\n");
+            let _ = writeln!(out, "| | |\n|---|---|");
+            for (label, value) in &summary {
+                let _ = writeln!(out, "| {label} | {value} |");
+            }
+            if !report.error_types.is_empty() {
+                let _ = writeln!(out, "\n## Failures by error type\n");
+                let _ = writeln!(out, "| Error type | Payloads | Rate |\n|---|---|---|");
+                for summary in &report.error_types {
+                    let _ = writeln!(
+                        out,
+                        "| `{}` | {} | {}% |",
+                        summary.error_type, summary.payloads, summary.rate
+                    );
+                }
+            }
+            for summary in &report.error_types {
+                let _ = writeln!(out, "\n### `{}`\n", summary.error_type);
+                for sample in &summary.samples {
+                    let _ = writeln!(
+                        out,
+                        "- {} `{}`: {}",
+                        sample.source, sample.instance_path, sample.message
+                    );
+                }
+            }
+        }
+        ReportFormat::Html => {
+            let title = escape_html(&title.replace('`', ""));
+            let _ = writeln!(out, "<!DOCTYPE html>");
+            let _ = writeln!(out, "<html>\n<head>\n<meta charset=\"utf-8\">");
+            let _ = writeln!(out, "<title>{title}</title>");
+            let _ = writeln!(
+                out,
+                "<style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}\
+                 td,th{{border:1px solid #ccc;padding:4px 8px}}</style>"
+            );
+            let _ = writeln!(out, "</head>\n<body>");
+            let _ = writeln!(out, "<h1>{title}</h1>\n<table>");
+            for (label, value) in &summary {
+                let _ = writeln!(out, "<tr><th>{label}</th><td>{value}</td></tr>");
+            }
+            let _ = writeln!(out, "</table>");
+            if !report.error_types.is_empty() {
+                let _ = writeln!(out, "<h2>Failures by error type</h2>\n<table>");
+                let _ = writeln!(
+                    out,
+                    "<tr><th>Error type</th><th>Payloads</th><th>Rate</th></tr>"
+                );
+                for summary in &report.error_types {
+                    let _ = writeln!(
+                        out,
+                        "<tr><td><code>{}</code></td><td>{}</td><td>{}%</td></tr>",
+                        escape_html(&summary.error_type),
+                        summary.payloads,
+                        summary.rate
+                    );
+                }
+                let _ = writeln!(out, "</table>");
+            }
+            for summary in &report.error_types {
+                let _ = writeln!(
+                    out,
+                    "<h3><code>{}</code></h3>\n<ul>",
+                    escape_html(&summary.error_type)
+                );
+                for sample in &summary.samples {
+                    let _ = writeln!(
+                        out,
+                        "<li>{} <code>{}</code>: {}</li>",
+                        escape_html(&sample.source),
+                        escape_html(&sample.instance_path),
+                        escape_html(&sample.message)
+                    );
+                }
+                let _ = writeln!(out, "</ul>");
+            }
+            let _ = writeln!(out, "</body>\n</html>");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use gts::test_support::store_from_json;
+    use serde_json::json;
+
+    fn store() -> GtsStore {
+        store_from_json(&[json!({
+            "$id": "gts://gts.x.core.events.type.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": {"id": {"type": "string"}},
+            "required": ["id"]
+        })])
+    }
+
+    fn candidate() -> Value {
+        json!({
+            "$id": "gts://gts.x.core.events.type.v1~x.app.orders.placed.v1.1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "allOf": [
+                {"$ref": "gts://gts.x.core.events.type.v1~"},
+                {
+                    "type": "object",
+                    "properties": {"amount": {"type": "number"}},
+                    "required": ["amount"]
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_replay_counts_failures_per_error_type() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.json"), r#"{"id": "o1", "amount": 3}"#).unwrap();
+        fs::write(
+            dir.path().join("b.jsonl"),
+            "{\"id\": \"o2\"}\n\n{\"amount\": \"ten\"}\nnot json\n{\"id\": \"o3\", \"amount\": 1}\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.txt"), "skipped").unwrap();
+
+        let report = run_replay(&store(), &candidate(), dir.path()).unwrap();
+        assert_eq!(
+            report.schema_id.as_deref(),
+            Some("gts.x.core.events.type.v1~x.app.orders.placed.v1.1~")
+        );
+        assert_eq!((report.total, report.passed, report.failed), (4, 2, 2));
+        assert_eq!(report.unparsable, 1);
+        assert!((report.failure_rate - 50.0).abs() < f64::EPSILON);
+
+        // Both failing payloads miss a required field; one has a wrong type too
+        let types: Vec<_> = report
+            .error_types
+            .iter()
+            .map(|t| (t.error_type.as_str(), t.payloads))
+            .collect();
+        assert_eq!(types, [("required", 2), ("type", 1)]);
+        let sample = &report.error_types[1].samples[0];
+        assert!(sample.source.ends_with("b.jsonl:3"), "{}", sample.source);
+        assert_eq!(sample.instance_path, "/amount");
+    }
+
+    #[test]
+    fn test_render_replay_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("events.jsonl");
+        fs::write(&data, "{\"id\": \"o1\"}\n").unwrap();
+        let report = run_replay(&store(), &candidate(), &data).unwrap();
+
+        let markdown = render_replay_report(&report, ReportFormat::Markdown);
+        assert!(markdown.contains("| failed | 1 (100%) |"), "{markdown}");
+        assert!(markdown.contains("| `required` | 1 | 100% |"), "{markdown}");
+        let html = render_replay_report(&report, ReportFormat::Html);
+        assert!(
+            html.contains("<td><code>required</code></td><td>1</td>"),
+            "{html}"
+        );
+    }
+}
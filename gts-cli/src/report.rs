@@ -0,0 +1,531 @@
+//! Consolidated Markdown/HTML reports over cast and compatibility results.
+//!
+//! Commands that produce [`GtsEntityCastResult`]s (`compatibility`, `cast`) can
+//! write them through [`write_cast_report`], and `gts diff` writes its
+//! [`SchemaDiff`] through [`write_diff_report`]; the format is picked from the
+//! report file extension.
+
+use anyhow::Result;
+use gts::{ChangeLevel, GtsEntityCastResult, SchemaDiff};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Output format of a cast report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// `.html`/`.htm` files get HTML, everything else Markdown
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm") => {
+                Self::Html
+            }
+            _ => Self::Markdown,
+        }
+    }
+}
+
+/// Verdict of a single cast/compatibility result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastVerdict {
+    FullyCompatible,
+    BackwardCompatible,
+    ForwardCompatible,
+    Incompatible,
+    Failed,
+}
+
+impl CastVerdict {
+    #[must_use]
+    pub fn of(result: &GtsEntityCastResult) -> Self {
+        if result.error.is_some() {
+            Self::Failed
+        } else if result.is_fully_compatible {
+            Self::FullyCompatible
+        } else if result.is_backward_compatible {
+            Self::BackwardCompatible
+        } else if result.is_forward_compatible {
+            Self::ForwardCompatible
+        } else {
+            Self::Incompatible
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::FullyCompatible => "fully compatible",
+            Self::BackwardCompatible => "backward compatible",
+            Self::ForwardCompatible => "forward compatible",
+            Self::Incompatible => "incompatible",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// Per-verdict counts over a set of results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CastReportSummary {
+    pub total: usize,
+    pub fully_compatible: usize,
+    pub backward_compatible: usize,
+    pub forward_compatible: usize,
+    pub incompatible: usize,
+    pub failed: usize,
+}
+
+impl CastReportSummary {
+    #[must_use]
+    pub fn from_results(results: &[GtsEntityCastResult]) -> Self {
+        let mut summary = Self {
+            total: results.len(),
+            ..Self::default()
+        };
+        for result in results {
+            match CastVerdict::of(result) {
+                CastVerdict::FullyCompatible => summary.fully_compatible += 1,
+                CastVerdict::BackwardCompatible => summary.backward_compatible += 1,
+                CastVerdict::ForwardCompatible => summary.forward_compatible += 1,
+                CastVerdict::Incompatible => summary.incompatible += 1,
+                CastVerdict::Failed => summary.failed += 1,
+            }
+        }
+        summary
+    }
+
+    fn rows(&self) -> [(&'static str, usize); 5] {
+        [
+            (CastVerdict::FullyCompatible.label(), self.fully_compatible),
+            (
+                CastVerdict::BackwardCompatible.label(),
+                self.backward_compatible,
+            ),
+            (
+                CastVerdict::ForwardCompatible.label(),
+                self.forward_compatible,
+            ),
+            (CastVerdict::Incompatible.label(), self.incompatible),
+            (CastVerdict::Failed.label(), self.failed),
+        ]
+    }
+}
+
+/// Render `results` as a single report document
+#[must_use]
+pub fn render_cast_report(
+    title: &str,
+    results: &[GtsEntityCastResult],
+    format: ReportFormat,
+) -> String {
+    match format {
+        ReportFormat::Markdown => render_markdown(title, results),
+        ReportFormat::Html => render_html(title, results),
+    }
+}
+
+/// Render `results` and write them to `path`, picking the format from the extension
+///
+/// # Errors
+///
+/// Returns an error if the report file cannot be written
+pub fn write_cast_report(path: &str, title: &str, results: &[GtsEntityCastResult]) -> Result<()> {
+    let path = Path::new(path);
+    let report = render_cast_report(title, results, ReportFormat::from_path(path));
+    fs::write(path, report)?;
+    Ok(())
+}
+
+/// Render `diff` between `old` and `new` as a single report document
+#[must_use]
+pub fn render_diff_report(old: &str, new: &str, diff: &SchemaDiff, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => render_diff_markdown(old, new, diff),
+        ReportFormat::Html => render_diff_html(old, new, diff),
+    }
+}
+
+/// Render `diff` and write it to `path`, picking the format from the extension
+///
+/// # Errors
+///
+/// Returns an error if the report file cannot be written
+pub fn write_diff_report(path: &str, old: &str, new: &str, diff: &SchemaDiff) -> Result<()> {
+    let path = Path::new(path);
+    let report = render_diff_report(old, new, diff, ReportFormat::from_path(path));
+    fs::write(path, report)?;
+    Ok(())
+}
+
+fn level_label(level: ChangeLevel) -> &'static str {
+    match level {
+        ChangeLevel::Patch => "patch",
+        ChangeLevel::Minor => "minor",
+        ChangeLevel::Major => "major",
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "no" }
+}
+
+/// Sections of a diff report as `(heading, entries)`, empty ones left out
+fn diff_sections(diff: &SchemaDiff) -> Vec<(&'static str, Vec<String>)> {
+    [
+        ("Added properties", diff.added_properties.clone()),
+        ("Removed properties", diff.removed_properties.clone()),
+        ("Newly required", diff.required_added.clone()),
+        ("No longer required", diff.required_removed.clone()),
+        (
+            "Keyword changes",
+            diff.changes.iter().map(ToString::to_string).collect(),
+        ),
+        ("Backward errors", diff.backward_errors.clone()),
+        ("Forward errors", diff.forward_errors.clone()),
+    ]
+    .into_iter()
+    .filter(|(_, entries)| !entries.is_empty())
+    .collect()
+}
+
+fn render_diff_markdown(old: &str, new: &str, diff: &SchemaDiff) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Schema diff `{old}` → `{new}`\n");
+    let _ = writeln!(out, "- Level: **{}**", level_label(diff.level));
+    let _ = writeln!(
+        out,
+        "- Backward compatible: {}",
+        yes_no(diff.is_backward_compatible)
+    );
+    let _ = writeln!(
+        out,
+        "- Forward compatible: {}",
+        yes_no(diff.is_forward_compatible)
+    );
+    for (heading, entries) in diff_sections(diff) {
+        let _ = writeln!(out, "\n## {heading}\n");
+        for entry in entries {
+            let _ = writeln!(out, "- `{entry}`");
+        }
+    }
+    out
+}
+
+fn render_diff_html(old: &str, new: &str, diff: &SchemaDiff) -> String {
+    let title = format!("Schema diff {old} → {new}");
+    let level = level_label(diff.level);
+    let mut out = String::new();
+    let _ = writeln!(out, "<!DOCTYPE html>");
+    let _ = writeln!(out, "<html>\n<head>\n<meta charset=\"utf-8\">");
+    let _ = writeln!(out, "<title>{}</title>", escape_html(&title));
+    let _ = writeln!(
+        out,
+        "<style>body{{font-family:sans-serif}}.major{{color:#b00}}.patch{{color:#070}}</style>"
+    );
+    let _ = writeln!(out, "</head>\n<body>");
+    let _ = writeln!(
+        out,
+        "<h1>Schema diff <code>{}</code> &rarr; <code>{}</code></h1>",
+        escape_html(old),
+        escape_html(new)
+    );
+    let _ = writeln!(
+        out,
+        "<p>Level: <strong class=\"{level}\">{level}</strong><br>\
+         Backward compatible: {}<br>Forward compatible: {}</p>",
+        yes_no(diff.is_backward_compatible),
+        yes_no(diff.is_forward_compatible)
+    );
+    for (heading, entries) in diff_sections(diff) {
+        let _ = writeln!(out, "<h2>{heading}</h2>\n<ul>");
+        for entry in entries {
+            let _ = writeln!(out, "<li><code>{}</code></li>", escape_html(&entry));
+        }
+        let _ = writeln!(out, "</ul>");
+    }
+    let _ = writeln!(out, "</body>\n</html>");
+    out
+}
+
+/// Messages explaining why a result is not fully compatible
+fn failures(result: &GtsEntityCastResult) -> Vec<&str> {
+    result
+        .error
+        .iter()
+        .map(String::as_str)
+        .chain(result.incompatibility_reasons.iter().map(String::as_str))
+        .chain(result.backward_errors.iter().map(String::as_str))
+        .chain(result.forward_errors.iter().map(String::as_str))
+        .collect()
+}
+
+/// Field-level changes as `(kind, description)` pairs
+fn field_changes(result: &GtsEntityCastResult) -> Vec<(&'static str, String)> {
+    let mut changes: Vec<(&'static str, String)> = Vec::new();
+    changes.extend(result.added_properties.iter().map(|p| ("added", p.clone())));
+    changes.extend(
+        result
+            .removed_properties
+            .iter()
+            .map(|p| ("removed", p.clone())),
+    );
+    for changed in &result.changed_properties {
+        let mut entries: Vec<_> = changed.iter().collect();
+        entries.sort();
+        let description = entries
+            .iter()
+            .map(|(k, v)| format!("{k}: {v}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        changes.push(("changed", description));
+    }
+    changes
+}
+
+fn render_markdown(title: &str, results: &[GtsEntityCastResult]) -> String {
+    let summary = CastReportSummary::from_results(results);
+    let mut out = String::new();
+    let _ = writeln!(out, "# {title}\n");
+    let _ = writeln!(out, "## Summary\n");
+    let _ = writeln!(out, "| Verdict | Count |");
+    let _ = writeln!(out, "|---|---|");
+    for (label, count) in summary.rows() {
+        let _ = writeln!(out, "| {label} | {count} |");
+    }
+    let _ = writeln!(out, "| **total** | {} |", summary.total);
+
+    for result in results {
+        let _ = writeln!(out, "\n## `{}` → `{}`\n", result.from_id, result.to_id);
+        let _ = writeln!(out, "- Verdict: **{}**", CastVerdict::of(result).label());
+        let _ = writeln!(out, "- Direction: {}", result.direction);
+
+        let changes = field_changes(result);
+        if !changes.is_empty() {
+            let _ = writeln!(out, "\n### Field changes\n");
+            for (kind, field) in &changes {
+                let _ = writeln!(out, "- {kind}: `{field}`");
+            }
+        }
+
+        let failures = failures(result);
+        if !failures.is_empty() {
+            let _ = writeln!(out, "\n### Failures\n");
+            for failure in failures {
+                let _ = writeln!(out, "- {failure}");
+            }
+        }
+    }
+    out
+}
+
+fn render_html(title: &str, results: &[GtsEntityCastResult]) -> String {
+    let summary = CastReportSummary::from_results(results);
+    let title = escape_html(title);
+    let mut out = String::new();
+    let _ = writeln!(out, "<!DOCTYPE html>");
+    let _ = writeln!(out, "<html>\n<head>\n<meta charset=\"utf-8\">");
+    let _ = writeln!(out, "<title>{title}</title>");
+    let _ = writeln!(
+        out,
+        "<style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}\
+         td,th{{border:1px solid #ccc;padding:4px 8px}}\
+         .incompatible,.failed{{color:#b00}}.fully-compatible{{color:#070}}</style>"
+    );
+    let _ = writeln!(out, "</head>\n<body>");
+    let _ = writeln!(out, "<h1>{title}</h1>");
+    let _ = writeln!(out, "<h2>Summary</h2>\n<table>");
+    let _ = writeln!(out, "<tr><th>Verdict</th><th>Count</th></tr>");
+    for (label, count) in summary.rows() {
+        let _ = writeln!(out, "<tr><td>{label}</td><td>{count}</td></tr>");
+    }
+    let _ = writeln!(
+        out,
+        "<tr><th>total</th><th>{}</th></tr>\n</table>",
+        summary.total
+    );
+
+    for result in results {
+        let verdict = CastVerdict::of(result).label();
+        let _ = writeln!(
+            out,
+            "<h2><code>{}</code> &rarr; <code>{}</code></h2>",
+            escape_html(&result.from_id),
+            escape_html(&result.to_id)
+        );
+        let _ = writeln!(
+            out,
+            "<p>Verdict: <strong class=\"{}\">{verdict}</strong><br>Direction: {}</p>",
+            verdict.replace(' ', "-"),
+            escape_html(&result.direction)
+        );
+
+        let changes = field_changes(result);
+        if !changes.is_empty() {
+            let _ = writeln!(out, "<h3>Field changes</h3>\n<ul>");
+            for (kind, field) in &changes {
+                let _ = writeln!(out, "<li>{kind}: <code>{}</code></li>", escape_html(field));
+            }
+            let _ = writeln!(out, "</ul>");
+        }
+
+        let failures = failures(result);
+        if !failures.is_empty() {
+            let _ = writeln!(out, "<h3>Failures</h3>\n<ul>");
+            for failure in failures {
+                let _ = writeln!(out, "<li>{}</li>", escape_html(failure));
+            }
+            let _ = writeln!(out, "</ul>");
+        }
+    }
+    let _ = writeln!(out, "</body>\n</html>");
+    out
+}
+
+/// `text` with the HTML special characters escaped
+#[must_use]
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn result(from: &str, to: &str) -> GtsEntityCastResult {
+        GtsEntityCastResult {
+            from_id: from.to_owned(),
+            to_id: to.to_owned(),
+            old: from.to_owned(),
+            new: to.to_owned(),
+            direction: "up".to_owned(),
+            added_properties: Vec::new(),
+            removed_properties: Vec::new(),
+            changed_properties: Vec::new(),
+            is_fully_compatible: true,
+            is_backward_compatible: true,
+            is_forward_compatible: true,
+            incompatibility_reasons: Vec::new(),
+            backward_errors: Vec::new(),
+            forward_errors: Vec::new(),
+            casted_entity: None,
+            error: None,
+        }
+    }
+
+    fn mixed_results() -> Vec<GtsEntityCastResult> {
+        let ok = result("gts.x.a.b.c.v1.0~", "gts.x.a.b.c.v1.1~");
+
+        let mut backward = result("gts.x.a.b.c.v1.1~", "gts.x.a.b.c.v1.2~");
+        backward.is_fully_compatible = false;
+        backward.is_forward_compatible = false;
+        backward.added_properties = vec!["email".to_owned()];
+        backward.forward_errors = vec!["removed required <email>".to_owned()];
+
+        let mut failed = result("gts.x.a.b.c.v1.2~", "gts.x.a.b.c.v9.0~");
+        failed.error = Some("Schema not found".to_owned());
+
+        vec![ok, backward, failed]
+    }
+
+    #[test]
+    fn test_summary_counts_verdicts() {
+        let summary = CastReportSummary::from_results(&mixed_results());
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.fully_compatible, 1);
+        assert_eq!(summary.backward_compatible, 1);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.incompatible, 0);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ReportFormat::from_path(Path::new("out/report.HTML")),
+            ReportFormat::Html
+        );
+        assert_eq!(
+            ReportFormat::from_path(Path::new("report.md")),
+            ReportFormat::Markdown
+        );
+        assert_eq!(
+            ReportFormat::from_path(Path::new("report")),
+            ReportFormat::Markdown
+        );
+    }
+
+    #[test]
+    fn test_markdown_report() {
+        let report = render_cast_report("Cast report", &mixed_results(), ReportFormat::Markdown);
+        assert!(report.starts_with("# Cast report\n"));
+        assert!(report.contains("| backward compatible | 1 |"));
+        assert!(report.contains("- added: `email`"));
+        assert!(report.contains("- Verdict: **failed**"));
+        assert!(report.contains("- Schema not found"));
+    }
+
+    fn schema_diff() -> SchemaDiff {
+        gts::diff_schemas(
+            &serde_json::json!({
+                "type": "object",
+                "properties": {"name": {"type": "string", "maxLength": 64}}
+            }),
+            &serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "maxLength": 128},
+                    "note": {"type": "string"}
+                },
+                "required": ["note"]
+            }),
+        )
+    }
+
+    #[test]
+    fn test_markdown_diff_report() {
+        let report = render_diff_report(
+            "old.json",
+            "new.json",
+            &schema_diff(),
+            ReportFormat::Markdown,
+        );
+        assert!(report.starts_with("# Schema diff `old.json` \u{2192} `new.json`\n"));
+        assert!(report.contains("- Level: **major**"));
+        assert!(report.contains("- Backward compatible: no"));
+        assert!(report.contains("## Added properties\n\n- `note`"));
+        assert!(report.contains("- `name: maxLength loosened 64 -> 128`"));
+        assert!(!report.contains("## Removed properties"));
+    }
+
+    #[test]
+    fn test_html_diff_report_escapes_content() {
+        let report = render_diff_report("<old>", "new.json", &schema_diff(), ReportFormat::Html);
+        assert!(report.contains("<code>&lt;old&gt;</code>"));
+        assert!(report.contains("<strong class=\"major\">major</strong>"));
+        assert!(report.contains("<li><code>note</code></li>"));
+    }
+
+    #[test]
+    fn test_html_report_escapes_content() {
+        let report = render_cast_report("A <b> report", &mixed_results(), ReportFormat::Html);
+        assert!(report.contains("<title>A &lt;b&gt; report</title>"));
+        assert!(report.contains("<li>removed required &lt;email&gt;</li>"));
+        assert!(report.contains("class=\"fully-compatible\""));
+    }
+}
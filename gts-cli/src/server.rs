@@ -1,17 +1,21 @@
 use axum::{
     Json, Router,
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::State,
+    http::{StatusCode, Uri},
     middleware,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::{get, post},
 };
-use gts::GtsOps;
+use gts::{GtsFileReader, GtsID, GtsLoadProgress, GtsOps, GtsStore, StoreError};
 use serde::Deserialize;
 use serde_json::{Value, json};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use crate::api_error::{ApiError, ApiErrorCode, ApiJson, ApiPath, ApiQuery};
 use crate::logging::LoggingMiddleware;
+use crate::server_config::{ConfigReloader, ServerConfig};
 
 #[derive(Clone)]
 pub struct AppState {
@@ -23,6 +27,23 @@ pub struct GtsHttpServer {
     host: String,
     port: u16,
     verbose: u8,
+    reload: Option<ReloadSettings>,
+    load: Option<BackgroundLoad>,
+    #[cfg(feature = "grpc")]
+    grpc_port: Option<u16>,
+}
+
+struct BackgroundLoad {
+    path: Vec<String>,
+    concurrency: usize,
+    progress: Arc<GtsLoadProgress>,
+}
+
+struct ReloadSettings {
+    file: PathBuf,
+    fallback: ServerConfig,
+    current: ServerConfig,
+    poll: Option<Duration>,
 }
 
 impl GtsHttpServer {
@@ -33,9 +54,65 @@ impl GtsHttpServer {
             host,
             port,
             verbose,
+            reload: None,
+            load: None,
+            #[cfg(feature = "grpc")]
+            grpc_port: None,
         }
     }
 
+    /// Also serve the gRPC API (`proto/gts/v1/gts.proto`) on `port`, backed
+    /// by the same store as the REST API.
+    #[cfg(feature = "grpc")]
+    #[must_use]
+    pub fn with_grpc(mut self, port: u16) -> Self {
+        self.grpc_port = Some(port);
+        self
+    }
+
+    /// Load the store from `path` after the server starts listening, at most
+    /// `concurrency` files at a time. Until it is loaded the store is empty
+    /// and `/healthz` reports the progress under `loading`; entities
+    /// registered meanwhile are kept in the loaded store.
+    #[must_use]
+    pub fn with_background_load(mut self, path: Vec<String>, concurrency: usize) -> Self {
+        let progress = Arc::new(GtsLoadProgress::default());
+        let handlers = self.ops.store.keyword_handlers().clone();
+        let migrations = self.ops.store.migrations().clone();
+        let retired = self.ops.store.configured_retired_ids().clone();
+        self.ops.store = GtsStore::loading(Arc::clone(&progress));
+        self.ops.store.set_keyword_handlers(handlers);
+        self.ops.store.set_migrations(migrations);
+        self.ops.store.add_retired_ids(retired);
+        self.load = Some(BackgroundLoad {
+            path,
+            concurrency,
+            progress,
+        });
+        self
+    }
+
+    /// Reload `file` on `SIGHUP` and every `poll` interval while running.
+    ///
+    /// `current` is the effective config the server's ops were built from;
+    /// settings missing from the file fall back to `fallback`.
+    #[must_use]
+    pub fn with_config_reload(
+        mut self,
+        file: PathBuf,
+        fallback: ServerConfig,
+        current: ServerConfig,
+        poll: Option<Duration>,
+    ) -> Self {
+        self.reload = Some(ReloadSettings {
+            file,
+            fallback,
+            current,
+            poll,
+        });
+        self
+    }
+
     /// Run the HTTP server
     ///
     /// # Errors
@@ -49,6 +126,38 @@ impl GtsHttpServer {
             ops: Arc::new(Mutex::new(self.ops)),
         };
 
+        if let Some(reload) = self.reload {
+            let reloader = ConfigReloader::new(
+                reload.file,
+                reload.fallback,
+                reload.current,
+                Arc::clone(&state.ops),
+                verbose,
+            );
+            tokio::spawn(async move {
+                if let Err(e) = reloader.run(reload.poll).await {
+                    tracing::error!("Config reload disabled: {e:#}");
+                }
+            });
+        }
+
+        if let Some(load) = self.load {
+            tokio::spawn(load.run(Arc::clone(&state.ops)));
+        }
+
+        #[cfg(feature = "grpc")]
+        if let Some(grpc_port) = self.grpc_port {
+            let addr = format!("{}:{grpc_port}", self.host);
+            let listener = tokio::net::TcpListener::bind(&addr).await?;
+            tracing::info!("gRPC server listening on {}", addr);
+            let grpc_state = state.clone();
+            tokio::spawn(async move {
+                if let Err(e) = crate::grpc::serve(grpc_state, listener).await {
+                    tracing::error!("gRPC server stopped: {e:#}");
+                }
+            });
+        }
+
         let app = Self::create_router(state, verbose);
 
         let addr = format!("{}:{}", self.host, self.port);
@@ -65,7 +174,11 @@ impl GtsHttpServer {
             .route("/entities", get(get_entities).post(add_entity))
             .route("/entities/{gts_id}", get(get_entity))
             .route("/entities/bulk", post(add_entities))
-            .route("/schemas", post(add_schema))
+            .route("/schemas", get(find_schemas).post(add_schema))
+            .route("/schemas/{gts_id}", get(get_schema))
+            .route("/instances", get(find_instances))
+            .route("/instances/{gts_id}", get(get_instance))
+            .route("/validate", post(validate_content))
             .route("/validate-id", get(validate_id))
             .route("/extract-id", post(extract_id))
             .route("/parse-id", get(parse_id))
@@ -75,10 +188,13 @@ impl GtsHttpServer {
             .route("/validate-schema", post(validate_schema))
             .route("/validate-entity", post(validate_entity))
             .route("/resolve-relationships", get(schema_graph))
+            .route("/effective-traits", get(effective_traits))
             .route("/compatibility", get(compatibility))
             .route("/cast", post(cast))
             .route("/query", get(query))
             .route("/attr", get(attr))
+            .route("/healthz", get(healthz))
+            .fallback(route_not_found)
             .with_state(state);
 
         // Add custom logging middleware if verbose >= 1
@@ -109,14 +225,104 @@ impl GtsHttpServer {
                     "get": { "summary": "Get all entities in the registry" },
                     "post": { "summary": "Register a single entity" }
                 },
+                "/schemas": {
+                    "get": { "summary": "Schemas whose ID matches the wildcard `pattern`" },
+                    "post": { "summary": "Register a schema" }
+                },
+                "/schemas/{gts_id}": {
+                    "get": { "summary": "Get a schema document" }
+                },
+                "/instances": {
+                    "get": { "summary": "Instances whose ID matches the wildcard `pattern`" }
+                },
+                "/instances/{gts_id}": {
+                    "get": { "summary": "Get an instance" }
+                },
+                "/validate": {
+                    "post": { "summary": "Validate an entity against its schema chain without registering it" }
+                },
                 "/validate-id": {
                     "get": { "summary": "Validate GTS identifier" }
+                },
+                "/healthz": {
+                    "get": { "summary": "Registry counts, last load time, load errors and load progress" }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Error": {
+                        "type": "object",
+                        "required": ["code", "message", "details"],
+                        "properties": {
+                            "code": {
+                                "type": "string",
+                                "enum": [
+                                    "bad_request",
+                                    "invalid_gts_id",
+                                    "invalid_pattern",
+                                    "retired_id",
+                                    "not_found",
+                                    "invalid_entity",
+                                    "validation_failed",
+                                    "cast_failed",
+                                    "internal"
+                                ]
+                            },
+                            "message": { "type": "string" },
+                            "details": { "type": "array", "items": { "type": "string" } },
+                            "gts_id": { "type": "string" }
+                        }
+                    }
                 }
             }
         })
     }
 }
 
+impl BackgroundLoad {
+    async fn run(self, ops: Arc<Mutex<GtsOps>>) {
+        let cfg = match ops.lock() {
+            Ok(ops) => ops.cfg.clone(),
+            Err(e) => {
+                tracing::error!("Store load aborted: {e}");
+                return;
+            }
+        };
+        let reader = GtsFileReader::new(&self.path, Some(cfg));
+        let store =
+            GtsStore::load_async(reader, self.concurrency, Arc::clone(&self.progress)).await;
+        let Ok(mut ops) = ops.lock() else {
+            tracing::error!("Store load aborted: ops lock poisoned");
+            return;
+        };
+        // A config reload may have replaced the placeholder store meanwhile
+        if ops
+            .store
+            .load_progress()
+            .is_some_and(|p| Arc::ptr_eq(p, &self.progress))
+        {
+            tracing::info!("Store loaded: {:?}", self.progress.snapshot());
+            Self::swap_in(&mut ops, store);
+            ops.path = Some(self.path);
+        }
+    }
+
+    /// Replace the placeholder store of `ops` with the loaded `store`.
+    fn swap_in(ops: &mut GtsOps, mut store: GtsStore) {
+        store.set_keyword_handlers(ops.store.keyword_handlers().clone());
+        store.set_migrations(ops.store.migrations().clone());
+        store.add_retired_ids(ops.store.configured_retired_ids().clone());
+        // Entities registered while loading went into the placeholder store;
+        // they are newer than the loaded files
+        for (id, entity) in ops.store.items() {
+            if let Err(e) = store.register(entity.clone()) {
+                tracing::warn!("Entity '{id}' registered while loading was dropped: {e}");
+            }
+        }
+        ops.store = store;
+    }
+}
+
 // Query parameters
 #[derive(Deserialize)]
 struct GtsIdQuery {
@@ -140,6 +346,8 @@ struct QueryParams {
     expr: String,
     #[serde(default = "default_limit")]
     limit: usize,
+    #[serde(default)]
+    explain: bool,
 }
 
 #[derive(Deserialize)]
@@ -153,6 +361,14 @@ struct LimitQuery {
     limit: usize,
 }
 
+#[derive(Deserialize)]
+struct PatternQuery {
+    #[serde(default = "default_pattern")]
+    pattern: String,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
 #[derive(Deserialize)]
 struct AddEntityQuery {
     #[serde(default)]
@@ -163,6 +379,10 @@ fn default_limit() -> usize {
     100
 }
 
+fn default_pattern() -> String {
+    "gts.*".to_owned()
+}
+
 #[derive(Deserialize)]
 struct SchemaRegister {
     type_id: String,
@@ -193,21 +413,20 @@ struct ValidateEntityRequest {
 }
 
 // Helper function to lock mutex or return error response
-fn lock_ops(
-    mutex: &Arc<Mutex<GtsOps>>,
-) -> Result<std::sync::MutexGuard<'_, GtsOps>, impl IntoResponse> {
-    mutex.lock().map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Server state corrupted"})),
-        )
-    })
+fn lock_ops(mutex: &Arc<Mutex<GtsOps>>) -> Result<std::sync::MutexGuard<'_, GtsOps>, ApiError> {
+    mutex
+        .lock()
+        .map_err(|_| ApiError::new(ApiErrorCode::Internal, "Server state corrupted"))
+}
+
+async fn route_not_found(uri: Uri) -> ApiError {
+    ApiError::route_not_found(uri.path())
 }
 
 // Async Handlers
 async fn get_entities(
     State(state): State<AppState>,
-    Query(params): Query<LimitQuery>,
+    ApiQuery(params): ApiQuery<LimitQuery>,
 ) -> impl IntoResponse {
     let ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -219,20 +438,28 @@ async fn get_entities(
 
 async fn get_entity(
     State(state): State<AppState>,
-    Path(gts_id): Path<String>,
+    ApiPath(gts_id): ApiPath<String>,
 ) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
         Err(response) => return response.into_response(),
     };
     let result = ops.get_entity(&gts_id);
-    Json(result).into_response()
+    if result.ok {
+        return Json(result).into_response();
+    }
+    // Anonymous instances are stored under their UUID
+    match GtsID::new(&gts_id) {
+        Err(e) if !gts_id::is_uuid(&gts_id) => ApiError::from(&e),
+        _ => ApiError::from(&StoreError::EntityNotFound(gts_id)),
+    }
+    .into_response()
 }
 
 async fn add_entity(
     State(state): State<AppState>,
-    Query(params): Query<AddEntityQuery>,
-    Json(body): Json<Value>,
+    ApiQuery(params): ApiQuery<AddEntityQuery>,
+    ApiJson(body): ApiJson<Value>,
 ) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -248,7 +475,7 @@ async fn add_entity(
 
 async fn add_entities(
     State(state): State<AppState>,
-    Json(body): Json<Vec<Value>>,
+    ApiJson(body): ApiJson<Vec<Value>>,
 ) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -260,7 +487,7 @@ async fn add_entities(
 
 async fn add_schema(
     State(state): State<AppState>,
-    Json(body): Json<SchemaRegister>,
+    ApiJson(body): ApiJson<SchemaRegister>,
 ) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -270,9 +497,98 @@ async fn add_schema(
     Json(result).into_response()
 }
 
+async fn get_schema(
+    State(state): State<AppState>,
+    ApiPath(gts_id): ApiPath<String>,
+) -> impl IntoResponse {
+    registry_entity(&state, &gts_id, true)
+}
+
+async fn get_instance(
+    State(state): State<AppState>,
+    ApiPath(gts_id): ApiPath<String>,
+) -> impl IntoResponse {
+    registry_entity(&state, &gts_id, false)
+}
+
+/// The content of the schema (with `schema`) or instance `gts_id`
+fn registry_entity(state: &AppState, gts_id: &str, schema: bool) -> Response {
+    let mut ops = match lock_ops(&state.ops) {
+        Ok(guard) => guard,
+        Err(response) => return response.into_response(),
+    };
+    let result = ops.get_entity(gts_id);
+    match result.content {
+        Some(content) if result.is_schema == schema => Json(content).into_response(),
+        _ => {
+            let kind = if schema { "Schema" } else { "Instance" };
+            ApiError::new(
+                ApiErrorCode::NotFound,
+                format!("{kind} '{gts_id}' not found"),
+            )
+            .with_gts_id(gts_id)
+            .into_response()
+        }
+    }
+}
+
+async fn find_schemas(
+    State(state): State<AppState>,
+    ApiQuery(params): ApiQuery<PatternQuery>,
+) -> impl IntoResponse {
+    find_entities(&state, &params, true)
+}
+
+async fn find_instances(
+    State(state): State<AppState>,
+    ApiQuery(params): ApiQuery<PatternQuery>,
+) -> impl IntoResponse {
+    find_entities(&state, &params, false)
+}
+
+/// The schemas (with `schemas`) or instances matching `params.pattern`
+fn find_entities(state: &AppState, params: &PatternQuery, schemas: bool) -> Response {
+    let ops = match lock_ops(&state.ops) {
+        Ok(guard) => guard,
+        Err(response) => return response.into_response(),
+    };
+    match ops.find_entities(&params.pattern, schemas, params.limit) {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => ApiError::from(&e).into_response(),
+    }
+}
+
+async fn validate_content(
+    State(state): State<AppState>,
+    ApiJson(body): ApiJson<Value>,
+) -> impl IntoResponse {
+    let mut ops = match lock_ops(&state.ops) {
+        Ok(guard) => guard,
+        Err(response) => return response.into_response(),
+    };
+    let result = ops.validate_content(&body);
+    if result.ok {
+        return Json(result).into_response();
+    }
+    let code = if result.id.is_empty() {
+        ApiErrorCode::InvalidEntity
+    } else if ops.store.retired_ids().get(&result.id).is_some() {
+        ApiErrorCode::RetiredId
+    } else {
+        ApiErrorCode::ValidationFailed
+    };
+    let error = ApiError::new(code, result.error);
+    if result.id.is_empty() {
+        error
+    } else {
+        error.with_gts_id(result.id)
+    }
+    .into_response()
+}
+
 async fn validate_id(
     State(state): State<AppState>,
-    Query(params): Query<GtsIdQuery>,
+    ApiQuery(params): ApiQuery<GtsIdQuery>,
 ) -> impl IntoResponse {
     let _ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -282,7 +598,10 @@ async fn validate_id(
     Json(result).into_response()
 }
 
-async fn extract_id(State(state): State<AppState>, Json(body): Json<Value>) -> impl IntoResponse {
+async fn extract_id(
+    State(state): State<AppState>,
+    ApiJson(body): ApiJson<Value>,
+) -> impl IntoResponse {
     let ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
         Err(response) => return response.into_response(),
@@ -293,7 +612,7 @@ async fn extract_id(State(state): State<AppState>, Json(body): Json<Value>) -> i
 
 async fn parse_id(
     State(state): State<AppState>,
-    Query(params): Query<GtsIdQuery>,
+    ApiQuery(params): ApiQuery<GtsIdQuery>,
 ) -> impl IntoResponse {
     let _ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -305,7 +624,7 @@ async fn parse_id(
 
 async fn match_id_pattern(
     State(state): State<AppState>,
-    Query(params): Query<MatchIdQuery>,
+    ApiQuery(params): ApiQuery<MatchIdQuery>,
 ) -> impl IntoResponse {
     let _ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -317,7 +636,7 @@ async fn match_id_pattern(
 
 async fn id_to_uuid(
     State(state): State<AppState>,
-    Query(params): Query<GtsIdQuery>,
+    ApiQuery(params): ApiQuery<GtsIdQuery>,
 ) -> impl IntoResponse {
     let _ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -329,7 +648,7 @@ async fn id_to_uuid(
 
 async fn validate_instance(
     State(state): State<AppState>,
-    Json(body): Json<ValidateInstanceRequest>,
+    ApiJson(body): ApiJson<ValidateInstanceRequest>,
 ) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -341,7 +660,7 @@ async fn validate_instance(
 
 async fn validate_schema(
     State(state): State<AppState>,
-    Json(body): Json<ValidateSchemaRequest>,
+    ApiJson(body): ApiJson<ValidateSchemaRequest>,
 ) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -353,7 +672,7 @@ async fn validate_schema(
 
 async fn validate_entity(
     State(state): State<AppState>,
-    Json(body): Json<ValidateEntityRequest>,
+    ApiJson(body): ApiJson<ValidateEntityRequest>,
 ) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -365,7 +684,7 @@ async fn validate_entity(
 
 async fn schema_graph(
     State(state): State<AppState>,
-    Query(params): Query<GtsIdQuery>,
+    ApiQuery(params): ApiQuery<GtsIdQuery>,
 ) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -375,9 +694,21 @@ async fn schema_graph(
     Json(result).into_response()
 }
 
+async fn effective_traits(
+    State(state): State<AppState>,
+    ApiQuery(params): ApiQuery<GtsIdQuery>,
+) -> impl IntoResponse {
+    let mut ops = match lock_ops(&state.ops) {
+        Ok(guard) => guard,
+        Err(response) => return response.into_response(),
+    };
+    let result = ops.effective_traits(&params.gts_id);
+    Json(result).into_response()
+}
+
 async fn compatibility(
     State(state): State<AppState>,
-    Query(params): Query<CompatibilityQuery>,
+    ApiQuery(params): ApiQuery<CompatibilityQuery>,
 ) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
@@ -387,7 +718,10 @@ async fn compatibility(
     Json(result).into_response()
 }
 
-async fn cast(State(state): State<AppState>, Json(body): Json<CastRequest>) -> impl IntoResponse {
+async fn cast(
+    State(state): State<AppState>,
+    ApiJson(body): ApiJson<CastRequest>,
+) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
         Err(response) => return response.into_response(),
@@ -398,17 +732,24 @@ async fn cast(State(state): State<AppState>, Json(body): Json<CastRequest>) -> i
 
 async fn query(
     State(state): State<AppState>,
-    Query(params): Query<QueryParams>,
+    ApiQuery(params): ApiQuery<QueryParams>,
 ) -> impl IntoResponse {
     let ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
         Err(response) => return response.into_response(),
     };
-    let result = ops.query(&params.expr, params.limit);
+    let result = if params.explain {
+        ops.query_explained(&params.expr, params.limit)
+    } else {
+        ops.query(&params.expr, params.limit)
+    };
     Json(result).into_response()
 }
 
-async fn attr(State(state): State<AppState>, Query(params): Query<AttrQuery>) -> impl IntoResponse {
+async fn attr(
+    State(state): State<AppState>,
+    ApiQuery(params): ApiQuery<AttrQuery>,
+) -> impl IntoResponse {
     let mut ops = match lock_ops(&state.ops) {
         Ok(guard) => guard,
         Err(response) => return response.into_response(),
@@ -417,6 +758,15 @@ async fn attr(State(state): State<AppState>, Query(params): Query<AttrQuery>) ->
     Json(result).into_response()
 }
 
+async fn healthz(State(state): State<AppState>) -> impl IntoResponse {
+    let ops = match lock_ops(&state.ops) {
+        Ok(guard) => guard,
+        Err(response) => return response.into_response(),
+    };
+    let result = ops.health();
+    Json(result).into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -445,6 +795,147 @@ mod tests {
         // AppState is Clone, verified by compilation
     }
 
+    #[tokio::test]
+    async fn test_background_load_swaps_in_store() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("event.json"),
+            r#"{"$id": "gts://gts.x.core.events.event.v1~", "$schema": "http://json-schema.org/draft-07/schema#"}"#,
+        )
+        .unwrap();
+        let path = vec![dir.path().to_string_lossy().to_string()];
+        let mut server =
+            GtsHttpServer::new(GtsOps::new(None, None, 0), "127.0.0.1".to_owned(), 0, 0)
+                .with_background_load(path.clone(), 2);
+        let load = server.load.take().unwrap();
+        let ops = Arc::new(Mutex::new(server.ops));
+
+        let health = ops.lock().unwrap().health();
+        assert_eq!(health.schemas, 0);
+        assert!(!health.loading.unwrap().done);
+
+        load.run(Arc::clone(&ops)).await;
+        let ops = ops.lock().unwrap();
+        let health = ops.health();
+        assert_eq!(health.schemas, 1);
+        let loading = health.loading.unwrap();
+        assert!(loading.done);
+        assert_eq!(loading.files_loaded, 1);
+        assert_eq!(ops.path, Some(path));
+    }
+
+    #[tokio::test]
+    async fn test_background_load_keeps_entities_registered_while_loading() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("event.json"),
+            r#"{"$id": "gts://gts.x.core.events.event.v1~", "$schema": "http://json-schema.org/draft-07/schema#"}"#,
+        )
+        .unwrap();
+        let path = vec![dir.path().to_string_lossy().to_string()];
+        let mut server =
+            GtsHttpServer::new(GtsOps::new(None, None, 0), "127.0.0.1".to_owned(), 0, 0)
+                .with_background_load(path, 2);
+        let load = server.load.take().unwrap();
+        let state = AppState {
+            ops: Arc::new(Mutex::new(server.ops)),
+        };
+        let app = GtsHttpServer::create_router(state.clone(), 0);
+
+        let schema = json!({
+            "$id": "gts://gts.x.core.events.posted.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#"
+        });
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/entities")
+                    .header("content-type", "application/json")
+                    .body(Body::from(schema.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        load.run(Arc::clone(&state.ops)).await;
+        assert_eq!(state.ops.lock().unwrap().health().schemas, 2);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/schemas/gts.x.core.events.posted.v1~")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_retired_ids_kept_by_background_load() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let retired_id = "gts.x.core.events.old.v1~";
+        let schema = json!({
+            "$id": format!("gts://{retired_id}"),
+            "$schema": "http://json-schema.org/draft-07/schema#"
+        });
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("old.json"), schema.to_string()).unwrap();
+        let mut ops = GtsOps::new(None, None, 0);
+        let mut retired = gts::GtsRetiredIds::new();
+        retired.insert(gts::RetiredIdEntry {
+            id: retired_id.to_owned(),
+            reason: "Split into plugins".to_owned(),
+            date: "2025-11-02".to_owned(),
+        });
+        ops.store.add_retired_ids(retired);
+        let path = vec![dir.path().to_string_lossy().to_string()];
+        let mut server =
+            GtsHttpServer::new(ops, "127.0.0.1".to_owned(), 0, 0).with_background_load(path, 2);
+        let load = server.load.take().unwrap();
+        let state = AppState {
+            ops: Arc::new(Mutex::new(server.ops)),
+        };
+        load.run(Arc::clone(&state.ops)).await;
+        let app = GtsHttpServer::create_router(state, 0);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/entities/{retired_id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/entities")
+                    .header("content-type", "application/json")
+                    .body(Body::from(schema.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
     #[test]
     fn test_gts_http_server_creation() {
         let ops = GtsOps::new(None, None, 0);
@@ -0,0 +1,302 @@
+//! Reloadable server configuration.
+//!
+//! `gts server --server-config <file>` reads the store path and GTS config
+//! from a YAML/JSON file. The file is re-read on `SIGHUP` and, with
+//! `--reload-interval`, periodically; the store is rebuilt only when one of
+//! its settings changed, and each changed field is logged. Settings missing
+//! from the file fall back to the global `--path`/`--config` flags.
+
+use anyhow::{Context, Result};
+use gts::GtsOps;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Path to json and schema files or directories
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Path to the GTS config JSON
+    #[serde(default)]
+    pub config: Option<String>,
+}
+
+/// A single changed setting between two [`ServerConfig`]s
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigChange {
+    pub field: &'static str,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl ServerConfig {
+    /// Parse a config file (YAML, or JSON which is valid YAML)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid server config
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read server config {}", path.display()))?;
+        serde_saphyr::from_str(&text)
+            .with_context(|| format!("Invalid server config {}", path.display()))
+    }
+
+    /// Fill settings unset in `self` from `base`
+    #[must_use]
+    pub fn or(self, base: &Self) -> Self {
+        Self {
+            path: self.path.or_else(|| base.path.clone()),
+            config: self.config.or_else(|| base.config.clone()),
+        }
+    }
+
+    /// Settings that differ from `previous`, in declaration order
+    #[must_use]
+    pub fn diff(&self, previous: &Self) -> Vec<ConfigChange> {
+        [
+            ("path", &previous.path, &self.path),
+            ("config", &previous.config, &self.config),
+        ]
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| ConfigChange {
+            field,
+            old: old.clone(),
+            new: new.clone(),
+        })
+        .collect()
+    }
+
+    /// Build the operations/store for these settings
+    #[must_use]
+    pub fn build_ops(&self, verbose: u8) -> GtsOps {
+        GtsOps::new(
+            self.path.clone().map(|p| vec![p]),
+            self.config.clone(),
+            verbose as usize,
+        )
+    }
+}
+
+/// Re-reads the server config file and swaps in a rebuilt store on change
+pub struct ConfigReloader {
+    file: PathBuf,
+    fallback: ServerConfig,
+    current: ServerConfig,
+    ops: Arc<Mutex<GtsOps>>,
+    verbose: u8,
+}
+
+impl ConfigReloader {
+    /// `current` is the effective config the running `ops` were built from
+    #[must_use]
+    pub fn new(
+        file: PathBuf,
+        fallback: ServerConfig,
+        current: ServerConfig,
+        ops: Arc<Mutex<GtsOps>>,
+        verbose: u8,
+    ) -> Self {
+        Self {
+            file,
+            fallback,
+            current,
+            ops,
+            verbose,
+        }
+    }
+
+    /// Re-read the config file and apply it, returning the changed settings
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be loaded; the running config is kept
+    pub fn reload(&mut self) -> Result<Vec<ConfigChange>> {
+        let next = ServerConfig::load(&self.file)?.or(&self.fallback);
+        let changes = next.diff(&self.current);
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+
+        // Build outside the lock so requests are served from the old store meanwhile
+        let mut ops = next.build_ops(self.verbose);
+        let mut running = self
+            .ops
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to lock ops: {e}"))?;
+        // Redaction, keyword handlers, migrations and configured retired IDs
+        // don't come from the reloaded file
+        ops.redaction = std::mem::take(&mut running.redaction);
+        ops.store
+            .set_keyword_handlers(running.store.keyword_handlers().clone());
+        ops.store.set_migrations(running.store.migrations().clone());
+        ops.store
+            .add_retired_ids(running.store.configured_retired_ids().clone());
+        *running = ops;
+        drop(running);
+        self.current = next;
+        Ok(changes)
+    }
+
+    /// Reload on `SIGHUP` (Unix) and every `poll` interval, logging each change
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signal handler cannot be installed
+    pub async fn run(mut self, poll: Option<Duration>) -> Result<()> {
+        #[cfg(unix)]
+        let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+
+        loop {
+            #[cfg(unix)]
+            let signal = async {
+                if hangup.recv().await.is_none() {
+                    std::future::pending::<()>().await;
+                }
+            };
+            #[cfg(not(unix))]
+            let signal = std::future::pending::<()>();
+            let tick = async {
+                match poll {
+                    Some(interval) => tokio::time::sleep(interval).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                () = signal => {}
+                () = tick => {}
+            }
+
+            match self.reload() {
+                Ok(changes) => log_changes(&changes),
+                Err(e) => tracing::warn!("Keeping current server config: {e:#}"),
+            }
+        }
+    }
+}
+
+fn log_changes(changes: &[ConfigChange]) {
+    for change in changes {
+        tracing::info!(
+            field = change.field,
+            old = change.old.as_deref().unwrap_or("<unset>"),
+            new = change.new.as_deref().unwrap_or("<unset>"),
+            "server config changed"
+        );
+    }
+    if !changes.is_empty() {
+        tracing::info!("store reloaded");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_and_fallback() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("server.yaml");
+        fs::write(&file, "path: ./store\n").unwrap();
+
+        let base = ServerConfig {
+            path: Some("ignored".to_owned()),
+            config: Some("gts.config.json".to_owned()),
+        };
+        let cfg = ServerConfig::load(&file).unwrap().or(&base);
+        assert_eq!(cfg.path.as_deref(), Some("./store"));
+        assert_eq!(cfg.config.as_deref(), Some("gts.config.json"));
+
+        fs::write(&file, "store: ./store\n").unwrap();
+        assert!(ServerConfig::load(&file).is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = ServerConfig {
+            path: Some("a".to_owned()),
+            config: None,
+        };
+        let new = ServerConfig {
+            path: Some("b".to_owned()),
+            config: None,
+        };
+        assert_eq!(
+            new.diff(&old),
+            vec![ConfigChange {
+                field: "path",
+                old: Some("a".to_owned()),
+                new: Some("b".to_owned()),
+            }]
+        );
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_reload_swaps_store() {
+        let dir = TempDir::new().unwrap();
+        let store = dir.path().join("store");
+        fs::create_dir(&store).unwrap();
+        fs::write(
+            store.join("type.schema.json"),
+            serde_json::json!({
+                "$id": "gts://gts.x.core.events.type.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object"
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(
+            store.join("old.schema.json"),
+            serde_json::json!({
+                "$id": "gts://gts.x.core.events.old.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object"
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let file = dir.path().join("server.yaml");
+        fs::write(&file, "{}\n").unwrap();
+        let current = ServerConfig::default();
+        let mut running = current.build_ops(0);
+        let mut retired = gts::GtsRetiredIds::new();
+        retired.insert(gts::RetiredIdEntry {
+            id: "gts.x.core.events.old.v1~".to_owned(),
+            reason: "Replaced by type".to_owned(),
+            date: "2025-11-02".to_owned(),
+        });
+        running.store.add_retired_ids(retired);
+        let ops = Arc::new(Mutex::new(running));
+        let mut reloader = ConfigReloader::new(
+            file.clone(),
+            ServerConfig::default(),
+            current,
+            Arc::clone(&ops),
+            0,
+        );
+
+        assert!(reloader.reload().unwrap().is_empty());
+        assert_eq!(ops.lock().unwrap().health().schemas, 0);
+
+        fs::write(&file, format!("path: {}\n", store.display())).unwrap();
+        let changes = reloader.reload().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "path");
+        // The configured retired IDs carry over to the rebuilt store
+        assert_eq!(ops.lock().unwrap().health().schemas, 1);
+
+        // A broken file keeps the running config
+        fs::write(&file, "path: [\n").unwrap();
+        assert!(reloader.reload().is_err());
+        assert_eq!(ops.lock().unwrap().health().schemas, 1);
+    }
+}
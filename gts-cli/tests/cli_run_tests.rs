@@ -9,6 +9,8 @@ async fn test_run_validate_id_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::ValidateId {
             gts_id: "test:schema:v1".to_owned(),
         },
@@ -27,6 +29,8 @@ async fn test_run_parse_id_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::ParseId {
             gts_id: "test:schema:v1".to_owned(),
         },
@@ -42,6 +46,8 @@ async fn test_run_match_id_pattern_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::MatchIdPattern {
             pattern: "test:*:v1".to_owned(),
             candidate: "test:schema:v1".to_owned(),
@@ -58,6 +64,8 @@ async fn test_run_uuid_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::Uuid {
             gts_id: "test:schema:v1".to_owned(),
             scope: "major".to_owned(),
@@ -74,6 +82,8 @@ async fn test_run_validate_instance_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::ValidateInstance {
             gts_id: "test:instance:v1".to_owned(),
         },
@@ -91,6 +101,8 @@ async fn test_run_resolve_relationships_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::ResolveRelationships {
             gts_id: "test:schema:v1".to_owned(),
         },
@@ -106,9 +118,12 @@ async fn test_run_compatibility_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::Compatibility {
             old_schema_id: "test:schema:v1".to_owned(),
             new_schema_id: "test:schema:v2".to_owned(),
+            report: None,
         },
     };
 
@@ -122,9 +137,12 @@ async fn test_run_cast_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::Cast {
             from_id: "test:instance:v1".to_owned(),
             to_schema_id: "test:schema:v2".to_owned(),
+            report: None,
         },
     };
 
@@ -132,15 +150,42 @@ async fn test_run_cast_command() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_run_cast_command_writes_report() -> Result<()> {
+    let dir = TempDir::new()?;
+    let report = dir.path().join("cast.html");
+    let cli = Cli {
+        verbose: 0,
+        config: None,
+        path: None,
+        project_config: None,
+        file_name_encoding: None,
+        command: Commands::Cast {
+            from_id: "test:instance:v1".to_owned(),
+            to_schema_id: "test:schema:v2".to_owned(),
+            report: Some(report.to_string_lossy().into_owned()),
+        },
+    };
+
+    run_with_cli(cli).await?;
+    let html = std::fs::read_to_string(&report)?;
+    assert!(html.contains("<h1>GTS cast report</h1>"));
+    assert!(html.contains("<tr><td>failed</td><td>1</td></tr>"));
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_run_query_command() -> Result<()> {
     let cli = Cli {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::Query {
             expr: "test:*".to_owned(),
             limit: 10,
+            explain: false,
         },
     };
 
@@ -154,6 +199,8 @@ async fn test_run_attr_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::Attr {
             gts_with_path: "test:instance:v1@field.nested".to_owned(),
         },
@@ -169,6 +216,8 @@ async fn test_run_list_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::List { limit: 50 },
     };
 
@@ -185,6 +234,8 @@ async fn test_run_openapi_spec_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::OpenapiSpec {
             out: output_path.to_str().unwrap().to_owned(),
             host: "127.0.0.1".to_owned(),
@@ -216,10 +267,16 @@ async fn test_run_generate_from_rust_command() -> Result<()> {
         verbose: 0,
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::GenerateFromRust {
             source: source_path.to_str().unwrap().to_owned(),
             output: Some(output_path.to_str().unwrap().to_owned()),
             exclude: vec![],
+            only: vec![],
+            skip_pattern: vec![],
+            progress: None,
+            check: false,
         },
     };
 
@@ -234,6 +291,8 @@ async fn test_run_with_verbose_flag() -> Result<()> {
         verbose: 2, // DEBUG level
         config: None,
         path: None,
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::ValidateId {
             gts_id: "test:schema:v1".to_owned(),
         },
@@ -256,6 +315,8 @@ async fn test_run_with_config_and_path() -> Result<()> {
         verbose: 0,
         config: Some(config_path.to_str().unwrap().to_owned()),
         path: Some(data_path.to_str().unwrap().to_owned()),
+        project_config: None,
+        file_name_encoding: None,
         command: Commands::List { limit: 100 },
     };
 
@@ -268,3 +329,406 @@ async fn test_run_with_config_and_path() -> Result<()> {
 // - Spawn it in a background task with a timeout
 // - Make HTTP requests to verify it's responding
 // - Gracefully shutdown the server
+
+#[tokio::test]
+async fn test_run_with_project_config() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let store = temp_dir.path().join("store");
+    fs::create_dir(&store)?;
+    let project = temp_dir.path().join("gts.toml");
+    fs::write(&project, "path = \"store\"\n")?;
+
+    let cli = Cli {
+        verbose: 0,
+        config: None,
+        path: None,
+        project_config: Some(project.to_string_lossy().into_owned()),
+        file_name_encoding: None,
+        command: Commands::List { limit: 10 },
+    };
+    run_with_cli(cli).await?;
+
+    fs::write(&project, "unknown_key = 1\n")?;
+    let cli = Cli {
+        verbose: 0,
+        config: None,
+        path: None,
+        project_config: Some(project.to_string_lossy().into_owned()),
+        file_name_encoding: None,
+        command: Commands::List { limit: 10 },
+    };
+    let err = run_with_cli(cli).await.unwrap_err();
+    assert!(err.to_string().contains("Invalid project config"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_validate_command() -> Result<()> {
+    use clap::Parser;
+
+    let temp_dir = TempDir::new()?;
+    let docs = temp_dir.path().join("docs");
+    fs::create_dir(&docs)?;
+    fs::write(docs.join("a.md"), "Uses `gts.x.core.pkg.mytype.v1~`.\n")?;
+    let docs = docs.to_str().unwrap().to_owned();
+
+    let cli = Cli::try_parse_from([
+        "gts",
+        "validate",
+        &docs,
+        "--allow-vendors",
+        "x,y",
+        "--progress",
+        "json",
+    ])?;
+    run_with_cli(cli).await?;
+
+    let cli = Cli::try_parse_from(["gts", "validate", &docs, "--vendor", "z"])?;
+    let error = run_with_cli(cli).await.unwrap_err();
+    assert!(error.to_string().contains("1 validation error"), "{error}");
+
+    let cli = Cli::try_parse_from(["gts", "validate", &docs, "--format", "sarif", "-e", "a.md"])?;
+    run_with_cli(cli).await?;
+
+    let cli = Cli::try_parse_from(["gts", "validate", &docs, "--list-vendors"])?;
+    run_with_cli(cli).await?;
+    assert!(
+        Cli::try_parse_from(["gts", "validate", &docs, "--list-vendors", "--vendor", "x"]).is_err()
+    );
+
+    // Paths and vendor from `[validator]` of the project config, flags win
+    let project = temp_dir.path().join("gts.toml");
+    fs::write(
+        &project,
+        "[validator]\npaths = [\"docs\"]\nvendor = \"z\"\n",
+    )?;
+    let project = project.to_str().unwrap();
+    let cli = Cli::try_parse_from(["gts", "--project-config", project, "validate"])?;
+    let error = run_with_cli(cli).await.unwrap_err();
+    assert!(error.to_string().contains("1 validation error"), "{error}");
+    let cli = Cli::try_parse_from([
+        "gts",
+        "--project-config",
+        project,
+        "validate",
+        "--vendor",
+        "x",
+    ])?;
+    run_with_cli(cli).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_lint_command() -> Result<()> {
+    use clap::Parser;
+
+    let temp_dir = TempDir::new()?;
+    let docs = temp_dir.path().join("docs");
+    fs::create_dir(&docs)?;
+    fs::write(docs.join("a.md"), "Uses `gts.oldco.core.pkg.mytype.v1~`.\n")?;
+    let docs = docs.to_str().unwrap().to_owned();
+
+    let cli = Cli::try_parse_from(["gts", "lint", &docs])?;
+    run_with_cli(cli).await?;
+
+    // Style rules only warn unless warnings are denied
+    let args = [
+        "gts",
+        "lint",
+        &docs,
+        "--deprecated-vendor",
+        "oldco=newco",
+        "--require-minor",
+    ];
+    let cli = Cli::try_parse_from(args)?;
+    run_with_cli(cli).await?;
+    let cli = Cli::try_parse_from(args.iter().chain(&["--deny-warnings", "--format", "json"]))?;
+    let error = run_with_cli(cli).await.unwrap_err();
+    assert!(error.to_string().contains("2 warning(s)"), "{error}");
+
+    let cli = Cli::try_parse_from(["gts", "lint", &docs, "--vendor", "newco"])?;
+    let error = run_with_cli(cli).await.unwrap_err();
+    assert!(error.to_string().contains("1 error(s)"), "{error}");
+
+    assert!(Cli::try_parse_from(["gts", "lint", &docs, "--deprecated-vendor", "oldco"]).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_replay_command() -> Result<()> {
+    use clap::Parser;
+
+    let temp_dir = TempDir::new()?;
+    let schema = temp_dir.path().join("new.schema.json");
+    fs::write(
+        &schema,
+        r#"{
+            "$id": "gts://gts.x.core.events.order.v1.1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "required": ["id", "amount"]
+        }"#,
+    )?;
+    let corpus = temp_dir.path().join("corpus");
+    fs::create_dir(&corpus)?;
+    fs::write(
+        corpus.join("orders.jsonl"),
+        "{\"id\": \"o1\", \"amount\": 1}\n{\"id\": \"o2\"}\n",
+    )?;
+    let report = temp_dir.path().join("replay.md");
+    let args = |extra: &[&str]| {
+        let mut args = vec![
+            "gts".to_owned(),
+            "replay".to_owned(),
+            "--schema".to_owned(),
+            schema.to_string_lossy().into_owned(),
+            "--data".to_owned(),
+            corpus.to_string_lossy().into_owned(),
+        ];
+        args.extend(extra.iter().map(|&arg| arg.to_owned()));
+        args
+    };
+
+    let cli = Cli::try_parse_from(args(&["--report", report.to_str().unwrap()]))?;
+    run_with_cli(cli).await?;
+    let markdown = fs::read_to_string(&report)?;
+    assert!(markdown.contains("| failed | 1 (50%) |"), "{markdown}");
+
+    let cli = Cli::try_parse_from(args(&["--max-failure-rate", "10"]))?;
+    let error = run_with_cli(cli).await.unwrap_err();
+    assert!(error.to_string().contains("Failure rate 50%"), "{error}");
+
+    // A bare --report prints Markdown
+    let cli = Cli::try_parse_from(args(&["--report"]))?;
+    run_with_cli(cli).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_diff_command() -> Result<()> {
+    use clap::Parser;
+
+    let temp_dir = TempDir::new()?;
+    let old = temp_dir.path().join("old.schema.json");
+    let new = temp_dir.path().join("new.schema.json");
+    fs::write(
+        &old,
+        r#"{"type": "object", "properties": {"id": {"type": "string"}}}"#,
+    )?;
+    fs::write(
+        &new,
+        r#"{"type": "object", "properties": {"id": {"type": "string"}}, "required": ["id"]}"#,
+    )?;
+    let cli = Cli::try_parse_from(["gts", "diff", old.to_str().unwrap(), new.to_str().unwrap()])?;
+    run_with_cli(cli).await?;
+
+    let report = temp_dir.path().join("diff.md");
+    let cli = Cli::try_parse_from([
+        "gts",
+        "diff",
+        old.to_str().unwrap(),
+        new.to_str().unwrap(),
+        "--report",
+        report.to_str().unwrap(),
+    ])?;
+    run_with_cli(cli).await?;
+    let report = fs::read_to_string(report)?;
+    assert!(report.contains("- Level: **major**"), "{report}");
+    assert!(report.contains("## Newly required\n\n- `id`"), "{report}");
+
+    // Neither a file nor a schema in the (empty) store
+    let cli = Cli::try_parse_from([
+        "gts",
+        "diff",
+        old.to_str().unwrap(),
+        "gts.x.core.events.type.v1~",
+    ])?;
+    assert!(run_with_cli(cli).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_compat_check_command() -> Result<()> {
+    use clap::Parser;
+
+    let temp_dir = TempDir::new()?;
+    let schema = |id: &str, required: &str| {
+        format!(
+            r#"{{
+                "$id": "gts://{id}",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "properties": {{"id": {{"type": "string"}}, "name": {{"type": "string"}}}},
+                "required": {required}
+            }}"#
+        )
+    };
+    let old = temp_dir.path().join("old");
+    let new = temp_dir.path().join("new");
+    fs::create_dir(&old)?;
+    fs::create_dir(&new)?;
+    for dir in [&old, &new] {
+        fs::write(
+            dir.join("gts.x.app.entities.user.v1~.schema.json"),
+            schema("gts.x.app.entities.user.v1~", r#"["id"]"#),
+        )?;
+    }
+    let args = || {
+        [
+            "gts".to_owned(),
+            "compat-check".to_owned(),
+            "--dir".to_owned(),
+            new.to_string_lossy().into_owned(),
+            "--against".to_owned(),
+            old.to_string_lossy().into_owned(),
+        ]
+    };
+
+    // A minor version that only adds an optional property is compatible
+    fs::write(
+        new.join("gts.x.app.entities.user.v1.1~.schema.json"),
+        schema("gts.x.app.entities.user.v1.1~", r#"["id"]"#),
+    )?;
+    run_with_cli(Cli::try_parse_from(args())?).await?;
+
+    // A minor version that requires more is not
+    fs::write(
+        new.join("gts.x.app.entities.user.v1.1~.schema.json"),
+        schema("gts.x.app.entities.user.v1.1~", r#"["id", "name"]"#),
+    )?;
+    let error = run_with_cli(Cli::try_parse_from(args())?)
+        .await
+        .unwrap_err();
+    let message = error.to_string();
+    assert!(
+        message.starts_with("1 incompatible schema evolution(s)"),
+        "{message}"
+    );
+    assert!(
+        message.contains("gts.x.app.entities.user.v1~ -> gts.x.app.entities.user.v1.1~"),
+        "{message}"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_clean_command() -> Result<()> {
+    use clap::Parser;
+
+    let temp_dir = TempDir::new()?;
+    let src = temp_dir.path().join("src");
+    fs::create_dir_all(&src)?;
+    fs::write(
+        src.join("lib.rs"),
+        r#"
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.app.entities.user.v1~",
+    description = "User"
+)]
+pub struct UserV1 {
+    pub id: GtsInstanceId,
+}
+"#,
+    )?;
+    // Generated files must stay within the source
+    let src = temp_dir.path().to_string_lossy();
+    let out = temp_dir.path().join("out").to_string_lossy().into_owned();
+    let generate = Cli::try_parse_from([
+        "gts",
+        "generate-from-rust",
+        "--source",
+        &src,
+        "--output",
+        &out,
+    ])?;
+    run_with_cli(generate).await?;
+
+    // A schema of a struct since renamed, and a hand-written file
+    let schemas = temp_dir.path().join("out/schemas");
+    let kept = schemas.join("gts.x.app.entities.user.v1~.schema.json");
+    let orphan = schemas.join("gts.x.app.entities.account.v1~.schema.json");
+    let manual = schemas.join("notes.schema.json");
+    fs::write(
+        &orphan,
+        r#"{"$id": "gts://gts.x.app.entities.account.v1~", "type": "object"}"#,
+    )?;
+    fs::write(&manual, r#"{"type": "object"}"#)?;
+
+    let clean = |dry_run: bool| {
+        let mut args = vec!["gts", "clean", "--source", &src, "--output", &out];
+        if dry_run {
+            args.push("--dry-run");
+        }
+        Cli::try_parse_from(args)
+    };
+    run_with_cli(clean(true)?).await?;
+    assert!(orphan.exists());
+    run_with_cli(clean(false)?).await?;
+    assert!(!orphan.exists());
+    assert!(kept.exists());
+    assert!(manual.exists());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_check_collisions_command() -> Result<()> {
+    use clap::Parser;
+
+    let temp_dir = TempDir::new()?;
+    let source = |name: &str, description: &str| {
+        format!(
+            r#"
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.app.entities.user.v1~",
+    description = "{description}"
+)]
+pub struct {name} {{
+    pub id: GtsInstanceId,
+}}
+"#
+        )
+    };
+    for (krate, description) in [("a", "User"), ("b", "User")] {
+        let src = temp_dir.path().join(krate).join("src");
+        fs::create_dir_all(&src)?;
+        fs::write(
+            temp_dir.path().join(krate).join("Cargo.toml"),
+            format!("[package]\nname = \"{krate}\"\n"),
+        )?;
+        fs::write(src.join("lib.rs"), source("UserV1", description))?;
+    }
+    let workspace = temp_dir.path().to_string_lossy().into_owned();
+    let check = || Cli::try_parse_from(["gts", "check-collisions", "--source", &workspace]);
+
+    let message = run_with_cli(check()?).await.unwrap_err().to_string();
+    assert!(
+        message.starts_with("1 schema ID(s) emitted by more than one crate"),
+        "{message}"
+    );
+    assert!(
+        message.contains("gts.x.app.entities.user.v1~ (same content)"),
+        "{message}"
+    );
+
+    // Generation refuses to write the file twice, naming both crates
+    fs::write(
+        temp_dir.path().join("b/src/lib.rs"),
+        source("UserV1", "Another user"),
+    )?;
+    let generate = Cli::try_parse_from(["gts", "generate-from-rust", "--source", &workspace])?;
+    let message = run_with_cli(generate).await.unwrap_err().to_string();
+    assert!(message.contains("(diverging content)"), "{message}");
+    for krate in ["a", "b"] {
+        let crate_dir = temp_dir.path().join(krate).display().to_string();
+        assert!(message.contains(&crate_dir), "{message}");
+    }
+
+    // IDs of a single crate do not collide
+    fs::remove_dir_all(temp_dir.path().join("b"))?;
+    run_with_cli(check()?).await?;
+    Ok(())
+}
@@ -0,0 +1,175 @@
+#![cfg(feature = "grpc")]
+
+use gts::GtsOps;
+use gts_cli::grpc::proto::gts_service_client::GtsServiceClient;
+use gts_cli::grpc::proto::{
+    EffectiveTraitsRequest, GetSchemaRequest, QueryRequest, ValidateInstanceRequest,
+};
+use gts_cli::server::AppState;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tonic::transport::Channel;
+
+fn create_test_ops() -> GtsOps {
+    let mut ops = GtsOps::new(None, None, 0);
+    let base = json!({
+        "$id": "gts://gts.x.core.events.type.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "x-gts-owner": "platform-events",
+        "properties": {"id": {"type": "string"}, "name": {"type": "string"}},
+        "required": ["id", "name"],
+        "x-gts-traits-schema": {
+            "type": "object",
+            "properties": {"retention": {"type": "string", "default": "P30D"}}
+        }
+    });
+    let derived = json!({
+        "$id": "gts://gts.x.core.events.type.v1~x.app._.orders.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "allOf": [
+            {"$ref": "gts://gts.x.core.events.type.v1~"},
+            {"type": "object", "x-gts-traits": {"retention": "P90D"}}
+        ]
+    });
+    assert!(
+        ops.add_schema("gts.x.core.events.type.v1~".to_owned(), &base)
+            .ok
+    );
+    assert!(
+        ops.add_schema(
+            "gts.x.core.events.type.v1~x.app._.orders.v1~".to_owned(),
+            &derived
+        )
+        .ok
+    );
+    for (id, name) in [
+        (
+            "gts.x.core.events.type.v1~x.app._.created.v1",
+            json!("created"),
+        ),
+        ("gts.x.core.events.type.v1~x.app._.broken.v1", json!(7)),
+    ] {
+        let result = ops.add_entity(&json!({"id": id, "name": name}), false);
+        assert!(result.ok, "{}", result.error);
+    }
+    ops
+}
+
+async fn connect() -> anyhow::Result<GtsServiceClient<Channel>> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let state = AppState {
+        ops: Arc::new(Mutex::new(create_test_ops())),
+    };
+    tokio::spawn(gts_cli::grpc::serve(state, listener));
+    Ok(GtsServiceClient::connect(format!("http://{addr}")).await?)
+}
+
+#[tokio::test]
+async fn test_grpc_get_schema() {
+    let mut client = connect().await.unwrap();
+
+    let response = client
+        .get_schema(GetSchemaRequest {
+            gts_id: "gts.x.core.events.type.v1~".to_owned(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.ok, "{}", response.error);
+    assert_eq!(response.owner.as_deref(), Some("platform-events"));
+    let content: Value = serde_json::from_str(&response.content_json).unwrap();
+    assert_eq!(content["required"], json!(["id", "name"]));
+
+    let response = client
+        .get_schema(GetSchemaRequest {
+            gts_id: "gts.x.core.events.type.v1~x.app._.created.v1".to_owned(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.ok);
+    assert!(response.error.contains("not a schema"));
+
+    let response = client
+        .get_schema(GetSchemaRequest {
+            gts_id: "gts.x.core.events.type.v9~".to_owned(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.ok);
+    assert!(!response.error.is_empty());
+}
+
+#[tokio::test]
+async fn test_grpc_query() {
+    let mut client = connect().await.unwrap();
+
+    let response = client
+        .query(QueryRequest {
+            expr: "gts.x.core.events.type.v1~x.app.*".to_owned(),
+            limit: 0,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.error.is_empty(), "{}", response.error);
+    assert_eq!(response.limit, 100);
+    assert_eq!(response.count, 3);
+
+    let response = client
+        .query(QueryRequest {
+            expr: "gts.x.core.events.type.v1~x.app.*".to_owned(),
+            limit: 1,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.results_json.len(), 1);
+    let result: Value = serde_json::from_str(&response.results_json[0]).unwrap();
+    assert!(result.is_object());
+}
+
+#[tokio::test]
+async fn test_grpc_validate_instance() {
+    let mut client = connect().await.unwrap();
+
+    let response = client
+        .validate_instance(ValidateInstanceRequest {
+            instance_id: "gts.x.core.events.type.v1~x.app._.created.v1".to_owned(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.ok, "{}", response.error);
+
+    let response = client
+        .validate_instance(ValidateInstanceRequest {
+            instance_id: "gts.x.core.events.type.v1~x.app._.broken.v1".to_owned(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.ok);
+    assert!(!response.error.is_empty());
+}
+
+#[tokio::test]
+async fn test_grpc_effective_traits() {
+    let mut client = connect().await.unwrap();
+
+    let response = client
+        .effective_traits(EffectiveTraitsRequest {
+            schema_id: "gts.x.core.events.type.v1~x.app._.orders.v1~".to_owned(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.ok, "{}", response.error);
+    let traits: Value = serde_json::from_str(&response.traits_json).unwrap();
+    assert_eq!(traits["retention"], "P90D");
+}
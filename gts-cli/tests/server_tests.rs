@@ -305,15 +305,227 @@ async fn test_get_entity_success() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_get_schema_renders_owner_and_changelog() {
+    let mut ops = create_test_ops();
+    let schema = serde_json::json!({
+        "$id": "gts://gts.x.core.events.type.v2~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "x-gts-owner": "platform-events",
+        "x-gts-changelog": [{"version": "v2", "changes": ["Add tenant_id"]}]
+    });
+    assert!(
+        ops.add_schema("gts.x.core.events.type.v2~".to_owned(), &schema)
+            .ok
+    );
+    let app = create_test_router(ops, 0);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/entities/gts.x.core.events.type.v2~")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = json_body(response).await;
+    assert_eq!(body["metadata"]["owner"], "platform-events");
+    assert_eq!(
+        body["metadata"]["changelog"][0]["changes"][0],
+        "Add tenant_id"
+    );
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/entities")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = json_body(response).await;
+    assert_eq!(body["entities"][0]["owner"], "platform-events");
+}
+
+fn traits_ops() -> GtsOps {
+    let mut ops = create_test_ops();
+    let base = serde_json::json!({
+        "$id": "gts://gts.x.core.events.type.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "x-gts-traits-schema": {
+            "type": "object",
+            "properties": {"retention": {"type": "string", "default": "P30D"}}
+        }
+    });
+    let derived = serde_json::json!({
+        "$id": "gts://gts.x.core.events.type.v1~x.app._.orders.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "allOf": [
+            {"$ref": "gts://gts.x.core.events.type.v1~"},
+            {"type": "object", "x-gts-traits": {"retention": "P90D"}}
+        ]
+    });
+    assert!(
+        ops.add_schema("gts.x.core.events.type.v1~".to_owned(), &base)
+            .ok
+    );
+    assert!(
+        ops.add_schema(
+            "gts.x.core.events.type.v1~x.app._.orders.v1~".to_owned(),
+            &derived
+        )
+        .ok
+    );
+    ops
+}
+
+#[tokio::test]
+async fn test_effective_traits_endpoint() {
+    let app = create_test_router(traits_ops(), 0);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/effective-traits?gts_id=gts.x.core.events.type.v1~x.app._.orders.v1~")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = json_body(response).await;
+    assert_eq!(body["ok"], true);
+    assert_eq!(body["traits"]["retention"], "P90D");
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/effective-traits?gts_id=gts.x.core.events.type.v1~x.app._.missing.v1~")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = json_body(response).await;
+    assert_eq!(body["ok"], false);
+    assert!(body["error"].is_string());
+}
+
 #[tokio::test]
 async fn test_get_entity_not_found() {
     let ops = create_test_ops();
     let app = create_test_router(ops, 0);
 
+    let (status, body) = send(
+        &app,
+        get("/entities/gts.x.core.events.type.v1~x.app._.missing.v1"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "not_found");
+    assert_eq!(
+        body["gts_id"],
+        "gts.x.core.events.type.v1~x.app._.missing.v1"
+    );
+
+    let (status, body) = send(&app, get("/entities/notanid")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_gts_id");
+}
+
+#[allow(clippy::unwrap_used)]
+async fn json_body(response: axum::response::Response) -> serde_json::Value {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn test_malformed_body_returns_error_envelope() {
+    let app = create_test_router(create_test_ops(), 0);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/cast")
+                .header("content-type", "application/json")
+                .body(Body::from("{\"instance_id\": 1}"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = json_body(response).await;
+    assert_eq!(body["code"], "bad_request");
+    assert!(body["message"].is_string());
+    assert_eq!(body["details"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_missing_query_param_returns_error_envelope() {
+    let app = create_test_router(create_test_ops(), 0);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/validate-id")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(json_body(response).await["code"], "bad_request");
+}
+
+#[tokio::test]
+async fn test_unknown_route_returns_error_envelope() {
+    let app = create_test_router(create_test_ops(), 0);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/no-such-endpoint")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = json_body(response).await;
+    assert_eq!(body["code"], "not_found");
+    assert_eq!(body["message"], "No route for '/no-such-endpoint'");
+}
+
+#[tokio::test]
+async fn test_healthz_endpoint() {
+    let mut ops = create_test_ops();
+    ops.add_schema(
+        "gts.x.core.events.event.v1~".to_owned(),
+        &serde_json::json!({
+            "$id": "gts://gts.x.core.events.event.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object"
+        }),
+    );
+    let app = create_test_router(ops, 0);
+
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/entities/gts:gts.test.foo.v1:nonexistent")
+                .uri("/healthz")
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -321,6 +533,11 @@ async fn test_get_entity_not_found() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::OK);
+    let body = json_body(response).await;
+    assert_eq!(body["schemas"], 1);
+    assert_eq!(body["instances"], 0);
+    assert_eq!(body["vendors"], 1);
+    assert_eq!(body["load_errors"], serde_json::json!([]));
 }
 
 #[tokio::test]
@@ -516,3 +733,104 @@ async fn test_attr_endpoint() {
 
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+#[allow(clippy::unwrap_used)]
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[allow(clippy::unwrap_used)]
+fn get(uri: &str) -> Request<Body> {
+    Request::builder().uri(uri).body(Body::empty()).unwrap()
+}
+
+#[allow(clippy::unwrap_used)]
+fn post_json(uri: &str, body: &serde_json::Value) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_registry_endpoints() {
+    let mut ops = create_test_ops();
+    let schema = serde_json::json!({
+        "$id": "gts://gts.x.core.events.type.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "required": ["id", "type", "name"],
+        "properties": {
+            "id": {"type": "string"},
+            "type": {"type": "string"},
+            "name": {"type": "string"}
+        }
+    });
+    assert!(ops.add_entity(&schema, false).ok);
+    let instance = serde_json::json!({
+        "id": "gts.x.core.events.type.v1~x.app._.orders.v1",
+        "type": "gts.x.core.events.type.v1~",
+        "name": "orders"
+    });
+    assert!(ops.add_entity(&instance, true).ok);
+    let app = create_test_router(ops, 0);
+
+    let (status, body) = send(&app, get("/schemas/gts.x.core.events.type.v1~")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, schema);
+    let (status, body) = send(
+        &app,
+        get("/instances/gts.x.core.events.type.v1~x.app._.orders.v1"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["name"], "orders");
+    let (status, body) = send(&app, get("/instances/gts.x.core.events.type.v1~")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "not_found");
+
+    let (status, body) = send(&app, get("/schemas?pattern=gts.x.core.*")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["entities"][0]["id"], "gts.x.core.events.type.v1~");
+    let (_, body) = send(&app, get("/instances")).await;
+    assert_eq!(
+        body["entities"][0]["id"],
+        "gts.x.core.events.type.v1~x.app._.orders.v1"
+    );
+    let (status, body) = send(&app, get("/schemas?pattern=gts.x.*.bad*")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_pattern");
+
+    let submitted = serde_json::json!({
+        "id": "gts.x.core.events.type.v1~x.app._.refunds.v1",
+        "type": "gts.x.core.events.type.v1~"
+    });
+    let (status, body) = send(&app, post_json("/validate", &submitted)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "validation_failed");
+    assert_eq!(
+        body["gts_id"],
+        "gts.x.core.events.type.v1~x.app._.refunds.v1"
+    );
+    assert!(body["message"].as_str().unwrap().contains("name"), "{body}");
+    // Validation does not register the entity
+    let (status, _) = send(
+        &app,
+        get("/instances/gts.x.core.events.type.v1~x.app._.refunds.v1"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let mut submitted = submitted;
+    submitted["name"] = "refunds".into();
+    let (_, body) = send(&app, post_json("/validate", &submitted)).await;
+    assert_eq!(body["ok"], true, "{body}");
+}
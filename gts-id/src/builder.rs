@@ -0,0 +1,292 @@
+//! Assembling GTS identifiers from their individual fields.
+//!
+//! [`GtsIdBuilder`] validates every field on its own before joining them, so
+//! a failure is reported as a [`GtsIdFieldError`] naming the segment, the
+//! field and the violated constraint rather than as a message about the
+//! assembled string.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::{GTS_MAX_LENGTH, GTS_PREFIX, GtsIdError, is_valid_segment_token, validate_gts_id};
+
+/// A field of a GTS segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GtsIdField {
+    Vendor,
+    Package,
+    Namespace,
+    TypeName,
+    Version,
+}
+
+impl fmt::Display for GtsIdField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Vendor => "vendor",
+            Self::Package => "package",
+            Self::Namespace => "namespace",
+            Self::TypeName => "type",
+            Self::Version => "version",
+        })
+    }
+}
+
+/// Errors from [`GtsIdBuilder::build`].
+#[derive(Debug, Error)]
+pub enum GtsIdFieldError {
+    /// The builder has no segments.
+    #[error("At least one segment is required")]
+    NoSegments,
+
+    /// A required field is empty or unset.
+    #[error("Segment #{segment}: {field} is required")]
+    Missing {
+        /// 1-based segment number.
+        segment: usize,
+        field: GtsIdField,
+    },
+
+    /// A name token does not match `[a-z_][a-z0-9_]*`.
+    #[error(
+        "Segment #{segment}: invalid {field} '{value}'. Must start with [a-z_] and contain only [a-z0-9_]"
+    )]
+    InvalidToken {
+        /// 1-based segment number.
+        segment: usize,
+        field: GtsIdField,
+        value: String,
+    },
+
+    /// The assembled identifier exceeds [`GTS_MAX_LENGTH`].
+    #[error("GTS ID too long ({length} chars, max {max})", max = GTS_MAX_LENGTH)]
+    TooLong { length: usize },
+
+    /// The fields are valid but the assembled identifier is not, e.g. a
+    /// single-segment instance.
+    #[error(transparent)]
+    Id(#[from] GtsIdError),
+}
+
+impl GtsIdFieldError {
+    /// The field the error is about, if it concerns a single field.
+    #[must_use]
+    pub fn field(&self) -> Option<(usize, GtsIdField)> {
+        match self {
+            Self::Missing { segment, field } | Self::InvalidToken { segment, field, .. } => {
+                Some((*segment, *field))
+            }
+            Self::NoSegments | Self::TooLong { .. } | Self::Id(_) => None,
+        }
+    }
+}
+
+/// The fields of one segment, `vendor.package.namespace.type.vMAJOR[.MINOR]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GtsSegmentParts {
+    pub vendor: String,
+    pub package: String,
+    pub namespace: String,
+    pub type_name: String,
+    pub ver_major: Option<u32>,
+    pub ver_minor: Option<u32>,
+}
+
+impl GtsSegmentParts {
+    fn check(&self, segment: usize) -> Result<(), GtsIdFieldError> {
+        for (field, value) in [
+            (GtsIdField::Vendor, &self.vendor),
+            (GtsIdField::Package, &self.package),
+            (GtsIdField::Namespace, &self.namespace),
+            (GtsIdField::TypeName, &self.type_name),
+        ] {
+            if value.is_empty() {
+                return Err(GtsIdFieldError::Missing { segment, field });
+            }
+            if !is_valid_segment_token(value) {
+                return Err(GtsIdFieldError::InvalidToken {
+                    segment,
+                    field,
+                    value: value.clone(),
+                });
+            }
+        }
+        if self.ver_major.is_none() {
+            return Err(GtsIdFieldError::Missing {
+                segment,
+                field: GtsIdField::Version,
+            });
+        }
+        Ok(())
+    }
+
+    fn write(&self, out: &mut String) {
+        use fmt::Write;
+        let _ = write!(
+            out,
+            "{}.{}.{}.{}.v{}",
+            self.vendor,
+            self.package,
+            self.namespace,
+            self.type_name,
+            self.ver_major.unwrap_or_default()
+        );
+        if let Some(minor) = self.ver_minor {
+            let _ = write!(out, ".{minor}");
+        }
+    }
+}
+
+/// Builds a GTS identifier segment by segment.
+///
+/// ```
+/// use gts_id::{GtsIdBuilder, GtsSegmentParts};
+///
+/// let id = GtsIdBuilder::new()
+///     .segment(GtsSegmentParts {
+///         vendor: "x".to_owned(),
+///         package: "core".to_owned(),
+///         namespace: "events".to_owned(),
+///         type_name: "type".to_owned(),
+///         ver_major: Some(1),
+///         ver_minor: None,
+///     })
+///     .build()
+///     .unwrap();
+/// assert_eq!(id, "gts.x.core.events.type.v1~");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GtsIdBuilder {
+    segments: Vec<GtsSegmentParts>,
+    instance: bool,
+}
+
+impl GtsIdBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a segment.
+    #[must_use]
+    pub fn segment(mut self, parts: GtsSegmentParts) -> Self {
+        self.segments.push(parts);
+        self
+    }
+
+    /// Build an instance ID: the last segment gets no trailing `~`.
+    #[must_use]
+    pub fn instance(mut self) -> Self {
+        self.instance = true;
+        self
+    }
+
+    /// Validate all fields and assemble the identifier.
+    ///
+    /// # Errors
+    /// Returns the first [`GtsIdFieldError`] found, checking segments in order.
+    pub fn build(&self) -> Result<String, GtsIdFieldError> {
+        if self.segments.is_empty() {
+            return Err(GtsIdFieldError::NoSegments);
+        }
+        for (i, parts) in self.segments.iter().enumerate() {
+            parts.check(i + 1)?;
+        }
+
+        let mut id = GTS_PREFIX.to_owned();
+        for (i, parts) in self.segments.iter().enumerate() {
+            parts.write(&mut id);
+            if !self.instance || i + 1 < self.segments.len() {
+                id.push('~');
+            }
+        }
+        if id.len() > GTS_MAX_LENGTH {
+            return Err(GtsIdFieldError::TooLong { length: id.len() });
+        }
+        validate_gts_id(&id, false)?;
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn parts(vendor: &str, type_name: &str) -> GtsSegmentParts {
+        GtsSegmentParts {
+            vendor: vendor.to_owned(),
+            package: "core".to_owned(),
+            namespace: "events".to_owned(),
+            type_name: type_name.to_owned(),
+            ver_major: Some(1),
+            ver_minor: None,
+        }
+    }
+
+    #[test]
+    fn test_build_type_and_instance() {
+        let builder = GtsIdBuilder::new()
+            .segment(parts("x", "type"))
+            .segment(GtsSegmentParts {
+                ver_minor: Some(2),
+                ..parts("y", "created")
+            });
+        assert_eq!(
+            builder.build().unwrap(),
+            "gts.x.core.events.type.v1~y.core.events.created.v1.2~"
+        );
+        assert_eq!(
+            builder.instance().build().unwrap(),
+            "gts.x.core.events.type.v1~y.core.events.created.v1.2"
+        );
+    }
+
+    #[test]
+    fn test_field_errors() {
+        let err = GtsIdBuilder::new()
+            .segment(parts("x", "type"))
+            .segment(parts("y", "Created"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            GtsIdFieldError::InvalidToken { segment: 2, field: GtsIdField::TypeName, value }
+                if value == "Created"
+        ));
+        assert_eq!(err.field(), Some((2, GtsIdField::TypeName)));
+
+        let err = GtsIdBuilder::new()
+            .segment(parts("", "type"))
+            .build()
+            .unwrap_err();
+        assert_eq!(err.field(), Some((1, GtsIdField::Vendor)));
+        assert_eq!(err.to_string(), "Segment #1: vendor is required");
+
+        let err = GtsIdBuilder::new()
+            .segment(GtsSegmentParts {
+                ver_major: None,
+                ..parts("x", "type")
+            })
+            .build()
+            .unwrap_err();
+        assert_eq!(err.field(), Some((1, GtsIdField::Version)));
+
+        assert!(matches!(
+            GtsIdBuilder::new().build(),
+            Err(GtsIdFieldError::NoSegments)
+        ));
+    }
+
+    #[test]
+    fn test_too_long() {
+        let long = "a".repeat(GTS_MAX_LENGTH);
+        let err = GtsIdBuilder::new()
+            .segment(parts(&long, "type"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, GtsIdFieldError::TooLong { .. }));
+        assert_eq!(err.field(), None);
+    }
+}
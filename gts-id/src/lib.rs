@@ -3,11 +3,20 @@
 //! This crate provides the single source of truth for GTS identifier validation,
 //! used by both the `gts` runtime library and the `gts-macros` proc-macro crate.
 
+mod builder;
+mod reserved;
+
 use thiserror::Error;
 
+pub use builder::{GtsIdBuilder, GtsIdField, GtsIdFieldError, GtsSegmentParts};
+pub use reserved::{GtsReservation, GtsReservations, validate_gts_id_reserved};
+
 /// The required prefix for all GTS identifiers.
 pub const GTS_PREFIX: &str = "gts.";
 
+/// URI form prefix (`gts://gts.vendor...`) used in JSON Schema `$id`/`$ref`.
+pub const GTS_URI_PREFIX: &str = "gts://";
+
 /// Maximum allowed length for a GTS identifier string.
 pub const GTS_MAX_LENGTH: usize = 1024;
 
@@ -35,6 +44,27 @@ pub enum GtsIdError {
         /// Human-readable description of the problem.
         cause: String,
     },
+
+    /// The ID defines a segment under a reserved vendor or package.
+    #[error("Segment #{num}: '{name}' is reserved{}", reserved_for(owners))]
+    Reserved {
+        /// The raw ID string.
+        id: String,
+        /// 1-based number of the defined segment.
+        num: usize,
+        /// The reserved `vendor` or `vendor.package`.
+        name: String,
+        /// Owners allowed to use the name.
+        owners: Vec<String>,
+    },
+}
+
+fn reserved_for(owners: &[String]) -> String {
+    if owners.is_empty() {
+        String::new()
+    } else {
+        format!(" for {}", owners.join(", "))
+    }
 }
 
 /// Result of successfully parsing a single GTS segment.
@@ -79,6 +109,40 @@ fn expected_format(segment_num: usize) -> &'static str {
     }
 }
 
+/// Cheap plausibility check usable in `const` context.
+///
+/// Checks the `gts.` prefix, the length limit and the character set
+/// (`[a-z0-9_.~-]`) only; segment structure is not looked at, so `true` does
+/// not mean [`validate_gts_id`] accepts the ID. Use the `gts_id!` macro of
+/// `gts-macros` for full validation at compile time.
+///
+/// ```
+/// const ORDER_PLACED: &str = "gts.x.core.events.type.v1~";
+/// const _: () = assert!(gts_id::is_probably_valid_gts_id(ORDER_PLACED));
+/// ```
+#[must_use]
+pub const fn is_probably_valid_gts_id(id: &str) -> bool {
+    let bytes = id.as_bytes();
+    let prefix = GTS_PREFIX.as_bytes();
+    if bytes.len() <= prefix.len() || bytes.len() > GTS_MAX_LENGTH {
+        return false;
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let ok = if i < prefix.len() {
+            b == prefix[i]
+        } else {
+            matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'.' | b'~' | b'-')
+        };
+        if !ok {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
 /// Checks whether a string matches the UUID format
 /// `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` (hex digits and dashes only).
 #[inline]
@@ -292,6 +356,39 @@ pub fn validate_segment(
     Ok(result)
 }
 
+/// Input handling options for [`validate_gts_id_with_options`].
+///
+/// The default is lenient about whitespace (trimmed) and rejects the
+/// `gts://` prefix, matching [`validate_gts_id`]. [`Self::strict`] rejects both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GtsIdValidationOptions {
+    /// Trim leading/trailing whitespace before validating; if `false`, any
+    /// surrounding whitespace is an error.
+    pub trim: bool,
+    /// Strip a leading `gts://` before validating; if `false`, it is an error.
+    pub accept_uri_prefix: bool,
+}
+
+impl Default for GtsIdValidationOptions {
+    fn default() -> Self {
+        Self {
+            trim: true,
+            accept_uri_prefix: false,
+        }
+    }
+}
+
+impl GtsIdValidationOptions {
+    /// Options that accept the raw string only: no trimming, no `gts://` prefix.
+    #[must_use]
+    pub const fn strict() -> Self {
+        Self {
+            trim: false,
+            accept_uri_prefix: false,
+        }
+    }
+}
+
 /// Validate a full GTS identifier string.
 ///
 /// Checks the `gts.` prefix, lowercase, length, then splits by `~` and
@@ -306,7 +403,42 @@ pub fn validate_segment(
 /// # Errors
 /// Returns [`GtsIdError`] on validation failure.
 pub fn validate_gts_id(id: &str, allow_wildcards: bool) -> Result<Vec<ParsedSegment>, GtsIdError> {
-    let raw = id.trim();
+    validate_gts_id_with_options(id, allow_wildcards, GtsIdValidationOptions::default())
+}
+
+/// Validate a full GTS identifier string with explicit input handling options.
+///
+/// Same as [`validate_gts_id`], but surrounding whitespace and the `gts://`
+/// prefix are accepted or rejected according to `options`.
+///
+/// # Errors
+/// Returns [`GtsIdError`] on validation failure.
+pub fn validate_gts_id_with_options(
+    id: &str,
+    allow_wildcards: bool,
+    options: GtsIdValidationOptions,
+) -> Result<Vec<ParsedSegment>, GtsIdError> {
+    let raw = if options.trim {
+        id.trim()
+    } else if id.trim() != id {
+        return Err(GtsIdError::Id {
+            id: id.to_owned(),
+            cause: "must not have leading or trailing whitespace".to_owned(),
+        });
+    } else {
+        id
+    };
+
+    let raw = match raw.strip_prefix(GTS_URI_PREFIX) {
+        Some(stripped) if options.accept_uri_prefix => stripped,
+        Some(_) => {
+            return Err(GtsIdError::Id {
+                id: id.to_owned(),
+                cause: format!("must not use the '{GTS_URI_PREFIX}' URI prefix"),
+            });
+        }
+        None => raw,
+    };
 
     if !raw.starts_with(GTS_PREFIX) {
         return Err(GtsIdError::Id {
@@ -667,7 +799,9 @@ mod tests {
             GtsIdError::Id { cause, .. } => {
                 assert!(cause.contains("must start with 'gts.'"), "got: {cause}");
             }
-            GtsIdError::Segment { .. } => panic!("expected Id error, got: {err}"),
+            GtsIdError::Segment { .. } | GtsIdError::Reserved { .. } => {
+                panic!("expected Id error, got: {err}")
+            }
         }
     }
 
@@ -678,7 +812,9 @@ mod tests {
             GtsIdError::Id { cause, .. } => {
                 assert!(cause.contains("lowercase"), "got: {cause}");
             }
-            GtsIdError::Segment { .. } => panic!("expected Id error, got: {err}"),
+            GtsIdError::Segment { .. } | GtsIdError::Reserved { .. } => {
+                panic!("expected Id error, got: {err}")
+            }
         }
     }
 
@@ -689,7 +825,9 @@ mod tests {
             GtsIdError::Id { cause, .. } => {
                 assert!(cause.contains("'-'"), "got: {cause}");
             }
-            GtsIdError::Segment { .. } => panic!("expected Id error, got: {err}"),
+            GtsIdError::Segment { .. } | GtsIdError::Reserved { .. } => {
+                panic!("expected Id error, got: {err}")
+            }
         }
     }
 
@@ -712,7 +850,9 @@ mod tests {
                     "got: {cause}"
                 );
             }
-            GtsIdError::Id { .. } => panic!("expected Segment error, got: {err}"),
+            GtsIdError::Id { .. } | GtsIdError::Reserved { .. } => {
+                panic!("expected Segment error, got: {err}")
+            }
         }
     }
 
@@ -731,7 +871,9 @@ mod tests {
             GtsIdError::Id { cause, .. } => {
                 assert!(cause.contains("empty segment"), "got: {cause}");
             }
-            GtsIdError::Segment { .. } => panic!("expected Id error, got: {err}"),
+            GtsIdError::Segment { .. } | GtsIdError::Reserved { .. } => {
+                panic!("expected Id error, got: {err}")
+            }
         }
     }
 
@@ -759,6 +901,28 @@ mod tests {
         assert!(!is_uuid("7a1d2f3405678-49ab-9012-abcdef123456")); // dash in wrong place
     }
 
+    // ---- is_probably_valid_gts_id ----
+
+    #[test]
+    fn test_is_probably_valid_gts_id() {
+        const { assert!(is_probably_valid_gts_id("gts.x.core.events.type.v1~")) };
+        assert!(is_probably_valid_gts_id(
+            "gts.x.core.events.type.v1~7a1d2f34-5678-49ab-9012-abcdef123456"
+        ));
+        assert!(!is_probably_valid_gts_id("gts."));
+        assert!(!is_probably_valid_gts_id(
+            "gts://gts.x.core.events.type.v1~"
+        ));
+        assert!(!is_probably_valid_gts_id("gts.x.core.events.Type.v1~"));
+        assert!(!is_probably_valid_gts_id("gts.x.core.events.*"));
+        assert!(!is_probably_valid_gts_id(&format!(
+            "gts.{}",
+            "a".repeat(GTS_MAX_LENGTH)
+        )));
+        // Structure is not checked
+        assert!(is_probably_valid_gts_id("gts.x~~"));
+    }
+
     // ---- combined anonymous instance ----
 
     #[test]
@@ -799,7 +963,9 @@ mod tests {
             GtsIdError::Id { cause, .. } => {
                 assert!(cause.contains("'-'"), "got: {cause}");
             }
-            GtsIdError::Segment { .. } => panic!("expected Id error, got: {err}"),
+            GtsIdError::Segment { .. } | GtsIdError::Reserved { .. } => {
+                panic!("expected Id error, got: {err}")
+            }
         }
     }
 
@@ -811,7 +977,9 @@ mod tests {
             GtsIdError::Id { cause, .. } => {
                 assert!(cause.contains("must start with 'gts.'"), "got: {cause}");
             }
-            GtsIdError::Segment { .. } => panic!("expected Id error, got: {err}"),
+            GtsIdError::Segment { .. } | GtsIdError::Reserved { .. } => {
+                panic!("expected Id error, got: {err}")
+            }
         }
     }
 
@@ -824,7 +992,62 @@ mod tests {
             GtsIdError::Id { cause, .. } => {
                 assert!(cause.contains("'-'"), "got: {cause}");
             }
-            GtsIdError::Segment { .. } => panic!("expected Id error, got: {err}"),
+            GtsIdError::Segment { .. } | GtsIdError::Reserved { .. } => {
+                panic!("expected Id error, got: {err}")
+            }
+        }
+    }
+
+    // ---- validate_gts_id_with_options ----
+
+    #[test]
+    fn test_default_options_trim_whitespace() {
+        let segments = validate_gts_id_with_options(
+            "  gts.x.core.events.event.v1~ ",
+            false,
+            GtsIdValidationOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(segments.len(), 1);
+    }
+
+    #[test]
+    fn test_strict_options_reject_whitespace() {
+        let err = validate_gts_id_with_options(
+            " gts.x.core.events.event.v1~",
+            false,
+            GtsIdValidationOptions::strict(),
+        )
+        .unwrap_err();
+        match err {
+            GtsIdError::Id { cause, .. } => assert!(cause.contains("whitespace"), "got: {cause}"),
+            GtsIdError::Segment { .. } | GtsIdError::Reserved { .. } => {
+                panic!("expected Id error, got: {err}")
+            }
         }
     }
+
+    #[test]
+    fn test_uri_prefix_rejected_with_specific_cause() {
+        let err = validate_gts_id("gts://gts.x.core.events.event.v1~", false).unwrap_err();
+        match err {
+            GtsIdError::Id { cause, .. } => assert!(cause.contains("'gts://'"), "got: {cause}"),
+            GtsIdError::Segment { .. } | GtsIdError::Reserved { .. } => {
+                panic!("expected Id error, got: {err}")
+            }
+        }
+    }
+
+    #[test]
+    fn test_uri_prefix_accepted_when_enabled() {
+        let options = GtsIdValidationOptions {
+            accept_uri_prefix: true,
+            ..GtsIdValidationOptions::strict()
+        };
+        let segments =
+            validate_gts_id_with_options("gts://gts.x.core.events.event.v1~", false, options)
+                .unwrap();
+        assert_eq!(segments[0].vendor, "x");
+        assert_eq!(segments[0].offset, GTS_PREFIX.len());
+    }
 }
@@ -0,0 +1,210 @@
+//! Reserved vendors and packages.
+//!
+//! A [`GtsReservations`] registry restricts who may define types or instances
+//! under a vendor (`gts`) or a vendor's package (`x.core`). Only the last
+//! segment of an ID is what the ID defines, so earlier segments (the parent
+//! types it is derived from) may use reserved names freely.
+//!
+//! The policy is opt-in: [`validate_gts_id`](crate::validate_gts_id) ignores
+//! reservations, [`validate_gts_id_reserved`] enforces them.
+
+use crate::{GtsIdError, ParsedSegment, validate_gts_id};
+
+/// A reserved vendor, or package of a vendor, and who may use it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GtsReservation {
+    pub vendor: String,
+    /// `None` reserves every package of the vendor.
+    pub package: Option<String>,
+    /// Owners allowed to define IDs under the reservation.
+    pub owners: Vec<String>,
+}
+
+impl GtsReservation {
+    /// The reserved name as written in an ID: `vendor` or `vendor.package`.
+    #[must_use]
+    pub fn name(&self) -> String {
+        match &self.package {
+            Some(package) => format!("{}.{package}", self.vendor),
+            None => self.vendor.clone(),
+        }
+    }
+
+    fn covers(&self, segment: &ParsedSegment) -> bool {
+        self.vendor == segment.vendor
+            && self
+                .package
+                .as_ref()
+                .is_none_or(|package| *package == segment.package)
+    }
+}
+
+/// Registry of reserved vendors and packages.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GtsReservations {
+    entries: Vec<GtsReservation>,
+}
+
+impl GtsReservations {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve `vendor` (every package if `package` is `None`) for `owners`.
+    #[must_use]
+    pub fn reserve(mut self, vendor: &str, package: Option<&str>, owners: &[&str]) -> Self {
+        self.push(GtsReservation {
+            vendor: vendor.to_owned(),
+            package: package.map(str::to_owned),
+            owners: owners.iter().map(|o| (*o).to_owned()).collect(),
+        });
+        self
+    }
+
+    pub fn push(&mut self, reservation: GtsReservation) {
+        self.entries.push(reservation);
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[GtsReservation] {
+        &self.entries
+    }
+
+    /// The reservation `owner` violates by defining `segment`, if any.
+    #[must_use]
+    pub fn violation(
+        &self,
+        segment: &ParsedSegment,
+        owner: Option<&str>,
+    ) -> Option<&GtsReservation> {
+        self.entries.iter().find(|reservation| {
+            reservation.covers(segment)
+                && !owner.is_some_and(|owner| reservation.owners.iter().any(|o| o == owner))
+        })
+    }
+
+    /// Check the segment defined by a parsed ID (its last GTS segment).
+    ///
+    /// # Errors
+    /// Returns [`GtsIdError::Reserved`] if `owner` may not define it.
+    pub fn check(
+        &self,
+        id: &str,
+        segments: &[ParsedSegment],
+        owner: Option<&str>,
+    ) -> Result<(), GtsIdError> {
+        let Some((num, defined)) = segments
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, s)| !s.is_uuid_tail)
+        else {
+            return Ok(());
+        };
+        match self.violation(defined, owner) {
+            Some(reservation) => Err(GtsIdError::Reserved {
+                id: id.to_owned(),
+                num: num + 1,
+                name: reservation.name(),
+                owners: reservation.owners.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Validate a GTS identifier and the reservations of the segment it defines.
+///
+/// `owner` is who defines the ID; `None` violates every reservation.
+///
+/// # Errors
+/// Returns [`GtsIdError`] on validation failure, [`GtsIdError::Reserved`] if
+/// the ID defines a segment under a reservation `owner` does not hold.
+pub fn validate_gts_id_reserved(
+    id: &str,
+    allow_wildcards: bool,
+    reservations: &GtsReservations,
+    owner: Option<&str>,
+) -> Result<Vec<ParsedSegment>, GtsIdError> {
+    let segments = validate_gts_id(id, allow_wildcards)?;
+    reservations.check(id, &segments, owner)?;
+    Ok(segments)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn registry() -> GtsReservations {
+        GtsReservations::new()
+            .reserve("gts", None, &["platform"])
+            .reserve("x", Some("core"), &["platform", "core_team"])
+    }
+
+    #[test]
+    fn test_reserved_vendor_and_package() {
+        let reservations = registry();
+        let err = validate_gts_id_reserved(
+            "gts.x.core.events.type.v1~",
+            false,
+            &reservations,
+            Some("app"),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            &err,
+            GtsIdError::Reserved { num: 1, name, .. } if name == "x.core"
+        ));
+        assert!(err.to_string().contains("platform, core_team"), "{err}");
+
+        assert!(validate_gts_id_reserved("gts.gts.a.b.c.v1~", false, &reservations, None).is_err());
+        assert!(
+            validate_gts_id_reserved(
+                "gts.x.core.events.type.v1~",
+                false,
+                &reservations,
+                Some("core_team")
+            )
+            .is_ok()
+        );
+        assert!(
+            validate_gts_id_reserved("gts.x.app.events.type.v1~", false, &reservations, None)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_only_defined_segment_is_checked() {
+        let reservations = registry();
+        // Deriving from a reserved type is fine
+        assert!(
+            validate_gts_id_reserved(
+                "gts.x.core.events.type.v1~acme.app._.orders.v1",
+                false,
+                &reservations,
+                None
+            )
+            .is_ok()
+        );
+        let err = validate_gts_id_reserved(
+            "gts.acme.app.events.type.v1~x.core._.orders.v1",
+            false,
+            &reservations,
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, GtsIdError::Reserved { num: 2, .. }));
+        // Invalid IDs fail validation before reservations are looked at
+        assert!(matches!(
+            validate_gts_id_reserved("gts.x.core", false, &reservations, None),
+            Err(GtsIdError::Segment { .. })
+        ));
+    }
+}
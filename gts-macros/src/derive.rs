@@ -0,0 +1,203 @@
+//! `#[derive(GtsSchema)]`: the schema of every field unless opted out.
+//!
+//! The container attribute `#[gts(...)]` takes the arguments of
+//! `#[struct_to_gts_schema(...)]` except `properties`. Field attributes:
+//!
+//! - `#[gts(skip)]` leaves the field out of the schema,
+//! - `#[gts(rename = "...")]` names its property (a `#[serde(rename)]` is
+//!   picked up without it),
+//! - `#[gts(description = "...")]` and `#[gts(format = "...")]` annotate it.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+use crate::{ArgsKind, BaseAttr, GtsSchemaArgs, get_serde_rename};
+
+/// The `#[gts(...)]` attributes of one named field
+pub struct FieldAttrs {
+    ident: String,
+    /// Property name the schema builders give the field
+    name: String,
+    skip: bool,
+    rename: Option<String>,
+    description: Option<String>,
+    format: Option<String>,
+}
+
+impl FieldAttrs {
+    fn parse(field: &syn::Field) -> syn::Result<Option<Self>> {
+        let Some(ident) = &field.ident else {
+            return Ok(None);
+        };
+        let serde_rename = get_serde_rename(field);
+        let mut attrs = Self {
+            ident: ident.to_string(),
+            name: serde_rename.clone().unwrap_or_else(|| ident.to_string()),
+            skip: false,
+            rename: None,
+            description: None,
+            format: None,
+        };
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("gts")) {
+            attr.parse_nested_meta(|meta| {
+                let string = || -> syn::Result<String> {
+                    Ok(meta.value()?.parse::<LitStr>()?.value())
+                };
+                if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                } else if meta.path.is_ident("rename") {
+                    attrs.rename = Some(string()?);
+                } else if meta.path.is_ident("description") {
+                    attrs.description = Some(string()?);
+                } else if meta.path.is_ident("format") {
+                    attrs.format = Some(string()?);
+                } else {
+                    return Err(meta.error(
+                        "derive(GtsSchema): unknown field attribute. Expected: skip, rename, description, or format",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        if let (Some(rename), Some(serde_rename)) = (&attrs.rename, &serde_rename)
+            && rename != serde_rename
+        {
+            return Err(syn::Error::new_spanned(
+                ident,
+                format!(
+                    "derive(GtsSchema): #[gts(rename = \"{rename}\")] conflicts with #[serde(rename = \"{serde_rename}\")]"
+                ),
+            ));
+        }
+        Ok(Some(attrs))
+    }
+
+    /// The `#[gts(rename)]` of `field`
+    pub fn rename_of(attrs: &[Self], field: &syn::Field) -> Option<String> {
+        let ident = field.ident.as_ref()?;
+        attrs
+            .iter()
+            .find(|a| *ident == a.ident)
+            .and_then(|a| a.rename.clone())
+    }
+
+    /// Whether `field` is left out of the schema
+    pub fn skips(attrs: &[Self], field: &syn::Field) -> bool {
+        field
+            .ident
+            .as_ref()
+            .is_some_and(|ident| attrs.iter().any(|a| a.skip && *ident == a.ident))
+    }
+}
+
+/// The container arguments and field attributes of a struct deriving `GtsSchema`
+pub fn parse(input: &DeriveInput) -> syn::Result<(GtsSchemaArgs, Vec<FieldAttrs>)> {
+    let Some(attr) = input.attrs.iter().find(|a| a.path().is_ident("gts")) else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "derive(GtsSchema): missing #[gts(dir_path = \"...\", schema_id = \"...\", description = \"...\", base = ...)]",
+        ));
+    };
+    let mut args = attr.parse_args_with(|input: syn::parse::ParseStream| {
+        GtsSchemaArgs::parse_with(input, ArgsKind::Derive)
+    })?;
+    if let Some(param) = input.generics.type_params().next() {
+        return Err(syn::Error::new_spanned(
+            param,
+            "derive(GtsSchema): generic structs need serde attributes a derive cannot add. Use #[struct_to_gts_schema] instead",
+        ));
+    }
+
+    if matches!(input.data, Data::Enum(_)) {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "derive(GtsSchema): enums are not supported. Use #[struct_to_gts_schema] instead",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .filter_map(|field| FieldAttrs::parse(field).transpose())
+                .collect::<syn::Result<Vec<_>>>()?,
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+    // Nested structs serialize through the generated GtsSerialize impl, which
+    // follows the rename; anything else serializes through the user's derive
+    if !matches!(args.base, BaseAttr::Parent(..))
+        && let Some(field) = fields
+            .iter()
+            .find(|f| f.rename.as_ref().is_some_and(|rename| *rename != f.name))
+    {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            format!(
+                "derive(GtsSchema): field '{}' of a base struct serializes through its own Serialize derive. Rename it with #[serde(rename = \"...\")]",
+                field.ident
+            ),
+        ));
+    }
+    args.properties = fields
+        .iter()
+        .filter(|f| !f.skip)
+        .map(|f| f.ident.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok((args, fields))
+}
+
+/// Statements applying the field attributes to the object schema in `schema`
+pub fn schema_overrides(attrs: &[FieldAttrs]) -> TokenStream {
+    let skipped: Vec<&str> = attrs
+        .iter()
+        .filter(|a| a.skip)
+        .map(|a| a.name.as_str())
+        .collect();
+    let mut property_updates = Vec::new();
+    let mut required_updates = Vec::new();
+    for attr in attrs.iter().filter(|a| !a.skip) {
+        let name = attr.rename.as_deref().unwrap_or(&attr.name);
+        if let Some(rename) = &attr.rename
+            && *rename != attr.name
+        {
+            let from = &attr.name;
+            property_updates.push(quote! {
+                if let Some(property) = properties.remove(#from) {
+                    properties.insert(#rename.to_owned(), property);
+                }
+            });
+            required_updates.push(quote! {
+                if *name == #from {
+                    *name = serde_json::Value::from(#rename);
+                }
+            });
+        }
+        for (keyword, value) in [("description", &attr.description), ("format", &attr.format)] {
+            if let Some(value) = value {
+                property_updates.push(quote! {
+                    if let Some(property) = properties.get_mut(#name).and_then(serde_json::Value::as_object_mut) {
+                        property.insert(#keyword.to_owned(), serde_json::Value::from(#value));
+                    }
+                });
+            }
+        }
+    }
+
+    quote! {
+        if let Some(properties) = schema.get_mut("properties").and_then(serde_json::Value::as_object_mut) {
+            #(properties.remove(#skipped);)*
+            #(#property_updates)*
+        }
+        if let Some(required) = schema.get_mut("required").and_then(serde_json::Value::as_array_mut) {
+            required.retain(|name| !name.as_str().is_some_and(|name| [#(#skipped),*].contains(&name)));
+            for name in required.iter_mut() {
+                #(#required_updates)*
+            }
+        }
+    }
+}
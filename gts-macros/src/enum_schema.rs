@@ -0,0 +1,362 @@
+//! `#[struct_to_gts_schema]` on enums.
+//!
+//! An enum of unit variants is a string enum in the schema. An enum with data
+//! variants is internally tagged (`#[serde(tag = "...")]`, added as `"type"`
+//! unless given) and its schema is a `oneOf` of one object per variant, with
+//! the tag as `discriminator`. Variant fields use the `static_schema` type
+//! mapping.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde_json::{Value, json};
+use syn::meta::ParseNestedMeta;
+use syn::{DataEnum, DeriveInput, Fields, LitStr, Token};
+
+use crate::{
+    BaseAttr, GtsSchemaArgs, add_missing_derives, get_serde_rename, static_schema,
+    validate_base_segments, validate_version_match,
+};
+
+/// Tag the macro adds to enums with data variants that do not name one
+const DEFAULT_TAG: &str = "type";
+
+/// Visit the `#[serde(...)]` entries in `attrs`, skipping those `visit` does not read
+fn for_each_serde_meta(
+    attrs: &[syn::Attribute],
+    mut visit: impl FnMut(&ParseNestedMeta) -> syn::Result<bool>,
+) -> syn::Result<()> {
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if !visit(&meta)? {
+                while !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+                    meta.input.parse::<proc_macro2::TokenTree>()?;
+                }
+            }
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// The serialized name of `variant` under the container's `rename_all` rule
+fn variant_name(variant: &syn::Variant, rename_all: Option<&LitStr>) -> syn::Result<String> {
+    let mut rename = None;
+    for_each_serde_meta(&variant.attrs, |meta| {
+        if meta.path.is_ident("rename") && meta.input.peek(Token![=]) {
+            rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            return Ok(true);
+        }
+        Ok(false)
+    })?;
+    if let Some(rename) = rename {
+        return Ok(rename);
+    }
+
+    let ident = variant.ident.to_string();
+    let Some(rule) = rename_all else {
+        return Ok(ident);
+    };
+    // Variant names are PascalCase; serde splits them at each uppercase letter
+    let snake: String = ident
+        .char_indices()
+        .flat_map(|(i, c)| {
+            let separator = (i > 0 && c.is_uppercase()).then_some('_');
+            separator.into_iter().chain(c.to_lowercase())
+        })
+        .collect();
+    Ok(match rule.value().as_str() {
+        "lowercase" => ident.to_lowercase(),
+        "UPPERCASE" => ident.to_uppercase(),
+        "PascalCase" => ident,
+        "camelCase" => {
+            let mut chars = ident.chars();
+            chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        "snake_case" => snake,
+        "SCREAMING_SNAKE_CASE" => snake.to_uppercase(),
+        "kebab-case" => snake.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake.to_uppercase().replace('_', "-"),
+        other => {
+            return Err(syn::Error::new_spanned(
+                rule,
+                format!("struct_to_gts_schema: Unknown rename_all rule '{other}'"),
+            ));
+        }
+    })
+}
+
+/// The schema of the enum, without `$id` and `$schema`
+fn enum_schema(input: &DeriveInput, data: &DataEnum, tag: Option<&str>) -> syn::Result<Value> {
+    let mut rename_all = None;
+    for_each_serde_meta(&input.attrs, |meta| {
+        if meta.path.is_ident("rename_all") && meta.input.peek(Token![=]) {
+            rename_all = Some(meta.value()?.parse::<LitStr>()?);
+            return Ok(true);
+        }
+        Ok(false)
+    })?;
+
+    let names = data
+        .variants
+        .iter()
+        .map(|variant| variant_name(variant, rename_all.as_ref()))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let Some(tag) = tag else {
+        return Ok(json!({"type": "string", "enum": names}));
+    };
+
+    let mut branches = Vec::new();
+    for (variant, name) in data.variants.iter().zip(names) {
+        let fields = match &variant.fields {
+            Fields::Named(fields) => Some(&fields.named),
+            // Tuple variants are rejected before
+            Fields::Unit | Fields::Unnamed(_) => None,
+        };
+        let mut branch = static_schema::object_schema(fields, &[], get_serde_rename).map_err(|e| {
+            syn::Error::new(
+                e.span(),
+                format!(
+                    "struct_to_gts_schema: Variant '{}' {e}. Variant fields take the types static_schema supports",
+                    variant.ident
+                ),
+            )
+        })?;
+        if branch["properties"].get(tag).is_some() {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                format!(
+                    "struct_to_gts_schema: Variant '{}' has a field serialized as '{tag}', the enum's tag. \
+                     Rename the field or name another tag with #[serde(tag = \"...\")]",
+                    variant.ident
+                ),
+            ));
+        }
+
+        let mut properties = serde_json::Map::new();
+        properties.insert(tag.to_owned(), json!({"const": name}));
+        if let Some(fields) = branch["properties"].as_object_mut() {
+            properties.append(fields);
+        }
+        let mut required = vec![Value::from(tag)];
+        if let Some(fields) = branch["required"].as_array_mut() {
+            required.append(fields);
+        }
+        branch = json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": properties,
+            "required": required
+        });
+        branches.push(branch);
+    }
+    Ok(json!({
+        "type": "object",
+        "discriminator": {"propertyName": tag},
+        "oneOf": branches
+    }))
+}
+
+/// The enum, with the derives and tag its schema assumes, and its `GtsSchema` impls
+pub fn expand(
+    args: &GtsSchemaArgs,
+    input: &DeriveInput,
+    data: &DataEnum,
+) -> syn::Result<TokenStream> {
+    if !matches!(args.base, BaseAttr::IsBase) {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "struct_to_gts_schema: Enums are base types. Use 'base = true'",
+        ));
+    }
+    if let Some(param) = input.generics.params.first() {
+        return Err(syn::Error::new_spanned(
+            param,
+            "struct_to_gts_schema: Generic enums are not supported",
+        ));
+    }
+    if data.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "struct_to_gts_schema: An enum needs at least one variant",
+        ));
+    }
+    if let Some(variant) = data
+        .variants
+        .iter()
+        .find(|v| matches!(v.fields, Fields::Unnamed(_)))
+    {
+        return Err(syn::Error::new_spanned(
+            variant,
+            format!(
+                "struct_to_gts_schema: Tuple variant '{}' is not supported. \
+                 Use named fields or a unit variant",
+                variant.ident
+            ),
+        ));
+    }
+    validate_version_match(&input.ident, &args.schema_id)?;
+    validate_base_segments(input, &args.base, &args.schema_id)?;
+
+    let mut tag = None;
+    for_each_serde_meta(&input.attrs, |meta| {
+        if meta.path.is_ident("tag") {
+            tag = Some(meta.value()?.parse::<LitStr>()?.value());
+        } else if meta.path.is_ident("untagged") || meta.path.is_ident("content") {
+            return Err(meta.error(
+                "struct_to_gts_schema: Enums are internally tagged. \
+                 Remove `untagged`/`content` and name the tag with #[serde(tag = \"...\")]",
+            ));
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    })?;
+
+    let mut modified_input = input.clone();
+    add_missing_derives(&mut modified_input, &args.base, args.static_schema);
+    // The added derives introduce the `serde` helper, which container attributes use
+    modified_input
+        .attrs
+        .sort_by_key(|attr| !attr.path().is_ident("derive"));
+    // Data variants are only representable as objects, so such enums are tagged
+    let has_data = data.variants.iter().any(|v| !v.fields.is_empty());
+    if tag.is_none() && has_data {
+        modified_input
+            .attrs
+            .push(syn::parse_quote!(#[serde(tag = #DEFAULT_TAG)]));
+        tag = Some(DEFAULT_TAG.to_owned());
+    }
+    let schema = enum_schema(input, data, tag.as_deref())?.to_string();
+
+    let enum_name = &input.ident;
+    let schema_id = &args.schema_id;
+    let description = &args.description;
+    let schema_file_path = format!("{}/{schema_id}.schema.json", args.dir_path);
+
+    Ok(quote! {
+        #modified_input
+
+        impl #enum_name {
+            /// File path where the GTS schema will be generated by the CLI.
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            const GTS_SCHEMA_FILE_PATH: &'static str = #schema_file_path;
+
+            /// GTS schema description.
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            const GTS_SCHEMA_DESCRIPTION: &'static str = #description;
+
+            /// Comma-separated list of properties included in the schema (none for enums).
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            const GTS_SCHEMA_PROPERTIES: &'static str = "";
+
+            /// Parent schema ID (None for base types). Use `gts_base_schema_id()` instead.
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            const BASE_SCHEMA_ID: Option<&'static str> = None;
+
+            /// Get the GTS schema identifier as a static reference.
+            #[allow(dead_code)]
+            #[must_use]
+            pub fn gts_schema_id() -> &'static ::gts::gts::GtsSchemaId {
+                static GTS_SCHEMA_ID: std::sync::LazyLock<::gts::gts::GtsSchemaId> =
+                    std::sync::LazyLock::new(|| ::gts::gts::GtsSchemaId::new(#schema_id));
+                &GTS_SCHEMA_ID
+            }
+
+            /// Get the parent (base) schema identifier; always `None`, enums are base types.
+            #[allow(dead_code)]
+            #[must_use]
+            pub fn gts_base_schema_id() -> Option<&'static ::gts::gts::GtsSchemaId> {
+                None
+            }
+
+            /// Generate a GTS instance ID by appending a segment to the schema ID.
+            #[allow(dead_code)]
+            #[must_use]
+            pub fn gts_make_instance_id(segment: &str) -> ::gts::GtsInstanceId {
+                ::gts::GtsInstanceId::new(#schema_id, segment)
+            }
+
+            /// Get the JSON Schema of the enum as a JSON string.
+            #[allow(dead_code)]
+            #[must_use]
+            pub fn gts_schema_with_refs_as_string() -> String {
+                use ::gts::GtsSchema;
+                serde_json::to_string(&Self::gts_schema_with_refs_allof()).expect("Failed to serialize schema")
+            }
+
+            /// Get the JSON Schema of the enum as a pretty-printed JSON string.
+            #[allow(dead_code)]
+            #[must_use]
+            pub fn gts_schema_with_refs_as_string_pretty() -> String {
+                use ::gts::GtsSchema;
+                serde_json::to_string_pretty(&Self::gts_schema_with_refs_allof()).expect("Failed to serialize schema")
+            }
+
+            /// Serialize this instance to a `serde_json::Value`.
+            #[allow(dead_code)]
+            #[must_use]
+            pub fn gts_instance_json(&self) -> serde_json::Value {
+                serde_json::to_value(self).expect("Failed to serialize instance to JSON")
+            }
+
+            /// Serialize this instance to a JSON string.
+            #[allow(dead_code)]
+            #[must_use]
+            pub fn gts_instance_json_as_string(&self) -> String {
+                serde_json::to_string(self).expect("Failed to serialize instance to JSON string")
+            }
+
+            /// Serialize this instance to a pretty-printed JSON string.
+            #[allow(dead_code)]
+            #[must_use]
+            pub fn gts_instance_json_as_string_pretty(&self) -> String {
+                serde_json::to_string_pretty(self).expect("Failed to serialize instance to JSON string")
+            }
+        }
+
+        impl ::gts::GtsSchema for #enum_name {
+            const SCHEMA_ID: &'static str = #schema_id;
+
+            fn gts_schema_with_refs() -> serde_json::Value {
+                Self::gts_schema_with_refs_allof()
+            }
+
+            fn innermost_schema() -> serde_json::Value {
+                let mut schema = serde_json::from_str::<serde_json::Value>(#schema).expect("static schema");
+                // Resolve the GtsInstanceId and GtsSchemaId references of variant fields
+                let branches = schema.get_mut("oneOf").and_then(serde_json::Value::as_array_mut);
+                for branch in branches.into_iter().flatten() {
+                    let properties = branch.get_mut("properties").and_then(serde_json::Value::as_object_mut);
+                    for value in properties.into_iter().flat_map(|p| p.values_mut()) {
+                        match value.get("$ref").and_then(serde_json::Value::as_str) {
+                            Some("#/$defs/GtsInstanceId") => *value = ::gts::GtsInstanceId::json_schema_value(),
+                            Some("#/$defs/GtsSchemaId") => *value = ::gts::GtsSchemaId::json_schema_value(),
+                            _ => {}
+                        }
+                    }
+                }
+                schema
+            }
+
+            fn gts_schema_with_refs_allof() -> serde_json::Value {
+                let mut schema = serde_json::json!({
+                    "$id": format!("gts://{}", Self::SCHEMA_ID),
+                    "$schema": "http://json-schema.org/draft-07/schema#"
+                });
+                if let (Some(schema), serde_json::Value::Object(body)) =
+                    (schema.as_object_mut(), Self::innermost_schema())
+                {
+                    schema.extend(body);
+                }
+                schema
+            }
+        }
+    })
+}
@@ -1,13 +1,15 @@
 // Proc macros run at compile time, so panics become compile errors
 #![allow(clippy::expect_used, clippy::unwrap_used)]
 
+mod derive;
+mod enum_schema;
+mod static_schema;
+mod typed_id;
+mod well_known;
+
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{
-    Data, DeriveInput, Fields, LitStr, Token,
-    parse::{Parse, ParseStream},
-    parse_macro_input,
-};
+use syn::{Data, DeriveInput, Fields, LitStr, Token, parse::ParseStream, parse_macro_input};
 
 // Field name constants to avoid duplication
 const ID_FIELD_NAMES: &[&str] = &["$id", "id", "gts_id", "gtsId"];
@@ -346,8 +348,7 @@ fn has_derive(input: &syn::DeriveInput, trait_name: &str) -> bool {
             && attr
                 .meta
                 .require_list()
-                .map(|meta| meta.tokens.to_string().contains(trait_name))
-                .unwrap_or(false)
+                .is_ok_and(|meta| meta.tokens.to_string().contains(trait_name))
     })
 }
 
@@ -468,31 +469,25 @@ fn remove_derives(input: &mut syn::DeriveInput, traits_to_remove: &[&str]) {
 ///
 /// For nested structs (`base = ParentStruct`), Serialize and Deserialize are NOT added.
 /// This prevents direct serialization of nested structs - they can only be serialized
-/// through their base struct wrapper.
-fn add_missing_derives(input: &mut syn::DeriveInput, base: &BaseAttr) {
+/// through their base struct wrapper. With `static_schema`, `JsonSchema` is not added.
+fn add_missing_derives(input: &mut syn::DeriveInput, base: &BaseAttr, static_schema: bool) {
     // For nested structs (base = ParentStruct), only add JsonSchema
     // Serialize/Deserialize will be provided via GtsSerialize/GtsDeserialize traits
-    let is_nested = matches!(base, BaseAttr::Parent(_));
-
-    let derives_to_add: Vec<&str> = if is_nested {
-        // Nested struct: only JsonSchema
-        [("JsonSchema", "schemars::JsonSchema")]
-            .into_iter()
-            .filter(|(check, _)| !has_derive(input, check))
-            .map(|(_, full)| full)
-            .collect()
-    } else {
-        // Base struct: all three derives
-        [
-            ("Serialize", "serde::Serialize"),
-            ("Deserialize", "serde::Deserialize"),
-            ("JsonSchema", "schemars::JsonSchema"),
-        ]
+    let is_nested = matches!(base, BaseAttr::Parent(..));
+
+    let mut required: Vec<(&str, &str)> = Vec::new();
+    if !is_nested {
+        required.push(("Serialize", "serde::Serialize"));
+        required.push(("Deserialize", "serde::Deserialize"));
+    }
+    if !static_schema {
+        required.push(("JsonSchema", "schemars::JsonSchema"));
+    }
+    let derives_to_add: Vec<&str> = required
         .into_iter()
         .filter(|(check, _)| !has_derive(input, check))
         .map(|(_, full)| full)
-        .collect()
-    };
+        .collect();
 
     if !derives_to_add.is_empty() {
         let derives_str = derives_to_add.join(", ");
@@ -521,7 +516,7 @@ fn validate_base_segments(
                  Either use 'base = ParentStruct' or fix the schema_id."
             ),
         )),
-        BaseAttr::Parent(_) if segment_count < 2 => Err(syn::Error::new_spanned(
+        BaseAttr::Parent(..) if segment_count < 2 => Err(syn::Error::new_spanned(
             &input.ident,
             format!(
                 "struct_to_gts_schema: 'base' specifies a parent struct but schema_id '{schema_id}' \
@@ -544,20 +539,27 @@ fn add_gts_serde_attrs(input: &mut syn::DeriveInput, base: &BaseAttr) {
         return;
     }
 
-    // Get the generic type parameter name if present
-    let generic_param_name: Option<String> = input
+    // Get the generic type parameter names
+    let generic_params: Vec<String> = input
         .generics
         .type_params()
-        .next()
-        .map(|tp| tp.ident.to_string());
+        .map(|tp| tp.ident.to_string())
+        .collect();
 
-    let Some(generic_param) = generic_param_name else {
+    if generic_params.is_empty() {
         return; // No generic parameter, nothing to do
-    };
+    }
 
     // Build the bound strings
-    let serialize_bound = format!("{generic_param}: ::gts::GtsSerialize");
-    let deserialize_bound = format!("{generic_param}: ::gts::GtsDeserialize<'de>");
+    let bound = |trait_path: &str| {
+        generic_params
+            .iter()
+            .map(|gp| format!("{gp}: {trait_path}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let serialize_bound = bound("::gts::GtsSerialize");
+    let deserialize_bound = bound("::gts::GtsDeserialize<'de>");
 
     // Add serde bound attribute on the struct
     let bound_attr: syn::Attribute = syn::parse_quote!(
@@ -573,10 +575,10 @@ fn add_gts_serde_attrs(input: &mut syn::DeriveInput, base: &BaseAttr) {
         && let syn::Fields::Named(ref mut fields) = data_struct.fields
     {
         for field in &mut fields.named {
-            // Check if this field's type is the generic parameter
+            // Check if this field's type is a generic parameter
             let field_type = &field.ty;
             let field_type_str = quote::quote!(#field_type).to_string().replace(' ', "");
-            if field_type_str == generic_param {
+            if generic_params.contains(&field_type_str) {
                 // Add serde attributes to this field
                 let field_attr: syn::Attribute = syn::parse_quote!(
                     #[serde(
@@ -596,17 +598,15 @@ fn build_where_clause(
     where_clause: Option<&syn::WhereClause>,
     bounds: &str,
 ) -> proc_macro2::TokenStream {
-    if let Some(generic_param) = generics.type_params().next() {
-        let generic_ident = &generic_param.ident;
-        let bounds_tokens: proc_macro2::TokenStream =
-            bounds.parse().expect("Failed to parse bounds");
-        if let Some(existing) = where_clause {
-            quote! { #existing #generic_ident: #bounds_tokens, }
-        } else {
-            quote! { where #generic_ident: #bounds_tokens }
-        }
+    let generic_idents: Vec<_> = generics.type_params().map(|p| &p.ident).collect();
+    if generic_idents.is_empty() {
+        return quote! { #where_clause };
+    }
+    let bounds_tokens: proc_macro2::TokenStream = bounds.parse().expect("Failed to parse bounds");
+    if let Some(existing) = where_clause {
+        quote! { #existing #(#generic_idents: #bounds_tokens,)* }
     } else {
-        quote! { #where_clause }
+        quote! { where #(#generic_idents: #bounds_tokens),* }
     }
 }
 
@@ -616,7 +616,9 @@ enum BaseAttr {
     IsBase,
     /// This struct inherits from the specified parent struct (e.g., `ParentStruct`)
     /// The macro automatically uses `ParentStruct<()>` in generated code
-    Parent(syn::Ident),
+    /// A parent with several generic fields is extended through the named one
+    /// (`ParentStruct(payload)`), or else through its first
+    Parent(syn::Ident, Option<syn::Ident>),
 }
 
 /// Arguments for the `struct_to_gts_schema` macro
@@ -626,15 +628,31 @@ struct GtsSchemaArgs {
     description: String,
     properties: String,
     base: BaseAttr,
+    /// Build the schema from the built-in type mapping instead of schemars
+    static_schema: bool,
 }
 
-impl Parse for GtsSchemaArgs {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
+/// Where the arguments of a `GtsSchemaArgs` come from
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ArgsKind {
+    /// `#[struct_to_gts_schema(...)]` on a struct
+    Struct,
+    /// `#[struct_to_gts_schema(...)]` on an enum, which takes no `properties`
+    Enum,
+    /// The `#[gts(...)]` container attribute of `#[derive(GtsSchema)]`, which
+    /// takes no `properties` and defaults to `static_schema = true`
+    Derive,
+}
+
+impl GtsSchemaArgs {
+    /// Parse the arguments of `#[struct_to_gts_schema(...)]` or `#[gts(...)]`
+    fn parse_with(input: ParseStream, kind: ArgsKind) -> syn::Result<Self> {
         let mut dir_path: Option<String> = None;
         let mut schema_id: Option<String> = None;
         let mut description: Option<String> = None;
         let mut properties: Option<String> = None;
         let mut base: Option<BaseAttr> = None;
+        let mut static_schema = kind == ArgsKind::Derive;
 
         while !input.is_empty() {
             let key: syn::Ident = input.parse()?;
@@ -666,6 +684,7 @@ impl Parse for GtsSchemaArgs {
                             gts_id::GtsIdError::Segment { num, cause, .. } => {
                                 format!("Segment #{num}: {cause}")
                             }
+                            reserved @ gts_id::GtsIdError::Reserved { .. } => reserved.to_string(),
                         };
                         return Err(syn::Error::new_spanned(
                             value,
@@ -678,6 +697,18 @@ impl Parse for GtsSchemaArgs {
                     let value: LitStr = input.parse()?;
                     description = Some(value.value());
                 }
+                "properties" if kind == ArgsKind::Derive => {
+                    return Err(syn::Error::new_spanned(
+                        key,
+                        "derive(GtsSchema): every field is a property; leave fields out with #[gts(skip)]",
+                    ));
+                }
+                "properties" if kind == ArgsKind::Enum => {
+                    return Err(syn::Error::new_spanned(
+                        key,
+                        "struct_to_gts_schema: every variant of an enum is part of its schema; remove properties",
+                    ));
+                }
                 "properties" => {
                     let value: LitStr = input.parse()?;
                     properties = Some(value.value());
@@ -698,7 +729,15 @@ impl Parse for GtsSchemaArgs {
                     } else if input.peek(syn::Ident) {
                         // Parse parent struct name - the macro automatically adds <()>
                         let ident: syn::Ident = input.parse()?;
-                        base = Some(BaseAttr::Parent(ident));
+                        // Optionally the parent's generic field: `ParentStruct(field)`
+                        let field = if input.peek(syn::token::Paren) {
+                            let content;
+                            syn::parenthesized!(content in input);
+                            Some(content.parse()?)
+                        } else {
+                            None
+                        };
+                        base = Some(BaseAttr::Parent(ident, field));
                     } else {
                         return Err(syn::Error::new_spanned(
                             key,
@@ -706,10 +745,14 @@ impl Parse for GtsSchemaArgs {
                         ));
                     }
                 }
+                "static_schema" => {
+                    let lit: syn::LitBool = input.parse()?;
+                    static_schema = lit.value;
+                }
                 _ => {
                     return Err(syn::Error::new_spanned(
                         key,
-                        "Unknown attribute. Expected: dir_path, schema_id, description, properties, or base",
+                        "Unknown attribute. Expected: dir_path, schema_id, description, properties, base, or static_schema",
                     ));
                 }
             }
@@ -726,10 +769,15 @@ impl Parse for GtsSchemaArgs {
                 .ok_or_else(|| input.error("Missing required attribute: schema_id"))?,
             description: description
                 .ok_or_else(|| input.error("Missing required attribute: description"))?,
-            properties: properties
-                .ok_or_else(|| input.error("Missing required attribute: properties"))?,
+            properties: match properties {
+                Some(properties) => properties,
+                // Filled in from the fields by the derive; enums have none
+                None if kind != ArgsKind::Struct => String::new(),
+                None => return Err(input.error("Missing required attribute: properties")),
+            },
             base: base
                 .ok_or_else(|| input.error("Missing required attribute: base (use 'base = true' for base types or 'base = ParentStruct' for child types)"))?,
+            static_schema,
         })
     }
 }
@@ -743,8 +791,9 @@ impl Parse for GtsSchemaArgs {
 /// The macro validates your annotations at compile time, catching errors early:
 /// - ✅ All required attributes exist (`dir_path`, `schema_id`, `description`, `properties`)
 /// - ✅ Every property in `properties` exists as a field in the struct
-/// - ✅ Only structs with named fields are supported (no tuple/unit structs or enums)
-/// - ✅ Single generic parameter maximum (prevents inheritance ambiguity)
+/// - ✅ Only structs with named fields, unit structs and enums are supported (no tuple
+///   structs or tuple variants)
+/// - ✅ Every generic parameter of a struct with several is the type of a field
 /// - ✅ Valid GTS ID format enforcement
 /// - ✅ Zero runtime allocation for generated constants
 ///
@@ -788,6 +837,20 @@ impl Parse for GtsSchemaArgs {
 /// * `base` - Explicit base/parent struct declaration (required):
 ///   - `base = true`: Marks this struct as a base type (must have single-segment `schema_id`)
 ///   - `base = ParentStruct`: Parent struct name (macro automatically uses `ParentStruct<()>`)
+/// * `static_schema` - Optional, `false` by default. With `static_schema = true` the struct's
+///   schema is built at compile time from its field types and embedded as a JSON string, so no
+///   `JsonSchema` derive or bound is added. Supported field types: `bool`, integers, floats,
+///   `char`, `String`/`&str`, `Uuid`, `serde_json::Value`, `GtsInstanceId`, `GtsSchemaId`,
+///   the generic parameter, and `Option`, `Box`, `Arc`, `Rc`, `Vec`, `VecDeque`, sets and
+///   `String`-keyed maps of these. Generic arguments of such a struct must use it as well.
+///
+/// # Enums
+///
+/// Enums are base types and take no `properties`. An enum of unit variants is a
+/// string enum in its schema; one with data variants is internally tagged
+/// (`#[serde(tag = "type")]` is added unless the enum names a tag) and its schema is
+/// a `oneOf` of one object per variant, with the tag as `discriminator`. Variant
+/// fields take the `static_schema` types.
 ///
 /// # Memory Efficiency
 ///
@@ -820,30 +883,41 @@ impl Parse for GtsSchemaArgs {
 /// assert_eq!(instance_id.as_ref(), "gts.x.core.events.topic.v1~vendor.marketplace.orders.order_created.v1");
 /// ```
 #[proc_macro_attribute]
+pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let kind = match &input.data {
+        Data::Enum(_) => ArgsKind::Enum,
+        _ => ArgsKind::Struct,
+    };
+    let parser = |input: ParseStream| GtsSchemaArgs::parse_with(input, kind);
+    let args = parse_macro_input!(attr with parser);
+    match &input.data {
+        Data::Enum(data) => enum_schema::expand(&args, &input, data)
+            .unwrap_or_else(syn::Error::into_compile_error)
+            .into(),
+        _ => expand_gts_schema(&args, &input, None),
+    }
+}
+
+/// The struct (unless derived) and the `GtsSchema` impls for `input`.
+///
+/// The attribute macro rewrites the struct (derives, serde attributes); the
+/// derive cannot, so with `derive` set only the impls are emitted and the
+/// per-field `#[gts(...)]` attributes are applied to the struct's own schema.
 #[allow(
     clippy::too_many_lines,
     clippy::missing_panics_doc,
     clippy::cognitive_complexity
 )]
-pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(attr as GtsSchemaArgs);
-    let input = parse_macro_input!(item as DeriveInput);
-
-    // Prohibit multiple type generic parameters (GTS notation assumes nested segments)
-    let generic_count = input.generics.type_params().count();
-    if generic_count > 1 {
-        return syn::Error::new_spanned(
-            &input.ident,
-            "struct_to_gts_schema: Multiple type generic parameters are not supported (GTS schemas assume nested segments)",
-        )
-        .to_compile_error()
-        .into();
-    }
-
-    // base = true can have 0 or 1 generic field:
+fn expand_gts_schema(
+    args: &GtsSchemaArgs,
+    input: &DeriveInput,
+    derive: Option<&[derive::FieldAttrs]>,
+) -> TokenStream {
+    // A struct can have any number of generic fields:
     // - 0 generics: This is a leaf/terminal type, no derived structs can extend it
-    // - 1 generic: Derived structs can extend via the generic field
-    // (validation that base = ParentStruct requires parent to have 1 generic is done later via compile-time assertion)
+    // - 1 or more generics: Derived structs extend it via one of the generic fields
+    // (validation that base = ParentStruct requires a generic field in the parent is done later via compile-time assertion)
 
     // Parse properties list
     let property_names: Vec<String> = args
@@ -884,7 +958,7 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
         _ => {
             return syn::Error::new_spanned(
                 &input.ident,
-                "struct_to_gts_schema: Only structs are supported",
+                "struct_to_gts_schema: Only structs and enums are supported",
             )
             .to_compile_error()
             .into();
@@ -912,7 +986,7 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
         }
 
         // Validate base struct field requirements
-        if let Err(err) = validate_base_struct_fields(&input, fields, &args) {
+        if let Err(err) = validate_base_struct_fields(input, fields, args) {
             return err.to_compile_error().into();
         }
     }
@@ -926,14 +1000,21 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
     // (those with struct_to_gts_schema applied, or ()) can be used as generic args.
     // This prevents usage like BaseEventV1<SomeRandomStruct> where SomeRandomStruct
     // is not a proper GTS schema type.
+    // With several generic parameters, each defaults to (), so that children
+    // can name the parent as `ParentStruct<()>` like a single-generic one.
+    let several_generics = input.generics.type_params().count() > 1;
     let mut modified_input = input.clone();
     for param in modified_input.generics.type_params_mut() {
         param.bounds.push(syn::parse_quote!(::gts::GtsSchema));
+        if several_generics && param.default.is_none() {
+            param.eq_token = Some(syn::parse_quote!(=));
+            param.default = Some(syn::parse_quote!(()));
+        }
     }
 
     // For nested structs, remove Serialize/Deserialize to prevent direct serialization
     // This is critical for Issue #24: nested structs can only be serialized through base struct
-    if matches!(&args.base, BaseAttr::Parent(_)) {
+    if matches!(&args.base, BaseAttr::Parent(..)) {
         if has_serde_derives(&input.attrs) {
             return syn::Error::new_spanned(
                 &input.ident,
@@ -946,15 +1027,18 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
         remove_derives(&mut modified_input, &["Serialize", "Deserialize"]);
     }
 
-    // Automatically add required derives: Serialize, Deserialize, JsonSchema
-    // For nested structs, only JsonSchema is added (no direct serialization)
-    add_missing_derives(&mut modified_input, &args.base);
+    // A derive cannot touch the struct, so its derives and serde attributes are the user's
+    if derive.is_none() {
+        // Automatically add required derives: Serialize, Deserialize, JsonSchema
+        // For nested structs, only JsonSchema is added (no direct serialization)
+        add_missing_derives(&mut modified_input, &args.base, args.static_schema);
 
-    // For base structs with generic fields, add serde attributes for GtsSerialize/GtsDeserialize
-    add_gts_serde_attrs(&mut modified_input, &args.base);
+        // For base structs with generic fields, add serde attributes for GtsSerialize/GtsDeserialize
+        add_gts_serde_attrs(&mut modified_input, &args.base);
+    }
 
     // Validate base attribute consistency with schema_id segments
-    if let Err(err) = validate_base_segments(&input, &args.base, &args.schema_id) {
+    if let Err(err) = validate_base_segments(input, &args.base, &args.schema_id) {
         return err.to_compile_error().into();
     }
     let expected_parent_schema_id = extract_parent_schema_id(&args.schema_id);
@@ -973,31 +1057,50 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
     let generics = &modified_input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    // Get the generic type parameter name if present
-    let generic_param_name: Option<String> = input
-        .generics
-        .type_params()
-        .next()
-        .map(|tp| tp.ident.to_string());
-
-    let mut generic_field_name: Option<String> = None;
+    // Get the generic type parameter names, in declaration order
+    let generic_params: Vec<&syn::Ident> =
+        input.generics.type_params().map(|tp| &tp.ident).collect();
+    let generic_param_names: Vec<String> = generic_params.iter().map(ToString::to_string).collect();
 
-    // Find the field that uses the generic type (only for structs with fields)
+    // Find the field that uses each generic type (only for structs with fields)
     // Use the SERIALIZED name (serde rename if present, otherwise field ident)
-    if let (Some(gp), Some(fields)) = (&generic_param_name, struct_fields) {
-        for field in fields {
-            let field_type = &field.ty;
-            let field_type_str = quote::quote!(#field_type).to_string().replace(' ', "");
-            if field_type_str == *gp
-                && let Some(ident) = &field.ident
-            {
+    let generic_field_names: Vec<Option<String>> = generic_param_names
+        .iter()
+        .map(|gp| {
+            struct_fields.into_iter().flatten().find_map(|field| {
+                let field_type = &field.ty;
+                let field_type_str = quote::quote!(#field_type).to_string().replace(' ', "");
+                let ident = field.ident.as_ref()?;
                 // Use serde rename if present, otherwise use the field identifier
-                generic_field_name =
-                    Some(get_serde_rename(field).unwrap_or_else(|| ident.to_string()));
-                break;
-            }
-        }
+                (field_type_str == *gp)
+                    .then(|| get_serde_rename(field).unwrap_or_else(|| ident.to_string()))
+            })
+        })
+        .collect();
+
+    // Children of a struct with several generic parameters pick one by field name
+    if several_generics
+        && let Some(gp) = generic_params
+            .iter()
+            .zip(&generic_field_names)
+            .find_map(|(gp, field)| field.is_none().then_some(gp))
+    {
+        return syn::Error::new_spanned(
+            gp,
+            format!(
+                "struct_to_gts_schema: Generic parameter '{gp}' must be the type of a field \
+                 when a struct has several generic parameters"
+            ),
+        )
+        .to_compile_error()
+        .into();
     }
+    let generic_field_name: Option<String> = generic_field_names.first().cloned().flatten();
+    let generic_fields: Vec<&str> = generic_field_names
+        .iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
 
     // Generate the GENERIC_FIELD constant value
     let generic_field_option = if let Some(ref field_name) = generic_field_name {
@@ -1032,7 +1135,7 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
 
     // Generate compile-time assertion when base = ParentStruct
     let base_assertion = match &args.base {
-        BaseAttr::Parent(parent_ident) => {
+        BaseAttr::Parent(parent_ident, parent_field) => {
             let parent_id = expected_parent_schema_id
                 .as_ref()
                 .expect("parent_id must exist when base is specified");
@@ -1043,6 +1146,37 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
                 "struct_to_gts_schema: Base struct '{parent_ident}' must have exactly 1 generic field. \
                  Parent types must define a generic field (e.g., `pub payload: P`) that child types extend."
             );
+            // Compile-time assertion: verify the named field is one of the parent's generic fields
+            let parent_field_assertion = parent_field.as_ref().map(|field| {
+                let field = field.to_string();
+                let parent_field_assertion_msg = format!(
+                    "struct_to_gts_schema: Base struct '{parent_ident}' has no generic field '{field}'"
+                );
+                quote! {
+                    const _: () = {
+                        const FIELDS: &[&str] = <#parent_ident<()> as ::gts::GtsSchema>::GENERIC_FIELDS;
+                        const FIELD: &str = #field;
+                        let mut found = false;
+                        let mut i = 0;
+                        while i < FIELDS.len() {
+                            let candidate = FIELDS[i].as_bytes();
+                            if candidate.len() == FIELD.len() {
+                                let mut j = 0;
+                                while j < candidate.len() && candidate[j] == FIELD.as_bytes()[j] {
+                                    j += 1;
+                                }
+                                if j == candidate.len() {
+                                    found = true;
+                                }
+                            }
+                            i += 1;
+                        }
+                        if !found {
+                            panic!(#parent_field_assertion_msg);
+                        }
+                    };
+                }
+            });
             quote! {
                 // Compile-time assertion: verify parent struct's GTS_SCHEMA_ID matches expected parent segment
                 // We use <ParentStruct<()> as GtsSchema> since all GTS structs must be generic
@@ -1073,6 +1207,8 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
                         panic!(#generic_field_assertion_msg);
                     }
                 };
+
+                #parent_field_assertion
             }
         }
         BaseAttr::IsBase => quote! {},
@@ -1085,8 +1221,48 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
     let gts_schema_where_clause = build_where_clause(
         generics,
         where_clause,
-        "::gts::GtsSchema + ::schemars::JsonSchema",
+        if args.static_schema {
+            "::gts::GtsSchema"
+        } else {
+            "::gts::GtsSchema + ::schemars::JsonSchema"
+        },
     );
+
+    // This struct's own schema (`properties`/`required`), from schemars or,
+    // with static_schema, a JSON literal built from the field types right here
+    let derived_fields: Option<syn::punctuated::Punctuated<syn::Field, Token![,]>> =
+        derive.map(|attrs| {
+            struct_fields
+                .into_iter()
+                .flatten()
+                .filter(|field| !derive::FieldAttrs::skips(attrs, field))
+                .cloned()
+                .collect()
+        });
+    let own_schema = if args.static_schema {
+        let json = match static_schema::build(
+            derived_fields.as_ref().or(struct_fields),
+            &generic_param_names,
+            get_serde_rename,
+        ) {
+            Ok(json) => json,
+            Err(err) => return err.to_compile_error().into(),
+        };
+        quote! { serde_json::from_str::<serde_json::Value>(#json).expect("static schema") }
+    } else {
+        quote! { serde_json::to_value(&schemars::schema_for!(Self)).expect("schemars") }
+    };
+    let own_schema = match derive {
+        Some(attrs) => {
+            let overrides = derive::schema_overrides(attrs);
+            quote! {{
+                let mut schema = #own_schema;
+                #overrides
+                schema
+            }}
+        }
+        None => own_schema,
+    };
     let serialize_where_clause = build_where_clause(
         generics,
         where_clause,
@@ -1094,9 +1270,12 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
     );
 
     let gts_schema_impl = if has_generic {
-        let generic_param = input.generics.type_params().next().unwrap();
-        let generic_ident = &generic_param.ident;
-        let generic_field_for_path = generic_field_name.as_deref().unwrap_or_default();
+        // With several generic parameters, the innermost type and the nesting path
+        // follow the first one instantiated with a GTS type (not ())
+        let generic_fields_for_path: Vec<&str> = generic_field_names
+            .iter()
+            .map(|field| field.as_deref().unwrap_or_default())
+            .collect();
 
         quote! {
             fn gts_schema() -> serde_json::Value {
@@ -1105,24 +1284,26 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
 
             fn innermost_schema_id() -> &'static str {
                 // Recursively get the innermost type's schema ID
-                let inner_id = <#generic_ident as ::gts::GtsSchema>::innermost_schema_id();
-                if inner_id.is_empty() {
-                    Self::SCHEMA_ID
-                } else {
-                    inner_id
-                }
+                #(
+                    let inner_id = <#generic_params as ::gts::GtsSchema>::innermost_schema_id();
+                    if !inner_id.is_empty() {
+                        return inner_id;
+                    }
+                )*
+                Self::SCHEMA_ID
             }
 
             fn innermost_schema() -> serde_json::Value {
                 // Get the innermost type's raw schemars schema
-                let inner = <#generic_ident as ::gts::GtsSchema>::innermost_schema();
-                // If inner is just {"type": "object"} (from ()), return our own schema
+                #(
+                    let inner = <#generic_params as ::gts::GtsSchema>::innermost_schema();
+                    if inner.get("properties").is_some() {
+                        return inner;
+                    }
+                )*
+                // If every inner type is just {"type": "object"} (from ()), return our own schema
                 // schemars RootSchema serializes at root level (not under "schema" field)
-                if inner.get("properties").is_none() {
-                    let root_schema = schemars::schema_for!(Self);
-                    return serde_json::to_value(&root_schema).expect("schemars");
-                }
-                inner
+                #own_schema
             }
 
             fn collect_nesting_path() -> Vec<&'static str> {
@@ -1133,24 +1314,28 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
                 // For Outer<Middle<Inner>> where Inner has properties:
                 //   - Inner is the innermost type with properties
                 //   - Path is ["a", "b"]
+                Self::collect_nesting_paths().into_iter().next().unwrap_or_default()
+            }
 
-                let inner_path = <#generic_ident as ::gts::GtsSchema>::collect_nesting_path();
-                let inner_id = <#generic_ident as ::gts::GtsSchema>::SCHEMA_ID;
-
-                // If inner type is () (empty ID), don't include this type's field
-                // because this type IS the innermost type with properties
-                if inner_id.is_empty() {
-                    return Vec::new();
-                }
-
-                // Otherwise, prepend this type's generic field to inner path
-                let mut path = Vec::new();
-                let field = #generic_field_for_path;
-                if !field.is_empty() {
-                    path.push(field);
-                }
-                path.extend(inner_path);
-                path
+            fn collect_nesting_paths() -> Vec<Vec<&'static str>> {
+                // One path per generic field whose type is not () (empty ID); a () field
+                // is not nested into. Each prepends this type's field to the inner paths.
+                let mut paths = Vec::new();
+                #(
+                    if !<#generic_params as ::gts::GtsSchema>::SCHEMA_ID.is_empty() {
+                        let field = #generic_fields_for_path;
+                        let inner_paths = <#generic_params as ::gts::GtsSchema>::collect_nesting_paths();
+                        for inner_path in if inner_paths.is_empty() { vec![Vec::new()] } else { inner_paths } {
+                            let mut path = Vec::new();
+                            if !field.is_empty() {
+                                path.push(field);
+                            }
+                            path.extend(inner_path);
+                            paths.push(path);
+                        }
+                    }
+                )*
+                paths
             }
 
             fn gts_schema_with_refs_allof() -> serde_json::Value {
@@ -1173,17 +1358,16 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
                 };
 
                 // Get THIS struct's schema (schemars will expand generic fields automatically)
-                let root_schema = schemars::schema_for!(Self);
-                let schema_val = serde_json::to_value(&root_schema).expect("schemars");
+                let schema_val = #own_schema;
                 let mut properties = schema_val.get("properties").cloned().unwrap_or(serde_json::json!({}));
                 let required = schema_val.get("required").cloned().unwrap_or(serde_json::json!([]));
 
-                // Replace the generic field with a simple {"type": "object"} placeholder
-                // The generic field should not be expanded, regardless of the concrete type parameter
-                if let Some(generic_field) = Self::GENERIC_FIELD {
-                    if let Some(props) = properties.as_object_mut() {
-                        if props.contains_key(generic_field) {
-                            props.insert(generic_field.to_owned(), serde_json::json!({
+                // Replace the generic fields with a simple {"type": "object"} placeholder
+                // The generic fields should not be expanded, regardless of the concrete type parameters
+                if let Some(props) = properties.as_object_mut() {
+                    for generic_field in Self::GENERIC_FIELDS {
+                        if props.contains_key(*generic_field) {
+                            props.insert((*generic_field).to_owned(), serde_json::json!({
                                 "type": "object"
                             }));
                         }
@@ -1214,9 +1398,18 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
                 //   - path ["a", "b"] wraps Inner's properties
                 let nesting_path = Self::collect_nesting_path();
 
-                // Get the generic field name for the innermost type (if it has one)
+                // Get the generic field name for the innermost type (if it has one), the one
+                // the nesting path follows
                 // This field should NOT have additionalProperties: false since it will be extended
-                let innermost_generic_field = <#generic_ident as ::gts::GtsSchema>::GENERIC_FIELD;
+                let inner_types = [#((
+                    <#generic_params as ::gts::GtsSchema>::SCHEMA_ID,
+                    <#generic_params as ::gts::GtsSchema>::GENERIC_FIELD,
+                )),*];
+                let innermost_generic_field = inner_types
+                    .iter()
+                    .find(|(id, _)| !id.is_empty())
+                    .unwrap_or(&inner_types[0])
+                    .1;
 
                 // Wrap properties in the nesting path
                 let nested_properties = Self::wrap_in_nesting_path(&nesting_path, properties, required.clone(), innermost_generic_field);
@@ -1241,7 +1434,14 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
         // For non-generic child types extending a generic base, we need to get the parent's
         // generic field name at compile time to properly nest the child properties
         let parent_generic_field_code = match &args.base {
-            BaseAttr::Parent(parent_ident) => {
+            BaseAttr::Parent(_, Some(parent_field)) => {
+                let parent_field = parent_field.to_string();
+                quote! {
+                    // The parent's generic field this type extends, named in `base`
+                    let parent_generic_field: Option<&'static str> = Some(#parent_field);
+                }
+            }
+            BaseAttr::Parent(parent_ident, None) => {
                 quote! {
                     // Get the parent's generic field name for nesting
                     let parent_generic_field: Option<&'static str> = <#parent_ident<()> as ::gts::GtsSchema>::GENERIC_FIELD;
@@ -1263,8 +1463,7 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
             }
             fn innermost_schema() -> serde_json::Value {
                 // Return this type's schemars schema (RootSchema serializes at root level)
-                let root_schema = schemars::schema_for!(Self);
-                serde_json::to_value(&root_schema).expect("schemars")
+                #own_schema
             }
             fn gts_schema_with_refs_allof() -> serde_json::Value {
                 let schema_id = Self::SCHEMA_ID;
@@ -1282,8 +1481,7 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
                 };
 
                 // Get this type's schemars schema (RootSchema serializes at root level)
-                let root_schema = schemars::schema_for!(Self);
-                let schema_val = serde_json::to_value(&root_schema).expect("schemars");
+                let schema_val = #own_schema;
                 let mut properties = schema_val.get("properties").cloned().unwrap_or_else(|| serde_json::json!({}));
                 let required = schema_val.get("required").cloned().unwrap_or_else(|| serde_json::json!([]));
 
@@ -1377,9 +1575,11 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
         });
 
         // Add just JsonSchema for unit structs (Serialize/Deserialize are custom impl'd below)
-        modified_input
-            .attrs
-            .push(syn::parse_quote!(#[derive(schemars::JsonSchema)]));
+        if !args.static_schema {
+            modified_input
+                .attrs
+                .push(syn::parse_quote!(#[derive(schemars::JsonSchema)]));
+        }
     }
 
     // Generate custom serialization implementation for BASE unit structs to serialize as {} instead of null
@@ -1445,7 +1645,7 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
 
     // Generate GtsSerialize/GtsDeserialize impls for nested structs (base = ParentStruct)
     // These structs don't have Serialize/Deserialize derives, so they need explicit impls
-    let gts_serialize_impl = if matches!(&args.base, BaseAttr::Parent(_)) && !is_unit_struct {
+    let gts_serialize_impl = if matches!(&args.base, BaseAttr::Parent(..)) && !is_unit_struct {
         // Collect field information for serialization, including whether each field is the generic type
         let fields_for_serialize: Vec<_> = struct_fields
             .map(|fields| {
@@ -1454,15 +1654,15 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
                     .filter_map(|field| {
                         let ident = field.ident.as_ref()?;
                         // Use serde rename if present, otherwise use field name
-                        let serialize_name =
-                            get_serde_rename(field).unwrap_or_else(|| ident.to_string());
+                        let serialize_name = derive
+                            .and_then(|attrs| derive::FieldAttrs::rename_of(attrs, field))
+                            .or_else(|| get_serde_rename(field))
+                            .unwrap_or_else(|| ident.to_string());
                         // Check if this field's type is the generic parameter
-                        let is_generic = generic_param_name.as_ref().is_some_and(|gp| {
-                            let field_type = &field.ty;
-                            let field_type_str =
-                                quote::quote!(#field_type).to_string().replace(' ', "");
-                            field_type_str == *gp
-                        });
+                        let field_type = &field.ty;
+                        let field_type_str =
+                            quote::quote!(#field_type).to_string().replace(' ', "");
+                        let is_generic = generic_param_names.contains(&field_type_str);
                         Some((ident.clone(), serialize_name, is_generic))
                     })
                     .collect()
@@ -1514,40 +1714,21 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
         };
 
         // Build base where clause that includes GtsSchema bound (required by struct definition)
-        let gts_schema_where = if let Some(ref gp) = generic_param_name {
-            let gp_ident: syn::Ident = syn::parse_str(gp).expect("valid ident");
-            if let Some(existing) = where_clause {
-                quote! { #existing #gp_ident: ::gts::GtsSchema, }
-            } else {
-                quote! { where #gp_ident: ::gts::GtsSchema }
-            }
-        } else {
-            quote! { #where_clause }
-        };
+        let gts_schema_where = build_where_clause(generics, where_clause, "::gts::GtsSchema");
 
         // Build where clause for GtsSerialize that includes GtsSchema + GtsSerialize bounds
-        let gts_serialize_where = if let Some(ref gp) = generic_param_name {
-            let gp_ident: syn::Ident = syn::parse_str(gp).expect("valid ident");
-            if let Some(existing) = where_clause {
-                quote! { #existing #gp_ident: ::gts::GtsSchema + ::gts::GtsSerialize, }
-            } else {
-                quote! { where #gp_ident: ::gts::GtsSchema + ::gts::GtsSerialize }
-            }
-        } else {
-            quote! { #where_clause }
-        };
+        let gts_serialize_where = build_where_clause(
+            generics,
+            where_clause,
+            "::gts::GtsSchema + ::gts::GtsSerialize",
+        );
 
         // Build where clause for GtsDeserialize that includes GtsSchema + GtsDeserialize bounds
-        let gts_deserialize_where = if let Some(ref gp) = generic_param_name {
-            let gp_ident: syn::Ident = syn::parse_str(gp).expect("valid ident");
-            if let Some(existing) = where_clause {
-                quote! { #existing #gp_ident: ::gts::GtsSchema + ::gts::GtsDeserialize<'de>, }
-            } else {
-                quote! { where #gp_ident: ::gts::GtsSchema + ::gts::GtsDeserialize<'de> }
-            }
-        } else {
-            quote! { #where_clause }
-        };
+        let gts_deserialize_where = build_where_clause(
+            generics,
+            where_clause,
+            "::gts::GtsSchema + ::gts::GtsDeserialize<'de>",
+        );
 
         // Generate field visit code - for generic fields, use GtsDeserializeWrapper
         let field_visit_code: Vec<_> = fields_for_serialize
@@ -1601,9 +1782,9 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
 
                     #[allow(non_camel_case_types)]
                     #[derive(serde::Deserialize)]
-                    #[serde(field_identifier, rename_all = "snake_case")]
+                    #[serde(field_identifier)]
                     enum Field {
-                        #(#field_idents,)*
+                        #(#[serde(rename = #field_names)] #field_idents,)*
                         #[serde(other)]
                         Unknown,
                     }
@@ -1646,7 +1827,7 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
                 }
             }
         }
-    } else if matches!(&args.base, BaseAttr::Parent(_)) && is_unit_struct {
+    } else if matches!(&args.base, BaseAttr::Parent(..)) && is_unit_struct {
         // Unit struct nested type - simple impls
         quote! {
             impl ::gts::GtsSerialize for #struct_name {
@@ -1702,7 +1883,7 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
 
     // Block direct serde Serialize/Deserialize impls on nested structs.
     // If a user tries to add them manually, this will conflict with the blanket impls.
-    let no_direct_serde_impl = if matches!(&args.base, BaseAttr::Parent(_)) {
+    let no_direct_serde_impl = if matches!(&args.base, BaseAttr::Parent(..)) {
         quote! {
             impl #impl_generics ::gts::GtsNoDirectSerialize for #struct_name #ty_generics #where_clause {}
             impl #impl_generics ::gts::GtsNoDirectDeserialize for #struct_name #ty_generics #where_clause {}
@@ -1713,7 +1894,7 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
 
     // For nested structs, we don't generate instance serialization methods (gts_instance_json, etc.)
     // because they don't have Serialize. Instead, they must be serialized through their base struct.
-    let instance_methods_impl = if matches!(&args.base, BaseAttr::Parent(_)) {
+    let instance_methods_impl = if matches!(&args.base, BaseAttr::Parent(..)) {
         quote! {}
     } else {
         quote! {
@@ -1743,8 +1924,14 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
         }
     };
 
+    let struct_item = if derive.is_none() {
+        quote! { #modified_input }
+    } else {
+        quote! {}
+    };
+
     let expanded = quote! {
-        #modified_input
+        #struct_item
 
         // Compile-time assertion for base struct matching (if specified)
         #base_assertion
@@ -1807,6 +1994,7 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
         impl #impl_generics ::gts::GtsSchema for #struct_name #ty_generics #gts_schema_where_clause {
             const SCHEMA_ID: &'static str = #schema_id;
             const GENERIC_FIELD: Option<&'static str> = #generic_field_option;
+            const GENERIC_FIELDS: &'static [&'static str] = &[#(#generic_fields),*];
 
             fn gts_schema_with_refs() -> serde_json::Value {
                 Self::gts_schema_with_refs_allof()
@@ -1840,3 +2028,182 @@ pub fn struct_to_gts_schema(attr: TokenStream, item: TokenStream) -> TokenStream
 
     TokenStream::from(expanded)
 }
+
+/// Validate a GTS identifier at compile time and expand to it as a `&'static str`.
+///
+/// The literal must be a complete, valid GTS ID (schema or instance; no
+/// wildcards, surrounding whitespace or `gts://` prefix), so a typo in a
+/// hard-coded ID fails the build instead of a lookup at runtime.
+///
+/// ```
+/// use gts_macros::gts_id;
+///
+/// const ORDER_PLACED: &str = gts_id!("gts.x.core.events.type.v1~x.commerce.orders.placed.v1~");
+/// assert!(ORDER_PLACED.ends_with('~'));
+/// ```
+///
+/// ```compile_fail
+/// // Segment #1: Too few tokens
+/// let id = gts_macros::gts_id!("gts.x.core.events.v1~");
+/// ```
+#[proc_macro]
+pub fn gts_id(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    let id = literal.value();
+    match gts_id::validate_gts_id_with_options(&id, false, gts_id::GtsIdValidationOptions::strict())
+    {
+        Ok(_) => quote!(#literal).into(),
+        Err(e) => syn::Error::new_spanned(&literal, format!("gts_id!: {e}"))
+            .to_compile_error()
+            .into(),
+    }
+}
+
+/// Derive the GTS schema of a struct from all of its fields.
+///
+/// The alternative to [`macro@struct_to_gts_schema`] without a property list to
+/// keep in sync: `#[gts(...)]` on the struct takes the same arguments except
+/// `properties` (`static_schema` defaults to `true`), and every named field is
+/// a property unless marked `#[gts(skip)]`. Fields take
+/// `#[gts(description = "...")]`, `#[gts(format = "...")]` and
+/// `#[gts(rename = "...")]`; `#[serde(rename)]` is honoured as before.
+///
+/// The struct is left as written, so it derives `Serialize`/`Deserialize`
+/// (and `schemars::JsonSchema` with `static_schema = false`) itself; nested
+/// structs get the same `GtsSerialize`/`GtsDeserialize` impls as with the
+/// attribute, which follow `#[gts(rename)]`. Generic structs still need the
+/// attribute macro.
+///
+/// ```ignore
+/// use gts_macros::GtsSchema;
+///
+/// #[derive(Debug, serde::Serialize, serde::Deserialize, GtsSchema)]
+/// #[gts(
+///     dir_path = "schemas",
+///     base = true,
+///     schema_id = "gts.x.app.entities.user.v1~",
+///     description = "User account"
+/// )]
+/// pub struct UserV1 {
+///     pub id: gts::GtsInstanceId,
+///     #[gts(format = "email", description = "Login address")]
+///     pub email: String,
+///     #[serde(skip)]
+///     #[gts(skip)]
+///     pub session_cache: Option<String>,
+/// }
+/// ```
+#[proc_macro_derive(GtsSchema, attributes(gts))]
+pub fn derive_gts_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match derive::parse(&input) {
+        Ok((args, fields)) => expand_gts_schema(&args, &input, Some(&fields)),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Derive a GTS ID newtype that only accepts IDs of one type family.
+///
+/// Applies to `struct Name(GtsID);` with a `#[gts_typed_id(pattern = "...")]`
+/// attribute holding a wildcard pattern (or exact ID), validated at compile
+/// time. Generates `Name::new`, `as_str`, `into_gts_id`, `FromStr`,
+/// `TryFrom<&str>`/`TryFrom<String>`, `Display`, `AsRef<str>`,
+/// `From<Name> for GtsID`, `gts::GtsTypedId`, and serde impls (as a string,
+/// checked on deserialization), so a signature can require e.g. a topic ID:
+///
+/// ```ignore
+/// use gts::GtsID;
+/// use gts_macros::GtsTypedId;
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash, GtsTypedId)]
+/// #[gts_typed_id(pattern = "gts.x.core.events.topic.v1~*")]
+/// pub struct TopicId(GtsID);
+///
+/// fn subscribe(topic: &TopicId) { /* ... */ }
+///
+/// let topic = TopicId::new("gts.x.core.events.topic.v1~x.commerce._.orders.v1")?;
+/// subscribe(&topic);
+/// assert!(TopicId::new("gts.x.core.events.type.v1~x.commerce._.orders.v1").is_err());
+/// ```
+#[proc_macro_derive(GtsTypedId, attributes(gts_typed_id))]
+pub fn derive_gts_typed_id(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    typed_id::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Embed a well-known instance, checked at compile time.
+///
+/// Applies to a `const` holding the instance JSON as a string literal. The
+/// `id` must be a valid instance ID, and the JSON an object whose `id`, if it
+/// has one, is that ID. The constant becomes a `gts::GtsWellKnownInstance`
+/// with the `id` and the JSON as written, so the instance is available at
+/// runtime without a file to load:
+///
+/// ```ignore
+/// use gts_macros::gts_well_known_instance;
+///
+/// #[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.orders.v1")]
+/// pub const ORDERS_TOPIC: &str = r#"{"name": "orders", "retention": "P30D"}"#;
+///
+/// assert_eq!(ORDERS_TOPIC.id, "gts.x.core.events.topic.v1~x.commerce._.orders.v1");
+/// assert_eq!(ORDERS_TOPIC.value()?["name"], "orders");
+/// ```
+///
+/// On a `fn() -> serde_json::Value` the function is kept and a constant named
+/// after it in upper case is added, whose JSON comes from calling it. The
+/// body can then use loops and shared constants; it is checked when
+/// `value()` is called:
+///
+/// ```ignore
+/// #[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.payments.v1")]
+/// pub fn payments_topic() -> serde_json::Value {
+///     serde_json::json!({"name": "payments", "retention": DEFAULT_RETENTION})
+/// }
+///
+/// assert_eq!(PAYMENTS_TOPIC.value()?["name"], "payments");
+/// ```
+///
+/// The `const` can also hold a [`gts_instance_json!`] invocation. An object
+/// written with literals only is checked like the string form; one using
+/// loops or shared constants is checked when `value()` is called:
+///
+/// ```ignore
+/// use gts_macros::gts_instance_json;
+///
+/// #[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.refunds.v1")]
+/// pub const REFUNDS_TOPIC: gts::GtsWellKnownBody = gts_instance_json! {
+///     "name": "refunds",
+///     "retention": DEFAULT_RETENTION,
+///     "partitions": (0..3).map(|n| format!("refunds-{n}")).collect::<Vec<_>>()
+/// };
+/// ```
+#[proc_macro_attribute]
+pub fn gts_well_known_instance(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as syn::Item);
+    well_known::expand(attr.into(), item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Build the JSON body of a well-known instance.
+///
+/// Takes a JSON object in `serde_json::json!` syntax, with or without its
+/// outer braces, and expands to a `gts::GtsWellKnownBody`: the JSON as
+/// written when the object only holds literals, otherwise a function
+/// evaluating it, so values can come from constants and expressions. Meant
+/// for a `const` annotated with [`macro@gts_well_known_instance`]; the crate
+/// using it needs `serde_json`.
+///
+/// ```ignore
+/// use gts::GtsWellKnownBody;
+/// use gts_macros::gts_instance_json;
+///
+/// const ORDERS: GtsWellKnownBody = gts_instance_json! {"name": "orders", "retention": "P30D"};
+/// const PAYMENTS: GtsWellKnownBody = gts_instance_json! {"name": "payments", "retention": DEFAULT_RETENTION};
+/// ```
+#[proc_macro]
+pub fn gts_instance_json(input: TokenStream) -> TokenStream {
+    well_known::expand_instance_json(input.into()).into()
+}
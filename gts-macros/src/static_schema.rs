@@ -0,0 +1,181 @@
+//! Built-in field type mapping for `static_schema = true`.
+//!
+//! Mirrors what schemars emits for the supported types, so switching a struct
+//! between the two modes does not change its generated schema.
+
+use serde_json::{Map, Value, json};
+
+/// Serde attribute flags of a field that affect its schema.
+fn serde_flags(field: &syn::Field) -> (bool, bool) {
+    let mut skip = false;
+    let mut default = false;
+    for attr in &field.attrs {
+        if attr.path().is_ident("serde")
+            && let Ok(meta) = attr.meta.require_list()
+        {
+            let tokens = meta.tokens.to_string();
+            for word in tokens.split(|c: char| !c.is_alphanumeric() && c != '_') {
+                match word {
+                    "skip" | "skip_serializing" => skip = true,
+                    "default" => default = true,
+                    _ => {}
+                }
+            }
+        }
+    }
+    (skip, default)
+}
+
+/// Innermost generic type arguments of a path segment.
+fn type_args(segment: &syn::PathSegment) -> Vec<&syn::Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn integer(format: &str, unsigned: bool) -> Value {
+    if unsigned {
+        json!({"type": "integer", "format": format, "minimum": 0})
+    } else {
+        json!({"type": "integer", "format": format})
+    }
+}
+
+/// Make `schema` accept `null` as well.
+fn nullable(schema: Value) -> Value {
+    match schema {
+        Value::Object(mut map) => match map.get("type").cloned() {
+            Some(Value::String(t)) => {
+                map.insert("type".to_owned(), json!([t, "null"]));
+                Value::Object(map)
+            }
+            _ => json!({"anyOf": [Value::Object(map), {"type": "null"}]}),
+        },
+        other => other,
+    }
+}
+
+fn single_arg<'a>(segment: &'a syn::PathSegment, name: &str) -> Result<&'a syn::Type, String> {
+    match type_args(segment).as_slice() {
+        [ty] => Ok(ty),
+        _ => Err(format!("{name} needs exactly one type argument")),
+    }
+}
+
+/// Schema of `ty` and whether it may be omitted (an `Option`).
+fn type_schema(ty: &syn::Type, generic_params: &[String]) -> Result<(Value, bool), String> {
+    let path = match ty {
+        syn::Type::Reference(reference) => return type_schema(&reference.elem, generic_params),
+        syn::Type::Path(type_path) if type_path.qself.is_none() => &type_path.path,
+        _ => return Err(format!("no built-in mapping for `{}`", quote::quote!(#ty))),
+    };
+    let Some(segment) = path.segments.last() else {
+        return Err("empty type path".to_owned());
+    };
+    let name = segment.ident.to_string();
+    if generic_params.contains(&name) {
+        // Placeholder: generic fields are always emitted as open objects
+        return Ok((json!({"type": "object"}), false));
+    }
+
+    let schema = match name.as_str() {
+        "bool" => json!({"type": "boolean"}),
+        "String" | "str" => json!({"type": "string"}),
+        "char" => json!({"type": "string", "minLength": 1, "maxLength": 1}),
+        "i8" | "i16" | "i32" | "i64" | "i128" => integer(&format!("int{}", &name[1..]), false),
+        "u8" | "u16" | "u32" | "u64" | "u128" => integer(&format!("uint{}", &name[1..]), true),
+        "isize" => integer("int", false),
+        "usize" => integer("uint", true),
+        "f32" => json!({"type": "number", "format": "float"}),
+        "f64" => json!({"type": "number", "format": "double"}),
+        "Uuid" => json!({"type": "string", "format": "uuid"}),
+        "Value" => Value::Bool(true),
+        // Same references as schemars; they are resolved like the schemars ones
+        "GtsInstanceId" | "GtsSchemaId" => json!({"$ref": format!("#/$defs/{name}")}),
+        "Option" => {
+            let (inner, _) = type_schema(single_arg(segment, "Option")?, generic_params)?;
+            return Ok((nullable(inner), true));
+        }
+        "Box" | "Arc" | "Rc" => {
+            return type_schema(single_arg(segment, &name)?, generic_params);
+        }
+        "Vec" | "VecDeque" | "BTreeSet" | "HashSet" => {
+            let (items, _) = type_schema(single_arg(segment, &name)?, generic_params)?;
+            if name.ends_with("Set") {
+                json!({"type": "array", "items": items, "uniqueItems": true})
+            } else {
+                json!({"type": "array", "items": items})
+            }
+        }
+        "HashMap" | "BTreeMap" => match type_args(segment).as_slice() {
+            [key, value] if type_schema(key, &[])?.0 == json!({"type": "string"}) => {
+                let (values, _) = type_schema(value, generic_params)?;
+                json!({"type": "object", "additionalProperties": values})
+            }
+            _ => return Err(format!("{name} needs String keys")),
+        },
+        _ => return Err(format!("no built-in mapping for `{name}`")),
+    };
+    Ok((schema, false))
+}
+
+/// Build the object schema of `fields` as a JSON string, in the shape
+/// `schemars::schema_for!` produces (`properties` and `required`).
+pub fn build(
+    fields: Option<&syn::punctuated::Punctuated<syn::Field, syn::Token![,]>>,
+    generic_params: &[String],
+    rename: impl Fn(&syn::Field) -> Option<String>,
+) -> syn::Result<String> {
+    object_schema(fields, generic_params, rename)
+        .map(|schema| schema.to_string())
+        .map_err(|e| {
+            syn::Error::new(
+                e.span(),
+                format!(
+                    "struct_to_gts_schema: static_schema: {e}. \
+                     Use a supported type or drop static_schema to use schemars"
+                ),
+            )
+        })
+}
+
+/// The object schema of `fields` (`properties` and `required`); errors name
+/// the field without a built-in mapping.
+pub fn object_schema(
+    fields: Option<&syn::punctuated::Punctuated<syn::Field, syn::Token![,]>>,
+    generic_params: &[String],
+    rename: impl Fn(&syn::Field) -> Option<String>,
+) -> syn::Result<Value> {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields.into_iter().flatten() {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+        let (skip, default) = serde_flags(field);
+        if skip {
+            continue;
+        }
+        let name = rename(field).unwrap_or_else(|| ident.to_string());
+        let (schema, optional) = type_schema(&field.ty, generic_params)
+            .map_err(|e| syn::Error::new_spanned(&field.ty, format!("field '{ident}': {e}")))?;
+        if !optional && !default {
+            required.push(Value::String(name.clone()));
+        }
+        properties.insert(name, schema);
+    }
+
+    let mut schema = json!({"type": "object", "properties": properties});
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    Ok(schema)
+}
@@ -0,0 +1,194 @@
+//! `#[derive(GtsTypedId)]`: newtypes over `GtsID` restricted to one family.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+/// The `pattern` of `#[gts_typed_id(pattern = "...")]`.
+fn parse_pattern(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut pattern = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("gts_typed_id"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("pattern") {
+                pattern = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("gts_typed_id: unknown attribute, expected `pattern`"))
+            }
+        })?;
+    }
+    let pattern = pattern.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "GtsTypedId: missing #[gts_typed_id(pattern = \"...\")]",
+        )
+    })?;
+
+    // Same rules as `GtsWildcard::new`
+    let value = pattern.value();
+    let wildcards = value.matches('*').count();
+    let cause = if wildcards > 1 {
+        Some("the wildcard '*' is allowed only once".to_owned())
+    } else if wildcards == 1 && !value.ends_with(".*") && !value.ends_with("~*") {
+        Some("the wildcard '*' is allowed only at the end, after '.' or '~'".to_owned())
+    } else {
+        gts_id::validate_gts_id(&value, true)
+            .err()
+            .map(|e| e.to_string())
+    };
+    match cause {
+        Some(cause) => Err(syn::Error::new_spanned(
+            &pattern,
+            format!("gts_typed_id: invalid pattern: {cause}"),
+        )),
+        None => Ok(pattern),
+    }
+}
+
+/// Check that `input` is a tuple struct with a single `GtsID` field.
+fn check_newtype(input: &DeriveInput) -> syn::Result<()> {
+    let error = || {
+        syn::Error::new_spanned(
+            &input.ident,
+            "GtsTypedId: only newtypes over GtsID are supported, e.g. `struct TopicId(GtsID);`",
+        )
+    };
+    let Data::Struct(data) = &input.data else {
+        return Err(error());
+    };
+    let Fields::Unnamed(fields) = &data.fields else {
+        return Err(error());
+    };
+    if fields.unnamed.len() != 1 {
+        return Err(error());
+    }
+    match &fields.unnamed[0].ty {
+        syn::Type::Path(path)
+            if path
+                .path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "GtsID") =>
+        {
+            Ok(())
+        }
+        _ => Err(error()),
+    }
+}
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    check_newtype(input)?;
+    let pattern = parse_pattern(input)?;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "GtsTypedId: generic newtypes are not supported",
+        ));
+    }
+    let name = &input.ident;
+
+    Ok(quote! {
+        impl ::gts::GtsTypedId for #name {
+            const PATTERN: &'static str = #pattern;
+
+            fn from_gts_id_unchecked(id: ::gts::GtsID) -> Self {
+                Self(id)
+            }
+
+            fn gts_id(&self) -> &::gts::GtsID {
+                &self.0
+            }
+        }
+
+        impl #name {
+            /// Parse `id`, checking that it belongs to this ID's family.
+            ///
+            /// # Errors
+            /// Returns an error if `id` is not a valid GTS ID or does not match
+            /// the family pattern.
+            #[allow(dead_code)]
+            pub fn new(id: &str) -> ::core::result::Result<Self, ::gts::GtsTypedIdError> {
+                <Self as ::gts::GtsTypedId>::parse_typed(id)
+            }
+
+            /// The ID as a string.
+            #[allow(dead_code)]
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                &self.0.id
+            }
+
+            /// The wrapped ID.
+            #[allow(dead_code)]
+            #[must_use]
+            pub fn into_gts_id(self) -> ::gts::GtsID {
+                self.0
+            }
+        }
+
+        impl ::core::str::FromStr for #name {
+            type Err = ::gts::GtsTypedIdError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                Self::new(s)
+            }
+        }
+
+        impl ::core::convert::TryFrom<&str> for #name {
+            type Error = ::gts::GtsTypedIdError;
+
+            fn try_from(s: &str) -> ::core::result::Result<Self, Self::Error> {
+                Self::new(s)
+            }
+        }
+
+        impl ::core::convert::TryFrom<::std::string::String> for #name {
+            type Error = ::gts::GtsTypedIdError;
+
+            fn try_from(s: ::std::string::String) -> ::core::result::Result<Self, Self::Error> {
+                Self::new(&s)
+            }
+        }
+
+        impl ::core::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::core::convert::AsRef<str> for #name {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl ::core::convert::From<#name> for ::gts::GtsID {
+            fn from(id: #name) -> Self {
+                id.0
+            }
+        }
+
+        impl serde::Serialize for #name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for #name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let s = <::std::string::String as serde::Deserialize>::deserialize(deserializer)?;
+                Self::new(&s).map_err(serde::de::Error::custom)
+            }
+        }
+    })
+}
@@ -0,0 +1,194 @@
+//! `#[gts_well_known_instance]`: instance constants checked at compile time,
+//! or built by a function or a `gts_instance_json!` body.
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::{ToTokens, format_ident, quote};
+use syn::{Expr, ExprLit, ExprMacro, Item, ItemConst, ItemFn, Lit, LitStr, ReturnType};
+
+/// The `id` of `#[gts_well_known_instance(id = "...")]`, checked to be a
+/// valid instance ID.
+fn parse_id(attr: TokenStream) -> syn::Result<LitStr> {
+    let mut id = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("id") {
+            id = Some(meta.value()?.parse::<LitStr>()?);
+            Ok(())
+        } else {
+            Err(meta.error("gts_well_known_instance: unknown attribute, expected `id`"))
+        }
+    });
+    syn::parse::Parser::parse2(parser, attr)?;
+    let id = id.ok_or_else(|| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "gts_well_known_instance: missing `id = \"...\"`",
+        )
+    })?;
+
+    let value = id.value();
+    if let Err(e) = gts_id::validate_gts_id_with_options(
+        &value,
+        false,
+        gts_id::GtsIdValidationOptions::strict(),
+    ) {
+        return Err(syn::Error::new_spanned(
+            &id,
+            format!("gts_well_known_instance: {e}"),
+        ));
+    }
+    if value.ends_with('~') {
+        return Err(syn::Error::new_spanned(
+            &id,
+            format!("gts_well_known_instance: '{value}' is a schema ID, expected an instance ID"),
+        ));
+    }
+    Ok(id)
+}
+
+/// Check that `value` is a JSON object whose `id`, if any, is `id`;
+/// errors point at `tokens`.
+fn check_object(value: &serde_json::Value, id: &str, tokens: impl ToTokens) -> syn::Result<()> {
+    let error = |message: String| {
+        syn::Error::new_spanned(&tokens, format!("gts_well_known_instance: {message}"))
+    };
+    let Some(object) = value.as_object() else {
+        return Err(error("the instance must be a JSON object".to_owned()));
+    };
+    match object.get("id") {
+        None => Ok(()),
+        Some(serde_json::Value::String(body_id)) if body_id == id => Ok(()),
+        Some(body_id) => Err(error(format!(
+            "the JSON `id` {body_id} does not match the attribute id '{id}'"
+        ))),
+    }
+}
+
+/// The tokens of a `gts_instance_json!` object: the input itself if it is a
+/// single `{ ... }` group, otherwise the input wrapped in braces.
+fn object_tokens(input: TokenStream) -> TokenStream {
+    let mut trees = input.clone().into_iter();
+    match (trees.next(), trees.next()) {
+        (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::Brace => input,
+        _ => quote!({ #input }),
+    }
+}
+
+/// The object as JSON if it is written with literals only, so it can be
+/// checked at compile time.
+fn literal_json(object: &TokenStream) -> Option<serde_json::Value> {
+    serde_json::from_str(&object.to_string()).ok()
+}
+
+/// Whether `mac` is a `gts_instance_json!` invocation.
+fn is_instance_json(mac: &ExprMacro) -> bool {
+    mac.mac
+        .path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "gts_instance_json")
+}
+
+/// The body of the constant: a string literal holding the JSON, checked
+/// here, or a `gts_instance_json!` invocation, checked here when it only
+/// holds literals.
+fn parse_body(item: &ItemConst, id: &str) -> syn::Result<TokenStream> {
+    match item.expr.as_ref() {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(json),
+            ..
+        }) => {
+            let value: serde_json::Value = serde_json::from_str(&json.value()).map_err(|e| {
+                syn::Error::new_spanned(json, format!("gts_well_known_instance: invalid JSON: {e}"))
+            })?;
+            check_object(&value, id, json)?;
+            Ok(quote!(::gts::GtsWellKnownBody::Json(#json)))
+        }
+        Expr::Macro(mac) if is_instance_json(mac) => {
+            if let Some(value) = literal_json(&object_tokens(mac.mac.tokens.clone())) {
+                check_object(&value, id, mac)?;
+            }
+            Ok(mac.to_token_stream())
+        }
+        other => Err(syn::Error::new_spanned(
+            other,
+            "gts_well_known_instance: the value must be a string literal holding the instance JSON \
+             or a `gts_instance_json!` invocation",
+        )),
+    }
+}
+
+/// Check that `item` is a plain `fn() -> serde_json::Value`: no
+/// parameters, generics or `async`.
+fn check_builder(item: &ItemFn) -> syn::Result<()> {
+    let sig = &item.sig;
+    if !sig.inputs.is_empty()
+        || !sig.generics.params.is_empty()
+        || sig.asyncness.is_some()
+        || matches!(sig.output, ReturnType::Default)
+    {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "gts_well_known_instance: a builder must be a plain `fn() -> serde_json::Value`",
+        ));
+    }
+    Ok(())
+}
+
+pub fn expand(attr: TokenStream, item: Item) -> syn::Result<TokenStream> {
+    let id = parse_id(attr)?;
+    match item {
+        Item::Const(item) => {
+            let body = parse_body(&item, &id.value())?;
+            let ItemConst {
+                attrs, vis, ident, ..
+            } = item;
+            Ok(quote! {
+                #(#attrs)*
+                #vis const #ident: ::gts::GtsWellKnownInstance = ::gts::GtsWellKnownInstance {
+                    id: #id,
+                    body: #body,
+                };
+            })
+        }
+        Item::Fn(item) => {
+            check_builder(&item)?;
+            let (vis, name) = (&item.vis, &item.sig.ident);
+            let constant = format_ident!("{}", name.to_string().to_uppercase(), span = name.span());
+            let doc = format!(
+                "The well-known instance `{}` built by [`{name}`].",
+                id.value()
+            );
+            Ok(quote! {
+                #item
+
+                #[doc = #doc]
+                #vis const #constant: ::gts::GtsWellKnownInstance = ::gts::GtsWellKnownInstance {
+                    id: #id,
+                    body: ::gts::GtsWellKnownBody::Build(#name),
+                };
+            })
+        }
+        other => Err(syn::Error::new_spanned(
+            other,
+            "gts_well_known_instance: expected a `const` holding the instance JSON or a `fn` returning it",
+        )),
+    }
+}
+
+/// `gts_instance_json!`: a `GtsWellKnownBody` holding the object as JSON
+/// when it only holds literals, otherwise built by `serde_json::json!`.
+pub fn expand_instance_json(input: TokenStream) -> TokenStream {
+    let object = object_tokens(input);
+    if let Some(value) = literal_json(&object) {
+        let json = LitStr::new(&value.to_string(), proc_macro2::Span::call_site());
+        return quote!(::gts::GtsWellKnownBody::Json(#json));
+    }
+    quote! {
+        ::gts::GtsWellKnownBody::Build({
+            fn __gts_instance_json() -> ::serde_json::Value {
+                ::serde_json::json!(#object)
+            }
+            __gts_instance_json
+        })
+    }
+}
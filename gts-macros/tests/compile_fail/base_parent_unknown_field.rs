@@ -0,0 +1,31 @@
+//! Test: base = ParentStruct(field) requires `field` to be a generic field of the parent
+
+use gts::GtsInstanceId;
+use gts_macros::struct_to_gts_schema;
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.app.entities.envelope.v1~",
+    description = "Envelope with metadata and payload",
+    properties = "id,meta,payload"
+)]
+pub struct EnvelopeV1<M, P> {
+    pub id: GtsInstanceId,
+    pub meta: M,
+    pub payload: P,
+}
+
+// This should fail: `body` is not a generic field of EnvelopeV1
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = EnvelopeV1(body),
+    schema_id = "gts.x.app.entities.envelope.v1~x.app.entities.order.v1~",
+    description = "Order extending a field the envelope does not have (invalid)",
+    properties = "order_id"
+)]
+pub struct OrderV1 {
+    pub order_id: String,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+//! Test: derive(GtsSchema) takes every field, so a properties list is rejected
+
+use gts::GtsInstanceId;
+use gts_macros::GtsSchema;
+
+#[derive(serde::Serialize, serde::Deserialize, GtsSchema)]
+#[gts(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.core.events.topic.v1~",
+    description = "Event topic",
+    properties = "id,name"
+)]
+pub struct TopicV1 {
+    pub id: GtsInstanceId,
+    pub name: String,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+//! Test: Enum variants with unnamed fields are not supported
+
+use gts_macros::struct_to_gts_schema;
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.app.entities.status.v1~",
+    description = "Status enum"
+)]
+pub enum StatusV1 {
+    Active,
+    Suspended(String),
+}
+
+fn main() {}
@@ -1,4 +1,4 @@
-//! Test: Enums are not supported
+//! Test: Enums take no properties list
 
 use gts_macros::struct_to_gts_schema;
 
@@ -9,7 +9,7 @@ use gts_macros::struct_to_gts_schema;
     description = "Status enum",
     properties = "Active"
 )]
-pub enum Status {
+pub enum StatusV1 {
     Active,
     Inactive,
 }
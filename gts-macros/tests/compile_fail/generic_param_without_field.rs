@@ -0,0 +1,19 @@
+//! Test: with several generic parameters, each must be the type of a field
+
+use gts::GtsInstanceId;
+use gts_macros::struct_to_gts_schema;
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.app.entities.envelope.v1~",
+    description = "Envelope whose metadata type is not a field (invalid)",
+    properties = "id,payload"
+)]
+pub struct EnvelopeV1<M, P> {
+    pub id: GtsInstanceId,
+    pub meta: Vec<M>,
+    pub payload: P,
+}
+
+fn main() {}
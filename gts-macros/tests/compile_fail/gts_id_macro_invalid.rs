@@ -0,0 +1,9 @@
+//! Test: gts_id! rejects an ID with a missing type token
+
+use gts_macros::gts_id;
+
+const ORDER_PLACED: &str = gts_id!("gts.x.core.events.v1~");
+
+fn main() {
+    let _ = ORDER_PLACED;
+}
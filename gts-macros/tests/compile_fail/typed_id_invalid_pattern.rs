@@ -0,0 +1,10 @@
+//! Test: GtsTypedId rejects a pattern with a misplaced wildcard
+
+use gts::GtsID;
+use gts_macros::GtsTypedId;
+
+#[derive(GtsTypedId)]
+#[gts_typed_id(pattern = "gts.x.core.*.topic.v1~")]
+pub struct TopicId(GtsID);
+
+fn main() {}
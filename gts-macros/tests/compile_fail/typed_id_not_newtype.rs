@@ -0,0 +1,9 @@
+//! Test: GtsTypedId only applies to newtypes over GtsID
+
+use gts_macros::GtsTypedId;
+
+#[derive(GtsTypedId)]
+#[gts_typed_id(pattern = "gts.x.core.events.topic.v1~*")]
+pub struct TopicId(String);
+
+fn main() {}
@@ -0,0 +1,10 @@
+//! Test: gts_well_known_instance rejects a JSON body with another ID
+
+use gts_macros::gts_well_known_instance;
+
+#[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.orders.v1")]
+const ORDERS_TOPIC: &str = r#"{"id": "gts.x.core.events.topic.v1~x.commerce._.payments.v1"}"#;
+
+fn main() {
+    let _ = ORDERS_TOPIC;
+}
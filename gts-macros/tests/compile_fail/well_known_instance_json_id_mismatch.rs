@@ -0,0 +1,12 @@
+//! Test: gts_well_known_instance checks the ID of a literal gts_instance_json! body
+
+use gts_macros::gts_well_known_instance;
+
+#[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.orders.v1")]
+const ORDERS_TOPIC: gts::GtsWellKnownBody = gts_macros::gts_instance_json! {
+    "id": "gts.x.core.events.topic.v1~x.commerce._.payments.v1"
+};
+
+fn main() {
+    let _ = ORDERS_TOPIC;
+}
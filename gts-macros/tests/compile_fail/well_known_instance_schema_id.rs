@@ -0,0 +1,10 @@
+//! Test: gts_well_known_instance rejects a schema ID
+
+use gts_macros::gts_well_known_instance;
+
+#[gts_well_known_instance(id = "gts.x.core.events.topic.v1~")]
+const ORDERS_TOPIC: &str = r#"{"name": "orders"}"#;
+
+fn main() {
+    let _ = ORDERS_TOPIC;
+}
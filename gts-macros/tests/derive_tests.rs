@@ -0,0 +1,130 @@
+//! Test: `#[derive(GtsSchema)]` takes every field unless skipped
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use gts::GtsInstanceId;
+use gts::GtsSchema;
+use gts::gts::GtsSchemaId;
+use gts_macros::{GtsSchema, struct_to_gts_schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, GtsSchema)]
+#[gts(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.derive.user.v1~",
+    description = "User derived from its fields"
+)]
+pub struct DeriveUserV1 {
+    pub id: GtsInstanceId,
+    #[gts(format = "email", description = "Login address")]
+    pub email: String,
+    #[serde(rename = "displayName")]
+    #[gts(rename = "displayName")]
+    pub display_name: Option<String>,
+    #[serde(skip)]
+    #[gts(skip)]
+    pub session_cache: Option<String>,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.derive.event.v1~",
+    description = "Base event for derived payloads",
+    properties = "event_type,id,payload"
+)]
+#[derive(Debug)]
+pub struct DeriveEventV1<P> {
+    #[serde(rename = "type")]
+    pub event_type: GtsSchemaId,
+    pub id: Uuid,
+    pub payload: P,
+}
+
+#[derive(Debug, GtsSchema)]
+#[gts(
+    dir_path = "schemas",
+    base = DeriveEventV1,
+    schema_id = "gts.x.test.derive.event.v1~x.test._.renamed.v1~",
+    description = "Nested payload with a renamed field"
+)]
+pub struct DeriveRenamedV1 {
+    #[gts(rename = "orderId")]
+    pub order_id: String,
+    #[gts(skip)]
+    pub internal: u32,
+}
+
+fn own_properties(schema: &Value) -> &serde_json::Map<String, Value> {
+    schema["properties"].as_object().unwrap()
+}
+
+#[test]
+fn test_derive_includes_fields_by_default() {
+    assert_eq!(
+        DeriveUserV1::gts_schema_id().clone().into_string(),
+        "gts.x.test.derive.user.v1~"
+    );
+    assert_eq!(DeriveUserV1::GTS_SCHEMA_PROPERTIES, "id,email,display_name");
+
+    let schema = DeriveUserV1::gts_schema_with_refs();
+    let properties = own_properties(&schema);
+    let mut names: Vec<_> = properties.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    assert_eq!(names, ["displayName", "email", "id"]);
+    assert_eq!(
+        properties["email"],
+        json!({"type": "string", "format": "email", "description": "Login address"})
+    );
+    assert!(
+        !schema["required"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("session_cache"))
+    );
+}
+
+#[test]
+fn test_derive_leaves_struct_serialization_alone() {
+    let user = DeriveUserV1 {
+        id: DeriveUserV1::gts_make_instance_id("x.test._.alice.v1"),
+        email: "a@example.com".to_owned(),
+        display_name: Some("A".to_owned()),
+        session_cache: Some("cached".to_owned()),
+    };
+    let json = user.gts_instance_json();
+    assert_eq!(json["displayName"], "A");
+    assert!(json.get("session_cache").is_none());
+}
+
+#[test]
+fn test_derive_nested_rename_applies_to_schema_and_serialization() {
+    let schema = DeriveRenamedV1::gts_schema_with_refs();
+    let own = schema["allOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find_map(|part| part.pointer("/properties/payload"))
+        .unwrap();
+    let properties = own_properties(own);
+    assert!(properties.contains_key("orderId"));
+    assert!(!properties.contains_key("order_id"));
+    assert!(!properties.contains_key("internal"));
+    assert_eq!(own["required"], json!(["orderId"]));
+
+    let event = DeriveEventV1 {
+        event_type: DeriveRenamedV1::gts_schema_id().clone(),
+        id: Uuid::nil(),
+        payload: DeriveRenamedV1 {
+            order_id: "o-1".to_owned(),
+            internal: 7,
+        },
+    };
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["payload"]["orderId"], "o-1");
+
+    let back: DeriveEventV1<DeriveRenamedV1> = serde_json::from_value(json).unwrap();
+    assert_eq!(back.payload.order_id, "o-1");
+}
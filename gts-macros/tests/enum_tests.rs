@@ -0,0 +1,163 @@
+//! Test: enums become string enums or tagged `oneOf` schemas
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use gts::{GtsInstanceId, GtsSchema};
+use gts_macros::struct_to_gts_schema;
+use serde_json::json;
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.enums.status.v1~",
+    description = "Order status"
+)]
+#[derive(Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusV1 {
+    Pending,
+    InTransit,
+    #[serde(rename = "done")]
+    Delivered,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.enums.shape.v1~",
+    description = "Shapes with data"
+)]
+#[derive(Debug, PartialEq)]
+pub enum ShapeV1 {
+    Circle {
+        radius: f64,
+    },
+    Rectangle {
+        width: u32,
+        height: u32,
+        label: Option<String>,
+    },
+    Empty,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.enums.command.v1~",
+    description = "Commands tagged by kind"
+)]
+#[derive(Debug)]
+#[serde(tag = "kind")]
+pub enum CommandV1 {
+    Start { target: GtsInstanceId },
+    Stop,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.enums.shipment.v1~",
+    description = "Shipment with an enum field",
+    properties = "id,status"
+)]
+#[derive(Debug)]
+pub struct ShipmentV1 {
+    pub id: GtsInstanceId,
+    pub status: StatusV1,
+}
+
+#[test]
+fn test_unit_enum_is_string_enum() {
+    let schema = StatusV1::gts_schema_with_refs_allof();
+    assert_eq!(
+        schema,
+        json!({
+            "$id": "gts://gts.x.test.enums.status.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "string",
+            "enum": ["pending", "in_transit", "done"]
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(StatusV1::InTransit).unwrap(),
+        json!("in_transit")
+    );
+    assert_eq!(StatusV1::gts_schema_id().as_ref(), StatusV1::SCHEMA_ID);
+    assert!(StatusV1::gts_base_schema_id().is_none());
+}
+
+#[test]
+fn test_data_enum_is_tagged_one_of() {
+    let schema = ShapeV1::gts_schema_with_refs_allof();
+    assert_eq!(schema["$id"], "gts://gts.x.test.enums.shape.v1~");
+    assert_eq!(schema["discriminator"], json!({"propertyName": "type"}));
+
+    let branches = schema["oneOf"].as_array().unwrap();
+    assert_eq!(branches.len(), 3);
+    assert_eq!(
+        branches[0],
+        json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "type": {"const": "Circle"},
+                "radius": {"type": "number", "format": "double"}
+            },
+            "required": ["type", "radius"]
+        })
+    );
+    assert_eq!(branches[1]["required"], json!(["type", "width", "height"]));
+    assert_eq!(
+        branches[1]["properties"]["label"],
+        json!({"type": ["string", "null"]})
+    );
+    assert_eq!(
+        branches[2]["properties"],
+        json!({"type": {"const": "Empty"}})
+    );
+}
+
+#[test]
+fn test_data_enum_round_trip() {
+    let shape = ShapeV1::Rectangle {
+        width: 2,
+        height: 3,
+        label: None,
+    };
+    let value = shape.gts_instance_json();
+    assert_eq!(
+        value,
+        json!({"type": "Rectangle", "width": 2, "height": 3, "label": null})
+    );
+    assert_eq!(serde_json::from_value::<ShapeV1>(value).unwrap(), shape);
+    assert_eq!(
+        serde_json::from_value::<ShapeV1>(json!({"type": "Empty"})).unwrap(),
+        ShapeV1::Empty
+    );
+}
+
+#[test]
+fn test_explicit_tag_and_resolved_refs() {
+    let schema = CommandV1::gts_schema_with_refs_allof();
+    assert_eq!(schema["discriminator"]["propertyName"], "kind");
+    let start = &schema["oneOf"][0]["properties"];
+    assert_eq!(start["kind"], json!({"const": "Start"}));
+    assert_eq!(start["target"], GtsInstanceId::json_schema_value());
+
+    let command = CommandV1::Start {
+        target: ShapeV1::gts_make_instance_id("x.test._.square.v1"),
+    };
+    assert_eq!(command.gts_instance_json()["kind"], "Start");
+}
+
+#[test]
+fn test_enum_as_struct_field() {
+    let schema = ShipmentV1::gts_schema_with_refs_allof();
+    assert!(schema["properties"].get("status").is_some());
+
+    let shipment = ShipmentV1 {
+        id: ShipmentV1::gts_make_instance_id("x.test._.parcel.v1"),
+        status: StatusV1::Delivered,
+    };
+    assert_eq!(shipment.gts_instance_json()["status"], "done");
+}
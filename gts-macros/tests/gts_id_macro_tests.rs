@@ -0,0 +1,17 @@
+//! Test: `gts_id!` expands to the validated ID
+
+use gts::GtsID;
+use gts_macros::gts_id;
+
+const ORDER_PLACED: &str = gts_id!("gts.x.core.events.type.v1~x.commerce.orders.placed.v1~");
+static AUDIT_TOPIC: &str = gts_id!("gts.x.core.events.topic.v1~x.core._.audit.v1");
+
+#[test]
+fn test_gts_id_macro_yields_str() {
+    assert_eq!(
+        ORDER_PLACED,
+        "gts.x.core.events.type.v1~x.commerce.orders.placed.v1~"
+    );
+    assert!(GtsID::new(ORDER_PLACED).is_ok_and(|id| id.is_type()));
+    assert!(GtsID::new(AUDIT_TOPIC).is_ok_and(|id| !id.is_type()));
+}
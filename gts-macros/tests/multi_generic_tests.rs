@@ -0,0 +1,112 @@
+//! Test: structs with several generic fields, extended through each of them
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use gts::{GtsInstanceId, GtsSchema};
+use gts_macros::struct_to_gts_schema;
+use serde_json::json;
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.multi.envelope.v1~",
+    description = "Envelope with metadata and payload",
+    properties = "id,meta,payload"
+)]
+#[derive(Debug)]
+pub struct EnvelopeV1<Meta, Payload> {
+    pub id: GtsInstanceId,
+    pub meta: Meta,
+    pub payload: Payload,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = EnvelopeV1(meta),
+    schema_id = "gts.x.test.multi.envelope.v1~x.test._.trace.v1~",
+    description = "Tracing metadata",
+    properties = "trace_id"
+)]
+#[derive(Debug)]
+pub struct TraceMetaV1 {
+    pub trace_id: String,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = EnvelopeV1(payload),
+    schema_id = "gts.x.test.multi.envelope.v1~x.test._.order.v1~",
+    description = "Order payload",
+    properties = "order_id,amount"
+)]
+#[derive(Debug)]
+pub struct OrderV1 {
+    pub order_id: String,
+    pub amount: u64,
+}
+
+#[test]
+fn test_generic_fields_in_parameter_order() {
+    assert_eq!(
+        <EnvelopeV1 as GtsSchema>::GENERIC_FIELDS,
+        ["meta", "payload"]
+    );
+    assert_eq!(<EnvelopeV1 as GtsSchema>::GENERIC_FIELD, Some("meta"));
+
+    let schema = EnvelopeV1::<(), ()>::gts_schema_with_refs_allof();
+    assert_eq!(schema["properties"]["meta"], json!({"type": "object"}));
+    assert_eq!(schema["properties"]["payload"], json!({"type": "object"}));
+}
+
+#[test]
+fn test_children_nest_under_their_field() {
+    let order = OrderV1::gts_schema_with_refs_allof();
+    assert_eq!(
+        order["allOf"][0]["$ref"],
+        "gts://gts.x.test.multi.envelope.v1~"
+    );
+    let payload = &order["allOf"][1]["properties"]["payload"];
+    assert_eq!(payload["properties"]["amount"]["type"], "integer");
+    assert!(order["allOf"][1]["properties"].get("meta").is_none());
+
+    let trace = TraceMetaV1::gts_schema_with_refs_allof();
+    assert_eq!(
+        trace["allOf"][1]["properties"]["meta"]["properties"]["trace_id"]["type"],
+        "string"
+    );
+}
+
+#[test]
+fn test_nesting_paths_per_field() {
+    type Both = EnvelopeV1<TraceMetaV1, OrderV1>;
+    type PayloadOnly = EnvelopeV1<(), OrderV1>;
+
+    assert_eq!(Both::collect_nesting_paths(), [["meta"], ["payload"]]);
+    assert_eq!(Both::collect_nesting_path(), ["meta"]);
+    assert_eq!(Both::innermost_schema_id(), TraceMetaV1::SCHEMA_ID);
+
+    assert_eq!(PayloadOnly::collect_nesting_paths(), [["payload"]]);
+    assert_eq!(PayloadOnly::innermost_schema_id(), OrderV1::SCHEMA_ID);
+    assert!(EnvelopeV1::<(), ()>::collect_nesting_paths().is_empty());
+}
+
+#[test]
+fn test_serialization_round_trip() {
+    let envelope = EnvelopeV1 {
+        id: EnvelopeV1::<(), ()>::gts_make_instance_id("x.test._.msg1.v1"),
+        meta: TraceMetaV1 {
+            trace_id: "t-1".to_owned(),
+        },
+        payload: OrderV1 {
+            order_id: "o-1".to_owned(),
+            amount: 42,
+        },
+    };
+    let value = serde_json::to_value(&envelope).unwrap();
+    assert_eq!(value["meta"], json!({"trace_id": "t-1"}));
+    assert_eq!(value["payload"], json!({"order_id": "o-1", "amount": 42}));
+
+    let back: EnvelopeV1<TraceMetaV1, OrderV1> = serde_json::from_value(value).unwrap();
+    assert_eq!(back.payload.amount, 42);
+    assert_eq!(back.meta.trace_id, "t-1");
+}
@@ -0,0 +1,134 @@
+//! Test: `static_schema = true` produces the same schemas as the schemars mode
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::HashMap;
+
+use gts::GtsSchema;
+use gts::gts::GtsSchemaId;
+use gts_macros::struct_to_gts_schema;
+use serde_json::Value;
+use uuid::Uuid;
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.static_mode.event.v1~",
+    description = "Base event with a static schema",
+    properties = "event_type,id,sequence_id,ratio,tags,labels,note,payload",
+    static_schema = true
+)]
+#[derive(Debug)]
+pub struct StaticEventV1<P> {
+    #[serde(rename = "type")]
+    pub event_type: GtsSchemaId,
+    pub id: Uuid,
+    pub sequence_id: u64,
+    pub ratio: f64,
+    pub tags: Vec<String>,
+    pub labels: HashMap<String, i32>,
+    pub note: Option<String>,
+    pub payload: P,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = StaticEventV1,
+    schema_id = "gts.x.test.static_mode.event.v1~x.test._.created.v1~",
+    description = "Child event with a static schema",
+    properties = "name,size",
+    static_schema = true
+)]
+#[derive(Debug)]
+pub struct StaticCreatedV1 {
+    pub name: String,
+    pub size: Option<u32>,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = true,
+    schema_id = "gts.x.test.schemars_mode.event.v1~",
+    description = "Base event with a schemars schema",
+    properties = "event_type,id,sequence_id,ratio,tags,labels,note,payload"
+)]
+#[derive(Debug)]
+pub struct SchemarsEventV1<P> {
+    #[serde(rename = "type")]
+    pub event_type: GtsSchemaId,
+    pub id: Uuid,
+    pub sequence_id: u64,
+    pub ratio: f64,
+    pub tags: Vec<String>,
+    pub labels: HashMap<String, i32>,
+    pub note: Option<String>,
+    pub payload: P,
+}
+
+#[struct_to_gts_schema(
+    dir_path = "schemas",
+    base = SchemarsEventV1,
+    schema_id = "gts.x.test.schemars_mode.event.v1~x.test._.created.v1~",
+    description = "Child event with a schemars schema",
+    properties = "name,size"
+)]
+#[derive(Debug)]
+pub struct SchemarsCreatedV1 {
+    pub name: String,
+    pub size: Option<u32>,
+}
+
+/// The schema without its `$id` and `$ref`s, which name the schema itself
+fn body(mut schema: Value) -> Value {
+    schema.as_object_mut().unwrap().remove("$id");
+    if let Some(all_of) = schema.get_mut("allOf").and_then(Value::as_array_mut) {
+        all_of.retain(|item| item.get("$ref").is_none());
+    }
+    schema
+}
+
+#[test]
+fn test_static_base_schema_matches_schemars() {
+    assert_eq!(
+        body(StaticEventV1::<()>::gts_schema_with_refs()),
+        body(SchemarsEventV1::<()>::gts_schema_with_refs())
+    );
+}
+
+#[test]
+fn test_static_child_schema_matches_schemars() {
+    let schema = StaticCreatedV1::gts_schema_with_refs();
+    assert_eq!(
+        schema["allOf"][0]["$ref"],
+        "gts://gts.x.test.static_mode.event.v1~"
+    );
+    assert_eq!(
+        body(schema),
+        body(SchemarsCreatedV1::gts_schema_with_refs())
+    );
+    assert_eq!(
+        body(<StaticEventV1<StaticCreatedV1> as GtsSchema>::gts_schema_with_refs_allof()),
+        body(<SchemarsEventV1<SchemarsCreatedV1> as GtsSchema>::gts_schema_with_refs_allof())
+    );
+}
+
+#[test]
+fn test_static_schema_instance_round_trip() {
+    let event = StaticEventV1 {
+        event_type: GtsSchemaId::new(StaticCreatedV1::SCHEMA_ID),
+        id: Uuid::nil(),
+        sequence_id: 1,
+        ratio: 0.5,
+        tags: vec!["a".to_owned()],
+        labels: HashMap::new(),
+        note: None,
+        payload: StaticCreatedV1 {
+            name: "x".to_owned(),
+            size: Some(3),
+        },
+    };
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["payload"]["size"], 3);
+    let back: StaticEventV1<StaticCreatedV1> = serde_json::from_value(json).unwrap();
+    assert_eq!(back.payload.name, "x");
+}
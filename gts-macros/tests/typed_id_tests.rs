@@ -0,0 +1,60 @@
+//! Test: `#[derive(GtsTypedId)]` newtypes accept only their family
+
+use gts::{GtsID, GtsTypedId, GtsTypedIdError};
+use gts_macros::GtsTypedId;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, GtsTypedId)]
+#[gts_typed_id(pattern = "gts.x.core.events.topic.v1~*")]
+pub struct TopicId(GtsID);
+
+#[derive(Debug, Clone, PartialEq, Eq, GtsTypedId)]
+#[gts_typed_id(pattern = "gts.x.core.events.type.v1~x.commerce.*")]
+pub struct CommerceEventTypeId(GtsID);
+
+fn topic_name(topic: &TopicId) -> &str {
+    topic.as_str()
+}
+
+#[test]
+fn test_typed_id_constructor_checks_family() {
+    let topic = TopicId::new("gts.x.core.events.topic.v1~x.commerce._.orders.v1").unwrap();
+    assert_eq!(
+        topic_name(&topic),
+        "gts.x.core.events.topic.v1~x.commerce._.orders.v1"
+    );
+    assert_eq!(TopicId::PATTERN, "gts.x.core.events.topic.v1~*");
+    assert!(!topic.gts_id().is_type());
+    assert_eq!(topic.to_string(), topic.as_str());
+    assert_eq!(GtsID::from(topic.clone()), topic.gts_id().clone());
+
+    assert!(matches!(
+        TopicId::new("gts.x.core.events.type.v1~x.commerce.orders.placed.v1~"),
+        Err(GtsTypedIdError::OutsideFamily { .. })
+    ));
+    assert!(matches!(
+        "gts.x.core".parse::<TopicId>(),
+        Err(GtsTypedIdError::Invalid(_))
+    ));
+
+    assert!(
+        CommerceEventTypeId::try_from("gts.x.core.events.type.v1~x.commerce.orders.placed.v1~")
+            .is_ok()
+    );
+    assert!(
+        CommerceEventTypeId::try_from(
+            "gts.x.core.events.type.v1~x.audit.logs.entry.v1~".to_owned()
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_typed_id_serde() {
+    let topic = TopicId::new("gts.x.core.events.topic.v1~x.commerce._.orders.v1").unwrap();
+    let json = serde_json::to_value(&topic).unwrap();
+    assert_eq!(json, "gts.x.core.events.topic.v1~x.commerce._.orders.v1");
+    assert_eq!(serde_json::from_value::<TopicId>(json).unwrap(), topic);
+
+    let err = serde_json::from_str::<TopicId>(r#""gts.x.core.events.type.v1~""#).unwrap_err();
+    assert!(err.to_string().contains("does not match"), "{err}");
+}
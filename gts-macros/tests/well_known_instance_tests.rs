@@ -0,0 +1,128 @@
+//! Test: `#[gts_well_known_instance]` constants carry their ID and JSON
+
+use gts::{GtsOps, GtsWellKnownBody, GtsWellKnownInstance};
+use gts_macros::{gts_instance_json, gts_well_known_instance};
+use serde_json::json;
+
+#[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.orders.v1")]
+pub const ORDERS_TOPIC: &str = r#"{"name": "orders", "retention": "P30D"}"#;
+
+/// Attributes and doc comments are kept
+#[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.payments.v1")]
+const PAYMENTS_TOPIC: &str = r#"{
+    "id": "gts.x.core.events.topic.v1~x.commerce._.payments.v1",
+    "name": "payments"
+}"#;
+
+const DEFAULT_RETENTION: &str = "P7D";
+
+#[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.refunds.v1")]
+fn refunds_topic() -> serde_json::Value {
+    let partitions: Vec<String> = (0..3).map(|n| format!("refunds-{n}")).collect();
+    json!({"name": "refunds", "retention": DEFAULT_RETENTION, "partitions": partitions})
+}
+
+#[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.invoices.v1")]
+const INVOICES_TOPIC: GtsWellKnownBody =
+    gts_instance_json! {"name": "invoices", "retention": "P90D"};
+
+#[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.shipments.v1")]
+const SHIPMENTS_TOPIC: GtsWellKnownBody = gts_instance_json!({
+    "name": "shipments",
+    "retention": DEFAULT_RETENTION,
+    "partitions": (0..2).map(|n| format!("shipments-{n}")).collect::<Vec<_>>()
+});
+
+fn topic_ids(topics: &[GtsWellKnownInstance]) -> Vec<&'static str> {
+    topics.iter().map(|topic| topic.id).collect()
+}
+
+#[test]
+fn test_well_known_instance_constants() {
+    assert_eq!(
+        topic_ids(&[ORDERS_TOPIC, PAYMENTS_TOPIC, REFUNDS_TOPIC]),
+        [
+            "gts.x.core.events.topic.v1~x.commerce._.orders.v1",
+            "gts.x.core.events.topic.v1~x.commerce._.payments.v1",
+            "gts.x.core.events.topic.v1~x.commerce._.refunds.v1"
+        ]
+    );
+    assert!(matches!(
+        ORDERS_TOPIC.body,
+        GtsWellKnownBody::Json(r#"{"name": "orders", "retention": "P30D"}"#)
+    ));
+    assert_eq!(
+        ORDERS_TOPIC.schema_id(),
+        Some("gts.x.core.events.topic.v1~")
+    );
+    assert_eq!(
+        ORDERS_TOPIC.value().unwrap(),
+        json!({
+            "id": "gts.x.core.events.topic.v1~x.commerce._.orders.v1",
+            "name": "orders",
+            "retention": "P30D"
+        })
+    );
+}
+
+#[test]
+fn test_well_known_instance_built_by_fn() {
+    // The function stays callable
+    assert_eq!(refunds_topic()["retention"], "P7D");
+    assert_eq!(
+        REFUNDS_TOPIC.value().unwrap(),
+        json!({
+            "id": "gts.x.core.events.topic.v1~x.commerce._.refunds.v1",
+            "name": "refunds",
+            "retention": "P7D",
+            "partitions": ["refunds-0", "refunds-1", "refunds-2"]
+        })
+    );
+}
+
+#[test]
+fn test_well_known_instance_built_by_instance_json() {
+    // Literals only: kept as JSON, checked at compile time
+    assert!(matches!(INVOICES_TOPIC.body, GtsWellKnownBody::Json(_)));
+    assert_eq!(
+        INVOICES_TOPIC.value().unwrap(),
+        json!({
+            "id": "gts.x.core.events.topic.v1~x.commerce._.invoices.v1",
+            "name": "invoices",
+            "retention": "P90D"
+        })
+    );
+    assert!(matches!(SHIPMENTS_TOPIC.body, GtsWellKnownBody::Build(_)));
+    assert_eq!(
+        SHIPMENTS_TOPIC.value().unwrap(),
+        json!({
+            "id": "gts.x.core.events.topic.v1~x.commerce._.shipments.v1",
+            "name": "shipments",
+            "retention": "P7D",
+            "partitions": ["shipments-0", "shipments-1"]
+        })
+    );
+}
+
+#[test]
+fn test_well_known_instance_loads_into_store() {
+    let mut ops = GtsOps::new(None, None, 0);
+    let schema = json!({
+        "$id": "gts://gts.x.core.events.topic.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "required": ["id", "name"],
+        "properties": {"id": {"type": "string"}, "name": {"type": "string"}}
+    });
+    assert!(ops.add_entity(&schema, false).ok);
+    for topic in [
+        ORDERS_TOPIC,
+        PAYMENTS_TOPIC,
+        REFUNDS_TOPIC,
+        INVOICES_TOPIC,
+        SHIPMENTS_TOPIC,
+    ] {
+        let result = ops.add_entity(&topic.value().unwrap(), true);
+        assert!(result.ok, "{}", result.error);
+    }
+}
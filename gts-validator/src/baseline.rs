@@ -0,0 +1,159 @@
+//! Baselines of known findings.
+//!
+//! A baseline is the JSON report of an earlier run (`--json`). Findings that
+//! were already present there are marked `baselined` instead of failing the
+//! run, so reviewers only see what their change introduced.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::error::ValidationError;
+
+#[derive(Deserialize)]
+struct BaselineFinding {
+    fingerprint: String,
+}
+
+#[derive(Deserialize)]
+struct BaselineReport {
+    validation_errors: Vec<BaselineFinding>,
+}
+
+/// Known findings, counted per [`fingerprint`](crate::fingerprint): a finding
+/// that occurs more often than in the baseline is new.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    counts: HashMap<String, usize>,
+}
+
+impl Baseline {
+    /// Build a baseline from the JSON report of an earlier run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `json` is not a validation report.
+    pub fn from_report_json(json: &str) -> anyhow::Result<Self> {
+        let report: BaselineReport = serde_json::from_str(json)
+            .map_err(|e| anyhow::anyhow!("Invalid baseline report: {e}"))?;
+        let mut counts = HashMap::new();
+        for finding in report.validation_errors {
+            *counts.entry(finding.fingerprint).or_default() += 1;
+        }
+        Ok(Self { counts })
+    }
+
+    /// Build a baseline from findings of an earlier run.
+    #[must_use]
+    pub fn from_errors(errors: &[ValidationError]) -> Self {
+        let mut counts = HashMap::new();
+        for error in errors {
+            *counts.entry(error.fingerprint.clone()).or_default() += 1;
+        }
+        Self { counts }
+    }
+
+    /// Number of findings in the baseline.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Whether the baseline holds no findings.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// A marker setting `baselined` on the errors covered by the baseline,
+    /// for errors that arrive file by file.
+    pub(crate) fn marker(&self) -> BaselineMarker {
+        BaselineMarker {
+            remaining: self.counts.clone(),
+        }
+    }
+}
+
+/// Baseline counts not yet matched by an error of the current run.
+pub struct BaselineMarker {
+    remaining: HashMap<String, usize>,
+}
+
+impl BaselineMarker {
+    /// Set `baselined` on every error covered by what is left of the baseline.
+    pub(crate) fn mark(&mut self, errors: &mut [ValidationError]) {
+        for error in errors {
+            if let Some(count) = self.remaining.get_mut(&error.fingerprint)
+                && *count > 0
+            {
+                *count -= 1;
+                error.baselined = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::fingerprint;
+    use std::path::{Path, PathBuf};
+
+    fn finding(file: &str, line: usize, raw: &str) -> ValidationError {
+        ValidationError {
+            file: PathBuf::from(file),
+            line,
+            column: 1,
+            json_path: String::new(),
+            raw_value: raw.to_owned(),
+            normalized_id: raw.to_owned(),
+            error: "Invalid GTS ID".to_owned(),
+            context: String::new(),
+            fingerprint: fingerprint(Path::new(file), raw, raw, "Invalid GTS ID", ""),
+            baselined: false,
+            severity: crate::Severity::Error,
+            context_window: None,
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn test_mark_ignores_position_and_counts_duplicates() {
+        let baseline = Baseline::from_errors(&[finding("a.md", 3, "gts.bad")]);
+        assert_eq!(baseline.len(), 1);
+
+        let mut errors = vec![
+            finding("a.md", 10, "gts.bad"),
+            finding("a.md", 11, "gts.bad"),
+            finding("b.md", 3, "gts.bad"),
+        ];
+        baseline.marker().mark(&mut errors);
+        let marks: Vec<bool> = errors.iter().map(|e| e.baselined).collect();
+        assert_eq!(marks, vec![true, false, false]);
+
+        // Counts carry over between batches
+        let mut marker = baseline.marker();
+        let mut first = vec![finding("a.md", 10, "gts.bad")];
+        let mut second = vec![finding("b.md", 3, "gts.bad")];
+        marker.mark(&mut first);
+        marker.mark(&mut second);
+        assert!(first[0].baselined);
+        assert!(!second[0].baselined);
+    }
+
+    #[test]
+    fn test_from_report_json() {
+        let json = serde_json::json!({
+            "ok": false,
+            "validation_errors": [serde_json::to_value(finding("a.md", 3, "gts.bad")).unwrap()]
+        })
+        .to_string();
+        let baseline = Baseline::from_report_json(&json).unwrap();
+        assert_eq!(
+            baseline,
+            Baseline::from_errors(&[finding("a.md", 1, "gts.bad")])
+        );
+
+        assert!(Baseline::from_report_json("{}").is_err());
+    }
+}
@@ -0,0 +1,211 @@
+//! Incremental validation cache.
+//!
+//! With [`FsSourceConfig::cache`](crate::FsSourceConfig::cache) set, the
+//! outcome of scanning each file is persisted with the file's modification
+//! time, size and content hash. A later run reuses the outcome of a file whose
+//! modification time and size are unchanged without reading it, and of a file
+//! whose content hash is unchanged without scanning it. Outcomes are stored
+//! before the allow-list filter and the baseline are applied, so both still
+//! act on cached findings.
+//!
+//! The cache is discarded as a whole when the scan settings or the crate
+//! version differ from the run that wrote it; a missing or unreadable cache
+//! file means a full scan.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::FileOutcome;
+use crate::host::FileSystem;
+
+/// Version of the cache file layout.
+const CACHE_FORMAT: u32 = 2;
+
+/// Modification time and size of a file, compared before its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    modified: SystemTime,
+    size: u64,
+}
+
+impl FileStamp {
+    /// The stamp of `path`; `None` if its metadata or modification time is unavailable.
+    pub fn of(fs: &dyn FileSystem, path: &Path) -> Option<Self> {
+        let info = fs.info(path).ok()?;
+        Some(Self {
+            modified: info.modified?,
+            size: info.len,
+        })
+    }
+}
+
+/// 64-bit FNV-1a hash of file content, as 16 lowercase hex digits.
+pub struct ContentHasher(u64);
+
+impl ContentHasher {
+    pub fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    pub fn finish(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+
+    /// Hash of `bytes`.
+    pub fn of(bytes: &[u8]) -> String {
+        let mut hasher = Self::new();
+        hasher.update(bytes);
+        hasher.finish()
+    }
+
+    /// Hash of the file at `path`, read in chunks.
+    pub fn of_file(fs: &dyn FileSystem, path: &Path) -> std::io::Result<String> {
+        let mut file = fs.open(path)?;
+        let mut hasher = Self::new();
+        let mut buffer = [0_u8; 8 * 1024];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                return Ok(hasher.finish());
+            }
+            hasher.update(&buffer[..read]);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    stamp: FileStamp,
+    hash: String,
+    outcome: FileOutcome,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    format: u32,
+    /// Fingerprint of the settings the outcomes were produced with.
+    settings: String,
+    files: BTreeMap<String, CacheEntry>,
+}
+
+/// Scan outcomes of an earlier run, updated as files are scanned.
+pub struct ScanCache {
+    settings: String,
+    files: BTreeMap<String, CacheEntry>,
+    /// Files whose outcome was taken from the cache since it was created.
+    hits: usize,
+}
+
+impl ScanCache {
+    /// An empty cache for `settings`.
+    pub fn new(settings: &str) -> Self {
+        Self {
+            settings: settings.to_owned(),
+            files: BTreeMap::new(),
+            hits: 0,
+        }
+    }
+
+    /// The cache stored at `path` for `settings`; empty if there is none, it
+    /// cannot be read, or it was written with other settings.
+    pub fn load(fs: &dyn FileSystem, path: &Path, settings: &str) -> Self {
+        let mut cache = Self::new(settings);
+        cache.files = fs
+            .open(path)
+            .and_then(|mut file| {
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes).map(|_| bytes)
+            })
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|cache| cache.format == CACHE_FORMAT && cache.settings == settings)
+            .map(|cache| cache.files)
+            .unwrap_or_default();
+        cache
+    }
+
+    /// The cached outcome of `file` if its stamp is unchanged.
+    pub fn unchanged(&mut self, file: &Path, stamp: FileStamp) -> Option<FileOutcome> {
+        let entry = self.files.get(&key(file))?;
+        if entry.stamp != stamp {
+            return None;
+        }
+        self.hits += 1;
+        Some(entry.outcome.clone())
+    }
+
+    /// The cached outcome of `file` if its content hash is unchanged; the
+    /// entry takes the new stamp.
+    pub fn same_content(
+        &mut self,
+        file: &Path,
+        stamp: FileStamp,
+        hash: &str,
+    ) -> Option<FileOutcome> {
+        let entry = self.files.get_mut(&key(file))?;
+        if entry.hash != hash {
+            return None;
+        }
+        entry.stamp = stamp;
+        self.hits += 1;
+        Some(entry.outcome.clone())
+    }
+
+    pub fn insert(&mut self, file: &Path, stamp: FileStamp, hash: String, outcome: &FileOutcome) {
+        self.files.insert(
+            key(file),
+            CacheEntry {
+                stamp,
+                hash,
+                outcome: outcome.clone(),
+            },
+        );
+    }
+
+    /// Number of files whose outcome came from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Drop the entries of files not in `files`.
+    pub fn retain(&mut self, files: &[PathBuf]) {
+        let found: HashSet<String> = files.iter().map(|f| key(f)).collect();
+        self.files.retain(|file, _| found.contains(file));
+    }
+
+    /// Write the cache to `path`, keeping only the entries of `files` (the
+    /// files found by this run, scanned or not).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache cannot be serialized or written.
+    pub fn save(
+        mut self,
+        fs: &dyn FileSystem,
+        path: &Path,
+        files: &[PathBuf],
+    ) -> anyhow::Result<()> {
+        self.retain(files);
+        let cache = CacheFile {
+            format: CACHE_FORMAT,
+            settings: self.settings,
+            files: self.files,
+        };
+        fs.write(path, &serde_json::to_vec(&cache)?)
+            .map_err(|e| anyhow::anyhow!("Failed to write cache {}: {e}", path.display()))
+    }
+}
+
+fn key(file: &Path) -> String {
+    file.to_string_lossy().into_owned()
+}
@@ -0,0 +1,135 @@
+//! Format-independent candidate model.
+//!
+//! Every scanner reduces what it finds to a [`Candidate`] and hands it to
+//! [`check_candidate`], which runs `normalize_candidate()` → `validate_candidate()`
+//! and builds the `ValidationError`s. Rules added here apply to all formats.
+
+use std::path::Path;
+
+use crate::error::{ValidationError, fingerprint};
+use crate::normalize::normalize_candidate;
+use crate::report::Severity;
+use crate::validator::validate_candidate;
+
+/// Where a candidate was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// Text position (markdown): 1-indexed line and column.
+    TextPos { line: usize, column: usize },
+    /// Tree position (JSON/YAML), e.g. `$.properties.type.x-gts-ref`.
+    JsonPath(String),
+}
+
+/// A raw GTS identifier candidate found by a scanner.
+#[derive(Debug, Clone)]
+pub struct Candidate<'a> {
+    /// The raw text as found (may carry `gts://` or quotes).
+    pub text: &'a str,
+    pub location: Location,
+    /// Surrounding context for reports (markdown: the line; JSON/YAML: the key).
+    pub context: String,
+    /// Whether wildcard patterns are acceptable at this location.
+    pub allow_wildcards: bool,
+}
+
+/// Normalize and validate `candidate`, returning one error per problem found.
+pub fn check_candidate(
+    candidate: &Candidate<'_>,
+    file: &Path,
+    vendor: Option<&str>,
+) -> Vec<ValidationError> {
+    match normalize_candidate(candidate.text) {
+        Ok(normalized) => validate_candidate(&normalized, vendor, candidate.allow_wildcards)
+            .into_iter()
+            .map(|error| {
+                candidate.error(
+                    file,
+                    normalized.original.clone(),
+                    normalized.gts_id.clone(),
+                    error,
+                )
+            })
+            .collect(),
+        Err(error) => vec![candidate.error(file, candidate.text.to_owned(), String::new(), error)],
+    }
+}
+
+impl Candidate<'_> {
+    pub(crate) fn error(
+        &self,
+        file: &Path,
+        raw_value: String,
+        normalized_id: String,
+        error: String,
+    ) -> ValidationError {
+        let (line, column, json_path) = match &self.location {
+            Location::TextPos { line, column } => (*line, *column, String::new()),
+            Location::JsonPath(json_path) => (0, 0, json_path.clone()),
+        };
+        let fingerprint = fingerprint(file, &normalized_id, &raw_value, &error, &json_path);
+        ValidationError {
+            file: file.to_owned(),
+            line,
+            column,
+            json_path,
+            raw_value,
+            normalized_id,
+            error,
+            context: self.context.clone(),
+            fingerprint,
+            baselined: false,
+            severity: Severity::Error,
+            context_window: None,
+            confidence: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_candidate_text_position() {
+        let candidate = Candidate {
+            text: "gts://gts.y.core.events.type.v1~",
+            location: Location::TextPos { line: 3, column: 7 },
+            context: "see gts://gts.y.core.events.type.v1~".to_owned(),
+            allow_wildcards: false,
+        };
+        let errors = check_candidate(&candidate, Path::new("doc.md"), Some("x"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].line, errors[0].column), (3, 7));
+        assert!(errors[0].json_path.is_empty());
+        assert_eq!(errors[0].normalized_id, "gts.y.core.events.type.v1~");
+    }
+
+    #[test]
+    fn test_check_candidate_json_path_normalize_error() {
+        let candidate = Candidate {
+            text: "gts://gts.x.core.events.type.v1~#frag",
+            location: Location::JsonPath("$.a".to_owned()),
+            context: "$.a".to_owned(),
+            allow_wildcards: false,
+        };
+        let errors = check_candidate(&candidate, Path::new("a.json"), None);
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].line, errors[0].column), (0, 0));
+        assert_eq!(errors[0].json_path, "$.a");
+        assert_eq!(errors[0].raw_value, "gts://gts.x.core.events.type.v1~#frag");
+        assert!(errors[0].normalized_id.is_empty());
+    }
+
+    #[test]
+    fn test_check_candidate_wildcards() {
+        let mut candidate = Candidate {
+            text: "gts.x.core.*",
+            location: Location::JsonPath("$.x-gts-ref".to_owned()),
+            context: String::new(),
+            allow_wildcards: true,
+        };
+        assert!(check_candidate(&candidate, Path::new("a.json"), None).is_empty());
+        candidate.allow_wildcards = false;
+        assert!(!check_candidate(&candidate, Path::new("a.json"), None).is_empty());
+    }
+}
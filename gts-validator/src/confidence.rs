@@ -0,0 +1,148 @@
+//! Confidence that a heuristic-mode match is meant as a GTS identifier.
+//!
+//! The relaxed discovery pattern matches anything from a GTS ID with a typo
+//! to a dotted string that merely starts with `gts.`. Each match found in
+//! heuristic mode is scored from 0 to 100, and findings below
+//! [`ValidationConfig::min_confidence`](crate::ValidationConfig::min_confidence)
+//! are demoted to `Info`. The score adds up:
+//!
+//! | Signal | Points |
+//! |--------|--------|
+//! | `gts://` prefix | 15 |
+//! | 4 tokens before the version (3 or 5: 20, otherwise 5) | 35 |
+//! | the ID ends at the version, `~` or a chained segment | 20 |
+//! | a trailing `~` or chained segment | 10 |
+//! | every token before the version is lowercase letters, digits and `_` | 10 |
+//! | quoted or in backticks | 15 |
+//! | part of a path or file name (`/` before, `/` or `.x` after) | -40 |
+//!
+//! capped to 0..=100.
+
+/// Score of the heuristic match `candidate` found at byte `start` of `line`.
+pub fn heuristic_confidence(candidate: &str, line: &str, start: usize) -> u8 {
+    let mut score: i32 = 0;
+    let id = match candidate.strip_prefix("gts://") {
+        Some(id) => {
+            score += 15;
+            id
+        }
+        None => candidate,
+    };
+    let (first, chained) = match id.split_once('~') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (id, None),
+    };
+
+    let tokens: Vec<&str> = first
+        .strip_prefix("gts.")
+        .unwrap_or(first)
+        .split('.')
+        .collect();
+    let version = tokens.iter().rposition(|token| is_major_version(token));
+    score += match version {
+        Some(4) => 35,
+        Some(3 | 5) => 20,
+        _ => 5,
+    };
+    // The version may only be followed by a minor version
+    if version.is_some_and(|v| {
+        let after = &tokens[v + 1..];
+        after.is_empty() || (after.len() == 1 && after[0].bytes().all(|b| b.is_ascii_digit()))
+    }) {
+        score += 20;
+    }
+    if chained.is_some() {
+        score += 10;
+    }
+    // The version and minor version are checked above
+    let named = &tokens[..version.unwrap_or(tokens.len())];
+    if named.iter().all(|token| is_clean_token(token)) {
+        score += 10;
+    }
+
+    let before = line[..start].chars().next_back();
+    let end = start + candidate.len();
+    let mut after = line.get(end..).unwrap_or_default().chars();
+    if matches!(before, Some('`' | '"' | '\'')) {
+        score += 15;
+    }
+    let in_path = before == Some('/')
+        || match after.next() {
+            Some('/') => true,
+            Some('.') => after.next().is_some_and(char::is_alphanumeric),
+            _ => false,
+        };
+    if in_path {
+        score -= 40;
+    }
+
+    u8::try_from(score.clamp(0, 100)).unwrap_or(0)
+}
+
+fn is_major_version(token: &str) -> bool {
+    token
+        .strip_prefix('v')
+        .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_clean_token(token: &str) -> bool {
+    let mut bytes = token.bytes();
+    bytes
+        .next()
+        .is_some_and(|b| b.is_ascii_lowercase() || b == b'_')
+        && bytes.all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(line: &str, candidate: &str) -> u8 {
+        let start = line.find(candidate).unwrap_or_default();
+        heuristic_confidence(candidate, line, start)
+    }
+
+    #[test]
+    fn test_confidence_ranks_matches() {
+        let quoted = score(
+            "Use `gts.x.core.events.type.v1~`.",
+            "gts.x.core.events.type.v1~",
+        );
+        let typo = score(
+            "Use gts.x.core.events.typ-e.v1 here",
+            "gts.x.core.events.typ-e.v1",
+        );
+        let extra_token = score(
+            "See gts.x.core.events.a.type.v1",
+            "gts.x.core.events.a.type.v1",
+        );
+        let file = score(
+            "Open schemas/gts.x.core.events.type.v1~.schema.json",
+            "gts.x.core.events.type.v1~",
+        );
+        assert_eq!(quoted, 90);
+        assert_eq!(typo, 55);
+        assert_eq!(extra_token, 50);
+        assert_eq!(file, 35);
+        assert!(quoted > typo && typo > extra_token && extra_token > file);
+    }
+
+    #[test]
+    fn test_confidence_uri_and_minor_version() {
+        assert_eq!(
+            score(
+                "gts://gts.x.core.events.type.v1.2~x.app.a.b.v1",
+                "gts://gts.x.core.events.type.v1.2~x.app.a.b.v1"
+            ),
+            90
+        );
+        // A version followed by more tokens is no anchor
+        assert_eq!(
+            score(
+                "gts.cfg.server.http.v2.timeout.ms",
+                "gts.cfg.server.http.v2.timeout.ms"
+            ),
+            30
+        );
+    }
+}
@@ -4,10 +4,19 @@
 //! (how content is discovered). This ensures the core API does not leak
 //! filesystem concerns.
 
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use glob::Pattern;
+use gts_id::GtsReservations;
+
+use crate::baseline::Baseline;
+use crate::error::RuleId;
+use crate::report::Severity;
 
 /// Vendor matching policy for GTS ID validation.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum VendorPolicy {
     /// Accept any vendor (no vendor enforcement).
@@ -17,6 +26,32 @@ pub enum VendorPolicy {
     MustMatch(String),
     /// All GTS IDs must match one of the listed vendors (example vendors are always tolerated).
     AllowList(Vec<String>),
+    /// Per sub-tree policies as `(glob, policy)` pairs, e.g. `("docs/a/**",
+    /// MustMatch("a"))`: a file gets the policy of the first glob matching its
+    /// path (as reported) or file name, like `exclude` patterns; files matching
+    /// none accept any vendor.
+    PathScoped(Vec<(String, VendorPolicy)>),
+}
+
+impl VendorPolicy {
+    /// The policy applying to `file`; only [`PathScoped`](Self::PathScoped)
+    /// differs from `self`.
+    #[must_use]
+    pub fn for_path(&self, file: &Path) -> &Self {
+        let Self::PathScoped(scopes) = self else {
+            return self;
+        };
+        let path = file.to_string_lossy();
+        let name = file.file_name().map(|name| name.to_string_lossy());
+        scopes
+            .iter()
+            .find(|(glob, _)| {
+                Pattern::new(glob).is_ok_and(|pattern| {
+                    pattern.matches(&path) || name.as_ref().is_some_and(|n| pattern.matches(n))
+                })
+            })
+            .map_or(&Self::Any, |(_, policy)| policy.for_path(file))
+    }
 }
 
 /// Controls how GTS identifier candidates are discovered in markdown files.
@@ -29,17 +64,36 @@ pub enum DiscoveryMode {
     /// Permissive regex catches ALL gts.* strings including malformed IDs.
     /// Use for strict CI enforcement where every malformed ID must be reported.
     Heuristic,
+    /// Well-formed patterns, rescanning with the heuristic pattern only the
+    /// files that contain a `gts.` token the well-formed pattern missed.
+    Auto,
+}
+
+/// Controls which markdown candidates are skipped as intentionally invalid examples.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidExamplePolicy {
+    /// Explicit markers (`<!-- gts:invalid-example -->`, `(invalid)`) plus the
+    /// emoji/keyword heuristics (e.g. a cross mark or `invalid:` before the candidate) (default).
+    #[default]
+    MarkersAndHeuristics,
+    /// Only explicit markers; heuristic contexts are validated like any other text.
+    MarkersOnly,
 }
 
 /// Core validation config — applies regardless of input source.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 #[non_exhaustive]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ValidationConfig {
     /// Vendor matching policy for all GTS IDs.
     /// Example vendors (acme, globex, etc.) are always tolerated regardless of policy.
     pub vendor_policy: VendorPolicy,
     /// Scan JSON/YAML object keys for GTS identifiers (default: off).
     pub scan_keys: bool,
+    /// Scan `$comment` and `description` string values in JSON/YAML (default: on).
+    /// Disable when documentation text in schemas should not be validated.
+    pub scan_comments: bool,
     /// Discovery mode for markdown scanning.
     ///
     /// - `StrictSpecOnly` (default): only well-formed GTS patterns are discovered.
@@ -50,6 +104,81 @@ pub struct ValidationConfig {
     /// validation is skipped for that candidate. Case-insensitive matching.
     /// Example: `vec!["**given**".to_owned()]` to skip BDD-style bold formatting.
     pub skip_tokens: Vec<String>,
+    /// Which markdown contexts mark a candidate as an intentionally invalid example.
+    pub invalid_examples: InvalidExamplePolicy,
+    /// Known findings: matching errors are reported as `baselined` and do not fail the run.
+    pub baseline: Option<Baseline>,
+    /// Retry files whose parser fails with the other structured format
+    /// (JSON <-> YAML), and scan Markdown files that are a single JSON document
+    /// as JSON (default: off). Files parsed this way are listed in
+    /// `ValidationReport::format_fallbacks`.
+    pub format_fallback: bool,
+    /// JSON path patterns of JSON/YAML fields that must hold type IDs
+    /// (ending with `~`), e.g. `$..x-gts-ref` or `$.extends`.
+    pub type_ref_paths: Vec<String>,
+    /// JSON path patterns of JSON/YAML fields that must hold instance IDs
+    /// (not ending with `~`).
+    pub instance_ref_paths: Vec<String>,
+    /// Reserved vendors/packages: JSON/YAML documents whose root `$id` or `id`
+    /// defines an entity under a reservation are reported (default: none).
+    pub reservations: GtsReservations,
+    /// Owner the validated files are written by; definitions under
+    /// reservations held by this owner are allowed.
+    pub reservation_owner: Option<String>,
+    /// Lines of context before and after each finding, carried in
+    /// `ValidationError::context_window` (default: 0, capped at
+    /// [`MAX_CONTEXT_LINES`](crate::MAX_CONTEXT_LINES)).
+    pub context_lines: usize,
+    /// Stop at the first validation error not covered by the baseline
+    /// (default: off). Same as `max_errors: Some(1)`.
+    pub fail_fast: bool,
+    /// Stop collecting after this many validation errors not covered by the
+    /// baseline (default: `None`, no limit; `0` acts as `1`). Remaining files
+    /// are not scanned and the report is marked `truncated`.
+    pub max_errors: Option<usize>,
+    /// Severity of findings per rule (default: empty, every rule is an
+    /// error). Demoting a rule to `Warning` or `Info` keeps its findings in
+    /// the report without failing the run or counting towards `max_errors`.
+    pub rule_overrides: BTreeMap<RuleId, Severity>,
+    /// Findings of heuristic discovery whose
+    /// [`confidence`](crate::ValidationError::confidence) is below this
+    /// (0-100) are demoted to `Info` (default: `None`, none are demoted).
+    pub min_confidence: Option<u8>,
+    /// Under `VendorPolicy::Any`, list the vendor of every scanned GTS ID in
+    /// [`ValidationReport::vendors`](crate::ValidationReport::vendors)
+    /// (default: off). Example vendors are left out, as every policy
+    /// tolerates them.
+    pub collect_vendors: bool,
+    /// Under `VendorPolicy::Any`, list every scanned GTS ID with its location
+    /// in [`ValidationReport::ids`](crate::ValidationReport::ids) (default:
+    /// off). Example vendors are left out, as with `collect_vendors`.
+    pub collect_ids: bool,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            vendor_policy: VendorPolicy::default(),
+            scan_keys: false,
+            scan_comments: true,
+            discovery_mode: DiscoveryMode::default(),
+            skip_tokens: Vec::new(),
+            invalid_examples: InvalidExamplePolicy::default(),
+            baseline: None,
+            format_fallback: false,
+            type_ref_paths: Vec::new(),
+            instance_ref_paths: Vec::new(),
+            reservations: GtsReservations::new(),
+            reservation_owner: None,
+            context_lines: 0,
+            fail_fast: false,
+            max_errors: None,
+            rule_overrides: BTreeMap::new(),
+            min_confidence: None,
+            collect_vendors: false,
+            collect_ids: false,
+        }
+    }
 }
 
 /// Filesystem-specific source options.
@@ -81,6 +210,22 @@ pub struct FsSourceConfig {
     /// Maximum total bytes to read across all files (default: 512 MB).
     /// Prevents memory exhaustion when many large files are present.
     pub max_total_bytes: u64,
+    /// Time budget for scanning a single file (default: 30 s); `None` disables it.
+    /// A file that overruns is reported as a `Timeout` scan error and the scan
+    /// moves on; panics are always reported as `Panicked` scan errors.
+    pub file_timeout: Option<Duration>,
+    /// JSON/YAML files larger than this many bytes are scanned as a stream
+    /// instead of being loaded (default: `None`, never stream). Streamed files
+    /// are exempt from `max_file_size` and `max_total_bytes`, since memory use
+    /// grows with the number of GTS-looking strings rather than the file size;
+    /// they are never retried with the fallback format.
+    pub stream_threshold: Option<u64>,
+    /// Incremental mode: file persisting each file's modification time,
+    /// content hash and scan outcome (default: `None`, scan everything).
+    /// Unchanged files are not scanned again; their cached findings are
+    /// merged into the report. The cache is rebuilt when the scan settings
+    /// change.
+    pub cache: Option<PathBuf>,
 }
 
 impl Default for FsSourceConfig {
@@ -93,6 +238,9 @@ impl Default for FsSourceConfig {
             max_depth: 64,
             max_files: 100_000,
             max_total_bytes: 536_870_912,
+            file_timeout: Some(Duration::from_secs(30)),
+            stream_threshold: None,
+            cache: None,
         }
     }
 }
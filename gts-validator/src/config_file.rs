@@ -0,0 +1,350 @@
+//! Config files (`.gtsvalidator.toml`, `gts.toml`).
+//!
+//! Settings repeated on every run can live in a `.gtsvalidator.toml`, or in
+//! the `[validator]` table of the project's `gts.toml`. The nearest of the two
+//! in the working directory or its ancestors is used (`.gtsvalidator.toml`
+//! first when a directory has both). Values from the file are defaults:
+//! flags given on the command line always win.
+//!
+//! ```toml
+//! paths = ["docs", "schemas"]          # relative to the file's directory
+//! exclude = ["docs/drafts/*"]
+//! vendor = "x"                         # or a list: ["x", "y"]
+//! skip-tokens = ["**"]
+//! discovery-mode = "auto"              # strict, heuristic or auto
+//!
+//! # Sub-trees with their own vendors, checked in order before `vendor`
+//! [[vendor-scope]]
+//! path = "docs/a/**"
+//! vendor = "a"
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Deserializer};
+
+use crate::config::{DiscoveryMode, FsSourceConfig, ValidationConfig, VendorPolicy};
+
+/// Validator config file, holding its settings at the top level.
+pub const VALIDATOR_CONFIG_FILE: &str = ".gtsvalidator.toml";
+
+/// Project config file, holding the validator settings in `[validator]`.
+pub const PROJECT_CONFIG_FILE: &str = "gts.toml";
+
+/// Settings of a config file, see the [module docs](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+#[non_exhaustive]
+pub struct ConfigFile {
+    /// Paths to scan (files or directories).
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    /// Exclude patterns (glob format).
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// `vendor = "x"` for [`VendorPolicy::MustMatch`], a list for
+    /// [`VendorPolicy::AllowList`].
+    #[serde(default, rename = "vendor", deserialize_with = "vendor_policy")]
+    pub vendor_policy: Option<VendorPolicy>,
+    /// `[[vendor-scope]]` tables of `path` (a glob) and `vendor`, see
+    /// [`VendorPolicy::PathScoped`].
+    #[serde(default, rename = "vendor-scope", deserialize_with = "vendor_scopes")]
+    pub vendor_scopes: Vec<(String, VendorPolicy)>,
+    /// Skip tokens for markdown scanning.
+    #[serde(default)]
+    pub skip_tokens: Vec<String>,
+    /// `strict`, `heuristic` or `auto`.
+    #[serde(default, deserialize_with = "discovery_mode")]
+    pub discovery_mode: Option<DiscoveryMode>,
+}
+
+/// The part of `gts.toml` read by the validator; other tables belong to the CLI.
+#[derive(Deserialize)]
+struct ProjectFile {
+    #[serde(default)]
+    validator: ConfigFile,
+}
+
+impl ConfigFile {
+    /// Find `.gtsvalidator.toml` or `gts.toml` in `start` or the nearest ancestor.
+    #[must_use]
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        start.ancestors().find_map(|dir| {
+            [VALIDATOR_CONFIG_FILE, PROJECT_CONFIG_FILE]
+                .into_iter()
+                .map(|name| dir.join(name))
+                .find(|candidate| candidate.is_file())
+        })
+    }
+
+    /// Parse a config file, resolving relative `paths` against its directory.
+    ///
+    /// A file named `gts.toml` is read from its `[validator]` table (absent:
+    /// no settings), any other file from the top level.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid config.
+    pub fn load(file: &Path) -> anyhow::Result<Self> {
+        let text = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read config {}", file.display()))?;
+        let parsed = if file.file_name() == Some(PROJECT_CONFIG_FILE.as_ref()) {
+            toml::from_str::<ProjectFile>(&text).map(|project| project.validator)
+        } else {
+            toml::from_str(&text)
+        };
+        let mut config = parsed.with_context(|| format!("Invalid config {}", file.display()))?;
+        config.resolve_paths(file.parent().unwrap_or_else(|| Path::new("")));
+        Ok(config)
+    }
+
+    /// Load `explicit` if given, else the discovered file, else no settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the selected file cannot be loaded.
+    pub fn resolve(explicit: Option<&Path>, cwd: &Path) -> anyhow::Result<Self> {
+        match explicit
+            .map(Path::to_path_buf)
+            .or_else(|| Self::discover(cwd))
+        {
+            Some(file) => Self::load(&file),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Make relative `paths` relative to `base`, the config file's directory.
+    pub fn resolve_paths(&mut self, base: &Path) {
+        for path in &mut self.paths {
+            if path.is_relative() {
+                *path = base.join(&*path);
+            }
+        }
+    }
+
+    /// Copy the settings the file has into the configs; flags applied
+    /// afterwards override them.
+    pub fn apply(&self, fs_config: &mut FsSourceConfig, config: &mut ValidationConfig) {
+        if !self.paths.is_empty() {
+            fs_config.paths.clone_from(&self.paths);
+        }
+        if !self.exclude.is_empty() {
+            fs_config.exclude.clone_from(&self.exclude);
+        }
+        if !self.vendor_scopes.is_empty() {
+            // Files outside every scope fall back to `vendor`
+            let mut scopes = self.vendor_scopes.clone();
+            if let Some(policy) = &self.vendor_policy {
+                scopes.push(("**".to_owned(), policy.clone()));
+            }
+            config.vendor_policy = VendorPolicy::PathScoped(scopes);
+        } else if let Some(policy) = &self.vendor_policy {
+            config.vendor_policy = policy.clone();
+        }
+        if !self.skip_tokens.is_empty() {
+            config.skip_tokens.clone_from(&self.skip_tokens);
+        }
+        if let Some(mode) = &self.discovery_mode {
+            config.discovery_mode = mode.clone();
+        }
+    }
+}
+
+/// A vendor, or a list of them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Vendors {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<Vendors> for VendorPolicy {
+    fn from(vendors: Vendors) -> Self {
+        match vendors {
+            Vendors::One(vendor) => Self::MustMatch(vendor),
+            Vendors::Many(vendors) => Self::AllowList(vendors),
+        }
+    }
+}
+
+fn vendor_policy<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<VendorPolicy>, D::Error> {
+    Ok(Some(Vendors::deserialize(deserializer)?.into()))
+}
+
+fn vendor_scopes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(String, VendorPolicy)>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Scope {
+        path: String,
+        vendor: Vendors,
+    }
+    Ok(Vec::<Scope>::deserialize(deserializer)?
+        .into_iter()
+        .map(|scope| (scope.path, scope.vendor.into()))
+        .collect())
+}
+
+fn discovery_mode<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<DiscoveryMode>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Mode {
+        Strict,
+        Heuristic,
+        Auto,
+    }
+    Ok(Some(match Mode::deserialize(deserializer)? {
+        Mode::Strict => DiscoveryMode::StrictSpecOnly,
+        Mode::Heuristic => DiscoveryMode::Heuristic,
+        Mode::Auto => DiscoveryMode::Auto,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_discover_nearest_file() {
+        let dir = TempDir::new().unwrap();
+        let nested = dir.path().join("a/b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(dir.path().join(PROJECT_CONFIG_FILE), "").unwrap();
+        assert_eq!(
+            ConfigFile::discover(&nested),
+            Some(dir.path().join(PROJECT_CONFIG_FILE))
+        );
+
+        fs::write(dir.path().join(VALIDATOR_CONFIG_FILE), "").unwrap();
+        assert_eq!(
+            ConfigFile::discover(&nested),
+            Some(dir.path().join(VALIDATOR_CONFIG_FILE))
+        );
+
+        fs::write(nested.join(PROJECT_CONFIG_FILE), "").unwrap();
+        assert_eq!(
+            ConfigFile::discover(&nested),
+            Some(nested.join(PROJECT_CONFIG_FILE))
+        );
+    }
+
+    #[test]
+    fn test_load_validator_file() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join(VALIDATOR_CONFIG_FILE);
+        fs::write(
+            &file,
+            "paths = [\"docs\", \"/abs\"]\nexclude = [\"drafts/*\"]\nvendor = \"x\"\nskip-tokens = [\"**\"]\ndiscovery-mode = \"auto\"\n",
+        )
+        .unwrap();
+
+        let config = ConfigFile::load(&file).unwrap();
+        assert_eq!(
+            config.paths,
+            [dir.path().join("docs"), PathBuf::from("/abs")]
+        );
+        assert_eq!(config.exclude, ["drafts/*"]);
+        assert_eq!(
+            config.vendor_policy,
+            Some(VendorPolicy::MustMatch("x".to_owned()))
+        );
+        assert_eq!(config.skip_tokens, ["**"]);
+        assert_eq!(config.discovery_mode, Some(DiscoveryMode::Auto));
+
+        fs::write(&file, "vendor = [\"x\", \"y\"]\n").unwrap();
+        assert_eq!(
+            ConfigFile::load(&file).unwrap().vendor_policy,
+            Some(VendorPolicy::AllowList(vec![
+                "x".to_owned(),
+                "y".to_owned()
+            ]))
+        );
+
+        fs::write(&file, "discovery-mode = \"loose\"\n").unwrap();
+        assert!(ConfigFile::load(&file).is_err());
+        fs::write(&file, "vendors = \"x\"\n").unwrap();
+        assert!(ConfigFile::load(&file).is_err());
+    }
+
+    #[test]
+    fn test_load_project_file_reads_validator_table() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join(PROJECT_CONFIG_FILE);
+        fs::write(&file, "path = \"schemas\"\n\n[lint]\nrequire-owner = []\n").unwrap();
+        assert_eq!(ConfigFile::load(&file).unwrap(), ConfigFile::default());
+
+        fs::write(
+            &file,
+            "path = \"schemas\"\n\n[validator]\npaths = [\"docs\"]\nvendor = \"x\"\n",
+        )
+        .unwrap();
+        let config = ConfigFile::load(&file).unwrap();
+        assert_eq!(config.paths, [dir.path().join("docs")]);
+        assert_eq!(
+            config.vendor_policy,
+            Some(VendorPolicy::MustMatch("x".to_owned()))
+        );
+
+        fs::write(&file, "[validator]\nstore = \"x\"\n").unwrap();
+        assert!(ConfigFile::load(&file).is_err());
+    }
+
+    #[test]
+    fn test_apply_keeps_unset_values() {
+        let mut fs_config = FsSourceConfig {
+            exclude: vec!["target/*".to_owned()],
+            ..FsSourceConfig::default()
+        };
+        let mut config = ValidationConfig::default();
+
+        let file = ConfigFile {
+            discovery_mode: Some(DiscoveryMode::Heuristic),
+            ..ConfigFile::default()
+        };
+        file.apply(&mut fs_config, &mut config);
+        assert_eq!(fs_config.exclude, ["target/*"]);
+        assert_eq!(config.vendor_policy, VendorPolicy::Any);
+        assert_eq!(config.discovery_mode, DiscoveryMode::Heuristic);
+    }
+
+    #[test]
+    fn test_vendor_scopes_fall_back_to_vendor() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join(VALIDATOR_CONFIG_FILE);
+        fs::write(
+            &file,
+            "vendor = \"x\"\n\n[[vendor-scope]]\npath = \"docs/a/**\"\nvendor = \"a\"\n\n[[vendor-scope]]\npath = \"docs/b/**\"\nvendor = [\"b\", \"c\"]\n",
+        )
+        .unwrap();
+        let mut fs_config = FsSourceConfig::default();
+        let mut config = ValidationConfig::default();
+        ConfigFile::load(&file)
+            .unwrap()
+            .apply(&mut fs_config, &mut config);
+        assert_eq!(
+            config.vendor_policy,
+            VendorPolicy::PathScoped(vec![
+                (
+                    "docs/a/**".to_owned(),
+                    VendorPolicy::MustMatch("a".to_owned())
+                ),
+                (
+                    "docs/b/**".to_owned(),
+                    VendorPolicy::AllowList(vec!["b".to_owned(), "c".to_owned()])
+                ),
+                ("**".to_owned(), VendorPolicy::MustMatch("x".to_owned())),
+            ])
+        );
+
+        fs::write(&file, "[[vendor-scope]]\npath = \"docs/a/**\"\n").unwrap();
+        assert!(ConfigFile::load(&file).is_err());
+    }
+}
@@ -1,11 +1,17 @@
 //! Error types for GTS validation.
 
-use std::path::PathBuf;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::format::yaml::DOCUMENT_CONTEXT_PREFIX;
+use crate::ref_form::{EXPECTED_INSTANCE_ID, EXPECTED_TYPE_ID};
+use crate::report::Severity;
+use crate::reserved::RESERVED_NAME;
 
 /// The kind of scan-level failure that prevented a file from being validated.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ScanErrorKind {
     /// An I/O error occurred while reading the file.
@@ -26,6 +32,10 @@ pub enum ScanErrorKind {
     WalkError,
     /// An exclude glob pattern could not be parsed.
     InvalidExcludePattern,
+    /// Scanning the file exceeded the per-file time budget.
+    Timeout,
+    /// The scanner panicked on the file.
+    Panicked,
 }
 
 /// A scan-level error: a file that could not be validated at all.
@@ -33,7 +43,7 @@ pub enum ScanErrorKind {
 /// These are distinct from `ValidationError` (which represents a GTS ID that
 /// was found and failed validation). A `ScanError` means the file could not
 /// even be read or parsed — CI must treat these as failures.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct ScanError {
     /// The file path that could not be scanned.
@@ -53,14 +63,14 @@ impl ScanError {
 }
 
 /// A single validation error found in a documentation/config file.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct ValidationError {
     /// File path where the error was found
     pub file: PathBuf,
-    /// Line number (1-indexed) — for .md files; 0 for structured files
+    /// Line number (1-indexed); 0 if unknown (files scanned as a stream)
     pub line: usize,
-    /// Column number (1-indexed) — for .md files; 0 for structured files
+    /// Column number (1-indexed, in characters); 0 if unknown
     pub column: usize,
     /// JSON path (e.g., "$.properties.type.x-gts-ref") — for .json/.yaml files; empty for .md
     pub json_path: String,
@@ -70,31 +80,266 @@ pub struct ValidationError {
     pub normalized_id: String,
     /// Human-readable error description
     pub error: String,
-    /// Surrounding context (for .md: the line content; for .json/.yaml: the parent key)
+    /// Surrounding context (for .md: the line content; for .json/.yaml: the
+    /// JSON path, or `key: {key}` for a key), prefixed with the document as
+    /// `document 2 (line 14): ` in a stream of several YAML documents
     pub context: String,
+    /// Stable identity of the finding across commits, see [`fingerprint`].
+    pub fingerprint: String,
+    /// Whether the finding is covered by the configured baseline (pre-existing)
+    pub baselined: bool,
+    /// Severity of the finding's rule, see
+    /// [`ValidationConfig::rule_overrides`](crate::ValidationConfig::rule_overrides)
+    #[serde(default)]
+    pub severity: Severity,
+    /// Lines around the finding, see
+    /// [`ValidationConfig::context_lines`](crate::ValidationConfig::context_lines)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<ContextWindow>,
+    /// How likely (0-100) a heuristic-mode match is meant as a GTS ID; unset
+    /// outside heuristic discovery, see
+    /// [`ValidationConfig::min_confidence`](crate::ValidationConfig::min_confidence)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<u8>,
+}
+
+/// Upper bound of [`ValidationConfig::context_lines`](crate::ValidationConfig::context_lines).
+pub const MAX_CONTEXT_LINES: usize = 10;
+
+/// A few lines of the file around a finding.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContextWindow {
+    /// Line number (1-indexed) of the first entry of `lines`
+    pub start_line: usize,
+    /// The lines themselves, including the finding's line
+    pub lines: Vec<String>,
+}
+
+impl ContextWindow {
+    /// Up to `radius` lines (capped at [`MAX_CONTEXT_LINES`]) before and after
+    /// the 1-indexed `line` of `content`; `None` if `line` is not in `content`.
+    #[must_use]
+    pub fn around(content: &str, line: usize, radius: usize) -> Option<Self> {
+        if line == 0 {
+            return None;
+        }
+        let radius = radius.min(MAX_CONTEXT_LINES);
+        let start_line = line.saturating_sub(radius).max(1);
+        let lines: Vec<String> = content
+            .lines()
+            .skip(start_line - 1)
+            .take(line + radius + 1 - start_line)
+            .map(str::to_owned)
+            .collect();
+        (start_line + lines.len() > line).then_some(Self { start_line, lines })
+    }
+}
+
+/// Stable identifier of a finding, for tracking it across commits.
+///
+/// 16 lowercase hex digits: the 64-bit FNV-1a hash of the file path (as
+/// reported), the normalized ID (the raw value if it could not be
+/// normalized), the error code (see [`ValidationError::error_code`]) and the
+/// JSON path, joined by NUL bytes. Line and column are deliberately left out,
+/// so a finding keeps its fingerprint when surrounding lines move; equal
+/// findings in one Markdown file share a fingerprint.
+#[must_use]
+pub fn fingerprint(
+    file: &Path,
+    normalized_id: &str,
+    raw_value: &str,
+    error: &str,
+    json_path: &str,
+) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let id = if normalized_id.is_empty() {
+        raw_value
+    } else {
+        normalized_id
+    };
+    let key = format!(
+        "{}\0{id}\0{}\0{json_path}",
+        file.display(),
+        error_code(error)
+    );
+    let hash = key.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    format!("{hash:016x}")
+}
+
+/// Every value [`ValidationError::error_code`] can return.
+pub const ERROR_CODES: &[&str] = &[
+    "vendor_mismatch",
+    "wildcard",
+    "expected_type_id",
+    "expected_instance_id",
+    "reserved_name",
+    "invalid_id",
+];
+
+/// The rule a finding violates; its code is [`ValidationError::error_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum RuleId {
+    /// The ID's vendor is not the expected one (`vendor_mismatch`).
+    VendorMismatch,
+    /// A wildcard outside a filter/pattern context (`wildcard`).
+    Wildcard,
+    /// An instance ID where a type ID is required (`expected_type_id`).
+    ExpectedTypeId,
+    /// A type ID where an instance ID is required (`expected_instance_id`).
+    ExpectedInstanceId,
+    /// A definition under a reserved vendor/package (`reserved_name`).
+    ReservedName,
+    /// A malformed GTS ID (`invalid_id`).
+    InvalidId,
+}
+
+impl RuleId {
+    /// Every rule, in the order of [`ERROR_CODES`].
+    pub const ALL: &[Self] = &[
+        Self::VendorMismatch,
+        Self::Wildcard,
+        Self::ExpectedTypeId,
+        Self::ExpectedInstanceId,
+        Self::ReservedName,
+        Self::InvalidId,
+    ];
+
+    /// The rule's error code, e.g. `vendor_mismatch`.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::VendorMismatch => "vendor_mismatch",
+            Self::Wildcard => "wildcard",
+            Self::ExpectedTypeId => "expected_type_id",
+            Self::ExpectedInstanceId => "expected_instance_id",
+            Self::ReservedName => "reserved_name",
+            Self::InvalidId => "invalid_id",
+        }
+    }
+
+    /// The rule with error code `code`.
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|rule| rule.code() == code)
+    }
+}
+
+impl std::str::FromStr for RuleId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_code(s).ok_or_else(|| {
+            format!(
+                "unknown rule '{s}' (expected one of: {})",
+                ERROR_CODES.join(", ")
+            )
+        })
+    }
+}
+
+fn rule_of(error: &str) -> RuleId {
+    if error.contains("Vendor mismatch") {
+        RuleId::VendorMismatch
+    } else if error.contains("Wildcard") {
+        RuleId::Wildcard
+    } else if error.starts_with(EXPECTED_TYPE_ID) {
+        RuleId::ExpectedTypeId
+    } else if error.starts_with(EXPECTED_INSTANCE_ID) {
+        RuleId::ExpectedInstanceId
+    } else if error.starts_with(RESERVED_NAME) {
+        RuleId::ReservedName
+    } else {
+        RuleId::InvalidId
+    }
+}
+
+fn error_code(error: &str) -> &'static str {
+    rule_of(error).code()
 }
 
 impl ValidationError {
+    /// Coarse, stable error code: `vendor_mismatch`, `wildcard`,
+    /// `expected_type_id`, `expected_instance_id`, `reserved_name` or `invalid_id`.
+    #[must_use]
+    pub fn error_code(&self) -> &'static str {
+        error_code(&self.error)
+    }
+
+    /// The rule this finding violates.
+    #[must_use]
+    pub fn rule(&self) -> RuleId {
+        rule_of(&self.error)
+    }
+
+    /// Whether the finding fails the run: an error (not a warning or info)
+    /// that is not covered by the baseline.
+    #[must_use]
+    pub fn fails_run(&self) -> bool {
+        !self.baselined && matches!(self.severity, Severity::Error | Severity::Fatal)
+    }
+
+    /// Format the error for human-readable output, followed by its context
+    /// window (if any) with the finding's line marked by `>`.
+    #[must_use]
+    pub fn format_with_context(&self) -> String {
+        let mut text = self.format_human_readable();
+        if let Some(window) = &self.context_window {
+            let width = (window.start_line + window.lines.len()).to_string().len();
+            for (offset, line) in window.lines.iter().enumerate() {
+                let number = window.start_line + offset;
+                let marker = if number == self.line { '>' } else { ' ' };
+                let _ = write!(text, "\n  {marker} {number:>width$} | {line}");
+            }
+        }
+        text
+    }
+
     /// Format the error for human-readable output.
     ///
-    /// For markdown errors: `{file}:{line}:{column}: {error} [{raw_value}]`
-    /// For JSON/YAML errors: `{file}: {error} [{raw_value}] (at {json_path})`
+    /// With a position: `{file}:{line}:{column}: {error} [{raw_value}]`,
+    /// followed by ` (at {json_path})` for JSON/YAML values
+    /// Without one: `{file}: {error} [{raw_value}] (at {json_path})`, with
+    /// ` in document {n}` after the path in a multi-document YAML stream
+    /// Warnings and infos carry a `warning: `/`info: ` prefix before `{error}`.
     #[must_use]
     pub fn format_human_readable(&self) -> String {
+        let prefix = match self.severity {
+            Severity::Warning => "warning: ",
+            Severity::Info => "info: ",
+            Severity::Error | Severity::Fatal => "",
+        };
         if self.line > 0 && self.column > 0 {
-            // Markdown error with line/column
-            format!(
-                "{}:{}:{}: {} [{}]",
+            // Error with line/column
+            let mut text = format!(
+                "{}:{}:{}: {prefix}{} [{}]",
                 self.file.display(),
                 self.line,
                 self.column,
                 self.error,
                 self.raw_value
-            )
+            );
+            if !self.json_path.is_empty() {
+                text.push_str(" (at ");
+                text.push_str(&self.json_path);
+                text.push(')');
+            }
+            text
         } else if !self.json_path.is_empty() {
-            // JSON/YAML error with json_path
+            // Streamed JSON/YAML error with json_path only, and its YAML document
+            let document = self
+                .context
+                .strip_prefix(DOCUMENT_CONTEXT_PREFIX)
+                .and_then(|rest| rest.split_once(": "))
+                .map(|(number, _)| format!(" in {DOCUMENT_CONTEXT_PREFIX}{number}"))
+                .unwrap_or_default();
             format!(
-                "{}: {} [{}] (at {})",
+                "{}: {prefix}{} [{}] (at {}{document})",
                 self.file.display(),
                 self.error,
                 self.raw_value,
@@ -103,7 +348,7 @@ impl ValidationError {
         } else {
             // Fallback: just file and error
             format!(
-                "{}: {} [{}]",
+                "{}: {prefix}{} [{}]",
                 self.file.display(),
                 self.error,
                 self.raw_value
@@ -128,6 +373,11 @@ mod tests {
             normalized_id: "gts.invalid".to_owned(),
             error: "Invalid GTS ID".to_owned(),
             context: "Some context".to_owned(),
+            fingerprint: String::new(),
+            baselined: false,
+            severity: Severity::Error,
+            context_window: None,
+            confidence: None,
         };
 
         let formatted = err.format_human_readable();
@@ -148,6 +398,11 @@ mod tests {
             normalized_id: "gts.invalid".to_owned(),
             error: "Invalid GTS ID".to_owned(),
             context: "x-gts-ref".to_owned(),
+            fingerprint: String::new(),
+            baselined: false,
+            severity: Severity::Error,
+            context_window: None,
+            confidence: None,
         };
 
         let formatted = err.format_human_readable();
@@ -156,5 +411,93 @@ mod tests {
         assert!(formatted.contains("[gts.invalid]"));
         assert!(formatted.contains("(at $.properties.type.x-gts-ref)"));
         assert!(!formatted.contains(":0:0"));
+
+        let err = ValidationError {
+            file: PathBuf::from("config/stream.yaml"),
+            json_path: "$.$id".to_owned(),
+            context: "document 2: $.$id".to_owned(),
+            ..err
+        };
+        assert!(
+            err.format_human_readable()
+                .ends_with("(at $.$id in document 2)")
+        );
+    }
+
+    #[test]
+    fn test_context_window() {
+        let content = "one\ntwo\nthree\nfour\nfive";
+        let window = ContextWindow::around(content, 2, 2).unwrap();
+        assert_eq!(window.start_line, 1);
+        assert_eq!(window.lines, ["one", "two", "three", "four"]);
+        let window = ContextWindow::around(content, 5, 1).unwrap();
+        assert_eq!((window.start_line, window.lines.len()), (4, 2));
+        assert!(ContextWindow::around(content, 9, 1).is_none());
+        assert!(ContextWindow::around(content, 0, 1).is_none());
+        let window = ContextWindow::around(content, 1, 50).unwrap();
+        assert_eq!((window.start_line, window.lines.len()), (1, 5));
+
+        let err = ValidationError {
+            file: PathBuf::from("docs/test.md"),
+            line: 2,
+            column: 1,
+            json_path: String::new(),
+            raw_value: "gts.invalid".to_owned(),
+            normalized_id: "gts.invalid".to_owned(),
+            error: "Invalid GTS ID".to_owned(),
+            context: "two".to_owned(),
+            fingerprint: String::new(),
+            baselined: false,
+            severity: Severity::Error,
+            context_window: ContextWindow::around(content, 2, 1),
+            confidence: None,
+        };
+        let formatted = err.format_with_context();
+        assert!(
+            formatted.ends_with("\n    1 | one\n  > 2 | two\n    3 | three"),
+            "{formatted}"
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        let file = Path::new("docs/a.md");
+        let fp = fingerprint(
+            file,
+            "gts.x.a.b.c.v1~",
+            "gts.x.a.b.c.v1~",
+            "Invalid GTS ID",
+            "",
+        );
+        assert_eq!(fp.len(), 16);
+        // Raw spelling and message wording do not matter, the error class does
+        assert_eq!(
+            fp,
+            fingerprint(
+                file,
+                "gts.x.a.b.c.v1~",
+                "gts://gts.x.a.b.c.v1~",
+                "Other text",
+                ""
+            )
+        );
+        assert_ne!(
+            fp,
+            fingerprint(file, "gts.x.a.b.c.v1~", "", "Vendor mismatch", "")
+        );
+        assert_ne!(
+            fp,
+            fingerprint(
+                Path::new("docs/b.md"),
+                "gts.x.a.b.c.v1~",
+                "",
+                "Invalid GTS ID",
+                ""
+            )
+        );
+        assert_ne!(
+            fingerprint(file, "", "gts.bad", "Invalid GTS ID", ""),
+            fingerprint(file, "", "gts.worse", "Invalid GTS ID", "")
+        );
     }
 }
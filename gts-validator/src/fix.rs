@@ -0,0 +1,449 @@
+//! Mechanical fixes of invalid GTS identifiers.
+//!
+//! [`suggest_fix`] rewrites the raw value of an `invalid_id` finding when the
+//! mistake is mechanical: uppercase letters, `-` instead of `_`, a `gts://`
+//! URI without the `gts.` prefix, or a missing `~` after a segment version.
+//! The rewrite is only offered if it is a valid GTS ID. [`apply_fixes`]
+//! applies the suggestions of a report to the files it was made from, or with
+//! [`FixMode::DryRun`] only renders them as a unified diff.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use gts::GtsID;
+use serde::Serialize;
+
+use crate::error::{RuleId, ValidationError};
+use crate::host::Host;
+use crate::report::ValidationReport;
+
+/// Lines of unchanged context around each change in [`FileFix::diff`].
+const DIFF_CONTEXT: usize = 3;
+
+/// What [`apply_fixes`] does with the fixed content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FixMode {
+    /// Rewrite the files in place (default).
+    #[default]
+    Write,
+    /// Leave the files alone; [`FixReport::diff`] shows what would change.
+    DryRun,
+}
+
+/// One identifier rewritten by [`apply_fixes`].
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AppliedFix {
+    /// Line number (1-indexed).
+    pub line: usize,
+    /// Column number (1-indexed, in characters).
+    pub column: usize,
+    /// The string as written.
+    pub from: String,
+    /// The string it was replaced with.
+    pub to: String,
+}
+
+/// The fixes of one file.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FileFix {
+    pub file: PathBuf,
+    pub fixes: Vec<AppliedFix>,
+    /// Unified diff of the file before and after the fixes.
+    pub diff: String,
+}
+
+/// Result of [`apply_fixes`].
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FixReport {
+    /// Files with at least one fix, in path order.
+    pub files: Vec<FileFix>,
+    /// Findings left as they are: no mechanical fix is known, the finding has
+    /// no position, or its raw value is not found at that position (e.g. a
+    /// JSON string with escapes).
+    pub unfixed: usize,
+    /// Whether the fixed files were written (false for [`FixMode::DryRun`]).
+    pub written: bool,
+}
+
+impl FixReport {
+    /// Number of identifiers rewritten.
+    #[must_use]
+    pub fn fixes_count(&self) -> usize {
+        self.files.iter().map(|f| f.fixes.len()).sum()
+    }
+
+    /// Unified diff of all fixed files.
+    #[must_use]
+    pub fn diff(&self) -> String {
+        self.files.iter().map(|f| f.diff.as_str()).collect()
+    }
+}
+
+/// The raw value of `error` with its mechanical mistakes fixed, if it is an
+/// `invalid_id` finding and the result is a valid GTS ID.
+///
+/// A `gts://` prefix is kept. Wildcards, URI fragments and query strings are
+/// not fixed.
+#[must_use]
+pub fn suggest_fix(error: &ValidationError) -> Option<String> {
+    if error.rule() != RuleId::InvalidId {
+        return None;
+    }
+    repair(error.raw_value.trim())
+}
+
+/// `raw` as a valid GTS ID, if one is found by mechanical rewrites.
+fn repair(raw: &str) -> Option<String> {
+    let (prefix, body) = match raw.strip_prefix("gts://") {
+        Some(body) => ("gts://", body),
+        None => ("", raw),
+    };
+    if body.is_empty() || body.contains(['*', '#', '?']) || body.contains(char::is_whitespace) {
+        return None;
+    }
+    let lower = body.to_lowercase();
+    let body = if lower.starts_with("gts.") {
+        lower
+    } else {
+        format!("gts.{lower}")
+    };
+    // A UUID tail keeps its hyphens, so try both spellings
+    [body.replace('-', "_"), body]
+        .iter()
+        .flat_map(|body| [body.clone(), regroup_segments(body)])
+        .find(|candidate| candidate != raw && GtsID::new(candidate).is_ok())
+        .map(|fixed| format!("{prefix}{fixed}"))
+}
+
+/// Whether `token` is a segment major version such as `v1`.
+fn is_major_version(token: &str) -> bool {
+    token
+        .strip_prefix('v')
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// `id` with a `~` after every segment version that is followed by another
+/// segment.
+fn regroup_segments(id: &str) -> String {
+    let rest = id.strip_prefix("gts.").unwrap_or(id);
+    let mut segments: Vec<String> = vec![];
+    let mut current: Vec<&str> = vec![];
+    let mut tokens = rest.split(['.', '~']).filter(|t| !t.is_empty()).peekable();
+    while let Some(token) = tokens.next() {
+        current.push(token);
+        if current.len() >= 5 && is_major_version(token) {
+            if let Some(minor) = tokens.next_if(|t| t.bytes().all(|b| b.is_ascii_digit())) {
+                current.push(minor);
+            }
+            segments.push(current.join("."));
+            current.clear();
+        }
+    }
+    let type_ended = current.is_empty() && rest.ends_with('~');
+    if !current.is_empty() {
+        segments.push(current.join("."));
+    }
+    let mut regrouped = format!("gts.{}", segments.join("~"));
+    if type_ended {
+        regrouped.push('~');
+    }
+    regrouped
+}
+
+/// Apply the [`suggest_fix`] fixes of `report` to the files on disk.
+///
+/// # Errors
+///
+/// Returns an error if a file with a fix cannot be read or written.
+pub fn apply_fixes(report: &ValidationReport, mode: FixMode) -> anyhow::Result<FixReport> {
+    apply_fixes_with(report, mode, &Host::os())
+}
+
+/// [`apply_fixes`] through the filesystem of `host`.
+///
+/// ```rust
+/// use std::path::{Path, PathBuf};
+/// use std::sync::Arc;
+/// use gts_validator::host::{FileSystem, ManualClock, MemoryFs};
+/// use gts_validator::{
+///     DiscoveryMode, FixMode, FsSourceConfig, Host, ValidationConfig, apply_fixes_with,
+///     validate_fs_with,
+/// };
+///
+/// let fs = Arc::new(MemoryFs::new());
+/// fs.insert("docs/a.md", "Uses `gts.x.core.pkg.my-type.v1~`.\n");
+/// let host = Host::deterministic(fs.clone(), Arc::new(ManualClock::new()));
+///
+/// let mut fs_config = FsSourceConfig::default();
+/// fs_config.paths = vec![PathBuf::from("docs")];
+/// let mut config = ValidationConfig::default();
+/// config.discovery_mode = DiscoveryMode::Heuristic;
+/// let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+///
+/// let fixes = apply_fixes_with(&report, FixMode::Write, &host).unwrap();
+/// assert_eq!(fixes.fixes_count(), 1);
+/// let mut fixed = String::new();
+/// fs.open(Path::new("docs/a.md")).unwrap().read_to_string(&mut fixed).unwrap();
+/// assert_eq!(fixed, "Uses `gts.x.core.pkg.my_type.v1~`.\n");
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if a file with a fix cannot be read or written.
+pub fn apply_fixes_with(
+    report: &ValidationReport,
+    mode: FixMode,
+    host: &Host,
+) -> anyhow::Result<FixReport> {
+    let mut unfixed = 0;
+    let mut by_file: BTreeMap<&Path, Vec<AppliedFix>> = BTreeMap::new();
+    for error in &report.validation_errors {
+        match suggest_fix(error) {
+            Some(to) if error.line > 0 && error.column > 0 => {
+                by_file.entry(&error.file).or_default().push(AppliedFix {
+                    line: error.line,
+                    column: error.column,
+                    from: error.raw_value.trim().to_owned(),
+                    to,
+                });
+            }
+            _ => unfixed += 1,
+        }
+    }
+
+    let fs = host.fs();
+    let mut files = vec![];
+    for (file, mut fixes) in by_file {
+        let mut original = String::new();
+        fs.open(file)
+            .and_then(|mut reader| reader.read_to_string(&mut original))
+            .with_context(|| format!("cannot read {}", file.display()))?;
+
+        // Right to left within a line, so earlier columns stay valid
+        fixes.sort_by(|a, b| a.line.cmp(&b.line).then(b.column.cmp(&a.column)));
+        fixes.dedup_by(|a, b| a.line == b.line && a.column == b.column);
+        let mut lines: Vec<String> = original.split_inclusive('\n').map(str::to_owned).collect();
+        let total = fixes.len();
+        fixes.retain(|fix| replace_at(&mut lines, fix));
+        unfixed += total - fixes.len();
+        if fixes.is_empty() {
+            continue;
+        }
+        fixes.reverse();
+        fixes.sort_by_key(|fix| (fix.line, fix.column));
+
+        let fixed = lines.concat();
+        if mode == FixMode::Write {
+            fs.write(file, fixed.as_bytes())
+                .with_context(|| format!("cannot write {}", file.display()))?;
+        }
+        let diff = unified_diff(file, &original, &fixed);
+        files.push(FileFix {
+            file: file.to_path_buf(),
+            fixes,
+            diff,
+        });
+    }
+    Ok(FixReport {
+        files,
+        unfixed,
+        written: mode == FixMode::Write,
+    })
+}
+
+/// Replace `fix.from` with `fix.to` at its position in `lines`; false if the
+/// text there is not `fix.from`.
+fn replace_at(lines: &mut [String], fix: &AppliedFix) -> bool {
+    let Some(line) = lines.get_mut(fix.line - 1) else {
+        return false;
+    };
+    let Some((start, _)) = line.char_indices().nth(fix.column - 1) else {
+        return false;
+    };
+    if !line[start..].starts_with(&fix.from) {
+        return false;
+    }
+    line.replace_range(start..start + fix.from.len(), &fix.to);
+    true
+}
+
+/// Unified diff of `old` and `new`, which have the same number of lines.
+fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let changed: Vec<usize> = (0..old_lines.len())
+        .filter(|&i| old_lines[i] != new_lines[i])
+        .collect();
+
+    let mut out = format!("--- {0}\n+++ {0}\n", path.display());
+    let mut next = 0;
+    while next < changed.len() {
+        // Changes closer than twice the context share a hunk
+        let first = changed[next];
+        let mut last = first;
+        while next + 1 < changed.len() && changed[next + 1] <= last + 2 * DIFF_CONTEXT {
+            next += 1;
+            last = changed[next];
+        }
+        next += 1;
+        let start = first.saturating_sub(DIFF_CONTEXT);
+        let end = (last + DIFF_CONTEXT + 1).min(old_lines.len());
+        let _ = writeln!(out, "@@ -{0},{1} +{0},{1} @@", start + 1, end - start);
+
+        let mut i = start;
+        while i < end {
+            if old_lines[i] == new_lines[i] {
+                push_diff_line(&mut out, ' ', old_lines[i]);
+                i += 1;
+                continue;
+            }
+            let run_end = (i..end)
+                .find(|&j| old_lines[j] == new_lines[j])
+                .unwrap_or(end);
+            for line in &old_lines[i..run_end] {
+                push_diff_line(&mut out, '-', line);
+            }
+            for line in &new_lines[i..run_end] {
+                push_diff_line(&mut out, '+', line);
+            }
+            i = run_end;
+        }
+    }
+    out
+}
+
+fn push_diff_line(out: &mut String, marker: char, line: &str) {
+    out.push(marker);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{ManualClock, MemoryFs};
+    use crate::{DiscoveryMode, FsSourceConfig, ValidationConfig, validate_fs_with};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_repair_mechanical_mistakes() {
+        assert_eq!(
+            repair("gts.x.core.pkg.my-type.v1~").as_deref(),
+            Some("gts.x.core.pkg.my_type.v1~")
+        );
+        assert_eq!(
+            repair("gts.X.Core.Pkg.MyType.v1~").as_deref(),
+            Some("gts.x.core.pkg.mytype.v1~")
+        );
+        assert_eq!(
+            repair("gts://x.core.pkg.mytype.v1~").as_deref(),
+            Some("gts://gts.x.core.pkg.mytype.v1~")
+        );
+        assert_eq!(
+            repair("gts.x.core.pkg.base.v1.0.y.app.pkg.item.v1").as_deref(),
+            Some("gts.x.core.pkg.base.v1.0~y.app.pkg.item.v1")
+        );
+    }
+
+    #[test]
+    fn test_repair_leaves_unfixable_ids() {
+        assert_eq!(repair("gts.x.core"), None);
+        assert_eq!(repair("gts.x.core.*"), None);
+        assert_eq!(repair("gts://gts.x.core.pkg.mytype.v1~#frag"), None);
+        assert_eq!(repair("gts.x.core.pkg.mytype.v1~"), None);
+        assert_eq!(
+            repair("gts.x.core.pkg.base.v1~7a1d2f34-5678-49ab-9012-abcdef123456"),
+            None
+        );
+    }
+
+    fn scan(fs: &Arc<MemoryFs>) -> (ValidationReport, Host) {
+        let host = Host::deterministic(fs.clone(), Arc::new(ManualClock::new()));
+        let fs_config = FsSourceConfig {
+            paths: vec![PathBuf::from("docs")],
+            ..FsSourceConfig::default()
+        };
+        let config = ValidationConfig {
+            vendor_policy: crate::VendorPolicy::MustMatch("x".to_owned()),
+            discovery_mode: DiscoveryMode::Heuristic,
+            ..ValidationConfig::default()
+        };
+        let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+        (report, host)
+    }
+
+    fn read(fs: &MemoryFs, path: &str) -> String {
+        use crate::host::FileSystem;
+        let mut content = String::new();
+        fs.open(Path::new(path))
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_apply_fixes_writes_files() {
+        let fs = Arc::new(MemoryFs::new());
+        fs.insert(
+            "docs/a.md",
+            "Uses `gts.x.core.pkg.my-type.v1~` and `gts.x.core.pkg.other-type.v1~`.\nAnd `gts.y.core.pkg.mytype.v1~`.",
+        );
+        fs.insert(
+            "docs/b.json",
+            "{\n  \"$id\": \"gts://x.core.pkg.Item.v1~\"\n}\n",
+        );
+        let (report, host) = scan(&fs);
+
+        let fixes = apply_fixes_with(&report, FixMode::Write, &host).unwrap();
+        assert!(fixes.written);
+        assert_eq!(fixes.fixes_count(), 3);
+        // The vendor mismatch has no mechanical fix
+        assert_eq!(fixes.unfixed, 1);
+        assert_eq!(fixes.files[0].fixes[0].column, 7);
+        assert_eq!(fixes.files[0].fixes[0].to, "gts.x.core.pkg.my_type.v1~");
+        assert_eq!(
+            read(&fs, "docs/a.md"),
+            "Uses `gts.x.core.pkg.my_type.v1~` and `gts.x.core.pkg.other_type.v1~`.\nAnd `gts.y.core.pkg.mytype.v1~`."
+        );
+        assert_eq!(
+            read(&fs, "docs/b.json"),
+            "{\n  \"$id\": \"gts://gts.x.core.pkg.item.v1~\"\n}\n"
+        );
+
+        let (report, host) = scan(&fs);
+        assert_eq!(report.validation_errors.len(), 1);
+        let fixes = apply_fixes_with(&report, FixMode::Write, &host).unwrap();
+        assert!(fixes.files.is_empty());
+    }
+
+    #[test]
+    fn test_apply_fixes_dry_run_diff() {
+        let fs = Arc::new(MemoryFs::new());
+        let lines: Vec<String> = (1..=10).map(|i| format!("line {i}")).collect();
+        let mut content = lines.join("\n");
+        content = content.replace("line 2", "Uses `gts.x.core.pkg.a-b.v1~`.");
+        content.push_str("\nLast `gts.x.core.pkg.c-d.v1~`.");
+        fs.insert("docs/a.md", content.clone());
+        let (report, host) = scan(&fs);
+
+        let fixes = apply_fixes_with(&report, FixMode::DryRun, &host).unwrap();
+        assert!(!fixes.written);
+        assert_eq!(read(&fs, "docs/a.md"), content);
+        assert_eq!(
+            fixes.diff(),
+            "--- docs/a.md\n+++ docs/a.md\n\
+             @@ -1,5 +1,5 @@\n line 1\n-Uses `gts.x.core.pkg.a-b.v1~`.\n+Uses `gts.x.core.pkg.a_b.v1~`.\n line 3\n line 4\n line 5\n\
+             @@ -8,4 +8,4 @@\n line 8\n line 9\n line 10\n-Last `gts.x.core.pkg.c-d.v1~`.\n\\ No newline at end of file\n+Last `gts.x.core.pkg.c_d.v1~`.\n\\ No newline at end of file\n"
+        );
+    }
+}
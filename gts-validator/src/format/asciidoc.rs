@@ -0,0 +1,210 @@
+//! `AsciiDoc` scanner for GTS identifiers.
+//!
+//! Prose and listing blocks go through the Markdown line scanner. A block
+//! attribute line names the language of the block that follows:
+//!
+//! - `[source,<language>]` (or `[,<language>]`) before a `----` delimited
+//!   block, or before a paragraph: grammar languages (`ebnf`, `regex`, ...)
+//!   are skipped like EBNF fences in Markdown, `json` and `yaml` blocks are
+//!   walked like JSON and YAML files.
+//! - ```` ```<language> ```` fences are read the same way.
+//! - `....` literal blocks and unlabeled listings are scanned line by line.
+
+use std::path::Path;
+
+use crate::format::markdown::{
+    BlockLanguage, LineScanner, MarkdownScanResult, is_grammar_language, mentions_gts,
+};
+
+/// How the lines of a block are scanned.
+#[derive(Debug, Clone, Copy, Default)]
+struct BlockStyle {
+    skip: bool,
+    structured: Option<BlockLanguage>,
+}
+
+impl BlockStyle {
+    fn for_language(language: &str) -> Self {
+        let language = language.trim().to_lowercase();
+        Self {
+            skip: is_grammar_language(&language),
+            structured: BlockLanguage::from_info(&language),
+        }
+    }
+}
+
+/// The style a block attribute line (`[source,json]`) sets for the next
+/// block; `None` for other lines, including other attribute lists.
+fn block_attributes(line: &str) -> Option<BlockStyle> {
+    let attributes = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+    if attributes.starts_with('[') {
+        return None; // [[anchor]]
+    }
+    let mut positional = attributes.split(',');
+    let style = positional.next().unwrap_or_default().trim();
+    match (style, positional.next()) {
+        ("source" | "", Some(language)) => Some(BlockStyle::for_language(language)),
+        ("source" | "listing" | "literal", None) => Some(BlockStyle::default()),
+        _ => None,
+    }
+}
+
+/// A delimiter line (`----`, `....`, or a ```` ``` ```` fence) and, for a
+/// fence, its language.
+fn parse_delimiter(line: &str) -> Option<(&str, Option<&str>)> {
+    let line = line.trim_end();
+    if let Some(language) = line.strip_prefix("```") {
+        return Some(("```", Some(language)));
+    }
+    let first = line.chars().next()?;
+    (matches!(first, '-' | '.') && line.len() >= 4 && line.chars().all(|c| c == first))
+        .then_some((line, None))
+}
+
+/// An open block: delimited (closed by the same delimiter) or a paragraph
+/// (closed by a blank line).
+struct Block<'a> {
+    delimiter: Option<&'a str>,
+    style: BlockStyle,
+    lines: Vec<(usize, &'a str)>,
+}
+
+impl<'a> Block<'a> {
+    fn push(&mut self, scanner: &mut LineScanner, line_number: usize, line: &'a str) {
+        if self.style.skip {
+            return;
+        }
+        if self.style.structured.is_some() {
+            self.lines.push((line_number, line));
+        } else {
+            scanner.scan_line(line_number, line);
+        }
+    }
+
+    fn close(self, scanner: &mut LineScanner) {
+        if let Some(language) = self.style.structured {
+            scanner.scan_block(language, &self.lines, 0);
+        }
+    }
+}
+
+/// Scan `AsciiDoc` content for GTS identifiers, with the options of
+/// [`scan_markdown_content`](crate::format::markdown::scan_markdown_content).
+pub fn scan_asciidoc_content(
+    content: &str,
+    path: &Path,
+    vendor: Option<&str>,
+    heuristic: bool,
+    example_heuristics: bool,
+    skip_tokens: &[String],
+) -> MarkdownScanResult {
+    let mut scanner = LineScanner::new(path, vendor, heuristic, example_heuristics, skip_tokens);
+    if !mentions_gts(content) {
+        return scanner.into_result();
+    }
+    let mut block: Option<Block> = None;
+    // Style set by the last block attribute line, for the next block
+    let mut pending: Option<BlockStyle> = None;
+
+    for (line_num, line) in content.lines().enumerate() {
+        let line_number = line_num + 1;
+        if let Some(open) = &mut block {
+            let closes = match open.delimiter {
+                Some(delimiter) => line.trim_end() == delimiter,
+                None => line.trim().is_empty(),
+            };
+            if closes {
+                if let Some(open) = block.take() {
+                    open.close(&mut scanner);
+                }
+            } else {
+                open.push(&mut scanner, line_number, line);
+            }
+            continue;
+        }
+
+        if let Some(style) = block_attributes(line) {
+            pending = Some(style);
+            continue;
+        }
+        if let Some((delimiter, language)) = parse_delimiter(line) {
+            let style = match language {
+                Some(language) if !language.trim().is_empty() => BlockStyle::for_language(language),
+                _ => pending.take().unwrap_or_default(),
+            };
+            pending = None;
+            block = Some(Block {
+                delimiter: Some(delimiter),
+                style,
+                lines: Vec::new(),
+            });
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(style) = pending.take() {
+            let mut paragraph = Block {
+                delimiter: None,
+                style,
+                lines: Vec::new(),
+            };
+            paragraph.push(&mut scanner, line_number, line);
+            block = Some(paragraph);
+            continue;
+        }
+        scanner.scan_line(line_number, line);
+    }
+
+    if let Some(open) = block {
+        open.close(&mut scanner);
+    }
+    scanner.into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(content: &str) -> MarkdownScanResult {
+        scan_asciidoc_content(content, Path::new("doc.adoc"), Some("x"), false, true, &[])
+    }
+
+    #[test]
+    fn test_asciidoc_grammar_blocks_skipped() {
+        let content = "[source,ebnf]\n\
+                       ----\n\
+                       id = \"gts.y.core.pkg.mytype.v1~\" ;\n\
+                       ----\n\
+                       \n\
+                       [source, regex]\n\
+                       gts.y.core.pkg.pattern.v1~\n\
+                       \n\
+                       ```ebnf\n\
+                       id = \"gts.y.core.pkg.fenced.v1~\" ;\n\
+                       ```\n\
+                       \n\
+                       Uses `gts.y.core.pkg.prose.v1~`.\n";
+        let errors = scan(content).errors;
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert_eq!(errors[0].line, 13);
+        assert_eq!(errors[0].normalized_id, "gts.y.core.pkg.prose.v1~");
+    }
+
+    #[test]
+    fn test_asciidoc_listing_and_json_blocks() {
+        let content = "....\n\
+                       gts.y.core.pkg.literal.v1~\n\
+                       ....\n\
+                       \n\
+                       [,json]\n\
+                       ----\n\
+                       {\"$id\": \"gts.y.core.pkg.block.v1~\"}\n\
+                       ----\n";
+        let errors = scan(content).errors;
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert_eq!((errors[0].line, errors[0].json_path.as_str()), (2, ""));
+        assert_eq!((errors[1].line, errors[1].column), (7, 10));
+        assert_eq!(errors[1].json_path, "$.$id");
+    }
+}
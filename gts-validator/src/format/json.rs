@@ -1,17 +1,29 @@
 //! JSON file scanner for GTS identifiers.
 //!
 //! Uses tree-walking to scan string values (not keys by default).
+//! Comment metadata (`$comment`, `description`) is scanned unless disabled.
 
 use std::path::Path;
 
 use serde_json::Value;
 
+use crate::candidate::{Candidate, Location, check_candidate};
 use crate::error::{ScanError, ScanErrorKind, ValidationError};
-use crate::normalize::normalize_candidate;
-use crate::validator::validate_candidate;
+use crate::format::position::SourceMap;
+use crate::format::stream::StreamRules;
+use crate::ignore::IgnoreScopes;
+
+/// Keys whose string values are documentation text rather than structural data.
+/// Scanning of these values is controlled by `ValidationConfig::scan_comments`.
+pub const COMMENT_KEYS: &[&str] = &["$comment", "description"];
+
+/// Prefix of the `context` of findings in object keys (rather than values).
+pub const KEY_CONTEXT_PREFIX: &str = "key: ";
 
 /// Scan JSON content for GTS identifiers.
 ///
+/// Findings covered by an `x-gts-ignore` directive are moved to `suppressed`.
+///
 /// # Errors
 ///
 /// Returns a `ScanError` if the content is not valid JSON.
@@ -19,8 +31,8 @@ use crate::validator::validate_candidate;
 pub fn scan_json_content(
     content: &str,
     path: &Path,
-    vendor: Option<&str>,
-    scan_keys: bool,
+    rules: &StreamRules<'_>,
+    suppressed: &mut Vec<ValidationError>,
 ) -> Result<Vec<ValidationError>, ScanError> {
     let value: Value = serde_json::from_str(content).map_err(|e| ScanError {
         file: path.to_owned(),
@@ -29,7 +41,21 @@ pub fn scan_json_content(
     })?;
 
     let mut errors = Vec::new();
-    walk_json_value(&value, path, vendor, &mut errors, "$", scan_keys);
+    walk_json_value(
+        &value,
+        path,
+        rules.vendor,
+        &mut errors,
+        "$",
+        rules.scan_keys,
+        rules.scan_comments,
+    );
+    rules.ref_forms.check(&value, path, "$", &mut errors);
+    rules.reservations.check(&value, path, &mut errors);
+    IgnoreScopes::of_value(&value, "$").split_off(&mut errors, 0, suppressed);
+    if !errors.is_empty() {
+        SourceMap::json(content).locate(&mut errors);
+    }
     Ok(errors)
 }
 
@@ -41,14 +67,23 @@ pub fn scan_json_file(
     max_file_size: u64,
     scan_keys: bool,
 ) -> Result<Vec<ValidationError>, ScanError> {
+    use crate::ref_form::RefFormRules;
+    use crate::reserved::ReservationRules;
     use crate::strategy::fs::{ScanResult, read_file_bounded};
 
-    let content = match read_file_bounded(path, max_file_size) {
+    let content = match read_file_bounded(&crate::host::OsFs, path, max_file_size) {
         ScanResult::Ok(c) => c,
         ScanResult::Err(e) => return Err(e),
     };
 
-    scan_json_content(&content, path, vendor, scan_keys)
+    let rules = StreamRules {
+        vendor,
+        scan_keys,
+        scan_comments: true,
+        ref_forms: &RefFormRules::default(),
+        reservations: &ReservationRules::default(),
+    };
+    scan_json_content(&content, path, &rules, &mut Vec::new())
 }
 
 /// Walk a JSON value tree and validate GTS identifiers in string values.
@@ -56,6 +91,10 @@ pub fn scan_json_file(
 /// deserialized to `serde_json::Value` and validated through this same path).
 /// Markdown scanning uses regex-based discovery instead, where the pattern
 /// itself stops at tilde-followed-by-dot to naturally exclude filenames.
+///
+/// When `scan_comments` is `false`, string values under [`COMMENT_KEYS`] are skipped.
+/// Non-string values under those keys (e.g. a property *named* `description`) are
+/// still walked.
 pub fn walk_json_value(
     value: &Value,
     path: &Path,
@@ -63,104 +102,18 @@ pub fn walk_json_value(
     errors: &mut Vec<ValidationError>,
     json_path: &str,
     scan_keys: bool,
+    scan_comments: bool,
 ) {
     match value {
-        Value::String(s) => {
-            let candidate_str = s.as_str();
-            let is_xgts_ref = json_path.ends_with(".x-gts-ref");
-
-            // PRE-FILTER: x-gts-ref special values that are NOT GTS identifiers.
-            // These must be checked BEFORE normalization to avoid misleading errors.
-            // Spec section 9.6 defines allowed x-gts-ref values:
-            //   - GTS identifier (gts.vendor.pkg...)
-            //   - Wildcard pattern (gts.*)
-            //   - Bare wildcard (*)
-            //   - Relative JSON pointer (/$id, /properties/id, etc.)
-            if is_xgts_ref && (candidate_str.starts_with('/') || candidate_str == "*") {
-                return; // valid x-gts-ref value, not a GTS ID to validate
-            }
-
-            // Only consider strings that look like GTS identifiers
-            // Skip filenames that contain GTS IDs (e.g., "gts.x.core.type.v1~.schema.json")
-            // A string is likely a filename if it contains a tilde followed by a dot and extension
-            let looks_like_filename = !candidate_str.starts_with("gts://")
-                && candidate_str.contains("~.")
-                && candidate_str
-                    .rfind('.')
-                    .is_some_and(|pos| pos > candidate_str.rfind('~').unwrap_or(0));
-
-            // For plain gts. strings, skip if it looks like a filename (e.g., "gts.x.type.v1~.schema.json")
-            if looks_like_filename {
-                return;
-            }
-
-            if candidate_str.starts_with("gts://gts.") || candidate_str.starts_with("gts.") {
-                match normalize_candidate(candidate_str) {
-                    Ok(candidate) => {
-                        let allow_wildcards = is_xgts_ref;
-                        let validation_errors =
-                            validate_candidate(&candidate, vendor, allow_wildcards);
-                        for err in validation_errors {
-                            errors.push(ValidationError {
-                                file: path.to_owned(),
-                                line: 0,
-                                column: 0,
-                                json_path: json_path.to_owned(),
-                                raw_value: candidate.original.clone(),
-                                normalized_id: candidate.gts_id.clone(),
-                                error: err,
-                                context: json_path.to_owned(),
-                            });
-                        }
-                    }
-                    Err(e) => {
-                        errors.push(ValidationError {
-                            file: path.to_owned(),
-                            line: 0,
-                            column: 0,
-                            json_path: json_path.to_owned(),
-                            raw_value: candidate_str.to_owned(),
-                            normalized_id: String::new(),
-                            error: e,
-                            context: json_path.to_owned(),
-                        });
-                    }
-                }
-            }
-        }
+        Value::String(s) => check_json_string(s, json_path, path, vendor, errors),
         Value::Object(map) => {
             for (key, val) in map {
                 // Optionally scan keys
-                if scan_keys && (key.starts_with("gts://") || key.starts_with("gts.")) {
-                    match normalize_candidate(key) {
-                        Ok(candidate) => {
-                            let validation_errors = validate_candidate(&candidate, vendor, false);
-                            for err in validation_errors {
-                                errors.push(ValidationError {
-                                    file: path.to_owned(),
-                                    line: 0,
-                                    column: 0,
-                                    json_path: format!("{json_path}.{key}"),
-                                    raw_value: candidate.original.clone(),
-                                    normalized_id: candidate.gts_id.clone(),
-                                    error: err,
-                                    context: format!("key: {key}"),
-                                });
-                            }
-                        }
-                        Err(e) => {
-                            errors.push(ValidationError {
-                                file: path.to_owned(),
-                                line: 0,
-                                column: 0,
-                                json_path: format!("{json_path}.{key}"),
-                                raw_value: key.clone(),
-                                normalized_id: String::new(),
-                                error: e,
-                                context: format!("key: {key}"),
-                            });
-                        }
-                    }
+                if scan_keys {
+                    check_json_key(key, json_path, path, vendor, errors);
+                }
+                if !scan_comments && val.is_string() && COMMENT_KEYS.contains(&key.as_str()) {
+                    continue;
                 }
                 walk_json_value(
                     val,
@@ -169,6 +122,7 @@ pub fn walk_json_value(
                     errors,
                     &format!("{json_path}.{key}"),
                     scan_keys,
+                    scan_comments,
                 );
             }
         }
@@ -181,6 +135,7 @@ pub fn walk_json_value(
                     errors,
                     &format!("{json_path}[{i}]"),
                     scan_keys,
+                    scan_comments,
                 );
             }
         }
@@ -188,9 +143,78 @@ pub fn walk_json_value(
     }
 }
 
+/// Validate the string value at `json_path` if it looks like a GTS identifier.
+pub fn check_json_string(
+    candidate_str: &str,
+    json_path: &str,
+    path: &Path,
+    vendor: Option<&str>,
+    errors: &mut Vec<ValidationError>,
+) {
+    let is_xgts_ref = json_path.ends_with(".x-gts-ref");
+
+    // PRE-FILTER: x-gts-ref special values that are NOT GTS identifiers.
+    // These must be checked BEFORE normalization to avoid misleading errors.
+    // Spec section 9.6 defines allowed x-gts-ref values:
+    //   - GTS identifier (gts.vendor.pkg...)
+    //   - Wildcard pattern (gts.*)
+    //   - Bare wildcard (*)
+    //   - Relative JSON pointer (/$id, /properties/id, etc.)
+    if is_xgts_ref && (candidate_str.starts_with('/') || candidate_str == "*") {
+        return; // valid x-gts-ref value, not a GTS ID to validate
+    }
+
+    // Only consider strings that look like GTS identifiers
+    // Skip filenames that contain GTS IDs (e.g., "gts.x.core.type.v1~.schema.json")
+    // A string is likely a filename if it contains a tilde followed by a dot and extension
+    let looks_like_filename = !candidate_str.starts_with("gts://")
+        && candidate_str.contains("~.")
+        && candidate_str
+            .rfind('.')
+            .is_some_and(|pos| pos > candidate_str.rfind('~').unwrap_or(0));
+
+    // For plain gts. strings, skip if it looks like a filename (e.g., "gts.x.type.v1~.schema.json")
+    if looks_like_filename {
+        return;
+    }
+
+    // A `gts://` URI is a GTS reference even when the `gts.` prefix is missing
+    if candidate_str.starts_with("gts://") || candidate_str.starts_with("gts.") {
+        let candidate = Candidate {
+            text: candidate_str,
+            location: Location::JsonPath(json_path.to_owned()),
+            context: json_path.to_owned(),
+            allow_wildcards: is_xgts_ref,
+        };
+        errors.extend(check_candidate(&candidate, path, vendor));
+    }
+}
+
+/// Validate the object key `key` of the object at `json_path` if it looks like
+/// a GTS identifier.
+pub fn check_json_key(
+    key: &str,
+    json_path: &str,
+    path: &Path,
+    vendor: Option<&str>,
+    errors: &mut Vec<ValidationError>,
+) {
+    if key.starts_with("gts://") || key.starts_with("gts.") {
+        let candidate = Candidate {
+            text: key,
+            location: Location::JsonPath(format!("{json_path}.{key}")),
+            context: format!("{KEY_CONTEXT_PREFIX}{key}"),
+            allow_wildcards: false,
+        };
+        errors.extend(check_candidate(&candidate, path, vendor));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ref_form::RefFormRules;
+    use crate::reserved::ReservationRules;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -216,6 +240,15 @@ mod tests {
         assert!(!errors.is_empty());
     }
 
+    #[test]
+    fn test_scan_json_uri_without_gts_prefix() {
+        let content = r#"{"$id": "gts://x.core.events.type.v1~"}"#;
+        let file = create_temp_json(content);
+        let errors = scan_json_file(file.path(), None, 10_485_760, false).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].error.contains("Does not start with 'gts.'"));
+    }
+
     #[test]
     fn test_scan_json_xgts_ref_wildcard() {
         let content = r#"{"x-gts-ref": "gts.x.core.*"}"#;
@@ -304,6 +337,26 @@ mod tests {
         assert!(errors[0].json_path.contains("properties.type.x-gts-ref"));
     }
 
+    #[test]
+    fn test_scan_json_error_positions() {
+        let content = "{\n  \"gts.bad.key\": {\n    \"x-gts-ref\": \"gts.invalid\"\n  }\n}\n";
+        let file = create_temp_json(content);
+        let errors = scan_json_file(file.path(), None, 10_485_760, true).unwrap();
+        let positions: Vec<_> = errors
+            .iter()
+            .map(|e| (e.json_path.as_str(), e.line, e.column))
+            .collect();
+        assert!(
+            positions.contains(&("$.gts.bad.key", 2, 4)),
+            "{positions:?}"
+        );
+        assert!(
+            positions.contains(&("$.gts.bad.key.x-gts-ref", 3, 19)),
+            "{positions:?}"
+        );
+        assert!(errors[0].format_human_readable().contains(":2:4: "));
+    }
+
     #[test]
     fn test_scan_json_vendor_mismatch() {
         let content = r#"{"$id": "gts://gts.hx.core.events.type.v1~"}"#;
@@ -342,4 +395,52 @@ mod tests {
             "Invalid GTS ID keys should be caught when key scanning is enabled"
         );
     }
+
+    #[test]
+    fn test_scan_json_comments_scanned_by_default() {
+        let content = r#"{"$comment": "gts.invalid", "description": "gts.bad.id"}"#;
+        let file = create_temp_json(content);
+        let errors = scan_json_file(file.path(), None, 10_485_760, false).unwrap();
+        assert_eq!(
+            errors.len(),
+            2,
+            "Comment fields should be scanned: {errors:?}"
+        );
+    }
+
+    #[test]
+    fn test_scan_json_comments_skipped_when_disabled() {
+        let content = r#"{
+            "$comment": "gts.invalid",
+            "description": "gts.bad.id",
+            "$id": "gts.also.invalid"
+        }"#;
+        let rules = StreamRules {
+            vendor: None,
+            scan_keys: false,
+            scan_comments: false,
+            ref_forms: &RefFormRules::default(),
+            reservations: &ReservationRules::default(),
+        };
+        let errors =
+            scan_json_content(content, Path::new("test.json"), &rules, &mut Vec::new()).unwrap();
+        assert_eq!(errors.len(), 1, "Only $id should be reported: {errors:?}");
+        assert_eq!(errors[0].json_path, "$.$id");
+    }
+
+    #[test]
+    fn test_scan_json_property_named_description_still_walked() {
+        // A schema property *named* "description" is structural, not a comment
+        let content = r#"{"properties": {"description": {"x-gts-ref": "gts.invalid"}}}"#;
+        let rules = StreamRules {
+            vendor: None,
+            scan_keys: false,
+            scan_comments: false,
+            ref_forms: &RefFormRules::default(),
+            reservations: &ReservationRules::default(),
+        };
+        let errors =
+            scan_json_content(content, Path::new("test.json"), &rules, &mut Vec::new()).unwrap();
+        assert_eq!(errors.len(), 1, "Nested object must be walked: {errors:?}");
+    }
 }
@@ -2,17 +2,32 @@
 //!
 //! Uses a two-stage approach:
 //! 1. Discovery regex finds candidates
-//! 2. `normalize_candidate()` → `validate_candidate()` validates them
+//! 2. `check_candidate()` normalizes and validates them
+//!
+//! Every candidate contains the literal `gts.`, so lines without it are rejected
+//! with a `memchr` substring search before the regex runs.
+//!
+//! Fenced blocks tagged `json` or `yaml`/`yml` are parsed and walked like JSON
+//! and YAML files instead, so their errors carry a `json_path`; line and column
+//! point at the value inside the Markdown file. Blocks that do not parse (e.g.
+//! examples elided with `...`) fall back to the regex scan.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::LazyLock;
 
+use memchr::memmem;
 use regex::Regex;
+use serde_json::Value;
 
+use crate::candidate::{Candidate, Location, check_candidate};
+use crate::confidence::heuristic_confidence;
 use crate::error::ValidationError;
-use crate::normalize::normalize_candidate;
-use crate::validator::{is_bad_example_context, is_wildcard_context, validate_candidate};
+use crate::format::json::walk_json_value;
+use crate::ignore::MarkdownIgnores;
+use crate::validator::{
+    is_acknowledged_invalid_example, is_bad_example_context, is_wildcard_context,
+};
 
 /// Markdown parsing state for code block tracking
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -20,11 +35,44 @@ enum MarkdownState {
     Prose,
     FencedBlock {
         skip: bool,
+        /// Language with a structured scanner; lines are buffered until the fence closes
+        structured: Option<BlockLanguage>,
         fence_char: char,
         opening_fence_len: usize,
     },
 }
 
+/// Fenced block languages routed to a structured scanner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockLanguage {
+    Json,
+    Yaml,
+}
+
+impl BlockLanguage {
+    pub fn from_info(language: &str) -> Option<Self> {
+        // Only the first word of the info string names the language
+        match language.split_whitespace().next()? {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Parse a block; `None` if it is not valid in this language.
+    fn parse(self, text: &str) -> Option<Vec<Value>> {
+        match self {
+            Self::Json => serde_json::from_str(text).ok().map(|doc| vec![doc]),
+            Self::Yaml => serde_saphyr::from_multiple(text).ok(),
+        }
+    }
+}
+
+/// Grammar/pattern definition blocks, which are not scanned at all.
+pub fn is_grammar_language(language: &str) -> bool {
+    matches!(language, "ebnf" | "regex" | "bnf" | "abnf" | "grammar")
+}
+
 fn parse_fence(trimmed_line: &str) -> Option<(char, usize)> {
     let fence_char = match trimmed_line.as_bytes().first() {
         Some(b'`') => '`',
@@ -86,22 +134,42 @@ static GTS_DISCOVERY_PATTERN_WELL_FORMED: LazyLock<Regex> = LazyLock::new(|| {
     }
 });
 
+/// Literal present in every discovery match (also inside `gts://gts.`).
+static GTS_LITERAL_FINDER: LazyLock<memmem::Finder<'static>> =
+    LazyLock::new(|| memmem::Finder::new(b"gts."));
+
+/// Findings of a markdown scan, including candidates skipped as invalid examples.
+#[derive(Debug, Default)]
+pub struct MarkdownScanResult {
+    pub errors: Vec<ValidationError>,
+    /// Candidates skipped because of an explicit invalid-example marker/tag.
+    pub acknowledged_examples: usize,
+    /// Candidates skipped because of the emoji/keyword bad-example heuristics.
+    pub heuristic_skips: usize,
+    /// Findings suppressed by `gts-ignore` directives, see [`crate::ignore`].
+    pub suppressed: Vec<ValidationError>,
+}
+
 /// Scan markdown content for GTS identifiers.
+///
+/// With `example_heuristics` off, only explicit markers
+/// (`<!-- gts:invalid-example -->`, `(invalid)`) exempt a candidate from validation.
 pub fn scan_markdown_content(
     content: &str,
     path: &Path,
     vendor: Option<&str>,
     heuristic: bool,
+    example_heuristics: bool,
     skip_tokens: &[String],
-) -> Vec<ValidationError> {
-    let pattern = if heuristic {
-        &*GTS_DISCOVERY_PATTERN_RELAXED
-    } else {
-        &*GTS_DISCOVERY_PATTERN_WELL_FORMED
-    };
-    let mut errors = Vec::new();
+) -> MarkdownScanResult {
+    let mut scanner = LineScanner::new(path, vendor, heuristic, example_heuristics, skip_tokens);
+    // Without the literal anywhere there is nothing to find; fences don't matter
+    if !mentions_gts(content) {
+        return scanner.result;
+    }
     let mut state = MarkdownState::Prose;
-    let mut seen_candidates: HashSet<(usize, String)> = HashSet::new();
+    // Lines of the open structured block, with their line numbers
+    let mut block: Vec<(usize, &str)> = Vec::new();
 
     for (line_num, line) in content.lines().enumerate() {
         let line_number = line_num + 1; // 1-indexed
@@ -115,25 +183,28 @@ pub fn scan_markdown_content(
                     let language = trimmed_line[fence_len..].trim().to_lowercase();
 
                     // Skip grammar/pattern definition blocks
-                    let skip = matches!(
-                        language.as_str(),
-                        "ebnf" | "regex" | "bnf" | "abnf" | "grammar"
-                    );
+                    let skip = is_grammar_language(&language);
 
                     state = MarkdownState::FencedBlock {
                         skip,
+                        structured: BlockLanguage::from_info(&language),
                         fence_char,
                         opening_fence_len: fence_len,
                     };
                     continue;
                 }
                 MarkdownState::FencedBlock {
+                    structured,
                     fence_char: open_fence_char,
                     opening_fence_len,
                     ..
                 } => {
                     // Exiting a fenced block requires matching delimiter with sufficient length.
                     if fence_char == *open_fence_char && fence_len >= *opening_fence_len {
+                        if let Some(language) = structured {
+                            scanner.scan_block(*language, &block, 0);
+                            block.clear();
+                        }
                         state = MarkdownState::Prose;
                         continue;
                     }
@@ -141,78 +212,262 @@ pub fn scan_markdown_content(
             }
         }
 
-        // Skip lines inside skip blocks
-        if let MarkdownState::FencedBlock { skip: true, .. } = state {
+        match state {
+            // Skip lines inside skip blocks
+            MarkdownState::FencedBlock { skip: true, .. } => {}
+            MarkdownState::FencedBlock {
+                structured: Some(_),
+                ..
+            } => block.push((line_number, line)),
+            _ => scanner.scan_line(line_number, line),
+        }
+    }
+
+    // An unclosed fence runs to the end of the document
+    if let MarkdownState::FencedBlock {
+        structured: Some(language),
+        ..
+    } = state
+    {
+        scanner.scan_block(language, &block, 0);
+    }
+
+    MarkdownIgnores::parse(content)
+        .split_off(&mut scanner.result.errors, &mut scanner.result.suppressed);
+    scanner.result
+}
+
+/// Number of leading spaces of `line`.
+pub fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Whether `content` has the `gts.` literal every candidate contains.
+pub fn mentions_gts(content: &str) -> bool {
+    GTS_LITERAL_FINDER.find(content.as_bytes()).is_some()
+}
+
+/// Whether `content` has a `gts.` token (at a word start) outside every
+/// match of the well-formed pattern, i.e. a possibly malformed ID that only
+/// the relaxed pattern can report.
+pub fn has_missed_candidates(content: &str) -> bool {
+    content
+        .lines()
+        .filter(|line| GTS_LITERAL_FINDER.find(line.as_bytes()).is_some())
+        .any(|line| {
+            let matches: Vec<_> = GTS_DISCOVERY_PATTERN_WELL_FORMED
+                .find_iter(line)
+                .map(|m| m.range())
+                .collect();
+            GTS_LITERAL_FINDER
+                .find_iter(line.as_bytes())
+                .filter(|&pos| {
+                    !line[..pos]
+                        .chars()
+                        .next_back()
+                        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+                })
+                .any(|pos| !matches.iter().any(|m| m.contains(&pos)))
+        })
+}
+
+/// Approximate offset in `text` of the value at `json_path`: each key is
+/// looked up (as `"key"` or `key:`) after the previous one. Array indices
+/// keep the offset of their array, whose elements the walk already visits in
+/// order.
+fn path_offset(text: &str, json_path: &str) -> usize {
+    let mut offset = 0;
+    let keys = json_path.trim_start_matches('$').split('.');
+    for key in keys.map(|k| k.split('[').next().unwrap_or(k)) {
+        if key.is_empty() {
             continue;
         }
+        let rest = &text[offset..];
+        let found = [format!("\"{key}\""), format!("{key}:")]
+            .iter()
+            .filter_map(|needle| rest.find(needle.as_str()))
+            .min();
+        if let Some(pos) = found {
+            offset += pos;
+        }
+    }
+    offset
+}
+
+/// Per-file state shared by the line and block scans, also driven by the
+/// reStructuredText and `AsciiDoc` scanners.
+pub struct LineScanner<'a> {
+    pattern: &'a Regex,
+    path: &'a Path,
+    vendor: Option<&'a str>,
+    /// Whether `pattern` is the relaxed one, whose matches get a confidence
+    heuristic: bool,
+    example_heuristics: bool,
+    skip_tokens: &'a [String],
+    seen_candidates: HashSet<(usize, String)>,
+    result: MarkdownScanResult,
+}
+
+impl<'a> LineScanner<'a> {
+    pub fn new(
+        path: &'a Path,
+        vendor: Option<&'a str>,
+        heuristic: bool,
+        example_heuristics: bool,
+        skip_tokens: &'a [String],
+    ) -> Self {
+        Self {
+            pattern: if heuristic {
+                &GTS_DISCOVERY_PATTERN_RELAXED
+            } else {
+                &GTS_DISCOVERY_PATTERN_WELL_FORMED
+            },
+            path,
+            vendor,
+            heuristic,
+            example_heuristics,
+            skip_tokens,
+            seen_candidates: HashSet::new(),
+            result: MarkdownScanResult::default(),
+        }
+    }
+
+    pub fn into_result(self) -> MarkdownScanResult {
+        self.result
+    }
+
+    /// Whether the candidate at `start..end` of `line` is exempt from validation,
+    /// counting the exemption.
+    fn is_exempt(&mut self, line: &str, start: usize, end: usize) -> bool {
+        // Skip validation for explicitly acknowledged invalid examples
+        if is_acknowledged_invalid_example(line, end) {
+            self.result.acknowledged_examples += 1;
+            return true;
+        }
+
+        // Skip validation if this is a "bad example" context
+        if self.example_heuristics && is_bad_example_context(line, start) {
+            self.result.heuristic_skips += 1;
+            return true;
+        }
+
+        // Check consumer-provided skip tokens
+        if !self.skip_tokens.is_empty()
+            && let Some(before) = line.get(..start)
+        {
+            let before_lower = before.to_lowercase();
+            if self
+                .skip_tokens
+                .iter()
+                .any(|token| before_lower.contains(&token.to_lowercase()))
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Regex scan of one prose or code line.
+    pub fn scan_line(&mut self, line_number: usize, line: &str) {
+        // Fast rejection: most lines never mention an ID
+        if GTS_LITERAL_FINDER.find(line.as_bytes()).is_none() {
+            return;
+        }
 
         // Find all GTS candidates on this line
-        for mat in pattern.find_iter(line) {
+        for mat in self.pattern.find_iter(line) {
             let candidate_str = mat.as_str();
             let match_start = mat.start();
 
             // Deduplicate: skip if we've seen this candidate on this line
-            if !seen_candidates.insert((line_number, candidate_str.to_owned())) {
+            if !self
+                .seen_candidates
+                .insert((line_number, candidate_str.to_owned()))
+            {
                 continue;
             }
 
-            // Skip validation if this is a "bad example" context
-            if is_bad_example_context(line, mat.start()) {
+            if self.is_exempt(line, match_start, mat.end()) {
                 continue;
             }
 
-            // Check consumer-provided skip tokens
-            if !skip_tokens.is_empty()
-                && let Some(before) = line.get(..mat.start())
-            {
-                let before_lower = before.to_lowercase();
-                if skip_tokens
-                    .iter()
-                    .any(|token| before_lower.contains(&token.to_lowercase()))
-                {
-                    continue;
-                }
+            let candidate = Candidate {
+                text: candidate_str,
+                location: Location::TextPos {
+                    line: line_number,
+                    column: match_start + 1, // 1-indexed
+                },
+                context: line.to_owned(),
+                allow_wildcards: is_wildcard_context(line, match_start),
+            };
+            let confidence = self
+                .heuristic
+                .then(|| heuristic_confidence(candidate_str, line, match_start));
+            self.result.errors.extend(
+                check_candidate(&candidate, self.path, self.vendor)
+                    .into_iter()
+                    .map(|error| ValidationError {
+                        confidence,
+                        ..error
+                    }),
+            );
+        }
+    }
+
+    /// Scan a JSON/YAML fenced block with the structured walker, falling back
+    /// to the line scan if it does not parse. Up to `indent` leading spaces
+    /// are stripped from each line before parsing (indented blocks of other
+    /// markup).
+    pub fn scan_block(&mut self, language: BlockLanguage, block: &[(usize, &str)], indent: usize) {
+        let text = block
+            .iter()
+            .map(|(_, line)| &line[indent_of(line).min(indent)..])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let Some(documents) = language.parse(&text) else {
+            for (line_number, line) in block {
+                self.scan_line(*line_number, line);
             }
+            return;
+        };
 
-            // Normalize the candidate
-            let candidate = match normalize_candidate(candidate_str) {
-                Ok(c) => c,
-                Err(e) => {
-                    errors.push(ValidationError {
-                        file: path.to_owned(),
-                        line: line_number,
-                        column: match_start + 1, // 1-indexed
-                        json_path: String::new(),
-                        raw_value: candidate_str.to_owned(),
-                        normalized_id: String::new(),
-                        error: e,
-                        context: line.to_owned(),
-                    });
+        let mut errors = Vec::new();
+        for doc in &documents {
+            walk_json_value(doc, self.path, self.vendor, &mut errors, "$", false, true);
+        }
+        // The walk visits keys sorted; restore document order so the n-th error
+        // for a value can be paired with its n-th occurrence in the block
+        errors.sort_by_key(|error| path_offset(&text, &error.json_path));
+
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        for mut error in errors {
+            let nth = occurrences.entry(error.raw_value.clone()).or_default();
+            let position = block
+                .iter()
+                .flat_map(|(line_number, line)| {
+                    line.match_indices(error.raw_value.as_str())
+                        .map(move |(start, _)| (*line_number, *line, start))
+                })
+                .nth(*nth);
+            *nth += 1;
+            if let Some((line_number, line, start)) = position {
+                if self.is_exempt(line, start, start + error.raw_value.len()) {
                     continue;
                 }
-            };
-
-            // Check if wildcards are allowed in this context
-            let allow_wildcards = is_wildcard_context(line, match_start);
-
-            // Validate the candidate
-            let validation_errors = validate_candidate(&candidate, vendor, allow_wildcards);
-            for err in validation_errors {
-                errors.push(ValidationError {
-                    file: path.to_owned(),
-                    line: line_number,
-                    column: match_start + 1, // 1-indexed
-                    json_path: String::new(),
-                    raw_value: candidate.original.clone(),
-                    normalized_id: candidate.gts_id.clone(),
-                    error: err,
-                    context: line.to_owned(),
-                });
+                // Same wildcard allowance as the line scan
+                if error.error_code() == "wildcard" && is_wildcard_context(line, start) {
+                    continue;
+                }
+                error.line = line_number;
+                error.column = start + 1;
+                line.clone_into(&mut error.context);
+            } else if let Some((line_number, _)) = block.first() {
+                error.line = *line_number;
+                error.column = 1;
             }
+            self.result.errors.push(error);
         }
     }
-
-    errors
 }
 
 /// Scan a markdown file for GTS identifiers (file-based convenience wrapper).
@@ -236,7 +491,7 @@ pub fn scan_markdown_file(
         Err(_e) => return vec![],
     };
 
-    scan_markdown_content(&content, path, vendor, heuristic, &[])
+    scan_markdown_content(&content, path, vendor, heuristic, true, &[]).errors
 }
 
 #[cfg(test)]
@@ -410,6 +665,18 @@ gts.invalid.pattern.here.v1~
         );
     }
 
+    #[test]
+    fn test_has_missed_candidates() {
+        assert!(!has_missed_candidates(
+            "Valid: gts.x.core.events.type.v1~ only"
+        ));
+        assert!(!has_missed_candidates("No IDs, just mygts.stuff and text"));
+        assert!(has_missed_candidates(
+            "Valid: gts.x.core.events.type.v1~\nmalformed: gts.bad-id.v1"
+        ));
+        assert!(has_missed_candidates("gts.x.core.events.type.name.v1~"));
+    }
+
     #[test]
     fn test_scan_markdown_skip_tokens() {
         // skip_tokens should suppress validation when the token appears before the candidate
@@ -419,8 +686,10 @@ gts.invalid.pattern.here.v1~
             Path::new("test.md"),
             None,
             true, // heuristic mode to ensure the relaxed regex would catch it
+            true,
             &["**given**".to_owned()],
-        );
+        )
+        .errors;
         assert!(
             errors.is_empty(),
             "skip_tokens should suppress validation: {errors:?}"
@@ -433,8 +702,10 @@ gts.invalid.pattern.here.v1~
             Path::new("test.md"),
             Some("x"),
             false,
+            true,
             &[],
-        );
+        )
+        .errors;
         assert!(
             !errors_no_skip.is_empty(),
             "Without skip_tokens, vendor mismatch should be reported"
@@ -446,8 +717,10 @@ gts.invalid.pattern.here.v1~
             Path::new("test.md"),
             Some("x"),
             false,
+            true,
             &["**given**".to_owned()],
-        );
+        )
+        .errors;
         assert!(
             errors_with_skip.is_empty(),
             "With skip_tokens, vendor mismatch should be suppressed: {errors_with_skip:?}"
@@ -494,10 +767,102 @@ gts.invalid.pattern.here.v1~
     fn test_scan_markdown_word_boundary() {
         // Regex should NOT match "xgts.x.core.events.type.v1~" (no word boundary)
         let content = "The identifier xgts.x.core.events.type.v1~ is wrong";
-        let errors = scan_markdown_content(content, Path::new("test.md"), None, false, &[]);
+        let errors =
+            scan_markdown_content(content, Path::new("test.md"), None, false, true, &[]).errors;
         assert!(
             errors.is_empty(),
             "Word boundary should prevent matching xgts.*: {errors:?}"
         );
     }
+
+    #[test]
+    fn test_scan_markdown_counts_acknowledged_examples() {
+        let content = "Bad: `gts.x.core.events.type.v1` (invalid)\n\
+                       gts.y.core.events.type.v1 <!-- gts:invalid-example -->\n\
+                       \u{274c} gts.x.core.events.other.v1\n";
+        let result = scan_markdown_content(content, Path::new("test.md"), None, true, true, &[]);
+        assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+        assert_eq!(result.acknowledged_examples, 2);
+        assert_eq!(result.heuristic_skips, 1);
+    }
+
+    #[test]
+    fn test_scan_markdown_example_heuristics_disabled() {
+        let content = "\u{274c} gts.x.core.events.type.v1\n";
+        let result = scan_markdown_content(content, Path::new("test.md"), None, true, false, &[]);
+        assert_eq!(result.errors.len(), 1, "errors: {:?}", result.errors);
+        assert_eq!(result.heuristic_skips, 0);
+
+        let content = "\u{274c} gts.x.core.events.type.v1 (invalid)\n";
+        let result = scan_markdown_content(content, Path::new("test.md"), None, true, false, &[]);
+        assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+        assert_eq!(result.acknowledged_examples, 1);
+    }
+
+    #[test]
+    fn test_scan_markdown_prefilter_keeps_positions() {
+        let content = "# Title\n\
+                       no ids here\n\
+                       ```ebnf\n\
+                       gts.y.core.events.type.v1~\n\
+                       ```\n\
+                       plain prose\n\
+                       \u{e9}\u{e9} gts://gts.y.core.events.type.v1~ end\n";
+        let result =
+            scan_markdown_content(content, Path::new("test.md"), Some("x"), false, true, &[]);
+        assert_eq!(result.errors.len(), 1, "errors: {:?}", result.errors);
+        assert_eq!(result.errors[0].line, 7);
+        assert_eq!(result.errors[0].column, 6);
+    }
+
+    #[test]
+    fn test_scan_markdown_without_literal_is_empty() {
+        let content = "```\nGTS.X.CORE.EVENTS.TYPE.V1~\n```\n";
+        let result = scan_markdown_content(content, Path::new("test.md"), None, true, true, &[]);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_scan_markdown_json_block_uses_structured_scanner() {
+        let content = "# Example\n\
+                       \n\
+                       ```json\n\
+                       {\n  \"b\": {\"type\": \"gts.y.core.events.type.v1~\"},\n  \"a\": [\"gts.x.core.events.type.v1~\", \"gts.y.core.events.type.v1~\"]\n}\n\
+                       ```\n";
+        let result =
+            scan_markdown_content(content, Path::new("doc.md"), Some("x"), false, true, &[]);
+        let mut found: Vec<_> = result
+            .errors
+            .iter()
+            .map(|e| (e.json_path.as_str(), e.line, e.column))
+            .collect();
+        found.sort_unstable();
+        assert_eq!(found, vec![("$.a[1]", 6, 40), ("$.b.type", 5, 18)]);
+        assert!(result.errors[0].format_human_readable().contains("(at $."));
+    }
+
+    #[test]
+    fn test_scan_markdown_yaml_block_uses_structured_scanner() {
+        let content = "```yaml title=\"event\"\n\
+                       x-gts-ref: gts.x.core.*\n\
+                       items:\n  - id: gts.y.core.events.type.v1~ # <!-- gts:invalid-example -->\n  - id: gts.y.core.events.type.v1~\n\
+                       ```\n";
+        let result =
+            scan_markdown_content(content, Path::new("doc.md"), Some("x"), false, true, &[]);
+        assert_eq!(result.errors.len(), 1, "errors: {:?}", result.errors);
+        assert_eq!(result.errors[0].json_path, "$.items[1].id");
+        assert_eq!((result.errors[0].line, result.errors[0].column), (5, 9));
+        assert_eq!(result.acknowledged_examples, 1);
+    }
+
+    #[test]
+    fn test_scan_markdown_block_fallback_and_wildcard_context() {
+        let content = "```json\n{\"type\": \"gts.y.core.events.type.v1~\", ...}\n```\n\
+                       ```json\n{\"pattern\": \"gts.x.core.*\"}\n```\n";
+        let result =
+            scan_markdown_content(content, Path::new("doc.md"), Some("x"), false, true, &[]);
+        assert_eq!(result.errors.len(), 1, "errors: {:?}", result.errors);
+        assert!(result.errors[0].json_path.is_empty());
+        assert_eq!((result.errors[0].line, result.errors[0].column), (2, 11));
+    }
 }
@@ -2,9 +2,17 @@
 //!
 //! Each sub-module handles a specific file format:
 //! - `markdown` — Markdown files with code-block state machine
+//! - `rst` — reStructuredText, on the Markdown line scanner
+//! - `asciidoc` — `AsciiDoc`, on the Markdown line scanner
 //! - `json` — JSON tree-walker
 //! - `yaml` — YAML scanner (delegates to JSON walker via `serde_json::Value`)
+//! - `stream` — bounded-memory JSON/YAML scanner for files too large to load
+//! - `position` — line/column of JSON/YAML nodes by JSON path
 
+pub mod asciidoc;
 pub mod json;
 pub mod markdown;
+pub mod position;
+pub mod rst;
+pub mod stream;
 pub mod yaml;
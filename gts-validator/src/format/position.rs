@@ -0,0 +1,468 @@
+//! Source positions of JSON/YAML nodes, keyed by JSON path.
+//!
+//! The JSON and YAML scanners walk parsed `serde_json::Value` trees, which
+//! carry no positions. A [`SourceMap`] re-reads the source (a lightweight
+//! cursor for JSON, `saphyr-parser` events for YAML) and records where each
+//! key and value starts under the same JSON paths the walk produces, so
+//! errors can be given a line and column afterwards.
+
+use std::collections::HashMap;
+
+use saphyr_parser::{Event, Parser, ScalarStyle, Span};
+
+use crate::error::ValidationError;
+use crate::format::json::KEY_CONTEXT_PREFIX;
+
+/// 1-indexed line and column (in characters) of a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Positions of the keys and values of one JSON/YAML document.
+///
+/// String positions point at the first character of the string's content,
+/// not at an opening quote. With duplicate keys the last one wins, as in the
+/// parsed tree.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    values: HashMap<String, Position>,
+    keys: HashMap<String, Position>,
+    /// Line of the root node of a YAML document; 0 if unknown.
+    first_line: usize,
+}
+
+impl SourceMap {
+    /// Map of a JSON document; empty past the first syntax error.
+    #[must_use]
+    pub fn json(content: &str) -> Self {
+        let mut cursor = JsonCursor {
+            text: content,
+            offset: 0,
+            line: 1,
+            column: 1,
+            map: Self::default(),
+        };
+        cursor.value("$");
+        cursor.map
+    }
+
+    /// Maps of the documents of a YAML stream, skipping empty (null)
+    /// documents as `serde_saphyr::from_multiple` does. Stops at the first
+    /// syntax error, keeping the documents completed before it.
+    #[must_use]
+    pub fn yaml_documents(content: &str) -> Vec<Self> {
+        let mut documents = Vec::new();
+        let mut builder = YamlBuilder::default();
+        let mut parser = Parser::new_from_str(content);
+        while let Some(Ok((event, span))) = parser.next_event() {
+            match event {
+                Event::DocumentStart(_) => builder = YamlBuilder::default(),
+                Event::DocumentEnd if !builder.null_root => {
+                    documents.push(std::mem::take(&mut builder.map));
+                }
+                event => builder.event(&event, span),
+            }
+        }
+        documents
+    }
+
+    /// Shift every position down by `lines`, for a document that starts
+    /// `lines` lines into the file.
+    #[must_use]
+    pub fn offset_lines(mut self, lines: usize) -> Self {
+        for position in self.values.values_mut().chain(self.keys.values_mut()) {
+            position.line += lines;
+        }
+        if self.first_line > 0 {
+            self.first_line += lines;
+        }
+        self
+    }
+
+    /// Line of the root node of a YAML document, after leading comments and
+    /// its `---` marker.
+    #[must_use]
+    pub fn first_line(&self) -> Option<usize> {
+        (self.first_line > 0).then_some(self.first_line)
+    }
+
+    /// Position of the value at `json_path`.
+    #[must_use]
+    pub fn value(&self, json_path: &str) -> Option<Position> {
+        self.values.get(json_path).copied()
+    }
+
+    /// Position of the key of the value at `json_path`.
+    #[must_use]
+    pub fn key(&self, json_path: &str) -> Option<Position> {
+        self.keys.get(json_path).copied()
+    }
+
+    /// Fill in the line and column of `errors` found by walking this
+    /// document: the key's position for key findings, the value's otherwise.
+    /// Errors already positioned or whose path is not mapped are left alone.
+    pub fn locate(&self, errors: &mut [ValidationError]) {
+        for error in errors.iter_mut().filter(|e| e.line == 0) {
+            let position = if error.context.starts_with(KEY_CONTEXT_PREFIX) {
+                self.key(&error.json_path)
+            } else {
+                self.value(&error.json_path)
+            };
+            if let Some(position) = position {
+                error.line = position.line;
+                error.column = position.column;
+            }
+        }
+    }
+}
+
+/// Recursive-descent reader over JSON text recording node positions.
+///
+/// Only used on text `serde_json` already accepted, so its nesting is
+/// bounded by `serde_json`'s recursion limit; on malformed input it stops
+/// early and keeps what it recorded.
+struct JsonCursor<'a> {
+    text: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+    map: SourceMap,
+}
+
+impl JsonCursor<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.offset).copied()
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.text[self.offset..].chars().next() {
+            self.offset += c.len_utf8();
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.skip_whitespace();
+        (self.peek()? == byte).then(|| self.bump())
+    }
+
+    fn value(&mut self, json_path: &str) -> Option<()> {
+        self.skip_whitespace();
+        let start = self.position();
+        match self.peek()? {
+            b'{' => {
+                self.map.values.insert(json_path.to_owned(), start);
+                self.object(json_path)
+            }
+            b'[' => {
+                self.map.values.insert(json_path.to_owned(), start);
+                self.array(json_path)
+            }
+            b'"' => {
+                let content = Position {
+                    column: start.column + 1,
+                    ..start
+                };
+                self.map.values.insert(json_path.to_owned(), content);
+                self.string().map(drop)
+            }
+            _ => {
+                self.map.values.insert(json_path.to_owned(), start);
+                while self.peek().is_some_and(|b| {
+                    !matches!(b, b',' | b']' | b'}' | b' ' | b'\t' | b'\r' | b'\n')
+                }) {
+                    self.bump();
+                }
+                Some(())
+            }
+        }
+    }
+
+    fn object(&mut self, json_path: &str) -> Option<()> {
+        self.bump();
+        self.skip_whitespace();
+        if self.peek()? == b'}' {
+            self.bump();
+            return Some(());
+        }
+        loop {
+            self.skip_whitespace();
+            let key_start = self.position();
+            let key = self.string()?;
+            let child = format!("{json_path}.{key}");
+            self.map.keys.insert(
+                child.clone(),
+                Position {
+                    column: key_start.column + 1,
+                    ..key_start
+                },
+            );
+            self.expect(b':')?;
+            self.value(&child)?;
+            self.skip_whitespace();
+            match self.peek()? {
+                b',' => self.bump(),
+                b'}' => {
+                    self.bump();
+                    return Some(());
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn array(&mut self, json_path: &str) -> Option<()> {
+        self.bump();
+        self.skip_whitespace();
+        if self.peek()? == b']' {
+            self.bump();
+            return Some(());
+        }
+        for index in 0.. {
+            self.value(&format!("{json_path}[{index}]"))?;
+            self.skip_whitespace();
+            match self.peek()? {
+                b',' => self.bump(),
+                b']' => {
+                    self.bump();
+                    return Some(());
+                }
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    /// Read a string literal, returning its decoded content.
+    fn string(&mut self) -> Option<String> {
+        let start = self.offset;
+        if self.peek()? != b'"' {
+            return None;
+        }
+        self.bump();
+        let mut escaped = false;
+        loop {
+            match self.peek()? {
+                b'\\' => {
+                    escaped = true;
+                    self.bump();
+                    self.bump();
+                }
+                b'"' => {
+                    self.bump();
+                    break;
+                }
+                _ => self.bump(),
+            }
+        }
+        let literal = &self.text[start..self.offset];
+        if escaped {
+            serde_json::from_str(literal).ok()
+        } else {
+            Some(literal[1..literal.len() - 1].to_owned())
+        }
+    }
+}
+
+/// What an open YAML mapping reads next.
+enum MappingSlot {
+    Key,
+    /// The value of the key just read, at this path; `None` after a complex
+    /// key, whose value cannot be addressed by a JSON path.
+    Value(Option<String>),
+}
+
+/// An open YAML collection and the path of the node it expects next.
+enum Frame {
+    Mapping {
+        path: Option<String>,
+        next: MappingSlot,
+    },
+    Sequence {
+        path: Option<String>,
+        next: usize,
+    },
+}
+
+/// Builds the [`SourceMap`] of one YAML document from parser events.
+#[derive(Default)]
+struct YamlBuilder {
+    map: SourceMap,
+    stack: Vec<Frame>,
+    /// Whether the document is a bare null scalar (or empty).
+    null_root: bool,
+}
+
+impl YamlBuilder {
+    fn event(&mut self, event: &Event<'_>, span: Span) {
+        let position = Position {
+            line: span.start.line(),
+            column: span.start.col() + 1,
+        };
+        if self.stack.is_empty() && self.map.first_line == 0 {
+            self.map.first_line = position.line;
+        }
+        match event {
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+            }
+            Event::Scalar(text, style, ..) => {
+                if let Some(Frame::Mapping {
+                    path,
+                    next: next @ MappingSlot::Key,
+                }) = self.stack.last_mut()
+                {
+                    let key_path = path.as_ref().map(|path| format!("{path}.{text}"));
+                    if let Some(key_path) = &key_path {
+                        self.map.keys.insert(key_path.clone(), position);
+                    }
+                    *next = MappingSlot::Value(key_path);
+                    return;
+                }
+                if self.stack.is_empty() {
+                    self.null_root = *style == ScalarStyle::Plain
+                        && matches!(text.as_ref(), "" | "~" | "null" | "Null" | "NULL");
+                }
+                let column = match style {
+                    ScalarStyle::SingleQuoted | ScalarStyle::DoubleQuoted => position.column + 1,
+                    _ => position.column,
+                };
+                if let Some(path) = self.next_path() {
+                    self.map
+                        .values
+                        .insert(path, Position { column, ..position });
+                }
+            }
+            Event::Alias(_) => {
+                if let Some(Frame::Mapping {
+                    next: next @ MappingSlot::Key,
+                    ..
+                }) = self.stack.last_mut()
+                {
+                    *next = MappingSlot::Value(None);
+                } else if let Some(path) = self.next_path() {
+                    self.map.values.insert(path, position);
+                }
+            }
+            Event::MappingStart(..) | Event::SequenceStart(..) => {
+                let path = if let Some(Frame::Mapping {
+                    next: next @ MappingSlot::Key,
+                    ..
+                }) = self.stack.last_mut()
+                {
+                    // Complex key: neither it nor its value has a JSON path
+                    *next = MappingSlot::Value(None);
+                    None
+                } else {
+                    self.next_path()
+                };
+                if let Some(path) = &path {
+                    self.map.values.insert(path.clone(), position);
+                }
+                self.stack
+                    .push(if matches!(event, Event::MappingStart(..)) {
+                        Frame::Mapping {
+                            path,
+                            next: MappingSlot::Key,
+                        }
+                    } else {
+                        Frame::Sequence { path, next: 0 }
+                    });
+            }
+            _ => {}
+        }
+    }
+
+    /// JSON path of the value node about to be read, consuming the pending
+    /// key or sequence index; `None` if it has no JSON path.
+    fn next_path(&mut self) -> Option<String> {
+        match self.stack.last_mut() {
+            None => Some("$".to_owned()),
+            Some(Frame::Mapping { next, .. }) => match std::mem::replace(next, MappingSlot::Key) {
+                MappingSlot::Value(path) => path,
+                MappingSlot::Key => None,
+            },
+            Some(Frame::Sequence { path, next }) => {
+                let index = *next;
+                *next += 1;
+                path.as_ref().map(|path| format!("{path}[{index}]"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+
+    #[test]
+    fn test_json_positions() {
+        let map = SourceMap::json(
+            "{\n  \"$id\": \"gts.x.a.b.c.v1~\",\n  \"items\": [1, {\"r\\u00e9f\": \"gts.y\"}],\n  \"\u{e9}\": \"x\"\n}",
+        );
+        assert_eq!(map.value("$"), Some(at(1, 1)));
+        assert_eq!(map.key("$.$id"), Some(at(2, 4)));
+        assert_eq!(map.value("$.$id"), Some(at(2, 11)));
+        assert_eq!(map.value("$.items[0]"), Some(at(3, 13)));
+        assert_eq!(map.key("$.items[1].r\u{e9}f"), Some(at(3, 18)));
+        assert_eq!(map.value("$.items[1].r\u{e9}f"), Some(at(3, 30)));
+        assert_eq!(map.value("$.\u{e9}"), Some(at(4, 9)));
+    }
+
+    #[test]
+    fn test_json_stops_at_malformed_input() {
+        let map = SourceMap::json("{\"a\": \"x\", \"b\" 1}");
+        assert_eq!(map.value("$.a"), Some(at(1, 8)));
+        assert_eq!(map.value("$.b"), None);
+    }
+
+    #[test]
+    fn test_yaml_positions() {
+        let maps = SourceMap::yaml_documents(
+            "a: gts.x\nb:\n  - \"q\"\n  - {c: 'd'}\n---\n---\n~\n---\n- plain\n",
+        );
+        assert_eq!(maps.len(), 2);
+        assert_eq!(maps[0].key("$.a"), Some(at(1, 1)));
+        assert_eq!(maps[0].value("$.a"), Some(at(1, 4)));
+        assert_eq!(maps[0].value("$.b"), Some(at(3, 3)));
+        assert_eq!(maps[0].value("$.b[0]"), Some(at(3, 6)));
+        assert_eq!(maps[0].key("$.b[1].c"), Some(at(4, 6)));
+        assert_eq!(maps[0].value("$.b[1].c"), Some(at(4, 10)));
+        assert_eq!(maps[1].value("$[0]"), Some(at(9, 3)));
+        assert_eq!(
+            maps[1].clone().offset_lines(2).value("$[0]"),
+            Some(at(11, 3))
+        );
+    }
+
+    #[test]
+    fn test_yaml_complex_keys_are_skipped() {
+        let maps = SourceMap::yaml_documents("? [a, b]\n: gts.x\nc: gts.y\n");
+        assert_eq!(maps[0].value("$.c"), Some(at(3, 4)));
+        assert_eq!(maps[0].values.len(), 2);
+    }
+}
@@ -0,0 +1,177 @@
+//! reStructuredText scanner for GTS identifiers.
+//!
+//! Prose and literal blocks go through the Markdown line scanner. Blocks are
+//! indented under the line that opens them:
+//!
+//! - `.. code-block:: <language>` (or `.. code::`, `.. sourcecode::`): grammar
+//!   languages (`ebnf`, `regex`, ...) are skipped like EBNF fences in Markdown,
+//!   `json` and `yaml` blocks are walked like JSON and YAML files.
+//! - `.. productionlist::` (Sphinx grammars) is skipped.
+//! - A paragraph ending with `::` opens a literal block, scanned line by line.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::format::markdown::{
+    BlockLanguage, LineScanner, MarkdownScanResult, indent_of, is_grammar_language, mentions_gts,
+};
+
+static CODE_DIRECTIVE: LazyLock<Regex> = LazyLock::new(|| {
+    match Regex::new(r"^\s*\.\.\s+(code-block|code|sourcecode|productionlist)::\s*(\S*)") {
+        Ok(regex) => regex,
+        Err(err) => panic!("Invalid code directive regex: {err}"),
+    }
+});
+
+/// An open indented block.
+struct Block<'a> {
+    /// Indentation of the line that opened the block; the block ends at the
+    /// first non-blank line indented no deeper
+    indent: usize,
+    skip: bool,
+    structured: Option<BlockLanguage>,
+    /// Whether directive options (`:linenos:`) may still follow
+    options: bool,
+    /// Indentation of the first content line
+    content_indent: Option<usize>,
+    lines: Vec<(usize, &'a str)>,
+}
+
+impl<'a> Block<'a> {
+    fn new(indent: usize, skip: bool, structured: Option<BlockLanguage>, options: bool) -> Self {
+        Self {
+            indent,
+            skip,
+            structured,
+            options,
+            content_indent: None,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Add a line indented under the block.
+    fn push(&mut self, scanner: &mut LineScanner, line_number: usize, line: &'a str) {
+        if self.options && line.trim_start().starts_with(':') {
+            return;
+        }
+        self.options = false;
+        self.content_indent.get_or_insert(indent_of(line));
+        if self.skip {
+            return;
+        }
+        if self.structured.is_some() {
+            self.lines.push((line_number, line));
+        } else {
+            scanner.scan_line(line_number, line);
+        }
+    }
+
+    fn close(self, scanner: &mut LineScanner) {
+        if let Some(language) = self.structured {
+            scanner.scan_block(language, &self.lines, self.content_indent.unwrap_or(0));
+        }
+    }
+}
+
+/// Scan reStructuredText content for GTS identifiers, with the options of
+/// [`scan_markdown_content`](crate::format::markdown::scan_markdown_content).
+pub fn scan_rst_content(
+    content: &str,
+    path: &Path,
+    vendor: Option<&str>,
+    heuristic: bool,
+    example_heuristics: bool,
+    skip_tokens: &[String],
+) -> MarkdownScanResult {
+    let mut scanner = LineScanner::new(path, vendor, heuristic, example_heuristics, skip_tokens);
+    if !mentions_gts(content) {
+        return scanner.into_result();
+    }
+    let mut block: Option<Block> = None;
+
+    for (line_num, line) in content.lines().enumerate() {
+        let line_number = line_num + 1;
+        if let Some(open) = &mut block {
+            if line.trim().is_empty() {
+                if open.structured.is_some() {
+                    open.lines.push((line_number, line));
+                }
+                continue;
+            }
+            if indent_of(line) > open.indent {
+                open.push(&mut scanner, line_number, line);
+                continue;
+            }
+            if let Some(open) = block.take() {
+                open.close(&mut scanner);
+            }
+        }
+
+        if let Some(captures) = CODE_DIRECTIVE.captures(line) {
+            let language = captures[2].to_lowercase();
+            let skip = &captures[1] == "productionlist" || is_grammar_language(&language);
+            block = Some(Block::new(
+                indent_of(line),
+                skip,
+                BlockLanguage::from_info(&language),
+                true,
+            ));
+            continue;
+        }
+        scanner.scan_line(line_number, line);
+        if line.trim_end().ends_with("::") {
+            block = Some(Block::new(indent_of(line), false, None, false));
+        }
+    }
+
+    if let Some(open) = block {
+        open.close(&mut scanner);
+    }
+    scanner.into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(content: &str) -> MarkdownScanResult {
+        scan_rst_content(content, Path::new("doc.rst"), Some("x"), false, true, &[])
+    }
+
+    #[test]
+    fn test_rst_grammar_blocks_skipped() {
+        let content = "Grammar:\n\
+                       \n\
+                       .. code-block:: ebnf\n   \
+                          :linenos:\n\
+                       \n   \
+                          id = \"gts.y.core.pkg.mytype.v1~\" ;\n\
+                       \n\
+                       .. productionlist::\n   \
+                          id: \"gts.y.core.pkg.other.v1~\"\n\
+                       \n\
+                       Uses ``gts.y.core.pkg.prose.v1~``.\n";
+        let errors = scan(content).errors;
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert_eq!(errors[0].line, 11);
+        assert_eq!(errors[0].normalized_id, "gts.y.core.pkg.prose.v1~");
+    }
+
+    #[test]
+    fn test_rst_literal_and_json_blocks() {
+        let content = "Example::\n\
+                       \n    \
+                           gts.y.core.pkg.literal.v1~\n\
+                       \n\
+                       .. code-block:: json\n\
+                       \n    \
+                           {\"$id\": \"gts.y.core.pkg.block.v1~\"}\n";
+        let errors = scan(content).errors;
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert_eq!((errors[0].line, errors[0].json_path.as_str()), (3, ""));
+        assert_eq!((errors[1].line, errors[1].column), (7, 14));
+        assert_eq!(errors[1].json_path, "$.$id");
+    }
+}
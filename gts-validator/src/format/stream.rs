@@ -0,0 +1,422 @@
+//! Bounded-memory scanning of large JSON and YAML files.
+//!
+//! Files above [`FsSourceConfig::stream_threshold`](crate::FsSourceConfig::stream_threshold)
+//! are read through `serde_json`'s reader and the YAML event stream instead of
+//! being loaded and parsed into a `serde_json::Value`. Each document is
+//! deserialized into the list of its strings and keys that may hold GTS
+//! identifiers, with their JSON paths, so memory grows with the number of
+//! such strings rather than with the size of the file. The candidates are then
+//! validated exactly like the tree walker does.
+
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+
+use crate::error::{ScanError, ScanErrorKind, ValidationError};
+use crate::format::json::{COMMENT_KEYS, check_json_key, check_json_string};
+use crate::format::yaml::mark_document;
+use crate::ignore::IgnoreScopes;
+use crate::ref_form::RefFormRules;
+use crate::reserved::ReservationRules;
+
+/// A string or key of a document that may hold a GTS identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Leaf {
+    /// Path of the string, or of the object holding the key
+    json_path: String,
+    text: String,
+    kind: LeafKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LeafKind {
+    /// A string value; `comment` if it is the value of a [`COMMENT_KEYS`] key.
+    Value {
+        comment: bool,
+    },
+    Key,
+}
+
+/// The GTS candidate strings of one document, in document order.
+#[derive(Debug, Default)]
+struct DocumentLeaves(Vec<Leaf>);
+
+impl<'de> Deserialize<'de> for DocumentLeaves {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut leaves = Vec::new();
+        LeafCollector {
+            json_path: "$".to_owned(),
+            comment: false,
+            leaves: &mut leaves,
+        }
+        .deserialize(deserializer)?;
+        Ok(Self(leaves))
+    }
+}
+
+/// Collects the candidates of the value at `json_path` without building it.
+struct LeafCollector<'a> {
+    json_path: String,
+    comment: bool,
+    leaves: &'a mut Vec<Leaf>,
+}
+
+/// Strings the validators could report; everything else is dropped while
+/// parsing. Covers `gts.` IDs, `gts://` URIs and quoted forms.
+fn is_candidate(text: &str) -> bool {
+    text.contains("gts.")
+}
+
+impl<'de> DeserializeSeed<'de> for LeafCollector<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for LeafCollector<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON/YAML value")
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<(), E> {
+        if is_candidate(text) {
+            self.leaves.push(Leaf {
+                json_path: self.json_path,
+                text: text.to_owned(),
+                kind: LeafKind::Value {
+                    comment: self.comment,
+                },
+            });
+        }
+        Ok(())
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut index = 0usize;
+        while seq
+            .next_element_seed(LeafCollector {
+                json_path: format!("{}[{index}]", self.json_path),
+                comment: false,
+                leaves: self.leaves,
+            })?
+            .is_some()
+        {
+            index += 1;
+        }
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if is_candidate(&key) {
+                self.leaves.push(Leaf {
+                    json_path: self.json_path.clone(),
+                    text: key.clone(),
+                    kind: LeafKind::Key,
+                });
+            }
+            map.next_value_seed(LeafCollector {
+                json_path: format!("{}.{key}", self.json_path),
+                comment: COMMENT_KEYS.contains(&key.as_str()),
+                leaves: self.leaves,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Validation settings shared by the JSON/YAML scanners, streaming or not.
+pub struct StreamRules<'a> {
+    pub vendor: Option<&'a str>,
+    pub scan_keys: bool,
+    pub scan_comments: bool,
+    pub ref_forms: &'a RefFormRules,
+    pub reservations: &'a ReservationRules,
+}
+
+impl StreamRules<'_> {
+    /// Validate the candidates of one document: identifiers first, then the
+    /// reference forms and reservations, as the tree walker does. Findings
+    /// covered by an `x-gts-ignore` directive are moved to `suppressed`.
+    fn check(
+        &self,
+        leaves: &[Leaf],
+        path: &Path,
+        errors: &mut Vec<ValidationError>,
+        suppressed: &mut Vec<ValidationError>,
+    ) {
+        let start = errors.len();
+        for leaf in leaves {
+            match leaf.kind {
+                LeafKind::Key if self.scan_keys => {
+                    check_json_key(&leaf.text, &leaf.json_path, path, self.vendor, errors);
+                }
+                LeafKind::Value { comment } if self.scan_comments || !comment => {
+                    check_json_string(&leaf.text, &leaf.json_path, path, self.vendor, errors);
+                }
+                _ => {}
+            }
+        }
+        let values = leaves.iter().filter(|l| l.kind != LeafKind::Key);
+        for leaf in values.clone() {
+            self.ref_forms
+                .check_string(&leaf.text, path, &leaf.json_path, errors);
+        }
+        for leaf in values.clone() {
+            self.reservations
+                .check_field(&leaf.text, path, &leaf.json_path, errors);
+        }
+        let mut scopes = IgnoreScopes::default();
+        for leaf in values {
+            scopes.add(&leaf.json_path, &leaf.text);
+        }
+        scopes.split_off(errors, start, suppressed);
+    }
+}
+
+/// Scan a JSON document from `reader` without loading it into memory.
+///
+/// # Errors
+///
+/// Returns a `ScanError` if the content is not valid JSON. Identifiers found
+/// before the syntax error are not reported.
+pub fn scan_json_reader<R: Read>(
+    reader: R,
+    path: &Path,
+    rules: &StreamRules<'_>,
+    suppressed: &mut Vec<ValidationError>,
+) -> Result<Vec<ValidationError>, ScanError> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let leaves = DocumentLeaves::deserialize(&mut deserializer)
+        .and_then(|leaves| deserializer.end().map(|()| leaves))
+        .map_err(|e| ScanError {
+            file: path.to_owned(),
+            kind: ScanErrorKind::JsonParseError,
+            message: format!("JSON parse error: {e}"),
+        })?;
+    let mut errors = Vec::new();
+    rules.check(&leaves.0, path, &mut errors, suppressed);
+    Ok(errors)
+}
+
+/// Scan the YAML documents of `reader` one at a time.
+///
+/// Returns `(validation_errors, scan_errors)` like
+/// [`scan_yaml_content`](crate::format::yaml::scan_yaml_content). Documents
+/// before a malformed one are validated; scanning stops at the malformed
+/// document, as the stream cannot be resynchronized without buffering it.
+pub fn scan_yaml_reader<R: Read>(
+    mut reader: R,
+    path: &Path,
+    rules: &StreamRules<'_>,
+    suppressed: &mut Vec<ValidationError>,
+) -> (Vec<ValidationError>, Vec<ScanError>) {
+    // The default budget guards in-memory parsing; a streamed manifest is
+    // expected to exceed its event, node and scalar counts.
+    let mut options = serde_saphyr::Options::default();
+    if let Some(budget) = &mut options.budget {
+        budget.max_reader_input_bytes = None;
+        budget.max_events = usize::MAX;
+        budget.max_nodes = usize::MAX;
+        budget.max_total_scalar_bytes = usize::MAX;
+        budget.max_documents = usize::MAX;
+    }
+
+    let mut errors = Vec::new();
+    let mut scan_errors = Vec::new();
+    // Index of the first error of each document
+    let mut starts = Vec::new();
+    for (idx, document) in
+        serde_saphyr::read_with_options::<_, DocumentLeaves>(&mut reader, options).enumerate()
+    {
+        match document {
+            Ok(leaves) => {
+                starts.push(errors.len());
+                rules.check(&leaves.0, path, &mut errors, suppressed);
+            }
+            Err(e) => {
+                let message = if idx == 0 {
+                    format!("YAML parse error: {e}")
+                } else {
+                    format!(
+                        "YAML parse error in document {} of multi-document stream: {e}",
+                        idx + 1
+                    )
+                };
+                scan_errors.push(ScanError {
+                    file: path.to_owned(),
+                    kind: ScanErrorKind::YamlParseError,
+                    message,
+                });
+                break;
+            }
+        }
+    }
+    // The stream carries no positions, so documents are named by number only;
+    // a malformed document ended the loop
+    if starts.len() + scan_errors.len() > 1 {
+        let ends: Vec<usize> = starts
+            .iter()
+            .skip(1)
+            .copied()
+            .chain([errors.len()])
+            .collect();
+        for (idx, (start, end)) in starts.iter().zip(ends).enumerate() {
+            mark_document(&mut errors[*start..end], idx, None);
+        }
+    }
+    (errors, scan_errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::json::scan_json_content;
+    use crate::format::yaml::scan_yaml_content;
+    use gts_id::GtsReservations;
+
+    fn rules<'a>(
+        ref_forms: &'a RefFormRules,
+        reservations: &'a ReservationRules,
+        scan_keys: bool,
+        scan_comments: bool,
+    ) -> StreamRules<'a> {
+        StreamRules {
+            vendor: Some("x"),
+            scan_keys,
+            scan_comments,
+            ref_forms,
+            reservations,
+        }
+    }
+
+    fn json_paths(errors: &[ValidationError]) -> Vec<&str> {
+        let mut paths: Vec<&str> = errors.iter().map(|e| e.json_path.as_str()).collect();
+        paths.sort_unstable();
+        paths
+    }
+
+    #[test]
+    fn test_stream_matches_tree_walk() {
+        let content = r#"{
+            "$id": "gts://gts.x.core.events.type.v1~",
+            "$comment": "gts.invalid",
+            "gts.bad.key": 1,
+            "properties": {
+                "type": {"x-gts-ref": "gts.x.core.*"},
+                "topic": {"x-gts-ref": "gts.x.core.events.topic.v1~x.app._.orders.v1"},
+                "description": {"x-gts-ref": "/$id"},
+                "owner": {"const": "gts.hx.core.events.type.v1~"},
+                "legacy": {
+                    "x-gts-ignore": ["gts.hx.core.events.type.v1~"],
+                    "const": "gts.hx.core.events.type.v1~"
+                }
+            },
+            "examples": [["gts.x.core.events.type.v1~", "gts.also-bad"], null, 3]
+        }"#;
+        let ref_forms = RefFormRules::new(&["x-gts-ref".to_owned()], &[]);
+        let reservations = ReservationRules::new(
+            GtsReservations::new().reserve("x", Some("core"), &["platform"]),
+            None,
+        );
+        for (scan_keys, scan_comments) in [(false, true), (true, false)] {
+            let rules = rules(&ref_forms, &reservations, scan_keys, scan_comments);
+            let path = Path::new("big.json");
+            let mut streamed_suppressed = Vec::new();
+            let streamed =
+                scan_json_reader(content.as_bytes(), path, &rules, &mut streamed_suppressed)
+                    .unwrap();
+            let mut walked_suppressed = Vec::new();
+            let walked = scan_json_content(content, path, &rules, &mut walked_suppressed).unwrap();
+            assert!(!walked.is_empty());
+            assert_eq!(json_paths(&streamed), json_paths(&walked));
+            assert_eq!(
+                json_paths(&walked_suppressed),
+                ["$.properties.legacy.const"]
+            );
+            assert_eq!(
+                json_paths(&streamed_suppressed),
+                json_paths(&walked_suppressed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_stream_yaml_documents() {
+        let content =
+            "$id: gts.invalid\n---\nitems:\n  - gts.x.core.events.type.v1~\n  - gts.bad\n";
+        let ref_forms = RefFormRules::default();
+        let reservations = ReservationRules::default();
+        let rules = rules(&ref_forms, &reservations, false, true);
+        let path = Path::new("big.yaml");
+        let (streamed, scan_errors) =
+            scan_yaml_reader(content.as_bytes(), path, &rules, &mut Vec::new());
+        assert!(scan_errors.is_empty(), "{scan_errors:?}");
+        let (walked, _) = scan_yaml_content(content, path, &rules, &mut Vec::new());
+        assert_eq!(json_paths(&streamed), ["$.$id", "$.items[1]"]);
+        assert_eq!(json_paths(&streamed), json_paths(&walked));
+
+        let broken = "$id: gts.invalid\n---\nitems: [unclosed\n";
+        let (streamed, scan_errors) =
+            scan_yaml_reader(broken.as_bytes(), path, &rules, &mut Vec::new());
+        assert_eq!(streamed.len(), 1);
+        assert_eq!(scan_errors.len(), 1);
+        assert!(
+            scan_errors[0].message.contains("document 2"),
+            "{}",
+            scan_errors[0].message
+        );
+    }
+
+    #[test]
+    fn test_stream_json_parse_error() {
+        let ref_forms = RefFormRules::default();
+        let reservations = ReservationRules::default();
+        let rules = rules(&ref_forms, &reservations, false, true);
+        for content in [r#"{"a": "gts.x"#, r#"{"a": 1} {"b": 2}"#] {
+            let err = scan_json_reader(
+                content.as_bytes(),
+                Path::new("a.json"),
+                &rules,
+                &mut Vec::new(),
+            )
+            .unwrap_err();
+            assert_eq!(err.kind, ScanErrorKind::JsonParseError);
+        }
+    }
+}
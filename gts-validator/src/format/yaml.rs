@@ -1,6 +1,7 @@
 //! YAML file scanner for GTS identifiers.
 //!
-//! Uses tree-walking to scan string values (not keys by default).
+//! Uses tree-walking to scan string values (not keys by default). Findings in
+//! a stream of several documents name their document in `context`.
 
 use std::path::Path;
 
@@ -8,18 +9,46 @@ use serde_json::Value;
 
 use crate::error::{ScanError, ScanErrorKind, ValidationError};
 use crate::format::json::walk_json_value;
+use crate::format::position::SourceMap;
+use crate::format::stream::StreamRules;
+use crate::ignore::IgnoreScopes;
 
-fn split_yaml_documents(content: &str) -> Vec<String> {
+/// Start of the context of a finding in a stream of several YAML documents,
+/// followed by the 1-indexed document number: `document 2 (line 14): $.a.b`.
+pub const DOCUMENT_CONTEXT_PREFIX: &str = "document ";
+
+/// `document N` with the line of the document, if known.
+fn document_label(index: usize, first_line: Option<usize>) -> String {
+    match first_line {
+        Some(line) => format!("{DOCUMENT_CONTEXT_PREFIX}{} (line {line})", index + 1),
+        None => format!("{DOCUMENT_CONTEXT_PREFIX}{}", index + 1),
+    }
+}
+
+/// Prefix the context of `errors`, found in document `index` (0-based) of a
+/// multi-document stream, with the document.
+pub fn mark_document(errors: &mut [ValidationError], index: usize, first_line: Option<usize>) {
+    let label = document_label(index, first_line);
+    for error in errors {
+        error.context = format!("{label}: {}", error.context);
+    }
+}
+
+/// Split a YAML stream at `---` lines, returning each non-empty document with
+/// the number of lines before it.
+fn split_yaml_documents(content: &str) -> Vec<(usize, String)> {
     let mut documents = Vec::new();
     let mut current_doc: Vec<&str> = Vec::new();
+    let mut first_line = 0;
 
-    for line in content.lines() {
+    for (index, line) in content.lines().enumerate() {
         if line.trim() == "---" {
             let doc = current_doc.join("\n");
             if !doc.trim().is_empty() {
-                documents.push(doc);
+                documents.push((first_line, doc));
             }
             current_doc.clear();
+            first_line = index + 1;
             continue;
         }
         current_doc.push(line);
@@ -27,7 +56,7 @@ fn split_yaml_documents(content: &str) -> Vec<String> {
 
     let doc = current_doc.join("\n");
     if !doc.trim().is_empty() {
-        documents.push(doc);
+        documents.push((first_line, doc));
     }
 
     documents
@@ -42,11 +71,14 @@ fn split_yaml_documents(content: &str) -> Vec<String> {
 ///
 /// This separation ensures malformed YAML documents are counted in `failed_files`
 /// and never silently mixed into the validation error layer.
+///
+/// Findings covered by an `x-gts-ignore` directive of their document are
+/// moved to `suppressed`.
 pub fn scan_yaml_content(
     content: &str,
     path: &Path,
-    vendor: Option<&str>,
-    scan_keys: bool,
+    rules: &StreamRules<'_>,
+    suppressed: &mut Vec<ValidationError>,
 ) -> (Vec<ValidationError>, Vec<ScanError>) {
     let mut validation_errors = Vec::new();
     let mut scan_errors = Vec::new();
@@ -58,13 +90,43 @@ pub fn scan_yaml_content(
         Ok(docs) => docs,
         Err(stream_err) => {
             let segments = split_yaml_documents(content);
+            let multi_document = segments.len() > 1;
             let mut any_parsed = false;
 
-            for (idx, segment) in segments.iter().enumerate() {
+            for (idx, (first_line, segment)) in segments.iter().enumerate() {
                 match serde_saphyr::from_str::<Value>(segment) {
                     Ok(doc) => {
                         any_parsed = true;
-                        walk_json_value(&doc, path, vendor, &mut validation_errors, "$", scan_keys);
+                        let start = validation_errors.len();
+                        walk_json_value(
+                            &doc,
+                            path,
+                            rules.vendor,
+                            &mut validation_errors,
+                            "$",
+                            rules.scan_keys,
+                            rules.scan_comments,
+                        );
+                        rules
+                            .ref_forms
+                            .check(&doc, path, "$", &mut validation_errors);
+                        rules.reservations.check(&doc, path, &mut validation_errors);
+                        IgnoreScopes::of_value(&doc, "$").split_off(
+                            &mut validation_errors,
+                            start,
+                            suppressed,
+                        );
+                        let map = SourceMap::yaml_documents(segment)
+                            .into_iter()
+                            .next()
+                            .map(|map| map.offset_lines(*first_line));
+                        let errors = &mut validation_errors[start..];
+                        if let Some(map) = &map {
+                            map.locate(errors);
+                        }
+                        if multi_document {
+                            mark_document(errors, idx, map.and_then(|m| m.first_line()));
+                        }
                     }
                     Err(doc_err) => {
                         // Per-document parse failure → ScanError (not ValidationError)
@@ -72,8 +134,8 @@ pub fn scan_yaml_content(
                             file: path.to_owned(),
                             kind: ScanErrorKind::YamlParseError,
                             message: format!(
-                                "YAML parse error in document {} of multi-document stream: {doc_err}",
-                                idx + 1
+                                "YAML parse error in {} of multi-document stream: {doc_err}",
+                                document_label(idx, Some(first_line + 1))
                             ),
                         });
                     }
@@ -94,8 +156,50 @@ pub fn scan_yaml_content(
         }
     };
 
-    for value in documents {
-        walk_json_value(&value, path, vendor, &mut validation_errors, "$", scan_keys);
+    // Index of the first error of each document
+    let mut starts = Vec::with_capacity(documents.len());
+    for value in &documents {
+        let start = validation_errors.len();
+        starts.push(start);
+        walk_json_value(
+            value,
+            path,
+            rules.vendor,
+            &mut validation_errors,
+            "$",
+            rules.scan_keys,
+            rules.scan_comments,
+        );
+        rules
+            .ref_forms
+            .check(value, path, "$", &mut validation_errors);
+        rules
+            .reservations
+            .check(value, path, &mut validation_errors);
+        IgnoreScopes::of_value(value, "$").split_off(&mut validation_errors, start, suppressed);
+    }
+
+    if !validation_errors.is_empty() {
+        // Positions are only trusted when the event parser sees the same documents
+        let mut maps = SourceMap::yaml_documents(content);
+        if maps.len() != documents.len() {
+            maps.clear();
+        }
+        let ends = starts
+            .iter()
+            .skip(1)
+            .copied()
+            .chain([validation_errors.len()]);
+        for (idx, (start, end)) in starts.iter().zip(ends).enumerate() {
+            let errors = &mut validation_errors[*start..end];
+            let map = maps.get(idx);
+            if let Some(map) = map {
+                map.locate(errors);
+            }
+            if documents.len() > 1 {
+                mark_document(errors, idx, map.and_then(SourceMap::first_line));
+            }
+        }
     }
 
     (validation_errors, scan_errors)
@@ -112,14 +216,23 @@ pub fn scan_yaml_file(
     max_file_size: u64,
     scan_keys: bool,
 ) -> Result<Vec<ValidationError>, ScanError> {
+    use crate::ref_form::RefFormRules;
+    use crate::reserved::ReservationRules;
     use crate::strategy::fs::{ScanResult, read_file_bounded};
 
-    let content = match read_file_bounded(path, max_file_size) {
+    let content = match read_file_bounded(&crate::host::OsFs, path, max_file_size) {
         ScanResult::Ok(c) => c,
         ScanResult::Err(e) => return Err(e),
     };
 
-    let (val_errs, scan_errs) = scan_yaml_content(&content, path, vendor, scan_keys);
+    let rules = StreamRules {
+        vendor,
+        scan_keys,
+        scan_comments: true,
+        ref_forms: &RefFormRules::default(),
+        reservations: &ReservationRules::default(),
+    };
+    let (val_errs, scan_errs) = scan_yaml_content(&content, path, &rules, &mut Vec::new());
     if let Some(first_scan_err) = scan_errs.into_iter().next() {
         return Err(first_scan_err);
     }
@@ -129,6 +242,8 @@ pub fn scan_yaml_file(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ref_form::RefFormRules;
+    use crate::reserved::ReservationRules;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -138,6 +253,23 @@ mod tests {
         file
     }
 
+    /// Scan `content` without key scanning, reference forms or reservations.
+    fn scan(
+        content: &str,
+        path: &Path,
+        vendor: Option<&str>,
+        scan_comments: bool,
+    ) -> (Vec<ValidationError>, Vec<ScanError>) {
+        let rules = StreamRules {
+            vendor,
+            scan_keys: false,
+            scan_comments,
+            ref_forms: &RefFormRules::default(),
+            reservations: &ReservationRules::default(),
+        };
+        scan_yaml_content(content, path, &rules, &mut Vec::new())
+    }
+
     #[test]
     fn test_scan_yaml_valid_id() {
         let content = r"
@@ -236,8 +368,7 @@ $id: gts.x.core.events.type.v1~
 ---
 $id: gts.invalid
 ";
-        let (val_errs, scan_errs) =
-            scan_yaml_content(content, Path::new("multi.yaml"), None, false);
+        let (val_errs, scan_errs) = scan(content, Path::new("multi.yaml"), None, true);
         assert!(
             scan_errs.is_empty(),
             "No scan errors expected for well-formed stream: {scan_errs:?}"
@@ -249,6 +380,66 @@ $id: gts.invalid
         );
     }
 
+    #[test]
+    fn test_scan_yaml_error_positions() {
+        let content =
+            "a: 1\n---\nitems:\n  - id: \"gts.invalid\"\n---\n$id: gts.y.core.pkg.mytype.v1~\n";
+        let (errors, _) = scan(content, Path::new("multi.yaml"), Some("x"), true);
+        let positions: Vec<_> = errors
+            .iter()
+            .map(|e| (e.json_path.as_str(), e.line, e.column))
+            .collect();
+        assert_eq!(positions, [("$.items[0].id", 4, 10), ("$.$id", 6, 6)]);
+
+        // Documents parsed one by one after a malformed sibling keep their lines
+        let content = "$id: gts.y.core.pkg.a.v1~\n---\ninvalid: yaml: syntax:\n---\n\n$id: gts.y.core.pkg.b.v1~\n";
+        let (errors, _) = scan(content, Path::new("multi.yaml"), Some("x"), true);
+        let lines: Vec<_> = errors.iter().map(|e| (e.line, e.column)).collect();
+        assert_eq!(lines, [(1, 6), (6, 6)]);
+    }
+
+    #[test]
+    fn test_scan_yaml_multi_document_context_names_document() {
+        let content =
+            "a: 1\n---\nitems:\n  - id: \"gts.invalid\"\n---\n$id: gts.y.core.pkg.mytype.v1~\n";
+        let (errors, _) = scan(content, Path::new("multi.yaml"), Some("x"), true);
+        let contexts: Vec<_> = errors.iter().map(|e| e.context.as_str()).collect();
+        assert_eq!(contexts.len(), 2);
+        assert!(
+            contexts[0].starts_with("document 2 (line 3): "),
+            "{contexts:?}"
+        );
+        assert!(
+            contexts[1].starts_with("document 3 (line 6): "),
+            "{contexts:?}"
+        );
+
+        // A single document keeps its context unchanged
+        let (errors, _) = scan("$id: gts.invalid\n", Path::new("single.yaml"), None, true);
+        assert!(!errors[0].context.starts_with(DOCUMENT_CONTEXT_PREFIX));
+    }
+
+    #[test]
+    fn test_scan_yaml_multi_document_parse_error_names_document() {
+        let content =
+            "$id: gts.y.core.pkg.a.v1~\n---\ninvalid: yaml: syntax:\n---\n$id: gts.invalid\n";
+        let (errors, scan_errors) = scan(content, Path::new("multi.yaml"), None, true);
+        assert_eq!(scan_errors.len(), 1);
+        assert!(
+            scan_errors[0].message.contains("document 2 (line 3)"),
+            "{}",
+            scan_errors[0].message
+        );
+        // Scanning continues past the broken document
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 5);
+        assert!(
+            errors[0].context.starts_with("document 3 (line 5): "),
+            "{}",
+            errors[0].context
+        );
+    }
+
     #[test]
     fn test_scan_yaml_multi_document_malformed_doc_does_not_suppress_valid_doc() {
         // A malformed document must be skipped, but valid documents around it must still be validated.
@@ -261,8 +452,7 @@ $id: gts.y.core.pkg.mytype.v1~
 ";
         // With vendor "x", both valid docs should produce vendor-mismatch errors.
         // The malformed middle doc must produce a ScanError, not suppress valid docs.
-        let (val_errs, scan_errs) =
-            scan_yaml_content(content, Path::new("multi.yaml"), Some("x"), false);
+        let (val_errs, scan_errs) = scan(content, Path::new("multi.yaml"), Some("x"), true);
         assert!(
             !val_errs.is_empty(),
             "Valid documents must be validated even when a sibling document is malformed, got no errors"
@@ -277,4 +467,25 @@ $id: gts.y.core.pkg.mytype.v1~
             "Malformed doc scan error must have YamlParseError kind"
         );
     }
+
+    #[test]
+    fn test_scan_yaml_comments_skipped_when_disabled() {
+        let content = "\
+description: gts.invalid
+$id: gts.x.core.events.type.v1~
+";
+        let (val_errs, scan_errs) = scan(content, Path::new("c.yaml"), None, false);
+        assert!(scan_errs.is_empty(), "{scan_errs:?}");
+        assert!(
+            val_errs.is_empty(),
+            "description must be skipped: {val_errs:?}"
+        );
+
+        let (val_errs, _) = scan(content, Path::new("c.yaml"), None, true);
+        assert_eq!(
+            val_errs.len(),
+            1,
+            "description must be scanned: {val_errs:?}"
+        );
+    }
 }
@@ -0,0 +1,528 @@
+//! Filesystem and clock access of the validator.
+//!
+//! [`validate_fs`](crate::validate_fs) reads files and measures time through a
+//! [`Host`]: the real filesystem and clock by default, or any [`FileSystem`]
+//! and [`Clock`] via [`validate_fs_with`](crate::validate_fs_with). A
+//! [deterministic](Host::deterministic) host scans every file on the calling
+//! thread and checks the per-file time budget against its clock once the scan
+//! returns, so it needs no threads (WASM hosts) and the same inputs always give
+//! the same report (tests).
+//!
+//! [`MemoryFs`] and [`ManualClock`] are test doubles for downstream crates
+//! that want to unit-test their validation wiring without touching the disk.
+
+use std::collections::BTreeMap;
+use std::io::{self, Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::isolation::{self, IsolationFailure};
+
+/// What a path refers to, after following symbolic links.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// A regular file.
+    File,
+    Dir,
+    /// Devices, pipes, sockets and dangling links; never scanned.
+    Other,
+}
+
+/// Metadata of a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileInfo {
+    pub kind: FileKind,
+    /// Size in bytes.
+    pub len: u64,
+    /// Last modification, if the filesystem records it.
+    pub modified: Option<SystemTime>,
+}
+
+/// A path found by [`FileSystem::walk`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkEntry {
+    /// The walked root joined with the path below it.
+    pub path: PathBuf,
+    pub kind: FileKind,
+}
+
+/// A directory that could not be traversed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkError {
+    pub path: Option<PathBuf>,
+    pub message: String,
+}
+
+/// Filesystem access used by the validator.
+pub trait FileSystem: Send + Sync {
+    /// Metadata of `path`, following symbolic links.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` does not exist or cannot be inspected.
+    fn info(&self, path: &Path) -> io::Result<FileInfo>;
+
+    /// Absolute form of `path` with all links resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` does not exist.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Open `path` for reading.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` cannot be opened.
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read + Send>>;
+
+    /// Write `bytes` to `path`, creating missing parent directories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` cannot be written.
+    fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()>;
+
+    /// Everything below the directory `root` up to `max_depth` levels deep,
+    /// not descending into directories named in `skip_dirs`.
+    fn walk(
+        &self,
+        root: &Path,
+        follow_links: bool,
+        max_depth: usize,
+        skip_dirs: &[&str],
+    ) -> Vec<Result<WalkEntry, WalkError>>;
+}
+
+/// The real filesystem.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsFs;
+
+impl FileSystem for OsFs {
+    fn info(&self, path: &Path) -> io::Result<FileInfo> {
+        let metadata = std::fs::metadata(path)?;
+        Ok(FileInfo {
+            kind: os_kind(&metadata),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        path.canonicalize()
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(std::fs::File::open(path)?))
+    }
+
+    fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, bytes)
+    }
+
+    fn walk(
+        &self,
+        root: &Path,
+        follow_links: bool,
+        max_depth: usize,
+        skip_dirs: &[&str],
+    ) -> Vec<Result<WalkEntry, WalkError>> {
+        walkdir::WalkDir::new(root)
+            .follow_links(follow_links)
+            .max_depth(max_depth)
+            .into_iter()
+            .filter_entry(|entry| {
+                let name = entry.file_name().to_str();
+                !(entry.file_type().is_dir() && name.is_some_and(|n| skip_dirs.contains(&n)))
+            })
+            .map(|entry| match entry {
+                Ok(entry) => {
+                    let kind =
+                        std::fs::metadata(entry.path()).map_or(FileKind::Other, |m| os_kind(&m));
+                    Ok(WalkEntry {
+                        path: entry.into_path(),
+                        kind,
+                    })
+                }
+                Err(e) => Err(WalkError {
+                    path: e.path().map(Path::to_path_buf),
+                    message: e.to_string(),
+                }),
+            })
+            .collect()
+    }
+}
+
+fn os_kind(metadata: &std::fs::Metadata) -> FileKind {
+    if metadata.is_file() {
+        FileKind::File
+    } else if metadata.is_dir() {
+        FileKind::Dir
+    } else {
+        FileKind::Other
+    }
+}
+
+/// In-memory filesystem holding regular files; directories exist implicitly.
+///
+/// Paths are normalized lexically (`.` and `..` resolved, no links). Every
+/// write stamps the file with a new modification time, one second after the
+/// previous write, starting at the Unix epoch.
+#[derive(Debug, Default)]
+pub struct MemoryFs {
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    files: BTreeMap<PathBuf, MemoryFile>,
+    writes: u64,
+}
+
+#[derive(Debug)]
+struct MemoryFile {
+    content: Vec<u8>,
+    modified: SystemTime,
+}
+
+impl MemoryFs {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create or replace the file at `path`.
+    pub fn insert(&self, path: impl AsRef<Path>, content: impl Into<Vec<u8>>) {
+        let mut state = self.lock();
+        state.writes += 1;
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(state.writes);
+        state.files.insert(
+            normalize(path.as_ref()),
+            MemoryFile {
+                content: content.into(),
+                modified,
+            },
+        );
+    }
+
+    /// Remove the file at `path`; `false` if there was none.
+    pub fn remove(&self, path: impl AsRef<Path>) -> bool {
+        self.lock()
+            .files
+            .remove(&normalize(path.as_ref()))
+            .is_some()
+    }
+
+    /// Content of the file at `path`.
+    #[must_use]
+    pub fn contents(&self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+        self.lock()
+            .files
+            .get(&normalize(path.as_ref()))
+            .map(|file| file.content.clone())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl FileSystem for MemoryFs {
+    fn info(&self, path: &Path) -> io::Result<FileInfo> {
+        let path = normalize(path);
+        let state = self.lock();
+        if let Some(file) = state.files.get(&path) {
+            return Ok(FileInfo {
+                kind: FileKind::File,
+                len: file.content.len() as u64,
+                modified: Some(file.modified),
+            });
+        }
+        if state.files.keys().any(|file| file.starts_with(&path)) {
+            return Ok(FileInfo {
+                kind: FileKind::Dir,
+                len: 0,
+                modified: None,
+            });
+        }
+        Err(not_found(&path))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.info(path)?;
+        Ok(normalize(path))
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read + Send>> {
+        self.contents(path)
+            .map(|content| Box::new(Cursor::new(content)) as Box<dyn Read + Send>)
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        self.insert(path, bytes);
+        Ok(())
+    }
+
+    fn walk(
+        &self,
+        root: &Path,
+        _follow_links: bool,
+        max_depth: usize,
+        skip_dirs: &[&str],
+    ) -> Vec<Result<WalkEntry, WalkError>> {
+        let normalized = normalize(root);
+        self.lock()
+            .files
+            .keys()
+            .filter_map(|file| file.strip_prefix(&normalized).ok())
+            .filter(|rel| {
+                let depth = rel.components().count();
+                depth > 0
+                    && depth <= max_depth
+                    && !rel.parent().is_some_and(|dirs| {
+                        dirs.iter()
+                            .any(|dir| dir.to_str().is_some_and(|d| skip_dirs.contains(&d)))
+                    })
+            })
+            .map(|rel| {
+                Ok(WalkEntry {
+                    path: root.join(rel),
+                    kind: FileKind::File,
+                })
+            })
+            .collect()
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} does not exist", path.display()),
+    )
+}
+
+/// Monotonic time source for per-file time budgets.
+pub trait Clock: Send + Sync {
+    /// Time elapsed since an arbitrary, fixed origin.
+    fn now(&self) -> Duration;
+}
+
+/// The system's monotonic clock.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// Clock that only moves when told to: by [`advance`](Self::advance), and by
+/// a fixed tick after every reading.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Mutex<Duration>,
+    tick: Duration,
+}
+
+impl ManualClock {
+    /// A clock standing still at zero.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A clock that moves on by `tick` each time it is read, so every scan
+    /// takes `tick`.
+    #[must_use]
+    pub fn with_tick(tick: Duration) -> Self {
+        Self {
+            now: Mutex::default(),
+            tick,
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner) += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        let mut now = self.now.lock().unwrap_or_else(PoisonError::into_inner);
+        let reading = *now;
+        *now += self.tick;
+        reading
+    }
+}
+
+/// Filesystem and clock a validation run uses.
+#[derive(Clone)]
+pub struct Host {
+    fs: Arc<dyn FileSystem>,
+    clock: Arc<dyn Clock>,
+    /// Run time-budgeted scans on worker threads that are abandoned on overrun.
+    worker_threads: bool,
+}
+
+impl Host {
+    /// The real filesystem and clock; scans with a time budget run on worker
+    /// threads and are abandoned once the budget is spent.
+    #[must_use]
+    pub fn os() -> Self {
+        Self {
+            fs: Arc::new(OsFs),
+            clock: Arc::new(SystemClock::new()),
+            worker_threads: true,
+        }
+    }
+
+    /// Scans run to completion on the calling thread; one that took longer
+    /// than its time budget by `clock` is reported as timed out.
+    #[must_use]
+    pub fn deterministic(fs: Arc<dyn FileSystem>, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            fs,
+            clock,
+            worker_threads: false,
+        }
+    }
+
+    pub(crate) fn fs(&self) -> &Arc<dyn FileSystem> {
+        &self.fs
+    }
+
+    /// Run `scan` isolated from panics and within `timeout`.
+    pub(crate) fn run_isolated<T, F>(
+        &self,
+        timeout: Option<Duration>,
+        scan: F,
+    ) -> Result<T, IsolationFailure>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        if self.worker_threads {
+            return isolation::run_isolated(timeout, scan);
+        }
+        let started = self.clock.now();
+        let result = isolation::run_isolated(None, scan)?;
+        match timeout {
+            Some(budget) if self.clock.now().saturating_sub(started) > budget => {
+                Err(IsolationFailure::TimedOut(budget))
+            }
+            _ => Ok(result),
+        }
+    }
+}
+
+impl Default for Host {
+    fn default() -> Self {
+        Self::os()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_fs_paths() {
+        let fs = MemoryFs::new();
+        fs.insert("repo/docs/a.md", "a");
+        fs.insert("repo/target/b.md", "b");
+        fs.insert("repo/docs/deep/c.json", "{}");
+
+        assert_eq!(fs.info(Path::new("repo/docs")).unwrap().kind, FileKind::Dir);
+        let info = fs.info(Path::new("repo/./docs/a.md")).unwrap();
+        assert_eq!(info.kind, FileKind::File);
+        assert_eq!(info.len, 1);
+        assert!(fs.info(Path::new("repo/missing")).is_err());
+        assert_eq!(
+            fs.canonicalize(Path::new("repo/docs/../docs/a.md"))
+                .unwrap(),
+            PathBuf::from("repo/docs/a.md")
+        );
+
+        let walked: Vec<PathBuf> = fs
+            .walk(Path::new("repo"), false, 2, &["target"])
+            .into_iter()
+            .map(|entry| entry.unwrap().path)
+            .collect();
+        assert_eq!(walked, vec![PathBuf::from("repo/docs/a.md")]);
+
+        let mut content = String::new();
+        fs.open(Path::new("repo/docs/deep/c.json"))
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "{}");
+    }
+
+    #[test]
+    fn test_memory_fs_stamps_writes() {
+        let fs = MemoryFs::new();
+        fs.insert("a.md", "a");
+        let first = fs.info(Path::new("a.md")).unwrap().modified;
+        fs.insert("a.md", "a");
+        assert_ne!(fs.info(Path::new("a.md")).unwrap().modified, first);
+        assert!(fs.remove("a.md"));
+        assert!(!fs.remove("a.md"));
+    }
+
+    #[test]
+    fn test_deterministic_time_budget() {
+        let budget = Some(Duration::from_secs(1));
+        let slow = Host::deterministic(
+            Arc::new(MemoryFs::new()),
+            Arc::new(ManualClock::with_tick(Duration::from_secs(2))),
+        );
+        assert_eq!(
+            slow.run_isolated(budget, || 42),
+            Err(IsolationFailure::TimedOut(Duration::from_secs(1)))
+        );
+        assert_eq!(slow.run_isolated(None, || 42), Ok(42));
+
+        let clock = Arc::new(ManualClock::new());
+        let fast = Host::deterministic(Arc::new(MemoryFs::new()), clock.clone());
+        assert_eq!(fast.run_isolated(budget, || 42), Ok(42));
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(clock.now(), Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,310 @@
+//! Inline ignore directives.
+//!
+//! Authors suppress a false positive where it occurs:
+//!
+//! - Markdown: `<!-- gts-ignore-next-line -->` suppresses every finding on
+//!   the following line, `<!-- gts-ignore: <id> -->` the findings for `<id>`
+//!   anywhere in the file.
+//! - JSON/YAML: an `x-gts-ignore` key holding an ID, or a list of IDs,
+//!   suppresses the findings for those IDs in its object and below.
+//!
+//! Scanners move the suppressed findings aside rather than dropping them, so
+//! the run can count those that would have been reported
+//! ([`ValidationReport::suppressed_findings`](crate::ValidationReport::suppressed_findings)).
+//! Findings in the directives themselves are dropped without being counted.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use memchr::memmem;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::error::ValidationError;
+
+/// Key of the JSON/YAML ignore directive.
+pub const IGNORE_KEY: &str = "x-gts-ignore";
+
+static NEXT_LINE_DIRECTIVE: LazyLock<Regex> =
+    LazyLock::new(|| match Regex::new(r"<!--\s*gts-ignore-next-line\s*-->") {
+        Ok(regex) => regex,
+        Err(err) => panic!("Invalid ignore directive regex: {err}"),
+    });
+
+static ID_DIRECTIVE: LazyLock<Regex> =
+    LazyLock::new(|| match Regex::new(r"<!--\s*gts-ignore:\s*(\S+)\s*-->") {
+        Ok(regex) => regex,
+        Err(err) => panic!("Invalid ignore directive regex: {err}"),
+    });
+
+/// Whether `error` is a finding for `id` (with or without `gts://`).
+fn is_finding_for(error: &ValidationError, id: &str) -> bool {
+    let id = id.strip_prefix("gts://").unwrap_or(id);
+    error.normalized_id == id
+        || error
+            .raw_value
+            .strip_prefix("gts://")
+            .unwrap_or(&error.raw_value)
+            == id
+}
+
+/// Move the findings of `errors[from..]` that `suppressed_by` covers to
+/// `suppressed`, dropping those that `in_directive` says sit in a directive.
+fn split_off(
+    errors: &mut Vec<ValidationError>,
+    from: usize,
+    suppressed: &mut Vec<ValidationError>,
+    in_directive: impl Fn(&ValidationError) -> bool,
+    suppressed_by: impl Fn(&ValidationError) -> bool,
+) {
+    let tail = errors.split_off(from);
+    for error in tail {
+        if in_directive(&error) {
+            continue;
+        }
+        if suppressed_by(&error) {
+            suppressed.push(error);
+        } else {
+            errors.push(error);
+        }
+    }
+}
+
+/// The ignore directives of a Markdown file.
+#[derive(Debug, Default)]
+pub struct MarkdownIgnores {
+    /// Lines (1-indexed) whose findings are suppressed
+    lines: HashSet<usize>,
+    /// IDs whose findings are suppressed, with the lines naming them
+    ids: Vec<(String, usize)>,
+}
+
+impl MarkdownIgnores {
+    /// Find the directives of `content`.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let mut ignores = Self::default();
+        if memmem::find(content.as_bytes(), b"gts-ignore").is_none() {
+            return ignores;
+        }
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            if NEXT_LINE_DIRECTIVE.is_match(line) {
+                ignores.lines.insert(line_number + 1);
+            }
+            for captures in ID_DIRECTIVE.captures_iter(line) {
+                ignores.ids.push((captures[1].to_owned(), line_number));
+            }
+        }
+        ignores
+    }
+
+    /// Move the findings the directives cover from `errors` to `suppressed`.
+    pub fn split_off(
+        &self,
+        errors: &mut Vec<ValidationError>,
+        suppressed: &mut Vec<ValidationError>,
+    ) {
+        if self.lines.is_empty() && self.ids.is_empty() {
+            return;
+        }
+        split_off(
+            errors,
+            0,
+            suppressed,
+            |error| {
+                self.ids
+                    .iter()
+                    .any(|(id, line)| *line == error.line && is_finding_for(error, id))
+            },
+            |error| {
+                self.lines.contains(&error.line)
+                    || self.ids.iter().any(|(id, _)| is_finding_for(error, id))
+            },
+        );
+    }
+}
+
+/// The `x-gts-ignore` directives of one JSON/YAML document.
+#[derive(Debug, Default)]
+pub struct IgnoreScopes {
+    /// JSON path of the object holding the directive, and an ID it names
+    scopes: Vec<(String, String)>,
+}
+
+impl IgnoreScopes {
+    /// Collect the directives of `value`, the document at `json_path`.
+    #[must_use]
+    pub fn of_value(value: &Value, json_path: &str) -> Self {
+        let mut scopes = Self::default();
+        scopes.walk(value, json_path);
+        scopes
+    }
+
+    fn walk(&mut self, value: &Value, json_path: &str) {
+        match value {
+            Value::Object(map) => {
+                for (key, val) in map {
+                    let path = format!("{json_path}.{key}");
+                    match val {
+                        Value::String(id) if key == IGNORE_KEY => self.add(&path, id),
+                        Value::Array(ids) if key == IGNORE_KEY => {
+                            for (i, id) in ids.iter().enumerate() {
+                                if let Value::String(id) = id {
+                                    self.add(&format!("{path}[{i}]"), id);
+                                }
+                            }
+                        }
+                        _ => self.walk(val, &path),
+                    }
+                }
+            }
+            Value::Array(arr) => {
+                for (i, val) in arr.iter().enumerate() {
+                    self.walk(val, &format!("{json_path}[{i}]"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Record the string `text` at `json_path` if it is (an item of) an
+    /// `x-gts-ignore` value.
+    pub fn add(&mut self, json_path: &str, text: &str) {
+        let directive = match json_path.strip_suffix(']') {
+            Some(item) => item
+                .rsplit_once('[')
+                .map_or(item, |(directive, _)| directive),
+            None => json_path,
+        };
+        if let Some(scope) = directive
+            .strip_suffix(IGNORE_KEY)
+            .and_then(|s| s.strip_suffix('.'))
+        {
+            self.scopes.push((scope.to_owned(), text.to_owned()));
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.scopes.is_empty()
+    }
+
+    /// Move the findings the directives cover from `errors[from..]`, the
+    /// findings of this document, to `suppressed`.
+    pub fn split_off(
+        &self,
+        errors: &mut Vec<ValidationError>,
+        from: usize,
+        suppressed: &mut Vec<ValidationError>,
+    ) {
+        if self.is_empty() {
+            return;
+        }
+        split_off(
+            errors,
+            from,
+            suppressed,
+            |error| {
+                self.scopes.iter().any(|(scope, _)| {
+                    let directive = format!("{scope}.{IGNORE_KEY}");
+                    error
+                        .json_path
+                        .strip_prefix(&directive)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('['))
+                })
+            },
+            |error| {
+                self.scopes.iter().any(|(scope, id)| {
+                    is_within(&error.json_path, scope) && is_finding_for(error, id)
+                })
+            },
+        );
+    }
+}
+
+/// Whether `json_path` is `scope` or a path below it.
+fn is_within(json_path: &str, scope: &str) -> bool {
+    json_path
+        .strip_prefix(scope)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.') || rest.starts_with('['))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn finding(line: usize, json_path: &str, id: &str) -> ValidationError {
+        ValidationError {
+            file: "doc".into(),
+            line,
+            column: 1,
+            json_path: json_path.to_owned(),
+            raw_value: id.to_owned(),
+            normalized_id: id.to_owned(),
+            error: "Vendor mismatch".to_owned(),
+            context: String::new(),
+            fingerprint: String::new(),
+            baselined: false,
+            severity: crate::Severity::Error,
+            context_window: None,
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn test_markdown_directives() {
+        let ignores = MarkdownIgnores::parse(
+            "<!-- gts-ignore-next-line -->\n\
+             `gts.y.core.pkg.a.v1~`\n\
+             `gts.y.core.pkg.b.v1~`\n\
+             <!-- gts-ignore: gts://gts.y.core.pkg.c.v1~ -->\n\
+             `gts.y.core.pkg.c.v1~`\n",
+        );
+        let mut errors = vec![
+            finding(2, "", "gts.y.core.pkg.a.v1~"),
+            finding(3, "", "gts.y.core.pkg.b.v1~"),
+            finding(4, "", "gts.y.core.pkg.c.v1~"),
+            finding(5, "", "gts.y.core.pkg.c.v1~"),
+        ];
+        let mut suppressed = Vec::new();
+        ignores.split_off(&mut errors, &mut suppressed);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 3);
+        // The ID inside the directive itself is neither reported nor counted
+        let lines: Vec<usize> = suppressed.iter().map(|e| e.line).collect();
+        assert_eq!(lines, [2, 5]);
+    }
+
+    #[test]
+    fn test_json_scopes() {
+        let doc = json!({
+            "a": {
+                "x-gts-ignore": ["gts.y.core.pkg.a.v1~"],
+                "ref": "gts.y.core.pkg.a.v1~",
+                "nested": {"ref": "gts.y.core.pkg.a.v1~"},
+                "other": "gts.y.core.pkg.b.v1~"
+            },
+            "ab": {"ref": "gts.y.core.pkg.a.v1~"}
+        });
+        let scopes = IgnoreScopes::of_value(&doc, "$");
+        let mut errors = vec![
+            finding(0, "$.a.x-gts-ignore[0]", "gts.y.core.pkg.a.v1~"),
+            finding(0, "$.a.ref", "gts.y.core.pkg.a.v1~"),
+            finding(0, "$.a.nested.ref", "gts.y.core.pkg.a.v1~"),
+            finding(0, "$.a.other", "gts.y.core.pkg.b.v1~"),
+            finding(0, "$.ab.ref", "gts.y.core.pkg.a.v1~"),
+        ];
+        let mut suppressed = Vec::new();
+        scopes.split_off(&mut errors, 0, &mut suppressed);
+
+        let paths = |errors: &[ValidationError]| -> Vec<String> {
+            errors.iter().map(|e| e.json_path.clone()).collect()
+        };
+        assert_eq!(paths(&errors), ["$.a.other", "$.ab.ref"]);
+        assert_eq!(paths(&suppressed), ["$.a.ref", "$.a.nested.ref"]);
+    }
+}
@@ -0,0 +1,105 @@
+//! Per-file isolation for the scan loop.
+//!
+//! A pathological file must not stall or abort the whole run: each scan runs
+//! under `catch_unwind`, and with a time budget it runs on a worker thread the
+//! loop stops waiting for once the budget is spent.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Why an isolated scan did not produce a result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IsolationFailure {
+    /// The scan did not finish within the time budget.
+    TimedOut(Duration),
+    /// The scan panicked; carries the panic message.
+    Panicked(String),
+}
+
+/// Run `scan`, converting panics and (when `timeout` is set) overruns into
+/// an [`IsolationFailure`].
+///
+/// A timed-out worker thread cannot be cancelled; it is detached and its
+/// result discarded when it eventually finishes.
+pub fn run_isolated<T, F>(timeout: Option<Duration>, scan: F) -> Result<T, IsolationFailure>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let Some(timeout) = timeout else {
+        return panic::catch_unwind(AssertUnwindSafe(scan))
+            .map_err(|payload| IsolationFailure::Panicked(panic_message(payload.as_ref())));
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name("gts-validator-scan".to_owned())
+        .spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(scan))
+                .map_err(|payload| panic_message(payload.as_ref()));
+            // The receiver is gone if the budget was already spent
+            let _ = tx.send(result);
+        });
+    if let Err(e) = spawned {
+        return Err(IsolationFailure::Panicked(format!(
+            "failed to spawn scan thread: {e}"
+        )));
+    }
+
+    match rx.recv_timeout(timeout) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(message)) => Err(IsolationFailure::Panicked(message)),
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(IsolationFailure::TimedOut(timeout)),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(IsolationFailure::Panicked(
+            "scan thread exited without a result".to_owned(),
+        )),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_owned()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_isolated_returns_value() {
+        assert_eq!(run_isolated(None, || 42), Ok(42));
+        assert_eq!(run_isolated(Some(Duration::from_secs(5)), || 42), Ok(42));
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn test_run_isolated_catches_panics() {
+        let inline = run_isolated(None, || -> u8 { panic!("boom") });
+        assert_eq!(inline, Err(IsolationFailure::Panicked("boom".to_owned())));
+
+        let threaded = run_isolated(Some(Duration::from_secs(5)), || -> u8 {
+            panic!("boom {}", 2)
+        });
+        assert_eq!(
+            threaded,
+            Err(IsolationFailure::Panicked("boom 2".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_run_isolated_times_out() {
+        let budget = Duration::from_millis(20);
+        let result = run_isolated(Some(budget), || {
+            thread::sleep(Duration::from_secs(2));
+        });
+        assert_eq!(result, Err(IsolationFailure::TimedOut(budget)));
+    }
+}
@@ -25,20 +25,65 @@
 //! println!("OK: {}", report.ok);
 //! ```
 
+mod baseline;
+mod cache;
+mod candidate;
+mod confidence;
 mod config;
+mod config_file;
 mod error;
+mod fix;
 mod format;
+pub mod host;
+mod ignore;
+mod isolation;
+mod messages;
 mod normalize;
 pub mod output;
+mod progress;
+mod ref_form;
 mod report;
+mod reserved;
 mod strategy;
 mod validator;
 
-pub use config::{DiscoveryMode, FsSourceConfig, ValidationConfig, VendorPolicy};
-pub use error::{ScanError, ScanErrorKind, ValidationError};
-pub use report::ValidationReport;
+pub use baseline::Baseline;
+pub use config::{
+    DiscoveryMode, FsSourceConfig, InvalidExamplePolicy, ValidationConfig, VendorPolicy,
+};
+pub use config_file::{ConfigFile, PROJECT_CONFIG_FILE, VALIDATOR_CONFIG_FILE};
+pub use error::{
+    ContextWindow, ERROR_CODES, MAX_CONTEXT_LINES, RuleId, ScanError, ScanErrorKind,
+    ValidationError, fingerprint,
+};
+pub use fix::{
+    AppliedFix, FileFix, FixMode, FixReport, apply_fixes, apply_fixes_with, suggest_fix,
+};
+pub use host::Host;
+pub use messages::MessageCatalog;
+pub use progress::{ScanPhase, ScanProgress};
+pub use report::{
+    FormatFallback, IdOccurrence, PathSummary, ReportFilter, Severity, ValidationReport,
+    VendorSummary,
+};
+pub use strategy::ContentFormat;
+#[cfg(feature = "async")]
+pub use strategy::stream::{ValidationEvent, validate_stream};
+#[cfg(feature = "watch")]
+pub use strategy::watch::validate_fs_watch;
+pub use validator::vendor_of;
 
-use strategy::ContentFormat;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use cache::{ContentHasher, FileStamp, ScanCache};
+use host::FileSystem;
+use isolation::IsolationFailure;
+use ref_form::RefFormRules;
+use reserved::ReservationRules;
 use strategy::fs::{ScanResult, content_format_for, find_files, read_file_bounded};
 
 /// Validate GTS identifiers in files on disk.
@@ -60,34 +105,208 @@ pub fn validate_fs(
     fs_config: &FsSourceConfig,
     validation_config: &ValidationConfig,
 ) -> anyhow::Result<ValidationReport> {
+    validate_fs_with(fs_config, validation_config, &Host::os())
+}
+
+/// [`validate_fs`] over the filesystem and clock of `host`.
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::path::PathBuf;
+/// use gts_validator::host::{ManualClock, MemoryFs};
+/// use gts_validator::{FsSourceConfig, Host, ValidationConfig, validate_fs_with};
+///
+/// let fs = Arc::new(MemoryFs::new());
+/// fs.insert("docs/a.md", "Uses `gts.x.core.pkg.mytype.v1~`.");
+/// let host = Host::deterministic(fs, Arc::new(ManualClock::new()));
+///
+/// let mut fs_config = FsSourceConfig::default();
+/// fs_config.paths = vec![PathBuf::from("docs")];
+/// let report = validate_fs_with(&fs_config, &ValidationConfig::default(), &host).unwrap();
+/// assert_eq!(report.scanned_files, 1);
+/// assert!(report.ok);
+/// ```
+///
+/// # Errors
+///
+/// Same as [`validate_fs`]; cache writes go to `host` as well.
+pub fn validate_fs_with(
+    fs_config: &FsSourceConfig,
+    validation_config: &ValidationConfig,
+    host: &Host,
+) -> anyhow::Result<ValidationReport> {
+    validate_fs_with_progress(fs_config, validation_config, host, &mut |_| {})
+}
+
+/// [`validate_fs_with`], calling `progress` once files are discovered, before
+/// each file is scanned and when the report is complete.
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::path::PathBuf;
+/// use gts_validator::host::{ManualClock, MemoryFs};
+/// use gts_validator::{
+///     FsSourceConfig, Host, ScanPhase, ValidationConfig, validate_fs_with_progress,
+/// };
+///
+/// let fs = Arc::new(MemoryFs::new());
+/// fs.insert("docs/a.md", "Uses `gts.x.core.pkg.mytype.v1~`.");
+/// let host = Host::deterministic(fs, Arc::new(ManualClock::new()));
+///
+/// let mut fs_config = FsSourceConfig::default();
+/// fs_config.paths = vec![PathBuf::from("docs")];
+/// let mut phases = Vec::new();
+/// validate_fs_with_progress(&fs_config, &ValidationConfig::default(), &host, &mut |p| {
+///     phases.push((p.phase, p.files_done, p.files_total));
+/// })
+/// .unwrap();
+/// assert_eq!(
+///     phases,
+///     [(ScanPhase::Discover, 0, 1), (ScanPhase::Scan, 0, 1), (ScanPhase::Done, 1, 1)]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Same as [`validate_fs`].
+pub fn validate_fs_with_progress(
+    fs_config: &FsSourceConfig,
+    validation_config: &ValidationConfig,
+    host: &Host,
+    progress: &mut dyn FnMut(ScanProgress<'_>),
+) -> anyhow::Result<ValidationReport> {
+    check_paths(fs_config, host.fs().as_ref())?;
+    check_vendor_scopes(&validation_config.vendor_policy)?;
+    let (files, scan_errors) = find_files(fs_config, host.fs().as_ref());
+    progress(ScanProgress {
+        phase: ScanPhase::Discover,
+        file: None,
+        files_done: 0,
+        files_total: files.len(),
+    });
+    if files.is_empty() && scan_errors.is_empty() {
+        progress(ScanProgress {
+            phase: ScanPhase::Done,
+            file: None,
+            files_done: 0,
+            files_total: 0,
+        });
+        return Ok(empty_report(fs_config, validation_config));
+    }
+
+    let fs = host.fs().as_ref();
+    let scan_options = Arc::new(ScanOptions::new(validation_config));
+    let mut cache = fs_config
+        .cache
+        .as_deref()
+        .map(|path| ScanCache::load(fs, path, &cache_settings(fs_config, &scan_options)));
+    let settings = ScanSettings {
+        fs_config,
+        validation_config,
+        host,
+        scan_options: &scan_options,
+    };
+    let report = scan_files(&files, scan_errors, settings, cache.as_mut(), progress);
+    progress(ScanProgress {
+        phase: ScanPhase::Done,
+        file: None,
+        files_done: files.len(),
+        files_total: files.len(),
+    });
+    if let (Some(cache), Some(path)) = (cache, &fs_config.cache) {
+        cache.save(fs, path, &files)?;
+    }
+    Ok(report)
+}
+
+/// Fail if `fs_config` has no paths or one of them does not exist.
+fn check_paths(fs_config: &FsSourceConfig, fs: &dyn FileSystem) -> anyhow::Result<()> {
     if fs_config.paths.is_empty() {
         anyhow::bail!("No paths provided for validation");
     }
-
     for path in &fs_config.paths {
-        if !path.exists() {
+        if fs.info(path).is_err() {
             anyhow::bail!("Path does not exist: {}", path.display());
         }
     }
+    Ok(())
+}
 
-    let (files, mut scan_errors) = find_files(fs_config);
+/// Fail if a glob of a [`VendorPolicy::PathScoped`] policy is invalid.
+fn check_vendor_scopes(policy: &VendorPolicy) -> anyhow::Result<()> {
+    if let VendorPolicy::PathScoped(scopes) = policy {
+        for (glob, scope_policy) in scopes {
+            if let Err(error) = glob::Pattern::new(glob) {
+                anyhow::bail!("Invalid vendor scope glob pattern '{glob}': {error}");
+            }
+            check_vendor_scopes(scope_policy)?;
+        }
+    }
+    Ok(())
+}
 
-    if files.is_empty() && scan_errors.is_empty() {
-        return Ok(ValidationReport {
-            scanned_files: 0,
-            failed_files: 0,
-            ok: true,
-            validation_errors: vec![],
-            scan_errors: vec![],
-        });
+/// The report of a run that found no files to scan.
+fn empty_report(
+    fs_config: &FsSourceConfig,
+    validation_config: &ValidationConfig,
+) -> ValidationReport {
+    ValidationReport {
+        scanned_files: 0,
+        failed_files: 0,
+        cached_files: 0,
+        ok: true,
+        validation_errors: vec![],
+        scan_errors: vec![],
+        acknowledged_invalid_examples: 0,
+        heuristic_example_skips: 0,
+        suppressed_findings: 0,
+        findings_by_vendor: BTreeMap::new(),
+        baseline_applied: validation_config.baseline.is_some(),
+        format_fallbacks: vec![],
+        truncated: false,
+        paths: report::summarize_paths(&fs_config.paths, &vec![0; fs_config.paths.len()], &[], &[]),
+        vendors: vec![],
+        ids: vec![],
     }
+}
+
+/// Fingerprint of the settings a cached outcome depends on.
+fn cache_settings(fs_config: &FsSourceConfig, scan_options: &ScanOptions) -> String {
+    let settings = format!(
+        "{}\0{:?}\0{:?}",
+        env!("CARGO_PKG_VERSION"),
+        fs_config.stream_threshold,
+        scan_options
+    );
+    ContentHasher::of(settings.as_bytes())
+}
 
-    let heuristic = validation_config.discovery_mode == DiscoveryMode::Heuristic;
-    // For AllowList, pass a sentinel vendor that no real GTS ID can match.
-    // This causes validate_candidate to emit "Vendor mismatch" for every non-example
-    // vendor, and apply_allow_list_filter then removes the allowed ones — leaving only
-    // genuinely disallowed vendors as errors.
-    let effective_vendor = effective_vendor_for_scanning(&validation_config.vendor_policy);
+/// The configuration and host [`scan_files`] runs with.
+#[derive(Clone, Copy)]
+struct ScanSettings<'a> {
+    fs_config: &'a FsSourceConfig,
+    validation_config: &'a ValidationConfig,
+    host: &'a Host,
+    scan_options: &'a Arc<ScanOptions>,
+}
+
+/// Scan `files` (found along with the discovery errors `scan_errors`) with
+/// `settings` into a report, taking unchanged files from `cache` and calling
+/// `progress` before each file.
+fn scan_files(
+    files: &[PathBuf],
+    mut scan_errors: Vec<ScanError>,
+    settings: ScanSettings<'_>,
+    mut cache: Option<&mut ScanCache>,
+    progress: &mut dyn FnMut(ScanProgress<'_>),
+) -> ValidationReport {
+    let ScanSettings {
+        fs_config,
+        validation_config,
+        host,
+        scan_options,
+    } = settings;
+    let cache_hits_before = cache.as_ref().map_or(0, |cache| cache.hits());
 
     let mut validation_errors = Vec::new();
     let mut scanned_files: usize = 0;
@@ -95,8 +314,24 @@ pub fn validate_fs(
     // are already in scan_errors from find_files. Count them as failed files upfront.
     let mut failed_files: usize = scan_errors.len();
     let mut total_bytes: u64 = 0;
+    let mut acknowledged_invalid_examples: usize = 0;
+    let mut heuristic_example_skips: usize = 0;
+    let mut suppressed_findings: usize = 0;
+    let mut format_fallbacks = Vec::new();
+    let mut baseline_marker = validation_config.baseline.as_ref().map(Baseline::marker);
+    let error_limit = error_limit(validation_config);
+    let mut truncated = false;
+    let mut scanned_per_path = vec![0; fs_config.paths.len()];
+    let mut vendors = BTreeMap::new();
+    let mut ids = Vec::new();
 
-    'files: for file_path in &files {
+    for (index, file_path) in files.iter().enumerate() {
+        progress(ScanProgress {
+            phase: ScanPhase::Scan,
+            file: Some(file_path),
+            files_done: index,
+            files_total: files.len(),
+        });
         if scanned_files + failed_files >= fs_config.max_files {
             scan_errors.push(ScanError {
                 file: file_path.clone(),
@@ -110,131 +345,646 @@ pub fn validate_fs(
             break;
         }
 
-        let content = match read_file_bounded(file_path, fs_config.max_file_size) {
-            ScanResult::Ok(c) => c,
-            ScanResult::Err(e) => {
-                scan_errors.push(e);
+        let step = scan_file(
+            file_path,
+            fs_config,
+            scan_options,
+            host,
+            &mut total_bytes,
+            cache.as_deref_mut(),
+        );
+        let outcome = match step {
+            FileStep::Done(outcome) => outcome,
+            FileStep::Unreadable(scan_err) => {
+                scan_errors.push(scan_err);
+                failed_files += 1;
+                continue;
+            }
+            FileStep::BudgetExhausted(scan_err) => {
+                scan_errors.push(scan_err);
+                failed_files += 1;
+                break;
+            }
+        };
+
+        let file_scan = match outcome {
+            FileOutcome::Unsupported => continue,
+            FileOutcome::Failed(scan_err) => {
+                scan_errors.push(scan_err);
                 failed_files += 1;
                 continue;
             }
+            FileOutcome::Scanned(file_scan) => file_scan,
+        };
+        acknowledged_invalid_examples += file_scan.acknowledged_examples;
+        format_fallbacks.extend(file_scan.fallback);
+        heuristic_example_skips += file_scan.heuristic_skips;
+        if !file_scan.scan_errors.is_empty() {
+            failed_files += 1;
+            scan_errors.extend(file_scan.scan_errors);
+        }
+        scanned_files += 1;
+        if let Some(path_index) = report::owning_path(&fs_config.paths, file_path) {
+            scanned_per_path[path_index] += 1;
+        }
+
+        // For AllowList and PathScoped: filter out errors where the vendor IS allowed.
+        // The sentinel vendor caused mismatches for all vendors; remove the allowed ones.
+        let collect_sightings = collects_sightings(validation_config);
+        let (file_errors, mut sightings) =
+            take_vendor_sightings(file_scan.errors, collect_sightings);
+        // Suppressed IDs are still sightings; what remains of them would have been reported
+        let (suppressed, suppressed_sightings) =
+            take_vendor_sightings(file_scan.suppressed, collect_sightings);
+        sightings.extend(suppressed_sightings);
+        suppressed_findings +=
+            apply_allow_list_filter(suppressed, &validation_config.vendor_policy).len();
+        if validation_config.collect_vendors {
+            report::tally_vendor_sightings(&mut vendors, &sightings);
+        }
+        if validation_config.collect_ids {
+            ids.extend(sightings.iter().map(IdOccurrence::from));
+        }
+        let mut file_errors =
+            apply_allow_list_filter(file_errors, &validation_config.vendor_policy);
+        apply_rule_overrides(&mut file_errors, &validation_config.rule_overrides);
+        apply_min_confidence(&mut file_errors, validation_config.min_confidence);
+        if let Some(marker) = &mut baseline_marker {
+            marker.mark(&mut file_errors);
+        }
+        validation_errors.extend(file_errors);
+
+        if let Some(limit) = error_limit
+            && let Some((last, _)) = validation_errors
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| e.fails_run())
+                .nth(limit - 1)
+        {
+            truncated = last + 1 < validation_errors.len() || index + 1 < files.len();
+            validation_errors.truncate(last + 1);
+            break;
+        }
+    }
+
+    let findings_by_vendor = report::tally_vendors(&validation_errors);
+
+    let cached_files = cache.map_or(0, |cache| cache.hits() - cache_hits_before);
+
+    let ok = !validation_errors.iter().any(ValidationError::fails_run) && scan_errors.is_empty();
+    let paths = report::summarize_paths(
+        &fs_config.paths,
+        &scanned_per_path,
+        &validation_errors,
+        &scan_errors,
+    );
+    ValidationReport {
+        scanned_files,
+        failed_files,
+        cached_files,
+        ok,
+        validation_errors,
+        scan_errors,
+        acknowledged_invalid_examples,
+        heuristic_example_skips,
+        suppressed_findings,
+        findings_by_vendor,
+        baseline_applied: validation_config.baseline.is_some(),
+        format_fallbacks,
+        truncated,
+        paths,
+        vendors: vendors.into_values().collect(),
+        ids,
+    }
+}
+
+/// Per-run scanner settings, shared with isolated scan threads.
+///
+/// Its `Debug` form keys the incremental cache, so it must cover every
+/// setting that changes a file's outcome.
+#[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
+struct ScanOptions {
+    discovery: DiscoveryMode,
+    example_heuristics: bool,
+    vendor: Option<String>,
+    skip_tokens: Vec<String>,
+    scan_keys: bool,
+    scan_comments: bool,
+    format_fallback: bool,
+    ref_forms: RefFormRules,
+    reservations: ReservationRules,
+    context_lines: usize,
+}
+
+impl ScanOptions {
+    fn new(validation_config: &ValidationConfig) -> Self {
+        Self {
+            discovery: validation_config.discovery_mode.clone(),
+            example_heuristics: validation_config.invalid_examples
+                == InvalidExamplePolicy::MarkersAndHeuristics,
+            // For AllowList, pass a sentinel vendor that no real GTS ID can match.
+            // This causes validate_candidate to emit "Vendor mismatch" for every non-example
+            // vendor, and apply_allow_list_filter then removes the allowed ones — leaving only
+            // genuinely disallowed vendors as errors. Collecting vendors uses the
+            // same sentinel, see take_vendor_sightings.
+            vendor: effective_vendor_for_scanning(
+                &validation_config.vendor_policy,
+                collects_sightings(validation_config),
+            ),
+            skip_tokens: validation_config.skip_tokens.clone(),
+            scan_keys: validation_config.scan_keys,
+            scan_comments: validation_config.scan_comments,
+            format_fallback: validation_config.format_fallback,
+            ref_forms: RefFormRules::new(
+                &validation_config.type_ref_paths,
+                &validation_config.instance_ref_paths,
+            ),
+            reservations: ReservationRules::new(
+                validation_config.reservations.clone(),
+                validation_config.reservation_owner.clone(),
+            ),
+            context_lines: validation_config.context_lines,
+        }
+    }
+
+    /// The settings of the JSON/YAML scanners.
+    fn stream_rules(&self) -> format::stream::StreamRules<'_> {
+        format::stream::StreamRules {
+            vendor: self.vendor.as_deref(),
+            scan_keys: self.scan_keys,
+            scan_comments: self.scan_comments,
+            ref_forms: &self.ref_forms,
+            reservations: &self.reservations,
+        }
+    }
+}
+
+/// Number of validation errors failing the run after which the scan stops.
+fn error_limit(validation_config: &ValidationConfig) -> Option<usize> {
+    if validation_config.fail_fast {
+        Some(1)
+    } else {
+        validation_config.max_errors.map(|max| max.max(1))
+    }
+}
+
+/// Findings of a file that was scanned (possibly with per-document scan errors).
+#[derive(Clone, Serialize, Deserialize)]
+struct FileScan {
+    errors: Vec<ValidationError>,
+    scan_errors: Vec<ScanError>,
+    acknowledged_examples: usize,
+    heuristic_skips: usize,
+    /// Findings suppressed by `gts-ignore` directives, see [`ignore`].
+    suppressed: Vec<ValidationError>,
+    /// Set when the file was parsed as another format than its extension implies.
+    fallback: Option<FormatFallback>,
+}
+
+impl FileScan {
+    fn new(
+        errors: Vec<ValidationError>,
+        scan_errors: Vec<ScanError>,
+        suppressed: Vec<ValidationError>,
+    ) -> Self {
+        Self {
+            errors,
+            scan_errors,
+            acknowledged_examples: 0,
+            heuristic_skips: 0,
+            suppressed,
+            fallback: None,
+        }
+    }
+
+    fn parsed_as(mut self, file: &Path, expected: ContentFormat, parsed_as: ContentFormat) -> Self {
+        self.fallback = Some(FormatFallback {
+            file: file.to_path_buf(),
+            expected,
+            parsed_as,
+        });
+        self
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+enum FileOutcome {
+    /// Not a scannable format.
+    Unsupported,
+    /// The file could not be scanned at all.
+    Failed(ScanError),
+    Scanned(FileScan),
+}
+
+/// What became of one discovered file.
+enum FileStep {
+    Done(FileOutcome),
+    /// The file could not be read; the scan moves on.
+    Unreadable(ScanError),
+    /// Reading the file would exceed `max_total_bytes`; the scan stops.
+    BudgetExhausted(ScanError),
+}
+
+/// Scan one file in isolation, or take its outcome from `cache` if the file
+/// is unchanged since the cached run. Fresh outcomes (not timeouts or panics)
+/// are added to `cache`.
+fn scan_file(
+    file_path: &Path,
+    fs_config: &FsSourceConfig,
+    scan_options: &Arc<ScanOptions>,
+    host: &Host,
+    total_bytes: &mut u64,
+    mut cache: Option<&mut ScanCache>,
+) -> FileStep {
+    let fs = host.fs();
+    let stamp = cache
+        .as_ref()
+        .and_then(|_| FileStamp::of(fs.as_ref(), file_path));
+    if let (Some(cache), Some(stamp)) = (cache.as_deref_mut(), stamp)
+        && let Some(outcome) = cache.unchanged(file_path, stamp)
+    {
+        return FileStep::Done(outcome);
+    }
+
+    let options = Arc::clone(scan_options);
+    let path = file_path.to_path_buf();
+    let (isolated, hash) = if is_streamed(fs.as_ref(), file_path, fs_config.stream_threshold) {
+        let hash = match stamp
+            .map(|_| ContentHasher::of_file(fs.as_ref(), file_path))
+            .transpose()
+        {
+            Ok(hash) => hash,
+            Err(e) => {
+                return FileStep::Unreadable(ScanError {
+                    file: path,
+                    kind: ScanErrorKind::IoError,
+                    message: format!("Failed to read file: {e}"),
+                });
+            }
+        };
+        if let (Some(cache), Some(stamp), Some(hash)) = (cache.as_deref_mut(), stamp, &hash)
+            && let Some(outcome) = cache.same_content(file_path, stamp, hash)
+        {
+            return FileStep::Done(outcome);
+        }
+        let fs = Arc::clone(fs);
+        let isolated = host.run_isolated(fs_config.file_timeout, move || {
+            stream_file(fs.as_ref(), &path, &options)
+        });
+        (isolated, hash)
+    } else {
+        let content = match read_file_bounded(fs.as_ref(), file_path, fs_config.max_file_size) {
+            ScanResult::Ok(c) => c,
+            ScanResult::Err(e) => return FileStep::Unreadable(e),
         };
 
         let file_bytes = content.len() as u64;
         if total_bytes.saturating_add(file_bytes) > fs_config.max_total_bytes {
-            scan_errors.push(ScanError {
-                file: file_path.clone(),
+            return FileStep::BudgetExhausted(ScanError {
+                file: path,
                 kind: ScanErrorKind::LimitExceeded,
                 message: format!(
                     "Scan aborted: max_total_bytes limit ({}) reached; remaining files not scanned",
                     fs_config.max_total_bytes
                 ),
             });
-            failed_files += 1;
-            break;
         }
-        total_bytes = total_bytes.saturating_add(file_bytes);
+        *total_bytes = total_bytes.saturating_add(file_bytes);
 
-        let vendor = effective_vendor.as_deref();
-        let file_errors = match content_format_for(file_path) {
-            Some(ContentFormat::Markdown) => format::markdown::scan_markdown_content(
-                &content,
-                file_path,
-                vendor,
-                heuristic,
-                &validation_config.skip_tokens,
-            ),
-            Some(ContentFormat::Json) => {
-                match format::json::scan_json_content(
-                    &content,
-                    file_path,
-                    vendor,
-                    validation_config.scan_keys,
-                ) {
-                    Ok(errs) => errs,
-                    Err(scan_err) => {
-                        scan_errors.push(scan_err);
-                        failed_files += 1;
-                        continue 'files;
-                    }
-                }
+        let hash = stamp.map(|_| ContentHasher::of(content.as_bytes()));
+        if let (Some(cache), Some(stamp), Some(hash)) = (cache.as_deref_mut(), stamp, &hash)
+            && let Some(outcome) = cache.same_content(file_path, stamp, hash)
+        {
+            return FileStep::Done(outcome);
+        }
+        let isolated = host.run_isolated(fs_config.file_timeout, move || {
+            scan_content_with_context(&content, &path, &options)
+        });
+        (isolated, hash)
+    };
+
+    match isolated {
+        Ok(outcome) => {
+            if let (Some(cache), Some(stamp), Some(hash)) = (cache, stamp, hash) {
+                cache.insert(file_path, stamp, hash, &outcome);
             }
-            Some(ContentFormat::Yaml) => {
-                let (val_errs, yaml_scan_errs) = format::yaml::scan_yaml_content(
-                    &content,
+            FileStep::Done(outcome)
+        }
+        Err(failure) => FileStep::Done(FileOutcome::Failed(isolation_scan_error(
+            file_path, &failure,
+        ))),
+    }
+}
+
+/// [`scan_content`], adding the configured context window to each error.
+fn scan_content_with_context(
+    content: &str,
+    file_path: &Path,
+    options: &ScanOptions,
+) -> FileOutcome {
+    let mut outcome = scan_content(content, file_path, options);
+    if options.context_lines > 0
+        && let FileOutcome::Scanned(file_scan) = &mut outcome
+    {
+        for error in &mut file_scan.errors {
+            error.context_window =
+                ContextWindow::around(content, error.line, options.context_lines);
+        }
+    }
+    outcome
+}
+
+/// Dispatch `content` to the scanner for its format, retrying with the
+/// fallback format if enabled.
+fn scan_content(content: &str, file_path: &Path, options: &ScanOptions) -> FileOutcome {
+    let Some(format) = content_format_for(file_path) else {
+        return FileOutcome::Unsupported;
+    };
+    if !options.format_fallback {
+        return scan_as(format, content, file_path, options);
+    }
+
+    match format {
+        // Markdown never fails to parse; a file that is one JSON document is
+        // a mislabeled JSON file rather than prose.
+        ContentFormat::Markdown => {
+            let trimmed = content.trim_start();
+            if (trimmed.starts_with('{') || trimmed.starts_with('['))
+                && let FileOutcome::Scanned(scan) =
+                    scan_as(ContentFormat::Json, content, file_path, options)
+            {
+                return FileOutcome::Scanned(scan.parsed_as(
                     file_path,
-                    vendor,
-                    validation_config.scan_keys,
-                );
-                if !yaml_scan_errs.is_empty() {
-                    failed_files += 1;
-                    scan_errors.extend(yaml_scan_errs);
+                    format,
+                    ContentFormat::Json,
+                ));
+            }
+            scan_as(format, content, file_path, options)
+        }
+        ContentFormat::Rst | ContentFormat::Asciidoc => {
+            scan_as(format, content, file_path, options)
+        }
+        ContentFormat::Json | ContentFormat::Yaml => {
+            let primary = scan_as(format, content, file_path, options);
+            let primary_failed = match &primary {
+                FileOutcome::Failed(_) => true,
+                FileOutcome::Scanned(scan) => !scan.scan_errors.is_empty(),
+                FileOutcome::Unsupported => false,
+            };
+            if !primary_failed {
+                return primary;
+            }
+            let secondary = if format == ContentFormat::Json {
+                ContentFormat::Yaml
+            } else {
+                ContentFormat::Json
+            };
+            match scan_as(secondary, content, file_path, options) {
+                FileOutcome::Scanned(scan) if scan.scan_errors.is_empty() => {
+                    FileOutcome::Scanned(scan.parsed_as(file_path, format, secondary))
                 }
-                val_errs
+                // Report the primary parser's error: it matches the file extension
+                _ => primary,
             }
-            None => continue,
-        };
+        }
+    }
+}
 
-        scanned_files += 1;
+/// Scan `content` with the scanner for `format`.
+fn scan_as(
+    format: ContentFormat,
+    content: &str,
+    file_path: &Path,
+    options: &ScanOptions,
+) -> FileOutcome {
+    let vendor = options.vendor.as_deref();
+    let mut suppressed = Vec::new();
+    match format {
+        ContentFormat::Markdown | ContentFormat::Rst | ContentFormat::Asciidoc => {
+            let heuristic = match options.discovery {
+                DiscoveryMode::StrictSpecOnly => false,
+                DiscoveryMode::Heuristic => true,
+                DiscoveryMode::Auto => format::markdown::has_missed_candidates(content),
+            };
+            // reStructuredText and AsciiDoc drive the Markdown line scanner
+            let scan = match format {
+                ContentFormat::Rst => format::rst::scan_rst_content,
+                ContentFormat::Asciidoc => format::asciidoc::scan_asciidoc_content,
+                _ => format::markdown::scan_markdown_content,
+            };
+            let md = scan(
+                content,
+                file_path,
+                vendor,
+                heuristic,
+                options.example_heuristics,
+                &options.skip_tokens,
+            );
+            FileOutcome::Scanned(FileScan {
+                errors: md.errors,
+                scan_errors: Vec::new(),
+                acknowledged_examples: md.acknowledged_examples,
+                heuristic_skips: md.heuristic_skips,
+                suppressed: md.suppressed,
+                fallback: None,
+            })
+        }
+        ContentFormat::Json => match format::json::scan_json_content(
+            content,
+            file_path,
+            &options.stream_rules(),
+            &mut suppressed,
+        ) {
+            Ok(errors) => FileOutcome::Scanned(FileScan::new(errors, Vec::new(), suppressed)),
+            Err(scan_err) => FileOutcome::Failed(scan_err),
+        },
+        ContentFormat::Yaml => {
+            let (errors, scan_errors) = format::yaml::scan_yaml_content(
+                content,
+                file_path,
+                &options.stream_rules(),
+                &mut suppressed,
+            );
+            FileOutcome::Scanned(FileScan::new(errors, scan_errors, suppressed))
+        }
+    }
+}
 
-        // For AllowList: filter out errors where the vendor IS in the allow list.
-        // The sentinel vendor caused mismatches for all vendors; remove the allowed ones.
-        let file_errors = apply_allow_list_filter(file_errors, &validation_config.vendor_policy);
-        validation_errors.extend(file_errors);
+/// Whether `file_path` is a JSON/YAML file above the streaming threshold.
+fn is_streamed(fs: &dyn FileSystem, file_path: &Path, threshold: Option<u64>) -> bool {
+    let Some(threshold) = threshold else {
+        return false;
+    };
+    matches!(
+        content_format_for(file_path),
+        Some(ContentFormat::Json | ContentFormat::Yaml)
+    ) && fs.info(file_path).is_ok_and(|info| info.len > threshold)
+}
+
+/// Scan a JSON/YAML file without loading it, see [`format::stream`].
+fn stream_file(fs: &dyn FileSystem, file_path: &Path, options: &ScanOptions) -> FileOutcome {
+    let file = match fs.open(file_path) {
+        Ok(file) => std::io::BufReader::new(file),
+        Err(e) => {
+            return FileOutcome::Failed(ScanError {
+                file: file_path.to_path_buf(),
+                kind: ScanErrorKind::IoError,
+                message: format!("Failed to open file: {e}"),
+            });
+        }
+    };
+    let rules = options.stream_rules();
+    let mut suppressed = Vec::new();
+    match content_format_for(file_path) {
+        Some(ContentFormat::Json) => {
+            match format::stream::scan_json_reader(file, file_path, &rules, &mut suppressed) {
+                Ok(errors) => FileOutcome::Scanned(FileScan::new(errors, Vec::new(), suppressed)),
+                Err(scan_err) => FileOutcome::Failed(scan_err),
+            }
+        }
+        Some(ContentFormat::Yaml) => {
+            let (errors, scan_errors) =
+                format::stream::scan_yaml_reader(file, file_path, &rules, &mut suppressed);
+            FileOutcome::Scanned(FileScan::new(errors, scan_errors, suppressed))
+        }
+        Some(ContentFormat::Markdown | ContentFormat::Rst | ContentFormat::Asciidoc) | None => {
+            FileOutcome::Unsupported
+        }
     }
+}
 
-    let ok = validation_errors.is_empty() && scan_errors.is_empty();
-    Ok(ValidationReport {
-        scanned_files,
-        failed_files,
-        ok,
-        validation_errors,
-        scan_errors,
-    })
+fn isolation_scan_error(file_path: &Path, failure: &IsolationFailure) -> ScanError {
+    let (kind, message) = match failure {
+        IsolationFailure::TimedOut(budget) => (
+            ScanErrorKind::Timeout,
+            format!(
+                "Scan did not finish within the per-file time budget ({} ms)",
+                budget.as_millis()
+            ),
+        ),
+        IsolationFailure::Panicked(message) => (
+            ScanErrorKind::Panicked,
+            format!("Scanner panicked: {message}"),
+        ),
+    };
+    ScanError {
+        file: file_path.to_path_buf(),
+        kind,
+        message,
+    }
 }
 
 /// Determine the effective vendor string to pass to scanners for a given policy.
 ///
-/// - `Any` → `None` (no vendor enforcement).
+/// - `Any` → `None` (no vendor enforcement), or the sentinel below when
+///   collecting IDs or vendors.
 /// - `MustMatch(v)` → `Some(v)` (scanner enforces exact match directly).
 /// - `AllowList(_)` → `Some("\x00")` (sentinel that no real GTS vendor can match).
 ///   GTS vendors must be lowercase alphanumeric, so `\x00` is guaranteed to never
 ///   equal any real vendor. This causes `validate_candidate` to emit "Vendor mismatch"
 ///   for every non-example vendor, and `apply_allow_list_filter` then removes the
 ///   vendors that are in the allow list — leaving only genuinely disallowed vendors.
-fn effective_vendor_for_scanning(policy: &VendorPolicy) -> Option<String> {
+/// - `PathScoped(_)` → the same sentinel; `apply_allow_list_filter` applies the
+///   policy of each file's scope.
+fn effective_vendor_for_scanning(policy: &VendorPolicy, collect_sightings: bool) -> Option<String> {
     match policy {
+        VendorPolicy::Any if collect_sightings => Some("\x00".to_owned()),
         VendorPolicy::Any => None,
         VendorPolicy::MustMatch(v) => Some(v.clone()),
-        VendorPolicy::AllowList(_) => Some("\x00".to_owned()),
+        VendorPolicy::AllowList(_) | VendorPolicy::PathScoped(_) => Some("\x00".to_owned()),
     }
 }
 
-/// For `VendorPolicy::AllowList`, remove validation errors whose vendor IS in the list.
+/// For `VendorPolicy::AllowList` and `PathScoped`, remove validation errors
+/// whose vendor the policy of their file accepts.
 ///
 /// Scanners run with a sentinel vendor (`\x00`) that generates "Vendor mismatch" for
 /// every non-example vendor. This function retains only errors where the vendor is NOT
-/// in the allow list — i.e., genuinely disallowed vendors produce errors.
+/// in the allow list — i.e., genuinely disallowed vendors produce errors. Under a
+/// `MustMatch` scope the message names the expected vendor instead of the sentinel.
 fn apply_allow_list_filter(
     errors: Vec<ValidationError>,
     policy: &VendorPolicy,
 ) -> Vec<ValidationError> {
-    let VendorPolicy::AllowList(allowed) = policy else {
+    if !matches!(
+        policy,
+        VendorPolicy::AllowList(_) | VendorPolicy::PathScoped(_)
+    ) {
         return errors;
-    };
+    }
 
     errors
         .into_iter()
-        .filter(|e| {
+        .filter_map(|mut e| {
             // Keep the error only if it is NOT a vendor-mismatch for an allowed vendor.
             // Vendor-mismatch errors contain "Vendor mismatch" in the message.
             // Extract the actual vendor from normalized_id (first segment before '.').
             if !e.error.contains("Vendor mismatch") {
-                return true; // non-vendor errors always kept
+                return Some(e); // non-vendor errors always kept
+            }
+            let Some(id_vendor) = vendor_of(&e.normalized_id) else {
+                return Some(e);
+            };
+            match policy.for_path(&e.file) {
+                VendorPolicy::AllowList(allowed) => (!allowed.contains(&id_vendor)).then_some(e),
+                VendorPolicy::MustMatch(expected) if *expected != id_vendor => {
+                    e.error =
+                        format!("Vendor mismatch: expected '{expected}', found '{id_vendor}'");
+                    Some(e)
+                }
+                _ => None,
             }
-            // normalized_id format: "gts.<vendor>.<rest>..."
-            // The vendor is the second dot-separated segment (index 1).
-            let id_vendor = e.normalized_id.split('.').nth(1).unwrap_or("");
-            !allowed.iter().any(|a| a == id_vendor)
         })
         .collect()
 }
+
+/// Whether the run lists the scanned IDs or their vendors, see
+/// [`ValidationConfig::collect_ids`] and [`ValidationConfig::collect_vendors`].
+fn collects_sightings(validation_config: &ValidationConfig) -> bool {
+    (validation_config.collect_vendors || validation_config.collect_ids)
+        && matches!(validation_config.vendor_policy, VendorPolicy::Any)
+}
+
+/// Split the "Vendor mismatch" errors the sentinel vendor produced for every
+/// non-example ID off `errors` when collecting IDs or vendors: they are
+/// sightings of the ID, not findings.
+fn take_vendor_sightings(
+    errors: Vec<ValidationError>,
+    collect_sightings: bool,
+) -> (Vec<ValidationError>, Vec<ValidationError>) {
+    if !collect_sightings {
+        return (errors, Vec::new());
+    }
+    let (sightings, errors) = errors
+        .into_iter()
+        .partition(|e| e.rule() == RuleId::VendorMismatch);
+    (errors, sightings)
+}
+
+/// Set the severity of each finding from `overrides`, keyed by its rule.
+fn apply_rule_overrides(errors: &mut [ValidationError], overrides: &BTreeMap<RuleId, Severity>) {
+    if overrides.is_empty() {
+        return;
+    }
+    for error in errors {
+        if let Some(&severity) = overrides.get(&error.rule()) {
+            error.severity = severity;
+        }
+    }
+}
+
+/// Demote to `Info` the findings whose confidence is below `threshold`.
+fn apply_min_confidence(errors: &mut [ValidationError], threshold: Option<u8>) {
+    let Some(threshold) = threshold else {
+        return;
+    };
+    for error in errors {
+        if error
+            .confidence
+            .is_some_and(|confidence| confidence < threshold)
+        {
+            error.severity = Severity::Info;
+        }
+    }
+}
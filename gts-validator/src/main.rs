@@ -10,28 +10,44 @@
 
 use std::path::PathBuf;
 use std::process::ExitCode;
+use std::time::Duration;
 
 use clap::Parser;
 
-use gts_validator::output;
-use gts_validator::{DiscoveryMode, FsSourceConfig, ValidationConfig, VendorPolicy};
+use gts_validator::output::{self, HumanOutputMode};
+use gts_validator::{
+    Baseline, ConfigFile, DiscoveryMode, ERROR_CODES, FixMode, FsSourceConfig,
+    InvalidExamplePolicy, MessageCatalog, ReportFilter, RuleId, Severity, ValidationConfig,
+    ValidationReport, VendorPolicy,
+};
 
 /// GTS Documentation Validator (DE0903)
 ///
-/// Validates GTS identifiers in .md/.json/.yaml/.yml files.
+/// Validates GTS identifiers in .md/.rst/.adoc/.json/.yaml/.yml files.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 #[allow(clippy::struct_excessive_bools)]
 struct Cli {
     /// Paths to scan (files or directories)
-    /// Defaults to: docs, modules, libs, examples
+    /// Defaults to: `paths` of the config file, else docs, modules, libs, examples
     #[arg(value_name = "PATH")]
     paths: Vec<PathBuf>,
 
+    /// Config file; by default `.gtsvalidator.toml` or the `[validator]`
+    /// table of `gts.toml` is looked up in the current directory and its
+    /// ancestors. Flags override its values.
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
     /// Expected vendor for all GTS IDs (validates vendor matches)
     #[arg(long)]
     vendor: Option<String>,
 
+    /// Without --vendor, list the vendor of every GTS ID found, with its
+    /// count and the first place it appears
+    #[arg(long, conflicts_with = "vendor")]
+    list_vendors: bool,
+
     /// Exclude patterns (can be specified multiple times)
     #[arg(long, short = 'e', action = clap::ArgAction::Append)]
     exclude: Vec<String>,
@@ -40,6 +56,14 @@ struct Cli {
     #[arg(long)]
     json: bool,
 
+    /// Print only the findings, one per line (no banner, silent on success)
+    #[arg(long, short = 'q', conflicts_with_all = ["json", "summary_only"])]
+    quiet: bool,
+
+    /// Print only counts per error class and per file, no individual findings
+    #[arg(long, conflicts_with = "json")]
+    summary_only: bool,
+
     /// Show verbose output including file scanning progress
     #[arg(long, short = 'v')]
     verbose: bool,
@@ -48,17 +72,133 @@ struct Cli {
     #[arg(long, default_value = "10485760")]
     max_file_size: u64,
 
+    /// Scan JSON/YAML files larger than this many bytes as a stream, without
+    /// loading them; such files are exempt from --max-file-size
+    #[arg(long, value_name = "BYTES")]
+    stream_threshold: Option<u64>,
+
+    /// Incremental mode: keep per-file results in this cache file and only
+    /// re-validate files that changed since the run that wrote it
+    #[arg(long, value_name = "FILE")]
+    cache: Option<PathBuf>,
+
+    /// Per-file scan time budget in seconds; 0 disables it (default: 30)
+    #[arg(long, default_value = "30")]
+    file_timeout: u64,
+
     /// Scan JSON/YAML object keys for GTS identifiers (default: off)
     #[arg(long)]
     scan_keys: bool,
 
+    /// Do not scan `$comment`/`description` values in JSON/YAML files
+    #[arg(long)]
+    skip_comments: bool,
+
     /// Strict mode: catches ALL gts.* strings including malformed IDs.
     #[arg(long)]
     strict: bool,
 
+    /// Auto discovery: apply strict mode only to files containing a gts.* string
+    /// that is not a well-formed ID.
+    #[arg(long, conflicts_with = "strict")]
+    auto_discovery: bool,
+
+    /// Only skip markdown examples carrying an explicit `<!-- gts:invalid-example -->`
+    /// marker or `(invalid)` tag; ignore emoji/keyword heuristics
+    #[arg(long)]
+    no_example_heuristics: bool,
+
     /// Skip tokens for markdown scanning (repeatable)
     #[arg(long = "skip-token", action = clap::ArgAction::Append)]
     skip_tokens: Vec<String>,
+
+    /// Retry files that fail to parse with the other format (JSON <-> YAML) and
+    /// scan Markdown files that are a single JSON document as JSON
+    #[arg(long)]
+    format_fallback: bool,
+
+    /// JSON path pattern of fields that must hold type IDs, e.g. `$..x-gts-ref`
+    /// or `$.extends` (repeatable)
+    #[arg(long = "type-ref-path", value_name = "PATTERN", action = clap::ArgAction::Append)]
+    type_ref_paths: Vec<String>,
+
+    /// JSON path pattern of fields that must hold instance IDs (repeatable)
+    #[arg(long = "instance-ref-path", value_name = "PATTERN", action = clap::ArgAction::Append)]
+    instance_ref_paths: Vec<String>,
+
+    /// YAML/JSON file of reserved vendors/packages; documents defining
+    /// entities under a reservation not held by `--owner` are reported
+    #[arg(long, value_name = "FILE")]
+    reservations: Option<PathBuf>,
+
+    /// Owner the scanned files are written by, for `--reservations`
+    #[arg(long, value_name = "NAME", requires = "reservations")]
+    owner: Option<String>,
+
+    /// JSON report (`--json`) of an earlier run; findings already in it are
+    /// reported as baselined and do not fail the run
+    #[arg(long, value_name = "REPORT")]
+    baseline: Option<PathBuf>,
+
+    /// Stop at the first validation error (baselined findings do not count)
+    #[arg(long, conflicts_with = "max_errors")]
+    fail_fast: bool,
+
+    /// Stop after N validation errors (baselined findings do not count); the
+    /// report notes the truncation
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    max_errors: Option<u64>,
+
+    /// Only report validation errors with this error code (repeatable); scan
+    /// errors are left out, and the exit status reflects what is reported
+    #[arg(
+        long = "only-code",
+        value_name = "CODE",
+        value_parser = clap::builder::PossibleValuesParser::new(ERROR_CODES),
+        action = clap::ArgAction::Append
+    )]
+    only_codes: Vec<String>,
+
+    /// Only report findings in files matching this glob (repeatable), e.g.
+    /// `docs/**`; the exit status reflects what is reported
+    #[arg(long = "only-path", value_name = "GLOB", action = clap::ArgAction::Append)]
+    only_paths: Vec<String>,
+
+    /// Severity of a rule, as `CODE=SEVERITY` (repeatable), e.g.
+    /// `vendor_mismatch=warning`; only `error` findings fail the run
+    #[arg(long = "rule", value_name = "CODE=SEVERITY", value_parser = parse_rule_override, action = clap::ArgAction::Append)]
+    rule_overrides: Vec<(RuleId, Severity)>,
+
+    /// With `--strict` or `--auto-discovery`, demote findings whose
+    /// confidence (0-100) is below N to `info`
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=100))]
+    min_confidence: Option<u8>,
+
+    /// Lines of context to show before and after each finding (max 10)
+    #[arg(long, value_name = "N", default_value = "0")]
+    context: usize,
+
+    /// Rewrite invalid IDs with a mechanical fix (uppercase letters, `-` for
+    /// `_`, missing `gts.` prefix or `~`) in place, then report what is left
+    #[arg(long)]
+    fix: bool,
+
+    /// With --fix, print the fixes as a unified diff instead of writing them
+    #[arg(long, requires = "fix", conflicts_with = "json")]
+    dry_run: bool,
+
+    /// YAML/JSON message catalog (message key -> text) translating or
+    /// rewording the human output; missing keys stay English
+    #[arg(long, value_name = "FILE", conflicts_with = "json")]
+    messages: Option<PathBuf>,
+}
+
+/// Parse a `--rule CODE=SEVERITY` value.
+fn parse_rule_override(value: &str) -> Result<(RuleId, Severity), String> {
+    let (code, severity) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected CODE=SEVERITY, got '{value}'"))?;
+    Ok((code.parse()?, severity.parse()?))
 }
 
 /// Default directories to scan if no paths are provided.
@@ -67,40 +207,116 @@ const DEFAULT_SCAN_DIRS: &[&str] = &["docs", "modules", "libs", "examples"];
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
-    let paths: Vec<PathBuf> = if cli.paths.is_empty() {
-        DEFAULT_SCAN_DIRS
-            .iter()
-            .map(PathBuf::from)
-            .filter(|path| path.exists())
-            .collect()
-    } else {
-        cli.paths
+    // The config file supplies defaults, command-line flags win
+    let config_file = match std::env::current_dir()
+        .map_err(anyhow::Error::from)
+        .and_then(|cwd| ConfigFile::resolve(cli.config.as_deref(), &cwd))
+    {
+        Ok(config_file) => config_file,
+        Err(error) => {
+            eprintln!("Error: {error:#}");
+            return ExitCode::FAILURE;
+        }
     };
+    let mut fs_config = FsSourceConfig::default();
+    let mut validation_config = ValidationConfig::default();
+    config_file.apply(&mut fs_config, &mut validation_config);
 
-    if paths.is_empty() {
+    fs_config.paths = scan_paths(cli.paths, std::mem::take(&mut fs_config.paths));
+    if fs_config.paths.is_empty() {
         eprintln!("No existing paths to scan. Provide paths explicitly.");
         return ExitCode::FAILURE;
     }
 
-    let mut fs_config = FsSourceConfig::default();
-    fs_config.paths = paths;
-    fs_config.exclude = cli.exclude;
+    if !cli.exclude.is_empty() {
+        fs_config.exclude = cli.exclude;
+    }
     fs_config.max_file_size = cli.max_file_size;
+    fs_config.stream_threshold = cli.stream_threshold;
+    fs_config.cache = cli.cache;
+    fs_config.file_timeout = (cli.file_timeout > 0).then(|| Duration::from_secs(cli.file_timeout));
 
-    let mut validation_config = ValidationConfig::default();
     validation_config.scan_keys = cli.scan_keys;
-    validation_config.discovery_mode = if cli.strict {
-        DiscoveryMode::Heuristic
-    } else {
-        DiscoveryMode::StrictSpecOnly
-    };
-    validation_config.skip_tokens = cli.skip_tokens;
+    validation_config.scan_comments = !cli.skip_comments;
+    if cli.strict {
+        validation_config.discovery_mode = DiscoveryMode::Heuristic;
+    } else if cli.auto_discovery {
+        validation_config.discovery_mode = DiscoveryMode::Auto;
+    }
+    if !cli.skip_tokens.is_empty() {
+        validation_config.skip_tokens = cli.skip_tokens;
+    }
+    validation_config.format_fallback = cli.format_fallback;
+    validation_config.type_ref_paths = cli.type_ref_paths;
+    validation_config.instance_ref_paths = cli.instance_ref_paths;
+    validation_config.context_lines = cli.context;
+    validation_config.rule_overrides = cli.rule_overrides.into_iter().collect();
+    validation_config.min_confidence = cli.min_confidence;
+    validation_config.collect_vendors = cli.list_vendors;
+    validation_config.fail_fast = cli.fail_fast;
+    validation_config.max_errors = cli
+        .max_errors
+        .map(|max| usize::try_from(max).unwrap_or(usize::MAX));
+    if cli.no_example_heuristics {
+        validation_config.invalid_examples = InvalidExamplePolicy::MarkersOnly;
+    }
 
-    validation_config.vendor_policy = match cli.vendor {
-        Some(vendor) => VendorPolicy::MustMatch(vendor),
-        None => VendorPolicy::Any,
+    if let Some(path) = &cli.reservations {
+        match gts::load_reservations(path) {
+            Ok(reservations) => validation_config.reservations = reservations,
+            Err(error) => {
+                eprintln!("Error reading reservations: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+        validation_config.reservation_owner = cli.owner;
+    }
+
+    if let Some(path) = &cli.baseline {
+        let baseline = std::fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Baseline::from_report_json(&json));
+        match baseline {
+            Ok(baseline) => validation_config.baseline = Some(baseline),
+            Err(error) => {
+                eprintln!("Error reading baseline {}: {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let mut filter = ReportFilter::new();
+    for code in cli.only_codes {
+        filter = filter.code(code);
+    }
+    for pattern in &cli.only_paths {
+        filter = match filter.path(pattern) {
+            Ok(filter) => filter,
+            Err(error) => {
+                eprintln!("Invalid --only-path pattern '{pattern}': {error}");
+                return ExitCode::FAILURE;
+            }
+        };
+    }
+
+    let catalog = match &cli.messages {
+        Some(path) => match std::fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| MessageCatalog::from_yaml(&text))
+        {
+            Ok(catalog) => catalog,
+            Err(error) => {
+                eprintln!("Error reading messages {}: {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        None => MessageCatalog::english(),
     };
 
+    if let Some(vendor) = cli.vendor {
+        validation_config.vendor_policy = VendorPolicy::MustMatch(vendor);
+    }
+
     if cli.verbose {
         let path_list: Vec<String> = fs_config
             .paths
@@ -114,7 +330,11 @@ fn main() -> ExitCode {
         }
     }
 
-    let report = match gts_validator::validate_fs(&fs_config, &validation_config) {
+    let validate = || {
+        gts_validator::validate_fs(&fs_config, &validation_config)
+            .map(|report| report.filtered(&filter))
+    };
+    let mut report = match validate() {
         Ok(report) => report,
         Err(error) => {
             eprintln!("Error: {error}");
@@ -122,15 +342,37 @@ fn main() -> ExitCode {
         }
     };
 
+    if cli.fix {
+        match run_fix(&report, cli.dry_run, &validate) {
+            Ok(Some(fixed)) => report = fixed,
+            Ok(None) if cli.dry_run => return exit_status(&report),
+            Ok(None) => {}
+            Err(error) => {
+                eprintln!("Error: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
     if cli.verbose {
-        eprintln!("Scanned {} files", report.scanned_files);
+        eprintln!(
+            "Scanned {} files ({} from cache)",
+            report.scanned_files, report.cached_files
+        );
     }
 
     let mut stdout = std::io::stdout();
     let result = if cli.json {
         output::write_json(&report, &mut stdout)
     } else {
-        output::write_human(&report, &mut stdout)
+        let mode = if cli.quiet {
+            HumanOutputMode::Quiet
+        } else if cli.summary_only {
+            HumanOutputMode::SummaryOnly
+        } else {
+            HumanOutputMode::Full
+        };
+        output::write_human_localized(&report, &mut stdout, mode, &catalog)
     };
 
     if let Err(error) = result {
@@ -138,9 +380,64 @@ fn main() -> ExitCode {
         return ExitCode::FAILURE;
     }
 
+    exit_status(&report)
+}
+
+/// The PATH arguments, else `paths` of the config file, else the default
+/// directories that exist.
+fn scan_paths(args: Vec<PathBuf>, configured: Vec<PathBuf>) -> Vec<PathBuf> {
+    if !args.is_empty() {
+        args
+    } else if !configured.is_empty() {
+        configured
+    } else {
+        DEFAULT_SCAN_DIRS
+            .iter()
+            .map(PathBuf::from)
+            .filter(|path| path.exists())
+            .collect()
+    }
+}
+
+fn exit_status(report: &ValidationReport) -> ExitCode {
     if report.ok {
         ExitCode::SUCCESS
     } else {
         ExitCode::FAILURE
     }
 }
+
+/// `--fix`: write the fixes of `report` and validate again, or with
+/// `--dry-run` print them as a diff. The new report, if files were written.
+fn run_fix(
+    report: &ValidationReport,
+    dry_run: bool,
+    validate: &dyn Fn() -> anyhow::Result<ValidationReport>,
+) -> anyhow::Result<Option<ValidationReport>> {
+    let mode = if dry_run {
+        FixMode::DryRun
+    } else {
+        FixMode::Write
+    };
+    let fixes = gts_validator::apply_fixes(report, mode)?;
+    if dry_run {
+        print!("{}", fixes.diff());
+        eprintln!(
+            "{} fix(es) in {} file(s) not written (--dry-run), {} finding(s) without a fix",
+            fixes.fixes_count(),
+            fixes.files.len(),
+            fixes.unfixed
+        );
+        return Ok(None);
+    }
+    eprintln!(
+        "Fixed {} identifier(s) in {} file(s), {} finding(s) without a fix",
+        fixes.fixes_count(),
+        fixes.files.len(),
+        fixes.unfixed
+    );
+    if fixes.files.is_empty() {
+        return Ok(None);
+    }
+    validate().map(Some)
+}
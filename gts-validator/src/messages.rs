@@ -0,0 +1,236 @@
+//! Message catalog for the human-readable output.
+//!
+//! Every banner, summary line, error class and fix hint of
+//! [`output::write_human`](crate::output::write_human) is looked up by key in a
+//! [`MessageCatalog`], so the plain-text report can be translated or reworded.
+//! Class, hint and explanation keys are the stable error codes of
+//! [`ValidationError::error_code`](crate::ValidationError::error_code):
+//!
+//! ```yaml
+//! section.errors: 検証エラー
+//! hint.wildcard: ワイルドカード (*) はフィルター/パターンの中でのみ使用できます
+//! explain.reserved_name: この名前は予約済みです
+//! ```
+//!
+//! Keys a catalog leaves out fall back to English. Messages may use the
+//! `{placeholders}` of their English version. JSON output and the finding
+//! lines themselves (`file:line:col: error [raw]`) are not localized.
+
+use std::collections::BTreeMap;
+
+/// English messages, the fallback for every key.
+const ENGLISH: &[(&str, &str)] = &[
+    ("banner.title", "GTS DOCUMENTATION VALIDATOR"),
+    ("totals.files_scanned", "Files scanned:  {count}"),
+    ("totals.files_failed", "Files failed:   {count}"),
+    ("totals.errors_found", "Errors found:   {count}"),
+    ("totals.warnings", "  warnings:     {count}"),
+    ("totals.info", "  info:         {count}"),
+    ("totals.new", "  new:          {count}"),
+    ("totals.baselined", "  baselined:    {count}"),
+    ("paths.title", "By path:"),
+    ("paths.path", "path"),
+    ("paths.scanned", "scanned"),
+    ("paths.failed", "failed"),
+    ("paths.errors", "errors"),
+    ("vendors.title", "Vendors seen:"),
+    ("vendors.vendor", "vendor"),
+    ("vendors.occurrences", "occurrences"),
+    ("vendors.first_seen", "first seen"),
+    ("examples.title", "Invalid examples skipped:"),
+    (
+        "examples.acknowledged",
+        "acknowledged (marker/tag): {count}",
+    ),
+    ("examples.heuristic", "heuristic (emoji/keyword): {count}"),
+    (
+        "suppressed.count",
+        "Suppressed by gts-ignore directives: {count}",
+    ),
+    ("fallbacks.title", "Parsed with fallback format:"),
+    (
+        "fallbacks.entry",
+        "{file}: {parsed_as} (expected {expected})",
+    ),
+    (
+        "section.scan_errors",
+        "SCAN ERRORS (files that could not be validated)",
+    ),
+    ("section.errors", "VALIDATION ERRORS"),
+    ("section.new", "NEW SINCE BASELINE ({count})"),
+    ("section.baselined", "PRE-EXISTING (BASELINED) ({count})"),
+    (
+        "result.passed",
+        "\u{2713} All {count} files passed validation",
+    ),
+    (
+        "result.baselined_remain",
+        "({count} baselined finding(s) remain)",
+    ),
+    (
+        "result.warnings_remain",
+        "({count} warning/info finding(s) do not fail the run)",
+    ),
+    (
+        "result.scan_failed",
+        "\u{2717} {count} file(s) could not be scanned \u{2014} CI must treat this as a failure",
+    ),
+    (
+        "result.invalid",
+        "\u{2717} {count} invalid GTS identifier(s) found",
+    ),
+    (
+        "result.new_invalid",
+        "\u{2717} {count} new invalid GTS identifier(s) found",
+    ),
+    (
+        "result.truncated",
+        "(stopped at the error limit; later files and findings were not checked)",
+    ),
+    ("hints.title", "To fix:"),
+    (
+        "hint.invalid_id",
+        "Schema IDs must end with ~ (e.g., gts.x.core.type.v1~)\n\
+         Each segment needs 5 parts: vendor.package.namespace.type.version\n\
+         No hyphens allowed, use underscores",
+    ),
+    (
+        "hint.wildcard",
+        "Wildcards (*) only in filter/pattern contexts",
+    ),
+    (
+        "hint.vendor_mismatch",
+        "Ensure all GTS IDs use the expected vendor",
+    ),
+    (
+        "hint.expected_type_id",
+        "Type references need type IDs (ending with ~), instance references instance IDs",
+    ),
+    (
+        "hint.expected_instance_id",
+        "Type references need type IDs (ending with ~), instance references instance IDs",
+    ),
+    (
+        "hint.reserved_name",
+        "Define entities under your own vendor/package; reserved names belong to their owners",
+    ),
+    ("class.invalid_id", "invalid id"),
+    ("class.wildcard", "wildcard"),
+    ("class.vendor_mismatch", "vendor mismatch"),
+    ("class.expected_type_id", "id form"),
+    ("class.expected_instance_id", "id form"),
+    ("class.reserved_name", "reserved name"),
+    ("class.scan_error", "scan error ({kind})"),
+    ("explain.invalid_id", ""),
+    ("explain.wildcard", ""),
+    ("explain.vendor_mismatch", ""),
+    ("explain.expected_type_id", ""),
+    ("explain.expected_instance_id", ""),
+    ("explain.reserved_name", ""),
+    (
+        "summary.totals",
+        "GTS validation: {scanned} file(s) scanned, {failed} failed, {errors} error(s)",
+    ),
+    (
+        "summary.baseline",
+        "New since baseline: {new}, baselined: {baselined}",
+    ),
+    ("summary.severity", "Warnings: {warnings}, info: {info}"),
+    ("summary.by_class", "By class:"),
+    ("summary.by_file", "By file:"),
+    ("summary.by_vendor", "By vendor:"),
+];
+
+/// Human-facing messages keyed by message ID, with English fallback.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageCatalog {
+    overrides: BTreeMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// The English catalog.
+    #[must_use]
+    pub fn english() -> Self {
+        Self::default()
+    }
+
+    /// Keys of every message, with their English text.
+    pub fn keys() -> impl Iterator<Item = (&'static str, &'static str)> {
+        ENGLISH.iter().copied()
+    }
+
+    /// Parse a catalog from a flat YAML (or JSON) map of key to message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is not such a map or names an unknown key.
+    pub fn from_yaml(text: &str) -> anyhow::Result<Self> {
+        let overrides: BTreeMap<String, String> = serde_saphyr::from_str(text)?;
+        if let Some(unknown) = overrides
+            .keys()
+            .find(|key| !ENGLISH.iter().any(|(k, _)| k == key))
+        {
+            anyhow::bail!("Unknown message key '{unknown}'");
+        }
+        Ok(Self { overrides })
+    }
+
+    /// Override the message for `key` (unknown keys are never looked up).
+    pub fn set(&mut self, key: &str, message: &str) {
+        self.overrides.insert(key.to_owned(), message.to_owned());
+    }
+
+    /// The message for `key`, with `{name}` placeholders replaced by `args`.
+    #[must_use]
+    pub fn format(&self, key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+        args.iter()
+            .fold(self.text(key).to_owned(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), &value.to_string())
+            })
+    }
+
+    /// The raw message for `key`; empty for unknown keys.
+    #[must_use]
+    pub fn text(&self, key: &str) -> &str {
+        self.overrides.get(key).map_or_else(
+            || {
+                ENGLISH
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map_or("", |(_, text)| text)
+            },
+            String::as_str,
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_falls_back_to_english() {
+        let catalog = MessageCatalog::from_yaml(
+            "section.errors: ERREURS DE VALIDATION\nresult.passed: '{count} fichier(s) OK'\n",
+        )
+        .unwrap();
+        assert_eq!(catalog.text("section.errors"), "ERREURS DE VALIDATION");
+        assert_eq!(
+            catalog.format("result.passed", &[("count", &3)]),
+            "3 fichier(s) OK"
+        );
+        assert_eq!(catalog.text("hints.title"), "To fix:");
+        assert_eq!(
+            MessageCatalog::english().format("summary.baseline", &[("new", &1), ("baselined", &2)]),
+            "New since baseline: 1, baselined: 2"
+        );
+    }
+
+    #[test]
+    fn test_catalog_rejects_unknown_keys() {
+        let err = MessageCatalog::from_yaml("section.eror: x\n").unwrap_err();
+        assert!(err.to_string().contains("section.eror"), "{err}");
+        assert!(MessageCatalog::from_yaml("- a\n- b\n").is_err());
+    }
+}
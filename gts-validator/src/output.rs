@@ -1,12 +1,35 @@
 //! Shared output formatting for validation reports.
 //!
-//! Provides JSON and plain-text formatters for `ValidationReport`.
+//! Provides JSON, SARIF and plain-text formatters for `ValidationReport`,
+//! and reads JSON reports back (`read_json`).
+//! Plain text comes in three flavors (see `HumanOutputMode`): the full
+//! report, errors only, and counts only. Its wording comes from a
+//! [`MessageCatalog`] (English unless another catalog is passed).
 //! Color/terminal formatting is intentionally excluded from this core module —
 //! that concern belongs to the CLI layer.
 
-use std::io::Write;
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
 
-use crate::report::ValidationReport;
+use serde_json::{Value, json};
+
+use crate::error::{ERROR_CODES, ValidationError};
+use crate::messages::MessageCatalog;
+use crate::report::{Severity, ValidationReport};
+
+/// How much detail `write_human_with_mode` prints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HumanOutputMode {
+    /// Banner, totals, every finding, and fix hints.
+    #[default]
+    Full,
+    /// One line per finding and nothing else (no banner, silent on success).
+    /// Baselined findings are left out.
+    Quiet,
+    /// Totals plus counts per error class and per file, no individual findings.
+    SummaryOnly,
+}
 
 /// Format a `ValidationReport` as JSON to a writer.
 ///
@@ -19,6 +42,110 @@ pub fn write_json(report: &ValidationReport, writer: &mut dyn Write) -> anyhow::
     Ok(())
 }
 
+/// Read a `ValidationReport` written by [`write_json`], e.g. to
+/// [`merge`](ValidationReport::merge) the reports of sharded runs.
+///
+/// # Errors
+///
+/// Returns an error if reading fails or the input is not a JSON report.
+pub fn read_json(reader: &mut dyn Read) -> anyhow::Result<ValidationReport> {
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// Format a `ValidationReport` as a SARIF 2.1.0 log to a writer, for code
+/// scanning tools.
+///
+/// Each validation error is a result whose rule is its error code, with the
+/// finding's `fingerprint` as partial fingerprint; when a baseline was applied,
+/// `baselineState` is `unchanged` for baselined findings and `new` otherwise.
+/// The confidence of heuristic findings is their `rank`.
+/// Scan errors are reported as tool execution notifications.
+///
+/// # Errors
+///
+/// Returns an error if serialization or writing fails.
+pub fn write_sarif(report: &ValidationReport, writer: &mut dyn Write) -> anyhow::Result<()> {
+    let rules: Vec<Value> = ERROR_CODES
+        .iter()
+        .map(|code| json!({ "id": code }))
+        .collect();
+    let results: Vec<Value> = report
+        .validation_errors
+        .iter()
+        .map(|error| {
+            let mut region = serde_json::Map::new();
+            if error.line > 0 {
+                region.insert("startLine".to_owned(), json!(error.line));
+            }
+            if error.column > 0 {
+                region.insert("startColumn".to_owned(), json!(error.column));
+            }
+            let mut location = json!({ "artifactLocation": { "uri": sarif_uri(&error.file) } });
+            if !region.is_empty() {
+                location["region"] = Value::Object(region);
+            }
+            let level = match error.severity {
+                Severity::Warning => "warning",
+                Severity::Info => "note",
+                Severity::Error | Severity::Fatal => "error",
+            };
+            let mut result = json!({
+                "ruleId": error.error_code(),
+                "level": level,
+                "message": { "text": format!("{}: {}", error.raw_value, error.error) },
+                "locations": [{ "physicalLocation": location }],
+                "partialFingerprints": { "gtsFingerprint/v1": error.fingerprint },
+            });
+            if let Some(confidence) = error.confidence {
+                result["rank"] = json!(confidence);
+            }
+            if report.baseline_applied {
+                result["baselineState"] = json!(if error.baselined { "unchanged" } else { "new" });
+            }
+            result
+        })
+        .collect();
+    let notifications: Vec<Value> = report
+        .scan_errors
+        .iter()
+        .map(|scan_err| {
+            json!({
+                "level": "error",
+                "message": { "text": format!("{:?}: {}", scan_err.kind, scan_err.message) },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": sarif_uri(&scan_err.file) } }
+                }],
+            })
+        })
+        .collect();
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "gts-validator",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "invocations": [{
+                "executionSuccessful": report.scan_errors.is_empty(),
+                "toolExecutionNotifications": notifications,
+            }],
+            "results": results,
+        }]
+    });
+    writeln!(writer, "{}", serde_json::to_string_pretty(&log)?)?;
+    Ok(())
+}
+
+/// Relative SARIF URI of `file`: forward slashes, no leading `./`.
+fn sarif_uri(file: &std::path::Path) -> String {
+    let uri = file.to_string_lossy().replace('\\', "/");
+    uri.trim_start_matches("./").to_owned()
+}
+
 /// Format a `ValidationReport` as human-readable plain text to a writer.
 ///
 /// Color/ANSI formatting is the responsibility of the caller (CLI layer).
@@ -27,19 +154,454 @@ pub fn write_json(report: &ValidationReport, writer: &mut dyn Write) -> anyhow::
 ///
 /// Returns an error if writing fails.
 pub fn write_human(report: &ValidationReport, writer: &mut dyn Write) -> anyhow::Result<()> {
+    write_human_with_mode(report, writer, HumanOutputMode::Full)
+}
+
+/// Format a `ValidationReport` as plain text with the given level of detail.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+pub fn write_human_with_mode(
+    report: &ValidationReport,
+    writer: &mut dyn Write,
+    mode: HumanOutputMode,
+) -> anyhow::Result<()> {
+    write_human_localized(report, writer, mode, &MessageCatalog::english())
+}
+
+/// Format a `ValidationReport` as plain text worded by `catalog`.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+pub fn write_human_localized(
+    report: &ValidationReport,
+    writer: &mut dyn Write,
+    mode: HumanOutputMode,
+    catalog: &MessageCatalog,
+) -> anyhow::Result<()> {
+    match mode {
+        HumanOutputMode::Full => write_full(report, writer, catalog),
+        HumanOutputMode::Quiet => write_quiet(report, writer),
+        HumanOutputMode::SummaryOnly => write_summary(report, writer, catalog),
+    }
+}
+
+fn write_quiet(report: &ValidationReport, writer: &mut dyn Write) -> anyhow::Result<()> {
+    for scan_err in &report.scan_errors {
+        writeln!(writer, "{}", scan_err.format_human_readable())?;
+    }
+    for error in report.validation_errors.iter().filter(|e| !e.baselined) {
+        writeln!(writer, "{}", error.format_human_readable())?;
+    }
+    Ok(())
+}
+
+fn write_summary(
+    report: &ValidationReport,
+    writer: &mut dyn Write,
+    catalog: &MessageCatalog,
+) -> anyhow::Result<()> {
+    writeln!(
+        writer,
+        "{}",
+        catalog.format(
+            "summary.totals",
+            &[
+                ("scanned", &report.scanned_files),
+                ("failed", &report.failed_files),
+                ("errors", &report.errors_count()),
+            ]
+        )
+    )?;
+    if report.baseline_applied {
+        writeln!(
+            writer,
+            "  {}",
+            catalog.format(
+                "summary.baseline",
+                &[
+                    ("new", &report.new_errors_count()),
+                    ("baselined", &report.baselined_errors_count()),
+                ]
+            )
+        )?;
+    }
+
+    let warnings = report.severity_count(Severity::Warning);
+    let infos = report.severity_count(Severity::Info);
+    if warnings > 0 || infos > 0 {
+        writeln!(
+            writer,
+            "  {}",
+            catalog.format(
+                "summary.severity",
+                &[("warnings", &warnings), ("info", &infos)]
+            )
+        )?;
+    }
+    if report.truncated {
+        writeln!(writer, "  {}", catalog.text("result.truncated"))?;
+    }
+    write_path_breakdown(report, writer, catalog)?;
+    write_vendor_table(report, writer, catalog)?;
+
+    let mut by_class: BTreeMap<String, usize> = BTreeMap::new();
+    let mut by_file: BTreeMap<String, usize> = BTreeMap::new();
+    for scan_err in &report.scan_errors {
+        let kind = format!("{:?}", scan_err.kind);
+        *by_class
+            .entry(catalog.format("class.scan_error", &[("kind", &kind)]))
+            .or_default() += 1;
+        *by_file
+            .entry(scan_err.file.display().to_string())
+            .or_default() += 1;
+    }
+    for error in &report.validation_errors {
+        *by_class
+            .entry(error_class(error, catalog).to_owned())
+            .or_default() += 1;
+        *by_file.entry(error.file.display().to_string()).or_default() += 1;
+    }
+
+    write_example_counts(report, writer, catalog)?;
+    write_suppressed_count(report, writer, catalog)?;
+    write_format_fallbacks(report, writer, catalog)?;
+    if !by_class.is_empty() {
+        writeln!(writer, "  {}", catalog.text("summary.by_class"))?;
+        for (class, count) in &by_class {
+            writeln!(writer, "    {class}: {count}")?;
+        }
+        writeln!(writer, "  {}", catalog.text("summary.by_file"))?;
+        for (file, count) in &by_file {
+            writeln!(writer, "    {file}: {count}")?;
+        }
+        if !report.findings_by_vendor.is_empty() {
+            writeln!(writer, "  {}", catalog.text("summary.by_vendor"))?;
+            for (vendor, count) in &report.findings_by_vendor {
+                writeln!(writer, "    {vendor}: {count}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Per-path totals as a table, when several paths were validated.
+fn write_path_breakdown(
+    report: &ValidationReport,
+    writer: &mut dyn Write,
+    catalog: &MessageCatalog,
+) -> anyhow::Result<()> {
+    if report.paths.is_empty() {
+        return Ok(());
+    }
+    let header = [
+        catalog.text("paths.path").to_owned(),
+        catalog.text("paths.scanned").to_owned(),
+        catalog.text("paths.failed").to_owned(),
+        catalog.text("paths.errors").to_owned(),
+    ];
+    let rows: Vec<[String; 4]> = report
+        .paths
+        .iter()
+        .map(|summary| {
+            [
+                summary.path.display().to_string(),
+                summary.scanned_files.to_string(),
+                summary.failed_files.to_string(),
+                summary.errors.to_string(),
+            ]
+        })
+        .collect();
+    let mut widths = header.clone().map(|cell| cell.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    writeln!(writer, "  {}", catalog.text("paths.title"))?;
+    writeln!(
+        writer,
+        "    {:<w0$}  {:>w1$}  {:>w2$}  {:>w3$}",
+        header[0],
+        header[1],
+        header[2],
+        header[3],
+        w0 = widths[0],
+        w1 = widths[1],
+        w2 = widths[2],
+        w3 = widths[3],
+    )?;
+    for (row, summary) in rows.iter().zip(&report.paths) {
+        let status = if summary.ok { '\u{2713}' } else { '\u{2717}' };
+        writeln!(
+            writer,
+            "    {:<w0$}  {:>w1$}  {:>w2$}  {:>w3$}  {status}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        )?;
+    }
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Vendors of the scanned IDs as a table, when they were collected.
+fn write_vendor_table(
+    report: &ValidationReport,
+    writer: &mut dyn Write,
+    catalog: &MessageCatalog,
+) -> anyhow::Result<()> {
+    if report.vendors.is_empty() {
+        return Ok(());
+    }
+    let header = [
+        catalog.text("vendors.vendor").to_owned(),
+        catalog.text("vendors.occurrences").to_owned(),
+        catalog.text("vendors.first_seen").to_owned(),
+    ];
+    let rows: Vec<[String; 3]> = report
+        .vendors
+        .iter()
+        .map(|summary| {
+            [
+                summary.vendor.clone(),
+                summary.occurrences.to_string(),
+                format!(
+                    "{}:{}:{}",
+                    summary.file.display(),
+                    summary.line,
+                    summary.column
+                ),
+            ]
+        })
+        .collect();
+    let mut widths = header.clone().map(|cell| cell.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    writeln!(writer, "  {}", catalog.text("vendors.title"))?;
+    for row in std::iter::once(&header).chain(&rows) {
+        writeln!(
+            writer,
+            "    {:<w0$}  {:>w1$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            w0 = widths[0],
+            w1 = widths[1],
+        )?;
+    }
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Invalid-example skips are reported so heuristics never swallow findings silently.
+fn write_example_counts(
+    report: &ValidationReport,
+    writer: &mut dyn Write,
+    catalog: &MessageCatalog,
+) -> anyhow::Result<()> {
+    if report.acknowledged_invalid_examples == 0 && report.heuristic_example_skips == 0 {
+        return Ok(());
+    }
+    writeln!(writer, "  {}", catalog.text("examples.title"))?;
+    writeln!(
+        writer,
+        "    {}",
+        catalog.format(
+            "examples.acknowledged",
+            &[("count", &report.acknowledged_invalid_examples)]
+        )
+    )?;
+    writeln!(
+        writer,
+        "    {}",
+        catalog.format(
+            "examples.heuristic",
+            &[("count", &report.heuristic_example_skips)]
+        )
+    )?;
+    Ok(())
+}
+
+/// Findings silenced by `gts-ignore` directives, so they stay visible as a count.
+fn write_suppressed_count(
+    report: &ValidationReport,
+    writer: &mut dyn Write,
+    catalog: &MessageCatalog,
+) -> anyhow::Result<()> {
+    if report.suppressed_findings == 0 {
+        return Ok(());
+    }
+    writeln!(
+        writer,
+        "  {}",
+        catalog.format(
+            "suppressed.count",
+            &[("count", &report.suppressed_findings)]
+        )
+    )?;
+    Ok(())
+}
+
+/// Files scanned with a parser other than the one their extension implies.
+fn write_format_fallbacks(
+    report: &ValidationReport,
+    writer: &mut dyn Write,
+    catalog: &MessageCatalog,
+) -> anyhow::Result<()> {
+    if report.format_fallbacks.is_empty() {
+        return Ok(());
+    }
+    writeln!(writer, "  {}", catalog.text("fallbacks.title"))?;
+    for fallback in &report.format_fallbacks {
+        writeln!(
+            writer,
+            "    {}",
+            catalog.format(
+                "fallbacks.entry",
+                &[
+                    ("file", &fallback.file.display()),
+                    ("parsed_as", &fallback.parsed_as.as_str()),
+                    ("expected", &fallback.expected.as_str()),
+                ]
+            )
+        )?;
+    }
+    Ok(())
+}
+
+/// Print a titled block of findings; nothing if there are none.
+fn write_error_section<'a>(
+    writer: &mut dyn Write,
+    title: &str,
+    errors: impl Iterator<Item = &'a ValidationError>,
+    catalog: &MessageCatalog,
+) -> anyhow::Result<()> {
+    let mut errors = errors.peekable();
+    if errors.peek().is_none() {
+        return Ok(());
+    }
+    writeln!(writer, "{}", "-".repeat(80))?;
+    writeln!(writer, "  {title}")?;
+    writeln!(writer, "{}", "-".repeat(80))?;
+    for error in errors {
+        writeln!(writer, "{}", error.format_with_context())?;
+        let explanation = catalog.text(&format!("explain.{}", error.error_code()));
+        if !explanation.is_empty() {
+            writeln!(writer, "  = {explanation}")?;
+        }
+    }
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Coarse error class used for summaries.
+fn error_class<'a>(error: &ValidationError, catalog: &'a MessageCatalog) -> &'a str {
+    catalog.text(&format!("class.{}", error.error_code()))
+}
+
+/// Error codes in the order their fix hints are printed.
+const HINT_ORDER: &[&str] = &[
+    "invalid_id",
+    "wildcard",
+    "vendor_mismatch",
+    "expected_type_id",
+    "expected_instance_id",
+    "reserved_name",
+];
+
+fn write_hints(
+    report: &ValidationReport,
+    writer: &mut dyn Write,
+    catalog: &MessageCatalog,
+) -> anyhow::Result<()> {
+    writeln!(writer, "  {}", catalog.text("hints.title"))?;
+    let mut printed: Vec<&str> = Vec::new();
+    for code in HINT_ORDER {
+        if !report
+            .validation_errors
+            .iter()
+            .any(|e| !e.baselined && e.error_code() == *code)
+        {
+            continue;
+        }
+        for line in catalog.text(&format!("hint.{code}")).lines() {
+            // Codes sharing a hint print it once
+            if !line.is_empty() && !printed.contains(&line) {
+                writeln!(writer, "    - {line}")?;
+                printed.push(line);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_full(
+    report: &ValidationReport,
+    writer: &mut dyn Write,
+    catalog: &MessageCatalog,
+) -> anyhow::Result<()> {
+    let count = |key: &str, count: usize| catalog.format(key, &[("count", &count)]);
     writeln!(writer)?;
     writeln!(writer, "{}", "=".repeat(80))?;
-    writeln!(writer, "  GTS DOCUMENTATION VALIDATOR")?;
+    writeln!(writer, "  {}", catalog.text("banner.title"))?;
     writeln!(writer, "{}", "=".repeat(80))?;
     writeln!(writer)?;
-    writeln!(writer, "  Files scanned:  {}", report.scanned_files)?;
-    writeln!(writer, "  Files failed:   {}", report.failed_files)?;
-    writeln!(writer, "  Errors found:   {}", report.errors_count())?;
+    writeln!(
+        writer,
+        "  {}",
+        count("totals.files_scanned", report.scanned_files)
+    )?;
+    writeln!(
+        writer,
+        "  {}",
+        count("totals.files_failed", report.failed_files)
+    )?;
+    writeln!(
+        writer,
+        "  {}",
+        count("totals.errors_found", report.errors_count())
+    )?;
+    let warnings = report.severity_count(Severity::Warning);
+    let infos = report.severity_count(Severity::Info);
+    if warnings > 0 || infos > 0 {
+        writeln!(writer, "  {}", count("totals.warnings", warnings))?;
+        writeln!(writer, "  {}", count("totals.info", infos))?;
+    }
+    if report.baseline_applied {
+        writeln!(
+            writer,
+            "  {}",
+            count("totals.new", report.new_errors_count())
+        )?;
+        writeln!(
+            writer,
+            "  {}",
+            count("totals.baselined", report.baselined_errors_count())
+        )?;
+    }
     writeln!(writer)?;
+    write_path_breakdown(report, writer, catalog)?;
+    write_vendor_table(report, writer, catalog)?;
+    write_example_counts(report, writer, catalog)?;
+    write_suppressed_count(report, writer, catalog)?;
+    write_format_fallbacks(report, writer, catalog)?;
 
     if !report.scan_errors.is_empty() {
         writeln!(writer, "{}", "-".repeat(80))?;
-        writeln!(writer, "  SCAN ERRORS (files that could not be validated)")?;
+        writeln!(writer, "  {}", catalog.text("section.scan_errors"))?;
         writeln!(writer, "{}", "-".repeat(80))?;
         for scan_err in &report.scan_errors {
             writeln!(writer, "{}", scan_err.format_human_readable())?;
@@ -47,73 +609,65 @@ pub fn write_human(report: &ValidationReport, writer: &mut dyn Write) -> anyhow:
         writeln!(writer)?;
     }
 
-    if !report.validation_errors.is_empty() {
-        writeln!(writer, "{}", "-".repeat(80))?;
-        writeln!(writer, "  VALIDATION ERRORS")?;
-        writeln!(writer, "{}", "-".repeat(80))?;
-        for error in &report.validation_errors {
-            writeln!(writer, "{}", error.format_human_readable())?;
-        }
-        writeln!(writer)?;
+    if report.baseline_applied {
+        write_error_section(
+            writer,
+            &count("section.new", report.new_errors_count()),
+            report.validation_errors.iter().filter(|e| !e.baselined),
+            catalog,
+        )?;
+        write_error_section(
+            writer,
+            &count("section.baselined", report.baselined_errors_count()),
+            report.validation_errors.iter().filter(|e| e.baselined),
+            catalog,
+        )?;
+    } else {
+        write_error_section(
+            writer,
+            catalog.text("section.errors"),
+            report.validation_errors.iter(),
+            catalog,
+        )?;
     }
 
     writeln!(writer, "{}", "=".repeat(80))?;
     if report.ok {
-        writeln!(
-            writer,
-            "\u{2713} All {} files passed validation",
-            report.scanned_files
-        )?;
-    } else {
-        if !report.scan_errors.is_empty() {
+        writeln!(writer, "{}", count("result.passed", report.scanned_files))?;
+        if report.baselined_errors_count() > 0 {
             writeln!(
                 writer,
-                "\u{2717} {} file(s) could not be scanned \u{2014} CI must treat this as a failure",
-                report.failed_files
+                "  {}",
+                count("result.baselined_remain", report.baselined_errors_count())
             )?;
         }
-        if !report.validation_errors.is_empty() {
+        if warnings + infos > 0 {
             writeln!(
                 writer,
-                "\u{2717} {} invalid GTS identifier(s) found",
-                report.errors_count()
+                "  {}",
+                count("result.warnings_remain", warnings + infos)
             )?;
-            writeln!(writer)?;
-            writeln!(writer, "  To fix:")?;
-
-            let has_vendor_mismatch = report
-                .validation_errors
-                .iter()
-                .any(|e| e.error.contains("Vendor mismatch"));
-            let has_wildcard_error = report
-                .validation_errors
-                .iter()
-                .any(|e| e.error.contains("Wildcard"));
-            let has_parse_error = report
-                .validation_errors
-                .iter()
-                .any(|e| !e.error.contains("Vendor mismatch") && !e.error.contains("Wildcard"));
-
-            if has_parse_error {
-                writeln!(
-                    writer,
-                    "    - Schema IDs must end with ~ (e.g., gts.x.core.type.v1~)"
-                )?;
-                writeln!(
-                    writer,
-                    "    - Each segment needs 5 parts: vendor.package.namespace.type.version"
-                )?;
-                writeln!(writer, "    - No hyphens allowed, use underscores")?;
-            }
-            if has_wildcard_error {
-                writeln!(
-                    writer,
-                    "    - Wildcards (*) only in filter/pattern contexts"
-                )?;
-            }
-            if has_vendor_mismatch {
-                writeln!(writer, "    - Ensure all GTS IDs use the expected vendor")?;
+        }
+    } else {
+        if !report.scan_errors.is_empty() {
+            writeln!(
+                writer,
+                "{}",
+                count("result.scan_failed", report.failed_files)
+            )?;
+        }
+        if report.failing_errors_count() > 0 {
+            let key = if report.baseline_applied {
+                "result.new_invalid"
+            } else {
+                "result.invalid"
+            };
+            writeln!(writer, "{}", count(key, report.failing_errors_count()))?;
+            if report.truncated {
+                writeln!(writer, "  {}", catalog.text("result.truncated"))?;
             }
+            writeln!(writer)?;
+            write_hints(report, writer, catalog)?;
         }
     }
     writeln!(writer, "{}", "=".repeat(80))?;
@@ -0,0 +1,26 @@
+//! Progress of a filesystem validation run.
+
+use std::path::Path;
+
+/// Stage of a [`validate_fs_with_progress`](crate::validate_fs_with_progress) run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ScanPhase {
+    /// Files were discovered; `files_total` is known from now on.
+    Discover,
+    /// `file` is about to be scanned.
+    Scan,
+    /// The report is complete.
+    Done,
+}
+
+/// A progress update, passed to the progress callback.
+#[derive(Debug, Clone, Copy)]
+pub struct ScanProgress<'a> {
+    pub phase: ScanPhase,
+    /// The file being scanned (`Scan` only)
+    pub file: Option<&'a Path>,
+    /// Files scanned, taken from the cache or failed so far
+    pub files_done: usize,
+    pub files_total: usize,
+}
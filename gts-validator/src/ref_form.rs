@@ -0,0 +1,284 @@
+//! Type-vs-instance form of IDs in designated JSON/YAML fields.
+//!
+//! Some fields only make sense with one form of identifier: `x-gts-ref` or an
+//! `extends` list name types (`gts.x.core.events.type.v1~`), while a field
+//! pointing at a concrete entity needs an instance ID (no trailing `~`).
+//! [`ValidationConfig::type_ref_paths`](crate::ValidationConfig::type_ref_paths)
+//! and [`ValidationConfig::instance_ref_paths`](crate::ValidationConfig::instance_ref_paths)
+//! designate such fields with JSON path patterns:
+//!
+//! - `$.extends` — the `extends` key of the document root, or each element
+//!   if it is an array
+//! - `$.properties.*.x-gts-ref` — `*` matches any single key or index,
+//!   `[*]` any array index
+//! - `$..x-gts-ref` — `..` matches any depth; a pattern without a leading
+//!   `$` is shorthand for this form
+//!
+//! IDs that are otherwise invalid, and wildcard patterns, are left to the
+//! regular validation.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::candidate::{Candidate, Location};
+use crate::error::ValidationError;
+use crate::normalize::normalize_candidate;
+
+/// Error message prefix for an instance ID found where a type ID is required.
+pub const EXPECTED_TYPE_ID: &str = "Expected a type ID (ending with '~')";
+/// Error message prefix for a type ID found where an instance ID is required.
+pub const EXPECTED_INSTANCE_ID: &str = "Expected an instance ID (not ending with '~')";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    /// `*`: any key or index
+    Any,
+    /// `[*]`: any index
+    AnyIndex,
+    /// `..`: zero or more steps
+    Descend,
+}
+
+impl Step {
+    fn matches(&self, actual: &Self) -> bool {
+        match (self, actual) {
+            (Self::Key(a), Self::Key(b)) => a == b,
+            (Self::Index(a), Self::Index(b)) => a == b,
+            (Self::Any, Self::Key(_) | Self::Index(_)) | (Self::AnyIndex, Self::Index(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Split a path like `$.a..b[0].*` into steps. Concrete paths produced by the
+/// scanners use the same syntax, without wildcards.
+fn parse_steps(path: &str) -> Vec<Step> {
+    let mut steps = Vec::new();
+    let mut rest = path.strip_prefix('$').unwrap_or(path);
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            steps.push(Step::Descend);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('.') {
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').unwrap_or(after.len());
+            let inner = &after[..end];
+            steps.push(match inner {
+                "*" => Step::AnyIndex,
+                _ => inner
+                    .parse()
+                    .map_or_else(|_| Step::Key(inner.to_owned()), Step::Index),
+            });
+            rest = after.get(end + 1..).unwrap_or("");
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            let key = &rest[..end];
+            steps.push(if key == "*" {
+                Step::Any
+            } else {
+                Step::Key(key.to_owned())
+            });
+            rest = &rest[end..];
+        }
+    }
+    steps
+}
+
+fn steps_match(pattern: &[Step], path: &[Step]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((Step::Descend, rest)) => {
+            (0..=path.len()).any(|skip| steps_match(rest, &path[skip..]))
+        }
+        Some((step, rest)) => path
+            .split_first()
+            .is_some_and(|(actual, tail)| step.matches(actual) && steps_match(rest, tail)),
+    }
+}
+
+/// A compiled JSON path pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PathPattern(Vec<Step>);
+
+impl PathPattern {
+    fn new(pattern: &str) -> Self {
+        if pattern.starts_with('$') {
+            Self(parse_steps(pattern))
+        } else {
+            Self(parse_steps(&format!("$..{pattern}")))
+        }
+    }
+
+    /// Whether the pattern designates `path` or the array holding it.
+    fn covers(&self, path: &[Step]) -> bool {
+        let mut path = path;
+        loop {
+            if steps_match(&self.0, path) {
+                return true;
+            }
+            match path.split_last() {
+                Some((Step::Index(_), parent)) => path = parent,
+                _ => return false,
+            }
+        }
+    }
+}
+
+/// The form an ID must have at a designated path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IdForm {
+    Type,
+    Instance,
+}
+
+/// Compiled [`ValidationConfig`](crate::ValidationConfig) type/instance reference paths.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefFormRules {
+    type_refs: Vec<PathPattern>,
+    instance_refs: Vec<PathPattern>,
+}
+
+impl RefFormRules {
+    #[must_use]
+    pub fn new(type_refs: &[String], instance_refs: &[String]) -> Self {
+        Self {
+            type_refs: type_refs.iter().map(|p| PathPattern::new(p)).collect(),
+            instance_refs: instance_refs.iter().map(|p| PathPattern::new(p)).collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.type_refs.is_empty() && self.instance_refs.is_empty()
+    }
+
+    /// The form required at `json_path`; type references win if both match.
+    fn expected(&self, json_path: &str) -> Option<IdForm> {
+        let path = parse_steps(json_path);
+        if self.type_refs.iter().any(|p| p.covers(&path)) {
+            Some(IdForm::Type)
+        } else if self.instance_refs.iter().any(|p| p.covers(&path)) {
+            Some(IdForm::Instance)
+        } else {
+            None
+        }
+    }
+
+    /// Report IDs of the wrong form in `value` and below it.
+    pub fn check(
+        &self,
+        value: &Value,
+        file: &Path,
+        json_path: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if self.is_empty() {
+            return;
+        }
+        match value {
+            Value::String(s) => self.check_string(s, file, json_path, errors),
+            Value::Object(map) => {
+                for (key, val) in map {
+                    self.check(val, file, &format!("{json_path}.{key}"), errors);
+                }
+            }
+            Value::Array(arr) => {
+                for (i, val) in arr.iter().enumerate() {
+                    self.check(val, file, &format!("{json_path}[{i}]"), errors);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Report the string `text` at `json_path` if it has the wrong form.
+    pub fn check_string(
+        &self,
+        text: &str,
+        file: &Path,
+        json_path: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if let Some(form) = self.expected(json_path) {
+            errors.extend(check_form(text, form, file, json_path));
+        }
+    }
+}
+
+fn check_form(text: &str, form: IdForm, file: &Path, json_path: &str) -> Option<ValidationError> {
+    let normalized = normalize_candidate(text).ok()?;
+    if normalized.gts_id.contains('*') || gts::GtsID::new(&normalized.gts_id).is_err() {
+        return None;
+    }
+    let is_type = normalized.gts_id.ends_with('~');
+    let message = match form {
+        IdForm::Type if !is_type => EXPECTED_TYPE_ID,
+        IdForm::Instance if is_type => EXPECTED_INSTANCE_ID,
+        _ => return None,
+    };
+    let candidate = Candidate {
+        text,
+        location: Location::JsonPath(json_path.to_owned()),
+        context: json_path.to_owned(),
+        allow_wildcards: true,
+    };
+    Some(candidate.error(
+        file,
+        normalized.original,
+        normalized.gts_id.clone(),
+        format!("{message} at {json_path}: '{}'", normalized.gts_id),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_pattern_matching() {
+        let path = |p: &str| parse_steps(p);
+        assert!(PathPattern::new("$.extends").covers(&path("$.extends")));
+        assert!(PathPattern::new("$.extends").covers(&path("$.extends[2]")));
+        assert!(!PathPattern::new("$.extends").covers(&path("$.a.extends")));
+        assert!(PathPattern::new("x-gts-ref").covers(&path("$.properties.type.x-gts-ref")));
+        assert!(PathPattern::new("$..x-gts-ref").covers(&path("$.x-gts-ref")));
+        assert!(
+            PathPattern::new("$.properties.*.x-gts-ref")
+                .covers(&path("$.properties.type.x-gts-ref"))
+        );
+        assert!(
+            !PathPattern::new("$.properties.*.x-gts-ref")
+                .covers(&path("$.properties.a.b.x-gts-ref"))
+        );
+        assert!(PathPattern::new("$.items[*].type").covers(&path("$.items[3].type")));
+        assert!(!PathPattern::new("$.items[*].type").covers(&path("$.items.a.type")));
+    }
+
+    #[test]
+    fn test_check_reports_wrong_form() {
+        let rules = RefFormRules::new(
+            &["$.extends".to_owned(), "x-gts-ref".to_owned()],
+            &["$.topic".to_owned()],
+        );
+        let doc = json!({
+            "extends": ["gts.x.core.events.type.v1~", "gts.x.core.events.type.v1~x.app._.orders.v1"],
+            "properties": {"a": {"x-gts-ref": "gts.x.core.*"}},
+            "topic": "gts.x.core.events.topic.v1~",
+            "other": "gts.x.core.events.topic.v1~x.app._.orders.v1"
+        });
+        let mut errors = Vec::new();
+        rules.check(&doc, Path::new("a.json"), "$", &mut errors);
+
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert_eq!(errors[0].json_path, "$.extends[1]");
+        assert!(errors[0].error.starts_with(EXPECTED_TYPE_ID));
+        assert_eq!(errors[0].error_code(), "expected_type_id");
+        assert_eq!(errors[1].json_path, "$.topic");
+        assert_eq!(errors[1].error_code(), "expected_instance_id");
+    }
+}
@@ -1,28 +1,81 @@
 //! Validation report types.
 
-use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use glob::{Pattern, PatternError};
+use serde::{Deserialize, Serialize};
 
 use crate::error::{ScanError, ValidationError};
+use crate::strategy::ContentFormat;
+use crate::validator::vendor_of;
+
+/// A file scanned with a different parser than its extension implies.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FormatFallback {
+    pub file: PathBuf,
+    /// Format implied by the file extension.
+    pub expected: ContentFormat,
+    /// Format whose parser succeeded.
+    pub parsed_as: ContentFormat,
+}
 
 /// Result of a validation run.
 ///
 /// CI pipelines must check both `validation_errors` and `scan_errors`.
 /// A non-empty `scan_errors` means the validator did not fully run —
 /// treat this as a build failure regardless of `validation_errors`.
-#[derive(Debug, Clone, Serialize)]
+/// With a baseline, only errors not marked `baselined` affect `ok`; findings
+/// demoted to warning or info never do.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ValidationReport {
     /// Number of files successfully scanned (read + parsed).
     pub scanned_files: usize,
     /// Number of files that could not be scanned (read/parse failures).
     pub failed_files: usize,
-    /// Whether all scanned files passed validation AND no scan errors occurred.
+    /// Files among `scanned_files` and `failed_files` whose outcome was taken
+    /// from the incremental cache ([`FsSourceConfig::cache`](crate::FsSourceConfig::cache)).
+    pub cached_files: usize,
+    /// Whether all scanned files passed validation (ignoring baselined errors,
+    /// warnings and infos) AND no scan errors occurred.
     pub ok: bool,
     /// Individual GTS ID validation errors found in scanned files.
     pub validation_errors: Vec<ValidationError>,
     /// Scan-level errors: files that could not be read or parsed.
     /// Non-empty means the validator did not fully cover the repository.
     pub scan_errors: Vec<ScanError>,
+    /// Markdown candidates skipped via an explicit invalid-example marker or tag.
+    pub acknowledged_invalid_examples: usize,
+    /// Markdown candidates skipped by the emoji/keyword bad-example heuristics.
+    pub heuristic_example_skips: usize,
+    /// Findings silenced by `gts-ignore` directives (`<!-- gts-ignore-next-line -->`,
+    /// `<!-- gts-ignore: <id> -->`, `x-gts-ignore`) that would otherwise be reported.
+    #[serde(default)]
+    pub suppressed_findings: usize,
+    /// Validation errors per vendor of the offending ID, for dashboards.
+    /// Findings whose ID has no parseable vendor are not counted here.
+    pub findings_by_vendor: BTreeMap<String, usize>,
+    /// Whether a baseline was configured for this run.
+    pub baseline_applied: bool,
+    /// Files scanned with the fallback parser (`ValidationConfig::format_fallback`).
+    pub format_fallbacks: Vec<FormatFallback>,
+    /// Whether the run stopped at `ValidationConfig::max_errors` (or
+    /// `fail_fast`): later findings and files may be missing.
+    pub truncated: bool,
+    /// Totals per path of [`FsSourceConfig::paths`](crate::FsSourceConfig::paths),
+    /// in the given order, when more than one path was validated; empty otherwise.
+    pub paths: Vec<PathSummary>,
+    /// Vendors of the scanned GTS IDs, by name, with
+    /// [`ValidationConfig::collect_vendors`](crate::ValidationConfig::collect_vendors);
+    /// empty otherwise.
+    pub vendors: Vec<VendorSummary>,
+    /// The scanned GTS IDs, in scan order, with
+    /// [`ValidationConfig::collect_ids`](crate::ValidationConfig::collect_ids);
+    /// empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ids: Vec<IdOccurrence>,
 }
 
 impl ValidationReport {
@@ -37,4 +90,471 @@ impl ValidationReport {
     pub fn errors_count(&self) -> usize {
         self.validation_errors.len()
     }
+
+    /// Number of validation errors not covered by the baseline.
+    #[must_use]
+    pub fn new_errors_count(&self) -> usize {
+        self.validation_errors
+            .iter()
+            .filter(|e| !e.baselined)
+            .count()
+    }
+
+    /// Number of validation errors covered by the baseline.
+    #[must_use]
+    pub fn baselined_errors_count(&self) -> usize {
+        self.errors_count() - self.new_errors_count()
+    }
+
+    /// Number of validation errors that fail the run, see
+    /// [`ValidationError::fails_run`].
+    #[must_use]
+    pub fn failing_errors_count(&self) -> usize {
+        self.validation_errors
+            .iter()
+            .filter(|e| e.fails_run())
+            .count()
+    }
+
+    /// Number of validation errors of `severity`.
+    #[must_use]
+    pub fn severity_count(&self, severity: Severity) -> usize {
+        self.validation_errors
+            .iter()
+            .filter(|e| e.severity == severity)
+            .count()
+    }
+
+    /// A copy of the report holding only the findings `filter` keeps.
+    ///
+    /// File counts, example skips, fallbacks and `truncated` describe the run
+    /// and are kept; `ok` and `findings_by_vendor` are recomputed from the
+    /// remaining findings.
+    #[must_use]
+    pub fn filtered(&self, filter: &ReportFilter) -> Self {
+        let validation_errors: Vec<ValidationError> = self
+            .validation_errors
+            .iter()
+            .filter(|e| filter.matches(e))
+            .cloned()
+            .collect();
+        let scan_errors: Vec<ScanError> = self
+            .scan_errors
+            .iter()
+            .filter(|e| filter.matches_scan_error(e))
+            .cloned()
+            .collect();
+
+        let findings_by_vendor = tally_vendors(&validation_errors);
+
+        let paths = recount_paths(&self.paths, &validation_errors, &scan_errors);
+        Self {
+            ok: !validation_errors.iter().any(ValidationError::fails_run) && scan_errors.is_empty(),
+            validation_errors,
+            scan_errors,
+            findings_by_vendor,
+            format_fallbacks: self.format_fallbacks.clone(),
+            paths,
+            vendors: self.vendors.clone(),
+            ids: self.ids.clone(),
+            ..*self
+        }
+    }
+
+    /// Combine the reports of runs over different files, e.g. CI jobs each
+    /// validating one directory, into one report.
+    ///
+    /// File and example counts are summed, so the runs are expected not to
+    /// share files. Findings reported by both (same location and
+    /// fingerprint) and equal scan errors, fallbacks and ID occurrences are
+    /// kept once. `ok` holds if it held for both runs; `truncated` and
+    /// `baseline_applied` if they held for either. Path summaries of the same
+    /// path and vendors of the same name are combined, and
+    /// `findings_by_vendor` and the path error counts are recomputed from the
+    /// remaining findings.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        let mut seen = BTreeSet::new();
+        let validation_errors: Vec<ValidationError> = self
+            .validation_errors
+            .into_iter()
+            .chain(other.validation_errors)
+            .filter(|e| seen.insert((e.file.clone(), e.line, e.column, e.fingerprint.clone())))
+            .collect();
+        let scan_errors = dedup(self.scan_errors, other.scan_errors);
+
+        let findings_by_vendor = tally_vendors(&validation_errors);
+
+        let mut paths: Vec<PathSummary> = Vec::new();
+        for summary in self.paths.into_iter().chain(other.paths) {
+            match paths.iter_mut().find(|p| p.path == summary.path) {
+                Some(existing) => {
+                    existing.scanned_files += summary.scanned_files;
+                    existing.failed_files += summary.failed_files;
+                }
+                None => paths.push(summary),
+            }
+        }
+        let paths = recount_paths(&paths, &validation_errors, &scan_errors);
+
+        let mut vendors: BTreeMap<String, VendorSummary> = BTreeMap::new();
+        for summary in self.vendors.into_iter().chain(other.vendors) {
+            match vendors.get_mut(&summary.vendor) {
+                Some(existing) => existing.occurrences += summary.occurrences,
+                None => {
+                    vendors.insert(summary.vendor.clone(), summary);
+                }
+            }
+        }
+
+        Self {
+            scanned_files: self.scanned_files + other.scanned_files,
+            failed_files: self.failed_files + other.failed_files,
+            cached_files: self.cached_files + other.cached_files,
+            ok: self.ok && other.ok,
+            validation_errors,
+            scan_errors,
+            acknowledged_invalid_examples: self.acknowledged_invalid_examples
+                + other.acknowledged_invalid_examples,
+            heuristic_example_skips: self.heuristic_example_skips + other.heuristic_example_skips,
+            suppressed_findings: self.suppressed_findings + other.suppressed_findings,
+            findings_by_vendor,
+            baseline_applied: self.baseline_applied || other.baseline_applied,
+            format_fallbacks: dedup(self.format_fallbacks, other.format_fallbacks),
+            truncated: self.truncated || other.truncated,
+            paths,
+            vendors: vendors.into_values().collect(),
+            ids: dedup(self.ids, other.ids),
+        }
+    }
+}
+
+/// The items of `first` then `second`, each kept once, in order.
+fn dedup<T: PartialEq>(first: Vec<T>, second: Vec<T>) -> Vec<T> {
+    let mut items: Vec<T> = Vec::with_capacity(first.len() + second.len());
+    for item in first.into_iter().chain(second) {
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+    items
+}
+
+/// Totals for one validated path, see [`ValidationReport::paths`].
+///
+/// A file under several of the paths counts towards the first one given.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PathSummary {
+    /// The path as passed in `FsSourceConfig::paths`.
+    pub path: PathBuf,
+    /// Files under `path` successfully scanned.
+    pub scanned_files: usize,
+    /// Files under `path` that could not be (fully) scanned.
+    pub failed_files: usize,
+    /// Validation errors in files under `path`.
+    pub errors: usize,
+    /// Whether files under `path` have no scan errors and no validation errors
+    /// failing the run.
+    pub ok: bool,
+}
+
+/// A vendor seen in the scanned files, see [`ValidationReport::vendors`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct VendorSummary {
+    pub vendor: String,
+    /// GTS IDs of the vendor found.
+    pub occurrences: usize,
+    /// File of the first ID found.
+    pub file: PathBuf,
+    /// Line of the first ID found (0 if unknown).
+    pub line: usize,
+    /// Column of the first ID found (0 if unknown).
+    pub column: usize,
+}
+
+/// A GTS ID found in the scanned files, see [`ValidationReport::ids`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct IdOccurrence {
+    /// The normalized ID.
+    pub id: String,
+    /// The string as written, e.g. with a `gts://` prefix.
+    pub raw_value: String,
+    pub file: PathBuf,
+    /// Line number (1-indexed); 0 if unknown.
+    pub line: usize,
+    /// Column number (1-indexed, in characters); 0 if unknown.
+    pub column: usize,
+    /// JSON path of the value in JSON/YAML files; empty otherwise.
+    pub json_path: String,
+}
+
+impl From<&ValidationError> for IdOccurrence {
+    fn from(sighting: &ValidationError) -> Self {
+        Self {
+            id: sighting.normalized_id.clone(),
+            raw_value: sighting.raw_value.clone(),
+            file: sighting.file.clone(),
+            line: sighting.line,
+            column: sighting.column,
+            json_path: sighting.json_path.clone(),
+        }
+    }
+}
+
+/// The number of `findings` per vendor of their IDs.
+pub fn tally_vendors(findings: &[ValidationError]) -> BTreeMap<String, usize> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for vendor in findings.iter().filter_map(|e| vendor_of(&e.normalized_id)) {
+        *counts.entry(vendor).or_default() += 1;
+    }
+    counts
+}
+
+/// Count the IDs of `sightings` towards their vendor in `vendors`, keeping
+/// the location of the first one.
+pub fn tally_vendor_sightings(
+    vendors: &mut BTreeMap<String, VendorSummary>,
+    sightings: &[ValidationError],
+) {
+    for sighting in sightings {
+        let Some(vendor) = vendor_of(&sighting.normalized_id) else {
+            continue;
+        };
+        vendors
+            .entry(vendor.clone())
+            .or_insert_with(|| VendorSummary {
+                vendor,
+                occurrences: 0,
+                file: sighting.file.clone(),
+                line: sighting.line,
+                column: sighting.column,
+            })
+            .occurrences += 1;
+    }
+}
+
+/// Index of the path in `roots` that `file` is counted towards.
+pub fn owning_path(roots: &[PathBuf], file: &Path) -> Option<usize> {
+    roots.iter().position(|root| file.starts_with(root))
+}
+
+/// Summaries of `roots` given the files scanned under each (`scanned[i]` for
+/// `roots[i]`) and the findings of the run; empty for a single root.
+pub fn summarize_paths(
+    roots: &[PathBuf],
+    scanned: &[usize],
+    validation_errors: &[ValidationError],
+    scan_errors: &[ScanError],
+) -> Vec<PathSummary> {
+    if roots.len() < 2 {
+        return Vec::new();
+    }
+    let mut failed: Vec<BTreeSet<&Path>> = vec![BTreeSet::new(); roots.len()];
+    for scan_err in scan_errors {
+        if let Some(index) = owning_path(roots, &scan_err.file) {
+            failed[index].insert(&scan_err.file);
+        }
+    }
+    let summaries: Vec<PathSummary> = roots
+        .iter()
+        .zip(scanned)
+        .zip(failed)
+        .map(|((path, &scanned_files), failed)| PathSummary {
+            path: path.clone(),
+            scanned_files,
+            failed_files: failed.len(),
+            errors: 0,
+            ok: true,
+        })
+        .collect();
+    recount_paths(&summaries, validation_errors, scan_errors)
+}
+
+/// `summaries` with `errors` and `ok` recomputed from the given findings.
+fn recount_paths(
+    summaries: &[PathSummary],
+    validation_errors: &[ValidationError],
+    scan_errors: &[ScanError],
+) -> Vec<PathSummary> {
+    let roots: Vec<PathBuf> = summaries.iter().map(|s| s.path.clone()).collect();
+    let mut summaries: Vec<PathSummary> = summaries
+        .iter()
+        .map(|s| PathSummary {
+            errors: 0,
+            ok: true,
+            ..s.clone()
+        })
+        .collect();
+    for scan_err in scan_errors {
+        if let Some(index) = owning_path(&roots, &scan_err.file) {
+            summaries[index].ok = false;
+        }
+    }
+    for error in validation_errors {
+        if let Some(index) = owning_path(&roots, &error.file) {
+            summaries[index].errors += 1;
+            summaries[index].ok &= !error.fails_run();
+        }
+    }
+    summaries
+}
+
+/// How serious a finding is.
+///
+/// Validation errors are `Error` unless their rule is overridden in
+/// [`ValidationConfig::rule_overrides`](crate::ValidationConfig::rule_overrides);
+/// only errors fail a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Severity {
+    /// A GTS ID that failed validation (`ValidationReport::validation_errors`).
+    #[default]
+    Error,
+    /// A finding that is reported but does not fail the run.
+    Warning,
+    /// A finding reported for information only.
+    Info,
+    /// A file that could not be scanned at all (`ValidationReport::scan_errors`).
+    Fatal,
+}
+
+impl Severity {
+    /// Lowercase name, as in JSON output.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+            Self::Fatal => "fatal",
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Self::Error),
+            "warning" => Ok(Self::Warning),
+            "info" => Ok(Self::Info),
+            "fatal" => Ok(Self::Fatal),
+            _ => Err(format!(
+                "unknown severity '{s}' (expected error, warning, info or fatal)"
+            )),
+        }
+    }
+}
+
+/// Selects findings of a [`ValidationReport`], see [`ValidationReport::filtered`].
+///
+/// Each criterion may be given several times and keeps findings matching any
+/// of its values; a finding must satisfy every criterion given. Error codes,
+/// vendors and the baselined flag only exist on validation errors, so setting
+/// any of them drops all scan errors.
+///
+/// ```rust
+/// use gts_validator::{ReportFilter, Severity};
+///
+/// let filter = ReportFilter::new()
+///     .severity(Severity::Error)
+///     .code("vendor_mismatch")
+///     .path("docs/**/*.md")
+///     .unwrap()
+///     .baselined(false);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ReportFilter {
+    severities: Vec<Severity>,
+    codes: Vec<String>,
+    vendors: Vec<String>,
+    paths: Vec<Pattern>,
+    baselined: Option<bool>,
+}
+
+impl ReportFilter {
+    /// A filter keeping every finding.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep findings of `severity`.
+    #[must_use]
+    pub fn severity(mut self, severity: Severity) -> Self {
+        self.severities.push(severity);
+        self
+    }
+
+    /// Keep validation errors with this [`ValidationError::error_code`].
+    #[must_use]
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.codes.push(code.into());
+        self
+    }
+
+    /// Keep validation errors whose ID belongs to `vendor`.
+    #[must_use]
+    pub fn vendor(mut self, vendor: impl Into<String>) -> Self {
+        self.vendors.push(vendor.into());
+        self
+    }
+
+    /// Keep findings in files matching the glob `pattern`, tested against the
+    /// reported path and its file name (as `--exclude` patterns are).
+    ///
+    /// # Errors
+    /// Returns an error if `pattern` is not a valid glob.
+    pub fn path(mut self, pattern: &str) -> Result<Self, PatternError> {
+        self.paths.push(Pattern::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Keep only baselined (`true`) or only new (`false`) validation errors.
+    #[must_use]
+    pub fn baselined(mut self, baselined: bool) -> Self {
+        self.baselined = Some(baselined);
+        self
+    }
+
+    /// Whether the filter keeps `error`.
+    #[must_use]
+    pub fn matches(&self, error: &ValidationError) -> bool {
+        self.keeps_severity(error.severity)
+            && self.keeps_path(&error.file)
+            && (self.codes.is_empty() || self.codes.iter().any(|c| c == error.error_code()))
+            && (self.vendors.is_empty()
+                || vendor_of(&error.normalized_id).is_some_and(|v| self.vendors.contains(&v)))
+            && self.baselined.is_none_or(|b| b == error.baselined)
+    }
+
+    /// Whether the filter keeps `error`.
+    #[must_use]
+    pub fn matches_scan_error(&self, error: &ScanError) -> bool {
+        self.keeps_severity(Severity::Fatal)
+            && self.keeps_path(&error.file)
+            && self.codes.is_empty()
+            && self.vendors.is_empty()
+            && self.baselined.is_none()
+    }
+
+    fn keeps_severity(&self, severity: Severity) -> bool {
+        self.severities.is_empty() || self.severities.contains(&severity)
+    }
+
+    fn keeps_path(&self, path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        self.paths.is_empty()
+            || self.paths.iter().any(|pattern| {
+                pattern.matches(&path_str)
+                    || path
+                        .file_name()
+                        .is_some_and(|name| pattern.matches(&name.to_string_lossy()))
+            })
+    }
 }
@@ -0,0 +1,140 @@
+//! Reserved vendors and packages in JSON/YAML definitions.
+//!
+//! A JSON/YAML document defines the entity named by its root `$id` (schemas)
+//! or `id` (instances). With [`ValidationConfig::reservations`](crate::ValidationConfig::reservations)
+//! set, a definition under a reserved vendor or package is reported unless
+//! [`ValidationConfig::reservation_owner`](crate::ValidationConfig::reservation_owner)
+//! holds the reservation. References to reserved types are always allowed.
+
+use std::path::Path;
+
+use gts_id::GtsReservations;
+use serde_json::Value;
+
+use crate::candidate::{Candidate, Location};
+use crate::error::ValidationError;
+use crate::normalize::normalize_candidate;
+
+/// Error message prefix for a definition under a reservation it does not hold.
+pub const RESERVED_NAME: &str = "Reserved name";
+
+/// Root fields naming the entity a document defines.
+const DEFINITION_KEYS: &[&str] = &["$id", "id"];
+
+/// Compiled [`ValidationConfig`](crate::ValidationConfig) reservation policy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReservationRules {
+    reservations: GtsReservations,
+    owner: Option<String>,
+}
+
+impl ReservationRules {
+    #[must_use]
+    pub fn new(reservations: GtsReservations, owner: Option<String>) -> Self {
+        Self {
+            reservations,
+            owner,
+        }
+    }
+
+    /// Report the definition fields of the document `value` that violate a reservation.
+    pub fn check(&self, value: &Value, file: &Path, errors: &mut Vec<ValidationError>) {
+        if self.reservations.is_empty() {
+            return;
+        }
+        let Value::Object(map) = value else {
+            return;
+        };
+        for key in DEFINITION_KEYS {
+            if let Some(Value::String(text)) = map.get(*key) {
+                errors.extend(self.check_definition(text, file, &format!("$.{key}")));
+            }
+        }
+    }
+
+    /// Report the string `text` at `json_path` if it is a root definition
+    /// field violating a reservation.
+    pub fn check_field(
+        &self,
+        text: &str,
+        file: &Path,
+        json_path: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if self.reservations.is_empty() {
+            return;
+        }
+        if DEFINITION_KEYS
+            .iter()
+            .any(|key| json_path.strip_prefix("$.") == Some(key))
+        {
+            errors.extend(self.check_definition(text, file, json_path));
+        }
+    }
+
+    fn check_definition(
+        &self,
+        text: &str,
+        file: &Path,
+        json_path: &str,
+    ) -> Option<ValidationError> {
+        let normalized = normalize_candidate(text).ok()?;
+        // Invalid IDs are left to the regular validation
+        let segments = gts_id::validate_gts_id(&normalized.gts_id, false).ok()?;
+        let err = self
+            .reservations
+            .check(&normalized.gts_id, &segments, self.owner.as_deref())
+            .err()?;
+        let candidate = Candidate {
+            text,
+            location: Location::JsonPath(json_path.to_owned()),
+            context: json_path.to_owned(),
+            allow_wildcards: false,
+        };
+        Some(candidate.error(
+            file,
+            normalized.original,
+            normalized.gts_id,
+            format!("{RESERVED_NAME} at {json_path}: {err}"),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_check_reports_reserved_definitions() {
+        let rules = ReservationRules::new(
+            GtsReservations::new().reserve("x", Some("core"), &["platform"]),
+            Some("app".to_owned()),
+        );
+        let mut errors = Vec::new();
+        let schema = json!({
+            "$id": "gts://gts.x.core.events.type.v1~",
+            "properties": {"type": {"x-gts-ref": "gts.x.core.events.type.v1~"}}
+        });
+        rules.check(&schema, Path::new("a.json"), &mut errors);
+        rules.check(
+            &json!({"id": "gts.x.core.events.type.v1~x.app._.orders.v1"}),
+            Path::new("b.json"),
+            &mut errors,
+        );
+
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert_eq!(errors[0].json_path, "$.$id");
+        assert_eq!(errors[0].normalized_id, "gts.x.core.events.type.v1~");
+        assert_eq!(errors[0].error_code(), "reserved_name");
+        assert!(errors[0].error.contains("'x.core'"), "{}", errors[0].error);
+
+        let owner = ReservationRules::new(
+            GtsReservations::new().reserve("x", Some("core"), &["platform"]),
+            Some("platform".to_owned()),
+        );
+        let mut errors = Vec::new();
+        owner.check(&schema, Path::new("a.json"), &mut errors);
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+}
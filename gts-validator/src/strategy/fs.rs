@@ -1,6 +1,7 @@
 //! Filesystem validation source.
 //!
-//! Discovers files on disk and reads them safely for the validation pipeline.
+//! Discovers files on disk (through a [`FileSystem`]) and reads them safely
+//! for the validation pipeline.
 //! Security properties enforced here:
 //! - Symlinks are not followed by default (`follow_links: false`)
 //! - Resolved paths are checked to remain within the repository root
@@ -12,10 +13,10 @@ use std::io::Read;
 use std::path::{Path, PathBuf};
 
 use glob::Pattern;
-use walkdir::WalkDir;
 
 use crate::config::FsSourceConfig;
 use crate::error::{ScanError, ScanErrorKind};
+use crate::host::{FileKind, FileSystem};
 use crate::strategy::ContentFormat;
 
 /// Directories to skip
@@ -49,23 +50,9 @@ fn matches_exclude(path: &Path, exclude_patterns: &[Pattern]) -> bool {
     false
 }
 
-/// Check if a directory entry is a skip directory (for `WalkDir::filter_entry`).
-/// Returns `true` if the entry should be **included** (i.e., is NOT a skip dir).
-fn is_not_skip_dir(entry: &walkdir::DirEntry) -> bool {
-    if entry.file_type().is_dir()
-        && let Some(name) = entry.file_name().to_str()
-    {
-        return !SKIP_DIRS.contains(&name);
-    }
-    true
-}
-
 /// Check if file has a supported extension.
 fn matches_file_pattern(path: &Path) -> bool {
-    matches!(
-        path.extension().and_then(|e| e.to_str()),
-        Some("md" | "json" | "yaml" | "yml")
-    )
+    content_format_for(path).is_some()
 }
 
 /// Find all files to scan in the given paths.
@@ -74,7 +61,7 @@ fn matches_file_pattern(path: &Path) -> bool {
 /// - `files`: paths that passed all filters and are ready to read.
 /// - `scan_errors`: walk errors (permission denied, loop, etc.) and boundary violations.
 ///   These are never silently discarded — CI must treat them as failures.
-pub fn find_files(config: &FsSourceConfig) -> (Vec<PathBuf>, Vec<ScanError>) {
+pub fn find_files(config: &FsSourceConfig, fs: &dyn FileSystem) -> (Vec<PathBuf>, Vec<ScanError>) {
     let mut files = Vec::new();
     let mut scan_errors = Vec::new();
 
@@ -94,7 +81,7 @@ pub fn find_files(config: &FsSourceConfig) -> (Vec<PathBuf>, Vec<ScanError>) {
 
     for root in &config.paths {
         // Canonicalize the root once so we can enforce the boundary for every entry.
-        let canonical_root = match root.canonicalize() {
+        let canonical_root = match fs.canonicalize(root) {
             Ok(r) => r,
             Err(e) => {
                 scan_errors.push(ScanError {
@@ -106,49 +93,41 @@ pub fn find_files(config: &FsSourceConfig) -> (Vec<PathBuf>, Vec<ScanError>) {
             }
         };
 
-        if root.is_file() {
-            if matches_file_pattern(root) && !matches_exclude(root, &exclude_patterns) {
-                files.push(root.clone());
+        match fs.info(root).map(|info| info.kind) {
+            Ok(FileKind::File) => {
+                if matches_file_pattern(root) && !matches_exclude(root, &exclude_patterns) {
+                    files.push(root.clone());
+                }
+                continue;
             }
-            continue;
-        }
-
-        if !root.is_dir() {
-            continue;
+            Ok(FileKind::Dir) => {}
+            _ => continue,
         }
 
-        for entry_result in WalkDir::new(root)
-            .follow_links(config.follow_links)
-            .max_depth(config.max_depth)
-            .into_iter()
-            .filter_entry(is_not_skip_dir)
-        {
+        for entry_result in fs.walk(root, config.follow_links, config.max_depth, SKIP_DIRS) {
             let entry = match entry_result {
                 Ok(e) => e,
                 Err(walk_err) => {
                     // Propagate walk errors (permission denied, loop, etc.) as ScanErrors.
-                    let path = walk_err
-                        .path()
-                        .map_or_else(|| root.clone(), Path::to_path_buf);
                     scan_errors.push(ScanError {
-                        file: path,
+                        file: walk_err.path.unwrap_or_else(|| root.clone()),
                         kind: ScanErrorKind::WalkError,
-                        message: format!("Directory traversal error: {walk_err}"),
+                        message: format!("Directory traversal error: {}", walk_err.message),
                     });
                     continue;
                 }
             };
 
-            let file_path = entry.path();
-
-            if !file_path.is_file() {
+            // Only regular files: devices, pipes and sockets are skipped
+            if entry.kind != FileKind::File {
                 continue;
             }
+            let file_path = entry.path.as_path();
 
             // Enforce repository boundary: canonicalize and verify the resolved path
             // stays within the root. This catches symlink escapes even when follow_links
             // is true, and rejects any path that resolves outside the scan root.
-            match file_path.canonicalize() {
+            match fs.canonicalize(file_path) {
                 Ok(canonical_path) => {
                     if !canonical_path.starts_with(&canonical_root) {
                         scan_errors.push(ScanError {
@@ -173,20 +152,6 @@ pub fn find_files(config: &FsSourceConfig) -> (Vec<PathBuf>, Vec<ScanError>) {
                 }
             }
 
-            // Skip devices, pipes, sockets — only regular files
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::FileTypeExt;
-                if let Ok(ft) = entry.metadata().map(|m| m.file_type())
-                    && (ft.is_block_device()
-                        || ft.is_char_device()
-                        || ft.is_fifo()
-                        || ft.is_socket())
-                {
-                    continue;
-                }
-            }
-
             if !matches_file_pattern(file_path) {
                 continue;
             }
@@ -200,7 +165,7 @@ pub fn find_files(config: &FsSourceConfig) -> (Vec<PathBuf>, Vec<ScanError>) {
                 continue;
             }
 
-            files.push(file_path.to_path_buf());
+            files.push(entry.path);
         }
     }
 
@@ -215,6 +180,8 @@ pub fn content_format_for(path: &Path) -> Option<ContentFormat> {
         Some("md") => Some(ContentFormat::Markdown),
         Some("json") => Some(ContentFormat::Json),
         Some("yaml" | "yml") => Some(ContentFormat::Yaml),
+        Some("rst") => Some(ContentFormat::Rst),
+        Some("adoc" | "asciidoc") => Some(ContentFormat::Asciidoc),
         _ => None,
     }
 }
@@ -229,8 +196,8 @@ pub fn content_format_for(path: &Path) -> Option<ContentFormat> {
 /// - The file exceeds `max_file_size`
 /// - An I/O error occurs
 /// - The content is not valid UTF-8
-pub fn read_file_bounded(path: &Path, max_file_size: u64) -> ScanResult {
-    let file = match std::fs::File::open(path) {
+pub fn read_file_bounded(fs: &dyn FileSystem, path: &Path, max_file_size: u64) -> ScanResult {
+    let file = match fs.open(path) {
         Ok(f) => f,
         Err(e) => {
             return ScanResult::Err(ScanError {
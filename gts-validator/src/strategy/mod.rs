@@ -1,16 +1,47 @@
 //! Validation source strategies.
 //!
-//! Phase 1 provides only the filesystem strategy (`fs` module) with a concrete
-//! `validate_fs()` public API. A `ValidationSource` trait may be introduced in
-//! a future phase when a second concrete strategy demands it — until then, the
-//! design stays concrete to avoid speculative abstraction.
+//! The filesystem strategy (`fs` module) backs `validate_fs()`; with the
+//! `async` feature, the stream strategy (`stream` module) backs
+//! `validate_stream()`, which validates content handed to it instead of files.
+//! Both call the same scanners directly; a `ValidationSource` trait is not
+//! worth it for two strategies with different result shapes (a final report
+//! vs. a stream of events). Disk access goes through
+//! [`crate::host::FileSystem`], so the filesystem strategy also runs over
+//! in-memory files. With the `watch` feature, `validate_fs_watch()` (the
+//! `watch` module) repeats the filesystem strategy whenever watched files
+//! change.
 
 pub mod fs;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "watch")]
+pub mod watch;
+
+use serde::{Deserialize, Serialize};
 
 /// Content format for dispatching to the correct scanner.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ContentFormat {
     Markdown,
     Json,
     Yaml,
+    /// reStructuredText (`.rst`)
+    Rst,
+    /// `AsciiDoc` (`.adoc`, `.asciidoc`)
+    Asciidoc,
+}
+
+impl ContentFormat {
+    /// Lowercase name, as used in JSON reports.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Rst => "rst",
+            Self::Asciidoc => "asciidoc",
+        }
+    }
 }
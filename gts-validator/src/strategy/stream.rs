@@ -0,0 +1,218 @@
+//! Async stream strategy (feature `async`).
+//!
+//! [`validate_stream`] validates `(path, content)` items as they arrive, e.g.
+//! uploads, instead of walking the filesystem. Nothing is read from disk and
+//! there is no final report: each item is scanned on tokio's blocking pool and
+//! its findings are yielded as [`ValidationEvent`]s before the next item is
+//! pulled. The format of an item comes from the extension of its path, as for
+//! files on disk.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+
+use crate::baseline::BaselineMarker;
+use crate::config::{ValidationConfig, VendorPolicy};
+use crate::error::RuleId;
+use crate::error::{ScanError, ValidationError};
+use crate::isolation::{self, IsolationFailure};
+use crate::report::Severity;
+use crate::{
+    Baseline, FileOutcome, ScanOptions, apply_allow_list_filter, apply_min_confidence,
+    apply_rule_overrides, collects_sightings, error_limit, isolation_scan_error,
+    scan_content_with_context, take_vendor_sightings,
+};
+
+/// One finding or progress step of [`validate_stream`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ValidationEvent {
+    /// A GTS identifier in the current item failed validation.
+    Error(ValidationError),
+    /// The current item, or a document in it, could not be scanned.
+    ScanError(ScanError),
+    /// No more events for `file` follow. `scanned` is false for an item whose
+    /// extension is not a scannable format.
+    FileDone { file: PathBuf, scanned: bool },
+    /// The error limit (`fail_fast`/`max_errors`) was reached with findings or
+    /// items left; it is the last event.
+    Truncated,
+}
+
+/// Validate the `(path, content)` items of `items`, yielding findings as each
+/// item is scanned.
+///
+/// Vendor policy, rule overrides, baseline and error limit of
+/// `validation_config` apply as in
+/// [`validate_fs`](crate::validate_fs). A scan running longer than
+/// `file_timeout` is reported as a [`ScanErrorKind::Timeout`](crate::ScanErrorKind::Timeout)
+/// scan error; like the scan threads of `validate_fs`, it cannot be cancelled
+/// and finishes in the background. Must be polled within a tokio runtime.
+///
+/// ```rust
+/// use std::path::PathBuf;
+/// use futures_util::{StreamExt, stream};
+/// use gts_validator::{ValidationConfig, ValidationEvent, validate_stream};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let uploads = stream::iter([(
+///     PathBuf::from("upload.md"),
+///     "Uses `gts.x.core.pkg.mytype.v1~`.".to_owned(),
+/// )]);
+/// let events: Vec<_> = validate_stream(uploads, &ValidationConfig::default(), None)
+///     .collect()
+///     .await;
+/// assert!(matches!(events.last(), Some(ValidationEvent::FileDone { scanned: true, .. })));
+/// # });
+/// ```
+pub fn validate_stream<S>(
+    items: S,
+    validation_config: &ValidationConfig,
+    file_timeout: Option<Duration>,
+) -> impl Stream<Item = ValidationEvent> + Send + 'static
+where
+    S: Stream<Item = (PathBuf, String)> + Send + 'static,
+{
+    let state = StreamState {
+        items: Box::pin(items),
+        options: Arc::new(ScanOptions::new(validation_config)),
+        vendor_policy: validation_config.vendor_policy.clone(),
+        collect_sightings: collects_sightings(validation_config),
+        rule_overrides: validation_config.rule_overrides.clone(),
+        min_confidence: validation_config.min_confidence,
+        baseline_marker: validation_config.baseline.as_ref().map(Baseline::marker),
+        error_limit: error_limit(validation_config),
+        file_timeout,
+        reported: 0,
+        pending: VecDeque::new(),
+        limit_reached: false,
+        stopped: false,
+    };
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                return Some((event, state));
+            }
+            if state.stopped {
+                return None;
+            }
+            let (file, content) = state.items.next().await?;
+            if state.limit_reached {
+                state.stop();
+                continue;
+            }
+            state.scan(file, content).await;
+        }
+    })
+}
+
+#[allow(clippy::struct_excessive_bools)]
+struct StreamState<S> {
+    items: Pin<Box<S>>,
+    options: Arc<ScanOptions>,
+    vendor_policy: VendorPolicy,
+    /// ID sightings are dropped: a stream has no report to list them in.
+    collect_sightings: bool,
+    rule_overrides: BTreeMap<RuleId, Severity>,
+    min_confidence: Option<u8>,
+    baseline_marker: Option<BaselineMarker>,
+    error_limit: Option<usize>,
+    file_timeout: Option<Duration>,
+    /// Validation errors failing the run yielded so far.
+    reported: usize,
+    pending: VecDeque<ValidationEvent>,
+    limit_reached: bool,
+    /// No more items are pulled once `pending` is drained.
+    stopped: bool,
+}
+
+impl<S> StreamState<S> {
+    /// Stop with findings or items left over.
+    fn stop(&mut self) {
+        self.pending.push_back(ValidationEvent::Truncated);
+        self.stopped = true;
+    }
+
+    /// Scan one item and queue its events.
+    async fn scan(&mut self, file: PathBuf, content: String) {
+        let options = Arc::clone(&self.options);
+        let path = file.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            isolation::run_isolated(None, move || {
+                scan_content_with_context(&content, &path, &options)
+            })
+        });
+        let joined = match self.file_timeout {
+            Some(budget) => tokio::time::timeout(budget, task)
+                .await
+                .unwrap_or(Ok(Err(IsolationFailure::TimedOut(budget)))),
+            None => task.await,
+        };
+        let outcome = match joined {
+            Ok(Ok(outcome)) => outcome,
+            Ok(Err(failure)) => FileOutcome::Failed(isolation_scan_error(&file, &failure)),
+            Err(e) => FileOutcome::Failed(isolation_scan_error(
+                &file,
+                &IsolationFailure::Panicked(e.to_string()),
+            )),
+        };
+
+        let file_scan = match outcome {
+            FileOutcome::Unsupported => {
+                self.pending.push_back(ValidationEvent::FileDone {
+                    file,
+                    scanned: false,
+                });
+                return;
+            }
+            FileOutcome::Failed(scan_err) => {
+                self.pending.push_back(ValidationEvent::ScanError(scan_err));
+                self.pending.push_back(ValidationEvent::FileDone {
+                    file,
+                    scanned: true,
+                });
+                return;
+            }
+            FileOutcome::Scanned(file_scan) => file_scan,
+        };
+        self.pending.extend(
+            file_scan
+                .scan_errors
+                .into_iter()
+                .map(ValidationEvent::ScanError),
+        );
+
+        let (errors, _) = take_vendor_sightings(file_scan.errors, self.collect_sightings);
+        let mut errors = apply_allow_list_filter(errors, &self.vendor_policy);
+        apply_rule_overrides(&mut errors, &self.rule_overrides);
+        apply_min_confidence(&mut errors, self.min_confidence);
+        if let Some(marker) = &mut self.baseline_marker {
+            marker.mark(&mut errors);
+        }
+        let mut errors = errors.into_iter();
+        for error in errors.by_ref() {
+            let counts = error.fails_run();
+            self.pending.push_back(ValidationEvent::Error(error));
+            if counts {
+                self.reported += 1;
+                if self.error_limit.is_some_and(|limit| self.reported >= limit) {
+                    self.limit_reached = true;
+                    break;
+                }
+            }
+        }
+        self.pending.push_back(ValidationEvent::FileDone {
+            file,
+            scanned: true,
+        });
+        if self.limit_reached && errors.next().is_some() {
+            self.stop();
+        }
+    }
+}
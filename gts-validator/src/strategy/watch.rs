@@ -0,0 +1,114 @@
+//! Watch strategy: re-validate files on disk as they change.
+//!
+//! Each run is a [`validate_fs`](crate::validate_fs) run over an incremental
+//! cache kept in memory, so only files whose modification time or size
+//! changed since the previous run are read, and only those whose content
+//! changed are scanned again.
+
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::cache::ScanCache;
+use crate::strategy::fs::find_files;
+use crate::{
+    FsSourceConfig, Host, ScanOptions, ScanSettings, ValidationConfig, ValidationReport,
+    cache_settings, check_paths, empty_report, scan_files,
+};
+
+/// How long a burst of change events (an editor writing a file in several
+/// steps) must stay quiet before files are validated again.
+const SETTLE_TIME: Duration = Duration::from_millis(100);
+
+/// Validate the files of `fs_config` as [`validate_fs`](crate::validate_fs)
+/// does, then again each time a file under its paths is created, modified or
+/// removed, passing every report to `callback` until it returns
+/// [`ControlFlow::Break`].
+///
+/// Files unchanged since the previous run keep their outcome without being
+/// scanned; [`ValidationReport::cached_files`] counts them. A cache file set
+/// in `fs_config.cache` seeds the first run and is not written.
+///
+/// ```rust,no_run
+/// use std::ops::ControlFlow;
+/// use std::path::PathBuf;
+/// use gts_validator::{FsSourceConfig, ValidationConfig, validate_fs_watch};
+///
+/// let mut fs_config = FsSourceConfig::default();
+/// fs_config.paths = vec![PathBuf::from("docs")];
+/// validate_fs_watch(&fs_config, &ValidationConfig::default(), |report| {
+///     println!("{} errors", report.errors_count());
+///     ControlFlow::Continue(())
+/// })
+/// .unwrap();
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if `fs_config.paths` is empty, a path does not exist
+/// (when starting or later), or the paths cannot be watched.
+pub fn validate_fs_watch<F>(
+    fs_config: &FsSourceConfig,
+    validation_config: &ValidationConfig,
+    mut callback: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(&ValidationReport) -> ControlFlow<()>,
+{
+    let host = Host::os();
+    check_paths(fs_config, host.fs().as_ref())?;
+
+    let (sender, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    for path in &fs_config.paths {
+        watcher.watch(path, RecursiveMode::Recursive)?;
+    }
+
+    let scan_options = Arc::new(ScanOptions::new(validation_config));
+    let settings = cache_settings(fs_config, &scan_options);
+    let mut cache = match &fs_config.cache {
+        Some(path) => ScanCache::load(host.fs().as_ref(), path, &settings),
+        None => ScanCache::new(&settings),
+    };
+    let scan_settings = ScanSettings {
+        fs_config,
+        validation_config,
+        host: &host,
+        scan_options: &scan_options,
+    };
+    loop {
+        check_paths(fs_config, host.fs().as_ref())?;
+        let (files, scan_errors) = find_files(fs_config, host.fs().as_ref());
+        cache.retain(&files);
+        let report = if files.is_empty() && scan_errors.is_empty() {
+            empty_report(fs_config, validation_config)
+        } else {
+            scan_files(
+                &files,
+                scan_errors,
+                scan_settings,
+                Some(&mut cache),
+                &mut |_| {},
+            )
+        };
+        if callback(&report).is_break() {
+            return Ok(());
+        }
+        wait_for_change(&events)?;
+    }
+}
+
+/// Block until a file changes and the events it causes settle.
+fn wait_for_change(events: &Receiver<notify::Result<notify::Event>>) -> anyhow::Result<()> {
+    loop {
+        let event = events.recv()??;
+        if !matches!(event.kind, EventKind::Access(_)) {
+            break;
+        }
+    }
+    while events.recv_timeout(SETTLE_TIME).is_ok() {}
+    Ok(())
+}
@@ -35,6 +35,14 @@ pub const SKIP_VALIDATION_CONTEXTS: &[&str] = &[
     "not allowed:",
 ];
 
+/// Explicit marker acknowledging every GTS candidate on the line as an intentionally
+/// invalid example. Unlike `SKIP_VALIDATION_CONTEXTS` it may appear anywhere on the line.
+pub const INVALID_EXAMPLE_MARKER: &str = "<!-- gts:invalid-example -->";
+
+/// Explicit tag acknowledging the candidate right before it as an intentionally
+/// invalid example, e.g. `` `gts.x.bad` (invalid) ``.
+pub const INVALID_EXAMPLE_TAG: &str = "(invalid)";
+
 /// Example vendors used in documentation that are tolerated during vendor validation.
 /// These are placeholder/example vendors commonly used in docs and tutorials.
 pub const EXAMPLE_VENDORS: &[&str] = &[
@@ -92,6 +100,22 @@ pub fn is_bad_example_context(line: &str, match_start: usize) -> bool {
     false
 }
 
+/// Check if the GTS identifier is explicitly acknowledged as an invalid example:
+/// the line carries `INVALID_EXAMPLE_MARKER`, or `INVALID_EXAMPLE_TAG` directly
+/// follows the candidate (closing backticks/quotes and whitespace are skipped).
+#[must_use]
+pub fn is_acknowledged_invalid_example(line: &str, match_end: usize) -> bool {
+    if line.contains(INVALID_EXAMPLE_MARKER) {
+        return true;
+    }
+    line.get(match_end..).is_some_and(|after| {
+        after
+            .trim_start_matches(['`', '"', '\'', ' ', '\t'])
+            .to_lowercase()
+            .starts_with(INVALID_EXAMPLE_TAG)
+    })
+}
+
 /// Validate a GTS identifier candidate.
 ///
 /// This function delegates all validation to `gts::GtsID::new()` and `gts::GtsWildcard::new()`.
@@ -168,6 +192,23 @@ pub fn validate_candidate(
     errors
 }
 
+/// Vendor of a normalized GTS ID (or wildcard pattern), e.g. `x` for
+/// `gts.x.core.events.type.v1~`.
+///
+/// Only the first segment is parsed, so IDs with a malformed chain still
+/// resolve to their vendor. Returns `None` if the first segment is not
+/// well-formed or the vendor itself is a wildcard.
+#[must_use]
+pub fn vendor_of(normalized_id: &str) -> Option<String> {
+    let rest = normalized_id.strip_prefix("gts.")?;
+    let first_segment = match rest.find('~') {
+        Some(end) => &rest[..=end],
+        None => rest,
+    };
+    let parsed = gts_id::validate_segment(1, first_segment, true).ok()?;
+    (!parsed.vendor.is_empty() && !parsed.vendor.contains('*')).then_some(parsed.vendor)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,6 +325,29 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_is_acknowledged_invalid_example() {
+        let line = "gts.x.core.type.v1 <!-- gts:invalid-example -->";
+        assert!(is_acknowledged_invalid_example(
+            line,
+            "gts.x.core.type.v1".len()
+        ));
+
+        let line = "Use `gts.x.core.type.v1` (invalid) as a counter-example";
+        let end = "Use `gts.x.core.type.v1".len();
+        assert!(is_acknowledged_invalid_example(line, end));
+
+        // Tag must directly follow the candidate
+        let line = "Use `gts.x.core.type.v1` here, not (invalid) things";
+        assert!(!is_acknowledged_invalid_example(line, end));
+
+        // Heuristic tokens are not explicit markers
+        assert!(!is_acknowledged_invalid_example(
+            "\u{274c} gts.x.core.type.v1",
+            "\u{274c} gts.x.core.type.v1".len()
+        ));
+    }
+
     #[test]
     fn test_is_bad_example_context_same_line_only() {
         // Skip token before the match
@@ -305,4 +369,26 @@ mod tests {
             "The error handling uses ".len()
         ));
     }
+
+    #[test]
+    fn test_vendor_of() {
+        assert_eq!(
+            vendor_of("gts.x.core.events.type.v1~").as_deref(),
+            Some("x")
+        );
+        assert_eq!(
+            vendor_of("gts.acme.core.events.type.v1~x.y.z.w.v1~").as_deref(),
+            Some("acme")
+        );
+        // Malformed chain still attributes the first segment's vendor
+        assert_eq!(
+            vendor_of("gts.acme.core.events.type.v1~bad").as_deref(),
+            Some("acme")
+        );
+        assert_eq!(vendor_of("gts.x.core.*").as_deref(), Some("x"));
+        assert_eq!(vendor_of("gts.*"), None);
+        assert_eq!(vendor_of("gts.x.core"), None);
+        assert_eq!(vendor_of("x.core.events.type.v1~"), None);
+        assert_eq!(vendor_of(""), None);
+    }
 }
@@ -168,3 +168,408 @@ fn cli_uses_default_paths_when_none_provided() {
     assert!(stdout.contains("\"ok\": true"), "stdout: {stdout}");
     assert!(stdout.contains("\"scanned_files\": 1"), "stdout: {stdout}");
 }
+
+#[test]
+fn cli_skip_comments_flag_ignores_comment_values() {
+    let tmp = TempDir::new().expect("temp dir");
+    let json_file = tmp.path().join("schema.json");
+    fs::write(
+        &json_file,
+        r#"{"$id": "gts://gts.x.core.pkg.mytype.v1~", "$comment": "gts.y.core.pkg.oldtype.v1~"}"#,
+    )
+    .expect("write json");
+
+    let without_skip = Command::new(validator_bin())
+        .arg("--vendor")
+        .arg("x")
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator without --skip-comments");
+    assert!(!without_skip.status.success());
+
+    let with_skip = Command::new(validator_bin())
+        .arg("--vendor")
+        .arg("x")
+        .arg("--skip-comments")
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator with --skip-comments");
+    assert!(with_skip.status.success());
+}
+
+#[test]
+fn cli_quiet_prints_only_findings() {
+    let tmp = TempDir::new().expect("temp dir");
+    let md = tmp.path().join("test.md");
+    fs::write(&md, "Uses `gts.y.core.pkg.mytype.v1~` schema.\n").expect("write markdown");
+
+    let failing = Command::new(validator_bin())
+        .arg("--quiet")
+        .arg("--vendor")
+        .arg("x")
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator --quiet");
+    assert!(!failing.status.success());
+    let stdout = String::from_utf8(failing.stdout).expect("stdout utf-8");
+    assert_eq!(stdout.lines().count(), 1, "stdout: {stdout}");
+    assert!(stdout.contains("Vendor mismatch"), "stdout: {stdout}");
+    assert!(!stdout.contains("GTS DOCUMENTATION VALIDATOR"));
+
+    let passing = Command::new(validator_bin())
+        .arg("--quiet")
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator --quiet");
+    assert!(passing.status.success());
+    assert!(passing.stdout.is_empty());
+}
+
+#[test]
+fn cli_summary_only_prints_counts() {
+    let tmp = TempDir::new().expect("temp dir");
+    let md = tmp.path().join("test.md");
+    fs::write(
+        &md,
+        "Uses `gts.y.core.pkg.mytype.v1~` and `gts.y.core.pkg.other.v1~`.\n",
+    )
+    .expect("write markdown");
+
+    let output = Command::new(validator_bin())
+        .arg("--summary-only")
+        .arg("--vendor")
+        .arg("x")
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator --summary-only");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout utf-8");
+    assert!(stdout.contains("2 error(s)"), "stdout: {stdout}");
+    assert!(stdout.contains("vendor mismatch: 2"), "stdout: {stdout}");
+    assert!(stdout.contains("test.md: 2"), "stdout: {stdout}");
+    assert!(
+        !stdout.contains("gts.y.core.pkg.mytype.v1~"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn cli_list_vendors_prints_vendor_table() {
+    let tmp = TempDir::new().expect("temp dir");
+    let md = tmp.path().join("test.md");
+    fs::write(
+        &md,
+        "Uses `gts.y.core.pkg.mytype.v1~` and `gts.y.core.pkg.other.v1~`.\n",
+    )
+    .expect("write markdown");
+
+    let output = Command::new(validator_bin())
+        .arg("--summary-only")
+        .arg("--list-vendors")
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator --list-vendors");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout utf-8");
+    assert!(stdout.contains("0 error(s)"), "stdout: {stdout}");
+    assert!(stdout.contains("Vendors seen:"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("    y                 2  "),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn cli_quiet_conflicts_with_json() {
+    let output = Command::new(validator_bin())
+        .arg("--quiet")
+        .arg("--json")
+        .arg(".")
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn cli_no_example_heuristics_reports_emoji_marked_ids() {
+    let tmp = TempDir::new().expect("temp dir");
+    let md = tmp.path().join("examples.md");
+    fs::write(
+        &md,
+        "\u{274c} gts.y.core.pkg.mytype.v1~\n\nAlso gts.y.core.pkg.other.v1~ <!-- gts:invalid-example -->\n",
+    )
+    .expect("write markdown");
+
+    let with_heuristics = Command::new(validator_bin())
+        .arg("--json")
+        .arg("--vendor")
+        .arg("x")
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(with_heuristics.status.success());
+    let stdout = String::from_utf8(with_heuristics.stdout).expect("stdout utf-8");
+    assert!(
+        stdout.contains("\"acknowledged_invalid_examples\": 1"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("\"heuristic_example_skips\": 1"),
+        "stdout: {stdout}"
+    );
+
+    let without_heuristics = Command::new(validator_bin())
+        .arg("--vendor")
+        .arg("x")
+        .arg("--no-example-heuristics")
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator --no-example-heuristics");
+    assert!(!without_heuristics.status.success());
+    let stdout = String::from_utf8(without_heuristics.stdout).expect("stdout utf-8");
+    assert!(
+        stdout.contains("gts.y.core.pkg.mytype.v1~"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        !stdout.contains("gts.y.core.pkg.other.v1~"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn cli_baseline_splits_new_and_preexisting_findings() {
+    let tmp = TempDir::new().expect("temp dir");
+    let docs = tmp.path().join("docs");
+    fs::create_dir(&docs).expect("create docs");
+    let md = docs.join("test.md");
+    fs::write(&md, "Uses `gts.y.core.pkg.old.v1~` schema.\n").expect("write markdown");
+
+    let first = Command::new(validator_bin())
+        .args(["--json", "--vendor", "x"])
+        .arg(&docs)
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(!first.status.success());
+    let baseline = tmp.path().join("baseline.json");
+    fs::write(&baseline, &first.stdout).expect("write baseline");
+
+    // Unchanged tree passes against its own baseline
+    let unchanged = Command::new(validator_bin())
+        .args(["--vendor", "x", "--baseline"])
+        .arg(&baseline)
+        .arg(&docs)
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(unchanged.status.success());
+
+    fs::write(
+        &md,
+        "Intro line.\nUses `gts.y.core.pkg.old.v1~` schema.\nAnd `gts.z.core.pkg.new.v1~`.\n",
+    )
+    .expect("write markdown");
+    let output = Command::new(validator_bin())
+        .args(["--vendor", "x", "--baseline"])
+        .arg(&baseline)
+        .arg(&docs)
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout utf-8");
+    assert!(
+        stdout.contains("NEW SINCE BASELINE (1)"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("PRE-EXISTING (BASELINED) (1)"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("1 new invalid GTS identifier(s) found"),
+        "stdout: {stdout}"
+    );
+
+    let json = Command::new(validator_bin())
+        .args(["--json", "--vendor", "x", "--baseline"])
+        .arg(&baseline)
+        .arg(&docs)
+        .output()
+        .expect("failed to run gts-validator");
+    let stdout = String::from_utf8(json.stdout).expect("stdout utf-8");
+    assert!(stdout.contains("\"baselined\": true"), "stdout: {stdout}");
+    assert!(stdout.contains("\"baselined\": false"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("\"baseline_applied\": true"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn cli_only_code_and_only_path_narrow_the_report() {
+    let tmp = TempDir::new().expect("temp dir");
+    let docs = tmp.path().join("docs");
+    fs::create_dir(&docs).expect("create docs");
+    fs::write(docs.join("guide.md"), "Uses `gts.y.core.pkg.mytype.v1~`.\n").expect("write md");
+    fs::write(tmp.path().join("schema.json"), r#"{"$id": "gts.bad"}"#).expect("write json");
+
+    let output = Command::new(validator_bin())
+        .args(["--json", "--vendor", "x", "--only-code", "invalid_id"])
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout utf-8");
+    assert!(stdout.contains("gts.bad"), "stdout: {stdout}");
+    assert!(!stdout.contains("Vendor mismatch"), "stdout: {stdout}");
+
+    // Nothing left under docs/ once vendor mismatches are excluded
+    let output = Command::new(validator_bin())
+        .args([
+            "--vendor",
+            "x",
+            "--only-path",
+            "*/docs/*",
+            "--only-code",
+            "invalid_id",
+        ])
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(output.status.success());
+
+    let output = Command::new(validator_bin())
+        .args(["--only-code", "no_such_code"])
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn cli_rule_override_demotes_to_warning() {
+    let tmp = TempDir::new().expect("temp dir");
+    fs::write(
+        tmp.path().join("test.md"),
+        "Uses `gts.y.core.pkg.mytype.v1~` schema.\n",
+    )
+    .expect("write markdown");
+
+    let strict = Command::new(validator_bin())
+        .args(["--vendor", "x", "--quiet"])
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(!strict.status.success());
+
+    let demoted = Command::new(validator_bin())
+        .args([
+            "--vendor",
+            "x",
+            "--quiet",
+            "--rule",
+            "vendor_mismatch=warning",
+        ])
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator with --rule");
+    assert!(demoted.status.success());
+    let stdout = String::from_utf8(demoted.stdout).expect("stdout utf-8");
+    assert!(
+        stdout.contains(": warning: Vendor mismatch"),
+        "stdout: {stdout}"
+    );
+
+    let invalid = Command::new(validator_bin())
+        .args(["--rule", "vendor_mismatch=loud"])
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator with an invalid --rule");
+    assert!(!invalid.status.success());
+    let stderr = String::from_utf8(invalid.stderr).expect("stderr utf-8");
+    assert!(
+        stderr.contains("unknown severity 'loud'"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn cli_fix_rewrites_fixable_ids_and_dry_run_prints_diff() {
+    let tmp = TempDir::new().expect("temp dir");
+    let md = tmp.path().join("test.md");
+    let original = "Uses `gts.x.core.pkg.my-type.v1~` schema.\n";
+    fs::write(&md, original).expect("write markdown");
+
+    let dry_run = Command::new(validator_bin())
+        .args(["--strict", "--fix", "--dry-run"])
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(!dry_run.status.success());
+    let stdout = String::from_utf8(dry_run.stdout).expect("stdout utf-8");
+    assert!(
+        stdout.contains(
+            "-Uses `gts.x.core.pkg.my-type.v1~` schema.\n+Uses `gts.x.core.pkg.my_type.v1~` schema.\n"
+        ),
+        "stdout: {stdout}"
+    );
+    assert_eq!(fs::read_to_string(&md).expect("read markdown"), original);
+
+    let fix = Command::new(validator_bin())
+        .args(["--strict", "--fix"])
+        .arg(tmp.path())
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(fix.status.success());
+    let stderr = String::from_utf8(fix.stderr).expect("stderr utf-8");
+    assert!(
+        stderr.contains("Fixed 1 identifier(s) in 1 file(s)"),
+        "stderr: {stderr}"
+    );
+    assert_eq!(
+        fs::read_to_string(&md).expect("read markdown"),
+        "Uses `gts.x.core.pkg.my_type.v1~` schema.\n"
+    );
+}
+
+#[test]
+fn cli_config_file_supplies_defaults_flags_override() {
+    let tmp = TempDir::new().expect("temp dir");
+    let docs = tmp.path().join("docs");
+    fs::create_dir(&docs).expect("create docs");
+    fs::write(docs.join("a.md"), "Uses `gts.y.core.pkg.mytype.v1~`.\n").expect("write markdown");
+    fs::write(
+        tmp.path().join(".gtsvalidator.toml"),
+        "paths = [\"docs\"]\nvendor = \"x\"\n",
+    )
+    .expect("write config");
+
+    // Found from a subdirectory; its paths are relative to the file
+    let output = Command::new(validator_bin())
+        .arg("--json")
+        .current_dir(&docs)
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout utf-8");
+    assert!(stdout.contains("\"scanned_files\": 1"), "stdout: {stdout}");
+    assert!(stdout.contains("Vendor mismatch"), "stdout: {stdout}");
+
+    let output = Command::new(validator_bin())
+        .args(["--json", "--vendor", "y"])
+        .current_dir(&docs)
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(output.status.success());
+
+    fs::write(tmp.path().join(".gtsvalidator.toml"), "vendor = 1\n").expect("write config");
+    let output = Command::new(validator_bin())
+        .arg(&docs)
+        .current_dir(tmp.path())
+        .output()
+        .expect("failed to run gts-validator");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).expect("stderr utf-8");
+    assert!(stderr.contains("Invalid config"), "stderr: {stderr}");
+}
@@ -0,0 +1,57 @@
+//! Runs the shared conformance vectors (`conformance/vectors`) through the
+//! validator: valid IDs produce no findings, invalid ones the expected error code.
+
+use std::fs;
+use std::path::Path;
+
+use gts::conformance::{load_vectors, vector_files};
+use gts_validator::{FsSourceConfig, ValidationConfig, validate_fs};
+use tempfile::TempDir;
+
+#[test]
+fn test_validator_matches_conformance_vectors() {
+    let vectors_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../conformance/vectors");
+    let tmp = TempDir::new().unwrap();
+
+    let mut checked = 0;
+    for file in vector_files(&vectors_dir).unwrap() {
+        // The validator only discovers strings carrying the `gts.` prefix
+        let vectors: Vec<_> = load_vectors(&file)
+            .unwrap()
+            .into_iter()
+            .filter(|v| v.id.starts_with("gts."))
+            .collect();
+        let ids: Vec<&str> = vectors.iter().map(|v| v.id.as_str()).collect();
+        let doc = tmp.path().join("vectors.json");
+        fs::write(&doc, serde_json::to_string(&ids).unwrap()).unwrap();
+
+        let mut fs_config = FsSourceConfig::default();
+        fs_config.paths = vec![doc];
+        let report = validate_fs(&fs_config, &ValidationConfig::default()).unwrap();
+        assert!(report.scan_errors.is_empty(), "{:?}", report.scan_errors);
+
+        for (i, vector) in vectors.iter().enumerate() {
+            let json_path = format!("$[{i}]");
+            let codes: Vec<&str> = report
+                .validation_errors
+                .iter()
+                .filter(|e| e.json_path == json_path)
+                .map(gts_validator::ValidationError::error_code)
+                .collect();
+            if vector.valid {
+                assert!(codes.is_empty(), "{}: unexpected {codes:?}", vector.id);
+            } else {
+                assert!(!codes.is_empty(), "{}: not reported", vector.id);
+                if let Some(expected) = &vector.error {
+                    assert!(
+                        codes.contains(&expected.as_str()),
+                        "{}: expected {expected}, got {codes:?}",
+                        vector.id
+                    );
+                }
+            }
+            checked += 1;
+        }
+    }
+    assert!(checked > 0);
+}
@@ -3,7 +3,8 @@
 use std::fs;
 use std::path::PathBuf;
 
-use gts_validator::{FsSourceConfig, ValidationConfig, VendorPolicy, validate_fs};
+use gts::test_support::assert_json_contains;
+use gts_validator::{ContentFormat, FsSourceConfig, ValidationConfig, VendorPolicy, validate_fs};
 use tempfile::TempDir;
 
 fn default_validation_config() -> ValidationConfig {
@@ -54,6 +55,32 @@ fn test_validate_fs_valid_markdown() {
     assert_eq!(report.errors_count(), 0);
 }
 
+#[test]
+fn test_validate_fs_same_results_without_file_timeout() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("test.md"),
+        "# Title\n\nUses `gts.y.core.pkg.mytype.v1~` schema.\n",
+    )
+    .unwrap();
+    fs::write(tmp.path().join("bad.json"), "{ not json").unwrap();
+
+    let mut config = ValidationConfig::default();
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+
+    let mut fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    let with_budget = validate_fs(&fs_config, &config).unwrap();
+    fs_config.file_timeout = None;
+    let inline = validate_fs(&fs_config, &config).unwrap();
+
+    for report in [&with_budget, &inline] {
+        assert_eq!(report.scanned_files, 1);
+        assert_eq!(report.failed_files, 1);
+        assert_eq!(report.errors_count(), 1);
+        assert_eq!(report.scan_errors.len(), 1);
+    }
+}
+
 #[test]
 fn test_validate_fs_vendor_mismatch() {
     let tmp = TempDir::new().unwrap();
@@ -79,6 +106,31 @@ fn test_validate_fs_vendor_mismatch() {
     );
 }
 
+#[test]
+fn test_validate_fs_findings_by_vendor() {
+    let tmp = TempDir::new().unwrap();
+    let md = tmp.path().join("test.md");
+    fs::write(
+        &md,
+        "Uses `gts.y.core.pkg.mytype.v1~` and `gts.y.core.pkg.other.v1~`.\n\
+         Also `gts.z.core.pkg.mytype.v1~` and `gts.x.core.pkg.mytype.v1~`.\n",
+    )
+    .unwrap();
+
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    let mut config = ValidationConfig::default();
+    config.vendor_policy = VendorPolicy::AllowList(vec!["x".to_owned(), "z".to_owned()]);
+    let report = validate_fs(&fs_config, &config).unwrap();
+
+    assert_eq!(report.errors_count(), 2);
+    assert_eq!(report.findings_by_vendor.len(), 1);
+    assert_eq!(report.findings_by_vendor.get("y"), Some(&2));
+    assert_json_contains(
+        &report,
+        &serde_json::json!({"ok": false, "findings_by_vendor": {"y": 2}}),
+    );
+}
+
 #[test]
 fn test_validate_fs_valid_json() {
     let tmp = TempDir::new().unwrap();
@@ -121,6 +173,38 @@ fn test_validate_fs_valid_yaml() {
     );
 }
 
+#[test]
+fn test_validate_fs_rst_and_asciidoc() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("a.rst"),
+        "Uses ``gts.y.core.pkg.mytype.v1~``.\n\n.. code-block:: ebnf\n\n   id = \"gts.y.core.pkg.grammar.v1~\" ;\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("b.adoc"),
+        "Uses `gts.y.core.pkg.mytype.v1~`.\n\n[source,ebnf]\n----\nid = \"gts.y.core.pkg.grammar.v1~\" ;\n----\n",
+    )
+    .unwrap();
+
+    let mut config = ValidationConfig::default();
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    let report = validate_fs(&default_fs_config(vec![tmp.path().to_path_buf()]), &config).unwrap();
+
+    assert_eq!(report.scanned_files, 2);
+    let found: Vec<(String, usize)> = report
+        .validation_errors
+        .iter()
+        .map(|e| {
+            (
+                e.file.file_name().unwrap().to_string_lossy().into_owned(),
+                e.line,
+            )
+        })
+        .collect();
+    assert_eq!(found, [("a.rst".to_owned(), 1), ("b.adoc".to_owned(), 1)]);
+}
+
 #[test]
 fn test_validate_fs_json_output_contract() {
     let tmp = TempDir::new().unwrap();
@@ -143,6 +227,97 @@ fn test_validate_fs_json_output_contract() {
     assert!(json["ok"].as_bool().unwrap());
 }
 
+#[test]
+fn test_merge_sharded_json_reports() {
+    let tmp = TempDir::new().unwrap();
+    let docs = tmp.path().join("docs");
+    let modules = tmp.path().join("modules");
+    fs::create_dir_all(&docs).unwrap();
+    fs::create_dir_all(&modules).unwrap();
+    fs::write(docs.join("a.md"), "Uses `gts.x.core.pkg.mytype.v1~`.\n").unwrap();
+    fs::write(
+        modules.join("b.md"),
+        "Uses `gts.y.core.pkg.mytype.v1~` and `gts.y.core.pkg.other.v1~`.\n",
+    )
+    .unwrap();
+    fs::write(modules.join("c.json"), "{ not json").unwrap();
+
+    let mut config = ValidationConfig::default();
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    // Each shard's report goes through JSON, as between CI jobs
+    let shard = |dir: &PathBuf| {
+        let report = validate_fs(&default_fs_config(vec![dir.clone()]), &config).unwrap();
+        let mut buf = Vec::new();
+        gts_validator::output::write_json(&report, &mut buf).unwrap();
+        gts_validator::output::read_json(&mut buf.as_slice()).unwrap()
+    };
+    let (docs_report, modules_report) = (shard(&docs), shard(&modules));
+    assert!(docs_report.ok);
+
+    let merged = docs_report.merge(modules_report.clone());
+    assert_eq!(merged.scanned_files, 2);
+    assert_eq!(merged.failed_files, 1);
+    assert!(!merged.ok);
+    assert_eq!(merged.errors_count(), 2);
+    assert_eq!(merged.scan_errors.len(), 1);
+    assert_eq!(merged.findings_by_vendor.get("y"), Some(&2));
+
+    // A finding reported by two shards is kept once
+    let twice = merged.merge(modules_report);
+    assert_eq!(twice.errors_count(), 2);
+    assert_eq!(twice.scan_errors.len(), 1);
+    assert_eq!(twice.findings_by_vendor.get("y"), Some(&2));
+}
+
+#[test]
+fn test_validate_fs_ignore_directives() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("a.md"),
+        "<!-- gts-ignore-next-line -->\n\
+         Legacy `gts.y.core.pkg.old.v1~`.\n\
+         <!-- gts-ignore: gts.y.core.pkg.other.v1~ -->\n\
+         Also `gts.y.core.pkg.other.v1~` and `gts.y.core.pkg.kept.v1~`.\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("b.json"),
+        r#"{"legacy": {"x-gts-ignore": "gts.y.core.pkg.old.v1~", "$ref": "gts.y.core.pkg.old.v1~"}}"#,
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("c.yaml"),
+        "a: gts.y.core.pkg.old.v1~\n---\nx-gts-ignore: [gts.y.core.pkg.old.v1~]\nb: gts.y.core.pkg.old.v1~\n",
+    )
+    .unwrap();
+
+    let mut config = ValidationConfig::default();
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    let report = validate_fs(&default_fs_config(vec![tmp.path().to_path_buf()]), &config).unwrap();
+
+    let mut reported: Vec<String> = report
+        .validation_errors
+        .iter()
+        .map(|e| {
+            format!(
+                "{}:{}",
+                e.file.file_name().unwrap().to_string_lossy(),
+                e.normalized_id
+            )
+        })
+        .collect();
+    reported.sort();
+    // A directive only covers its line, its ID or its YAML document
+    assert_eq!(
+        reported,
+        [
+            "a.md:gts.y.core.pkg.kept.v1~",
+            "c.yaml:gts.y.core.pkg.old.v1~"
+        ]
+    );
+    assert_eq!(report.suppressed_findings, 4);
+}
+
 #[test]
 fn test_validate_fs_exclude_pattern() {
     let tmp = TempDir::new().unwrap();
@@ -345,6 +520,119 @@ fn test_validate_fs_invalid_yaml_produces_scan_error() {
     assert!(!report.ok, "Scan errors must make the report not-ok");
 }
 
+#[test]
+fn test_validate_fs_fingerprint_survives_line_shifts() {
+    let tmp = TempDir::new().unwrap();
+    let md = tmp.path().join("test.md");
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+
+    fs::write(&md, "Uses `gts.y.core.pkg.mytype.v1~`.\n").unwrap();
+    let mut config = default_validation_config();
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    let before = validate_fs(&fs_config, &config).unwrap();
+
+    fs::write(
+        &md,
+        "# Title\n\nSome intro.\n\nUses `gts.y.core.pkg.mytype.v1~`.\n",
+    )
+    .unwrap();
+    let after = validate_fs(&fs_config, &config).unwrap();
+
+    assert_eq!(before.errors_count(), 1);
+    assert_eq!(after.errors_count(), 1);
+    assert_ne!(
+        before.validation_errors[0].line,
+        after.validation_errors[0].line
+    );
+    assert_eq!(
+        before.validation_errors[0].fingerprint,
+        after.validation_errors[0].fingerprint
+    );
+}
+
+#[test]
+fn test_validate_fs_format_fallback() {
+    let tmp = TempDir::new().unwrap();
+    // YAML in a .json file, and a .md file that is a JSON document
+    fs::write(
+        tmp.path().join("config.json"),
+        "id: gts.x.core.pkg.mytype.v1~\nref: gts.bad\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("snippet.md"),
+        "{\n  \"$id\": \"gts://gts.x.core.pkg.mytype.v1~\",\n  \"ref\": \"gts.y.core.pkg.other\"\n}\n",
+    )
+    .unwrap();
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+
+    let report = validate_fs(&fs_config, &default_validation_config()).unwrap();
+    assert_eq!(report.failed_files, 1, "fallback is off by default");
+    assert!(report.format_fallbacks.is_empty());
+
+    let mut config = default_validation_config();
+    config.format_fallback = true;
+    let report = validate_fs(&fs_config, &config).unwrap();
+    assert_eq!(
+        report.failed_files, 0,
+        "scan errors: {:?}",
+        report.scan_errors
+    );
+    assert_eq!(report.scanned_files, 2);
+
+    let mut fallbacks: Vec<_> = report
+        .format_fallbacks
+        .iter()
+        .map(|f| {
+            (
+                f.file.file_name().unwrap().to_string_lossy().into_owned(),
+                f.expected,
+                f.parsed_as,
+            )
+        })
+        .collect();
+    fallbacks.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        fallbacks,
+        vec![
+            (
+                "config.json".to_owned(),
+                ContentFormat::Json,
+                ContentFormat::Yaml
+            ),
+            (
+                "snippet.md".to_owned(),
+                ContentFormat::Markdown,
+                ContentFormat::Json
+            ),
+        ]
+    );
+    // Findings come from the fallback parser, with JSON paths
+    assert!(
+        report
+            .validation_errors
+            .iter()
+            .any(|e| e.json_path == "$.ref" && e.file.ends_with("snippet.md")),
+        "errors: {:?}",
+        report.validation_errors
+    );
+}
+
+#[test]
+fn test_validate_fs_format_fallback_keeps_primary_error() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(tmp.path().join("bad.json"), "{ not: [valid").unwrap();
+
+    let mut config = default_validation_config();
+    config.format_fallback = true;
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    let report = validate_fs(&fs_config, &config).unwrap();
+
+    assert_eq!(report.failed_files, 1);
+    assert!(report.scan_errors[0].message.contains("JSON parse error"));
+    assert!(report.format_fallbacks.is_empty());
+}
+
 #[test]
 fn test_validate_fs_skip_tokens_integration() {
     let tmp = TempDir::new().unwrap();
@@ -378,3 +666,859 @@ fn test_validate_fs_skip_tokens_integration() {
         report_skip.validation_errors
     );
 }
+
+#[test]
+fn test_validate_fs_ref_form_paths() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("schema.json"),
+        r#"{
+  "properties": {"type": {"x-gts-ref": "gts.x.core.events.type.v1~x.app._.orders.v1"}},
+  "target": "gts.x.core.events.topic.v1~"
+}"#,
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("config.yaml"),
+        "extends:\n  - gts.x.core.events.type.v1~\n  - gts.x.core.events.type.v1~x.app._.orders.v1\n",
+    )
+    .unwrap();
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+
+    let report = validate_fs(&fs_config, &default_validation_config()).unwrap();
+    assert!(report.ok, "forms are not enforced by default");
+
+    let mut config = default_validation_config();
+    config.type_ref_paths = vec!["x-gts-ref".to_owned(), "$.extends".to_owned()];
+    config.instance_ref_paths = vec!["$.target".to_owned()];
+    let report = validate_fs(&fs_config, &config).unwrap();
+
+    let mut found: Vec<(&str, &str)> = report
+        .validation_errors
+        .iter()
+        .map(|e| (e.json_path.as_str(), e.error_code()))
+        .collect();
+    found.sort_unstable();
+    assert_eq!(
+        found,
+        vec![
+            ("$.extends[1]", "expected_type_id"),
+            ("$.properties.type.x-gts-ref", "expected_type_id"),
+            ("$.target", "expected_instance_id"),
+        ]
+    );
+}
+
+#[test]
+fn test_validate_fs_reserved_definitions() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("schema.json"),
+        r#"{"$id": "gts://gts.x.core.events.type.v1~", "type": "object"}"#,
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("instance.yaml"),
+        "id: gts.x.core.events.type.v1~x.app._.orders.v1\n",
+    )
+    .unwrap();
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+
+    let report = validate_fs(&fs_config, &default_validation_config()).unwrap();
+    assert!(report.ok, "reservations are not enforced by default");
+
+    let mut config = default_validation_config();
+    config.reservations = gts_id::GtsReservations::new().reserve("x", Some("core"), &["platform"]);
+    config.reservation_owner = Some("app".to_owned());
+    let report = validate_fs(&fs_config, &config).unwrap();
+    assert!(!report.ok);
+    assert_eq!(report.errors_count(), 1, "{:?}", report.validation_errors);
+    let error = &report.validation_errors[0];
+    assert!(error.file.ends_with("schema.json"));
+    assert_eq!(error.json_path, "$.$id");
+    assert_eq!(error.error_code(), "reserved_name");
+
+    config.reservation_owner = Some("platform".to_owned());
+    assert!(validate_fs(&fs_config, &config).unwrap().ok);
+}
+
+#[test]
+fn test_validate_fs_context_window() {
+    let tmp = TempDir::new().unwrap();
+    let table = "| Event | Type |\n|---|---|\n| placed | `gts.x.core.events.type.v1~` |\n| broken | `gts.x.core.events.type.v1.0.0~` |\n| refunded | `gts.x.core.events.type.v1~` |\n";
+    fs::write(tmp.path().join("events.md"), table).unwrap();
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+
+    let mut config = default_validation_config();
+    config.discovery_mode = gts_validator::DiscoveryMode::Heuristic;
+    let report = validate_fs(&fs_config, &config).unwrap();
+    assert_eq!(report.errors_count(), 1, "{:?}", report.validation_errors);
+    assert!(report.validation_errors[0].context_window.is_none());
+
+    config.context_lines = 1;
+    let report = validate_fs(&fs_config, &config).unwrap();
+    let error = &report.validation_errors[0];
+    assert_eq!(error.line, 4);
+    let window = error.context_window.as_ref().unwrap();
+    assert_eq!(window.start_line, 3);
+    assert_eq!(window.lines.len(), 3);
+    assert!(window.lines[2].contains("refunded"));
+}
+
+#[test]
+fn test_validate_fs_auto_discovery_escalates_per_file() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("clean.md"),
+        "Uses `gts.x.core.events.type.v1~` only.\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("suspect.md"),
+        "Uses `gts.x.core.events.type.v1~` and `gts.x.core.events.type.name.v1~`.\n",
+    )
+    .unwrap();
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+
+    let mut config = default_validation_config();
+    let strict = validate_fs(&fs_config, &config).unwrap();
+    config.discovery_mode = gts_validator::DiscoveryMode::Heuristic;
+    let heuristic = validate_fs(&fs_config, &config).unwrap();
+    config.discovery_mode = gts_validator::DiscoveryMode::Auto;
+    let auto = validate_fs(&fs_config, &config).unwrap();
+
+    assert!(strict.ok, "{:?}", strict.validation_errors);
+    assert!(!auto.ok);
+    assert_eq!(auto.errors_count(), heuristic.errors_count());
+    assert!(
+        auto.validation_errors
+            .iter()
+            .all(|e| e.file.ends_with("suspect.md")),
+        "{:?}",
+        auto.validation_errors
+    );
+}
+
+#[test]
+fn test_validate_fs_streams_large_files() {
+    use std::fmt::Write;
+
+    let tmp = TempDir::new().unwrap();
+    let mut json = String::from("{\"items\": [");
+    for i in 0..2_000 {
+        write!(
+            json,
+            "{{\"n\": {i}, \"type\": \"gts.x.core.events.type.v1~\"}},"
+        )
+        .unwrap();
+    }
+    json.push_str("{\"type\": \"gts.x.core.events.type.v1.0.0~\"}]}");
+    fs::write(tmp.path().join("manifest.json"), &json).unwrap();
+    fs::write(
+        tmp.path().join("manifest.yaml"),
+        "a: gts.x.core.events.type.v1~\n---\nb: gts.invalid\n",
+    )
+    .unwrap();
+
+    let mut fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    fs_config.max_file_size = 1_024;
+    let report = validate_fs(&fs_config, &default_validation_config()).unwrap();
+    assert_eq!(report.scan_errors.len(), 1, "{:?}", report.scan_errors);
+
+    fs_config.stream_threshold = Some(16);
+    let report = validate_fs(&fs_config, &default_validation_config()).unwrap();
+    assert!(report.scan_errors.is_empty(), "{:?}", report.scan_errors);
+    assert_eq!(report.scanned_files, 2);
+    let mut paths: Vec<&str> = report
+        .validation_errors
+        .iter()
+        .map(|e| e.json_path.as_str())
+        .collect();
+    paths.sort_unstable();
+    assert_eq!(paths, ["$.b", "$.items[2000].type"]);
+}
+
+#[test]
+fn test_validate_fs_error_limits() {
+    let tmp = TempDir::new().unwrap();
+    for (name, id) in [("a", "gts.bad.a"), ("b", "gts.bad.b"), ("c", "gts.bad.c")] {
+        fs::write(
+            tmp.path().join(format!("{name}.json")),
+            format!(r#"{{"$id": "{id}", "ref": "{id}"}}"#),
+        )
+        .unwrap();
+    }
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    let full = validate_fs(&fs_config, &default_validation_config()).unwrap();
+    assert_eq!(full.errors_count(), 6);
+    assert!(!full.truncated);
+
+    let mut config = default_validation_config();
+    config.max_errors = Some(3);
+    let report = validate_fs(&fs_config, &config).unwrap();
+    assert_eq!(report.errors_count(), 3);
+    assert_eq!(report.scanned_files, 2);
+    assert!(report.truncated);
+    assert!(!report.ok);
+
+    // Baselined findings do not count towards the limit
+    let mut config = default_validation_config();
+    config.fail_fast = true;
+    config.baseline = Some(gts_validator::Baseline::from_errors(
+        &full.validation_errors[..2],
+    ));
+    let report = validate_fs(&fs_config, &config).unwrap();
+    assert_eq!(report.errors_count(), 3);
+    assert_eq!(report.new_errors_count(), 1);
+    assert!(report.truncated);
+
+    let mut buf = Vec::new();
+    gts_validator::output::write_human(&report, &mut buf).unwrap();
+    let output = String::from_utf8(buf).unwrap();
+    assert!(output.contains("stopped at the error limit"), "{output}");
+
+    // Reaching the limit on the last finding of the last file loses nothing
+    let mut config = default_validation_config();
+    config.max_errors = Some(6);
+    assert!(!validate_fs(&fs_config, &config).unwrap().truncated);
+}
+
+#[test]
+fn test_write_human_localized() {
+    let tmp = TempDir::new().unwrap();
+    let md = tmp.path().join("test.md");
+    fs::write(&md, "Uses `gts.y.core.pkg.mytype.v1~` schema.\n").unwrap();
+
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    let mut config = ValidationConfig::default();
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    let report = validate_fs(&fs_config, &config).unwrap();
+
+    let catalog = gts_validator::MessageCatalog::from_yaml(
+        "section.errors: FEHLER\n\
+         hint.vendor_mismatch: Erwarteten Vendor verwenden\n\
+         explain.vendor_mismatch: Fremder Vendor\n\
+         class.vendor_mismatch: Vendor\n",
+    )
+    .unwrap();
+    let mut buf = Vec::new();
+    gts_validator::output::write_human_localized(
+        &report,
+        &mut buf,
+        gts_validator::output::HumanOutputMode::Full,
+        &catalog,
+    )
+    .unwrap();
+    let output = String::from_utf8(buf).unwrap();
+    assert!(output.contains("  FEHLER\n"), "{output}");
+    assert!(output.contains("  = Fremder Vendor\n"), "{output}");
+    assert!(
+        output.contains("    - Erwarteten Vendor verwenden\n"),
+        "{output}"
+    );
+    // Untranslated keys stay English
+    assert!(output.contains("Files scanned:  1"), "{output}");
+
+    let mut buf = Vec::new();
+    gts_validator::output::write_human_localized(
+        &report,
+        &mut buf,
+        gts_validator::output::HumanOutputMode::SummaryOnly,
+        &catalog,
+    )
+    .unwrap();
+    let output = String::from_utf8(buf).unwrap();
+    assert!(output.contains("    Vendor: 1\n"), "{output}");
+}
+
+#[test]
+fn test_report_filter() {
+    use gts_validator::{ReportFilter, Severity};
+
+    let tmp = TempDir::new().unwrap();
+    let docs = tmp.path().join("docs");
+    fs::create_dir(&docs).unwrap();
+    fs::write(
+        docs.join("guide.md"),
+        "Uses `gts.y.core.pkg.mytype.v1~` and `gts.z.core.pkg.other.v1~`.\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("schema.json"),
+        r#"{"$id": "gts.bad", "ref": "gts.y.core.pkg.mytype.v1~"}"#,
+    )
+    .unwrap();
+
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    let mut config = default_validation_config();
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    let full = validate_fs(&fs_config, &config).unwrap();
+    assert_eq!(full.errors_count(), 4);
+
+    let report = full.filtered(&ReportFilter::new().code("vendor_mismatch"));
+    assert_eq!(report.errors_count(), 3);
+    assert!(!report.ok);
+    assert_eq!(report.scanned_files, full.scanned_files);
+
+    let report = full.filtered(&ReportFilter::new().path("*/docs/*").unwrap().vendor("y"));
+    assert_eq!(report.errors_count(), 1);
+    assert_eq!(report.findings_by_vendor.get("y"), Some(&1));
+    assert!(!report.findings_by_vendor.contains_key("z"));
+
+    // Only scan errors, of which there are none
+    let report = full.filtered(&ReportFilter::new().severity(Severity::Fatal));
+    assert_eq!(report.errors_count(), 0);
+    assert!(report.ok);
+
+    config.baseline = Some(gts_validator::Baseline::from_errors(
+        &full.validation_errors[..1],
+    ));
+    let baselined = validate_fs(&fs_config, &config).unwrap();
+    let report = baselined.filtered(&ReportFilter::new().baselined(true));
+    assert_eq!(report.errors_count(), 1);
+    assert!(report.ok);
+    assert_eq!(
+        baselined
+            .filtered(&ReportFilter::new().baselined(false))
+            .new_errors_count(),
+        3
+    );
+
+    assert!(ReportFilter::new().path("docs/[").is_err());
+}
+
+#[test]
+fn test_validate_fs_incremental_cache() {
+    let tmp = TempDir::new().unwrap();
+    let docs = tmp.path().join("docs");
+    fs::create_dir(&docs).unwrap();
+    fs::write(docs.join("a.json"), r#"{"$id": "gts.bad.a"}"#).unwrap();
+    fs::write(
+        docs.join("b.json"),
+        r#"{"$id": "gts://gts.x.core.events.type.v1~"}"#,
+    )
+    .unwrap();
+    let cache = tmp.path().join("cache").join("validator.json");
+    let mut fs_config = default_fs_config(vec![docs.clone()]);
+    fs_config.cache = Some(cache.clone());
+    let config = default_validation_config();
+
+    let first = validate_fs(&fs_config, &config).unwrap();
+    assert!(cache.exists());
+    assert_eq!(first.scanned_files, 2);
+    assert_eq!(first.cached_files, 0);
+    assert_eq!(first.errors_count(), 1);
+
+    let second = validate_fs(&fs_config, &config).unwrap();
+    assert_eq!(second.scanned_files, 2);
+    assert_eq!(second.cached_files, 2);
+    assert_eq!(second.validation_errors, first.validation_errors);
+
+    // A changed file is scanned again, the other one still comes from the cache
+    fs::write(
+        docs.join("b.json"),
+        r#"{"$id": "gts://gts.x.core.events.type.v1~", "ref": "gts.bad.b"}"#,
+    )
+    .unwrap();
+    let third = validate_fs(&fs_config, &config).unwrap();
+    assert_eq!(third.cached_files, 1);
+    assert_eq!(third.errors_count(), 2);
+
+    // The baseline still applies to cached findings
+    let mut baselined = default_validation_config();
+    baselined.baseline = Some(gts_validator::Baseline::from_errors(
+        &third.validation_errors,
+    ));
+    let report = validate_fs(&fs_config, &baselined).unwrap();
+    assert_eq!(report.cached_files, 2);
+    assert_eq!(report.new_errors_count(), 0);
+    assert!(report.ok);
+
+    // Other scan settings discard the cache
+    let mut strict = default_validation_config();
+    strict.vendor_policy = VendorPolicy::MustMatch("y".to_owned());
+    let report = validate_fs(&fs_config, &strict).unwrap();
+    assert_eq!(report.cached_files, 0);
+    assert!(report.errors_count() > 2);
+}
+
+#[test]
+fn test_validate_fs_with_memory_host() {
+    use gts_validator::host::{ManualClock, MemoryFs, OsFs};
+    use gts_validator::{Host, ScanErrorKind, validate_fs_with};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let memory = Arc::new(MemoryFs::new());
+    memory.insert("repo/docs/a.json", r#"{"$id": "gts.bad.a"}"#);
+    memory.insert("repo/docs/b.md", "Uses `gts.x.core.pkg.mytype.v1~`.\n");
+    memory.insert("repo/node_modules/c.json", r#"{"$id": "gts.bad.c"}"#);
+    let host = Host::deterministic(memory.clone(), Arc::new(ManualClock::new()));
+
+    let mut fs_config = default_fs_config(vec![PathBuf::from("repo")]);
+    fs_config.cache = Some(PathBuf::from("cache/validator.json"));
+    let config = default_validation_config();
+
+    let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+    assert_eq!(report.scanned_files, 2);
+    assert_eq!(report.errors_count(), 1);
+    assert_eq!(
+        report.validation_errors[0].file,
+        PathBuf::from("repo/docs/a.json")
+    );
+    // The cache is written to the host, not the disk
+    assert!(memory.contents("cache/validator.json").is_some());
+    assert!(!PathBuf::from("cache/validator.json").exists());
+
+    let again = validate_fs_with(&fs_config, &config, &host).unwrap();
+    assert_eq!(again.cached_files, 2);
+    assert_eq!(again.validation_errors, report.validation_errors);
+    memory.insert(
+        "repo/docs/a.json",
+        r#"{"$id": "gts.x.core.pkg.mytype.v1~"}"#,
+    );
+    let fixed = validate_fs_with(&fs_config, &config, &host).unwrap();
+    assert_eq!(fixed.cached_files, 1);
+    assert!(fixed.ok);
+
+    // Every scan takes two seconds on this clock
+    let slow = Host::deterministic(
+        memory,
+        Arc::new(ManualClock::with_tick(Duration::from_secs(2))),
+    );
+    fs_config.cache = None;
+    fs_config.file_timeout = Some(Duration::from_secs(1));
+    let report = validate_fs_with(&fs_config, &config, &slow).unwrap();
+    assert_eq!(report.scan_errors.len(), 2);
+    assert!(
+        report
+            .scan_errors
+            .iter()
+            .all(|e| e.kind == ScanErrorKind::Timeout)
+    );
+
+    fs_config.paths = vec![PathBuf::from("repo/missing")];
+    assert!(validate_fs_with(&fs_config, &config, &host).is_err());
+
+    // A deterministic host over the real filesystem matches validate_fs
+    let tmp = TempDir::new().unwrap();
+    fs::write(tmp.path().join("a.json"), r#"{"$id": "gts.bad.a"}"#).unwrap();
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    let host = Host::deterministic(Arc::new(OsFs), Arc::new(ManualClock::new()));
+    assert_eq!(
+        validate_fs_with(&fs_config, &config, &host)
+            .unwrap()
+            .validation_errors,
+        validate_fs(&fs_config, &config).unwrap().validation_errors
+    );
+}
+
+#[test]
+fn test_write_sarif() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(tmp.path().join("a.json"), "{\n  \"$id\": \"gts.bad.a\"\n}").unwrap();
+    fs::write(tmp.path().join("b.json"), "{").unwrap();
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    let report = validate_fs(&fs_config, &default_validation_config()).unwrap();
+
+    let mut buf = Vec::new();
+    gts_validator::output::write_sarif(&report, &mut buf).unwrap();
+    let log: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "gts-validator");
+    assert_eq!(run["results"].as_array().unwrap().len(), 1);
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "invalid_id");
+    let location = &result["locations"][0]["physicalLocation"];
+    assert!(
+        location["artifactLocation"]["uri"]
+            .as_str()
+            .unwrap()
+            .ends_with("/a.json")
+    );
+    assert_eq!(location["region"]["startLine"], 2);
+    assert_eq!(
+        result["partialFingerprints"]["gtsFingerprint/v1"],
+        report.validation_errors[0].fingerprint.as_str()
+    );
+    assert!(result.get("baselineState").is_none());
+    let invocation = &run["invocations"][0];
+    assert_eq!(invocation["executionSuccessful"], false);
+    assert_eq!(
+        invocation["toolExecutionNotifications"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_validate_fs_per_path_summaries() {
+    use gts_validator::host::{ManualClock, MemoryFs};
+    use gts_validator::{Host, ReportFilter, validate_fs_with};
+    use std::sync::Arc;
+
+    let memory = Arc::new(MemoryFs::new());
+    memory.insert("docs/a.md", "Uses `gts.x.core.pkg.mytype.v1~`.\n");
+    memory.insert(
+        "modules/b.json",
+        r#"{"$id": "gts.bad.b", "ref": "gts.bad.c"}"#,
+    );
+    memory.insert("schemas/c.json", "{");
+    memory.insert("schemas/d.yaml", "$id: gts.x.core.pkg.mytype.v1~\n");
+    let host = Host::deterministic(memory, Arc::new(ManualClock::new()));
+    let config = default_validation_config();
+
+    let fs_config = default_fs_config(vec![PathBuf::from("docs")]);
+    let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+    assert!(report.paths.is_empty());
+
+    let fs_config = default_fs_config(vec![
+        PathBuf::from("docs"),
+        PathBuf::from("modules"),
+        PathBuf::from("schemas"),
+    ]);
+    let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+    let totals: Vec<_> = report
+        .paths
+        .iter()
+        .map(|p| {
+            (
+                p.path.to_str().unwrap(),
+                p.scanned_files,
+                p.failed_files,
+                p.errors,
+                p.ok,
+            )
+        })
+        .collect();
+    assert_eq!(
+        totals,
+        [
+            ("docs", 1, 0, 0, true),
+            ("modules", 1, 0, 2, false),
+            ("schemas", 1, 1, 0, false),
+        ]
+    );
+
+    // Filtering recounts findings but keeps file counts
+    let filtered = report.filtered(&ReportFilter::new().path("schemas/*").unwrap());
+    assert_eq!(filtered.paths[1].errors, 0);
+    assert!(filtered.paths[1].ok);
+    assert_eq!(filtered.paths[1].scanned_files, 1);
+    assert!(!filtered.paths[2].ok);
+
+    let mut buf = Vec::new();
+    gts_validator::output::write_human(&report, &mut buf).unwrap();
+    let output = String::from_utf8(buf).unwrap();
+    assert!(output.contains("  By path:\n"), "{output}");
+    assert!(
+        output.contains("    path     scanned  failed  errors\n"),
+        "{output}"
+    );
+    assert!(
+        output.contains("    modules        1       0       2  \u{2717}\n"),
+        "{output}"
+    );
+    assert!(
+        output.contains("    docs           1       0       0  \u{2713}\n"),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_validate_fs_collects_vendors_under_any_policy() {
+    use gts_validator::host::{ManualClock, MemoryFs};
+    use gts_validator::{Host, validate_fs_with};
+    use std::sync::Arc;
+
+    let memory = Arc::new(MemoryFs::new());
+    memory.insert(
+        "docs/a.md",
+        "Uses `gts.x.core.pkg.mytype.v1~` and `gts.acme.core.pkg.demo.v1~`.\n\
+         Then `gts.y.core.pkg.other.v1~` and `gts.x.core.pkg.other.v1~`.\n",
+    );
+    memory.insert("docs/b.json", r#"{"$id": "gts.y.core.pkg.mytype.v1~"}"#);
+    let host = Host::deterministic(memory, Arc::new(ManualClock::new()));
+    let fs_config = default_fs_config(vec![PathBuf::from("docs")]);
+
+    let mut config = default_validation_config();
+    let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+    assert!(report.vendors.is_empty());
+
+    config.collect_vendors = true;
+    let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+    assert!(report.ok, "{:?}", report.validation_errors);
+    assert_eq!(report.errors_count(), 0);
+    let vendors: Vec<_> = report
+        .vendors
+        .iter()
+        .map(|v| {
+            (
+                v.vendor.as_str(),
+                v.occurrences,
+                v.file.to_str().unwrap(),
+                v.line,
+            )
+        })
+        .collect();
+    assert_eq!(
+        vendors,
+        [("x", 2, "docs/a.md", 1), ("y", 2, "docs/a.md", 2)]
+    );
+    assert_json_contains(
+        &report,
+        &serde_json::json!({"vendors": [
+            {"vendor": "x", "occurrences": 2, "file": "docs/a.md", "line": 1, "column": 7},
+            {"vendor": "y", "occurrences": 2, "file": "docs/a.md", "line": 2, "column": 7},
+        ]}),
+    );
+
+    let mut buf = Vec::new();
+    gts_validator::output::write_human(&report, &mut buf).unwrap();
+    let output = String::from_utf8(buf).unwrap();
+    assert!(output.contains("  Vendors seen:\n"), "{output}");
+    assert!(
+        output.contains("    vendor  occurrences  first seen\n"),
+        "{output}"
+    );
+    assert!(
+        output.contains("    x                 2  docs/a.md:1:7\n"),
+        "{output}"
+    );
+
+    // Enforcing policies report mismatches as usual and list nothing
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+    assert!(report.vendors.is_empty());
+    assert_eq!(report.errors_count(), 2);
+}
+
+#[test]
+fn test_validate_fs_collects_ids_under_any_policy() {
+    use gts_validator::host::{ManualClock, MemoryFs};
+    use gts_validator::{Host, validate_fs_with};
+    use std::sync::Arc;
+
+    let memory = Arc::new(MemoryFs::new());
+    memory.insert(
+        "docs/a.md",
+        "Uses `gts.x.core.pkg.mytype.v1~` and `gts.acme.core.pkg.demo.v1~`.\n",
+    );
+    memory.insert(
+        "docs/b.json",
+        r#"{"$ref": "gts://gts.y.core.pkg.mytype.v1~"}"#,
+    );
+    let host = Host::deterministic(memory, Arc::new(ManualClock::new()));
+    let fs_config = default_fs_config(vec![PathBuf::from("docs")]);
+
+    let mut config = default_validation_config();
+    config.collect_ids = true;
+    let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+    assert!(report.ok, "{:?}", report.validation_errors);
+    assert!(report.vendors.is_empty());
+    let ids: Vec<_> = report
+        .ids
+        .iter()
+        .map(|o| (o.id.as_str(), o.raw_value.as_str(), o.json_path.as_str()))
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            ("gts.x.core.pkg.mytype.v1~", "gts.x.core.pkg.mytype.v1~", ""),
+            (
+                "gts.y.core.pkg.mytype.v1~",
+                "gts://gts.y.core.pkg.mytype.v1~",
+                "$.$ref"
+            ),
+        ]
+    );
+    assert_eq!((report.ids[0].line, report.ids[0].column), (1, 7));
+
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+    assert!(report.ids.is_empty());
+    assert_eq!(report.errors_count(), 1);
+}
+
+#[test]
+fn test_validate_fs_rule_overrides() {
+    use gts_validator::{ReportFilter, RuleId, Severity};
+
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("a.md"),
+        "Uses `gts.y.core.pkg.mytype.v1~` and `gts.z.core.pkg.other.v1~`.\n",
+    )
+    .unwrap();
+    fs::write(tmp.path().join("b.json"), r#"{"$id": "gts.bad"}"#).unwrap();
+
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    let mut config = default_validation_config();
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    let report = validate_fs(&fs_config, &config).unwrap();
+    assert!(!report.ok);
+    assert!(
+        report
+            .validation_errors
+            .iter()
+            .all(|e| e.severity == Severity::Error)
+    );
+
+    config
+        .rule_overrides
+        .insert(RuleId::VendorMismatch, Severity::Warning);
+    // Warnings do not count towards the error limit
+    config.max_errors = Some(1);
+    let report = validate_fs(&fs_config, &config).unwrap();
+    assert_eq!(report.errors_count(), 3);
+    assert!(!report.truncated);
+    assert!(!report.ok);
+    assert_eq!(report.failing_errors_count(), 1);
+    assert_eq!(report.severity_count(Severity::Warning), 2);
+    assert_eq!(report.validation_errors[0].rule(), RuleId::VendorMismatch);
+
+    config
+        .rule_overrides
+        .insert(RuleId::InvalidId, Severity::Info);
+    let report = validate_fs(&fs_config, &config).unwrap();
+    assert!(report.ok);
+    let warnings = report.filtered(&ReportFilter::new().severity(Severity::Warning));
+    assert_eq!(warnings.errors_count(), 2);
+
+    let json = serde_json::to_value(&report).unwrap();
+    let severities: Vec<&str> = json["validation_errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["severity"].as_str().unwrap())
+        .collect();
+    assert_eq!(severities, ["warning", "warning", "info"]);
+
+    let mut buf = Vec::new();
+    gts_validator::output::write_human(&report, &mut buf).unwrap();
+    let output = String::from_utf8(buf).unwrap();
+    assert!(output.contains("    warnings:     2\n"), "{output}");
+    assert!(output.contains(": info: "), "{output}");
+    assert!(
+        output.contains("(3 warning/info finding(s) do not fail the run)"),
+        "{output}"
+    );
+
+    assert_eq!("reserved_name".parse::<RuleId>(), Ok(RuleId::ReservedName));
+    assert!("deprecated".parse::<RuleId>().is_err());
+}
+
+#[test]
+fn test_validate_fs_min_confidence() {
+    use gts_validator::Severity;
+
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("a.md"),
+        "Emit `gts.x.core.events.typ-e.v1~` events.\n\
+         Stored in schemas/gts.x.core.events.sta-te.v1~.json\n",
+    )
+    .unwrap();
+
+    let fs_config = default_fs_config(vec![tmp.path().to_path_buf()]);
+    let mut config = default_validation_config();
+    config.discovery_mode = gts_validator::DiscoveryMode::Heuristic;
+    let report = validate_fs(&fs_config, &config).unwrap();
+    let confidences: Vec<_> = report
+        .validation_errors
+        .iter()
+        .map(|e| e.confidence)
+        .collect();
+    assert_eq!(confidences, [Some(80), Some(25)]);
+    assert_eq!(report.failing_errors_count(), 2);
+
+    config.min_confidence = Some(50);
+    let report = validate_fs(&fs_config, &config).unwrap();
+    assert_eq!(report.failing_errors_count(), 1);
+    assert_eq!(report.validation_errors[1].severity, Severity::Info);
+    assert_json_contains(
+        &report,
+        &serde_json::json!({"validation_errors": [
+            {"confidence": 80, "severity": "error"},
+            {"confidence": 25, "severity": "info"}
+        ]}),
+    );
+
+    let mut buf = Vec::new();
+    gts_validator::output::write_sarif(&report, &mut buf).unwrap();
+    let sarif: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(sarif["runs"][0]["results"][1]["rank"], 25);
+    assert_eq!(sarif["runs"][0]["results"][1]["level"], "note");
+
+    // Well-formed discovery carries no confidence and demotes nothing
+    config.discovery_mode = gts_validator::DiscoveryMode::StrictSpecOnly;
+    fs::write(
+        tmp.path().join("a.md"),
+        "Emit `gts.y.core.events.type.v1~`.\n",
+    )
+    .unwrap();
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    let report = validate_fs(&fs_config, &config).unwrap();
+    assert_eq!(report.validation_errors[0].confidence, None);
+    assert_eq!(report.failing_errors_count(), 1);
+}
+
+#[test]
+fn test_validate_fs_path_scoped_vendor_policy() {
+    use gts_validator::host::{ManualClock, MemoryFs};
+    use gts_validator::{Host, validate_fs_with};
+    use std::sync::Arc;
+
+    let memory = Arc::new(MemoryFs::new());
+    memory.insert(
+        "docs/a/one.md",
+        "Uses `gts.a.core.pkg.mytype.v1~` and `gts.b.core.pkg.mytype.v1~`.\n",
+    );
+    memory.insert(
+        "docs/b/two.json",
+        r#"{"$id": "gts.b.core.pkg.mytype.v1~", "ref": "gts.c.core.pkg.mytype.v1~"}"#,
+    );
+    memory.insert("docs/shared.md", "Uses `gts.z.core.pkg.mytype.v1~`.\n");
+    let host = Host::deterministic(memory, Arc::new(ManualClock::new()));
+
+    let fs_config = default_fs_config(vec![PathBuf::from("docs")]);
+    let mut config = default_validation_config();
+    config.vendor_policy = VendorPolicy::PathScoped(vec![
+        (
+            "docs/a/**".to_owned(),
+            VendorPolicy::MustMatch("a".to_owned()),
+        ),
+        (
+            "docs/b/**".to_owned(),
+            VendorPolicy::AllowList(vec!["b".to_owned()]),
+        ),
+    ]);
+    let report = validate_fs_with(&fs_config, &config, &host).unwrap();
+
+    // Files outside every scope accept any vendor
+    let findings: Vec<_> = report
+        .validation_errors
+        .iter()
+        .map(|e| (e.file.to_string_lossy().into_owned(), e.raw_value.as_str()))
+        .collect();
+    assert_eq!(
+        findings,
+        [
+            ("docs/a/one.md".to_owned(), "gts.b.core.pkg.mytype.v1~"),
+            ("docs/b/two.json".to_owned(), "gts.c.core.pkg.mytype.v1~"),
+        ]
+    );
+    assert_eq!(
+        report.validation_errors[0].error,
+        "Vendor mismatch: expected 'a', found 'b'"
+    );
+
+    config.vendor_policy = VendorPolicy::PathScoped(vec![(
+        "docs/[".to_owned(),
+        VendorPolicy::MustMatch("a".to_owned()),
+    )]);
+    assert!(validate_fs_with(&fs_config, &config, &host).is_err());
+}
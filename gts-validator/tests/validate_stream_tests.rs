@@ -0,0 +1,111 @@
+//! Integration tests for `gts_validator::validate_stream`.
+#![cfg(feature = "async")]
+
+use std::path::PathBuf;
+
+use futures_util::{StreamExt, stream};
+use gts_validator::{
+    ScanErrorKind, ValidationConfig, ValidationEvent, VendorPolicy, validate_stream,
+};
+
+fn item(path: &str, content: &str) -> (PathBuf, String) {
+    (PathBuf::from(path), content.to_owned())
+}
+
+#[tokio::test]
+async fn test_validate_stream_events() {
+    let items = stream::iter([
+        item("docs/a.md", "Uses `gts.y.core.pkg.mytype.v1~` schema.\n"),
+        item("schemas/b.json", "{invalid"),
+        item("notes.txt", "gts.y.core.pkg.mytype.v1~"),
+        item("c.yaml", "$id: gts.x.core.pkg.mytype.v1~\n"),
+    ]);
+    let mut config = ValidationConfig::default();
+    config.vendor_policy = VendorPolicy::MustMatch("x".to_owned());
+    let events: Vec<_> = validate_stream(items, &config, None).collect().await;
+
+    assert_eq!(events.len(), 6, "{events:?}");
+    assert!(
+        matches!(&events[0], ValidationEvent::Error(e) if e.error.contains("Vendor mismatch")),
+        "{events:?}"
+    );
+    assert_eq!(
+        events[1],
+        ValidationEvent::FileDone {
+            file: PathBuf::from("docs/a.md"),
+            scanned: true
+        }
+    );
+    assert!(
+        matches!(&events[2], ValidationEvent::ScanError(e) if e.kind == ScanErrorKind::JsonParseError),
+        "{events:?}"
+    );
+    assert!(matches!(&events[3], ValidationEvent::FileDone { .. }));
+    assert_eq!(
+        events[4],
+        ValidationEvent::FileDone {
+            file: PathBuf::from("notes.txt"),
+            scanned: false
+        }
+    );
+    assert!(matches!(
+        &events[5],
+        ValidationEvent::FileDone { scanned: true, .. }
+    ));
+
+    let json = serde_json::to_value(&events[1]).unwrap();
+    assert_eq!(json["event"], "file_done");
+}
+
+#[tokio::test]
+async fn test_validate_stream_is_incremental() {
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    let items = stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|i| (i, rx)) });
+    let mut events = Box::pin(validate_stream(items, &ValidationConfig::default(), None));
+
+    tx.send(item("a.json", r#"{"$id": "gts.bad.a"}"#))
+        .await
+        .unwrap();
+    assert!(matches!(
+        events.next().await,
+        Some(ValidationEvent::Error(_))
+    ));
+    assert!(matches!(
+        events.next().await,
+        Some(ValidationEvent::FileDone { .. })
+    ));
+
+    drop(tx);
+    assert!(events.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_validate_stream_error_limits() {
+    let items = || {
+        stream::iter(["a", "b", "c"].map(|name| {
+            let id = format!("gts.bad.{name}");
+            item(
+                &format!("{name}.json"),
+                &format!(r#"{{"$id": "{id}", "ref": "{id}"}}"#),
+            )
+        }))
+    };
+    let errors = |events: &[ValidationEvent]| {
+        events
+            .iter()
+            .filter(|e| matches!(e, ValidationEvent::Error(_)))
+            .count()
+    };
+
+    let mut config = ValidationConfig::default();
+    config.max_errors = Some(3);
+    let events: Vec<_> = validate_stream(items(), &config, None).collect().await;
+    assert_eq!(errors(&events), 3);
+    assert_eq!(events.last(), Some(&ValidationEvent::Truncated));
+
+    // Reaching the limit on the last finding of the last item loses nothing
+    config.max_errors = Some(6);
+    let events: Vec<_> = validate_stream(items(), &config, None).collect().await;
+    assert_eq!(errors(&events), 6);
+    assert!(!events.contains(&ValidationEvent::Truncated));
+}
@@ -0,0 +1,48 @@
+//! Integration tests for `gts_validator::validate_fs_watch`.
+#![cfg(feature = "watch")]
+
+use std::fs;
+use std::ops::ControlFlow;
+
+use gts_validator::{FsSourceConfig, ValidationConfig, validate_fs_watch};
+use tempfile::TempDir;
+
+#[test]
+fn test_validate_fs_watch_rescans_changed_files() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("a.md"),
+        "Uses `gts.x.core.events.type.v1~`.\n",
+    )
+    .unwrap();
+    let mut fs_config = FsSourceConfig::default();
+    fs_config.paths = vec![tmp.path().to_path_buf()];
+
+    let mut reports = Vec::new();
+    validate_fs_watch(&fs_config, &ValidationConfig::default(), |report| {
+        reports.push(report.clone());
+        if reports.len() == 1 {
+            fs::write(
+                tmp.path().join("b.md"),
+                "Uses `gts.x.core.events.type.v1.0.0~`.\n",
+            )
+            .unwrap();
+        }
+        // An editor's save may be seen before the content is complete
+        if report.ok && reports.len() < 10 {
+            ControlFlow::Continue(())
+        } else {
+            ControlFlow::Break(())
+        }
+    })
+    .unwrap();
+
+    assert!(reports[0].ok);
+    assert_eq!(reports[0].scanned_files, 1);
+    assert_eq!(reports[0].cached_files, 0);
+    let last = reports.last().unwrap();
+    assert!(!last.ok, "{reports:?}");
+    assert_eq!(last.scanned_files, 2);
+    assert_eq!(last.cached_files, 1);
+    assert!(last.validation_errors[0].file.ends_with("b.md"));
+}
@@ -0,0 +1,148 @@
+//! Where a store reads its entities from, selected by its configured path.
+//!
+//! [`GtsOps`](crate::GtsOps) (and so `gts --path` and `path` in `gts.toml`)
+//! picks the [`GtsReader`] from the location it is given:
+//!
+//! | Location | Backend |
+//! |----------|---------|
+//! | `http://...`, `https://...` | [`GtsHttpReader`](crate::GtsHttpReader), entities fetched by ID (`http` feature) |
+//! | `sled:<path>` | [`GtsStorageReader`] over a [`GtsSledStorage`](crate::GtsSledStorage) database (`sled` feature) |
+//! | anything else | [`GtsFileReader`] over the local files and directories |
+//!
+//! A backend that cannot be opened, or was not compiled in, leaves the store
+//! empty and is reported in its load errors. Other key-value stores plug in
+//! through [`GtsStorageReader`] and [`GtsStore::new`](crate::GtsStore::new).
+
+use std::path::PathBuf;
+
+use crate::entities::{GtsConfig, GtsEntity};
+use crate::files_reader::GtsFileReader;
+#[cfg(feature = "sled")]
+use crate::storage::GtsStorageReader;
+use crate::store::{GtsLoadError, GtsReader};
+
+/// A store backend parsed from the configured path(s).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GtsBackend {
+    /// Files and directories on the local file system
+    Files(Vec<String>),
+    /// Base URL serving a schema directory
+    Http(String),
+    /// sled database directory
+    Sled(PathBuf),
+}
+
+impl GtsBackend {
+    /// The backend for `paths`: a single URL or `sled:` path selects that
+    /// backend, anything else is read from the file system.
+    #[must_use]
+    pub fn parse(paths: &[String]) -> Self {
+        if let [location] = paths {
+            if location.starts_with("http://") || location.starts_with("https://") {
+                return Self::Http(location.clone());
+            }
+            if let Some(path) = location.strip_prefix("sled:") {
+                return Self::Sled(PathBuf::from(shellexpand::tilde(path).as_ref()));
+            }
+        }
+        Self::Files(paths.to_vec())
+    }
+
+    /// A reader over this backend, extracting IDs with `cfg`.
+    #[must_use]
+    pub fn reader(&self, cfg: &GtsConfig) -> Box<dyn GtsReader> {
+        match self {
+            Self::Files(paths) => Box::new(GtsFileReader::new(paths, Some(cfg.clone()))),
+            #[cfg(feature = "http")]
+            Self::Http(url) => Box::new(crate::http_reader::GtsHttpReader::new(
+                url,
+                Some(cfg.clone()),
+            )),
+            #[cfg(not(feature = "http"))]
+            Self::Http(url) => Box::new(UnavailableReader::new(
+                url,
+                "gts was built without the `http` feature",
+            )),
+            #[cfg(feature = "sled")]
+            Self::Sled(path) => match crate::storage::GtsSledStorage::open(path) {
+                Ok(storage) => Box::new(GtsStorageReader::new(Box::new(storage))),
+                Err(e) => Box::new(UnavailableReader::new(
+                    &path.to_string_lossy(),
+                    &e.to_string(),
+                )),
+            },
+            #[cfg(not(feature = "sled"))]
+            Self::Sled(path) => Box::new(UnavailableReader::new(
+                &path.to_string_lossy(),
+                "gts was built without the `sled` feature",
+            )),
+        }
+    }
+}
+
+/// Stands in for a backend that cannot be read, reporting why
+struct UnavailableReader {
+    error: GtsLoadError,
+}
+
+impl UnavailableReader {
+    fn new(location: &str, message: &str) -> Self {
+        Self {
+            error: GtsLoadError {
+                path: location.to_owned(),
+                message: message.to_owned(),
+            },
+        }
+    }
+}
+
+impl GtsReader for UnavailableReader {
+    fn iter(&mut self) -> Box<dyn Iterator<Item = GtsEntity> + '_> {
+        Box::new(std::iter::empty())
+    }
+
+    fn read_by_id(&self, _entity_id: &str) -> Option<GtsEntity> {
+        None
+    }
+
+    fn reset(&mut self) {}
+
+    fn load_errors(&self) -> Vec<GtsLoadError> {
+        vec![self.error.clone()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_selects_backend() {
+        let parse = |paths: &[&str]| {
+            GtsBackend::parse(&paths.iter().map(|&p| p.to_owned()).collect::<Vec<_>>())
+        };
+        assert_eq!(
+            parse(&["https://schemas.example.com/gts"]),
+            GtsBackend::Http("https://schemas.example.com/gts".to_owned())
+        );
+        assert_eq!(
+            parse(&["sled:/var/lib/gts.sled"]),
+            GtsBackend::Sled(PathBuf::from("/var/lib/gts.sled"))
+        );
+        assert_eq!(
+            parse(&["schemas", "https://example.com"]),
+            GtsBackend::Files(vec!["schemas".to_owned(), "https://example.com".to_owned()])
+        );
+    }
+
+    #[cfg(not(feature = "http"))]
+    #[test]
+    fn test_missing_backend_is_a_load_error() {
+        let store = crate::GtsStore::new(Some(
+            GtsBackend::Http("https://example.com".to_owned()).reader(&GtsConfig::default()),
+        ));
+        let errors = store.health().load_errors;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("`http` feature"));
+    }
+}
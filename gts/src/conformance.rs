@@ -0,0 +1,238 @@
+//! Data-driven conformance vectors for GTS identifiers.
+//!
+//! Vectors are YAML files, each holding a list of entries:
+//!
+//! ```yaml
+//! - id: gts.x.core.events.type.v1~x.app._.orders.v1.0
+//!   valid: true
+//!   segments: [x.core.events.type.v1~, x.app._.orders.v1.0]
+//! - id: gts.x.core.events.*
+//!   valid: false
+//!   error: wildcard
+//! ```
+//!
+//! The same files are meant to be shared with the other GTS implementations,
+//! so they only describe observable behaviour: validity, the segments of a
+//! valid ID and the [`error_code`] of an invalid one.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::gts::GtsWildcard;
+
+#[derive(Debug, Error)]
+pub enum ConformanceError {
+    #[error("Failed to read {path}: {cause}")]
+    Read { path: String, cause: String },
+    #[error("Invalid conformance vectors in {path}: {cause}")]
+    Parse { path: String, cause: String },
+}
+
+/// One conformance test vector.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConformanceVector {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub valid: bool,
+    /// Expected [`error_code`] of an invalid ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Expected segments of a valid ID, without the `gts.` prefix
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<String>,
+}
+
+/// A vector the current build disagrees with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConformanceFailure {
+    pub file: String,
+    pub id: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConformanceReport {
+    pub ok: bool,
+    pub files: usize,
+    pub total: usize,
+    pub passed: usize,
+    pub failures: Vec<ConformanceFailure>,
+}
+
+/// Stable code for why `id` is not a GTS ID: `wildcard` for a valid wildcard
+/// pattern, `invalid_id` otherwise. `None` if `id` is valid.
+#[must_use]
+pub fn error_code(id: &str) -> Option<&'static str> {
+    if gts_id::validate_gts_id(id, false).is_ok() {
+        None
+    } else if GtsWildcard::new(id).is_ok() {
+        Some("wildcard")
+    } else {
+        Some("invalid_id")
+    }
+}
+
+impl ConformanceVector {
+    /// Check the vector against this build, describing the first mismatch.
+    ///
+    /// # Errors
+    ///
+    /// Returns the mismatch if this build disagrees with the vector.
+    pub fn check(&self) -> Result<(), String> {
+        match (gts_id::validate_gts_id(&self.id, false), self.valid) {
+            (Ok(parsed), true) => {
+                let segments: Vec<&str> = parsed.iter().map(|s| s.raw.as_str()).collect();
+                if !self.segments.is_empty() && segments != self.segments {
+                    return Err(format!(
+                        "expected segments {:?}, got {segments:?}",
+                        self.segments
+                    ));
+                }
+                Ok(())
+            }
+            (Ok(_), false) => Err("expected an invalid ID, but it was accepted".to_owned()),
+            (Err(e), true) => Err(format!("expected a valid ID, but it was rejected: {e}")),
+            (Err(_), false) => {
+                let code = error_code(&self.id).unwrap_or_default();
+                match &self.error {
+                    Some(expected) if expected != code => {
+                        Err(format!("expected error code '{expected}', got '{code}'"))
+                    }
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Parse one vectors file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a list of vectors.
+pub fn load_vectors(path: &Path) -> Result<Vec<ConformanceVector>, ConformanceError> {
+    let text = fs::read_to_string(path).map_err(|e| ConformanceError::Read {
+        path: path.display().to_string(),
+        cause: e.to_string(),
+    })?;
+    serde_saphyr::from_str(&text).map_err(|e| ConformanceError::Parse {
+        path: path.display().to_string(),
+        cause: e.to_string(),
+    })
+}
+
+/// The `.yaml`/`.yml` files in `dir`, sorted by name.
+///
+/// # Errors
+///
+/// Returns an error if `dir` cannot be listed.
+pub fn vector_files(dir: &Path) -> Result<Vec<PathBuf>, ConformanceError> {
+    let entries = fs::read_dir(dir).map_err(|e| ConformanceError::Read {
+        path: dir.display().to_string(),
+        cause: e.to_string(),
+    })?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext == "yaml" || ext == "yml")
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Run every vector file in `dir` against this build.
+///
+/// # Errors
+///
+/// Returns an error if the directory or a vectors file cannot be loaded.
+pub fn run_conformance(dir: &Path) -> Result<ConformanceReport, ConformanceError> {
+    let mut report = ConformanceReport::default();
+    for file in vector_files(dir)? {
+        let vectors = load_vectors(&file)?;
+        report.files += 1;
+        for vector in vectors {
+            report.total += 1;
+            match vector.check() {
+                Ok(()) => report.passed += 1,
+                Err(message) => report.failures.push(ConformanceFailure {
+                    file: file.display().to_string(),
+                    id: vector.id,
+                    message,
+                }),
+            }
+        }
+    }
+    report.ok = report.failures.is_empty();
+    Ok(report)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn vector(id: &str, valid: bool) -> ConformanceVector {
+        ConformanceVector {
+            id: id.to_owned(),
+            description: None,
+            valid,
+            error: None,
+            segments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_error_code() {
+        assert_eq!(error_code("gts.x.core.events.type.v1~"), None);
+        assert_eq!(error_code("gts.x.core.events.*"), Some("wildcard"));
+        assert_eq!(error_code("gts.X.core.events.type.v1~"), Some("invalid_id"));
+    }
+
+    #[test]
+    fn test_check_reports_mismatches() {
+        assert!(vector("gts.x.core.events.type.v1~", true).check().is_ok());
+        assert!(vector("gts.x.core.events.type.v1~", false).check().is_err());
+        assert!(vector("gts.x.core.events.type~", true).check().is_err());
+
+        let mut wrong_segments = vector("gts.x.core.events.type.v1~x.app._.a.v1", true);
+        wrong_segments.segments = vec!["x.core.events.type.v1~".to_owned()];
+        let err = wrong_segments.check().unwrap_err();
+        assert!(err.contains("expected segments"), "{err}");
+
+        let mut wrong_code = vector("gts.x.core.*", false);
+        wrong_code.error = Some("invalid_id".to_owned());
+        let err = wrong_code.check().unwrap_err();
+        assert!(err.contains("got 'wildcard'"), "{err}");
+    }
+
+    #[test]
+    fn test_vendored_vectors_pass() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../conformance/vectors");
+        let report = run_conformance(&dir).unwrap();
+        assert!(report.files > 0);
+        assert!(report.ok, "{:#?}", report.failures);
+        assert_eq!(report.passed, report.total);
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_fields() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("bad.yaml");
+        fs::write(&file, "- id: gts.x.core.events.type.v1~\n  vaild: true\n").unwrap();
+        assert!(matches!(
+            load_vectors(&file),
+            Err(ConformanceError::Parse { .. })
+        ));
+        assert!(run_conformance(dir.path()).is_err());
+    }
+}
@@ -1,10 +1,32 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use thiserror::Error;
 
 use crate::gts::{GTS_URI_PREFIX, GtsID};
+use crate::migrations::GtsMigrationFn;
 use crate::path_resolver::JsonPathResolver;
+use crate::schema::GtsSchema;
 use crate::schema_cast::{GtsEntityCastResult, SchemaCastError};
+use crate::store::{GtsStore, StoreError};
+
+/// Errors returned by [`GtsEntity::as_type`].
+#[derive(Debug, Error)]
+pub enum GtsEntityTypeError {
+    #[error("Entity '{0}' is a schema, not an instance")]
+    NotAnInstance(String),
+    #[error("Entity '{0}' has no schema ID")]
+    MissingSchemaId(String),
+    #[error("Entity '{entity}' of type '{actual}' does not belong to schema '{expected}'")]
+    SchemaMismatch {
+        entity: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Failed to deserialize entity '{entity}': {cause}")]
+    Deserialize { entity: String, cause: String },
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationError {
@@ -425,7 +447,21 @@ impl GtsEntity {
         &self,
         to_schema: &GtsEntity,
         from_schema: &GtsEntity,
-        resolver: Option<&()>,
+        _resolver: Option<&()>,
+    ) -> Result<GtsEntityCastResult, SchemaCastError> {
+        self.cast_migrated(to_schema, from_schema, None)
+    }
+
+    /// Like [`cast`](Self::cast), first running `migration` on this entity's
+    /// content, see [`crate::migrations`].
+    ///
+    /// # Errors
+    /// Returns `SchemaCastError` if the cast fails.
+    pub fn cast_migrated(
+        &self,
+        to_schema: &GtsEntity,
+        from_schema: &GtsEntity,
+        migration: Option<&GtsMigrationFn>,
     ) -> Result<GtsEntityCastResult, SchemaCastError> {
         // When casting a schema, from_schema might be a standard JSON Schema (no gts_id)
         if self.is_schema
@@ -457,13 +493,13 @@ impl GtsEntity {
             .map(|g| g.id.clone())
             .unwrap_or_default();
 
-        GtsEntityCastResult::cast(
+        GtsEntityCastResult::cast_migrated(
             &from_id,
             &to_id,
             &self.content,
             &from_schema.content,
             &to_schema.content,
-            resolver,
+            migration,
         )
     }
 
@@ -622,6 +658,72 @@ impl GtsEntity {
         None
     }
 
+    /// Returns the type ID of an instance: the `type` field value, or for
+    /// well-known instances the chain without the trailing instance segment.
+    fn instance_type_id(&self) -> Option<String> {
+        self.schema_id
+            .clone()
+            .or_else(|| self.gts_id.as_ref().and_then(GtsID::get_type_id))
+    }
+
+    /// Deserializes this instance into `T` after checking that its type is
+    /// `T::SCHEMA_ID` or a schema derived from it.
+    ///
+    /// A newer minor version of the schema (or of any schema along the chain
+    /// up to `T::SCHEMA_ID`'s last segment) is accepted: an instance of
+    /// `…type.v1.1~` deserializes into a `…type.v1~` type, see
+    /// [`crate::version::is_compatible_upgrade`].
+    ///
+    /// # Errors
+    /// Returns `GtsEntityTypeError` if the entity is a schema, has no type,
+    /// belongs to another schema family, or does not deserialize into `T`.
+    pub fn as_type<T: GtsSchema + DeserializeOwned>(&self) -> Result<T, GtsEntityTypeError> {
+        let entity = self.effective_id().unwrap_or_else(|| self.label.clone());
+        if self.is_schema {
+            return Err(GtsEntityTypeError::NotAnInstance(entity));
+        }
+        let actual = self
+            .instance_type_id()
+            .ok_or_else(|| GtsEntityTypeError::MissingSchemaId(entity.clone()))?;
+        if !belongs_to_schema(&actual, T::SCHEMA_ID) {
+            return Err(GtsEntityTypeError::SchemaMismatch {
+                entity,
+                expected: T::SCHEMA_ID.to_owned(),
+                actual,
+            });
+        }
+        T::deserialize(&self.content).map_err(|e| GtsEntityTypeError::Deserialize {
+            entity,
+            cause: e.to_string(),
+        })
+    }
+
+    /// Returns a resolved trait value of this schema, or of the schema of this
+    /// instance: `x-gts-traits` are merged along the chain and filled from
+    /// trait schema defaults by [`GtsStore::resolve_traits`], which reads the
+    /// schemas from `store`.
+    ///
+    /// # Errors
+    /// Returns `StoreError::SchemaForInstanceNotFound` for an instance without
+    /// a type, and `StoreError` if the traits cannot be resolved.
+    pub fn trait_value(
+        &self,
+        store: &mut GtsStore,
+        name: &str,
+    ) -> Result<Option<Value>, StoreError> {
+        let schema_id = if self.is_schema {
+            self.gts_id.as_ref().map(|id| id.id.clone())
+        } else {
+            self.instance_type_id()
+        };
+        let schema_id = schema_id.ok_or_else(|| {
+            StoreError::SchemaForInstanceNotFound(
+                self.effective_id().unwrap_or_else(|| self.label.clone()),
+            )
+        })?;
+        store.trait_value(&schema_id, name)
+    }
+
     /// Returns the effective ID for this entity (for store indexing and CLI output).
     /// - For schemas: the GTS ID from `$id` field
     /// - For well-known instances: the GTS ID from `id` field
@@ -637,6 +739,20 @@ impl GtsEntity {
     }
 }
 
+/// Whether the type `actual` is `schema_id`, a compatible minor version of
+/// it, or a schema derived from either.
+fn belongs_to_schema(actual: &str, schema_id: &str) -> bool {
+    let (Ok(actual), Ok(expected)) = (GtsID::new(actual), GtsID::new(schema_id)) else {
+        return false;
+    };
+    let Some(segments) = actual.gts_id_segments.get(..expected.gts_id_segments.len()) else {
+        return false;
+    };
+    let prefix: String = segments.iter().map(|s| s.segment.as_str()).collect();
+    GtsID::new(&format!("gts.{prefix}"))
+        .is_ok_and(|prefix| crate::version::is_compatible_upgrade(&expected, &prefix))
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -1573,4 +1689,113 @@ mod tests {
         assert!(entity.instance_id.is_none());
         assert!(entity.gts_id.is_none());
     }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct TestEventV1 {
+        id: String,
+        r#type: String,
+    }
+
+    impl GtsSchema for TestEventV1 {
+        const SCHEMA_ID: &'static str = "gts.x.core.events.type.v1~";
+
+        fn gts_schema_with_refs() -> Value {
+            json!({})
+        }
+    }
+
+    fn instance(content: &Value) -> GtsEntity {
+        let cfg = GtsConfig::default();
+        GtsEntity::new(
+            None,
+            None,
+            content,
+            Some(&cfg),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_as_type_accepts_derived_schema_instance() {
+        let entity = instance(&json!({
+            "id": "7a1d2f34-5678-49ab-9012-abcdef123456",
+            "type": "gts.x.core.events.type.v1~x.commerce.orders.order_placed.v1.0~"
+        }));
+
+        let event: TestEventV1 = entity.as_type().unwrap();
+        assert_eq!(event.id, "7a1d2f34-5678-49ab-9012-abcdef123456");
+        assert_eq!(
+            event.r#type,
+            "gts.x.core.events.type.v1~x.commerce.orders.order_placed.v1.0~"
+        );
+    }
+
+    #[test]
+    fn test_as_type_rejects_other_schema_family() {
+        let entity = instance(&json!({
+            "id": "7a1d2f34-5678-49ab-9012-abcdef123456",
+            "type": "gts.x.core.events.topic.v1~"
+        }));
+
+        let err = entity.as_type::<TestEventV1>().unwrap_err();
+        assert!(matches!(err, GtsEntityTypeError::SchemaMismatch { .. }));
+    }
+
+    #[test]
+    fn test_as_type_accepts_compatible_minor_versions() {
+        let entity = instance(&json!({
+            "id": "7a1d2f34-5678-49ab-9012-abcdef123456",
+            "type": "gts.x.core.events.type.v1.1~x.commerce.orders.order_placed.v1~"
+        }));
+        assert!(entity.as_type::<TestEventV1>().is_ok());
+
+        let entity = instance(&json!({
+            "id": "7a1d2f34-5678-49ab-9012-abcdef123456",
+            "type": "gts.x.core.events.type.v2~"
+        }));
+        let err = entity.as_type::<TestEventV1>().unwrap_err();
+        assert!(matches!(err, GtsEntityTypeError::SchemaMismatch { .. }));
+    }
+
+    #[test]
+    fn test_as_type_reports_deserialize_errors() {
+        let entity = instance(&json!({
+            "id": "7a1d2f34-5678-49ab-9012-abcdef123456",
+            "type": "gts.x.core.events.type.v1~",
+            "extra": true
+        }));
+        assert!(entity.as_type::<TestEventV1>().is_ok());
+
+        let entity = instance(&json!({
+            "id": "gts.x.core.events.type.v1~x.app._.no_type.v1"
+        }));
+        let err = entity.as_type::<TestEventV1>().unwrap_err();
+        assert!(matches!(err, GtsEntityTypeError::Deserialize { .. }));
+    }
+
+    #[test]
+    fn test_as_type_rejects_schemas() {
+        let content = json!({
+            "$id": "gts://gts.x.core.events.type.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#"
+        });
+        let entity = GtsEntity::new(
+            None,
+            None,
+            &content,
+            Some(&GtsConfig::default()),
+            None,
+            true,
+            String::new(),
+            None,
+            None,
+        );
+
+        let err = entity.as_type::<TestEventV1>().unwrap_err();
+        assert!(matches!(err, GtsEntityTypeError::NotAnInstance(_)));
+    }
 }
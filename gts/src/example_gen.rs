@@ -0,0 +1,747 @@
+//! Random example instances of a schema.
+//!
+//! [`ExampleGenerator`] walks a flattened schema (see
+//! [`GtsStore::flatten_chain`](crate::GtsStore::flatten_chain)) and produces
+//! values honouring `type`, `enum`, `const`, `default`, `required`, numeric
+//! and length bounds and the common string formats, including GTS IDs for
+//! `x-gts-ref` fields. Constraints it cannot construct values for (e.g.
+//! `pattern`) are left to the caller, which validates every candidate and
+//! retries. The same seed always yields the same sequence of examples.
+//!
+//! [`ExampleGenerator::mutate`] derives negative examples from valid ones,
+//! each breaking one constraint and annotated with an [`ExpectedViolation`].
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::gts::GtsID;
+
+/// Nesting depth after which optional properties are omitted and arrays are
+/// kept at their minimum length, so recursive schemas terminate.
+const MAX_DEPTH: usize = 16;
+
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// `SplitMix64`, small and good enough for test data.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n` (`0` when `n == 0`).
+    fn below(&mut self, n: u64) -> u64 {
+        if n == 0 { 0 } else { self.next_u64() % n }
+    }
+
+    /// Uniform in `lo..=hi`; `lo` if the range is empty.
+    fn between(&mut self, lo: i64, hi: i64) -> i64 {
+        if hi <= lo {
+            return lo;
+        }
+        let span = hi.abs_diff(lo).saturating_add(1);
+        lo.saturating_add_unsigned(self.below(span))
+    }
+
+    fn chance(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        let len = u64::try_from(items.len()).unwrap_or(u64::MAX);
+        items.get(usize::try_from(self.below(len)).unwrap_or(0))
+    }
+}
+
+/// Generates example instances of the schema `schema_id`.
+pub struct ExampleGenerator {
+    schema_id: String,
+    rng: Rng,
+}
+
+impl ExampleGenerator {
+    #[must_use]
+    pub fn new(schema_id: &str, seed: u64) -> Self {
+        Self {
+            schema_id: schema_id.to_owned(),
+            rng: Rng(seed),
+        }
+    }
+
+    /// Generate one value for `schema`.
+    pub fn generate(&mut self, schema: &Value) -> Value {
+        self.value(schema, 0)
+    }
+
+    fn value(&mut self, schema: &Value, depth: usize) -> Value {
+        let Value::Object(map) = schema else {
+            // `true` / `{}` accept anything, `false` accepts nothing
+            return Value::Null;
+        };
+        if let Some(constant) = map.get("const") {
+            return constant.clone();
+        }
+        if let Some(Value::Array(options)) = map.get("enum")
+            && let Some(option) = self.rng.pick(options)
+        {
+            return option.clone();
+        }
+        if let Some(default) = map.get("default")
+            && self.rng.chance()
+        {
+            return default.clone();
+        }
+        for combinator in ["oneOf", "anyOf"] {
+            if let Some(Value::Array(branches)) = map.get(combinator)
+                && let Some(branch) = self.rng.pick(branches)
+            {
+                let mut merged = map.clone();
+                merged.remove(combinator);
+                merge_subschema(&mut merged, branch);
+                return self.value(&Value::Object(merged), depth);
+            }
+        }
+        if let Some(Value::Array(parts)) = map.get("allOf") {
+            let mut merged = map.clone();
+            merged.remove("allOf");
+            for part in parts {
+                merge_subschema(&mut merged, part);
+            }
+            return self.value(&Value::Object(merged), depth);
+        }
+
+        match self.pick_type(map).as_str() {
+            "object" => self.object(map, depth),
+            "array" => self.array(map, depth),
+            "integer" => self.integer(map),
+            "number" => self.number(map),
+            "boolean" => Value::Bool(self.rng.chance()),
+            "null" => Value::Null,
+            _ => Value::String(self.string(map)),
+        }
+    }
+
+    fn pick_type(&mut self, map: &Map<String, Value>) -> String {
+        match map.get("type") {
+            Some(Value::String(t)) => t.clone(),
+            Some(Value::Array(types)) => self
+                .rng
+                .pick(types)
+                .and_then(Value::as_str)
+                .unwrap_or("string")
+                .to_owned(),
+            _ if map.contains_key("properties") => "object".to_owned(),
+            _ if map.contains_key("items") => "array".to_owned(),
+            _ => "string".to_owned(),
+        }
+    }
+
+    fn object(&mut self, map: &Map<String, Value>, depth: usize) -> Value {
+        let required: Vec<&str> = map
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|r| r.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let mut out = Map::new();
+        if let Some(Value::Object(props)) = map.get("properties") {
+            for (name, prop) in props {
+                let include = required.contains(&name.as_str())
+                    || (depth < MAX_DEPTH && prop != &Value::Bool(false) && self.rng.chance());
+                if include {
+                    out.insert(name.clone(), self.value(prop, depth + 1));
+                }
+            }
+        }
+        Value::Object(out)
+    }
+
+    fn array(&mut self, map: &Map<String, Value>, depth: usize) -> Value {
+        let min = bound(map, "minItems").unwrap_or(0);
+        let max = bound(map, "maxItems").unwrap_or(min + 3);
+        let len = if depth < MAX_DEPTH {
+            self.rng.between(min, max)
+        } else {
+            min
+        };
+        let items = map.get("items");
+        let values = (0..len)
+            .map(|i| {
+                let item = match items {
+                    Some(Value::Array(tuple)) => usize::try_from(i)
+                        .ok()
+                        .and_then(|i| tuple.get(i))
+                        .unwrap_or(&Value::Bool(true)),
+                    Some(item) => item,
+                    None => &Value::Bool(true),
+                };
+                self.value(item, depth + 1)
+            })
+            .collect();
+        Value::Array(values)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn integer(&mut self, map: &Map<String, Value>) -> Value {
+        let (lo, hi) = numeric_range(map, true);
+        let (lo, hi) = (lo.ceil() as i64, hi.floor() as i64);
+        let step = map
+            .get("multipleOf")
+            .and_then(Value::as_i64)
+            .filter(|m| *m > 0);
+        let value = match step {
+            Some(m) => {
+                let k = self.rng.between(-(-lo).div_euclid(m), hi.div_euclid(m));
+                k * m
+            }
+            None => self.rng.between(lo, hi),
+        };
+        Value::Number(value.into())
+    }
+
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn number(&mut self, map: &Map<String, Value>) -> Value {
+        let (lo, hi) = numeric_range(map, false);
+        let step = map
+            .get("multipleOf")
+            .and_then(Value::as_f64)
+            .filter(|m| *m > 0.0);
+        let value = if let Some(m) = step {
+            let k = self
+                .rng
+                .between((lo / m).ceil() as i64, (hi / m).floor() as i64);
+            k as f64 * m
+        } else {
+            let v = lo + self.rng.unit() * (hi - lo);
+            (v * 100.0).round() / 100.0
+        };
+        Number::from_f64(value).map_or(Value::Null, Value::Number)
+    }
+
+    fn string(&mut self, map: &Map<String, Value>) -> String {
+        if let Some(id) = self.gts_id(map) {
+            return id;
+        }
+        let word = self.word(3, 8);
+        match map.get("format").and_then(Value::as_str) {
+            Some("uuid") => {
+                let mut bytes = [0u8; 16];
+                for chunk in bytes.chunks_mut(8) {
+                    chunk.copy_from_slice(&self.rng.next_u64().to_le_bytes());
+                }
+                uuid::Builder::from_random_bytes(bytes)
+                    .into_uuid()
+                    .to_string()
+            }
+            Some("date-time") => format!(
+                "2024-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                self.rng.between(1, 12),
+                self.rng.between(1, 28),
+                self.rng.between(0, 23),
+                self.rng.between(0, 59),
+                self.rng.between(0, 59)
+            ),
+            Some("date") => format!(
+                "2024-{:02}-{:02}",
+                self.rng.between(1, 12),
+                self.rng.between(1, 28)
+            ),
+            Some("email") => format!("{word}@example.com"),
+            Some("hostname") => format!("{word}.example.com"),
+            Some("uri" | "url" | "iri") => format!("https://example.com/{word}"),
+            Some("ipv4") => format!(
+                "10.{}.{}.{}",
+                self.rng.between(0, 255),
+                self.rng.between(0, 255),
+                self.rng.between(1, 254)
+            ),
+            _ => {
+                let min = bound(map, "minLength").unwrap_or(1);
+                let max = bound(map, "maxLength").unwrap_or(min.max(1) + 11);
+                self.word(min, max)
+            }
+        }
+    }
+
+    /// A GTS ID for `x-gts-ref` and `gts-*-id` formatted fields, or `None`
+    /// for any other string.
+    fn gts_id(&mut self, map: &Map<String, Value>) -> Option<String> {
+        let format = map.get("format").and_then(Value::as_str);
+        let reference = map.get("x-gts-ref").and_then(Value::as_str);
+        if reference.is_none() && !matches!(format, Some("gts-schema-id" | "gts-instance-id")) {
+            return None;
+        }
+
+        // The closest ID the reference pattern allows, or the schema itself
+        let prefix = reference
+            .filter(|r| !r.starts_with('/'))
+            .map(|r| r.strip_suffix('*').unwrap_or(r))
+            .filter(|p| GtsID::is_valid(p))
+            .unwrap_or(&self.schema_id)
+            .to_owned();
+        if format == Some("gts-instance-id") && prefix.ends_with('~') {
+            let n = self.rng.below(1000);
+            return Some(format!("{prefix}x.examples._.item_{n}.v1"));
+        }
+        Some(prefix)
+    }
+
+    /// Turn a valid instance of `schema` into one violating a single
+    /// constraint, or `None` if no schema-aware mutation applies.
+    ///
+    /// Successive rounds cycle through the kinds of violation available, so a
+    /// batch covers each of them; the mutated field is picked at random.
+    pub fn mutate(
+        &mut self,
+        schema: &Value,
+        valid: &Value,
+        round: usize,
+    ) -> Option<InvalidExample> {
+        let mut mutations = Vec::new();
+        collect_mutations(schema, valid, &mut Vec::new(), &mut mutations);
+        let mut kinds: Vec<ViolationKind> = mutations.iter().map(|m| m.kind).collect();
+        kinds.sort_unstable();
+        kinds.dedup();
+        let kind = *kinds.get(round % kinds.len().max(1))?;
+
+        mutations.retain(|m| m.kind == kind);
+        let len = u64::try_from(mutations.len()).unwrap_or(u64::MAX);
+        let index = usize::try_from(self.rng.below(len)).unwrap_or(0);
+        Some(mutations.swap_remove(index).apply(valid))
+    }
+
+    fn word(&mut self, min: i64, max: i64) -> String {
+        let len = self.rng.between(min, max);
+        (0..len)
+            .map(|_| {
+                let i = self.rng.below(ALPHABET.len() as u64);
+                char::from(ALPHABET[usize::try_from(i).unwrap_or(0)])
+            })
+            .collect()
+    }
+}
+
+/// The constraint a negative example is expected to violate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    MissingRequired,
+    AdditionalProperty,
+    WrongType,
+    Enum,
+    Const,
+    GtsRef,
+    Range,
+    Length,
+}
+
+/// Annotation of a negative example: which constraint breaks and where.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedViolation {
+    pub kind: ViolationKind,
+    /// Property path like `payload.tags[0]`; empty for the instance itself
+    pub path: String,
+    pub message: String,
+}
+
+/// An instance that differs from a valid one by exactly one violation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvalidExample {
+    pub instance: Value,
+    pub violation: ExpectedViolation,
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+/// A single schema-aware mutation: replace (or, with `None`, remove) the
+/// value at `path`.
+struct Mutation {
+    path: Vec<Step>,
+    replacement: Option<Value>,
+    kind: ViolationKind,
+    message: String,
+}
+
+impl Mutation {
+    fn new(
+        path: &[Step],
+        kind: ViolationKind,
+        replacement: Option<Value>,
+        message: String,
+    ) -> Self {
+        Self {
+            path: path.to_vec(),
+            replacement,
+            kind,
+            message,
+        }
+    }
+
+    fn apply(self, valid: &Value) -> InvalidExample {
+        let mut instance = valid.clone();
+        if let Some((last, parents)) = self.path.split_last() {
+            let mut target = &mut instance;
+            for step in parents {
+                target = match step {
+                    Step::Key(key) => &mut target[key.as_str()],
+                    Step::Index(i) => &mut target[*i],
+                };
+            }
+            match (last, self.replacement, target) {
+                (Step::Key(key), None, Value::Object(map)) => {
+                    map.remove(key);
+                }
+                (Step::Key(key), Some(value), Value::Object(map)) => {
+                    map.insert(key.clone(), value);
+                }
+                (Step::Index(i), Some(value), Value::Array(items)) if *i < items.len() => {
+                    items[*i] = value;
+                }
+                _ => {}
+            }
+        } else if let Some(value) = self.replacement {
+            instance = value;
+        }
+        InvalidExample {
+            instance,
+            violation: ExpectedViolation {
+                kind: self.kind,
+                path: display_path(&self.path),
+                message: self.message,
+            },
+        }
+    }
+}
+
+fn display_path(path: &[Step]) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    for step in path {
+        match step {
+            Step::Key(key) if out.is_empty() => out.push_str(key),
+            Step::Key(key) => {
+                out.push('.');
+                out.push_str(key);
+            }
+            Step::Index(i) => {
+                let _ = write!(out, "[{i}]");
+            }
+        }
+    }
+    out
+}
+
+/// A value of a different JSON type than `value`.
+fn wrong_type(value: &Value) -> Value {
+    match value {
+        Value::String(_) => Value::from(12345),
+        Value::Number(_) => Value::from("not-a-number"),
+        Value::Bool(b) => Value::from(b.to_string()),
+        Value::Null => Value::from(false),
+        Value::Object(_) => Value::from("not-an-object"),
+        Value::Array(_) => Value::from("not-an-array"),
+    }
+}
+
+/// Mutations applicable to `value` (valid against `schema`) and below it.
+fn collect_mutations(schema: &Value, value: &Value, path: &mut Vec<Step>, out: &mut Vec<Mutation>) {
+    let Value::Object(map) = schema else {
+        return;
+    };
+    value_mutations(map, value, path, out);
+
+    match value {
+        Value::Object(fields) => {
+            let required = map.get("required").and_then(Value::as_array);
+            for name in required.into_iter().flatten().filter_map(Value::as_str) {
+                if fields.contains_key(name) {
+                    path.push(Step::Key(name.to_owned()));
+                    out.push(Mutation::new(
+                        path,
+                        ViolationKind::MissingRequired,
+                        None,
+                        format!("required property '{name}' is missing"),
+                    ));
+                    path.pop();
+                }
+            }
+            if map.get("additionalProperties") == Some(&Value::Bool(false)) {
+                path.push(Step::Key("unexpected_property".to_owned()));
+                out.push(Mutation::new(
+                    path,
+                    ViolationKind::AdditionalProperty,
+                    Some(Value::Bool(true)),
+                    "property 'unexpected_property' is not allowed".to_owned(),
+                ));
+                path.pop();
+            }
+            if let Some(Value::Object(props)) = map.get("properties") {
+                for (name, prop) in props {
+                    if let Some(field) = fields.get(name) {
+                        path.push(Step::Key(name.clone()));
+                        collect_mutations(prop, field, path, out);
+                        path.pop();
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema @ Value::Object(_)) = map.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    path.push(Step::Index(i));
+                    collect_mutations(item_schema, item, path, out);
+                    path.pop();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Mutations replacing `value` itself.
+fn value_mutations(
+    map: &Map<String, Value>,
+    value: &Value,
+    path: &[Step],
+    out: &mut Vec<Mutation>,
+) {
+    let mut replace = |kind, replacement: Value, message: String| {
+        out.push(Mutation::new(path, kind, Some(replacement), message));
+    };
+
+    if map.contains_key("type") {
+        let replacement = wrong_type(value);
+        let message = format!("{replacement} does not match type {}", map["type"]);
+        replace(ViolationKind::WrongType, replacement, message);
+    }
+    if let Some(Value::Array(options)) = map.get("enum") {
+        let replacement = Value::from("not_in_enum");
+        if !options.contains(&replacement) {
+            replace(
+                ViolationKind::Enum,
+                replacement,
+                "value is not one of the enum values".to_owned(),
+            );
+        }
+    }
+    if let Some(constant) = map.get("const") {
+        let replacement = match constant {
+            Value::String(s) => Value::from(format!("{s}_changed")),
+            Value::Null => Value::from(0),
+            _ => Value::Null,
+        };
+        replace(
+            ViolationKind::Const,
+            replacement,
+            format!("value differs from const {constant}"),
+        );
+    }
+    if map.contains_key("x-gts-ref") && value.is_string() {
+        replace(
+            ViolationKind::GtsRef,
+            Value::from("not-a-gts-id"),
+            "value is not a GTS ID matching x-gts-ref".to_owned(),
+        );
+    }
+    let get = |key: &str| map.get(key).and_then(Value::as_f64);
+    if let Some(min) = get("minimum").or(get("exclusiveMinimum")) {
+        replace(
+            ViolationKind::Range,
+            Value::from((min - 1.0).floor()),
+            format!("value is below the minimum {min}"),
+        );
+    }
+    if let Some(max) = get("maximum").or(get("exclusiveMaximum")) {
+        replace(
+            ViolationKind::Range,
+            Value::from((max + 1.0).ceil()),
+            format!("value is above the maximum {max}"),
+        );
+    }
+    if let Value::String(s) = value {
+        if let Some(min) = map.get("minLength").and_then(Value::as_u64)
+            && min > 0
+        {
+            let len = usize::try_from(min - 1).unwrap_or(0);
+            replace(
+                ViolationKind::Length,
+                Value::from(s.chars().take(len).collect::<String>()),
+                format!("value is shorter than minLength {min}"),
+            );
+        }
+        if let Some(max) = map.get("maxLength").and_then(Value::as_u64) {
+            let len = usize::try_from(max + 1).unwrap_or(usize::MAX);
+            replace(
+                ViolationKind::Length,
+                Value::from("x".repeat(len)),
+                format!("value is longer than maxLength {max}"),
+            );
+        }
+    }
+}
+
+/// Non-negative integer keyword such as `minLength`, capped so generated
+/// values stay small.
+fn bound(map: &Map<String, Value>, key: &str) -> Option<i64> {
+    map.get(key)
+        .and_then(Value::as_u64)
+        .map(|v| i64::try_from(v.min(64)).unwrap_or(64))
+}
+
+/// Inclusive range for a numeric schema; exclusive bounds are tightened by
+/// one for integers and by a small step for numbers.
+fn numeric_range(map: &Map<String, Value>, integer: bool) -> (f64, f64) {
+    let step = if integer { 1.0 } else { 0.01 };
+    let get = |key: &str| map.get(key).and_then(Value::as_f64);
+    let lo = match (get("minimum"), get("exclusiveMinimum")) {
+        (Some(a), Some(b)) => Some(a.max(b + step)),
+        (a, b) => a.or(b.map(|b| b + step)),
+    };
+    let hi = match (get("maximum"), get("exclusiveMaximum")) {
+        (Some(a), Some(b)) => Some(a.min(b - step)),
+        (a, b) => a.or(b.map(|b| b - step)),
+    };
+    match (lo, hi) {
+        (Some(lo), Some(hi)) => (lo, hi),
+        (Some(lo), None) => (lo, lo + 1000.0),
+        (None, Some(hi)) => (hi - 1000.0, hi),
+        (None, None) => (0.0, 1000.0),
+    }
+}
+
+/// Shallow-merge a subschema of `allOf`/`oneOf`/`anyOf` into `target`:
+/// properties and `required` are combined, any other keyword is overridden.
+fn merge_subschema(target: &mut Map<String, Value>, extra: &Value) {
+    let Value::Object(extra) = extra else {
+        return;
+    };
+    for (key, value) in extra {
+        match (key.as_str(), target.get_mut(key), value) {
+            ("properties", Some(Value::Object(props)), Value::Object(more)) => {
+                props.extend(more.clone());
+            }
+            ("required", Some(Value::Array(req)), Value::Array(more)) => {
+                for name in more {
+                    if !req.contains(name) {
+                        req.push(name.clone());
+                    }
+                }
+            }
+            ("$id" | "$schema", ..) => {}
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "string", "format": "uuid"},
+                "kind": {"enum": ["a", "b"]},
+                "count": {"type": "integer", "minimum": 5, "exclusiveMaximum": 8},
+                "ratio": {"type": "number", "minimum": 0, "maximum": 1},
+                "tags": {"type": "array", "items": {"type": "string", "maxLength": 3}, "minItems": 1, "maxItems": 2},
+                "type": {"type": "string", "format": "gts-schema-id", "x-gts-ref": "/$id"},
+                "mode": {"type": "string", "default": "auto"}
+            },
+            "required": ["id", "kind", "count", "ratio", "tags", "type"]
+        })
+    }
+
+    #[test]
+    fn test_generate_respects_constraints() {
+        let mut generator = ExampleGenerator::new("gts.x.core.events.type.v1~", 7);
+        for _ in 0..50 {
+            let value = generator.generate(&schema());
+            assert!(uuid::Uuid::parse_str(value["id"].as_str().unwrap()).is_ok());
+            assert!(["a", "b"].contains(&value["kind"].as_str().unwrap()));
+            assert!((5..8).contains(&value["count"].as_i64().unwrap()));
+            assert!((0.0..=1.0).contains(&value["ratio"].as_f64().unwrap()));
+            let tags = value["tags"].as_array().unwrap();
+            assert!((1..=2).contains(&tags.len()));
+            assert!(tags.iter().all(|t| t.as_str().unwrap().len() <= 3));
+            assert_eq!(value["type"], "gts.x.core.events.type.v1~");
+        }
+    }
+
+    #[test]
+    fn test_generate_is_deterministic_per_seed() {
+        let run = |seed| {
+            let mut generator = ExampleGenerator::new("gts.x.core.events.type.v1~", seed);
+            (0..5)
+                .map(|_| generator.generate(&schema()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(1), run(1));
+        assert_ne!(run(1), run(2));
+    }
+
+    #[test]
+    fn test_mutate_cycles_through_violation_kinds() {
+        let mut generator = ExampleGenerator::new("gts.x.core.events.type.v1~", 3);
+        let schema = schema();
+        let valid = generator.generate(&schema);
+
+        let mut kinds = Vec::new();
+        for round in 0..8 {
+            let invalid = generator.mutate(&schema, &valid, round).unwrap();
+            assert_ne!(invalid.instance, valid);
+            kinds.push(invalid.violation.kind);
+        }
+        kinds.sort_unstable();
+        kinds.dedup();
+        assert_eq!(
+            kinds,
+            vec![
+                ViolationKind::MissingRequired,
+                ViolationKind::WrongType,
+                ViolationKind::Enum,
+                ViolationKind::GtsRef,
+                ViolationKind::Range,
+                ViolationKind::Length,
+            ]
+        );
+
+        let dropped = generator.mutate(&schema, &valid, 0).unwrap();
+        assert_eq!(dropped.violation.kind, ViolationKind::MissingRequired);
+        let path = dropped.violation.path.as_str();
+        assert!(valid.get(path).is_some() && dropped.instance.get(path).is_none());
+
+        assert!(generator.mutate(&json!({}), &json!({}), 0).is_none());
+    }
+
+    #[test]
+    fn test_generate_instance_id_under_referenced_type() {
+        let mut generator = ExampleGenerator::new("gts.x.core.events.type.v1~", 0);
+        let value = generator.generate(&json!({
+            "type": "string",
+            "format": "gts-instance-id",
+            "x-gts-ref": "gts.x.core.events.topic.v1~*"
+        }));
+        let id = value.as_str().unwrap();
+        assert!(id.starts_with("gts.x.core.events.topic.v1~x.examples._.item_"));
+        assert!(GtsID::is_valid(id));
+    }
+}
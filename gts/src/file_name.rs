@@ -0,0 +1,214 @@
+//! File names of schema and instance files.
+//!
+//! Generated files are named `<encoded-id>.schema.json` /
+//! `<encoded-id>.instance.json`. Raw GTS IDs contain `~` (and, in patterns,
+//! `*`), which some artifact stores and URL encoders mangle, so the ID part
+//! can be encoded:
+//!
+//! | Encoding  | `gts.x.core.events.type.v1~`              | Reversible |
+//! |-----------|-------------------------------------------|------------|
+//! | `raw`     | `gts.x.core.events.type.v1~`              | yes        |
+//! | `percent` | `gts.x.core.events.type.v1%7E`            | yes        |
+//! | `hashed`  | `gts.x.core.events.type.v1_-5cbf4244`     | no         |
+//!
+//! `percent` escapes every byte outside `[A-Za-z0-9._-]` as `%XX` (uppercase
+//! hex). `hashed` replaces those characters with `_` and appends `-` and the
+//! first 8 hex digits of the 64-bit FNV-1a hash of the raw ID, which keeps
+//! IDs that sanitize alike apart; such names are checked by encoding the ID
+//! again rather than decoded.
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Suffix of schema files written by the generators.
+pub const SCHEMA_FILE_SUFFIX: &str = ".schema.json";
+/// Suffix of instance files written by the generators.
+pub const INSTANCE_FILE_SUFFIX: &str = ".instance.json";
+
+/// How a GTS ID is turned into the stem of a file name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GtsFileNameEncoding {
+    /// The ID as is.
+    #[default]
+    Raw,
+    /// Percent-encoding of everything outside `[A-Za-z0-9._-]`.
+    Percent,
+    /// Sanitized ID followed by a hash of the raw ID.
+    Hashed,
+}
+
+impl GtsFileNameEncoding {
+    pub const ALL: [Self; 3] = [Self::Raw, Self::Percent, Self::Hashed];
+
+    /// The file name stem for `id`.
+    #[must_use]
+    pub fn encode(self, id: &str) -> String {
+        match self {
+            Self::Raw => id.to_owned(),
+            Self::Percent => {
+                let mut stem = String::with_capacity(id.len());
+                for byte in id.bytes() {
+                    if is_safe(byte) {
+                        stem.push(char::from(byte));
+                    } else {
+                        let _ = write!(stem, "%{byte:02X}");
+                    }
+                }
+                stem
+            }
+            Self::Hashed => {
+                let sanitized: String = id
+                    .bytes()
+                    .map(|b| if is_safe(b) { char::from(b) } else { '_' })
+                    .collect();
+                let hash = format!("{:016x}", fnv1a(id));
+                format!("{sanitized}-{}", &hash[..8])
+            }
+        }
+    }
+
+    /// The ID a file name stem encodes; `None` if `stem` is not a valid
+    /// encoding, and always for [`Hashed`](Self::Hashed).
+    #[must_use]
+    pub fn decode(self, stem: &str) -> Option<String> {
+        match self {
+            Self::Raw => Some(stem.to_owned()),
+            Self::Percent => {
+                let mut bytes = Vec::with_capacity(stem.len());
+                let mut rest = stem.as_bytes();
+                while let Some((&byte, tail)) = rest.split_first() {
+                    if byte == b'%' {
+                        let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+                        bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                        rest = &tail[2..];
+                    } else if is_safe(byte) {
+                        bytes.push(byte);
+                        rest = tail;
+                    } else {
+                        return None;
+                    }
+                }
+                String::from_utf8(bytes).ok()
+            }
+            Self::Hashed => None,
+        }
+    }
+
+    /// Whether `stem` is the encoding of `id`.
+    #[must_use]
+    pub fn matches(self, stem: &str, id: &str) -> bool {
+        self.encode(id) == stem
+    }
+
+    /// `<encoded-id>.schema.json`
+    #[must_use]
+    pub fn schema_file_name(self, id: &str) -> String {
+        format!("{}{SCHEMA_FILE_SUFFIX}", self.encode(id))
+    }
+
+    /// `<encoded-id>.instance.json`
+    #[must_use]
+    pub fn instance_file_name(self, id: &str) -> String {
+        format!("{}{INSTANCE_FILE_SUFFIX}", self.encode(id))
+    }
+}
+
+impl fmt::Display for GtsFileNameEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Raw => "raw",
+            Self::Percent => "percent",
+            Self::Hashed => "hashed",
+        })
+    }
+}
+
+impl FromStr for GtsFileNameEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|encoding| encoding.to_string() == s)
+            .ok_or_else(|| {
+                format!("Unknown file name encoding '{s}', expected raw, percent or hashed")
+            })
+    }
+}
+
+fn is_safe(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'_' | b'-')
+}
+
+fn fnv1a(text: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    text.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "gts.x.core.events.type.v1~x.app._.orders.v1~";
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        assert_eq!(GtsFileNameEncoding::Raw.encode(ID), ID);
+        let percent = GtsFileNameEncoding::Percent.encode(ID);
+        assert_eq!(percent, "gts.x.core.events.type.v1%7Ex.app._.orders.v1%7E");
+        for encoding in [GtsFileNameEncoding::Raw, GtsFileNameEncoding::Percent] {
+            let stem = encoding.encode(ID);
+            assert_eq!(encoding.decode(&stem).as_deref(), Some(ID));
+            assert!(encoding.matches(&stem, ID));
+        }
+        assert_eq!(
+            GtsFileNameEncoding::Percent.schema_file_name("gts.x.a.b.c.v1~"),
+            "gts.x.a.b.c.v1%7E.schema.json"
+        );
+        assert_eq!(GtsFileNameEncoding::Percent.decode("gts%7"), None);
+        assert_eq!(GtsFileNameEncoding::Percent.decode("gts~"), None);
+    }
+
+    #[test]
+    fn test_hashed_names() {
+        let stem = GtsFileNameEncoding::Hashed.encode(ID);
+        assert!(
+            stem.starts_with("gts.x.core.events.type.v1_x.app._.orders.v1_-"),
+            "{stem}"
+        );
+        assert_eq!(stem.len(), ID.len() + 9);
+        assert!(GtsFileNameEncoding::Hashed.matches(&stem, ID));
+        // IDs that sanitize alike get different names
+        assert_ne!(
+            GtsFileNameEncoding::Hashed.encode("gts.x.a.b.c.v1~"),
+            GtsFileNameEncoding::Hashed.encode("gts.x.a.b.c.v1_")
+        );
+        assert_eq!(GtsFileNameEncoding::Hashed.decode(&stem), None);
+        assert_eq!(
+            GtsFileNameEncoding::Hashed.encode("gts.x.core.events.type.v1~"),
+            "gts.x.core.events.type.v1_-5cbf4244"
+        );
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for encoding in GtsFileNameEncoding::ALL {
+            assert_eq!(
+                encoding.to_string().parse::<GtsFileNameEncoding>(),
+                Ok(encoding)
+            );
+        }
+        assert!("base64".parse::<GtsFileNameEncoding>().is_err());
+        assert_eq!(
+            serde_json::to_value(GtsFileNameEncoding::Percent).unwrap(),
+            "percent"
+        );
+    }
+}
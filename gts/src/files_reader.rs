@@ -4,7 +4,13 @@ use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 use crate::entities::{GtsConfig, GtsEntity, GtsFile};
-use crate::store::GtsReader;
+#[cfg(feature = "async")]
+use crate::store::GtsLoadProgress;
+use crate::store::{GtsLoadError, GtsReader};
+#[cfg(feature = "async")]
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use tokio::sync::Semaphore;
 
 const EXCLUDE_LIST: &[&str] = &["node_modules", "dist", "build"];
 const VALID_EXTENSIONS: &[&str] = &[".json", ".jsonc", ".gts", ".yaml", ".yml"];
@@ -14,6 +20,7 @@ pub struct GtsFileReader {
     cfg: GtsConfig,
     files: Vec<PathBuf>,
     initialized: bool,
+    load_errors: Vec<GtsLoadError>,
 }
 
 impl GtsFileReader {
@@ -29,72 +36,23 @@ impl GtsFileReader {
             cfg: cfg.unwrap_or_default(),
             files: Vec::new(),
             initialized: false,
+            load_errors: Vec::new(),
         }
     }
 
-    #[allow(clippy::cognitive_complexity)]
     fn collect_files(&mut self) {
-        let mut seen = std::collections::HashSet::new();
-        let mut collected = Vec::new();
-
-        for path in &self.paths {
-            let resolved_path = path.canonicalize().unwrap_or_else(|_| path.clone());
-
-            if resolved_path.is_file() {
-                if let Some(ext) = resolved_path.extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    if VALID_EXTENSIONS.contains(&format!(".{ext_str}").as_str()) {
-                        let rp = resolved_path.to_string_lossy().to_string();
-                        if !seen.contains(&rp) {
-                            seen.insert(rp.clone());
-                            tracing::debug!("- discovered file: {:?}", resolved_path);
-                            collected.push(resolved_path.clone());
-                        }
-                    }
-                }
-            } else if resolved_path.is_dir() {
-                for entry in WalkDir::new(&resolved_path)
-                    .follow_links(true)
-                    .into_iter()
-                    .flatten()
-                {
-                    let path = entry.path();
-
-                    // Skip excluded directories
-                    if path.is_dir()
-                        && let Some(name) = path.file_name()
-                        && EXCLUDE_LIST.contains(&name.to_string_lossy().as_ref())
-                    {
-                        continue;
-                    }
-
-                    if path.is_file()
-                        && let Some(ext) = path.extension()
-                    {
-                        let ext_str = ext.to_string_lossy().to_lowercase();
-                        if VALID_EXTENSIONS.contains(&format!(".{ext_str}").as_str()) {
-                            let rp = path
-                                .canonicalize()
-                                .unwrap_or_else(|_| path.to_path_buf())
-                                .to_string_lossy()
-                                .to_string();
-                            if !seen.contains(&rp) {
-                                seen.insert(rp.clone());
-                                tracing::debug!("- discovered file: {:?}", path);
-                                collected.push(PathBuf::from(rp));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        self.files = collected;
+        self.files = discover_files(&self.paths);
     }
 
     fn load_json_file(file_path: &Path) -> Result<Value, Box<dyn std::error::Error>> {
         let content = fs::read_to_string(file_path)?;
+        Self::parse_file_content(file_path, &content)
+    }
 
+    fn parse_file_content(
+        file_path: &Path,
+        content: &str,
+    ) -> Result<Value, Box<dyn std::error::Error>> {
         // Determine file type by extension
         let extension = file_path
             .extension()
@@ -105,88 +63,227 @@ impl GtsFileReader {
         let value: Value = match extension.as_str() {
             "yaml" | "yml" => {
                 // Parse YAML and convert to JSON
-                serde_saphyr::from_str(&content)?
+                serde_saphyr::from_str(content)?
             }
             _ => {
                 // Default: parse as JSON
-                serde_json::from_str(&content)?
+                serde_json::from_str(content)?
             }
         };
 
         Ok(value)
     }
 
+    fn process_file(&self, file_path: &Path) -> Result<Vec<GtsEntity>, GtsLoadError> {
+        match Self::load_json_file(file_path) {
+            Ok(content) => Ok(Self::file_entities(file_path, &content, &self.cfg)),
+            Err(e) => Err(Self::load_error(file_path, &*e)),
+        }
+    }
+
+    fn load_error(file_path: &Path, e: &dyn std::error::Error) -> GtsLoadError {
+        // Skip files that can't be parsed; the store reports them via load_errors()
+        tracing::debug!("Failed to parse file {:?}: {}", file_path, e);
+        GtsLoadError {
+            path: file_path.to_string_lossy().to_string(),
+            message: e.to_string(),
+        }
+    }
+
     #[allow(clippy::cognitive_complexity)]
-    fn process_file(&self, file_path: &Path) -> Vec<GtsEntity> {
+    fn file_entities(file_path: &Path, content: &Value, cfg: &GtsConfig) -> Vec<GtsEntity> {
         let mut entities = Vec::new();
+        let json_file = GtsFile::new(
+            file_path.to_string_lossy().to_string(),
+            file_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            content.clone(),
+        );
 
-        match Self::load_json_file(file_path) {
-            Ok(content) => {
-                let json_file = GtsFile::new(
-                    file_path.to_string_lossy().to_string(),
-                    file_path
-                        .file_name()
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .to_string(),
-                    content.clone(),
+        // Handle both single objects and arrays
+        if let Some(arr) = content.as_array() {
+            for (idx, item) in arr.iter().enumerate() {
+                let entity = GtsEntity::new(
+                    Some(json_file.clone()),
+                    Some(idx),
+                    item,
+                    Some(cfg),
+                    None,
+                    false,
+                    String::new(),
+                    None,
+                    None,
                 );
-
-                // Handle both single objects and arrays
-                if let Some(arr) = content.as_array() {
-                    for (idx, item) in arr.iter().enumerate() {
-                        let entity = GtsEntity::new(
-                            Some(json_file.clone()),
-                            Some(idx),
-                            item,
-                            Some(&self.cfg),
-                            None,
-                            false,
-                            String::new(),
-                            None,
-                            None,
-                        );
-                        // Use effective_id() which handles both GTS IDs and anonymous instance IDs
-                        if let Some(id) = entity.effective_id() {
-                            tracing::debug!("- discovered entity: {}", id);
-                            entities.push(entity);
-                        } else {
-                            tracing::debug!("- skipped entity from {:?} (no valid ID)", file_path);
-                        }
-                    }
+                // Use effective_id() which handles both GTS IDs and anonymous instance IDs
+                if let Some(id) = entity.effective_id() {
+                    tracing::debug!("- discovered entity: {}", id);
+                    entities.push(entity);
                 } else {
-                    let entity = GtsEntity::new(
-                        Some(json_file),
-                        None,
-                        &content,
-                        Some(&self.cfg),
-                        None,
-                        false,
-                        String::new(),
-                        None,
-                        None,
-                    );
-                    // Use effective_id() which handles both GTS IDs and anonymous instance IDs
-                    if let Some(id) = entity.effective_id() {
-                        tracing::debug!("- discovered entity: {}", id);
-                        entities.push(entity);
-                    } else {
-                        tracing::debug!(
-                            "- skipped entity from {:?} (no valid ID found in content: {:?})",
-                            file_path,
-                            content
-                        );
+                    tracing::debug!("- skipped entity from {:?} (no valid ID)", file_path);
+                }
+            }
+        } else {
+            let entity = GtsEntity::new(
+                Some(json_file),
+                None,
+                content,
+                Some(cfg),
+                None,
+                false,
+                String::new(),
+                None,
+                None,
+            );
+            // Use effective_id() which handles both GTS IDs and anonymous instance IDs
+            if let Some(id) = entity.effective_id() {
+                tracing::debug!("- discovered entity: {}", id);
+                entities.push(entity);
+            } else {
+                tracing::debug!(
+                    "- skipped entity from {:?} (no valid ID found in content: {:?})",
+                    file_path,
+                    content
+                );
+            }
+        }
+        entities
+    }
+}
+
+/// Files with a supported extension under `paths`, deduplicated, skipping
+/// [`EXCLUDE_LIST`] directories.
+#[allow(clippy::cognitive_complexity)]
+fn discover_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut seen = std::collections::HashSet::new();
+    let mut collected = Vec::new();
+
+    for path in paths {
+        let resolved_path = path.canonicalize().unwrap_or_else(|_| path.clone());
+
+        if resolved_path.is_file() {
+            if let Some(ext) = resolved_path.extension() {
+                let ext_str = ext.to_string_lossy().to_lowercase();
+                if VALID_EXTENSIONS.contains(&format!(".{ext_str}").as_str()) {
+                    let rp = resolved_path.to_string_lossy().to_string();
+                    if !seen.contains(&rp) {
+                        seen.insert(rp.clone());
+                        tracing::debug!("- discovered file: {:?}", resolved_path);
+                        collected.push(resolved_path.clone());
                     }
                 }
             }
-            Err(e) => {
-                // Skip files that can't be parsed
-                tracing::debug!("Failed to parse file {:?}: {}", file_path, e);
+        } else if resolved_path.is_dir() {
+            for entry in WalkDir::new(&resolved_path)
+                .follow_links(true)
+                .into_iter()
+                .flatten()
+            {
+                let path = entry.path();
+
+                // Skip excluded directories
+                if path.is_dir()
+                    && let Some(name) = path.file_name()
+                    && EXCLUDE_LIST.contains(&name.to_string_lossy().as_ref())
+                {
+                    continue;
+                }
+
+                if path.is_file()
+                    && let Some(ext) = path.extension()
+                {
+                    let ext_str = ext.to_string_lossy().to_lowercase();
+                    if VALID_EXTENSIONS.contains(&format!(".{ext_str}").as_str()) {
+                        let rp = path
+                            .canonicalize()
+                            .unwrap_or_else(|_| path.to_path_buf())
+                            .to_string_lossy()
+                            .to_string();
+                        if !seen.contains(&rp) {
+                            seen.insert(rp.clone());
+                            tracing::debug!("- discovered file: {:?}", path);
+                            collected.push(PathBuf::from(rp));
+                        }
+                    }
+                }
             }
         }
+    }
+
+    collected
+}
+
+#[cfg(feature = "async")]
+impl GtsFileReader {
+    /// Load every file like [`GtsReader::iter`] without blocking the async
+    /// runtime: files are read with `tokio::fs` and parsed on the blocking
+    /// pool, at most `concurrency` at a time. Entities keep the file order of
+    /// the synchronous load, so duplicate IDs resolve the same way.
+    ///
+    /// `progress` is updated as files complete; failed files are available
+    /// from [`GtsReader::load_errors`] afterwards.
+    pub async fn load_async(
+        &mut self,
+        concurrency: usize,
+        progress: &Arc<GtsLoadProgress>,
+    ) -> Vec<GtsEntity> {
+        let paths = self.paths.clone();
+        self.files = tokio::task::spawn_blocking(move || discover_files(&paths))
+            .await
+            .unwrap_or_default();
+        self.initialized = true;
+        progress.start(self.files.len());
+
+        let permits = Arc::new(Semaphore::new(concurrency.max(1)));
+        let tasks: Vec<_> = self
+            .files
+            .iter()
+            .map(|file_path| {
+                let permits = Arc::clone(&permits);
+                let progress = Arc::clone(progress);
+                let file_path = file_path.clone();
+                let cfg = self.cfg.clone();
+                tokio::spawn(async move {
+                    let _permit = permits.acquire_owned().await;
+                    let result = Self::load_file_async(file_path, cfg).await;
+                    progress.record(result.is_ok());
+                    result
+                })
+            })
+            .collect();
 
+        let mut entities = Vec::new();
+        let mut load_errors = Vec::new();
+        for (file_path, task) in self.files.iter().zip(tasks) {
+            match task.await {
+                Ok(Ok(found)) => entities.extend(found),
+                Ok(Err(e)) => load_errors.push(e),
+                Err(e) => load_errors.push(Self::load_error(file_path, &e)),
+            }
+        }
+        self.load_errors = load_errors;
+        progress.finish();
         entities
     }
+
+    async fn load_file_async(
+        file_path: PathBuf,
+        cfg: GtsConfig,
+    ) -> Result<Vec<GtsEntity>, GtsLoadError> {
+        let content = tokio::fs::read_to_string(&file_path)
+            .await
+            .map_err(|e| Self::load_error(&file_path, &e))?;
+        let parse_path = file_path.clone();
+        tokio::task::spawn_blocking(move || {
+            Self::parse_file_content(&parse_path, &content)
+                .map(|value| Self::file_entities(&parse_path, &value, &cfg))
+                .map_err(|e| Self::load_error(&parse_path, &*e))
+        })
+        .await
+        .map_err(|e| Self::load_error(&file_path, &e))?
+    }
 }
 
 impl GtsReader for GtsFileReader {
@@ -202,12 +299,15 @@ impl GtsReader for GtsFileReader {
             self.paths
         );
 
-        #[allow(clippy::needless_collect)]
-        let entities: Vec<GtsEntity> = self
-            .files
-            .iter()
-            .flat_map(|file_path| self.process_file(file_path))
-            .collect();
+        let mut entities = Vec::new();
+        let mut load_errors = Vec::new();
+        for file_path in &self.files {
+            match self.process_file(file_path) {
+                Ok(found) => entities.extend(found),
+                Err(e) => load_errors.push(e),
+            }
+        }
+        self.load_errors = load_errors;
 
         Box::new(entities.into_iter())
     }
@@ -220,6 +320,10 @@ impl GtsReader for GtsFileReader {
     fn reset(&mut self) {
         self.initialized = false;
     }
+
+    fn load_errors(&self) -> Vec<GtsLoadError> {
+        self.load_errors.clone()
+    }
 }
 
 #[cfg(test)]
@@ -462,7 +566,7 @@ mod tests {
         fs::write(&file_path, r#"{"$id": "gts://test/schema"}"#).unwrap();
 
         let reader = GtsFileReader::new(&[], None);
-        let entities = reader.process_file(&file_path);
+        let entities = reader.process_file(&file_path).unwrap();
 
         assert_eq!(entities.len(), 1);
     }
@@ -478,7 +582,7 @@ mod tests {
         .unwrap();
 
         let reader = GtsFileReader::new(&[], None);
-        let entities = reader.process_file(&file_path);
+        let entities = reader.process_file(&file_path).unwrap();
 
         assert_eq!(entities.len(), 2);
     }
@@ -490,7 +594,7 @@ mod tests {
         fs::write(&file_path, r#"{"name": "test"}"#).unwrap();
 
         let reader = GtsFileReader::new(&[], None);
-        let entities = reader.process_file(&file_path);
+        let entities = reader.process_file(&file_path).unwrap();
 
         // Entities might get instance_id even without explicit $id
         // This depends on GtsEntity::new and extract_instance_ids behavior
@@ -499,15 +603,72 @@ mod tests {
     }
 
     #[test]
-    fn test_process_file_invalid_json_returns_empty() {
+    fn test_process_file_invalid_json_returns_error() {
         let temp_dir = TempDir::new().unwrap();
         let file_path = temp_dir.path().join("test.json");
         fs::write(&file_path, "{invalid}").unwrap();
 
         let reader = GtsFileReader::new(&[], None);
-        let entities = reader.process_file(&file_path);
+        let err = reader.process_file(&file_path).unwrap_err();
+
+        assert_eq!(err.path, file_path.to_string_lossy());
+        assert!(!err.message.is_empty());
+    }
+
+    #[test]
+    fn test_iter_records_load_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("bad.json"), "{invalid}").unwrap();
+        fs::write(
+            temp_dir.path().join("good.json"),
+            r#"{"$id": "gts.x.core.events.type.v1~"}"#,
+        )
+        .unwrap();
+
+        let mut reader = GtsFileReader::new(&[temp_dir.path().to_string_lossy().to_string()], None);
+        assert_eq!(reader.iter().count(), 1);
+        let errors = reader.load_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].path.ends_with("bad.json"));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_load_async_matches_sync_load() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("bad.json"), "{invalid}").unwrap();
+        for i in 0..5 {
+            fs::write(
+                temp_dir.path().join(format!("type{i}.yaml")),
+                format!("$id: gts.x.core.events.type{i}.v1~\n"),
+            )
+            .unwrap();
+        }
+        let paths = [temp_dir.path().to_string_lossy().to_string()];
+
+        let progress = Arc::new(GtsLoadProgress::default());
+        let mut reader = GtsFileReader::new(&paths, None);
+        let ids: Vec<_> = reader
+            .load_async(2, &progress)
+            .await
+            .iter()
+            .filter_map(GtsEntity::effective_id)
+            .collect();
+        let errors = reader.load_errors();
+
+        let mut sync_reader = GtsFileReader::new(&paths, None);
+        let sync_ids: Vec<_> = sync_reader
+            .iter()
+            .filter_map(|e| e.effective_id())
+            .collect();
+        assert_eq!(ids, sync_ids);
+        assert_eq!(errors, sync_reader.load_errors());
 
-        assert_eq!(entities.len(), 0);
+        let snapshot = progress.snapshot();
+        assert_eq!(snapshot.files_total, 6);
+        assert_eq!(snapshot.files_loaded, 5);
+        assert_eq!(snapshot.files_failed, 1);
+        assert!(snapshot.done);
     }
 
     #[test]
@@ -521,7 +682,7 @@ mod tests {
         .unwrap();
 
         let reader = GtsFileReader::new(&[], None);
-        let entities = reader.process_file(&file_path);
+        let entities = reader.process_file(&file_path).unwrap();
 
         // At least one entity with explicit $id should be found
         assert!(
@@ -1,3 +1,4 @@
+use gts_id::{GtsIdFieldError, GtsSegmentParts};
 use std::fmt;
 use std::str::FromStr;
 use std::sync::LazyLock;
@@ -25,6 +26,10 @@ pub enum GtsError {
 
     #[error("Invalid GTS wildcard pattern: {pattern}: {cause}")]
     Wildcard { pattern: String, cause: String },
+
+    /// A field given to [`GtsIdBuilder`] is missing or invalid.
+    #[error("Invalid GTS identifier: {0}")]
+    Field(#[from] GtsIdFieldError),
 }
 
 /// Parsed GTS segment containing vendor, package, namespace, type, and version info.
@@ -91,6 +96,31 @@ impl GtsIdSegment {
         self.is_uuid_tail = parsed.is_uuid_tail;
         Ok(())
     }
+
+    /// The segment's version.
+    #[must_use]
+    pub fn version(&self) -> GtsVersion {
+        GtsVersion {
+            major: self.ver_major,
+            minor: self.ver_minor,
+        }
+    }
+}
+
+/// Version of a GTS segment: `v<major>[.<minor>]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GtsVersion {
+    pub major: u32,
+    pub minor: Option<u32>,
+}
+
+impl fmt::Display for GtsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.minor {
+            Some(minor) => write!(f, "v{}.{minor}", self.major),
+            None => write!(f, "v{}", self.major),
+        }
+    }
 }
 
 /// GTS ID - a validated Global Type System identifier.
@@ -128,6 +158,11 @@ impl GtsID {
                 segment,
                 cause,
             },
+            // Not produced without a reservation policy
+            reserved @ gts_id::GtsIdError::Reserved { .. } => GtsError::Id {
+                id: id.to_owned(),
+                cause: reserved.to_string(),
+            },
         })?;
 
         // Convert ParsedSegment → GtsIdSegment
@@ -171,11 +206,66 @@ impl GtsID {
         })
     }
 
+    /// Start building an ID from its components, see [`GtsIdBuilder`].
+    #[must_use]
+    pub fn builder() -> GtsIdBuilder {
+        GtsIdBuilder::default()
+    }
+
     #[must_use]
     pub fn is_type(&self) -> bool {
         self.id.ends_with('~')
     }
 
+    /// The segment that defines the entity: the last one that is not a UUID tail.
+    #[must_use]
+    pub fn defining_segment(&self) -> &GtsIdSegment {
+        self.gts_id_segments
+            .iter()
+            .rev()
+            .find(|s| !s.is_uuid_tail)
+            .unwrap_or(&self.gts_id_segments[0])
+    }
+
+    /// Vendor of the [defining segment](Self::defining_segment).
+    #[must_use]
+    pub fn vendor(&self) -> &str {
+        &self.defining_segment().vendor
+    }
+
+    /// Package of the [defining segment](Self::defining_segment).
+    #[must_use]
+    pub fn package(&self) -> &str {
+        &self.defining_segment().package
+    }
+
+    /// Namespace of the [defining segment](Self::defining_segment).
+    #[must_use]
+    pub fn namespace(&self) -> &str {
+        &self.defining_segment().namespace
+    }
+
+    /// Type name of the [defining segment](Self::defining_segment).
+    #[must_use]
+    pub fn type_name(&self) -> &str {
+        &self.defining_segment().type_name
+    }
+
+    /// Version of the [defining segment](Self::defining_segment).
+    #[must_use]
+    pub fn version(&self) -> GtsVersion {
+        self.defining_segment().version()
+    }
+
+    /// The UUID tail of a combined anonymous instance ID.
+    #[must_use]
+    pub fn uuid_tail(&self) -> Option<Uuid> {
+        self.gts_id_segments
+            .last()
+            .filter(|s| s.is_uuid_tail)
+            .and_then(|s| Uuid::parse_str(&s.segment).ok())
+    }
+
     #[must_use]
     pub fn get_type_id(&self) -> Option<String> {
         if self.gts_id_segments.len() < 2 {
@@ -346,6 +436,172 @@ impl AsRef<str> for GtsID {
     }
 }
 
+/// Builds a [`GtsID`] from its components instead of string formatting.
+///
+/// The component methods describe one segment; [`chain`](Self::chain) ends it
+/// as a type segment and appends a raw segment (a type segment ending with
+/// `~`, an instance segment or a UUID tail). The last structured segment is a
+/// type unless [`instance`](Self::instance) is called. Nothing is checked
+/// until [`build`](Self::build), which assembles the segments with
+/// [`gts_id::GtsIdBuilder`]:
+///
+/// ```
+/// use gts::GtsID;
+///
+/// let id = GtsID::builder()
+///     .vendor("x")
+///     .package("core")
+///     .namespace("events")
+///     .type_name("type")
+///     .version(1, None)
+///     .chain("x.commerce.orders.order_placed.v1.0~")
+///     .build()
+///     .unwrap();
+/// assert_eq!(id.id, "gts.x.core.events.type.v1~x.commerce.orders.order_placed.v1.0~");
+/// assert_eq!(id.type_name(), "order_placed");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GtsIdBuilder {
+    segments: Vec<BuilderSegment>,
+    /// The segment the component methods describe
+    pending: Option<GtsSegmentParts>,
+    instance: bool,
+}
+
+#[derive(Debug, Clone)]
+enum BuilderSegment {
+    Parts(GtsSegmentParts),
+    /// A segment passed to `chain`
+    Raw(String),
+}
+
+impl GtsIdBuilder {
+    #[must_use]
+    pub fn vendor(mut self, vendor: impl Into<String>) -> Self {
+        self.pending.get_or_insert_default().vendor = vendor.into();
+        self
+    }
+
+    #[must_use]
+    pub fn package(mut self, package: impl Into<String>) -> Self {
+        self.pending.get_or_insert_default().package = package.into();
+        self
+    }
+
+    #[must_use]
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.pending.get_or_insert_default().namespace = namespace.into();
+        self
+    }
+
+    #[must_use]
+    pub fn type_name(mut self, type_name: impl Into<String>) -> Self {
+        self.pending.get_or_insert_default().type_name = type_name.into();
+        self
+    }
+
+    /// `version(1, None)` is `v1`, `version(1, 2)` is `v1.2`.
+    #[must_use]
+    pub fn version(mut self, major: u32, minor: impl Into<Option<u32>>) -> Self {
+        let parts = self.pending.get_or_insert_default();
+        parts.ver_major = Some(major);
+        parts.ver_minor = minor.into();
+        self
+    }
+
+    /// Make the last structured segment an instance segment (no trailing `~`).
+    #[must_use]
+    pub fn instance(mut self) -> Self {
+        self.instance = true;
+        self
+    }
+
+    /// End the segment described so far and append `segment`.
+    #[must_use]
+    pub fn chain(mut self, segment: impl Into<String>) -> Self {
+        self.push_pending();
+        self.segments.push(BuilderSegment::Raw(segment.into()));
+        self
+    }
+
+    /// Assemble and validate the ID.
+    ///
+    /// # Errors
+    /// Returns `GtsError::Field` naming the segment and field that is missing
+    /// or invalid, `GtsError::Segment` if a chained segment does not parse,
+    /// and `GtsError::Id` if the result is not a valid GTS ID.
+    pub fn build(mut self) -> Result<GtsID, GtsError> {
+        self.push_pending();
+        let last = self.segments.len().saturating_sub(1);
+        let mut builder = gts_id::GtsIdBuilder::new();
+        let mut instance = self.instance;
+        let mut uuid_tail = None;
+        // Offset of the segment in the assembled ID, for parse errors
+        let mut offset = GTS_PREFIX.len();
+        for (i, segment) in self.segments.into_iter().enumerate() {
+            let parts = match segment {
+                BuilderSegment::Parts(parts) => parts,
+                BuilderSegment::Raw(raw) => {
+                    let raw = raw.trim();
+                    // The first segment may be passed with its `gts.` prefix
+                    let raw = if i == 0 {
+                        raw.strip_prefix(GTS_PREFIX).unwrap_or(raw)
+                    } else {
+                        raw
+                    };
+                    if i == last && gts_id::is_uuid(raw) {
+                        uuid_tail = Some(raw.to_owned());
+                        continue;
+                    }
+                    let parsed = GtsIdSegment::new(i + 1, offset, raw)?;
+                    if i == last {
+                        instance = !parsed.is_type;
+                    }
+                    GtsSegmentParts {
+                        vendor: parsed.vendor,
+                        package: parsed.package,
+                        namespace: parsed.namespace,
+                        type_name: parsed.type_name,
+                        ver_major: Some(parsed.ver_major),
+                        ver_minor: parsed.ver_minor,
+                    }
+                }
+            };
+            offset += segment_len(&parts) + 1;
+            builder = builder.segment(parts);
+        }
+        if instance && uuid_tail.is_none() {
+            builder = builder.instance();
+        }
+        let id = builder.build()?;
+        GtsID::new(&format!("{id}{}", uuid_tail.unwrap_or_default()))
+    }
+
+    fn push_pending(&mut self) {
+        if let Some(parts) = self.pending.take() {
+            self.segments.push(BuilderSegment::Parts(parts));
+        }
+    }
+}
+
+/// Length of the segment `parts` assemble to, without the `~`.
+fn segment_len(parts: &GtsSegmentParts) -> usize {
+    let version = GtsVersion {
+        major: parts.ver_major.unwrap_or_default(),
+        minor: parts.ver_minor,
+    };
+    [
+        &parts.vendor,
+        &parts.package,
+        &parts.namespace,
+        &parts.type_name,
+    ]
+    .iter()
+    .map(|part| part.len() + 1)
+    .sum::<usize>()
+        + version.to_string().len()
+}
+
 /// GTS Wildcard pattern
 #[derive(Debug, Clone, PartialEq)]
 pub struct GtsWildcard {
@@ -547,6 +803,7 @@ impl<'de> serde::Deserialize<'de> for GtsInstanceId {
     }
 }
 
+#[cfg(feature = "schemars")]
 impl schemars::JsonSchema for GtsInstanceId {
     fn schema_name() -> std::borrow::Cow<'static, str> {
         std::borrow::Cow::Borrowed("GtsInstanceId")
@@ -620,6 +877,95 @@ impl GtsInstanceId {
         Self(GtsEntityId::new(&format!("{schema_id}{segment}")))
     }
 
+    /// Parse and validate a GTS instance ID: a valid GTS ID not ending with `~`.
+    ///
+    /// ```
+    /// use gts::GtsInstanceId;
+    ///
+    /// let id = GtsInstanceId::parse("gts.x.core.events.topic.v1~x.commerce._.orders.v1.2").unwrap();
+    /// assert_eq!(id.split(), ("gts.x.core.events.topic.v1~", "x.commerce._.orders.v1.2"));
+    /// assert_eq!(id.schema_id().unwrap(), "gts.x.core.events.topic.v1~");
+    /// assert_eq!(id.type_name(), Some("orders"));
+    /// assert_eq!(id.version().unwrap().to_string(), "v1.2");
+    /// assert!(GtsInstanceId::parse("gts.x.core.events.topic.v1~").is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `GtsError::Id` if `id` is not a valid GTS ID or is a type ID.
+    pub fn parse(id: &str) -> Result<Self, GtsError> {
+        let parsed = GtsID::new(id)?;
+        if parsed.is_type() {
+            return Err(GtsError::Id {
+                id: id.to_owned(),
+                cause: "Is a type ID (ends with '~'), not an instance ID".to_owned(),
+            });
+        }
+        Ok(Self(GtsEntityId::new(&parsed.id)))
+    }
+
+    /// The schema chain (through the last `~`) and the instance segment.
+    #[must_use]
+    pub fn split(&self) -> (&str, &str) {
+        let id = self.0.as_ref();
+        id.rfind('~')
+            .map_or(("", id), |tilde| id.split_at(tilde + 1))
+    }
+
+    /// The instance segment after the schema chain, e.g. `vendor.app.orders.v1.0`.
+    #[must_use]
+    pub fn instance_segment(&self) -> &str {
+        self.split().1
+    }
+
+    /// The type this is an instance of, `None` if the ID has no `~`.
+    #[must_use]
+    pub fn schema_id(&self) -> Option<GtsSchemaId> {
+        let (chain, _) = self.split();
+        (!chain.is_empty()).then(|| GtsSchemaId::new(chain))
+    }
+
+    /// Vendor of the instance segment.
+    #[must_use]
+    pub fn vendor(&self) -> Option<&str> {
+        self.components().map(|(parts, _)| parts[0])
+    }
+
+    /// Package of the instance segment.
+    #[must_use]
+    pub fn package(&self) -> Option<&str> {
+        self.components().map(|(parts, _)| parts[1])
+    }
+
+    /// Namespace of the instance segment.
+    #[must_use]
+    pub fn namespace(&self) -> Option<&str> {
+        self.components().map(|(parts, _)| parts[2])
+    }
+
+    /// Type name of the instance segment.
+    #[must_use]
+    pub fn type_name(&self) -> Option<&str> {
+        self.components().map(|(parts, _)| parts[3])
+    }
+
+    /// Version of the instance segment.
+    #[must_use]
+    pub fn version(&self) -> Option<GtsVersion> {
+        self.components().map(|(_, version)| version)
+    }
+
+    /// Vendor, package, namespace and type name of the instance segment, and
+    /// its version; `None` for a UUID tail or a malformed segment.
+    fn components(&self) -> Option<([&str; 4], GtsVersion)> {
+        let (chain, segment) = self.split();
+        let parsed = GtsIdSegment::new(2, chain.len(), segment)
+            .ok()
+            .filter(|s| !s.is_type && !s.is_wildcard && !s.is_uuid_tail)?;
+        let mut parts = segment.split('.');
+        let parts = [parts.next()?, parts.next()?, parts.next()?, parts.next()?];
+        Some((parts, parsed.version()))
+    }
+
     /// Returns the underlying string representation of the instance ID.
     #[must_use]
     pub fn into_string(self) -> String {
@@ -627,6 +973,25 @@ impl GtsInstanceId {
     }
 }
 
+impl FromStr for GtsInstanceId {
+    type Err = GtsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Instances of the same type path (schema chain, vendor, package, namespace
+/// and type name) are ordered by version; others are not comparable.
+impl PartialOrd for GtsInstanceId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        let (parts, version) = self.components()?;
+        let (other_parts, other_version) = other.components()?;
+        (self.split().0 == other.split().0 && parts == other_parts)
+            .then(|| version.cmp(&other_version))
+    }
+}
+
 impl fmt::Display for GtsInstanceId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -707,6 +1072,7 @@ impl<'de> serde::Deserialize<'de> for GtsSchemaId {
     }
 }
 
+#[cfg(feature = "schemars")]
 impl schemars::JsonSchema for GtsSchemaId {
     fn schema_name() -> std::borrow::Cow<'static, str> {
         std::borrow::Cow::Borrowed("GtsSchemaId")
@@ -834,6 +1200,119 @@ impl PartialEq<String> for GtsSchemaId {
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use gts_id::GtsIdField;
+
+    #[test]
+    fn test_gts_id_builder() {
+        let id = GtsID::builder()
+            .vendor("x")
+            .package("core")
+            .namespace("events")
+            .type_name("event")
+            .version(1, 2)
+            .build()
+            .unwrap();
+        assert_eq!(id.id, "gts.x.core.events.event.v1.2~");
+        assert_eq!(id.vendor(), "x");
+        assert_eq!(id.package(), "core");
+        assert_eq!(id.namespace(), "events");
+        assert_eq!(id.type_name(), "event");
+        assert_eq!(
+            id.version(),
+            GtsVersion {
+                major: 1,
+                minor: Some(2)
+            }
+        );
+        assert_eq!(id.version().to_string(), "v1.2");
+
+        // Structured instance segment after a raw type segment
+        let id = GtsID::builder()
+            .chain("gts.x.core.events.topic.v1~")
+            .vendor("x")
+            .package("commerce")
+            .namespace("_")
+            .type_name("orders")
+            .version(1, None)
+            .instance()
+            .build()
+            .unwrap();
+        assert_eq!(id.id, "gts.x.core.events.topic.v1~x.commerce._.orders.v1");
+        let id = GtsID::builder()
+            .chain("x.core.events.topic.v1")
+            .vendor("x")
+            .package("commerce")
+            .namespace("_")
+            .type_name("orders")
+            .version(1, None)
+            .instance()
+            .build()
+            .unwrap();
+        assert_eq!(id.id, "gts.x.core.events.topic.v1~x.commerce._.orders.v1");
+        assert!(!id.is_type());
+        assert_eq!(id.package(), "commerce");
+        assert!(id.uuid_tail().is_none());
+
+        // Missing components and invalid tokens fail at build(), naming the field
+        let missing = GtsID::builder().vendor("x").package("core").build();
+        assert!(
+            matches!(&missing, Err(GtsError::Field(err)) if err.field() == Some((1, GtsIdField::Namespace))),
+            "{missing:?}"
+        );
+        let invalid = GtsID::builder()
+            .chain("x.core.events.topic.v1~")
+            .vendor("X")
+            .package("core")
+            .namespace("events")
+            .type_name("event")
+            .version(1, None)
+            .build();
+        assert!(
+            matches!(&invalid, Err(GtsError::Field(err)) if err.field() == Some((2, GtsIdField::Vendor))),
+            "{invalid:?}"
+        );
+        let invalid = GtsID::builder()
+            .chain("x.core.events.topic.v1~")
+            .chain("x.core.Events.topic.v1")
+            .build();
+        assert!(
+            matches!(
+                &invalid,
+                Err(GtsError::Segment {
+                    num: 2,
+                    offset: 27,
+                    ..
+                })
+            ),
+            "{invalid:?}"
+        );
+        assert!(matches!(
+            GtsID::builder().build(),
+            Err(GtsError::Field(GtsIdFieldError::NoSegments))
+        ));
+
+        // A UUID tail closes the chain
+        let id = GtsID::builder()
+            .chain("gts.x.core.events.topic.v1~")
+            .chain("7a1d2f34-5678-49ab-9012-abcdef123456")
+            .build()
+            .unwrap();
+        assert_eq!(
+            id.uuid_tail().map(|uuid| uuid.to_string()).as_deref(),
+            Some("7a1d2f34-5678-49ab-9012-abcdef123456")
+        );
+    }
+
+    #[test]
+    fn test_gts_id_accessors_uuid_tail() {
+        let id =
+            GtsID::new("gts.x.core.events.type.v1~7a1d2f34-5678-49ab-9012-abcdef123456").unwrap();
+        assert_eq!(id.type_name(), "type");
+        assert_eq!(
+            id.uuid_tail().unwrap().to_string(),
+            "7a1d2f34-5678-49ab-9012-abcdef123456"
+        );
+    }
 
     #[test]
     fn test_gts_id_valid() {
@@ -1413,4 +1892,53 @@ mod tests {
         assert!(!l1.is_subset_of(&l3));
         assert!(!l2.is_subset_of(&l3));
     }
+
+    #[test]
+    fn test_gts_instance_id_components() {
+        let id: GtsInstanceId = "gts.x.core.events.topic.v1~x.commerce._.orders.v2.1"
+            .parse()
+            .unwrap();
+        assert_eq!(id.schema_id().unwrap(), "gts.x.core.events.topic.v1~");
+        assert_eq!(id.instance_segment(), "x.commerce._.orders.v2.1");
+        assert_eq!(id.vendor(), Some("x"));
+        assert_eq!(id.package(), Some("commerce"));
+        assert_eq!(id.namespace(), Some("_"));
+        assert_eq!(id.type_name(), Some("orders"));
+        assert_eq!(
+            id.version(),
+            Some(GtsVersion {
+                major: 2,
+                minor: Some(1)
+            })
+        );
+
+        // Combined anonymous instance: the UUID tail has no components
+        let id =
+            GtsInstanceId::parse("gts.x.core.events.topic.v1~7a1d2f34-5678-49ab-9012-abcdef123456")
+                .unwrap();
+        assert_eq!(id.schema_id().unwrap(), "gts.x.core.events.topic.v1~");
+        assert_eq!(id.vendor(), None);
+        assert_eq!(id.version(), None);
+
+        assert!(GtsInstanceId::parse("gts.x.core.events.topic.v1~").is_err());
+        assert!(GtsInstanceId::parse("gts.x.core.events.topic.v1").is_err());
+        assert!(GtsInstanceId::parse("not-an-id").is_err());
+        assert_eq!(GtsInstanceId::new("", "x.a.b.c.v1").schema_id(), None);
+    }
+
+    #[test]
+    fn test_gts_instance_id_ordered_by_version() {
+        let id = |s: &str| GtsInstanceId::parse(s).unwrap();
+        let v1 = id("gts.x.core.events.topic.v1~x.app._.orders.v1");
+        let v1_2 = id("gts.x.core.events.topic.v1~x.app._.orders.v1.2");
+        let v2 = id("gts.x.core.events.topic.v1~x.app._.orders.v2.0");
+        assert!(v1 < v1_2 && v1_2 < v2);
+        assert_eq!(v2.partial_cmp(&v2.clone()), Some(std::cmp::Ordering::Equal));
+
+        // Other type paths are not comparable
+        let other_type = id("gts.x.core.events.topic.v1~x.app._.refunds.v1.0");
+        let other_schema = id("gts.x.core.events.topic.v2~x.app._.orders.v1.0");
+        assert_eq!(v1.partial_cmp(&other_type), None);
+        assert_eq!(v1.partial_cmp(&other_schema), None);
+    }
 }
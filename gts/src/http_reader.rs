@@ -0,0 +1,239 @@
+//! [`GtsReader`] fetching entities from an HTTP(S) base URL.
+//!
+//! The base URL serves a schema directory as the generators write it, e.g.
+//! an object storage bucket the schemas are synced to: the entity with ID
+//! `gts.x.core.events.type.v1~` is fetched from
+//! `<base>/gts.x.core.events.type.v1~.schema.json` and instances from
+//! `<base>/<id>.instance.json`, with the ID part encoded by the reader's
+//! [`GtsFileNameEncoding`]. Nothing is listed up front; entities are fetched
+//! the first time the store asks for them and cached, misses included, until
+//! the reader is reset.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use crate::entities::{GtsConfig, GtsEntity, GtsFile};
+use crate::file_name::GtsFileNameEncoding;
+use crate::store::{GtsLoadError, GtsReader};
+
+/// Timeout of one request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct GtsHttpReader {
+    base_url: String,
+    cfg: GtsConfig,
+    encoding: GtsFileNameEncoding,
+    agent: ureq::Agent,
+    /// Fetched entities by ID, `None` for IDs the server does not have
+    cache: Mutex<HashMap<String, Option<GtsEntity>>>,
+    load_errors: Mutex<Vec<GtsLoadError>>,
+}
+
+impl GtsHttpReader {
+    #[must_use]
+    pub fn new(base_url: &str, cfg: Option<GtsConfig>) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .build()
+            .into();
+        GtsHttpReader {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            cfg: cfg.unwrap_or_default(),
+            encoding: GtsFileNameEncoding::default(),
+            agent,
+            cache: Mutex::new(HashMap::new()),
+            load_errors: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Encode IDs in the requested file names with `encoding`.
+    #[must_use]
+    pub fn with_encoding(mut self, encoding: GtsFileNameEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// URL of the file holding `entity_id`
+    #[must_use]
+    pub fn url_for(&self, entity_id: &str) -> String {
+        let file_name = if entity_id.ends_with('~') {
+            self.encoding.schema_file_name(entity_id)
+        } else {
+            self.encoding.instance_file_name(entity_id)
+        };
+        format!("{}/{file_name}", self.base_url)
+    }
+
+    /// The entity at `url`, `Ok(None)` if the server has none
+    fn fetch(&self, entity_id: &str, url: &str) -> Result<Option<GtsEntity>, String> {
+        let mut response = match self.agent.get(url).call() {
+            Ok(response) => response,
+            Err(ureq::Error::StatusCode(404 | 410)) => return Ok(None),
+            Err(e) => return Err(e.to_string()),
+        };
+        let text = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| e.to_string())?;
+        let content: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+        let name = url.rsplit('/').next().unwrap_or_default().to_owned();
+        let entity = GtsEntity::new(
+            Some(GtsFile::new(url.to_owned(), name, content.clone())),
+            None,
+            &content,
+            Some(&self.cfg),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        );
+        match entity.effective_id() {
+            Some(id) if id == entity_id => Ok(Some(entity)),
+            Some(id) => Err(format!("expected entity '{entity_id}', found '{id}'")),
+            None => Err(format!("no entity ID, expected '{entity_id}'")),
+        }
+    }
+}
+
+impl GtsReader for GtsHttpReader {
+    fn iter(&mut self) -> Box<dyn Iterator<Item = GtsEntity> + '_> {
+        // The server is not listed; entities arrive through `read_by_id`
+        Box::new(std::iter::empty())
+    }
+
+    fn read_by_id(&self, entity_id: &str) -> Option<GtsEntity> {
+        if let Some(cached) = self
+            .cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(entity_id)
+        {
+            return cached.clone();
+        }
+
+        let url = self.url_for(entity_id);
+        tracing::debug!("Fetching {entity_id} from {url}");
+        let entity = self.fetch(entity_id, &url).unwrap_or_else(|message| {
+            tracing::warn!("Cannot load '{entity_id}' from {url}: {message}");
+            self.load_errors
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(GtsLoadError { path: url, message });
+            None
+        });
+        self.cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(entity_id.to_owned(), entity.clone());
+        entity
+    }
+
+    fn reset(&mut self) {
+        self.cache
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+        self.load_errors
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    fn load_errors(&self) -> Vec<GtsLoadError> {
+        self.load_errors
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::store::GtsStore;
+    use serde_json::json;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serve `files` by request path on a local port, counting requests
+    fn serve(files: HashMap<String, String>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut header = String::new();
+                while reader.read_line(&mut header).unwrap() > 2 {
+                    header.clear();
+                }
+                counter.fetch_add(1, Ordering::SeqCst);
+                let path = request_line.split(' ').nth(1).unwrap_or_default();
+                let response = match files.get(path) {
+                    Some(body) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    ),
+                    None => {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_owned()
+                    }
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (base, requests)
+    }
+
+    #[test]
+    fn test_http_reader_fetches_lazily_and_caches() {
+        let schema = json!({
+            "$id": "gts://gts.x.core.events.type.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object"
+        });
+        let (base, requests) = serve(HashMap::from([(
+            "/schemas/gts.x.core.events.type.v1~.schema.json".to_owned(),
+            schema.to_string(),
+        )]));
+        let reader = GtsHttpReader::new(&format!("{base}/schemas/"), None);
+        let mut store = GtsStore::new(Some(Box::new(reader)));
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+        let fetched = store
+            .get_schema_content("gts.x.core.events.type.v1~")
+            .unwrap();
+        assert_eq!(fetched, schema);
+        assert!(store.get("gts.x.core.events.other.v1~").is_none());
+        assert!(store.get("gts.x.core.events.other.v1~").is_none());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_http_reader_rejects_mismatched_entity() {
+        let (base, _) = serve(HashMap::from([(
+            "/gts.x.core.events.type.v1~.schema.json".to_owned(),
+            json!({"$id": "gts://gts.x.core.events.other.v1~", "$schema": "http://json-schema.org/draft-07/schema#"})
+                .to_string(),
+        )]));
+        let reader = GtsHttpReader::new(&base, None);
+        assert!(reader.read_by_id("gts.x.core.events.type.v1~").is_none());
+        let errors = reader.load_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0]
+                .message
+                .contains("found 'gts.x.core.events.other.v1~'")
+        );
+    }
+}
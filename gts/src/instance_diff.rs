@@ -0,0 +1,220 @@
+//! Structural diff of two instances of the same type.
+//!
+//! Unlike a text diff, property order and formatting are ignored, and the
+//! identity fields (`id`, `type`, ... as selected for each instance) are left
+//! out since they differ between any two instances by construction.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::entities::GtsEntity;
+use crate::store::{GtsStore, StoreError};
+
+#[derive(Debug, Error)]
+pub enum InstanceDiffError {
+    #[error("Entity '{0}' is a schema, not an instance")]
+    NotAnInstance(String),
+    #[error("Instances have different types: '{schema_a}' and '{schema_b}'")]
+    TypeMismatch { schema_a: String, schema_b: String },
+    #[error(transparent)]
+    Store(#[from] StoreError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A single property that differs, addressed like `a.b[0].c`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyChange {
+    pub path: String,
+    pub kind: PropertyChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+impl fmt::Display for PropertyChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: &Option<Value>| v.as_ref().map(Value::to_string).unwrap_or_default();
+        match self.kind {
+            PropertyChangeKind::Added => write!(f, "+ {}: {}", self.path, show(&self.new)),
+            PropertyChangeKind::Removed => write!(f, "- {}: {}", self.path, show(&self.old)),
+            PropertyChangeKind::Changed => write!(
+                f,
+                "~ {}: {} -> {}",
+                self.path,
+                show(&self.old),
+                show(&self.new)
+            ),
+        }
+    }
+}
+
+/// Property changes turning `old` into `new`, sorted by path.
+///
+/// Objects are compared key by key and arrays index by index; any other
+/// difference (including a change of JSON type) is a single `changed` entry.
+#[must_use]
+pub fn diff_values(old: &Value, new: &Value) -> Vec<PropertyChange> {
+    let mut changes = Vec::new();
+    diff_at(String::new(), old, new, &mut changes);
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+fn diff_at(path: String, old: &Value, new: &Value, changes: &mut Vec<PropertyChange>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, old_value) in a {
+                let child = join_key(&path, key);
+                match b.get(key) {
+                    Some(new_value) => diff_at(child, old_value, new_value, changes),
+                    None => changes.push(removed(child, old_value)),
+                }
+            }
+            for (key, new_value) in b {
+                if !a.contains_key(key) {
+                    changes.push(added(join_key(&path, key), new_value));
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let child = format!("{path}[{i}]");
+                match (a.get(i), b.get(i)) {
+                    (Some(old_value), Some(new_value)) => {
+                        diff_at(child, old_value, new_value, changes);
+                    }
+                    (Some(old_value), None) => changes.push(removed(child, old_value)),
+                    (None, Some(new_value)) => changes.push(added(child, new_value)),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => changes.push(PropertyChange {
+            path,
+            kind: PropertyChangeKind::Changed,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn join_key(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn added(path: String, value: &Value) -> PropertyChange {
+    PropertyChange {
+        path,
+        kind: PropertyChangeKind::Added,
+        old: None,
+        new: Some(value.clone()),
+    }
+}
+
+fn removed(path: String, value: &Value) -> PropertyChange {
+    PropertyChange {
+        path,
+        kind: PropertyChangeKind::Removed,
+        old: Some(value.clone()),
+        new: None,
+    }
+}
+
+/// Content of an instance without its identity fields.
+fn comparable_content(entity: &GtsEntity) -> Value {
+    let mut content = entity.content.clone();
+    if let Value::Object(map) = &mut content {
+        for field in [
+            &entity.selected_entity_field,
+            &entity.selected_schema_id_field,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            map.remove(field);
+        }
+    }
+    content
+}
+
+/// Diff instance `id_b` against instance `id_a`.
+///
+/// Returns the shared schema ID (if any) and the property changes.
+///
+/// # Errors
+/// Returns an error if either ID is not a registered instance or the two
+/// instances conform to different schemas.
+pub fn diff_instances(
+    store: &mut GtsStore,
+    id_a: &str,
+    id_b: &str,
+) -> Result<(Option<String>, Vec<PropertyChange>), InstanceDiffError> {
+    let mut load = |id: &str| -> Result<(Option<String>, Value), InstanceDiffError> {
+        let entity = store
+            .get(id)
+            .ok_or_else(|| StoreError::EntityNotFound(id.to_owned()))?;
+        if entity.is_schema {
+            return Err(InstanceDiffError::NotAnInstance(id.to_owned()));
+        }
+        Ok((entity.schema_id.clone(), comparable_content(entity)))
+    };
+    let (schema_a, content_a) = load(id_a)?;
+    let (schema_b, content_b) = load(id_b)?;
+    if schema_a != schema_b {
+        return Err(InstanceDiffError::TypeMismatch {
+            schema_a: schema_a.unwrap_or_default(),
+            schema_b: schema_b.unwrap_or_default(),
+        });
+    }
+    Ok((schema_a, diff_values(&content_a, &content_b)))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_values_ignores_order() {
+        let old = json!({"a": 1, "b": {"c": [1, 2], "d": "x"}, "gone": true});
+        let new = json!({"b": {"d": "y", "c": [1, 2, 3]}, "a": 1, "extra": null});
+
+        let changes = diff_values(&old, &new);
+        let lines: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "+ b.c[2]: 3",
+                "~ b.d: \"x\" -> \"y\"",
+                "+ extra: null",
+                "- gone: true",
+            ]
+        );
+        assert!(diff_values(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_diff_values_type_change() {
+        let changes = diff_values(&json!({"a": {"b": 1}}), &json!({"a": [1]}));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "a");
+        assert_eq!(changes[0].kind, PropertyChangeKind::Changed);
+    }
+}
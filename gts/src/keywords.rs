@@ -0,0 +1,273 @@
+//! Custom schema keyword handlers.
+//!
+//! Teams extend schemas with their own keywords (`x-acl`, `x-pii`, ...) that
+//! the JSON Schema validator ignores. A handler registered for such a keyword
+//! is called for every occurrence of it in the schema chain of the entity
+//! being validated, so the keyword is checked once in the store rather than in
+//! every consuming service:
+//!
+//! ```
+//! use gts::{GtsKeywordContext, GtsOps};
+//! use serde_json::json;
+//!
+//! let mut ops = GtsOps::new(None, None, 0);
+//! ops.register_keyword("x-pii", |ctx: &GtsKeywordContext<'_>| {
+//!     if ctx.value.is_boolean() {
+//!         Ok(())
+//!     } else {
+//!         Err(format!("expected a boolean, got {}", ctx.value))
+//!     }
+//! });
+//! let result = ops.add_entity(
+//!     &json!({
+//!         "$schema": "http://json-schema.org/draft-07/schema#",
+//!         "$id": "gts://gts.x.core.users.user.v1~",
+//!         "type": "object",
+//!         "properties": {"email": {"type": "string", "x-pii": "yes"}}
+//!     }),
+//!     false,
+//! );
+//! assert!(!result.ok);
+//! assert!(result.error.contains("x-pii"));
+//! ```
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::entities::GtsEntity;
+
+/// Schema keywords whose values are maps of names to subschemas.
+const SCHEMA_MAP_KEYWORDS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "$defs",
+    "definitions",
+    "dependentSchemas",
+];
+
+/// Schema keywords whose values are data rather than subschemas.
+const DATA_KEYWORDS: &[&str] = &["const", "enum", "default", "examples"];
+
+/// An occurrence of a custom keyword, passed to its handler.
+#[derive(Debug, Clone, Copy)]
+pub struct GtsKeywordContext<'a> {
+    pub keyword: &'a str,
+    /// The keyword's value in the schema
+    pub value: &'a Value,
+    /// The schema of the chain declaring the keyword
+    pub schema_id: &'a str,
+    /// JSON pointer of the subschema declaring the keyword, `""` for the root
+    pub path: &'a str,
+    /// The entity being validated: an instance or a schema
+    pub entity: &'a GtsEntity,
+    /// The schema IDs of the entity's type chain, from base to leaf
+    pub chain: &'a [String],
+}
+
+/// A keyword occurrence its handler rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GtsKeywordViolation {
+    pub keyword: String,
+    pub schema_id: String,
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for GtsKeywordViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}#{} {}: {}",
+            self.schema_id, self.path, self.keyword, self.message
+        )
+    }
+}
+
+type KeywordHandler = Arc<dyn Fn(&GtsKeywordContext<'_>) -> Result<(), String> + Send + Sync>;
+
+/// Handlers of custom schema keywords, by keyword name.
+#[derive(Clone, Default)]
+pub struct GtsKeywordHandlers {
+    handlers: BTreeMap<String, KeywordHandler>,
+}
+
+impl fmt::Debug for GtsKeywordHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+impl GtsKeywordHandlers {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate `keyword` with `handler`, replacing its previous handler.
+    pub fn register<F>(&mut self, keyword: impl Into<String>, handler: F)
+    where
+        F: Fn(&GtsKeywordContext<'_>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.handlers.insert(keyword.into(), Arc::new(handler));
+    }
+
+    /// Stop validating `keyword`; returns whether it had a handler.
+    pub fn unregister(&mut self, keyword: &str) -> bool {
+        self.handlers.remove(keyword).is_some()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// The keywords with a handler, sorted.
+    pub fn keywords(&self) -> impl Iterator<Item = &str> {
+        self.handlers.keys().map(String::as_str)
+    }
+
+    /// Run the handlers on the keywords of `schema` (the raw content of
+    /// `schema_id`, one schema of `chain`) for `entity`.
+    #[must_use]
+    pub fn check(
+        &self,
+        schema_id: &str,
+        schema: &Value,
+        entity: &GtsEntity,
+        chain: &[String],
+    ) -> Vec<GtsKeywordViolation> {
+        let mut violations = Vec::new();
+        if !self.is_empty() {
+            self.walk(schema, "", &mut |keyword, value, path| {
+                let ctx = GtsKeywordContext {
+                    keyword,
+                    value,
+                    schema_id,
+                    path,
+                    entity,
+                    chain,
+                };
+                if let Err(message) = (self.handlers[keyword])(&ctx) {
+                    violations.push(GtsKeywordViolation {
+                        keyword: keyword.to_owned(),
+                        schema_id: schema_id.to_owned(),
+                        path: path.to_owned(),
+                        message,
+                    });
+                }
+            });
+        }
+        violations
+    }
+
+    /// Call `visit` with each registered keyword of the subschema `schema` at
+    /// `path` and of its nested subschemas.
+    fn walk(&self, schema: &Value, path: &str, visit: &mut dyn FnMut(&str, &Value, &str)) {
+        match schema {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if self.handlers.contains_key(key) {
+                        visit(key, value, path);
+                        continue;
+                    }
+                    if DATA_KEYWORDS.contains(&key.as_str()) {
+                        continue;
+                    }
+                    let path = format!("{path}/{}", escape_pointer(key));
+                    match value {
+                        Value::Object(entries) if SCHEMA_MAP_KEYWORDS.contains(&key.as_str()) => {
+                            for (name, subschema) in entries {
+                                let path = format!("{path}/{}", escape_pointer(name));
+                                self.walk(subschema, &path, visit);
+                            }
+                        }
+                        _ => self.walk(value, &path, visit),
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    self.walk(item, &format!("{path}/{i}"), visit);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn escape_pointer(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entity(content: &Value) -> GtsEntity {
+        GtsEntity::new(
+            None,
+            None,
+            content,
+            None,
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_check_visits_nested_keywords_only() {
+        let mut handlers = GtsKeywordHandlers::new();
+        handlers.register("x-acl", |ctx| {
+            if ctx.value.as_str() == Some("admin") {
+                Ok(())
+            } else {
+                Err(format!("unknown role {}", ctx.value))
+            }
+        });
+        let schema = json!({
+            "type": "object",
+            "x-acl": "admin",
+            "properties": {
+                // A property named like the keyword is not an occurrence
+                "x-acl": {"type": "string", "x-acl": "root"},
+                "tags": {"type": "array", "items": {"x-acl": "guest"}}
+            },
+            "default": {"x-acl": "ignored"}
+        });
+        let chain = vec!["gts.x.core.acl.doc.v1~".to_owned()];
+        let violations = handlers.check(&chain[0], &schema, &entity(&json!({})), &chain);
+        let mut messages: Vec<String> = violations.iter().map(ToString::to_string).collect();
+        messages.sort();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0],
+            "gts.x.core.acl.doc.v1~#/properties/tags/items x-acl: unknown role \"guest\""
+        );
+        assert_eq!(
+            messages[1],
+            "gts.x.core.acl.doc.v1~#/properties/x-acl x-acl: unknown role \"root\""
+        );
+    }
+
+    #[test]
+    fn test_register_replaces_and_unregister_removes() {
+        let mut handlers = GtsKeywordHandlers::new();
+        handlers.register("x-pii", |_| Err("first".to_owned()));
+        handlers.register("x-pii", |_| Ok(()));
+        let schema = json!({"x-pii": true});
+        assert!(
+            handlers
+                .check("s", &schema, &entity(&json!({})), &[])
+                .is_empty()
+        );
+        assert_eq!(handlers.keywords().collect::<Vec<_>>(), ["x-pii"]);
+        assert!(handlers.unregister("x-pii"));
+        assert!(handlers.is_empty());
+    }
+}
@@ -1,25 +1,110 @@
+pub mod backend;
+pub mod conformance;
 pub mod entities;
+pub mod example_gen;
+pub mod file_name;
 pub mod files_reader;
 pub mod gts;
+#[cfg(feature = "http")]
+pub mod http_reader;
+pub mod instance_diff;
+pub mod keywords;
+pub mod matcher;
+pub mod migrations;
 pub mod ops;
+pub mod partition;
 pub mod path_resolver;
+pub mod redaction;
+pub mod ref_resolver;
+pub mod reservations;
+pub mod retired;
 pub mod schema;
 pub mod schema_cast;
 pub mod schema_compat;
+pub mod schema_diff;
+pub mod schema_evolution;
+pub mod schema_flatten;
+pub mod schema_metadata;
 pub mod schema_traits;
+pub mod storage;
 pub mod store;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_support;
+pub mod trait_manifest;
+pub mod trait_query;
+pub mod transaction;
+pub mod typed_id;
+pub mod version;
+pub mod well_known;
 pub mod x_gts_ref;
 
 // Re-export commonly used types
-pub use entities::{GtsConfig, GtsEntity, GtsFile, ValidationError, ValidationResult};
+pub use backend::GtsBackend;
+pub use conformance::{
+    ConformanceError, ConformanceFailure, ConformanceReport, ConformanceVector, run_conformance,
+};
+pub use entities::{
+    GtsConfig, GtsEntity, GtsEntityTypeError, GtsFile, ValidationError, ValidationResult,
+};
+pub use example_gen::{ExampleGenerator, ExpectedViolation, InvalidExample, ViolationKind};
+pub use file_name::{GtsFileNameEncoding, INSTANCE_FILE_SUFFIX, SCHEMA_FILE_SUFFIX};
 pub use files_reader::GtsFileReader;
-pub use gts::{GtsError, GtsID, GtsIdSegment, GtsInstanceId, GtsSchemaId, GtsWildcard};
+pub use gts::{
+    GtsError, GtsID, GtsIdBuilder, GtsIdSegment, GtsInstanceId, GtsSchemaId, GtsVersion,
+    GtsWildcard,
+};
+pub use gts_id::{GtsIdField, GtsIdFieldError};
+#[cfg(feature = "http")]
+pub use http_reader::GtsHttpReader;
+pub use instance_diff::{InstanceDiffError, PropertyChange, PropertyChangeKind, diff_values};
+pub use keywords::{GtsKeywordContext, GtsKeywordHandlers, GtsKeywordViolation};
+pub use matcher::GtsPattern;
+pub use migrations::{GtsMigrationFn, GtsMigrations};
 pub use ops::GtsOps;
+pub use partition::{
+    DanglingRef, ExternalRef, GtsBundle, GtsPartitioning, PartitionError, PartitionKey,
+    PartitionReport, PartitionSummary,
+};
 pub use path_resolver::JsonPathResolver;
+pub use redaction::{
+    DEFAULT_REDACTION_MASK, GtsRedaction, RedactionDocument, RedactionError, RedactionRule,
+    load_redaction,
+};
+pub use ref_resolver::{GtsRefResolver, RefResolverError};
+pub use reservations::{
+    RESERVATIONS_INSTANCE_ID, ReservationEntry, ReservationsDocument, ReservationsError,
+    load_reservations,
+};
+pub use retired::{
+    GtsRetiredIds, RETIRED_IDS_INSTANCE_ID, RetiredIdEntry, RetiredIdsDocument, RetiredIdsError,
+    load_retired_ids,
+};
 pub use schema::{
     GtsDeserialize, GtsDeserializeWrapper, GtsNoDirectDeserialize, GtsNoDirectSerialize, GtsSchema,
     GtsSerialize, GtsSerializeWrapper, deserialize_gts, serialize_gts, strip_schema_metadata,
 };
-pub use schema_cast::{GtsEntityCastResult, SchemaCastError};
-pub use store::{GtsReader, GtsStore, GtsStoreQueryResult, StoreError};
+pub use schema_cast::{GtsCastChainResult, GtsEntityCastResult, SchemaCastError};
+pub use schema_diff::{ChangeLevel, ConstraintChange, ConstraintEffect, SchemaDiff, diff_schemas};
+pub use schema_evolution::{
+    EvolutionKind, EvolutionReport, SchemaEvolution, check_store_evolution,
+};
+pub use schema_flatten::{ChainConflict, ChainConflictKind, FlattenedSchema};
+pub use schema_metadata::{
+    GtsChangelogEntry, GtsMetadataIssue, GtsSchemaMetadata, SchemaMetadataError, X_GTS_CHANGELOG,
+    X_GTS_OWNER,
+};
+#[cfg(feature = "sled")]
+pub use storage::GtsSledStorage;
+pub use storage::{GtsMemoryStorage, GtsStorage, GtsStorageReader, GtsStoredEntry, StorageError};
+pub use store::{
+    GtsFileNameMismatch, GtsFilterMatch, GtsLoadError, GtsLoadProgress, GtsLoadProgressSnapshot,
+    GtsQueryMatchExplanation, GtsReader, GtsSegmentMatch, GtsStore, GtsStoreHealth,
+    GtsStoreQueryResult, StoreError,
+};
+pub use trait_manifest::{TraitManifestEntry, TraitManifestError, TraitMap, build_trait_manifest};
+pub use trait_query::{TraitFilter, TraitOp, TraitQuery, TraitQueryError, TraitQueryMatch};
+pub use transaction::{StoreTransaction, TransactionError};
+pub use typed_id::{GtsTypedId, GtsTypedIdError, parse_typed_id};
+pub use version::{is_compatible_upgrade, latest_of};
+pub use well_known::{GtsWellKnownBody, GtsWellKnownInstance, GtsWellKnownInstanceError};
 pub use x_gts_ref::{XGtsRefValidationError, XGtsRefValidator};
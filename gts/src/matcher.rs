@@ -0,0 +1,390 @@
+//! GTS ID patterns and set operations on them.
+//!
+//! A [`GtsPattern`] is an exact GTS ID or a wildcard pattern such as
+//! `gts.x.core.*` or `gts.x.core.events.topic.v1~*`. It matches the IDs that
+//! [`GtsID::wildcard_match`] accepts: every segment of the pattern constrains
+//! the corresponding segment of the ID (a pattern segment without a minor
+//! version accepts any minor), a trailing `*` only constrains the fields
+//! before it, and IDs may continue the chain after the pattern's last segment.
+//!
+//! Beyond matching, patterns can be intersected and compared, e.g. to route a
+//! capability to the handlers whose patterns overlap it:
+//!
+//! ```
+//! use gts::{GtsID, GtsPattern};
+//!
+//! let events = GtsPattern::parse("gts.x.core.events.*")?;
+//! let topics = GtsPattern::parse("gts.x.core.events.topic.v1~*")?;
+//!
+//! assert!(events.matches(&GtsID::new("gts.x.core.events.topic.v1~x.app._.orders.v1")?));
+//! assert!(events.subsumes(&topics));
+//! assert_eq!(events.intersection(&topics), Some(topics.clone()));
+//! assert!(!topics.intersects(&GtsPattern::parse("gts.x.core.audit.*")?));
+//! # Ok::<(), gts::GtsError>(())
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::gts::{GTS_PREFIX, GtsError, GtsID, GtsIdSegment, GtsWildcard};
+
+/// An exact GTS ID or wildcard pattern, see the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct GtsPattern {
+    wildcard: GtsWildcard,
+}
+
+impl GtsPattern {
+    /// Parses an exact GTS ID or a pattern ending in `.*` or `~*`.
+    ///
+    /// # Errors
+    /// Returns `GtsError::Wildcard` if the pattern is invalid.
+    pub fn parse(pattern: &str) -> Result<Self, GtsError> {
+        GtsWildcard::new(pattern).map(Self::from)
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.wildcard.id
+    }
+
+    #[must_use]
+    pub fn as_wildcard(&self) -> &GtsWildcard {
+        &self.wildcard
+    }
+
+    /// Whether the pattern contains a `*`.
+    #[must_use]
+    pub fn is_wildcard(&self) -> bool {
+        self.wildcard.id.contains('*')
+    }
+
+    #[must_use]
+    pub fn matches(&self, id: &GtsID) -> bool {
+        id.wildcard_match(&self.wildcard)
+    }
+
+    /// Like [`Self::matches`] for an ID string; invalid IDs match nothing.
+    #[must_use]
+    pub fn matches_str(&self, id: &str) -> bool {
+        GtsID::new(id).is_ok_and(|id| self.matches(&id))
+    }
+
+    fn segments(&self) -> impl Iterator<Item = Constraint<'_>> {
+        self.wildcard.gts_id_segments.iter().map(Constraint::of)
+    }
+
+    /// Whether some GTS ID matches both patterns.
+    #[must_use]
+    pub fn intersects(&self, other: &GtsPattern) -> bool {
+        for (a, b) in self.segments().zip(other.segments()) {
+            if !a.compatible(&b) {
+                return false;
+            }
+            if a.open || b.open {
+                return true;
+            }
+        }
+        // The shorter pattern accepts any continuation of the longer one
+        true
+    }
+
+    /// Whether every GTS ID matching `other` also matches this pattern.
+    #[must_use]
+    pub fn subsumes(&self, other: &GtsPattern) -> bool {
+        let mut narrow = other.segments();
+        for broad in self.segments() {
+            let Some(narrow) = narrow.next() else {
+                // `other` accepts any continuation, this pattern does not
+                return false;
+            };
+            if !narrow.within(&broad) {
+                return false;
+            }
+            if broad.open {
+                return true;
+            }
+            if narrow.open {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Whether every GTS ID matching this pattern also matches `other`.
+    #[must_use]
+    pub fn is_subset_of(&self, other: &GtsPattern) -> bool {
+        other.subsumes(self)
+    }
+
+    /// The pattern matching exactly the IDs both patterns match, if any.
+    #[must_use]
+    pub fn intersection(&self, other: &GtsPattern) -> Option<GtsPattern> {
+        if self.subsumes(other) {
+            return Some(other.clone());
+        }
+        if other.subsumes(self) {
+            return Some(self.clone());
+        }
+        if !self.intersects(other) {
+            return None;
+        }
+        let (a_segs, b_segs) = (
+            &self.wildcard.gts_id_segments,
+            &other.wildcard.gts_id_segments,
+        );
+        // Past the shorter pattern, or a wildcard of one pattern, only the
+        // other constrains the ID
+        let mut rest = if a_segs.len() > b_segs.len() {
+            &a_segs[b_segs.len()..]
+        } else {
+            &b_segs[a_segs.len()..]
+        };
+        let mut segments = Vec::new();
+        for (i, (a, b)) in a_segs.iter().zip(b_segs).enumerate() {
+            let (a, b) = (Constraint::of(a), Constraint::of(b));
+            segments.push(a.merge(&b).render());
+            if a.open || b.open {
+                rest = match (a.open, b.open) {
+                    (true, true) => &[],
+                    (true, false) => &b_segs[i + 1..],
+                    _ => &a_segs[i + 1..],
+                };
+                break;
+            }
+        }
+        segments.extend(rest.iter().map(|s| s.segment.clone()));
+        Self::parse(&format!("{GTS_PREFIX}{}", segments.concat())).ok()
+    }
+}
+
+impl From<GtsWildcard> for GtsPattern {
+    fn from(wildcard: GtsWildcard) -> Self {
+        Self { wildcard }
+    }
+}
+
+impl PartialEq for GtsPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.wildcard.id == other.wildcard.id
+    }
+}
+
+impl Eq for GtsPattern {}
+
+impl std::hash::Hash for GtsPattern {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.wildcard.id.hash(state);
+    }
+}
+
+impl fmt::Display for GtsPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.wildcard.id)
+    }
+}
+
+impl FromStr for GtsPattern {
+    type Err = GtsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl AsRef<str> for GtsPattern {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// What one pattern segment requires of an ID segment; `None` fields are
+/// unconstrained.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Constraint<'a> {
+    vendor: Option<&'a str>,
+    package: Option<&'a str>,
+    namespace: Option<&'a str>,
+    type_name: Option<&'a str>,
+    major: Option<u32>,
+    minor: Option<u32>,
+    is_type: Option<bool>,
+    uuid: Option<&'a str>,
+    /// A wildcard segment: any ID segments may follow
+    open: bool,
+}
+
+impl<'a> Constraint<'a> {
+    /// Mirrors the segment comparison of [`GtsID::wildcard_match`].
+    fn of(seg: &'a GtsIdSegment) -> Self {
+        if seg.is_wildcard {
+            let name = |s: &'a str| Some(s).filter(|s| !s.is_empty());
+            return Self {
+                vendor: name(&seg.vendor),
+                package: name(&seg.package),
+                namespace: name(&seg.namespace),
+                type_name: name(&seg.type_name),
+                major: Some(seg.ver_major).filter(|&major| major != 0),
+                minor: seg.ver_minor,
+                is_type: seg.is_type.then_some(true),
+                uuid: None,
+                open: true,
+            };
+        }
+        Self {
+            vendor: Some(&seg.vendor),
+            package: Some(&seg.package),
+            namespace: Some(&seg.namespace),
+            type_name: Some(&seg.type_name),
+            major: Some(seg.ver_major),
+            minor: seg.ver_minor,
+            is_type: Some(seg.is_type),
+            uuid: seg.is_uuid_tail.then_some(seg.segment.as_str()),
+            open: false,
+        }
+    }
+
+    fn compatible(&self, other: &Self) -> bool {
+        fn agree<T: PartialEq + Copy>(a: Option<T>, b: Option<T>) -> bool {
+            a.zip(b).is_none_or(|(a, b)| a == b)
+        }
+        agree(self.vendor, other.vendor)
+            && agree(self.package, other.package)
+            && agree(self.namespace, other.namespace)
+            && agree(self.type_name, other.type_name)
+            && agree(self.major, other.major)
+            && agree(self.minor, other.minor)
+            && agree(self.is_type, other.is_type)
+            && agree(self.uuid, other.uuid)
+    }
+
+    /// Whether every ID segment satisfying `self` satisfies `broad`.
+    fn within(&self, broad: &Self) -> bool {
+        fn implies<T: PartialEq + Copy>(narrow: Option<T>, broad: Option<T>) -> bool {
+            broad.is_none() || narrow == broad
+        }
+        implies(self.vendor, broad.vendor)
+            && implies(self.package, broad.package)
+            && implies(self.namespace, broad.namespace)
+            && implies(self.type_name, broad.type_name)
+            && implies(self.major, broad.major)
+            && implies(self.minor, broad.minor)
+            && implies(self.is_type, broad.is_type)
+            && implies(self.uuid, broad.uuid)
+    }
+
+    /// The constraints of both segments, assuming they are compatible.
+    fn merge(&self, other: &Self) -> Self {
+        Self {
+            vendor: self.vendor.or(other.vendor),
+            package: self.package.or(other.package),
+            namespace: self.namespace.or(other.namespace),
+            type_name: self.type_name.or(other.type_name),
+            major: self.major.or(other.major),
+            minor: self.minor.or(other.minor),
+            is_type: self.is_type.or(other.is_type),
+            uuid: self.uuid.or(other.uuid),
+            open: self.open && other.open,
+        }
+    }
+
+    /// The pattern segment text, including its trailing `~` or `*`.
+    fn render(&self) -> String {
+        if let Some(uuid) = self.uuid {
+            return uuid.to_owned();
+        }
+        let mut parts: Vec<String> = [self.vendor, self.package, self.namespace, self.type_name]
+            .into_iter()
+            .map_while(|part| part.map(str::to_owned))
+            .collect();
+        if parts.len() == 4
+            && let Some(major) = self.major
+        {
+            parts.push(format!("v{major}"));
+            if let Some(minor) = self.minor {
+                parts.push(minor.to_string());
+            }
+        }
+        let mut segment = parts.join(".");
+        if self.open {
+            segment.push_str(if segment.is_empty() { "*" } else { ".*" });
+        } else if self.is_type == Some(true) {
+            segment.push('~');
+        }
+        segment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(s: &str) -> GtsPattern {
+        GtsPattern::parse(s).expect("test pattern")
+    }
+
+    #[test]
+    fn test_matches_follows_wildcard_match() {
+        let p = pattern("gts.x.core.events.topic.v1~*");
+        assert!(p.matches_str("gts.x.core.events.topic.v1~x.app._.orders.v1"));
+        assert!(p.matches_str("gts.x.core.events.topic.v1.2~x.app._.orders.v1"));
+        assert!(!p.matches_str("gts.x.core.events.type.v1~x.app._.orders.v1"));
+        assert!(!p.matches_str("not-an-id"));
+        let exact = pattern("gts.x.core.events.topic.v1~");
+        assert!(!exact.is_wildcard());
+        assert!(exact.matches_str("gts.x.core.events.topic.v1.3~"));
+        assert!(GtsPattern::parse("gts.*.core.events.topic.v1~").is_err());
+    }
+
+    #[test]
+    fn test_subsumption() {
+        let all = pattern("gts.*");
+        let vendor = pattern("gts.x.*");
+        let topics = pattern("gts.x.core.events.topic.v1~*");
+        let minor = pattern("gts.x.core.events.topic.v1.2~*");
+        let exact = pattern("gts.x.core.events.topic.v1~");
+        let acme = pattern("gts.x.core.events.topic.v1~acme.*");
+
+        assert!(all.subsumes(&vendor));
+        assert!(vendor.subsumes(&topics));
+        assert!(topics.subsumes(&minor));
+        assert!(!minor.subsumes(&topics));
+        assert!(topics.subsumes(&acme));
+        assert!(acme.is_subset_of(&topics));
+        // The exact type pattern also matches every ID derived from it
+        assert!(exact.subsumes(&topics));
+        // ... but `v1~*` needs a second segment, which the type ID itself lacks
+        assert!(!topics.subsumes(&exact));
+        assert!(!acme.subsumes(&exact));
+        assert!(topics.subsumes(&topics));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let vendor = pattern("gts.x.*");
+        let topics = pattern("gts.x.core.events.topic.v1~*");
+        let audit = pattern("gts.x.core.audit.*");
+        assert_eq!(vendor.intersection(&topics), Some(topics.clone()));
+        assert_eq!(topics.intersection(&audit), None);
+        assert!(!topics.intersects(&audit));
+
+        // Neither subsumes the other: the minor comes from one, the rest of
+        // the chain from the other
+        let minor = pattern("gts.x.core.events.topic.v1.2~");
+        let acme = pattern("gts.x.core.events.topic.v1~acme.*");
+        assert!(minor.intersects(&acme));
+        assert_eq!(
+            minor.intersection(&acme),
+            Some(pattern("gts.x.core.events.topic.v1.2~acme.*"))
+        );
+        assert_eq!(
+            acme.intersection(&minor),
+            Some(pattern("gts.x.core.events.topic.v1.2~acme.*"))
+        );
+
+        let instance = "gts.x.core.events.topic.v1.2~acme.app._.orders.v1";
+        let both = minor.intersection(&acme).expect("overlap");
+        assert!(both.matches_str(instance));
+        assert!(minor.matches_str(instance) && acme.matches_str(instance));
+    }
+}
@@ -0,0 +1,161 @@
+//! Custom migrations between schema versions.
+//!
+//! Casting only does what the schemas describe: it fills in defaults, updates
+//! `const` GTS IDs and drops properties the target forbids. A migration
+//! registered for a `(from, to)` pair of schema IDs runs on the instance
+//! before those automatic steps whenever an instance is cast from `from` to
+//! `to`, including each hop of [`GtsStore::cast_chain`], so renames and value
+//! transformations need no hand-written glue:
+//!
+//! ```
+//! use gts::GtsOps;
+//! use serde_json::{Value, json};
+//!
+//! let mut ops = GtsOps::new(None, None, 0);
+//! for (id, properties) in [
+//!     ("gts.x.app.entities.user.v1.0~", json!({"name": {"type": "string"}})),
+//!     (
+//!         "gts.x.app.entities.user.v1.1~",
+//!         json!({"first": {"type": "string"}, "last": {"type": "string"}}),
+//!     ),
+//! ] {
+//!     let schema = json!({
+//!         "$id": format!("gts://{id}"),
+//!         "$schema": "http://json-schema.org/draft-07/schema#",
+//!         "type": "object",
+//!         "properties": properties
+//!     });
+//!     assert!(ops.add_entity(&schema, false).ok);
+//! }
+//! ops.register_migration(
+//!     "gts.x.app.entities.user.v1.0~",
+//!     "gts.x.app.entities.user.v1.1~",
+//!     |user| {
+//!         let Some(Value::String(name)) = user.remove("name") else {
+//!             return Err("expected a name".to_owned());
+//!         };
+//!         let (first, last) = name.split_once(' ').unwrap_or((&name, ""));
+//!         user.insert("first".to_owned(), json!(first));
+//!         user.insert("last".to_owned(), json!(last));
+//!         Ok(())
+//!     },
+//! );
+//!
+//! ops.add_entity(
+//!     &json!({
+//!         "id": "gts.x.app.entities.user.v1.0~x.app._.ada.v1",
+//!         "name": "Ada Lovelace"
+//!     }),
+//!     false,
+//! );
+//! let cast = ops.cast(
+//!     "gts.x.app.entities.user.v1.0~x.app._.ada.v1",
+//!     "gts.x.app.entities.user.v1.1~",
+//! );
+//! let user = cast.casted_entity.unwrap();
+//! assert_eq!(user["first"], "Ada");
+//! assert_eq!(user["last"], "Lovelace");
+//! assert_eq!(cast.removed_properties, ["name"]);
+//! ```
+//!
+//! [`GtsStore::cast_chain`]: crate::store::GtsStore::cast_chain
+
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A migration: rewrites an instance in place, or explains why it cannot.
+pub type GtsMigrationFn = dyn Fn(&mut Map<String, Value>) -> Result<(), String> + Send + Sync;
+
+/// Migrations by `(from, to)` schema ID pair.
+#[derive(Clone, Default)]
+pub struct GtsMigrations {
+    migrations: BTreeMap<(String, String), Arc<GtsMigrationFn>>,
+}
+
+impl fmt::Debug for GtsMigrations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.migrations.keys()).finish()
+    }
+}
+
+impl GtsMigrations {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `migration` when casting from schema `from` to schema `to`,
+    /// replacing the previous migration of the pair.
+    pub fn register<F>(&mut self, from: impl Into<String>, to: impl Into<String>, migration: F)
+    where
+        F: Fn(&mut Map<String, Value>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.migrations
+            .insert((from.into(), to.into()), Arc::new(migration));
+    }
+
+    /// Remove the migration of a pair; returns whether it had one.
+    pub fn unregister(&mut self, from: &str, to: &str) -> bool {
+        self.migrations
+            .remove(&(from.to_owned(), to.to_owned()))
+            .is_some()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.migrations.is_empty()
+    }
+
+    /// The migration from `from` to `to`, if one is registered.
+    #[must_use]
+    pub fn get(&self, from: &str, to: &str) -> Option<&GtsMigrationFn> {
+        self.migrations
+            .get(&(from.to_owned(), to.to_owned()))
+            .map(AsRef::as_ref)
+    }
+
+    /// The `(from, to)` pairs with a migration, sorted.
+    pub fn pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.migrations
+            .keys()
+            .map(|(from, to)| (from.as_str(), to.as_str()))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_register_and_lookup() {
+        let mut migrations = GtsMigrations::new();
+        assert!(migrations.is_empty());
+        migrations.register("gts.x.a.b.c.v1~", "gts.x.a.b.c.v2~", |instance| {
+            instance.insert("migrated".to_owned(), json!(true));
+            Ok(())
+        });
+
+        assert!(
+            migrations
+                .get("gts.x.a.b.c.v2~", "gts.x.a.b.c.v1~")
+                .is_none()
+        );
+        let migration = migrations
+            .get("gts.x.a.b.c.v1~", "gts.x.a.b.c.v2~")
+            .unwrap();
+        let mut instance = Map::new();
+        migration(&mut instance).unwrap();
+        assert_eq!(instance["migrated"], true);
+
+        assert_eq!(
+            migrations.pairs().collect::<Vec<_>>(),
+            [("gts.x.a.b.c.v1~", "gts.x.a.b.c.v2~")]
+        );
+        assert!(migrations.unregister("gts.x.a.b.c.v1~", "gts.x.a.b.c.v2~"));
+        assert!(!migrations.unregister("gts.x.a.b.c.v1~", "gts.x.a.b.c.v2~"));
+    }
+}
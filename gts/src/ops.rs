@@ -4,12 +4,22 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::backend::GtsBackend;
 use crate::entities::{GtsConfig, GtsEntity};
+use crate::example_gen::InvalidExample;
+use crate::file_name::GtsFileNameEncoding;
+#[cfg(feature = "async")]
 use crate::files_reader::GtsFileReader;
 use crate::gts::{GtsID, GtsWildcard};
+use crate::instance_diff::{PropertyChange, diff_instances};
 use crate::path_resolver::JsonPathResolver;
+use crate::redaction::GtsRedaction;
 use crate::schema_cast::GtsEntityCastResult;
-use crate::store::{GtsStore, GtsStoreQueryResult};
+use crate::schema_flatten::ChainConflict;
+use crate::schema_metadata::{GtsMetadataIssue, GtsSchemaMetadata};
+use crate::store::{
+    GtsFileNameMismatch, GtsStore, GtsStoreHealth, GtsStoreQueryResult, StoreError,
+};
 
 /// `is_schema` is `Some(true)` for schema/type IDs (ending with `~`),
 /// `Some(false)` for instance IDs, and `None` when the input couldn't be
@@ -89,6 +99,17 @@ pub struct GtsValidationResult {
     pub error: String,
 }
 
+/// OP#13 effective traits of a schema.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtsEffectiveTraitsResult {
+    pub id: String,
+    pub ok: bool,
+    /// `x-gts-traits` merged along the chain, with trait schema defaults applied
+    pub traits: serde_json::Map<String, Value>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GtsEntityValidationResult {
     pub id: String,
@@ -110,6 +131,9 @@ pub struct GtsEntityInfo {
     pub id: String,
     pub schema_id: Option<String>,
     pub is_schema: bool,
+    /// `x-gts-owner` of a schema
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -121,6 +145,9 @@ pub struct GtsGetEntityResult {
     pub is_schema: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<Value>,
+    /// `x-gts-owner` and `x-gts-changelog` of a schema declaring them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<GtsSchemaMetadata>,
     #[serde(skip_serializing_if = "String::is_empty")]
     pub error: String,
 }
@@ -167,20 +194,100 @@ pub struct GtsExtractIdResult {
     pub is_schema: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtsExpandPatternResult {
+    pub pattern: String,
+    pub ids: Vec<String>,
+    pub count: usize,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtsLintStoreResult {
+    pub ok: bool,
+    pub file_name_mismatches: Vec<GtsFileNameMismatch>,
+    /// Schemas under the owner-required namespaces without `x-gts-owner`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_owners: Vec<String>,
+    /// Schemas with a malformed `x-gts-owner` or `x-gts-changelog`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub invalid_metadata: Vec<GtsMetadataIssue>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtsInstanceDiffResult {
+    pub ok: bool,
+    pub id_a: String,
+    pub id_b: String,
+    pub schema_id: Option<String>,
+    pub changes: Vec<PropertyChange>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtsFlattenChainResult {
+    /// `false` if the chain could not be merged or the merge found conflicts
+    pub ok: bool,
+    pub schema_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
+    pub conflicts: Vec<ChainConflict>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtsGenerateExamplesResult {
+    pub ok: bool,
+    pub schema_id: String,
+    pub seed: u64,
+    pub examples: Vec<Value>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtsGenerateInvalidExamplesResult {
+    pub ok: bool,
+    pub schema_id: String,
+    pub seed: u64,
+    pub examples: Vec<InvalidExample>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+/// Schemas are recognised by their `$schema` field, as in [`GtsEntity::new`].
+fn is_schema_content(content: &Value) -> bool {
+    content.get("$schema").is_some()
+}
+
+fn entity_info(entity_id: &str, entity: &GtsEntity) -> GtsEntityInfo {
+    GtsEntityInfo {
+        id: entity_id.to_owned(),
+        schema_id: entity.schema_id.clone(),
+        is_schema: entity.is_schema,
+        owner: entity.schema_metadata().ok().and_then(|m| m.owner),
+    }
+}
+
 pub struct GtsOps {
     pub verbose: usize,
     pub cfg: GtsConfig,
     pub path: Option<Vec<String>>,
     pub store: GtsStore,
+    /// Masks applied to instance values in every result rendering them
+    pub redaction: GtsRedaction,
 }
 
 impl GtsOps {
     #[must_use]
     pub fn new(path: Option<Vec<String>>, config: Option<String>, verbose: usize) -> Self {
         let cfg = Self::load_config(config);
-        let reader: Option<Box<dyn crate::store::GtsReader>> = path.as_ref().map(|p| {
-            Box::new(GtsFileReader::new(p, Some(cfg.clone()))) as Box<dyn crate::store::GtsReader>
-        });
+        let reader = path.as_ref().map(|p| GtsBackend::parse(p).reader(&cfg));
         let store = GtsStore::new(reader);
 
         GtsOps {
@@ -188,6 +295,37 @@ impl GtsOps {
             cfg,
             path,
             store,
+            redaction: GtsRedaction::default(),
+        }
+    }
+
+    /// Like [`GtsOps::new`], loading the store with
+    /// [`GtsStore::load_async`] (at most `concurrency` files at a time).
+    #[cfg(feature = "async")]
+    pub async fn new_async(
+        path: Option<Vec<String>>,
+        config: Option<String>,
+        verbose: usize,
+        concurrency: usize,
+    ) -> Self {
+        let cfg = Self::load_config(config);
+        // Only the file system is read concurrently
+        let store = match path.as_deref().map(GtsBackend::parse) {
+            Some(GtsBackend::Files(p)) => {
+                let reader = GtsFileReader::new(&p, Some(cfg.clone()));
+                let progress = std::sync::Arc::new(crate::store::GtsLoadProgress::default());
+                GtsStore::load_async(reader, concurrency, progress).await
+            }
+            Some(backend) => GtsStore::new(Some(backend.reader(&cfg))),
+            None => GtsStore::new(None),
+        };
+
+        GtsOps {
+            verbose,
+            cfg,
+            path,
+            store,
+            redaction: GtsRedaction::default(),
         }
     }
 
@@ -223,7 +361,7 @@ impl GtsOps {
             .and_then(|v| v.as_array())
             .map(|arr| {
                 arr.iter()
-                    .filter_map(|v| v.as_str().map(ToString::to_string))
+                    .filter_map(|v| v.as_str().map(str::to_owned))
                     .collect()
             })
             .unwrap_or(default_cfg.entity_id_fields);
@@ -233,7 +371,7 @@ impl GtsOps {
             .and_then(|v| v.as_array())
             .map(|arr| {
                 arr.iter()
-                    .filter_map(|v| v.as_str().map(ToString::to_string))
+                    .filter_map(|v| v.as_str().map(str::to_owned))
                     .collect()
             })
             .unwrap_or(default_cfg.schema_id_fields);
@@ -246,9 +384,7 @@ impl GtsOps {
 
     pub fn reload_from_path(&mut self, path: &[String]) {
         self.path = Some(path.to_vec());
-        let reader = Box::new(GtsFileReader::new(path, Some(self.cfg.clone())))
-            as Box<dyn crate::store::GtsReader>;
-        self.store = GtsStore::new(Some(reader));
+        self.store = GtsStore::new(Some(GtsBackend::parse(path).reader(&self.cfg)));
     }
 
     fn get_details(&mut self, entity: &GtsEntity) -> String {
@@ -272,6 +408,14 @@ impl GtsOps {
     }
 
     pub fn add_entity(&mut self, content: &Value, validate: bool) -> GtsAddEntityResult {
+        let mut result = self.add_entity_unredacted(content, validate);
+        if !result.error.is_empty() && !is_schema_content(content) {
+            result.error = self.redaction.redact_message(content, &result.error);
+        }
+        result
+    }
+
+    fn add_entity_unredacted(&mut self, content: &Value, validate: bool) -> GtsAddEntityResult {
         let entity = GtsEntity::new(
             None,
             None,
@@ -364,6 +508,71 @@ impl GtsOps {
         }
     }
 
+    /// Validate `content`, a schema or an instance, against its declared
+    /// schema chain as [`validate_schema`](Self::validate_schema) and
+    /// [`validate_instance`](Self::validate_instance) do, without registering it.
+    pub fn validate_content(&mut self, content: &Value) -> GtsEntityValidationResult {
+        let mut result = self.validate_content_unredacted(content);
+        if !result.error.is_empty() && !is_schema_content(content) {
+            result.error = self.redaction.redact_message(content, &result.error);
+        }
+        result
+    }
+
+    fn validate_content_unredacted(&mut self, content: &Value) -> GtsEntityValidationResult {
+        let entity = GtsEntity::new(
+            None,
+            None,
+            content,
+            Some(&self.cfg),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        );
+        let entity_type = if entity.is_schema {
+            "schema"
+        } else {
+            "instance"
+        }
+        .to_owned();
+        let Some(entity_id) = entity.effective_id() else {
+            return GtsEntityValidationResult {
+                id: String::new(),
+                ok: false,
+                entity_type,
+                error: format!(
+                    "Unable to detect ID in entity:\n{}",
+                    self.get_details(&entity)
+                ),
+            };
+        };
+        if let Err(e) = self.store.retired_ids().check(&entity_id) {
+            return GtsEntityValidationResult {
+                id: entity_id,
+                ok: false,
+                entity_type,
+                error: e.to_string(),
+            };
+        }
+
+        let is_schema = entity.is_schema;
+        let previous = self.store.swap_entity(&entity_id, Some(entity));
+        let result = if is_schema {
+            self.validate_schema(&entity_id)
+        } else {
+            self.validate_instance(&entity_id)
+        };
+        self.store.swap_entity(&entity_id, previous);
+        GtsEntityValidationResult {
+            id: result.id,
+            ok: result.ok,
+            entity_type,
+            error: result.error,
+        }
+    }
+
     pub fn add_entities(&mut self, items: &[Value]) -> GtsAddEntitiesResult {
         let results: Vec<GtsAddEntityResult> =
             items.iter().map(|it| self.add_entity(it, false)).collect();
@@ -568,6 +777,28 @@ impl GtsOps {
         }
     }
 
+    /// Validate the custom schema keyword `keyword` with `handler`, see
+    /// [`GtsStore::register_keyword`].
+    pub fn register_keyword<F>(&mut self, keyword: impl Into<String>, handler: F)
+    where
+        F: Fn(&crate::GtsKeywordContext<'_>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.store.register_keyword(keyword, handler);
+    }
+
+    /// Run `migration` when casting from schema `from` to schema `to`, see
+    /// [`GtsStore::register_migration`].
+    pub fn register_migration<F>(
+        &mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        migration: F,
+    ) where
+        F: Fn(&mut serde_json::Map<String, Value>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.store.register_migration(from, to, migration);
+    }
+
     pub fn validate_instance(&mut self, gts_id: &str) -> GtsValidationResult {
         match self.store.validate_instance(gts_id) {
             Ok(()) => GtsValidationResult {
@@ -578,11 +809,21 @@ impl GtsOps {
             Err(e) => GtsValidationResult {
                 id: gts_id.to_owned(),
                 ok: false,
-                error: e.to_string(),
+                error: self.redact_message(gts_id, &e.to_string()),
             },
         }
     }
 
+    /// `message` about the instance `gts_id` with its redacted values masked
+    fn redact_message(&mut self, gts_id: &str, message: &str) -> String {
+        match self.store.get(gts_id) {
+            Some(entity) if !entity.is_schema => {
+                self.redaction.redact_message(&entity.content, message)
+            }
+            _ => message.to_owned(),
+        }
+    }
+
     pub fn validate_schema(&mut self, gts_id: &str) -> GtsValidationResult {
         // First run basic schema validation (meta-schema, refs, etc.)
         if let Err(e) = self.store.validate_schema(gts_id) {
@@ -617,6 +858,23 @@ impl GtsOps {
         }
     }
 
+    pub fn effective_traits(&mut self, schema_id: &str) -> GtsEffectiveTraitsResult {
+        match self.store.resolve_traits(schema_id) {
+            Ok(traits) => GtsEffectiveTraitsResult {
+                id: schema_id.to_owned(),
+                ok: true,
+                traits,
+                error: String::new(),
+            },
+            Err(e) => GtsEffectiveTraitsResult {
+                id: schema_id.to_owned(),
+                ok: false,
+                traits: serde_json::Map::new(),
+                error: e.to_string(),
+            },
+        }
+    }
+
     pub fn validate_entity(&mut self, gts_id: &str) -> GtsEntityValidationResult {
         if gts_id.ends_with('~') {
             let result = self.validate_schema(gts_id);
@@ -674,7 +932,14 @@ impl GtsOps {
 
     pub fn cast(&mut self, from_id: &str, to_schema_id: &str) -> GtsEntityCastResult {
         match self.store.cast(from_id, to_schema_id) {
-            Ok(result) => result,
+            Ok(mut result) => {
+                if let Some(casted) = &mut result.casted_entity
+                    && !is_schema_content(casted)
+                {
+                    self.redaction.redact(casted);
+                }
+                result
+            }
             Err(e) => GtsEntityCastResult {
                 from_id: from_id.to_owned(),
                 to_id: to_schema_id.to_owned(),
@@ -698,14 +963,52 @@ impl GtsOps {
 
     #[must_use]
     pub fn query(&self, expr: &str, limit: usize) -> GtsStoreQueryResult {
-        self.store.query(expr, limit)
+        self.redact_query(self.store.query(expr, limit))
+    }
+
+    /// Query and explain why each result matched.
+    #[must_use]
+    pub fn query_explained(&self, expr: &str, limit: usize) -> GtsStoreQueryResult {
+        self.redact_query(self.store.query_explained(expr, limit))
+    }
+
+    fn redact_query(&self, mut result: GtsStoreQueryResult) -> GtsStoreQueryResult {
+        if self.redaction.is_empty() {
+            return result;
+        }
+        for content in &mut result.results {
+            if !is_schema_content(content) {
+                self.redaction.redact(content);
+            }
+        }
+        for explanation in &mut result.explanations {
+            if explanation.gts_id.ends_with('~') {
+                continue;
+            }
+            for filter in &mut explanation.filters {
+                self.redaction.redact_at(&filter.key, &mut filter.actual);
+                // Echoing the expected value would confirm the secret
+                let mut expected = Value::String(std::mem::take(&mut filter.expected));
+                self.redaction.redact_at(&filter.key, &mut expected);
+                if let Value::String(expected) = expected {
+                    filter.expected = expected;
+                }
+            }
+        }
+        result
     }
 
     pub fn attr(&mut self, gts_with_path: &str) -> JsonPathResolver {
         match GtsID::split_at_path(gts_with_path) {
             Ok((gts, Some(path))) => {
                 if let Some(entity) = self.store.get(&gts) {
-                    entity.resolve_path(&path)
+                    if entity.is_schema {
+                        entity.resolve_path(&path)
+                    } else {
+                        let gts_id = entity.gts_id.as_ref().map(|g| g.id.clone());
+                        let content = self.redaction.redacted(&entity.content);
+                        JsonPathResolver::new(gts_id.unwrap_or_default(), content).resolve(&path)
+                    }
                 } else {
                     JsonPathResolver::new(gts.clone(), Value::Null)
                         .failure(&path, &format!("Entity not found: {gts}"))
@@ -750,7 +1053,12 @@ impl GtsOps {
                     .map_or_else(|| gts_id.to_owned(), |g| g.id.clone()),
                 schema_id: entity.schema_id.clone(),
                 is_schema: entity.is_schema,
-                content: Some(entity.content.clone()),
+                content: Some(if entity.is_schema {
+                    entity.content.clone()
+                } else {
+                    self.redaction.redacted(&entity.content)
+                }),
+                metadata: entity.schema_metadata().ok().filter(|m| !m.is_empty()),
                 error: String::new(),
             },
             None => GtsGetEntityResult {
@@ -759,6 +1067,7 @@ impl GtsOps {
                 schema_id: None,
                 is_schema: false,
                 content: None,
+                metadata: None,
                 error: format!("Entity '{gts_id}' not found"),
             },
         }
@@ -772,11 +1081,7 @@ impl GtsOps {
         let entities: Vec<GtsEntityInfo> = all_entities
             .into_iter()
             .take(limit)
-            .map(|(entity_id, entity)| GtsEntityInfo {
-                id: entity_id.clone(),
-                schema_id: entity.schema_id.clone(),
-                is_schema: entity.is_schema,
-            })
+            .map(|(entity_id, entity)| entity_info(entity_id, entity))
             .collect();
 
         let count = entities.len();
@@ -792,6 +1097,192 @@ impl GtsOps {
     pub fn list(&self, limit: usize) -> GtsEntitiesListResult {
         self.get_entities(limit)
     }
+
+    /// The schemas (with `schemas`) or instances whose GTS ID matches
+    /// `pattern` as in [`expand_pattern`](Self::expand_pattern), sorted by
+    /// ID; `total` counts them all, `entities` holds the first `limit`.
+    ///
+    /// # Errors
+    /// Returns `StoreError::InvalidPattern` if the pattern cannot be parsed.
+    pub fn find_entities(
+        &self,
+        pattern: &str,
+        schemas: bool,
+        limit: usize,
+    ) -> Result<GtsEntitiesListResult, StoreError> {
+        let ids = self.store.expand_pattern(pattern)?;
+        let by_id: HashMap<&str, &GtsEntity> = self
+            .store
+            .items()
+            .map(|(id, entity)| (id.as_str(), entity))
+            .collect();
+        let matching: Vec<GtsEntityInfo> = ids
+            .iter()
+            .filter_map(|id| Some((id.id.as_str(), *by_id.get(id.id.as_str())?)))
+            .filter(|(_, entity)| entity.is_schema == schemas)
+            .map(|(id, entity)| entity_info(id, entity))
+            .collect();
+        let total = matching.len();
+        let entities: Vec<GtsEntityInfo> = matching.into_iter().take(limit).collect();
+        let count = entities.len();
+        Ok(GtsEntitiesListResult {
+            entities,
+            count,
+            total,
+        })
+    }
+
+    /// Expand a GTS pattern into all matching IDs in the store.
+    /// With `require_match`, a pattern matching nothing is reported as an error.
+    #[must_use]
+    pub fn expand_pattern(&self, pattern: &str, require_match: bool) -> GtsExpandPatternResult {
+        let (ids, error) = match self.store.expand_pattern(pattern) {
+            Ok(ids) if ids.is_empty() && require_match => (
+                Vec::new(),
+                StoreError::NoPatternMatches(pattern.to_owned()).to_string(),
+            ),
+            Ok(ids) => (ids.into_iter().map(|g| g.id).collect(), String::new()),
+            Err(e) => (Vec::new(), e.to_string()),
+        };
+        let count = ids.len();
+        GtsExpandPatternResult {
+            pattern: pattern.to_owned(),
+            ids,
+            count,
+            error,
+        }
+    }
+
+    /// Check store consistency: file names must match the embedded `$id`/`id`.
+    #[must_use]
+    pub fn lint_store(&self) -> GtsLintStoreResult {
+        self.lint_store_requiring_owners(&[])
+    }
+
+    /// [`lint_store`](Self::lint_store), also requiring an `x-gts-owner` on
+    /// every schema matching one of the `owner_namespaces` wildcard patterns.
+    #[must_use]
+    pub fn lint_store_requiring_owners(&self, owner_namespaces: &[String]) -> GtsLintStoreResult {
+        self.lint_store_with(owner_namespaces, GtsFileNameEncoding::Raw)
+    }
+
+    /// [`lint_store_requiring_owners`](Self::lint_store_requiring_owners),
+    /// expecting file names written with `encoding`.
+    #[must_use]
+    pub fn lint_store_with(
+        &self,
+        owner_namespaces: &[String],
+        encoding: GtsFileNameEncoding,
+    ) -> GtsLintStoreResult {
+        let file_name_mismatches = self.store.check_file_names_with(encoding);
+        let (missing_owners, invalid_metadata, error) =
+            match self.store.check_owners(owner_namespaces) {
+                Ok((missing, invalid)) => (missing, invalid, String::new()),
+                Err(e) => (Vec::new(), Vec::new(), e.to_string()),
+            };
+        GtsLintStoreResult {
+            ok: file_name_mismatches.is_empty()
+                && missing_owners.is_empty()
+                && invalid_metadata.is_empty()
+                && error.is_empty(),
+            file_name_mismatches,
+            missing_owners,
+            invalid_metadata,
+            error,
+        }
+    }
+
+    /// Diff two instances of the same type, property by property.
+    /// Identity fields and property order are ignored.
+    pub fn diff_instances(&mut self, id_a: &str, id_b: &str) -> GtsInstanceDiffResult {
+        let (ok, schema_id, changes, error) = match diff_instances(&mut self.store, id_a, id_b) {
+            Ok((schema_id, mut changes)) => {
+                for change in &mut changes {
+                    for value in [&mut change.old, &mut change.new].into_iter().flatten() {
+                        self.redaction.redact_at(&change.path, value);
+                    }
+                }
+                (true, schema_id, changes, String::new())
+            }
+            Err(e) => (false, None, Vec::new(), e.to_string()),
+        };
+        GtsInstanceDiffResult {
+            ok,
+            id_a: id_a.to_owned(),
+            id_b: id_b.to_owned(),
+            schema_id,
+            changes,
+            error,
+        }
+    }
+
+    /// Merge the `~` chain of a schema into one schema, reporting conflicts.
+    pub fn flatten_chain(&mut self, schema_id: &str) -> GtsFlattenChainResult {
+        match self.store.flatten_chain(schema_id) {
+            Ok(flat) => GtsFlattenChainResult {
+                ok: flat.conflicts.is_empty(),
+                schema_id: schema_id.to_owned(),
+                schema: Some(flat.schema),
+                conflicts: flat.conflicts,
+                error: String::new(),
+            },
+            Err(e) => GtsFlattenChainResult {
+                ok: false,
+                schema_id: schema_id.to_owned(),
+                schema: None,
+                conflicts: Vec::new(),
+                error: e.to_string(),
+            },
+        }
+    }
+
+    /// Generate `count` random valid instances of a schema, reproducible by `seed`.
+    pub fn generate_examples(
+        &mut self,
+        schema_id: &str,
+        count: usize,
+        seed: u64,
+    ) -> GtsGenerateExamplesResult {
+        let (ok, examples, error) = match self.store.generate_examples(schema_id, count, seed) {
+            Ok(examples) => (true, examples, String::new()),
+            Err(e) => (false, Vec::new(), e.to_string()),
+        };
+        GtsGenerateExamplesResult {
+            ok,
+            schema_id: schema_id.to_owned(),
+            seed,
+            examples,
+            error,
+        }
+    }
+
+    /// Generate `count` instances of a schema that each violate one constraint,
+    /// annotated with the expected violation.
+    pub fn generate_invalid_examples(
+        &mut self,
+        schema_id: &str,
+        count: usize,
+        seed: u64,
+    ) -> GtsGenerateInvalidExamplesResult {
+        let (ok, examples, error) =
+            match self.store.generate_invalid_examples(schema_id, count, seed) {
+                Ok(examples) => (true, examples, String::new()),
+                Err(e) => (false, Vec::new(), e.to_string()),
+            };
+        GtsGenerateInvalidExamplesResult {
+            ok,
+            schema_id: schema_id.to_owned(),
+            seed,
+            examples,
+            error,
+        }
+    }
+
+    /// Snapshot of the registry state (counts, last load, load errors, memory).
+    #[must_use]
+    pub fn health(&self) -> GtsStoreHealth {
+        self.store.health()
+    }
 }
 
 #[cfg(test)]
@@ -1354,6 +1845,7 @@ mod tests {
             id: "gts.vendor.package.namespace.type.v1.0".to_owned(),
             schema_id: Some("gts.vendor.package.namespace.type.v1.0~".to_owned()),
             is_schema: false,
+            owner: None,
         };
 
         let json = to_json_obj(&info);
@@ -1380,11 +1872,13 @@ mod tests {
                 id: "gts.test.id1.v1.0".to_owned(),
                 schema_id: None,
                 is_schema: false,
+                owner: None,
             },
             GtsEntityInfo {
                 id: "gts.test.id2.v1.0".to_owned(),
                 schema_id: None,
                 is_schema: false,
+                owner: None,
             },
         ];
 
@@ -3437,4 +3931,374 @@ mod tests {
         assert_eq!(result.id, "gts.test.get.entity.success.v1~");
         assert!(result.is_schema);
     }
+
+    #[test]
+    fn test_lint_store_reports_file_name_mismatch() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let schema = json!({
+            "$id": "gts://gts.x.lint.store.item.v2~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object"
+        });
+        fs::write(
+            dir.path().join("gts.x.lint.store.item.v1~.schema.json"),
+            schema.to_string(),
+        )
+        .unwrap();
+
+        let path = vec![dir.path().to_string_lossy().into_owned()];
+        let ops = GtsOps::new(Some(path), None, 0);
+        let result = ops.lint_store();
+
+        assert!(!result.ok);
+        assert_eq!(result.file_name_mismatches.len(), 1);
+        assert_eq!(
+            result.file_name_mismatches[0].file_name_id,
+            "gts.x.lint.store.item.v1~"
+        );
+        assert_eq!(
+            result.file_name_mismatches[0].entity_id,
+            "gts.x.lint.store.item.v2~"
+        );
+    }
+
+    #[test]
+    fn test_lint_store_with_file_name_encoding() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let schema = json!({
+            "$id": "gts://gts.x.lint.store.item.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object"
+        });
+        fs::write(
+            dir.path().join("gts.x.lint.store.item.v1%7E.schema.json"),
+            schema.to_string(),
+        )
+        .unwrap();
+
+        let path = vec![dir.path().to_string_lossy().into_owned()];
+        let ops = GtsOps::new(Some(path), None, 0);
+        assert!(ops.lint_store_with(&[], GtsFileNameEncoding::Percent).ok);
+
+        let result = ops.lint_store();
+        assert!(!result.ok);
+        let mismatch = &result.file_name_mismatches[0];
+        assert_eq!(mismatch.file_name_id, "gts.x.lint.store.item.v1%7E");
+        assert_eq!(
+            mismatch.expected_file_name,
+            "gts.x.lint.store.item.v1~.schema.json"
+        );
+
+        let result = ops.lint_store_with(&[], GtsFileNameEncoding::Hashed);
+        assert_eq!(
+            result.file_name_mismatches[0].expected_file_name,
+            GtsFileNameEncoding::Hashed.schema_file_name("gts.x.lint.store.item.v1~")
+        );
+    }
+
+    #[test]
+    fn test_health_reports_loaded_store() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let schema = json!({
+            "$id": "gts://gts.x.health.store.item.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object"
+        });
+        fs::write(dir.path().join("item.schema.json"), schema.to_string()).unwrap();
+        fs::write(dir.path().join("broken.json"), "{not json").unwrap();
+
+        let path = vec![dir.path().to_string_lossy().into_owned()];
+        let health = GtsOps::new(Some(path), None, 0).health();
+
+        assert_eq!(health.schemas, 1);
+        assert_eq!(health.instances, 0);
+        assert_eq!(health.vendors, 1);
+        assert!(health.last_load_unix_secs.is_some());
+        assert_eq!(health.load_errors.len(), 1);
+        assert!(health.load_errors[0].path.ends_with("broken.json"));
+        assert!(health.index_memory_estimate_bytes > 0);
+    }
+
+    #[test]
+    fn test_lint_store_empty_store_is_ok() {
+        let ops = GtsOps::new(None, None, 0);
+        assert!(ops.lint_store().ok);
+    }
+
+    #[test]
+    fn test_expand_pattern() {
+        let mut ops = GtsOps::new(None, None, 0);
+        for id in ["gts.x.expand.ops.a.v1~", "gts.x.expand.ops.b.v1~"] {
+            let schema = json!({
+                "$id": format!("gts://{id}"),
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object"
+            });
+            ops.add_entity(&schema, false);
+        }
+
+        let result = ops.expand_pattern("gts.x.expand.ops.*", true);
+        assert!(result.error.is_empty());
+        assert_eq!(
+            result.ids,
+            vec!["gts.x.expand.ops.a.v1~", "gts.x.expand.ops.b.v1~"]
+        );
+        assert_eq!(result.count, 2);
+    }
+
+    #[test]
+    fn test_expand_pattern_zero_matches() {
+        let ops = GtsOps::new(None, None, 0);
+
+        let lenient = ops.expand_pattern("gts.x.nothing.*", false);
+        assert!(lenient.error.is_empty());
+        assert_eq!(lenient.count, 0);
+
+        let strict = ops.expand_pattern("gts.x.nothing.*", true);
+        assert!(strict.error.contains("matches no entities"));
+    }
+
+    #[test]
+    fn test_diff_instances() {
+        let mut ops = GtsOps::new(None, None, 0);
+        for content in [
+            json!({"id": "gts.x.core.events.topic.v1~x.app._.orders.v1", "retention": "P7D", "partitions": 3}),
+            json!({"partitions": 6, "id": "gts.x.core.events.topic.v1~x.app._.orders.v2", "retention": "P7D", "owner": "team-a"}),
+            json!({"id": "gts.x.core.events.queue.v1~x.app._.jobs.v1", "retention": "P7D"}),
+        ] {
+            assert!(ops.add_entity(&content, false).ok);
+        }
+
+        let result = ops.diff_instances(
+            "gts.x.core.events.topic.v1~x.app._.orders.v1",
+            "gts.x.core.events.topic.v1~x.app._.orders.v2",
+        );
+        assert!(result.ok, "{}", result.error);
+        assert_eq!(
+            result.schema_id.as_deref(),
+            Some("gts.x.core.events.topic.v1~")
+        );
+        let lines: Vec<String> = result.changes.iter().map(ToString::to_string).collect();
+        assert_eq!(lines, vec!["+ owner: \"team-a\"", "~ partitions: 3 -> 6"]);
+
+        let mismatch = ops.diff_instances(
+            "gts.x.core.events.topic.v1~x.app._.orders.v1",
+            "gts.x.core.events.queue.v1~x.app._.jobs.v1",
+        );
+        assert!(!mismatch.ok);
+        assert!(mismatch.error.contains("different types"));
+
+        let missing = ops.diff_instances(
+            "gts.x.core.events.topic.v1~x.app._.orders.v1",
+            "gts.x.nope.a.b.v1~x.y._.z.v1",
+        );
+        assert!(!missing.ok);
+    }
+
+    #[test]
+    fn test_flatten_chain() {
+        let mut ops = GtsOps::new(None, None, 0);
+        for content in [
+            json!({
+                "$id": "gts://gts.x.core.events.type.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "properties": {"id": {"type": "string"}},
+                "required": ["id"]
+            }),
+            json!({
+                "$id": "gts://gts.x.core.events.type.v1~x.core.audit.event.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "allOf": [
+                    {"$ref": "gts://gts.x.core.events.type.v1~"},
+                    {"properties": {"id": {"type": "integer"}, "user": {"type": "string"}}, "required": ["user"]}
+                ]
+            }),
+        ] {
+            assert!(ops.add_entity(&content, false).ok);
+        }
+
+        let base = ops.flatten_chain("gts.x.core.events.type.v1~");
+        assert!(base.ok, "{}", base.error);
+
+        let result = ops.flatten_chain("gts.x.core.events.type.v1~x.core.audit.event.v1~");
+        assert!(!result.ok);
+        assert!(result.error.is_empty());
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].property.as_deref(), Some("id"));
+        let schema = result.schema.unwrap();
+        assert_eq!(schema["required"], json!(["id", "user"]));
+        assert_eq!(schema["properties"]["id"], json!({"type": "string"}));
+
+        let missing = ops.flatten_chain("gts.x.core.events.type.v1~x.nope.a.b.v1~");
+        assert!(!missing.ok);
+        assert!(missing.schema.is_none());
+    }
+
+    #[test]
+    fn test_redaction_applies_to_every_output() {
+        const SECRET: &str = "sk-live-4242";
+        let mut ops = GtsOps::new(None, None, 0);
+        ops.redaction =
+            GtsRedaction::parse("redact:\n  - path: $..api_key\n  - path: $.endpoint\n").unwrap();
+        for schema in [
+            json!({
+                "$id": "gts://gts.x.core.services.client.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "properties": {
+                    "id": {"type": "string"},
+                    "api_key": {"type": "integer"},
+                    "endpoint": {"type": "object"}
+                }
+            }),
+            json!({
+                "$id": "gts://gts.x.core.services.client.v1.1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "properties": {
+                    "id": {"type": "string"},
+                    "api_key": {"type": "integer"},
+                    "endpoint": {"type": "object"},
+                    "region": {"type": "string", "default": "eu"}
+                }
+            }),
+        ] {
+            assert!(ops.add_entity(&schema, false).ok);
+        }
+        let a = "gts.x.core.services.client.v1~x.app._.billing.v1";
+        let b = "gts.x.core.services.client.v1~x.app._.billing_copy.v1";
+        for (id, key) in [(a, SECRET), (b, "sk-other")] {
+            let instance = json!({
+                "id": id,
+                "api_key": key,
+                "endpoint": {"url": format!("https://{SECRET}.internal")}
+            });
+            assert!(ops.add_entity(&instance, false).ok);
+        }
+        let invalid = json!({"id": a, "api_key": SECRET});
+        let outputs = [
+            serde_json::to_string(&ops.get_entity(a)).unwrap(),
+            serde_json::to_string(&ops.query("gts.x.core.services.*", 10)).unwrap(),
+            serde_json::to_string(
+                &ops.query_explained(&format!("gts.x.core.services.*[api_key={SECRET}]"), 10),
+            )
+            .unwrap(),
+            serde_json::to_string(&ops.attr(&format!("{a}@api_key"))).unwrap(),
+            serde_json::to_string(&ops.attr(&format!("{a}@endpoint.url"))).unwrap(),
+            serde_json::to_string(&ops.validate_instance(a)).unwrap(),
+            serde_json::to_string(&ops.validate_entity(a)).unwrap(),
+            serde_json::to_string(&ops.cast(a, "gts.x.core.services.client.v1.1~")).unwrap(),
+            serde_json::to_string(&ops.diff_instances(a, b)).unwrap(),
+            serde_json::to_string(&ops.add_entity(&invalid, true)).unwrap(),
+            serde_json::to_string(&ops.validate_content(&invalid)).unwrap(),
+            serde_json::to_string(&ops.validate_content(&json!({"api_key": SECRET}))).unwrap(),
+        ];
+        for output in &outputs {
+            assert!(!output.contains(SECRET), "{output}");
+        }
+        // The validation error is still reported, just masked
+        assert!(outputs[5].contains("[REDACTED]"), "{}", outputs[5]);
+        assert!(outputs[11].contains("[REDACTED]"), "{}", outputs[11]);
+        // The store keeps the real values
+        assert_eq!(ops.store.get(a).unwrap().content["api_key"], json!(SECRET));
+    }
+
+    #[test]
+    fn test_keyword_handlers_validate_schemas_and_instances() {
+        let mut ops = GtsOps::new(None, None, 0);
+        // `x-acl` names the instance field holding the owning tenant
+        ops.register_keyword("x-acl", |ctx| {
+            let field = ctx.value.as_str().ok_or("expected a field name")?;
+            if ctx.entity.is_schema || ctx.entity.content.get(field).is_some() {
+                Ok(())
+            } else {
+                Err(format!(
+                    "missing '{field}' required by {} of {} chain schemas",
+                    ctx.schema_id,
+                    ctx.chain.len()
+                ))
+            }
+        });
+        let base = json!({
+            "$id": "gts://gts.x.core.docs.doc.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "x-acl": "tenant",
+            "properties": {"id": {"type": "string"}, "tenant": {"type": "string"}}
+        });
+        assert!(ops.add_entity(&base, false).ok);
+        let derived = json!({
+            "$id": "gts://gts.x.core.docs.doc.v1~x.app._.note.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "allOf": [{"$ref": "gts://gts.x.core.docs.doc.v1~"}]
+        });
+        assert!(ops.add_entity(&derived, false).ok);
+
+        let bad_schema = json!({
+            "$id": "gts://gts.x.core.docs.bad.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": {"id": {"type": "string", "x-acl": 7}}
+        });
+        let result = ops.add_entity(&bad_schema, false);
+        assert!(!result.ok);
+        assert!(
+            result
+                .error
+                .contains("gts.x.core.docs.bad.v1~#/properties/id x-acl: expected a field name"),
+            "{}",
+            result.error
+        );
+
+        let owned = "gts.x.core.docs.doc.v1~x.app._.note.v1~x.app._.owned.v1";
+        let orphan = "gts.x.core.docs.doc.v1~x.app._.note.v1~x.app._.orphan.v1";
+        assert!(
+            ops.add_entity(&json!({"id": owned, "tenant": "t1"}), true)
+                .ok
+        );
+        assert!(ops.add_entity(&json!({"id": orphan}), false).ok);
+        let result = ops.validate_instance(orphan);
+        assert!(!result.ok);
+        assert!(
+            result.error.contains(
+                "x-acl: missing 'tenant' required by gts.x.core.docs.doc.v1~ of 2 chain schemas"
+            ),
+            "{}",
+            result.error
+        );
+    }
+
+    #[test]
+    fn test_validate_content_leaves_store_unchanged() {
+        let mut ops = GtsOps::new(None, None, 0);
+        let schema = json!({
+            "$id": "gts://gts.x.core.events.type.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object"
+        });
+        assert!(ops.add_entity(&schema, false).ok);
+
+        let mut broken = schema.clone();
+        broken["properties"] = json!({"a": {"$ref": "https://example.com/a.json"}});
+        let result = ops.validate_content(&broken);
+        assert!(!result.ok);
+        assert_eq!(result.entity_type, "schema");
+        assert_eq!(
+            ops.get_entity("gts.x.core.events.type.v1~").content,
+            Some(schema)
+        );
+
+        let instance = json!({
+            "id": "gts.x.core.events.type.v1~x.app._.orders.v1",
+            "type": "gts.x.core.events.type.v1~"
+        });
+        let result = ops.validate_content(&instance);
+        assert!(result.ok, "{}", result.error);
+        assert!(
+            !ops.get_entity("gts.x.core.events.type.v1~x.app._.orders.v1")
+                .ok
+        );
+    }
 }
@@ -0,0 +1,347 @@
+//! Per-vendor (or per-pattern) slices of a store.
+//!
+//! [`GtsStore::partition`] splits the loaded entities into [`GtsBundle`]s that
+//! can be handed out separately, e.g. one per partner vendor. References that
+//! leave a bundle are kept as explicit [`ExternalRef`]s naming the partition
+//! that holds the target; references no partition can resolve are collected
+//! in the [`PartitionReport`].
+//!
+//! An entity belongs to the vendor of the segment its ID defines (the last
+//! one), so `gts.x.core.events.type.v1~acme.app._.orders.v1` is `acme`'s and
+//! refers to `x`'s base type.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::entities::GtsEntity;
+use crate::gts::{GtsID, GtsWildcard};
+use crate::store::GtsStore;
+
+#[derive(Debug, Error)]
+pub enum PartitionError {
+    #[error("Invalid partition pattern '{pattern}': {cause}")]
+    InvalidPattern { pattern: String, cause: String },
+}
+
+/// How entities are assigned to partitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionKey {
+    /// One partition per vendor.
+    Vendor,
+    /// `(partition, GTS wildcard pattern)` pairs; a partition may be listed
+    /// with several patterns. The first matching pattern wins, entities
+    /// matching none are left out.
+    Patterns(Vec<(String, String)>),
+}
+
+/// A reference from a bundle's entity to an entity outside the bundle.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ExternalRef {
+    /// Referring entity
+    pub from: String,
+    /// Where the reference sits in the referring entity
+    pub path: String,
+    /// Referenced entity
+    pub to: String,
+    /// Partition holding `to`; `None` if no partition does
+    pub partition: Option<String>,
+}
+
+/// The entities of one partition.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GtsBundle {
+    pub partition: String,
+    pub schemas: BTreeMap<String, Value>,
+    pub instances: BTreeMap<String, Value>,
+    pub external_refs: Vec<ExternalRef>,
+}
+
+impl GtsBundle {
+    /// External references no partition resolves.
+    pub fn dangling_refs(&self) -> impl Iterator<Item = &ExternalRef> {
+        self.external_refs.iter().filter(|r| r.partition.is_none())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartitionSummary {
+    pub partition: String,
+    pub schemas: usize,
+    pub instances: usize,
+    pub external_refs: usize,
+}
+
+/// A reference that leaves its partition and that no partition resolves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DanglingRef {
+    pub partition: String,
+    pub from: String,
+    pub path: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartitionReport {
+    pub ok: bool,
+    pub partitions: Vec<PartitionSummary>,
+    pub dangling: Vec<DanglingRef>,
+    /// Entities that no partition selected
+    pub unassigned: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GtsPartitioning {
+    /// Bundles sorted by partition name
+    pub bundles: Vec<GtsBundle>,
+    pub report: PartitionReport,
+}
+
+impl GtsStore {
+    /// Split the loaded entities into bundles according to `key`.
+    ///
+    /// # Errors
+    /// Returns `PartitionError::InvalidPattern` if a pattern is not a valid
+    /// GTS wildcard.
+    pub fn partition(&self, key: &PartitionKey) -> Result<GtsPartitioning, PartitionError> {
+        let patterns = match key {
+            PartitionKey::Vendor => Vec::new(),
+            PartitionKey::Patterns(patterns) => patterns
+                .iter()
+                .map(|(name, p)| {
+                    GtsWildcard::new(p)
+                        .map(|w| (name.as_str(), w))
+                        .map_err(|e| PartitionError::InvalidPattern {
+                            pattern: p.clone(),
+                            cause: e.to_string(),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+
+        let mut assigned: HashMap<&str, String> = HashMap::new();
+        let mut unassigned = BTreeSet::new();
+        for (id, entity) in self.items() {
+            let name = partition_id(id, entity).and_then(|gts_id| match key {
+                PartitionKey::Vendor => defining_vendor(&gts_id),
+                PartitionKey::Patterns(_) => patterns
+                    .iter()
+                    .find(|(_, p)| gts_id.wildcard_match(p))
+                    .map(|(name, _)| (*name).to_owned()),
+            });
+            match name {
+                Some(name) => {
+                    assigned.insert(id.as_str(), name);
+                }
+                None => {
+                    unassigned.insert(id.clone());
+                }
+            }
+        }
+
+        let mut bundles: BTreeMap<&str, GtsBundle> = BTreeMap::new();
+        for (id, entity) in self.items() {
+            let Some(name) = assigned.get(id.as_str()) else {
+                continue;
+            };
+            let bundle = bundles.entry(name).or_insert_with(|| GtsBundle {
+                partition: name.clone(),
+                ..GtsBundle::default()
+            });
+            let target = if entity.is_schema {
+                &mut bundle.schemas
+            } else {
+                &mut bundle.instances
+            };
+            target.insert(id.clone(), entity.content.clone());
+            for (path, to) in entity_refs(id, entity) {
+                let partition = assigned.get(to.as_str());
+                if partition != Some(name) {
+                    bundle.external_refs.push(ExternalRef {
+                        from: id.clone(),
+                        path,
+                        to,
+                        partition: partition.cloned(),
+                    });
+                }
+            }
+        }
+
+        let mut report = PartitionReport {
+            unassigned: unassigned.into_iter().collect(),
+            ..PartitionReport::default()
+        };
+        let bundles: Vec<GtsBundle> = bundles
+            .into_values()
+            .map(|mut bundle| {
+                bundle.external_refs.sort();
+                report.partitions.push(PartitionSummary {
+                    partition: bundle.partition.clone(),
+                    schemas: bundle.schemas.len(),
+                    instances: bundle.instances.len(),
+                    external_refs: bundle.external_refs.len(),
+                });
+                report
+                    .dangling
+                    .extend(bundle.dangling_refs().map(|r| DanglingRef {
+                        partition: bundle.partition.clone(),
+                        from: r.from.clone(),
+                        path: r.path.clone(),
+                        to: r.to.clone(),
+                    }));
+                bundle
+            })
+            .collect();
+        report.ok = report.dangling.is_empty();
+        Ok(GtsPartitioning { bundles, report })
+    }
+}
+
+/// The GTS ID an entity is partitioned by: its own, or its type's for
+/// anonymous instances.
+fn partition_id(id: &str, entity: &GtsEntity) -> Option<GtsID> {
+    GtsID::new(id)
+        .ok()
+        .or_else(|| GtsID::new(entity.schema_id.as_deref()?).ok())
+}
+
+/// Vendor of the last non-UUID segment.
+fn defining_vendor(id: &GtsID) -> Option<String> {
+    id.gts_id_segments
+        .iter()
+        .rev()
+        .find(|s| !s.is_uuid_tail)
+        .map(|s| s.vendor.clone())
+}
+
+/// `(path, target)` of every concrete GTS ID the entity refers to besides itself:
+/// its type, `$ref`s and GTS ID strings in the content.
+fn entity_refs(id: &str, entity: &GtsEntity) -> BTreeSet<(String, String)> {
+    let type_ref = entity.schema_id.as_ref().map(|schema_id| {
+        let path = entity
+            .selected_schema_id_field
+            .clone()
+            .unwrap_or_else(|| "type".to_owned());
+        (path, schema_id.clone())
+    });
+    type_ref
+        .into_iter()
+        .chain(
+            entity
+                .gts_refs
+                .iter()
+                .chain(&entity.schema_refs)
+                .map(|r| (r.source_path.clone(), r.id.clone())),
+        )
+        .filter_map(|(path, to)| {
+            let to = to.split('#').next().unwrap_or_default().to_owned();
+            (to != id && !to.contains('*') && GtsID::is_valid(&to)).then_some((path, to))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_support::store_from_json;
+    use serde_json::json;
+
+    fn store() -> GtsStore {
+        store_from_json(&[
+            json!({
+                "$id": "gts://gts.x.core.events.type.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object"
+            }),
+            json!({
+                "$id": "gts://gts.x.core.events.type.v1~acme.app.orders.placed.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "allOf": [{"$ref": "gts://gts.x.core.events.type.v1~"}],
+                "properties": {
+                    "topic": {"x-gts-ref": "gts.x.core.events.topic.v1~*"},
+                    "audit": {"const": "gts.x.core.events.type.v1~globex.audit._.trail.v1"}
+                }
+            }),
+            json!({"id": "gts.x.core.events.type.v1~acme.app.orders.placed.v1~acme.app._.first.v1"}),
+        ])
+    }
+
+    #[test]
+    fn test_partition_by_vendor() {
+        let partitioning = store().partition(&PartitionKey::Vendor).unwrap();
+        let names: Vec<&str> = partitioning
+            .bundles
+            .iter()
+            .map(|b| b.partition.as_str())
+            .collect();
+        assert_eq!(names, ["acme", "x"]);
+
+        let acme = &partitioning.bundles[0];
+        assert_eq!(acme.schemas.len(), 1);
+        assert_eq!(acme.instances.len(), 1);
+        let base = ExternalRef {
+            from: "gts.x.core.events.type.v1~acme.app.orders.placed.v1~".to_owned(),
+            path: "allOf[0].$ref".to_owned(),
+            to: "gts.x.core.events.type.v1~".to_owned(),
+            partition: Some("x".to_owned()),
+        };
+        assert!(
+            acme.external_refs.contains(&base),
+            "{:?}",
+            acme.external_refs
+        );
+        // The instance's type lives in the same bundle
+        assert!(
+            acme.external_refs
+                .iter()
+                .all(|r| r.to != "gts.x.core.events.type.v1~acme.app.orders.placed.v1~")
+        );
+        assert!(partitioning.bundles[1].external_refs.is_empty());
+
+        let report = &partitioning.report;
+        assert!(!report.ok);
+        assert_eq!(
+            report.dangling,
+            [DanglingRef {
+                partition: "acme".to_owned(),
+                from: "gts.x.core.events.type.v1~acme.app.orders.placed.v1~".to_owned(),
+                path: "properties.audit.const".to_owned(),
+                to: "gts.x.core.events.type.v1~globex.audit._.trail.v1".to_owned(),
+            }]
+        );
+        assert!(report.unassigned.is_empty());
+    }
+
+    #[test]
+    fn test_partition_by_pattern() {
+        let key = PartitionKey::Patterns(vec![(
+            "orders".to_owned(),
+            "gts.x.core.events.type.v1~acme.app.orders.*".to_owned(),
+        )]);
+        let partitioning = store().partition(&key).unwrap();
+        assert_eq!(partitioning.bundles.len(), 1);
+        assert_eq!(partitioning.bundles[0].partition, "orders");
+        assert_eq!(partitioning.bundles[0].instances.len(), 1);
+        assert_eq!(
+            partitioning.report.unassigned,
+            ["gts.x.core.events.type.v1~"]
+        );
+        // The base type is in the store but in no partition
+        assert!(
+            partitioning
+                .report
+                .dangling
+                .iter()
+                .any(|r| r.to == "gts.x.core.events.type.v1~")
+        );
+
+        let invalid = PartitionKey::Patterns(vec![("bad".to_owned(), "x.*".to_owned())]);
+        assert!(matches!(
+            store().partition(&invalid),
+            Err(PartitionError::InvalidPattern { .. })
+        ));
+    }
+}
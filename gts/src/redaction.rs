@@ -0,0 +1,403 @@
+//! Redaction of sensitive instance values in rendered output.
+//!
+//! Instance bodies can carry keys, tokens or internal URLs. A redaction
+//! document lists JSON path patterns whose values are replaced by a mask
+//! whenever [`GtsOps`](crate::GtsOps) renders an instance: entity lookups,
+//! queries, attribute selectors, casts, diffs and validation messages, and
+//! the CLI's partition export. Stored instances are never modified.
+//!
+//! ```yaml
+//! redact:
+//!   - path: $..api_key               # at any depth
+//!   - path: $.endpoints[*].url
+//!     mask: "<internal url>"
+//! ```
+//!
+//! Patterns start at the instance root (`$`): `.key` or `['key']` selects a
+//! field, `*` any field or index, `[*]` any index, `[2]` one index and `..`
+//! any depth. Schemas are not redacted.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// Mask used when a rule does not set one.
+pub const DEFAULT_REDACTION_MASK: &str = "[REDACTED]";
+
+#[derive(Debug, Error)]
+pub enum RedactionError {
+    #[error("Failed to read {path}: {cause}")]
+    Read { path: String, cause: String },
+    #[error("Invalid redaction rules: {0}")]
+    Invalid(String),
+    #[error("Invalid redaction path '{path}': {cause}")]
+    Path { path: String, cause: String },
+}
+
+/// One entry of a redaction document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionRule {
+    pub path: String,
+    #[serde(default = "default_mask")]
+    pub mask: String,
+}
+
+fn default_mask() -> String {
+    DEFAULT_REDACTION_MASK.to_owned()
+}
+
+/// Top-level shape of a redaction document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionDocument {
+    #[serde(default)]
+    pub redact: Vec<RedactionRule>,
+}
+
+/// A step of a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    /// `*`: any key or index
+    Any,
+    /// `[*]`: any index
+    AnyIndex,
+    /// `..`: zero or more levels
+    Descend,
+}
+
+/// A step of a concrete location in an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Elem {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CompiledRule {
+    steps: Vec<Step>,
+    mask: String,
+}
+
+/// Compiled redaction rules; empty (the default) redacts nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GtsRedaction {
+    rules: Vec<CompiledRule>,
+}
+
+impl GtsRedaction {
+    /// # Errors
+    /// Returns `RedactionError::Path` for a malformed path pattern.
+    pub fn new(rules: &[RedactionRule]) -> Result<Self, RedactionError> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                parse_pattern(&rule.path)
+                    .map(|steps| CompiledRule {
+                        steps,
+                        mask: rule.mask.clone(),
+                    })
+                    .map_err(|cause| RedactionError::Path {
+                        path: rule.path.clone(),
+                        cause,
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Parse a redaction document from YAML (or JSON, which is valid YAML).
+    ///
+    /// # Errors
+    /// Returns `RedactionError` if the text is not a redaction document.
+    pub fn parse(text: &str) -> Result<Self, RedactionError> {
+        let document: RedactionDocument =
+            serde_saphyr::from_str(text).map_err(|e| RedactionError::Invalid(e.to_string()))?;
+        Self::new(&document.redact)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Mask every value of `instance` that a rule selects.
+    pub fn redact(&self, instance: &mut Value) {
+        self.redact_from(&mut Vec::new(), instance, &mut Vec::new());
+    }
+
+    /// A masked copy of `instance`.
+    #[must_use]
+    pub fn redacted(&self, instance: &Value) -> Value {
+        let mut instance = instance.clone();
+        self.redact(&mut instance);
+        instance
+    }
+
+    /// Mask `value`, found at `path` (`a.b[0]`, `$.a.b[0]` or `a/b`) of an
+    /// instance, as if the whole instance were redacted.
+    pub fn redact_at(&self, path: &str, value: &mut Value) {
+        if self.is_empty() {
+            return;
+        }
+        let mut location = parse_location(path);
+        self.redact_from(&mut location, value, &mut Vec::new());
+    }
+
+    /// `message`, rendered from `instance` (e.g. a schema validation error),
+    /// with the redacted values of `instance` masked.
+    #[must_use]
+    pub fn redact_message(&self, instance: &Value, message: &str) -> String {
+        if self.is_empty() {
+            return message.to_owned();
+        }
+        let mut hits = Vec::new();
+        let mut copy = instance.clone();
+        self.redact_from(&mut Vec::new(), &mut copy, &mut hits);
+
+        // Longest renderings first, so a value is not half-masked by a shorter one
+        let mut needles: Vec<(String, &str)> = Vec::new();
+        for (value, mask) in &hits {
+            if !value.is_string() {
+                needles.push((value.to_string(), mask));
+                if let Ok(pretty) = serde_json::to_string_pretty(value) {
+                    needles.push((pretty, mask));
+                }
+            }
+            collect_strings(value, &mut |s| {
+                needles.push((Value::String(s.to_owned()).to_string(), mask));
+                needles.push((s.to_owned(), mask));
+            });
+        }
+        needles.retain(|(needle, _)| !needle.is_empty());
+        needles.sort_by_key(|(needle, _)| std::cmp::Reverse(needle.len()));
+        needles
+            .into_iter()
+            .fold(message.to_owned(), |message, (needle, mask)| {
+                message.replace(&needle, mask)
+            })
+    }
+
+    fn mask_for(&self, location: &[Elem]) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|rule| matches(&rule.steps, location))
+            .map(|rule| rule.mask.as_str())
+    }
+
+    fn redact_from<'a>(
+        &'a self,
+        location: &mut Vec<Elem>,
+        value: &mut Value,
+        hits: &mut Vec<(Value, &'a str)>,
+    ) {
+        if let Some(mask) = self.mask_for(location) {
+            let original = std::mem::replace(value, Value::String(mask.to_owned()));
+            hits.push((original, mask));
+            return;
+        }
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    location.push(Elem::Key(key.clone()));
+                    self.redact_from(location, child, hits);
+                    location.pop();
+                }
+            }
+            Value::Array(items) => {
+                for (i, child) in items.iter_mut().enumerate() {
+                    location.push(Elem::Index(i));
+                    self.redact_from(location, child, hits);
+                    location.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Read a redaction document from a YAML/JSON file.
+///
+/// # Errors
+/// Returns `RedactionError::Read` if the file cannot be read and
+/// `RedactionError::Invalid`/`Path` if it is not a valid document.
+pub fn load_redaction(path: &Path) -> Result<GtsRedaction, RedactionError> {
+    let text = std::fs::read_to_string(path).map_err(|e| RedactionError::Read {
+        path: path.display().to_string(),
+        cause: e.to_string(),
+    })?;
+    GtsRedaction::parse(&text)
+}
+
+fn collect_strings(value: &Value, f: &mut impl FnMut(&str)) {
+    match value {
+        Value::String(s) => f(s),
+        Value::Array(items) => items.iter().for_each(|v| collect_strings(v, f)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, f)),
+        _ => {}
+    }
+}
+
+fn matches(steps: &[Step], location: &[Elem]) -> bool {
+    match steps.split_first() {
+        None => location.is_empty(),
+        Some((Step::Descend, rest)) => {
+            (0..=location.len()).any(|skip| matches(rest, &location[skip..]))
+        }
+        Some((step, rest)) => {
+            let Some((elem, location)) = location.split_first() else {
+                return false;
+            };
+            let hit = match (step, elem) {
+                (Step::Key(k), Elem::Key(e)) => k == e,
+                (Step::Index(i), Elem::Index(e)) => i == e,
+                (Step::Any, _) | (Step::AnyIndex, Elem::Index(_)) => true,
+                _ => false,
+            };
+            hit && matches(rest, location)
+        }
+    }
+}
+
+fn parse_pattern(pattern: &str) -> Result<Vec<Step>, String> {
+    let mut rest = pattern
+        .trim()
+        .strip_prefix('$')
+        .ok_or("must start with '$'")?;
+    let mut steps = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            steps.push(Step::Descend);
+            // `$..key` and `$..*`: the name follows without a dot
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end > 0 {
+                steps.push(name_step(&after[..end]));
+            }
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err("empty field name".to_owned());
+            }
+            steps.push(name_step(&after[..end]));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or("unclosed '['")?;
+            let inner = &after[..end];
+            steps.push(if inner == "*" {
+                Step::AnyIndex
+            } else if let Some(key) = inner.strip_prefix('\'').and_then(|k| k.strip_suffix('\'')) {
+                Step::Key(key.to_owned())
+            } else {
+                Step::Index(
+                    inner
+                        .parse()
+                        .map_err(|_| format!("invalid index '[{inner}]'"))?,
+                )
+            });
+            rest = &after[end + 1..];
+        } else {
+            return Err(format!("unexpected '{rest}'"));
+        }
+    }
+    if steps.last() == Some(&Step::Descend) {
+        return Err("'..' must be followed by a field".to_owned());
+    }
+    Ok(steps)
+}
+
+fn name_step(name: &str) -> Step {
+    if name == "*" {
+        Step::Any
+    } else {
+        Step::Key(name.to_owned())
+    }
+}
+
+/// Concrete location `a.b[0]` (also `$.a.b[0]` and `a/b`).
+fn parse_location(path: &str) -> Vec<Elem> {
+    let path = path.trim().trim_start_matches('$').replace('/', ".");
+    let mut location = Vec::new();
+    for part in path.split('.').filter(|p| !p.is_empty()) {
+        let (key, indexes) = part.split_once('[').unwrap_or((part, ""));
+        if !key.is_empty() {
+            location.push(Elem::Key(key.to_owned()));
+        }
+        location.extend(
+            indexes
+                .split(['[', ']'])
+                .filter_map(|i| i.parse().ok())
+                .map(Elem::Index),
+        );
+    }
+    location
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules(yaml: &str) -> GtsRedaction {
+        GtsRedaction::parse(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_redact_patterns() {
+        let redaction = rules(
+            "redact:\n  - path: $..api_key\n  - path: $.endpoints[*].url\n    mask: '<url>'\n",
+        );
+        let instance = json!({
+            "name": "billing",
+            "api_key": "sk-top",
+            "nested": {"api_key": "sk-nested", "other": 1},
+            "endpoints": [{"url": "https://internal", "port": 443}]
+        });
+        assert_eq!(
+            redaction.redacted(&instance),
+            json!({
+                "name": "billing",
+                "api_key": "[REDACTED]",
+                "nested": {"api_key": "[REDACTED]", "other": 1},
+                "endpoints": [{"url": "<url>", "port": 443}]
+            })
+        );
+
+        let mut endpoint = json!({"url": "https://internal", "port": 443});
+        redaction.redact_at("endpoints[0]", &mut endpoint);
+        assert_eq!(endpoint, json!({"url": "<url>", "port": 443}));
+        let mut key = json!("sk-top");
+        redaction.redact_at("$.api_key", &mut key);
+        assert_eq!(key, json!("[REDACTED]"));
+    }
+
+    #[test]
+    fn test_redact_message() {
+        let redaction = rules("redact:\n  - path: $.token\n");
+        let instance = json!({"token": "abc123", "count": "x"});
+        assert_eq!(
+            redaction.redact_message(&instance, r#""abc123" is not of type "integer""#),
+            r#"[REDACTED] is not of type "integer""#
+        );
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        for path in ["api_key", "$.a[", "$.a[x]", "$..", "$.a..b.", "$a"] {
+            let rule = RedactionRule {
+                path: path.to_owned(),
+                mask: default_mask(),
+            };
+            assert!(
+                matches!(GtsRedaction::new(&[rule]), Err(RedactionError::Path { .. })),
+                "{path}"
+            );
+        }
+        assert!(GtsRedaction::parse("redact:\n  - pth: $.a\n").is_err());
+    }
+}
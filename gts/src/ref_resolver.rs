@@ -0,0 +1,444 @@
+//! Resolution of `gts://` `$ref` URIs for JSON Schema validators.
+//!
+//! Schemas with references (`gts_schema_with_refs_as_string()`, or stored
+//! schemas extending a base type through `allOf`) point at other schemas by
+//! `gts://<id>` URIs that standard validators cannot fetch. A
+//! [`GtsRefResolver`] holds the schemas those URIs name, taken from a
+//! [`GtsStore`] or added by hand, and plugs into the `jsonschema` crate as its
+//! retriever:
+//!
+//! ```
+//! use gts::GtsRefResolver;
+//! use serde_json::json;
+//!
+//! let mut resolver = GtsRefResolver::new();
+//! resolver.insert(
+//!     "gts.x.core.events.type.v1~",
+//!     json!({
+//!         "$id": "gts://gts.x.core.events.type.v1~",
+//!         "type": "object",
+//!         "required": ["id"]
+//!     }),
+//! );
+//! let validator = resolver
+//!     .validator(&json!({
+//!         "$id": "gts://gts.x.core.events.type.v1~x.app.orders.placed.v1~",
+//!         "allOf": [
+//!             {"$ref": "gts://gts.x.core.events.type.v1~"},
+//!             {"required": ["order_id"]}
+//!         ]
+//!     }))
+//!     .unwrap();
+//! assert!(validator.is_valid(&json!({"id": "e1", "order_id": "o1"})));
+//! assert!(!validator.is_valid(&json!({"order_id": "o1"})));
+//! ```
+//!
+//! [`GtsRefResolver::validator`] checks first that every `gts://` reference
+//! reachable from the schema resolves and that they form no cycle, since a
+//! GTS type cannot extend itself.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::gts::{GTS_URI_PREFIX, GtsID};
+use crate::schema_flatten::{FlattenedSchema, level_fragments, merge_levels};
+use crate::store::GtsStore;
+
+#[derive(Debug, Error)]
+pub enum RefResolverError {
+    #[error("Schema '{from}' references '{id}', which is not known to the resolver")]
+    Unresolved { from: String, id: String },
+    #[error("Cyclic gts:// references: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(String),
+}
+
+/// Schemas by GTS ID, resolving `gts://` references; clones share them.
+#[derive(Debug, Clone, Default)]
+pub struct GtsRefResolver {
+    schemas: Arc<HashMap<String, Value>>,
+}
+
+impl GtsRefResolver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolver over the schemas `store` holds; schemas its reader or
+    /// storage has not loaded yet are not included.
+    #[must_use]
+    pub fn from_store(store: &GtsStore) -> Self {
+        store
+            .items()
+            .filter(|(_, entity)| entity.is_schema)
+            .map(|(id, entity)| (id.clone(), entity.content.clone()))
+            .collect()
+    }
+
+    /// Resolve `id` (with or without the `gts://` prefix) to `schema`,
+    /// replacing the previous schema of `id`.
+    pub fn insert(&mut self, id: &str, schema: Value) {
+        let id = id.strip_prefix(GTS_URI_PREFIX).unwrap_or(id);
+        Arc::make_mut(&mut self.schemas).insert(id.to_owned(), schema);
+    }
+
+    /// The schema of `id` (with or without the `gts://` prefix), if known.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&Value> {
+        self.schemas
+            .get(id.strip_prefix(GTS_URI_PREFIX).unwrap_or(id))
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.schemas.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+
+    /// Check that every `gts://` reference reachable from `schema` resolves
+    /// and that they form no cycle.
+    ///
+    /// # Errors
+    /// Returns `RefResolverError::Unresolved` for the first unknown schema
+    /// and `RefResolverError::Cycle` with the IDs along the first cycle.
+    pub fn check_refs(&self, schema: &Value) -> Result<(), RefResolverError> {
+        let root = schema
+            .get("$id")
+            .and_then(Value::as_str)
+            .map(|id| id.strip_prefix(GTS_URI_PREFIX).unwrap_or(id).to_owned())
+            .unwrap_or_default();
+        let mut path = vec![root];
+        let mut checked = HashSet::new();
+        for id in gts_refs(schema) {
+            self.check_from(&id, &mut path, &mut checked)?;
+        }
+        Ok(())
+    }
+
+    fn check_from(
+        &self,
+        id: &str,
+        path: &mut Vec<String>,
+        checked: &mut HashSet<String>,
+    ) -> Result<(), RefResolverError> {
+        if let Some(start) = path.iter().position(|seen| seen == id) {
+            let mut cycle = path.split_off(start);
+            cycle.push(id.to_owned());
+            return Err(RefResolverError::Cycle(cycle));
+        }
+        if checked.contains(id) {
+            return Ok(());
+        }
+        let schema = self.get(id).ok_or_else(|| RefResolverError::Unresolved {
+            from: path.last().cloned().unwrap_or_default(),
+            id: id.to_owned(),
+        })?;
+        path.push(id.to_owned());
+        for next in gts_refs(schema) {
+            self.check_from(&next, path, checked)?;
+        }
+        path.pop();
+        checked.insert(id.to_owned());
+        Ok(())
+    }
+
+    /// A validator of `schema` resolving its `gts://` references here, after
+    /// [`check_refs`](Self::check_refs).
+    ///
+    /// # Errors
+    /// Returns the errors of [`check_refs`](Self::check_refs) and
+    /// `RefResolverError::InvalidSchema` if the schema does not compile.
+    pub fn validator(&self, schema: &Value) -> Result<jsonschema::Validator, RefResolverError> {
+        self.check_refs(schema)?;
+        jsonschema::options()
+            .with_retriever(self.clone())
+            .build(schema)
+            .map_err(|e| RefResolverError::InvalidSchema(e.to_string()))
+    }
+
+    /// `schema` as a single self-contained document: the schemas of its `~`
+    /// chain, resolved here, are merged into it base first (properties,
+    /// `required` and `additionalProperties`, as by
+    /// [`GtsStore::flatten_chain`]) and any other `gts://` reference is
+    /// replaced by the schema it names.
+    ///
+    /// # Errors
+    /// Returns the errors of [`check_refs`](Self::check_refs) for `schema`
+    /// and each schema of its chain, `RefResolverError::Unresolved` if a
+    /// chain schema is unknown, and `RefResolverError::InvalidSchema` if
+    /// `schema` has no GTS schema `$id`.
+    pub fn inline(&self, schema: &Value) -> Result<FlattenedSchema, RefResolverError> {
+        let id = schema
+            .get("$id")
+            .and_then(Value::as_str)
+            .map(|id| id.strip_prefix(GTS_URI_PREFIX).unwrap_or(id))
+            .and_then(|id| GtsID::new(id).ok())
+            .filter(GtsID::is_type)
+            .ok_or_else(|| {
+                RefResolverError::InvalidSchema("inlining needs a GTS schema $id".to_owned())
+            })?;
+
+        let mut contents = Vec::with_capacity(id.gts_id_segments.len());
+        let mut chain_id = String::from("gts.");
+        for segment in &id.gts_id_segments {
+            chain_id.push_str(&segment.segment);
+            let content = if chain_id == id.id {
+                schema
+            } else {
+                self.get(&chain_id)
+                    .ok_or_else(|| RefResolverError::Unresolved {
+                        from: id.id.clone(),
+                        id: chain_id.clone(),
+                    })?
+            };
+            self.check_refs(content)?;
+            contents.push((chain_id.clone(), content));
+        }
+
+        let resolve = |value: &Value| Ok(self.inline_refs(value));
+        let mut levels = Vec::with_capacity(contents.len());
+        for (i, (level_id, content)) in contents.iter().enumerate() {
+            let ancestors: Vec<String> = contents[..i].iter().map(|(id, _)| id.clone()).collect();
+            let mut fragments = Vec::new();
+            level_fragments(content, &ancestors, &resolve, &mut fragments)
+                .map_err(RefResolverError::InvalidSchema)?;
+            levels.push((level_id.clone(), fragments));
+        }
+        Ok(merge_levels(&levels))
+    }
+
+    /// `value` with each `gts://` reference replaced by the (fragment of
+    /// the) schema it names, next to the keywords beside the `$ref`.
+    /// References must have been checked to form no cycle.
+    fn inline_refs(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let target = map
+                    .get("$ref")
+                    .and_then(Value::as_str)
+                    .and_then(|r| r.strip_prefix(GTS_URI_PREFIX))
+                    .and_then(|r| {
+                        let (id, pointer) = r.split_once('#').unwrap_or((r, ""));
+                        self.get(id)?.pointer(pointer)
+                    });
+                let Some(target) = target else {
+                    return Value::Object(
+                        map.iter()
+                            .map(|(key, value)| (key.clone(), self.inline_refs(value)))
+                            .collect(),
+                    );
+                };
+                let mut inlined = match self.inline_refs(target) {
+                    Value::Object(inlined) => inlined,
+                    other => return other,
+                };
+                inlined.remove("$id");
+                inlined.remove("$schema");
+                for (key, value) in map.iter().filter(|(key, _)| *key != "$ref") {
+                    inlined.insert(key.clone(), self.inline_refs(value));
+                }
+                Value::Object(inlined)
+            }
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|item| self.inline_refs(item)).collect())
+            }
+            other => other.clone(),
+        }
+    }
+}
+
+impl FromIterator<(String, Value)> for GtsRefResolver {
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(schemas: I) -> Self {
+        let mut resolver = Self::new();
+        for (id, schema) in schemas {
+            resolver.insert(&id, schema);
+        }
+        resolver
+    }
+}
+
+impl jsonschema::Retrieve for GtsRefResolver {
+    fn retrieve(
+        &self,
+        uri: &jsonschema::Uri<String>,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        let uri = uri.as_str();
+        let Some(id) = uri.strip_prefix(GTS_URI_PREFIX) else {
+            return Err(format!("Unknown scheme for URI: {uri}").into());
+        };
+        if let Some(schema) = self.schemas.get(id) {
+            return Ok(schema.clone());
+        }
+        tracing::warn!("GtsRefResolver: Schema not found: {uri}");
+        Err(format!("Schema not found: {uri}").into())
+    }
+}
+
+/// The GTS IDs of the `gts://` references in `schema`, fragments dropped.
+fn gts_refs(schema: &Value) -> Vec<String> {
+    fn collect(value: &Value, ids: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if key == "$ref"
+                        && let Some(id) =
+                            value.as_str().and_then(|r| r.strip_prefix(GTS_URI_PREFIX))
+                    {
+                        let id = id.split('#').next().unwrap_or(id);
+                        if !ids.iter().any(|seen| seen == id) {
+                            ids.push(id.to_owned());
+                        }
+                    } else {
+                        collect(value, ids);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| collect(item, ids)),
+            _ => {}
+        }
+    }
+    let mut ids = Vec::new();
+    collect(schema, &mut ids);
+    ids
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn extending(id: &str, base: &str) -> Value {
+        json!({
+            "$id": format!("gts://{id}"),
+            "allOf": [{"$ref": format!("gts://{base}")}, {"type": "object"}]
+        })
+    }
+
+    #[test]
+    fn test_check_refs_reports_missing_schema() {
+        let resolver: GtsRefResolver = [(
+            "gts.x.a.b.c.v1~".to_owned(),
+            extending("gts.x.a.b.c.v1~", "gts.x.a.b.base.v1~"),
+        )]
+        .into_iter()
+        .collect();
+        let err = resolver
+            .check_refs(&json!({"$ref": "gts://gts.x.a.b.c.v1~#/properties/id"}))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RefResolverError::Unresolved { from, id }
+                if from == "gts.x.a.b.c.v1~" && id == "gts.x.a.b.base.v1~"
+        ));
+    }
+
+    #[test]
+    fn test_check_refs_detects_cycles() {
+        let mut resolver = GtsRefResolver::new();
+        resolver.insert(
+            "gts://gts.x.a.b.one.v1~",
+            extending("gts.x.a.b.one.v1~", "gts.x.a.b.two.v1~"),
+        );
+        resolver.insert(
+            "gts.x.a.b.two.v1~",
+            extending("gts.x.a.b.two.v1~", "gts.x.a.b.one.v1~"),
+        );
+        let err = resolver
+            .validator(&extending("gts.x.a.b.leaf.v1~", "gts.x.a.b.one.v1~"))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cyclic gts:// references: gts.x.a.b.one.v1~ -> gts.x.a.b.two.v1~ -> gts.x.a.b.one.v1~"
+        );
+    }
+
+    #[test]
+    fn test_inline_merges_chain_and_refs() {
+        let mut resolver = GtsRefResolver::new();
+        resolver.insert(
+            "gts.x.core.events.type.v1~",
+            json!({
+                "$id": "gts://gts.x.core.events.type.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "properties": {
+                    "id": {"type": "string"},
+                    "payload": {"type": "object", "properties": {"kind": {"type": "string"}}}
+                },
+                "required": ["id"]
+            }),
+        );
+        resolver.insert(
+            "gts.x.core.money.amount.v1~",
+            json!({
+                "$id": "gts://gts.x.core.money.amount.v1~",
+                "type": "object",
+                "properties": {"value": {"type": "number"}}
+            }),
+        );
+        let flat = resolver
+            .inline(&json!({
+                "$id": "gts://gts.x.core.events.type.v1~x.shop.orders.placed.v1~",
+                "allOf": [
+                    {"$ref": "gts://gts.x.core.events.type.v1~"},
+                    {
+                        "properties": {
+                            "payload": {
+                                "type": "object",
+                                "properties": {"total": {"$ref": "gts://gts.x.core.money.amount.v1~"}}
+                            }
+                        },
+                        "required": ["payload"]
+                    }
+                ]
+            }))
+            .unwrap();
+        assert!(flat.conflicts.is_empty(), "{:?}", flat.conflicts);
+        assert!(!flat.schema.to_string().contains("$ref"));
+        assert_eq!(flat.schema["required"], json!(["id", "payload"]));
+        let payload = &flat.schema["properties"]["payload"]["properties"];
+        assert_eq!(payload["kind"], json!({"type": "string"}));
+        assert_eq!(
+            payload["total"],
+            json!({"type": "object", "properties": {"value": {"type": "number"}}})
+        );
+
+        let err = resolver
+            .inline(&json!({"$id": "gts://gts.x.core.events.type.v1~x.a.b.c.v1~x.d.e.f.v1~"}))
+            .unwrap_err();
+        assert!(
+            matches!(err, RefResolverError::Unresolved { id, .. } if id == "gts.x.core.events.type.v1~x.a.b.c.v1~")
+        );
+    }
+
+    #[test]
+    fn test_from_store_resolves_stored_schemas() {
+        let store = crate::test_support::store_from_json(&[json!({
+            "$id": "gts://gts.x.core.events.type.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": {"id": {"type": "string"}},
+            "required": ["id"]
+        })]);
+        let resolver = GtsRefResolver::from_store(&store);
+        assert_eq!(resolver.len(), 1);
+        let validator = resolver
+            .validator(&extending(
+                "gts.x.core.events.type.v1~x.app.a.b.v1~",
+                "gts.x.core.events.type.v1~",
+            ))
+            .unwrap();
+        assert!(validator.is_valid(&json!({"id": "x"})));
+        assert!(!validator.is_valid(&json!({"id": 1})));
+    }
+}
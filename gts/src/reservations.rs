@@ -0,0 +1,177 @@
+//! Reserved vendor/package registries from configuration or the store.
+//!
+//! The same document describes a registry in a standalone file and in the
+//! well-known [`RESERVATIONS_INSTANCE_ID`] instance:
+//!
+//! ```yaml
+//! reservations:
+//!   - vendor: gts
+//!     owners: [platform]
+//!   - vendor: x
+//!     package: core
+//!     owners: [platform]
+//! ```
+//!
+//! Enforcement lives in `gts_id::validate_gts_id_reserved`.
+
+use std::path::Path;
+
+use gts_id::{GtsReservation, GtsReservations, is_valid_segment_token};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::store::GtsStore;
+
+/// Well-known instance holding the store's reservation registry.
+pub const RESERVATIONS_INSTANCE_ID: &str = "gts.x.core.gts.reservations.v1~x.core._.registry.v1";
+
+#[derive(Debug, Error)]
+pub enum ReservationsError {
+    #[error("Failed to read {path}: {cause}")]
+    Read { path: String, cause: String },
+    #[error("Invalid reservations: {0}")]
+    Invalid(String),
+}
+
+/// One entry of a reservations document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReservationEntry {
+    pub vendor: String,
+    /// Omitted to reserve every package of the vendor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    #[serde(default)]
+    pub owners: Vec<String>,
+}
+
+/// Top-level shape of a reservations document; other keys (`id`, `type`,
+/// ...) are allowed so that the document can be a store instance.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReservationsDocument {
+    #[serde(default)]
+    pub reservations: Vec<ReservationEntry>,
+}
+
+impl ReservationsDocument {
+    /// Parse YAML (or JSON, which is valid YAML).
+    ///
+    /// # Errors
+    /// Returns `ReservationsError::Invalid` if the text is not a reservations document.
+    pub fn parse(text: &str) -> Result<Self, ReservationsError> {
+        serde_saphyr::from_str(text).map_err(|e| ReservationsError::Invalid(e.to_string()))
+    }
+
+    /// # Errors
+    /// Returns `ReservationsError::Invalid` if `value` is not a reservations document.
+    pub fn from_value(value: &Value) -> Result<Self, ReservationsError> {
+        Self::deserialize(value).map_err(|e| ReservationsError::Invalid(e.to_string()))
+    }
+
+    /// The registry described by the document.
+    ///
+    /// # Errors
+    /// Returns `ReservationsError::Invalid` if a vendor or package is not a valid token.
+    pub fn registry(&self) -> Result<GtsReservations, ReservationsError> {
+        let mut registry = GtsReservations::new();
+        for entry in &self.reservations {
+            for token in std::iter::once(&entry.vendor).chain(&entry.package) {
+                if !is_valid_segment_token(token) {
+                    return Err(ReservationsError::Invalid(format!(
+                        "'{token}' is not a valid vendor/package name"
+                    )));
+                }
+            }
+            registry.push(GtsReservation {
+                vendor: entry.vendor.clone(),
+                package: entry.package.clone(),
+                owners: entry.owners.clone(),
+            });
+        }
+        Ok(registry)
+    }
+}
+
+/// Load a registry from a YAML/JSON file.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or is not a valid reservations document.
+pub fn load_reservations(path: &Path) -> Result<GtsReservations, ReservationsError> {
+    let text = std::fs::read_to_string(path).map_err(|e| ReservationsError::Read {
+        path: path.display().to_string(),
+        cause: e.to_string(),
+    })?;
+    ReservationsDocument::parse(&text)?.registry()
+}
+
+impl GtsStore {
+    /// The registry of the [`RESERVATIONS_INSTANCE_ID`] instance; empty if
+    /// the store has no such instance.
+    ///
+    /// # Errors
+    /// Returns an error if the instance is not a valid reservations document.
+    pub fn reservations(&mut self) -> Result<GtsReservations, ReservationsError> {
+        match self.get(RESERVATIONS_INSTANCE_ID) {
+            Some(entity) => ReservationsDocument::from_value(&entity.content)?.registry(),
+            None => Ok(GtsReservations::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::entities::{GtsConfig, GtsEntity};
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_registry() {
+        let doc = ReservationsDocument::parse(
+            "reservations:\n  - vendor: gts\n    owners: [platform]\n  - vendor: x\n    package: core\n",
+        )
+        .unwrap();
+        let registry = doc.registry().unwrap();
+        assert_eq!(registry.entries().len(), 2);
+        assert_eq!(registry.entries()[1].name(), "x.core");
+        assert!(registry.entries()[1].owners.is_empty());
+
+        let bad = ReservationsDocument::parse("reservations:\n  - vendor: Bad-Vendor\n").unwrap();
+        assert!(bad.registry().is_err());
+        assert!(ReservationsDocument::parse("reservations:\n  - vendr: x\n").is_err());
+    }
+
+    #[test]
+    fn test_store_reservations_instance() {
+        let mut store = GtsStore::new(None);
+        assert!(store.reservations().unwrap().is_empty());
+
+        let content = json!({
+            "id": RESERVATIONS_INSTANCE_ID,
+            "reservations": [{"vendor": "x", "package": "core", "owners": ["platform"]}]
+        });
+        let entity = GtsEntity::new(
+            None,
+            None,
+            &content,
+            Some(&GtsConfig::default()),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        );
+        store.register(entity).unwrap();
+
+        let registry = store.reservations().unwrap();
+        let err = gts_id::validate_gts_id_reserved(
+            "gts.x.core.events.type.v1~",
+            false,
+            &registry,
+            Some("app"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, gts_id::GtsIdError::Reserved { .. }));
+    }
+}
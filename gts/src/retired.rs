@@ -0,0 +1,310 @@
+//! Registries of retired GTS IDs.
+//!
+//! A retired ID must never come back with different semantics. The registry
+//! lives in a standalone file or in the well-known [`RETIRED_IDS_INSTANCE_ID`]
+//! instance:
+//!
+//! ```yaml
+//! retired:
+//!   - id: gts.x.core.events.order_placed.v1~
+//!     reason: Replaced by gts.x.commerce.orders.placed.v1~
+//!     date: 2025-11-02
+//! ```
+//!
+//! Store loading drops entities with a retired ID and reports them as load
+//! errors; [`GtsStore::register`](crate::GtsStore::register) refuses them, and
+//! the CLI generators check the IDs they are about to write.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::gts::GtsID;
+
+/// Well-known instance holding the store's retired-ID registry.
+pub const RETIRED_IDS_INSTANCE_ID: &str = "gts.x.core.gts.retired_ids.v1~x.core._.registry.v1";
+
+#[derive(Debug, Error)]
+pub enum RetiredIdsError {
+    #[error("Failed to read {path}: {cause}")]
+    Read { path: String, cause: String },
+    #[error("Invalid retired IDs: {0}")]
+    Invalid(String),
+    #[error("GTS ID '{id}' was retired on {date} ({reason}) and must not be reused")]
+    Reused {
+        id: String,
+        reason: String,
+        date: String,
+    },
+}
+
+/// One entry of a retired-IDs document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetiredIdEntry {
+    pub id: String,
+    pub reason: String,
+    /// Retirement date, `YYYY-MM-DD`
+    pub date: String,
+}
+
+/// Top-level shape of a retired-IDs document; other keys (`id`, `type`, ...)
+/// are allowed so that the document can be a store instance.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetiredIdsDocument {
+    #[serde(default)]
+    pub retired: Vec<RetiredIdEntry>,
+}
+
+impl RetiredIdsDocument {
+    /// Parse YAML (or JSON, which is valid YAML).
+    ///
+    /// # Errors
+    /// Returns `RetiredIdsError::Invalid` if the text is not a retired-IDs document.
+    pub fn parse(text: &str) -> Result<Self, RetiredIdsError> {
+        serde_saphyr::from_str(text).map_err(|e| RetiredIdsError::Invalid(e.to_string()))
+    }
+
+    /// # Errors
+    /// Returns `RetiredIdsError::Invalid` if `value` is not a retired-IDs document.
+    pub fn from_value(value: &Value) -> Result<Self, RetiredIdsError> {
+        Self::deserialize(value).map_err(|e| RetiredIdsError::Invalid(e.to_string()))
+    }
+
+    /// The registry described by the document.
+    ///
+    /// # Errors
+    /// Returns `RetiredIdsError::Invalid` if an ID is not a valid GTS ID or a
+    /// date is not `YYYY-MM-DD`.
+    pub fn registry(&self) -> Result<GtsRetiredIds, RetiredIdsError> {
+        let mut registry = GtsRetiredIds::new();
+        for entry in &self.retired {
+            if !GtsID::is_valid(&entry.id) || entry.id.contains('*') {
+                return Err(RetiredIdsError::Invalid(format!(
+                    "'{}' is not a valid GTS ID",
+                    entry.id
+                )));
+            }
+            if !is_iso_date(&entry.date) {
+                return Err(RetiredIdsError::Invalid(format!(
+                    "retirement date '{}' of '{}' is not YYYY-MM-DD",
+                    entry.date, entry.id
+                )));
+            }
+            registry.insert(entry.clone());
+        }
+        Ok(registry)
+    }
+}
+
+/// Whether `date` looks like `YYYY-MM-DD` with a month of 1-12 and a day of 1-31.
+fn is_iso_date(date: &str) -> bool {
+    let mut parts = date.split('-');
+    let (Some(year), Some(month), Some(day), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let number = |part: &str, len: usize| {
+        (part.len() == len && part.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| part.parse::<u16>().ok())
+            .flatten()
+    };
+    number(year, 4).is_some()
+        && number(month, 2).is_some_and(|m| (1..=12).contains(&m))
+        && number(day, 2).is_some_and(|d| (1..=31).contains(&d))
+}
+
+/// Retired IDs, keyed by ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GtsRetiredIds {
+    by_id: BTreeMap<String, RetiredIdEntry>,
+}
+
+impl GtsRetiredIds {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+
+    pub fn insert(&mut self, entry: RetiredIdEntry) {
+        self.by_id.insert(entry.id.clone(), entry);
+    }
+
+    /// Add the entries of `other`, replacing entries for the same ID.
+    pub fn extend(&mut self, other: Self) {
+        self.by_id.extend(other.by_id);
+    }
+
+    /// The retirement of `id`, if it is retired.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&RetiredIdEntry> {
+        self.by_id.get(id)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &RetiredIdEntry> {
+        self.by_id.values()
+    }
+
+    /// Fail if `id` is retired.
+    ///
+    /// # Errors
+    /// Returns `RetiredIdsError::Reused` with the retirement reason and date.
+    pub fn check(&self, id: &str) -> Result<(), RetiredIdsError> {
+        match self.get(id) {
+            Some(entry) => Err(RetiredIdsError::Reused {
+                id: entry.id.clone(),
+                reason: entry.reason.clone(),
+                date: entry.date.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Load a registry from a YAML/JSON file.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or is not a valid retired-IDs document.
+pub fn load_retired_ids(path: &Path) -> Result<GtsRetiredIds, RetiredIdsError> {
+    let text = std::fs::read_to_string(path).map_err(|e| RetiredIdsError::Read {
+        path: path.display().to_string(),
+        cause: e.to_string(),
+    })?;
+    RetiredIdsDocument::parse(&text)?.registry()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::entities::{GtsConfig, GtsEntity};
+    use crate::store::{GtsStore, StoreError};
+    use serde_json::json;
+
+    const RETIRED: &str = "gts.x.core.events.order_placed.v1~";
+
+    fn entity(content: &Value) -> GtsEntity {
+        GtsEntity::new(
+            None,
+            None,
+            content,
+            Some(&GtsConfig::default()),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_parse_registry() {
+        let doc = RetiredIdsDocument::parse(&format!(
+            "retired:\n  - id: {RETIRED}\n    reason: Replaced\n    date: 2025-11-02\n"
+        ))
+        .unwrap();
+        let registry = doc.registry().unwrap();
+        let err = registry.check(RETIRED).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "GTS ID '{RETIRED}' was retired on 2025-11-02 (Replaced) and must not be reused"
+            )
+        );
+        assert!(registry.check("gts.x.core.events.order_placed.v2~").is_ok());
+
+        for bad in [
+            "retired:\n  - id: not-an-id\n    reason: r\n    date: 2025-11-02\n",
+            "retired:\n  - id: gts.x.core.events.*\n    reason: r\n    date: 2025-11-02\n",
+            "retired:\n  - id: gts.x.core.events.type.v1~\n    reason: r\n    date: soon\n",
+        ] {
+            assert!(RetiredIdsDocument::parse(bad).unwrap().registry().is_err());
+        }
+        assert!(RetiredIdsDocument::parse("retired:\n  - id: x\n    date: 2025-11-02\n").is_err());
+    }
+
+    #[test]
+    fn test_store_refuses_retired_ids() {
+        let mut store = GtsStore::new(None);
+        assert!(store.retired_ids().is_empty());
+        store
+            .register_schema("gts.x.core.events.other.v1~", &json!({}))
+            .unwrap();
+        store
+            .register(entity(&json!({
+                "id": RETIRED_IDS_INSTANCE_ID,
+                "retired": [{"id": RETIRED, "reason": "Replaced", "date": "2025-11-02"}]
+            })))
+            .unwrap();
+
+        let err = store.register_schema(RETIRED, &json!({})).unwrap_err();
+        assert!(
+            matches!(err, StoreError::RetiredIds(RetiredIdsError::Reused { .. })),
+            "{err}"
+        );
+        assert!(err.to_string().contains("retired on 2025-11-02 (Replaced)"));
+
+        // A configured registry retires an entity already in the store
+        let mut configured = GtsRetiredIds::new();
+        configured.insert(RetiredIdEntry {
+            id: "gts.x.core.events.other.v1~".to_owned(),
+            reason: "Unused".to_owned(),
+            date: "2024-01-31".to_owned(),
+        });
+        store.add_retired_ids(configured);
+        assert_eq!(store.retired_ids().entries().count(), 2);
+        assert!(store.get("gts.x.core.events.other.v1~").is_none());
+        let load_errors = store.health().load_errors;
+        assert_eq!(load_errors.len(), 1);
+        assert!(load_errors[0].message.contains("retired on 2024-01-31"));
+
+        let invalid = entity(&json!({
+            "id": RETIRED_IDS_INSTANCE_ID,
+            "retired": [{"id": "not-an-id", "reason": "r", "date": "2025-11-02"}]
+        }));
+        assert!(store.register(invalid).is_err());
+        assert_eq!(store.retired_ids().entries().count(), 2);
+    }
+
+    #[test]
+    fn test_store_load_drops_retired_ids() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("retired.json"),
+            json!({
+                "id": RETIRED_IDS_INSTANCE_ID,
+                "retired": [{"id": RETIRED, "reason": "Replaced", "date": "2025-11-02"}]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let schema = dir.path().join(format!("{RETIRED}.schema.json"));
+        std::fs::write(
+            &schema,
+            json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "$id": format!("gts://{RETIRED}"),
+                "type": "object"
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let paths = vec![dir.path().to_string_lossy().into_owned()];
+        let mut store = GtsStore::new(Some(Box::new(crate::GtsFileReader::new(&paths, None))));
+        assert!(store.get(RETIRED).is_none());
+        let load_errors = store.health().load_errors;
+        assert_eq!(load_errors.len(), 1);
+        assert_eq!(load_errors[0].path, schema.to_string_lossy());
+        assert!(load_errors[0].message.contains("must not be reused"));
+    }
+}
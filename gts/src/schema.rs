@@ -29,6 +29,11 @@ pub trait GtsSchema {
     /// For example, `BaseEventV1<P>` has `payload` as the generic field.
     const GENERIC_FIELD: Option<&'static str> = None;
 
+    /// The names of all generic fields, in type parameter order.
+    /// For example, `Envelope<M, P>` has `["meta", "payload"]`; `GENERIC_FIELD`
+    /// is the first of them.
+    const GENERIC_FIELDS: &'static [&'static str] = &[];
+
     /// Returns the JSON schema for this type with $ref references intact.
     fn gts_schema_with_refs() -> Value;
 
@@ -79,6 +84,19 @@ pub trait GtsSchema {
         Vec::new()
     }
 
+    /// Collect one nesting path per generic field instantiated with a GTS type.
+    /// For `Envelope<MetaV1, AuditPayloadV1<PlaceOrderDataV1>>`, returns
+    /// `[["meta"], ["payload", "data"]]`; `collect_nesting_path` follows the first.
+    #[must_use]
+    fn collect_nesting_paths() -> Vec<Vec<&'static str>> {
+        let path = Self::collect_nesting_path();
+        if path.is_empty() {
+            Vec::new()
+        } else {
+            vec![path]
+        }
+    }
+
     /// Wrap properties in a nested structure following the nesting path.
     /// For path `["payload", "data"]` and properties `{order_id, product_id, last}`,
     /// returns `{ "payload": { "type": "object", "properties": { "data": { "type": "object", "additionalProperties": false, "properties": {...}, "required": [...] } } } }`
@@ -382,6 +400,8 @@ mod tests {
         assert_eq!(schema, json!({"type": "object"}));
         assert_eq!(<()>::SCHEMA_ID, "");
         assert_eq!(<()>::GENERIC_FIELD, None);
+        assert!(<()>::GENERIC_FIELDS.is_empty());
+        assert!(<()>::collect_nesting_paths().is_empty());
     }
 
     #[test]
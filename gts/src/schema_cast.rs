@@ -4,6 +4,7 @@ use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
 use crate::gts::GtsID;
+use crate::migrations::{GtsMigrationFn, GtsMigrations};
 
 #[derive(Debug, Error)]
 pub enum SchemaCastError {
@@ -55,6 +56,30 @@ impl GtsEntityCastResult {
         from_schema_content: &Value,
         to_schema_content: &Value,
         _resolver: Option<&()>,
+    ) -> Result<Self, SchemaCastError> {
+        Self::cast_migrated(
+            from_instance_id,
+            to_schema_id,
+            from_instance_content,
+            from_schema_content,
+            to_schema_content,
+            None,
+        )
+    }
+
+    /// Like [`cast`](Self::cast), first running `migration` (see
+    /// [`crate::migrations`]) on the instance. Properties the migration adds or
+    /// removes are reported with those of the automatic steps.
+    ///
+    /// # Errors
+    /// Returns `SchemaCastError` if the cast fails.
+    pub fn cast_migrated(
+        from_instance_id: &str,
+        to_schema_id: &str,
+        from_instance_content: &Value,
+        from_schema_content: &Value,
+        to_schema_content: &Value,
+        migration: Option<&GtsMigrationFn>,
     ) -> Result<Self, SchemaCastError> {
         // Flatten target schema to merge allOf and get all properties including const values
         let target_schema = Self::flatten_schema(to_schema_content);
@@ -76,30 +101,47 @@ impl GtsEntityCastResult {
             .as_object()
             .ok_or(SchemaCastError::InstanceMustBeObject)?;
 
-        let (casted, added, removed, incompatibility_reasons) =
-            match Self::cast_instance_to_schema(instance_obj, &target_schema, "") {
-                Ok(result) => result,
-                Err(e) => {
-                    return Ok(GtsEntityCastResult {
-                        from_id: from_instance_id.to_owned(),
-                        to_id: to_schema_id.to_owned(),
-                        old: from_instance_id.to_owned(),
-                        new: to_schema_id.to_owned(),
-                        direction,
-                        added_properties: Vec::new(),
-                        removed_properties: Vec::new(),
-                        changed_properties: Vec::new(),
-                        is_fully_compatible: false,
-                        is_backward_compatible: is_backward,
-                        is_forward_compatible: is_forward,
-                        incompatibility_reasons: vec![e.to_string()],
-                        backward_errors,
-                        forward_errors,
-                        casted_entity: None,
-                        error: None,
-                    });
-                }
-            };
+        let cast = Self::migrate(instance_obj, migration).and_then(|migrated| {
+            let (casted, mut added, mut removed, reasons) =
+                Self::cast_instance_to_schema(&migrated, &target_schema, "")?;
+            // Keys the migration added or removed
+            added.extend(
+                migrated
+                    .keys()
+                    .filter(|key| !instance_obj.contains_key(*key) && casted.contains_key(*key))
+                    .cloned(),
+            );
+            removed.extend(
+                instance_obj
+                    .keys()
+                    .filter(|key| !migrated.contains_key(*key) && !casted.contains_key(*key))
+                    .cloned(),
+            );
+            Ok((casted, added, removed, reasons))
+        });
+        let (casted, added, removed, incompatibility_reasons) = match cast {
+            Ok(result) => result,
+            Err(e) => {
+                return Ok(GtsEntityCastResult {
+                    from_id: from_instance_id.to_owned(),
+                    to_id: to_schema_id.to_owned(),
+                    old: from_instance_id.to_owned(),
+                    new: to_schema_id.to_owned(),
+                    direction,
+                    added_properties: Vec::new(),
+                    removed_properties: Vec::new(),
+                    changed_properties: Vec::new(),
+                    is_fully_compatible: false,
+                    is_backward_compatible: is_backward,
+                    is_forward_compatible: is_forward,
+                    incompatibility_reasons: vec![e.to_string()],
+                    backward_errors,
+                    forward_errors,
+                    casted_entity: None,
+                    error: None,
+                });
+            }
+        };
 
         // Validate the transformed instance against the FULL target schema
         let is_fully_compatible = true; // Simplified for now
@@ -135,6 +177,19 @@ impl GtsEntityCastResult {
         })
     }
 
+    /// The instance after `migration`, or a copy of it without one.
+    fn migrate(
+        instance: &Map<String, Value>,
+        migration: Option<&GtsMigrationFn>,
+    ) -> Result<Map<String, Value>, SchemaCastError> {
+        let mut migrated = instance.clone();
+        if let Some(migration) = migration {
+            migration(&mut migrated)
+                .map_err(|e| SchemaCastError::CastError(format!("Migration failed: {e}")))?;
+        }
+        Ok(migrated)
+    }
+
     #[must_use]
     pub fn infer_direction(from_id: &str, to_id: &str) -> String {
         if let (Ok(gid_from), Ok(gid_to)) = (GtsID::new(from_id), GtsID::new(to_id))
@@ -205,7 +260,7 @@ impl GtsEntityCastResult {
             .and_then(|r| r.as_array())
             .map(|arr| {
                 arr.iter()
-                    .filter_map(|v| v.as_str().map(ToString::to_string))
+                    .filter_map(|v| v.as_str().map(str::to_owned))
                     .collect()
             })
             .unwrap_or_default();
@@ -561,7 +616,7 @@ impl GtsEntityCastResult {
             .and_then(|r| r.as_array())
             .map(|arr| {
                 arr.iter()
-                    .filter_map(|v| v.as_str().map(ToString::to_string))
+                    .filter_map(|v| v.as_str().map(str::to_owned))
                     .collect()
             })
             .unwrap_or_default();
@@ -571,7 +626,7 @@ impl GtsEntityCastResult {
             .and_then(|r| r.as_array())
             .map(|arr| {
                 arr.iter()
-                    .filter_map(|v| v.as_str().map(ToString::to_string))
+                    .filter_map(|v| v.as_str().map(str::to_owned))
                     .collect()
             })
             .unwrap_or_default();
@@ -619,11 +674,11 @@ impl GtsEntityCastResult {
                 if let (Some(old_e), Some(new_e)) = (old_enum, new_enum) {
                     let old_enum_set: HashSet<String> = old_e
                         .iter()
-                        .filter_map(|v| v.as_str().map(ToString::to_string))
+                        .filter_map(|v| v.as_str().map(str::to_owned))
                         .collect();
                     let new_enum_set: HashSet<String> = new_e
                         .iter()
-                        .filter_map(|v| v.as_str().map(ToString::to_string))
+                        .filter_map(|v| v.as_str().map(str::to_owned))
                         .collect();
 
                     if check_backward {
@@ -680,6 +735,97 @@ impl GtsEntityCastResult {
         (errors.is_empty(), errors)
     }
 }
+
+/// Result of casting an instance across several versions of its schema, one
+/// hop per version, see [`GtsStore::cast_chain`](crate::store::GtsStore::cast_chain).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtsCastChainResult {
+    #[serde(rename = "from")]
+    pub from_id: String,
+    #[serde(rename = "to")]
+    pub to_id: String,
+    /// Schema IDs visited, starting with the instance's schema.
+    pub path: Vec<String>,
+    /// One cast per consecutive pair of `path`; stops at the first failed hop.
+    pub hops: Vec<GtsEntityCastResult>,
+    /// Net properties added over the whole chain.
+    pub added_properties: Vec<String>,
+    /// Net properties removed over the whole chain.
+    pub removed_properties: Vec<String>,
+    pub is_backward_compatible: bool,
+    pub is_forward_compatible: bool,
+    /// The instance after the last hop; `None` if a hop failed.
+    pub casted_entity: Option<Value>,
+}
+
+impl GtsCastChainResult {
+    /// Cast `instance` along `path`, each hop starting from the previous
+    /// hop's casted entity and running the hop's migration, if any.
+    ///
+    /// `schemas` holds the content of each schema in `path`.
+    ///
+    /// # Errors
+    /// Returns `SchemaCastError` if a hop fails.
+    pub fn cast(
+        from_instance_id: &str,
+        path: Vec<String>,
+        instance: &Value,
+        schemas: &[Value],
+        migrations: &GtsMigrations,
+    ) -> Result<Self, SchemaCastError> {
+        let mut hops = Vec::with_capacity(path.len().saturating_sub(1));
+        let mut added: Vec<String> = Vec::new();
+        let mut removed: Vec<String> = Vec::new();
+        let mut current = Some(instance.clone());
+        for (ids, contents) in path.windows(2).zip(schemas.windows(2)) {
+            let Some(entity) = current.take() else {
+                break;
+            };
+            let hop = GtsEntityCastResult::cast_migrated(
+                &ids[0],
+                &ids[1],
+                &entity,
+                &contents[0],
+                &contents[1],
+                migrations.get(&ids[0], &ids[1]),
+            )?;
+            // Net changes: a property added then removed again is neither
+            for prop in &hop.added_properties {
+                if let Some(pos) = removed.iter().position(|p| p == prop) {
+                    removed.remove(pos);
+                } else if !added.contains(prop) {
+                    added.push(prop.clone());
+                }
+            }
+            for prop in &hop.removed_properties {
+                if let Some(pos) = added.iter().position(|p| p == prop) {
+                    added.remove(pos);
+                } else if !removed.contains(prop) {
+                    removed.push(prop.clone());
+                }
+            }
+            current.clone_from(&hop.casted_entity);
+            hops.push(hop);
+        }
+        added.sort();
+        removed.sort();
+
+        let is_backward_compatible = hops.iter().all(|h| h.is_backward_compatible);
+        let is_forward_compatible = hops.iter().all(|h| h.is_forward_compatible);
+        Ok(Self {
+            from_id: from_instance_id.to_owned(),
+            to_id: path.last().cloned().unwrap_or_default(),
+            path,
+            hops,
+            added_properties: added,
+            removed_properties: removed,
+            is_backward_compatible,
+            is_forward_compatible,
+            casted_entity: current,
+        })
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -1206,6 +1352,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cast_migrated_runs_migration_before_defaults() {
+        let from_schema = json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}}
+        });
+        let to_schema = json!({
+            "type": "object",
+            "properties": {
+                "first": {"type": "string"},
+                "last": {"type": "string"},
+                "region": {"type": "string", "default": "us-east"}
+            },
+            "additionalProperties": false
+        });
+        let split_name = |instance: &mut Map<String, Value>| {
+            let name = instance
+                .remove("name")
+                .and_then(|v| v.as_str().map(str::to_owned))
+                .ok_or("missing name")?;
+            let (first, last) = name.split_once(' ').ok_or("expected two names")?;
+            instance.insert("first".to_owned(), json!(first));
+            instance.insert("last".to_owned(), json!(last));
+            Ok(())
+        };
+
+        let cast = GtsEntityCastResult::cast_migrated(
+            "gts.vendor.pkg.ns.type.v1.0",
+            "gts.vendor.pkg.ns.type.v1.1",
+            &json!({"name": "Ada Lovelace"}),
+            &from_schema,
+            &to_schema,
+            Some(&split_name),
+        )
+        .expect("cast ok");
+        assert_eq!(
+            cast.casted_entity,
+            Some(json!({"first": "Ada", "last": "Lovelace", "region": "us-east"}))
+        );
+        assert_eq!(cast.added_properties, ["first", "last", "region"]);
+        assert_eq!(cast.removed_properties, ["name"]);
+
+        // A failing migration fails the cast like an incompatible instance
+        let cast = GtsEntityCastResult::cast_migrated(
+            "gts.vendor.pkg.ns.type.v1.0",
+            "gts.vendor.pkg.ns.type.v1.1",
+            &json!({"name": "Plato"}),
+            &from_schema,
+            &to_schema,
+            Some(&split_name),
+        )
+        .expect("cast ok");
+        assert!(cast.casted_entity.is_none());
+        assert_eq!(
+            cast.incompatibility_reasons,
+            ["Migration failed: expected two names"]
+        );
+    }
+
     #[test]
     fn test_cast_removes_additional_properties_when_disallowed() {
         let from_instance_id = "gts.vendor.pkg.ns.type.v1.0";
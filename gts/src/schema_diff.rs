@@ -0,0 +1,503 @@
+//! Structural diff of two versions of a schema.
+//!
+//! Lists the properties added and removed, the `required` changes and every
+//! keyword that changed on the schema or one of its (nested) properties,
+//! marking constraint changes as tightening or loosening. `allOf` members are
+//! merged first, so a schema split into `allOf` parts diffs like the flat one.
+//!
+//! The change is classified with the compatibility rules of
+//! [`GtsEntityCastResult`]: a new version that is not backward compatible is
+//! a `major` change, any other change of what validates is `minor`, and
+//! annotation-only changes (`title`, `description`, `examples`, ...) are a
+//! `patch`.
+//!
+//! ```
+//! use gts::schema_diff::{ChangeLevel, diff_schemas};
+//! use serde_json::json;
+//!
+//! let old = json!({
+//!     "type": "object",
+//!     "properties": {"name": {"type": "string", "maxLength": 64}}
+//! });
+//! let new = json!({
+//!     "type": "object",
+//!     "properties": {
+//!         "name": {"type": "string", "maxLength": 128},
+//!         "note": {"type": "string"}
+//!     }
+//! });
+//! let diff = diff_schemas(&old, &new);
+//! assert_eq!(diff.added_properties, ["note"]);
+//! assert_eq!(diff.changes[0].to_string(), "name: maxLength loosened 64 -> 128");
+//! assert_eq!(diff.level, ChangeLevel::Minor);
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::schema_cast::GtsEntityCastResult;
+
+/// Semantic-version part a schema change calls for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeLevel {
+    Patch,
+    Minor,
+    Major,
+}
+
+/// How a keyword change affects the values a schema accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstraintEffect {
+    /// Fewer values are accepted
+    Tightened,
+    /// More values are accepted
+    Loosened,
+    /// Some values are accepted that were not and vice versa
+    Changed,
+    /// Documentation only (`title`, `description`, ...)
+    Annotation,
+}
+
+impl ConstraintEffect {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Tightened => "tightened",
+            Self::Loosened => "loosened",
+            Self::Changed => "changed",
+            Self::Annotation => "annotation changed",
+        }
+    }
+}
+
+/// A keyword that differs on the schema (`property` empty) or a property,
+/// addressed like `a.b` (`a[]` for the items of array `a`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintChange {
+    pub property: String,
+    pub keyword: String,
+    pub effect: ConstraintEffect,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+impl fmt::Display for ConstraintChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: &Option<Value>| v.as_ref().map_or_else(|| "-".to_owned(), Value::to_string);
+        if !self.property.is_empty() {
+            write!(f, "{}: ", self.property)?;
+        }
+        write!(
+            f,
+            "{} {} {} -> {}",
+            self.keyword,
+            self.effect.label(),
+            show(&self.old),
+            show(&self.new)
+        )
+    }
+}
+
+/// The differences between two versions of a schema, see [`crate::schema_diff`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    pub added_properties: Vec<String>,
+    pub removed_properties: Vec<String>,
+    /// Properties required by the new version only
+    pub required_added: Vec<String>,
+    /// Properties required by the old version only
+    pub required_removed: Vec<String>,
+    pub changes: Vec<ConstraintChange>,
+    pub is_backward_compatible: bool,
+    pub backward_errors: Vec<String>,
+    pub is_forward_compatible: bool,
+    pub forward_errors: Vec<String>,
+    pub level: ChangeLevel,
+}
+
+impl SchemaDiff {
+    /// Whether the two versions accept the same values and differ, if at
+    /// all, in annotations only.
+    #[must_use]
+    pub fn is_patch(&self) -> bool {
+        self.level == ChangeLevel::Patch
+    }
+}
+
+/// Keywords that document a schema without constraining it
+const ANNOTATIONS: &[&str] = &[
+    "title",
+    "description",
+    "examples",
+    "default",
+    "$comment",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+];
+
+/// Keywords whose larger value accepts fewer values
+const LOWER_BOUNDS: &[&str] = &[
+    "minimum",
+    "exclusiveMinimum",
+    "minLength",
+    "minItems",
+    "minProperties",
+    "minContains",
+];
+
+/// Keywords whose smaller value accepts fewer values
+const UPPER_BOUNDS: &[&str] = &[
+    "maximum",
+    "exclusiveMaximum",
+    "maxLength",
+    "maxItems",
+    "maxProperties",
+    "maxContains",
+];
+
+/// Keywords the diff walks into instead of comparing them as values
+const STRUCTURE: &[&str] = &["properties", "required", "allOf", "items", "$id", "$schema"];
+
+/// Diff `old` against `new`. Both should have their `$ref`s resolved, e.g.
+/// by [`GtsStore::resolve_schema_refs`](crate::GtsStore::resolve_schema_refs).
+#[must_use]
+pub fn diff_schemas(old: &Value, new: &Value) -> SchemaDiff {
+    let (is_backward_compatible, backward_errors) =
+        GtsEntityCastResult::check_backward_compatibility(old, new);
+    let (is_forward_compatible, forward_errors) =
+        GtsEntityCastResult::check_forward_compatibility(old, new);
+    let mut diff = SchemaDiff {
+        added_properties: Vec::new(),
+        removed_properties: Vec::new(),
+        required_added: Vec::new(),
+        required_removed: Vec::new(),
+        changes: Vec::new(),
+        is_backward_compatible,
+        backward_errors,
+        is_forward_compatible,
+        forward_errors,
+        level: ChangeLevel::Patch,
+    };
+    diff_level(&mut diff, "", old, new);
+
+    diff.level = if !diff.is_backward_compatible {
+        ChangeLevel::Major
+    } else if !diff.added_properties.is_empty()
+        || !diff.removed_properties.is_empty()
+        || !diff.required_added.is_empty()
+        || !diff.required_removed.is_empty()
+        || diff
+            .changes
+            .iter()
+            .any(|change| change.effect != ConstraintEffect::Annotation)
+    {
+        ChangeLevel::Minor
+    } else {
+        ChangeLevel::Patch
+    };
+    diff
+}
+
+/// `schema` with its `allOf` members merged in: properties and `required`
+/// are combined, any other keyword is taken from the last member declaring it.
+fn merged(schema: &Value) -> Map<String, Value> {
+    let Some(obj) = schema.as_object() else {
+        return Map::new();
+    };
+    let mut result = Map::new();
+    let mut properties = Map::new();
+    let mut required: Vec<Value> = Vec::new();
+    let members = obj
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(merged)
+        .chain(std::iter::once(obj.clone()));
+    for member in members {
+        for (key, value) in member {
+            match (key.as_str(), value) {
+                ("properties", Value::Object(props)) => properties.extend(props),
+                ("required", Value::Array(names)) => {
+                    for name in names {
+                        if !required.contains(&name) {
+                            required.push(name);
+                        }
+                    }
+                }
+                ("allOf", _) => {}
+                (_, value) => {
+                    result.insert(key, value);
+                }
+            }
+        }
+    }
+    if !properties.is_empty() {
+        result.insert("properties".to_owned(), Value::Object(properties));
+    }
+    if !required.is_empty() {
+        result.insert("required".to_owned(), Value::Array(required));
+    }
+    result
+}
+
+fn diff_level(diff: &mut SchemaDiff, prefix: &str, old: &Value, new: &Value) {
+    let old = merged(old);
+    let new = merged(new);
+    let path = |name: &str| {
+        if prefix.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{prefix}.{name}")
+        }
+    };
+
+    let keywords: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for keyword in keywords {
+        if STRUCTURE.contains(&keyword.as_str()) {
+            continue;
+        }
+        let (old_value, new_value) = (old.get(keyword), new.get(keyword));
+        if old_value == new_value {
+            continue;
+        }
+        diff.changes.push(ConstraintChange {
+            property: prefix.to_owned(),
+            keyword: keyword.clone(),
+            effect: effect(keyword, old_value, new_value),
+            old: old_value.cloned(),
+            new: new_value.cloned(),
+        });
+    }
+
+    let names = |schema: &Map<String, Value>| -> BTreeSet<String> {
+        schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_owned)
+            .collect()
+    };
+    let (old_required, new_required) = (names(&old), names(&new));
+    diff.required_added.extend(
+        new_required
+            .difference(&old_required)
+            .map(|name| path(name)),
+    );
+    diff.required_removed.extend(
+        old_required
+            .difference(&new_required)
+            .map(|name| path(name)),
+    );
+
+    let empty = Map::new();
+    let properties = |schema: &Map<String, Value>| {
+        schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_else(|| empty.clone())
+    };
+    let (old_props, new_props) = (properties(&old), properties(&new));
+    for (name, old_prop) in &old_props {
+        match new_props.get(name) {
+            Some(new_prop) => diff_level(diff, &path(name), old_prop, new_prop),
+            None => diff.removed_properties.push(path(name)),
+        }
+    }
+    for name in new_props
+        .keys()
+        .filter(|name| !old_props.contains_key(*name))
+    {
+        diff.added_properties.push(path(name));
+    }
+
+    match (old.get("items"), new.get("items")) {
+        (Some(old_items @ Value::Object(_)), Some(new_items @ Value::Object(_))) => {
+            diff_level(diff, &format!("{prefix}[]"), old_items, new_items);
+        }
+        (old_items, new_items) if old_items != new_items => diff.changes.push(ConstraintChange {
+            property: prefix.to_owned(),
+            keyword: "items".to_owned(),
+            effect: effect("items", old_items, new_items),
+            old: old_items.cloned(),
+            new: new_items.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+/// The effect of `keyword` changing from `old` to `new` (not both absent).
+fn effect(keyword: &str, old: Option<&Value>, new: Option<&Value>) -> ConstraintEffect {
+    if ANNOTATIONS.contains(&keyword) {
+        return ConstraintEffect::Annotation;
+    }
+    let bound = |larger_is_tighter: bool| match (
+        old.and_then(Value::as_f64),
+        new.and_then(Value::as_f64),
+    ) {
+        (Some(o), Some(n)) if (n > o) == larger_is_tighter => ConstraintEffect::Tightened,
+        (Some(_), _) => ConstraintEffect::Loosened,
+        (None, Some(_)) => ConstraintEffect::Tightened,
+        (None, None) => ConstraintEffect::Changed,
+    };
+    match keyword {
+        _ if LOWER_BOUNDS.contains(&keyword) => bound(true),
+        _ if UPPER_BOUNDS.contains(&keyword) => bound(false),
+        "enum" | "type" => {
+            let values = |v: Option<&Value>| -> Option<Vec<Value>> {
+                v.map(|v| match v {
+                    Value::Array(values) => values.clone(),
+                    other => vec![other.clone()],
+                })
+            };
+            match (values(old), values(new)) {
+                (Some(o), Some(n)) if n.iter().all(|v| o.contains(v)) => {
+                    ConstraintEffect::Tightened
+                }
+                (Some(o), Some(n)) if o.iter().all(|v| n.contains(v)) => ConstraintEffect::Loosened,
+                (None, Some(_)) => ConstraintEffect::Tightened,
+                (Some(_), None) => ConstraintEffect::Loosened,
+                _ => ConstraintEffect::Changed,
+            }
+        }
+        "additionalProperties" | "uniqueItems" => {
+            // How much the setting restricts: `false` forbids any additional
+            // property, a schema restricts them, `true` or none allows them
+            let rank = |v: Option<&Value>| match (keyword, v) {
+                ("additionalProperties", Some(Value::Bool(false)))
+                | ("uniqueItems", Some(Value::Bool(true))) => 2,
+                (_, None | Some(Value::Bool(_))) => 0,
+                _ => 1,
+            };
+            match rank(new).cmp(&rank(old)) {
+                std::cmp::Ordering::Greater => ConstraintEffect::Tightened,
+                std::cmp::Ordering::Less => ConstraintEffect::Loosened,
+                std::cmp::Ordering::Equal => ConstraintEffect::Changed,
+            }
+        }
+        // Any other keyword constrains when present
+        _ => match (old, new) {
+            (None, Some(_)) => ConstraintEffect::Tightened,
+            (Some(_), None) => ConstraintEffect::Loosened,
+            _ => ConstraintEffect::Changed,
+        },
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn base() -> Value {
+        json!({
+            "$id": "gts://gts.x.core.events.order.v1~",
+            "type": "object",
+            "title": "Order",
+            "properties": {
+                "id": {"type": "string"},
+                "status": {"type": "string", "enum": ["new", "paid"]},
+                "amount": {"type": "number", "minimum": 0},
+                "address": {
+                    "type": "object",
+                    "properties": {"city": {"type": "string"}}
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    #[test]
+    fn test_annotation_only_change_is_patch() {
+        let mut new = base();
+        new["title"] = json!("Customer order");
+        new["properties"]["id"]["description"] = json!("Order ID");
+        let diff = diff_schemas(&base(), &new);
+        assert!(diff.is_patch());
+        assert_eq!(diff.changes.len(), 2);
+        assert_eq!(
+            diff.changes[1].to_string(),
+            "id: description annotation changed - -> \"Order ID\""
+        );
+        assert_eq!(diff_schemas(&base(), &base()).changes, []);
+    }
+
+    #[test]
+    fn test_additions_and_loosening_are_minor() {
+        let mut new = base();
+        new["properties"]["amount"]["minimum"] = json!(-10);
+        new["properties"]["address"]["properties"]["zip"] = json!({"type": "string"});
+        let diff = diff_schemas(&base(), &new);
+        assert_eq!(diff.added_properties, ["address.zip"]);
+        assert_eq!(diff.changes[0].property, "amount");
+        assert_eq!(diff.changes[0].effect, ConstraintEffect::Loosened);
+        assert_eq!(diff.level, ChangeLevel::Minor);
+
+        // schema_cast counts new enum values as breaking old readers
+        let mut new = base();
+        new["properties"]["status"]["enum"] = json!(["new", "paid", "refunded"]);
+        let diff = diff_schemas(&base(), &new);
+        assert_eq!(diff.changes[0].effect, ConstraintEffect::Loosened);
+        assert_eq!(diff.level, ChangeLevel::Major);
+    }
+
+    #[test]
+    fn test_breaking_changes_are_major() {
+        let mut new = base();
+        new["required"] = json!(["id", "amount"]);
+        new["properties"]["amount"]["minimum"] = json!(1);
+        new["properties"]["address"]["properties"]["city"]["type"] = json!("integer");
+        new["properties"].as_object_mut().unwrap().remove("status");
+        let diff = diff_schemas(&base(), &new);
+        assert_eq!(diff.required_added, ["amount"]);
+        assert_eq!(diff.removed_properties, ["status"]);
+        let changes: Vec<_> = diff
+            .changes
+            .iter()
+            .map(|c| (c.property.as_str(), c.keyword.as_str(), c.effect))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("address.city", "type", ConstraintEffect::Changed),
+                ("amount", "minimum", ConstraintEffect::Tightened),
+            ]
+        );
+        assert!(!diff.is_backward_compatible);
+        assert_eq!(diff.level, ChangeLevel::Major);
+    }
+
+    #[test]
+    fn test_all_of_members_are_merged() {
+        let split = json!({
+            "allOf": [
+                {"type": "object", "properties": {"id": {"type": "string"}}, "required": ["id"]},
+                {"properties": {"name": {"type": "string"}}, "additionalProperties": false}
+            ]
+        });
+        let flat = json!({
+            "type": "object",
+            "properties": {"id": {"type": "string"}, "name": {"type": "string"}},
+            "required": ["id"],
+            "additionalProperties": false
+        });
+        assert!(diff_schemas(&split, &flat).changes.is_empty());
+
+        let mut open = flat;
+        open.as_object_mut().unwrap().remove("additionalProperties");
+        let diff = diff_schemas(&open, &split);
+        assert_eq!(diff.changes[0].keyword, "additionalProperties");
+        assert_eq!(diff.changes[0].effect, ConstraintEffect::Tightened);
+    }
+}
@@ -0,0 +1,280 @@
+//! Backward compatibility of a whole store against an earlier one.
+//!
+//! [`check_store_evolution`] pairs the schemas of two stores by GTS ID and
+//! checks every evolution with
+//! [`GtsEntityCastResult::check_backward_compatibility`]:
+//!
+//! - a schema whose content changed under the same ID (`changed`),
+//! - a new minor version (`v1.2~`) against the highest earlier minor version
+//!   of the same major (`v1.1~`, `v1~`), looked up in the new store first and
+//!   then in the old one (`minor_bump`),
+//! - a schema of the old store missing from the new one (`removed`), which is
+//!   always incompatible.
+//!
+//! New major versions are free to break compatibility and are not checked.
+//! `gts://` references are resolved within each store before comparing, so a
+//! derived schema is compared with its parents inlined.
+//!
+//! ```
+//! use gts::schema_evolution::{EvolutionKind, check_store_evolution};
+//! use gts::test_support::{schema_json as schema, store_from_json};
+//!
+//! let old = store_from_json(&[schema("gts.x.app.entities.user.v1~", &["id"])]);
+//! let new = store_from_json(&[
+//!     schema("gts.x.app.entities.user.v1~", &["id"]),
+//!     schema("gts.x.app.entities.user.v1.1~", &["id", "name"]),
+//! ]);
+//!
+//! let report = check_store_evolution(&old, &new);
+//! assert!(!report.is_compatible());
+//! let broken: Vec<_> = report.incompatible().collect();
+//! assert_eq!(broken[0].kind, EvolutionKind::MinorBump);
+//! assert_eq!(broken[0].old_id, "gts.x.app.entities.user.v1~");
+//! ```
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::gts::GtsID;
+use crate::schema_cast::GtsEntityCastResult;
+use crate::store::GtsStore;
+
+/// How a schema evolved between the two stores
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvolutionKind {
+    /// Same ID, different content
+    Changed,
+    /// A new minor version of an existing schema
+    MinorBump,
+    /// In the old store only
+    Removed,
+}
+
+/// One checked evolution from `old_id` to `new_id`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaEvolution {
+    pub kind: EvolutionKind,
+    pub old_id: String,
+    /// `None` for a removed schema
+    pub new_id: Option<String>,
+    pub compatible: bool,
+    /// Why instances of `old_id` are not valid against `new_id`
+    pub errors: Vec<String>,
+}
+
+impl std::fmt::Display for SchemaEvolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.new_id {
+            Some(new_id) if *new_id == self.old_id => write!(f, "{new_id}: changed")?,
+            Some(new_id) => write!(f, "{} -> {new_id}", self.old_id)?,
+            None => write!(f, "{}: removed", self.old_id)?,
+        }
+        if !self.errors.is_empty() {
+            write!(f, ": {}", self.errors.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Every evolution found by [`check_store_evolution`], sorted by old ID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EvolutionReport {
+    /// Schemas in the old and the new store
+    pub old_schemas: usize,
+    pub new_schemas: usize,
+    pub evolutions: Vec<SchemaEvolution>,
+}
+
+impl EvolutionReport {
+    /// Whether every evolution is backward compatible
+    #[must_use]
+    pub fn is_compatible(&self) -> bool {
+        self.evolutions.iter().all(|e| e.compatible)
+    }
+
+    /// The evolutions that break readers of the old schemas
+    pub fn incompatible(&self) -> impl Iterator<Item = &SchemaEvolution> {
+        self.evolutions.iter().filter(|e| !e.compatible)
+    }
+}
+
+/// Check that every schema of `new` stays backward compatible with `old`.
+#[must_use]
+pub fn check_store_evolution(old: &GtsStore, new: &GtsStore) -> EvolutionReport {
+    let old_schemas = resolved_schemas(old);
+    let new_schemas = resolved_schemas(new);
+    let mut evolutions = Vec::new();
+
+    for (id, old_schema) in &old_schemas {
+        match new_schemas.get(id) {
+            None => evolutions.push(SchemaEvolution {
+                kind: EvolutionKind::Removed,
+                old_id: id.clone(),
+                new_id: None,
+                compatible: false,
+                errors: Vec::new(),
+            }),
+            Some(new_schema) if new_schema != old_schema => {
+                evolutions.push(evolution(
+                    EvolutionKind::Changed,
+                    id,
+                    old_schema,
+                    id,
+                    new_schema,
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (id, new_schema) in new_schemas
+        .iter()
+        .filter(|(id, _)| !old_schemas.contains_key(*id))
+    {
+        let Some((family, minor)) = minor_family(id) else {
+            continue;
+        };
+        let previous = [&old_schemas, &new_schemas]
+            .into_iter()
+            .flat_map(|schemas| schemas.iter())
+            .filter_map(|(other, schema)| {
+                let (other_family, other_minor) = minor_family(other)?;
+                (other_family == family && other_minor < minor).then_some((
+                    other_minor,
+                    other,
+                    schema,
+                ))
+            })
+            // The last maximum wins, so the new store takes precedence
+            .max_by_key(|(other_minor, _, _)| *other_minor);
+        if let Some((_, old_id, old_schema)) = previous {
+            evolutions.push(evolution(
+                EvolutionKind::MinorBump,
+                old_id,
+                old_schema,
+                id,
+                new_schema,
+            ));
+        }
+    }
+
+    evolutions.sort_by(|a, b| (&a.old_id, &a.new_id).cmp(&(&b.old_id, &b.new_id)));
+    EvolutionReport {
+        old_schemas: old_schemas.len(),
+        new_schemas: new_schemas.len(),
+        evolutions,
+    }
+}
+
+fn evolution(
+    kind: EvolutionKind,
+    old_id: &str,
+    old_schema: &Value,
+    new_id: &str,
+    new_schema: &Value,
+) -> SchemaEvolution {
+    let (compatible, errors) =
+        GtsEntityCastResult::check_backward_compatibility(old_schema, new_schema);
+    SchemaEvolution {
+        kind,
+        old_id: old_id.to_owned(),
+        new_id: Some(new_id.to_owned()),
+        compatible,
+        errors,
+    }
+}
+
+/// Schemas of `store` by ID, with `gts://` references resolved
+fn resolved_schemas(store: &GtsStore) -> BTreeMap<String, Value> {
+    store
+        .items()
+        .filter(|(_, entity)| entity.is_schema)
+        .map(|(id, entity)| (id.clone(), store.resolve_schema_refs(&entity.content)))
+        .collect()
+}
+
+/// The schema ID without its minor version, and the minor version (`v1~` is
+/// minor 0)
+fn minor_family(id: &str) -> Option<(String, u32)> {
+    let gts_id = GtsID::new(id).ok()?;
+    let (last, parents) = gts_id.gts_id_segments.split_last()?;
+    let parents: String = parents.iter().map(|s| s.segment.as_str()).collect();
+    let family = format!(
+        "{parents}{}.{}.{}.{}.v{}",
+        last.vendor, last.package, last.namespace, last.type_name, last.ver_major
+    );
+    Some((family, last.ver_minor.unwrap_or(0)))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_support::{schema_json as schema, store_from_json};
+
+    #[test]
+    fn test_changed_and_removed_schemas() {
+        let old = store_from_json(&[
+            schema("gts.x.app.entities.user.v1~", &["id"]),
+            schema("gts.x.app.entities.order.v1~", &["id"]),
+            schema("gts.x.app.entities.item.v1~", &["id", "name"]),
+        ]);
+        let new = store_from_json(&[
+            schema("gts.x.app.entities.user.v1~", &["id", "name"]),
+            schema("gts.x.app.entities.item.v1~", &["id"]),
+        ]);
+
+        let report = check_store_evolution(&old, &new);
+        assert_eq!((report.old_schemas, report.new_schemas), (3, 2));
+        let summary: Vec<_> = report
+            .evolutions
+            .iter()
+            .map(|e| (e.old_id.as_str(), e.kind, e.compatible))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("gts.x.app.entities.item.v1~", EvolutionKind::Changed, true),
+                (
+                    "gts.x.app.entities.order.v1~",
+                    EvolutionKind::Removed,
+                    false
+                ),
+                ("gts.x.app.entities.user.v1~", EvolutionKind::Changed, false),
+            ]
+        );
+        let user = report.incompatible().last().unwrap();
+        assert!(
+            user.to_string()
+                .starts_with("gts.x.app.entities.user.v1~: changed: ")
+        );
+    }
+
+    #[test]
+    fn test_minor_bump_pairs_with_highest_earlier_minor() {
+        let old = store_from_json(&[
+            schema("gts.x.app.entities.user.v1~", &["id", "name"]),
+            schema("gts.x.app.entities.user.v1.1~", &["id"]),
+        ]);
+        let new = store_from_json(&[
+            schema("gts.x.app.entities.user.v1~", &["id", "name"]),
+            schema("gts.x.app.entities.user.v1.1~", &["id"]),
+            schema("gts.x.app.entities.user.v1.3~", &["id"]),
+            // Major versions may break
+            schema("gts.x.app.entities.user.v2~", &["id", "name"]),
+        ]);
+
+        let report = check_store_evolution(&old, &new);
+        assert!(report.is_compatible(), "{report:?}");
+        assert_eq!(report.evolutions.len(), 1);
+        let bump = &report.evolutions[0];
+        assert_eq!(bump.kind, EvolutionKind::MinorBump);
+        assert_eq!(bump.old_id, "gts.x.app.entities.user.v1.1~");
+        assert_eq!(
+            bump.new_id.as_deref(),
+            Some("gts.x.app.entities.user.v1.3~")
+        );
+    }
+}
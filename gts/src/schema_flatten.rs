@@ -0,0 +1,450 @@
+//! Flattening a schema inheritance chain into a single schema.
+//!
+//! Every level of `gts.A~B~C~` contributes the properties, `required`
+//! entries and `additionalProperties` it declares itself; its `$ref`s to
+//! earlier levels are skipped since those levels are merged before it. An
+//! object property redeclared with nested `properties` (such as a `payload`
+//! a child type fills in) extends the earlier declaration. When two
+//! contributions cannot both hold, the base declaration is kept and a
+//! [`ChainConflict`] is recorded instead of letting the later one win.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::gts::GTS_URI_PREFIX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainConflictKind {
+    /// A property is redeclared with a type the earlier declaration excludes
+    PropertyType,
+    /// A level reopens or extends a schema an earlier level closed with
+    /// `additionalProperties: false`
+    AdditionalProperties,
+    /// A level lists the same `required` entry more than once
+    DuplicateRequired,
+}
+
+/// A conflict found while merging a chain level into its bases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainConflict {
+    pub kind: ChainConflictKind,
+    /// The chain level whose contribution conflicts
+    pub schema_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ChainConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.schema_id, self.message)
+    }
+}
+
+/// The merged schema of a chain and the conflicts met while merging it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlattenedSchema {
+    pub schema: Value,
+    pub conflicts: Vec<ChainConflict>,
+}
+
+fn is_ancestor_ref(map: &Map<String, Value>, ancestors: &[String]) -> bool {
+    map.get("$ref")
+        .and_then(Value::as_str)
+        .map(|r| r.strip_prefix(GTS_URI_PREFIX).unwrap_or(r))
+        .is_some_and(|r| ancestors.iter().any(|a| a == r))
+}
+
+/// Split the raw content of one chain level into the schema fragments it
+/// declares itself: the object without `allOf`, then each `allOf` item.
+/// `$ref`s to `ancestors` are dropped, any other `$ref` goes through `resolve`.
+pub(crate) fn level_fragments(
+    content: &Value,
+    ancestors: &[String],
+    resolve: &dyn Fn(&Value) -> Result<Value, String>,
+    out: &mut Vec<Value>,
+) -> Result<(), String> {
+    let Value::Object(map) = content else {
+        return Ok(());
+    };
+    let mut own = map.clone();
+    own.remove("allOf");
+    if is_ancestor_ref(map, ancestors) {
+        own.remove("$ref");
+    }
+    if !own.is_empty() {
+        out.push(resolve(&Value::Object(own))?);
+    }
+    if let Some(Value::Array(items)) = map.get("allOf") {
+        for item in items {
+            level_fragments(item, ancestors, resolve, out)?;
+        }
+    }
+    Ok(())
+}
+
+fn type_set(schema: &Value) -> Option<Vec<&str>> {
+    match schema.get("type")? {
+        Value::String(t) => Some(vec![t.as_str()]),
+        Value::Array(types) => Some(types.iter().filter_map(Value::as_str).collect()),
+        _ => None,
+    }
+}
+
+/// Whether every type `derived` allows is allowed by `base` as well.
+fn types_compatible(base: &[&str], derived: &[&str]) -> bool {
+    derived
+        .iter()
+        .all(|t| base.contains(t) || (*t == "integer" && base.contains(&"number")))
+}
+
+/// The redeclaration `derived` of a property declared as `base`. Object
+/// schemas that both declare `properties` are extended: the nested
+/// properties (recursively) and `required` entries of both are kept. Any
+/// other redeclaration replaces the base one.
+fn extend_object(base: &Value, derived: &Value) -> Value {
+    let (Value::Object(base), Value::Object(derived)) = (base, derived) else {
+        return derived.clone();
+    };
+    let (Some(Value::Object(base_props)), Some(Value::Object(derived_props))) =
+        (base.get("properties"), derived.get("properties"))
+    else {
+        return Value::Object(derived.clone());
+    };
+    let mut properties = base_props.clone();
+    for (name, def) in derived_props {
+        let def = match base_props.get(name) {
+            Some(previous) => extend_object(previous, def),
+            None => def.clone(),
+        };
+        properties.insert(name.clone(), def);
+    }
+    let mut required: Vec<Value> = base
+        .get("required")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for name in derived
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        if !required.contains(name) {
+            required.push(name.clone());
+        }
+    }
+
+    let mut extended = base.clone();
+    extended.extend(derived.clone());
+    extended.insert("properties".to_owned(), Value::Object(properties));
+    if !required.is_empty() {
+        extended.insert("required".to_owned(), Value::Array(required));
+    }
+    Value::Object(extended)
+}
+
+#[derive(Default)]
+struct Merger {
+    keywords: Map<String, Value>,
+    properties: Map<String, Value>,
+    /// Level that declared each property
+    owners: HashMap<String, String>,
+    required: Vec<String>,
+    /// `additionalProperties` and the level that set it
+    additional: Option<(Value, String)>,
+    conflicts: Vec<ChainConflict>,
+}
+
+impl Merger {
+    fn conflict(
+        &mut self,
+        kind: ChainConflictKind,
+        schema_id: &str,
+        property: Option<&str>,
+        message: String,
+    ) {
+        self.conflicts.push(ChainConflict {
+            kind,
+            schema_id: schema_id.to_owned(),
+            property: property.map(str::to_owned),
+            message,
+        });
+    }
+
+    fn level(&mut self, schema_id: &str, fragments: &[Value]) {
+        // Only a `false` from an earlier level forbids new properties here
+        let closed_by = self
+            .additional
+            .as_ref()
+            .filter(|(value, _)| *value == Value::Bool(false))
+            .map(|(_, owner)| owner.clone());
+        let mut level_required: Vec<String> = Vec::new();
+
+        for fragment in fragments {
+            let Value::Object(map) = fragment else {
+                continue;
+            };
+            for (key, value) in map {
+                match key.as_str() {
+                    "properties" => {
+                        if let Value::Object(props) = value {
+                            for (name, def) in props {
+                                self.property(schema_id, name, def, closed_by.as_deref());
+                            }
+                        }
+                    }
+                    "required" => {
+                        for name in value.as_array().into_iter().flatten() {
+                            let Some(name) = name.as_str() else {
+                                continue;
+                            };
+                            if level_required.iter().any(|r| r == name) {
+                                self.conflict(
+                                    ChainConflictKind::DuplicateRequired,
+                                    schema_id,
+                                    Some(name),
+                                    format!("'{name}' is listed in required more than once"),
+                                );
+                            } else {
+                                level_required.push(name.to_owned());
+                            }
+                        }
+                    }
+                    "additionalProperties" => self.additional_properties(schema_id, value),
+                    "$id" | "$ref" | "allOf" => {}
+                    _ => {
+                        self.keywords.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+
+        for name in level_required {
+            if !self.required.contains(&name) {
+                self.required.push(name);
+            }
+        }
+    }
+
+    fn property(&mut self, schema_id: &str, name: &str, def: &Value, closed_by: Option<&str>) {
+        if let Some(previous) = self.properties.get(name) {
+            if let (Some(base), Some(derived)) = (type_set(previous), type_set(def))
+                && !types_compatible(&base, &derived)
+            {
+                let owner = self.owners.get(name).cloned().unwrap_or_default();
+                let message = format!(
+                    "property '{name}' is declared as {} but '{owner}' declares it as {}",
+                    derived.join("|"),
+                    base.join("|")
+                );
+                self.conflict(
+                    ChainConflictKind::PropertyType,
+                    schema_id,
+                    Some(name),
+                    message,
+                );
+                return;
+            }
+        } else if let Some(owner) = closed_by {
+            self.conflict(
+                ChainConflictKind::AdditionalProperties,
+                schema_id,
+                Some(name),
+                format!("adds property '{name}' but '{owner}' sets additionalProperties to false"),
+            );
+            return;
+        }
+        let def = match self.properties.get(name) {
+            Some(previous) => extend_object(previous, def),
+            None => def.clone(),
+        };
+        self.properties.insert(name.to_owned(), def);
+        self.owners.insert(name.to_owned(), schema_id.to_owned());
+    }
+
+    fn additional_properties(&mut self, schema_id: &str, value: &Value) {
+        if let Some((previous, owner)) = &self.additional
+            && *previous == Value::Bool(false)
+            && *value != Value::Bool(false)
+        {
+            let message =
+                format!("sets additionalProperties to {value} but '{owner}' sets it to false");
+            self.conflict(
+                ChainConflictKind::AdditionalProperties,
+                schema_id,
+                None,
+                message,
+            );
+            return;
+        }
+        self.additional = Some((value.clone(), schema_id.to_owned()));
+    }
+
+    fn finish(self, schema_id: &str) -> FlattenedSchema {
+        let mut schema = Map::new();
+        schema.insert(
+            "$id".to_owned(),
+            Value::String(format!("{GTS_URI_PREFIX}{schema_id}")),
+        );
+        schema.extend(self.keywords);
+        if !self.properties.is_empty() {
+            schema.insert("properties".to_owned(), Value::Object(self.properties));
+        }
+        if !self.required.is_empty() {
+            schema.insert(
+                "required".to_owned(),
+                Value::Array(self.required.into_iter().map(Value::String).collect()),
+            );
+        }
+        if let Some((additional, _)) = self.additional {
+            schema.insert("additionalProperties".to_owned(), additional);
+        }
+        FlattenedSchema {
+            schema: Value::Object(schema),
+            conflicts: self.conflicts,
+        }
+    }
+}
+
+/// Merge chain levels, base first, each given as its own fragments
+/// (see [`level_fragments`]). Keywords other than `properties`, `required`
+/// and `additionalProperties` are taken from the last level declaring them.
+pub(crate) fn merge_levels(levels: &[(String, Vec<Value>)]) -> FlattenedSchema {
+    let mut merger = Merger::default();
+    for (schema_id, fragments) in levels {
+        merger.level(schema_id, fragments);
+    }
+    let leaf = levels.last().map(|(id, _)| id.as_str()).unwrap_or_default();
+    merger.finish(leaf)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn levels(items: &[(&str, Value)]) -> Vec<(String, Vec<Value>)> {
+        items
+            .iter()
+            .map(|(id, fragment)| ((*id).to_owned(), vec![fragment.clone()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_without_conflicts() {
+        let flat = merge_levels(&levels(&[
+            (
+                "gts.a~",
+                json!({"type": "object", "properties": {"n": {"type": "number"}}, "required": ["n"]}),
+            ),
+            (
+                "gts.a~b~",
+                json!({"properties": {"n": {"type": "integer"}, "s": {"type": "string"}}, "required": ["n", "s"], "additionalProperties": false}),
+            ),
+        ]));
+        assert!(flat.conflicts.is_empty(), "{:?}", flat.conflicts);
+        assert_eq!(
+            flat.schema,
+            json!({
+                "$id": "gts://gts.a~b~",
+                "type": "object",
+                "properties": {"n": {"type": "integer"}, "s": {"type": "string"}},
+                "required": ["n", "s"],
+                "additionalProperties": false
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_extends_nested_objects() {
+        let flat = merge_levels(&levels(&[
+            (
+                "gts.a~",
+                json!({"properties": {"payload": {"type": "object", "properties": {"kind": {"type": "string"}}, "required": ["kind"]}}}),
+            ),
+            (
+                "gts.a~b~",
+                json!({"properties": {"payload": {"type": "object", "properties": {"total": {"type": "number"}}, "required": ["total"], "additionalProperties": false}}}),
+            ),
+        ]));
+        assert!(flat.conflicts.is_empty(), "{:?}", flat.conflicts);
+        assert_eq!(
+            flat.schema["properties"]["payload"],
+            json!({
+                "type": "object",
+                "properties": {"kind": {"type": "string"}, "total": {"type": "number"}},
+                "required": ["kind", "total"],
+                "additionalProperties": false
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_reports_conflicts() {
+        let flat = merge_levels(&levels(&[
+            (
+                "gts.a~",
+                json!({"properties": {"n": {"type": "string"}}, "additionalProperties": false}),
+            ),
+            (
+                "gts.a~b~",
+                json!({"properties": {"n": {"type": ["string", "null"]}, "extra": {}}, "required": ["n", "n"], "additionalProperties": true}),
+            ),
+        ]));
+        let mut kinds: Vec<(ChainConflictKind, Option<&str>)> = flat
+            .conflicts
+            .iter()
+            .map(|c| (c.kind, c.property.as_deref()))
+            .collect();
+        kinds.sort_unstable();
+        assert_eq!(
+            kinds,
+            vec![
+                (ChainConflictKind::PropertyType, Some("n")),
+                (ChainConflictKind::AdditionalProperties, None),
+                (ChainConflictKind::AdditionalProperties, Some("extra")),
+                (ChainConflictKind::DuplicateRequired, Some("n")),
+            ]
+        );
+        assert!(flat.conflicts.iter().all(|c| c.schema_id == "gts.a~b~"));
+        // The base declarations are kept
+        assert_eq!(flat.schema["properties"], json!({"n": {"type": "string"}}));
+        assert_eq!(flat.schema["additionalProperties"], json!(false));
+        assert_eq!(flat.schema["required"], json!(["n"]));
+    }
+
+    #[test]
+    fn test_level_fragments_skips_ancestor_refs() {
+        let content = json!({
+            "$id": "gts://gts.a~b~",
+            "allOf": [
+                {"$ref": "gts://gts.a~"},
+                {"$ref": "gts://gts.mixin~"},
+                {"properties": {"s": {"type": "string"}}}
+            ]
+        });
+        let resolve = |v: &Value| -> Result<Value, String> {
+            if v.get("$ref").is_some() {
+                Ok(json!({"properties": {"m": {"type": "boolean"}}}))
+            } else {
+                Ok(v.clone())
+            }
+        };
+        let mut out = Vec::new();
+        level_fragments(&content, &["gts.a~".to_owned()], &resolve, &mut out).unwrap();
+        assert_eq!(
+            out,
+            vec![
+                json!({"$id": "gts://gts.a~b~"}),
+                json!({"properties": {"m": {"type": "boolean"}}}),
+                json!({"properties": {"s": {"type": "string"}}}),
+            ]
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Ownership and changelog metadata of schemas.
+//!
+//! Schemas may declare who owns them and what changed between versions:
+//!
+//! ```json
+//! {
+//!   "$id": "gts://gts.x.core.events.type.v3~",
+//!   "x-gts-owner": "platform-events@example.com",
+//!   "x-gts-changelog": [
+//!     {"version": "v3", "date": "2026-03-01", "changes": ["Drop `legacy_id`"]},
+//!     {"version": "v2", "changes": ["Add `tenant_id`"]}
+//!   ]
+//! }
+//! ```
+//!
+//! Both keys are optional and apply to the declaring schema only; a derived
+//! schema does not inherit its parent's owner.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::entities::GtsEntity;
+use crate::gts::{GtsID, GtsWildcard};
+use crate::store::GtsStore;
+
+/// Schema keyword naming the owner (team, person or contact) of a schema.
+pub const X_GTS_OWNER: &str = "x-gts-owner";
+/// Schema keyword listing the changes of each version, newest first.
+pub const X_GTS_CHANGELOG: &str = "x-gts-changelog";
+
+#[derive(Debug, Error)]
+pub enum SchemaMetadataError {
+    #[error("Invalid {key} in schema '{id}': {cause}")]
+    Invalid {
+        id: String,
+        key: &'static str,
+        cause: String,
+    },
+    #[error("Invalid owner namespace pattern '{pattern}': {cause}")]
+    InvalidPattern { pattern: String, cause: String },
+}
+
+/// One `x-gts-changelog` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GtsChangelogEntry {
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub changes: Vec<String>,
+}
+
+/// The `x-gts-owner` and `x-gts-changelog` of a schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GtsSchemaMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changelog: Vec<GtsChangelogEntry>,
+}
+
+impl GtsSchemaMetadata {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.owner.is_none() && self.changelog.is_empty()
+    }
+}
+
+/// A schema whose metadata keywords are malformed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GtsMetadataIssue {
+    pub id: String,
+    pub error: String,
+}
+
+impl GtsEntity {
+    /// The ownership and changelog metadata this schema declares; empty for
+    /// instances and schemas without the keywords.
+    ///
+    /// # Errors
+    /// Returns `SchemaMetadataError::Invalid` if `x-gts-owner` is not a
+    /// non-empty string or `x-gts-changelog` not a list of entries.
+    pub fn schema_metadata(&self) -> Result<GtsSchemaMetadata, SchemaMetadataError> {
+        let mut metadata = GtsSchemaMetadata::default();
+        if !self.is_schema {
+            return Ok(metadata);
+        }
+        let id = || self.effective_id().unwrap_or_default();
+        if let Some(owner) = self.content.get(X_GTS_OWNER) {
+            match owner.as_str().map(str::trim) {
+                Some(owner) if !owner.is_empty() => metadata.owner = Some(owner.to_owned()),
+                _ => {
+                    return Err(SchemaMetadataError::Invalid {
+                        id: id(),
+                        key: X_GTS_OWNER,
+                        cause: format!("expected a non-empty string, got {owner}"),
+                    });
+                }
+            }
+        }
+        if let Some(changelog) = self.content.get(X_GTS_CHANGELOG) {
+            metadata.changelog = Vec::<GtsChangelogEntry>::deserialize(changelog).map_err(|e| {
+                SchemaMetadataError::Invalid {
+                    id: id(),
+                    key: X_GTS_CHANGELOG,
+                    cause: e.to_string(),
+                }
+            })?;
+        }
+        Ok(metadata)
+    }
+}
+
+impl GtsStore {
+    /// Schemas matching any of the `namespaces` (GTS wildcard patterns) that
+    /// declare no `x-gts-owner`, sorted, and the schemas with malformed metadata.
+    ///
+    /// # Errors
+    /// Returns `SchemaMetadataError::InvalidPattern` if a pattern is invalid.
+    pub fn check_owners(
+        &self,
+        namespaces: &[String],
+    ) -> Result<(Vec<String>, Vec<GtsMetadataIssue>), SchemaMetadataError> {
+        let patterns = namespaces
+            .iter()
+            .map(|p| {
+                GtsWildcard::new(p).map_err(|e| SchemaMetadataError::InvalidPattern {
+                    pattern: p.clone(),
+                    cause: e.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut missing = Vec::new();
+        let mut issues = Vec::new();
+        for (id, entity) in self.items().filter(|(_, e)| e.is_schema) {
+            match entity.schema_metadata() {
+                Ok(metadata) if metadata.owner.is_none() => {
+                    let required = GtsID::new(id)
+                        .is_ok_and(|gts_id| patterns.iter().any(|p| gts_id.wildcard_match(p)));
+                    if required {
+                        missing.push(id.clone());
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => issues.push(GtsMetadataIssue {
+                    id: id.clone(),
+                    error: e.to_string(),
+                }),
+            }
+        }
+        missing.sort();
+        issues.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok((missing, issues))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn store() -> GtsStore {
+        let mut store = GtsStore::new(None);
+        for (id, extra) in [
+            (
+                "gts.x.core.events.type.v1~",
+                json!({
+                    "x-gts-owner": "platform-events",
+                    "x-gts-changelog": [
+                        {"version": "v1.1", "date": "2026-03-01", "changes": ["Add tenant_id"]},
+                        {"version": "v1"}
+                    ]
+                }),
+            ),
+            ("gts.x.core.events.topic.v1~", json!({})),
+            ("gts.x.app.events.audit.v1~", json!({})),
+            ("gts.x.core.events.queue.v1~", json!({"x-gts-owner": 42})),
+        ] {
+            let mut schema = json!({
+                "$id": format!("gts://{id}"),
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object"
+            });
+            schema
+                .as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            store.register_schema(id, &schema).unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn test_schema_metadata() {
+        let mut store = store();
+        let metadata = store
+            .get("gts.x.core.events.type.v1~")
+            .unwrap()
+            .schema_metadata()
+            .unwrap();
+        assert_eq!(metadata.owner.as_deref(), Some("platform-events"));
+        assert_eq!(metadata.changelog.len(), 2);
+        assert_eq!(metadata.changelog[0].date.as_deref(), Some("2026-03-01"));
+        assert_eq!(metadata.changelog[0].changes, ["Add tenant_id"]);
+
+        let topic = store.get("gts.x.core.events.topic.v1~").unwrap();
+        assert!(topic.schema_metadata().unwrap().is_empty());
+        let queue = store.get("gts.x.core.events.queue.v1~").unwrap();
+        assert!(matches!(
+            queue.schema_metadata(),
+            Err(SchemaMetadataError::Invalid {
+                key: X_GTS_OWNER,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_check_owners() {
+        let store = store();
+        let (missing, issues) = store.check_owners(&["gts.x.core.*".to_owned()]).unwrap();
+        assert_eq!(missing, ["gts.x.core.events.topic.v1~"]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].id, "gts.x.core.events.queue.v1~");
+
+        let (missing, _) = store.check_owners(&[]).unwrap();
+        assert!(missing.is_empty());
+        assert!(store.check_owners(&["x.*".to_owned()]).is_err());
+    }
+}
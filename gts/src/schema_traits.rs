@@ -117,6 +117,19 @@ pub fn validate_effective_traits(
     validate_traits_against_schema(&effective_trait_schema, &effective_traits, check_unresolved)
 }
 
+/// Returns the effective traits object: `merged_traits` with defaults from the
+/// composed trait schema applied to missing properties.
+pub(crate) fn effective_traits(
+    resolved_trait_schemas: &[Value],
+    merged_traits: &Value,
+) -> serde_json::Map<String, Value> {
+    let effective_trait_schema = build_effective_trait_schema(resolved_trait_schemas);
+    match apply_defaults(&effective_trait_schema, merged_traits) {
+        Value::Object(map) => map,
+        _ => serde_json::Map::new(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Collection helpers (pub(crate) so the store can call them)
 // ---------------------------------------------------------------------------
@@ -0,0 +1,408 @@
+//! Persistent storage backends for [`GtsStore`](crate::GtsStore).
+//!
+//! A store backed by a [`GtsStorage`] writes every registered entity through
+//! to it and fetches entities it does not hold yet from it, so a process can
+//! start from a previously persisted store instead of re-reading thousands of
+//! schema files:
+//!
+//! ```ignore
+//! // First run: load the files once and persist them
+//! let mut store = GtsStore::new(Some(Box::new(GtsFileReader::new(&paths, None))));
+//! store.set_storage(Box::new(GtsSledStorage::open("gts.sled")?));
+//! store.persist()?;
+//!
+//! // Later runs: entities are loaded lazily, by ID or by ID prefix
+//! let mut store = GtsStore::with_storage(Box::new(GtsSledStorage::open("gts.sled")?));
+//! store.load_prefix("gts.x.core.events.")?;
+//! ```
+//!
+//! Backends store opaque bytes by entity ID; [`GtsMemoryStorage`] keeps them
+//! in memory and, with the `sled` feature, [`GtsSledStorage`] in an embedded
+//! sled database. Any other key-value store (a database table, an object
+//! store) plugs in by implementing [`GtsStorage`]; [`GtsStorageReader`] also
+//! makes such a backend the reader a store is populated from.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::entities::GtsEntity;
+use crate::gts::GtsID;
+use crate::store::{GtsLoadError, GtsReader};
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("Storage backend error: {0}")]
+    Backend(String),
+    #[error("Stored entity '{id}' is corrupt: {cause}")]
+    Corrupt { id: String, cause: String },
+}
+
+/// An entity ID and its encoded entity.
+pub type GtsStoredEntry = (String, Vec<u8>);
+
+type Entries = BTreeMap<String, Vec<u8>>;
+
+/// A key-value backend persisting encoded entities by entity ID.
+pub trait GtsStorage: Send {
+    /// The entity stored under `id`, if any.
+    ///
+    /// # Errors
+    /// Returns `StorageError::Backend` if the backend fails.
+    fn get(&self, id: &str) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// All entities whose ID starts with `prefix`, sorted by ID.
+    ///
+    /// # Errors
+    /// Returns `StorageError::Backend` if the backend fails.
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<GtsStoredEntry>, StorageError>;
+
+    /// Store `entity` under `id`, replacing the previous one.
+    ///
+    /// # Errors
+    /// Returns `StorageError::Backend` if the backend fails.
+    fn put(&mut self, id: &str, entity: Vec<u8>) -> Result<(), StorageError>;
+
+    /// Remove the entity stored under `id`, if any.
+    ///
+    /// # Errors
+    /// Returns `StorageError::Backend` if the backend fails.
+    fn remove(&mut self, id: &str) -> Result<(), StorageError>;
+
+    /// Make the writes so far durable.
+    ///
+    /// # Errors
+    /// Returns `StorageError::Backend` if the backend fails.
+    fn flush(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
+
+/// The persisted form of an entity: its content and the IDs extracted from
+/// it with the configuration it was loaded with.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredEntity {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gts_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    instance_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schema_id: Option<String>,
+    content: Value,
+}
+
+pub(crate) fn encode_entity(entity: &GtsEntity) -> Vec<u8> {
+    let stored = StoredEntity {
+        gts_id: entity.gts_id.as_ref().map(|id| id.id.clone()),
+        instance_id: entity.instance_id.clone(),
+        schema_id: entity.schema_id.clone(),
+        content: entity.content.clone(),
+    };
+    serde_json::to_vec(&stored).unwrap_or_default()
+}
+
+pub(crate) fn decode_entity(id: &str, bytes: &[u8]) -> Result<GtsEntity, StorageError> {
+    let corrupt = |cause: String| StorageError::Corrupt {
+        id: id.to_owned(),
+        cause,
+    };
+    let stored: StoredEntity = serde_json::from_slice(bytes).map_err(|e| corrupt(e.to_string()))?;
+    let gts_id = stored
+        .gts_id
+        .as_deref()
+        .map(GtsID::new)
+        .transpose()
+        .map_err(|e| corrupt(e.to_string()))?;
+    let mut entity = GtsEntity::new(
+        None,
+        None,
+        &stored.content,
+        None,
+        gts_id,
+        false,
+        String::new(),
+        None,
+        stored.schema_id,
+    );
+    if let Some(instance_id) = stored.instance_id {
+        entity.label.clone_from(&instance_id);
+        entity.instance_id = Some(instance_id);
+    }
+    Ok(entity)
+}
+
+/// In-memory [`GtsStorage`], e.g. for tests; clones share their entries.
+#[derive(Debug, Clone, Default)]
+pub struct GtsMemoryStorage {
+    entries: Arc<Mutex<Entries>>,
+}
+
+impl GtsMemoryStorage {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(&self) -> Result<MutexGuard<'_, Entries>, StorageError> {
+        self.entries
+            .lock()
+            .map_err(|e| StorageError::Backend(e.to_string()))
+    }
+}
+
+impl GtsStorage for GtsMemoryStorage {
+    fn get(&self, id: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.entries()?.get(id).cloned())
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<GtsStoredEntry>, StorageError> {
+        Ok(self
+            .entries()?
+            .range(prefix.to_owned()..)
+            .take_while(|(id, _)| id.starts_with(prefix))
+            .map(|(id, bytes)| (id.clone(), bytes.clone()))
+            .collect())
+    }
+
+    fn put(&mut self, id: &str, entity: Vec<u8>) -> Result<(), StorageError> {
+        self.entries()?.insert(id.to_owned(), entity);
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &str) -> Result<(), StorageError> {
+        self.entries()?.remove(id);
+        Ok(())
+    }
+}
+
+/// [`GtsReader`] over a [`GtsStorage`]: the store is populated with every
+/// entity of the backend, and entities added to it later are fetched by ID.
+pub struct GtsStorageReader {
+    storage: Box<dyn GtsStorage>,
+    load_errors: Vec<GtsLoadError>,
+}
+
+impl GtsStorageReader {
+    #[must_use]
+    pub fn new(storage: Box<dyn GtsStorage>) -> Self {
+        Self {
+            storage,
+            load_errors: Vec::new(),
+        }
+    }
+}
+
+impl GtsReader for GtsStorageReader {
+    fn iter(&mut self) -> Box<dyn Iterator<Item = GtsEntity> + '_> {
+        let mut load_errors = Vec::new();
+        let entries = self.storage.scan_prefix("").unwrap_or_else(|e| {
+            load_errors.push(GtsLoadError {
+                path: String::new(),
+                message: e.to_string(),
+            });
+            Vec::new()
+        });
+        let entities = entries
+            .into_iter()
+            .filter_map(|(id, bytes)| match decode_entity(&id, &bytes) {
+                Ok(entity) => Some(entity),
+                Err(e) => {
+                    load_errors.push(GtsLoadError {
+                        path: id,
+                        message: e.to_string(),
+                    });
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        self.load_errors = load_errors;
+        Box::new(entities.into_iter())
+    }
+
+    fn read_by_id(&self, entity_id: &str) -> Option<GtsEntity> {
+        match self
+            .storage
+            .get(entity_id)
+            .and_then(|bytes| bytes.map(|b| decode_entity(entity_id, &b)).transpose())
+        {
+            Ok(entity) => entity,
+            Err(e) => {
+                tracing::warn!("Cannot load '{entity_id}' from storage: {e}");
+                None
+            }
+        }
+    }
+
+    fn reset(&mut self) {}
+
+    fn load_errors(&self) -> Vec<GtsLoadError> {
+        self.load_errors.clone()
+    }
+}
+
+/// [`GtsStorage`] in an embedded sled database.
+#[cfg(feature = "sled")]
+#[derive(Debug, Clone)]
+pub struct GtsSledStorage {
+    db: sled::Db,
+}
+
+#[cfg(feature = "sled")]
+impl GtsSledStorage {
+    /// Open (or create) the database at `path`.
+    ///
+    /// # Errors
+    /// Returns `StorageError::Backend` if the database cannot be opened.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, StorageError> {
+        Ok(sled::open(path)?.into())
+    }
+}
+
+#[cfg(feature = "sled")]
+impl From<sled::Db> for GtsSledStorage {
+    fn from(db: sled::Db) -> Self {
+        Self { db }
+    }
+}
+
+#[cfg(feature = "sled")]
+impl From<sled::Error> for StorageError {
+    fn from(e: sled::Error) -> Self {
+        Self::Backend(e.to_string())
+    }
+}
+
+#[cfg(feature = "sled")]
+impl GtsStorage for GtsSledStorage {
+    fn get(&self, id: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.db.get(id)?.map(|bytes| bytes.to_vec()))
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<GtsStoredEntry>, StorageError> {
+        self.db
+            .scan_prefix(prefix)
+            .map(|entry| {
+                let (id, bytes) = entry?;
+                Ok((String::from_utf8_lossy(&id).into_owned(), bytes.to_vec()))
+            })
+            .collect()
+    }
+
+    fn put(&mut self, id: &str, entity: Vec<u8>) -> Result<(), StorageError> {
+        self.db.insert(id, entity)?;
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &str) -> Result<(), StorageError> {
+        self.db.remove(id)?;
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), StorageError> {
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_memory_storage_scans_by_prefix() {
+        let mut storage = GtsMemoryStorage::new();
+        for id in [
+            "gts.x.a.b.c.v1~",
+            "gts.x.core.a.b.v1~",
+            "gts.x.core.c.d.v1~",
+        ] {
+            storage.put(id, id.as_bytes().to_vec()).unwrap();
+        }
+        let ids: Vec<String> = storage
+            .scan_prefix("gts.x.core.")
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids, ["gts.x.core.a.b.v1~", "gts.x.core.c.d.v1~"]);
+        storage.remove("gts.x.core.a.b.v1~").unwrap();
+        assert_eq!(storage.scan_prefix("gts.x.core.").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_decode_restores_entity_ids() {
+        let id = "gts.x.core.events.type.v1~x.app._.orders.v1";
+        let content = json!({"id": id, "type": "gts.x.core.events.type.v1~"});
+        let mut entity = GtsEntity::new(
+            None,
+            None,
+            &content,
+            Some(&crate::GtsConfig::default()),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        );
+        entity.instance_id = Some(id.to_owned());
+        let decoded = decode_entity(id, &encode_entity(&entity)).unwrap();
+        assert_eq!(decoded.effective_id().as_deref(), Some(id));
+        assert_eq!(decoded.schema_id, entity.schema_id);
+        assert_eq!(decoded.content, content);
+        assert!(matches!(
+            decode_entity(id, b"{"),
+            Err(StorageError::Corrupt { .. })
+        ));
+    }
+
+    #[test]
+    fn test_storage_reader_populates_store() {
+        let id = "gts.x.core.events.type.v1~";
+        let schema = json!({"$id": format!("gts://{id}"), "$schema": "http://json-schema.org/draft-07/schema#"});
+        let mut storage = GtsMemoryStorage::new();
+        let entity = crate::GtsEntity::new(
+            None,
+            None,
+            &schema,
+            Some(&crate::GtsConfig::default()),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        );
+        storage.put(id, encode_entity(&entity)).unwrap();
+        storage.put("gts.x.core.broken.v1~", b"{".to_vec()).unwrap();
+
+        let store = crate::GtsStore::new(Some(Box::new(GtsStorageReader::new(Box::new(storage)))));
+        let health = store.health();
+        assert_eq!(health.schemas, 1);
+        assert_eq!(health.load_errors.len(), 1);
+        assert_eq!(health.load_errors[0].path, "gts.x.core.broken.v1~");
+    }
+
+    #[cfg(feature = "sled")]
+    #[test]
+    fn test_sled_storage_persists_across_opens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gts.sled");
+        {
+            let mut storage = GtsSledStorage::open(&path).unwrap();
+            storage.put("gts.x.core.a.b.v1~", b"one".to_vec()).unwrap();
+            storage.put("gts.x.other.a.b.v1~", b"two".to_vec()).unwrap();
+            storage.flush().unwrap();
+        }
+        let storage = GtsSledStorage::open(&path).unwrap();
+        assert_eq!(
+            storage.get("gts.x.core.a.b.v1~").unwrap(),
+            Some(b"one".to_vec())
+        );
+        assert_eq!(storage.scan_prefix("gts.x.other.").unwrap().len(), 1);
+        assert_eq!(storage.get("gts.x.none.a.b.v1~").unwrap(), None);
+    }
+}
@@ -1,70 +1,20 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 use crate::entities::GtsEntity;
-use crate::gts::{GTS_URI_PREFIX, GtsID, GtsWildcard};
-use crate::schema_cast::GtsEntityCastResult;
-
-/// Custom retriever for resolving gts:// URI scheme references in JSON Schema validation
-struct GtsRetriever {
-    store: Arc<RwLock<HashMap<String, Value>>>,
-}
-
-impl GtsRetriever {
-    fn new(store_map: &HashMap<String, GtsEntity>) -> Self {
-        let mut schemas = HashMap::new();
-
-        // Pre-populate with all schemas from the store
-        for (id, entity) in store_map {
-            if entity.is_schema {
-                // Store with gts:// URI format
-                let uri = format!("{GTS_URI_PREFIX}{id}");
-                schemas.insert(uri, entity.content.clone());
-            }
-        }
-
-        Self {
-            store: Arc::new(RwLock::new(schemas)),
-        }
-    }
-}
-
-impl jsonschema::Retrieve for GtsRetriever {
-    #[allow(clippy::cognitive_complexity)]
-    fn retrieve(
-        &self,
-        uri: &jsonschema::Uri<String>,
-    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
-        let uri_str = uri.as_str();
-
-        tracing::debug!("GtsRetriever: Attempting to retrieve URI: {uri_str}");
-
-        // Only handle gts:// URIs
-        if !uri_str.starts_with(GTS_URI_PREFIX) {
-            tracing::warn!("GtsRetriever: Unknown scheme for URI: {uri_str}");
-            return Err(format!("Unknown scheme for URI: {uri_str}").into());
-        }
-
-        let store = self.store.read().map_err(|e| format!("Lock error: {e}"))?;
-
-        tracing::debug!("GtsRetriever: Store contains {} schemas", store.len());
-
-        if let Some(schema) = store.get(uri_str) {
-            tracing::debug!("GtsRetriever: Successfully retrieved schema for {uri_str}");
-            Ok(schema.clone())
-        } else {
-            tracing::warn!("GtsRetriever: Schema not found: {uri_str}");
-            tracing::debug!(
-                "GtsRetriever: Available URIs: {:?}",
-                store.keys().collect::<Vec<_>>()
-            );
-            Err(format!("Schema not found: {uri_str}").into())
-        }
-    }
-}
+use crate::file_name::{GtsFileNameEncoding, INSTANCE_FILE_SUFFIX, SCHEMA_FILE_SUFFIX};
+use crate::gts::{GTS_URI_PREFIX, GtsID, GtsIdSegment, GtsWildcard};
+use crate::keywords::{GtsKeywordContext, GtsKeywordHandlers};
+use crate::migrations::GtsMigrations;
+use crate::ref_resolver::GtsRefResolver;
+use crate::retired::{GtsRetiredIds, RETIRED_IDS_INSTANCE_ID, RetiredIdsDocument, RetiredIdsError};
+use crate::schema_cast::{GtsCastChainResult, GtsEntityCastResult};
+use crate::storage::{GtsStorage, StorageError, decode_entity, encode_entity};
 
 #[derive(Debug, Error)]
 pub enum StoreError {
@@ -88,12 +38,105 @@ pub enum StoreError {
     ValidationError(String),
     #[error("Invalid $ref: {0}")]
     InvalidRef(String),
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+    #[error("Wildcard pattern '{0}' matches no entities in store")]
+    NoPatternMatches(String),
+    #[error(transparent)]
+    RetiredIds(#[from] RetiredIdsError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
 }
 
 pub trait GtsReader: Send {
     fn iter(&mut self) -> Box<dyn Iterator<Item = GtsEntity> + '_>;
     fn read_by_id(&self, entity_id: &str) -> Option<GtsEntity>;
     fn reset(&mut self);
+    /// Sources that could not be loaded during the last `iter()`.
+    fn load_errors(&self) -> Vec<GtsLoadError> {
+        Vec::new()
+    }
+}
+
+/// A source (e.g. a file) the reader failed to load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GtsLoadError {
+    pub path: String,
+    pub message: String,
+}
+
+/// One-call snapshot of the registry state, see [`GtsStore::health`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtsStoreHealth {
+    pub schemas: usize,
+    pub instances: usize,
+    /// Number of distinct vendors among entities with a GTS ID
+    pub vendors: usize,
+    /// Unix time (seconds) the store was last populated from its reader
+    pub last_load_unix_secs: Option<u64>,
+    pub load_errors: Vec<GtsLoadError>,
+    /// Rough heap size of the ID index and the entity contents, in bytes
+    pub index_memory_estimate_bytes: usize,
+    /// Progress of the background load, for stores loaded asynchronously
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loading: Option<GtsLoadProgressSnapshot>,
+}
+
+/// Progress of an asynchronous store load, updated by the loader and readable
+/// from other tasks while it runs.
+#[derive(Debug, Default)]
+pub struct GtsLoadProgress {
+    files_total: AtomicUsize,
+    files_loaded: AtomicUsize,
+    files_failed: AtomicUsize,
+    done: AtomicBool,
+}
+
+impl GtsLoadProgress {
+    /// File discovery finished with `files_total` files to load.
+    pub fn start(&self, files_total: usize) {
+        self.files_total.store(files_total, Ordering::Relaxed);
+    }
+
+    /// One file finished loading, successfully or not.
+    pub fn record(&self, ok: bool) {
+        let counter = if ok {
+            &self.files_loaded
+        } else {
+            &self.files_failed
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The store is populated.
+    pub fn finish(&self) {
+        self.done.store(true, Ordering::Release);
+    }
+
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.done.load(Ordering::Acquire)
+    }
+
+    #[must_use]
+    pub fn snapshot(&self) -> GtsLoadProgressSnapshot {
+        GtsLoadProgressSnapshot {
+            files_total: self.files_total.load(Ordering::Relaxed),
+            files_loaded: self.files_loaded.load(Ordering::Relaxed),
+            files_failed: self.files_failed.load(Ordering::Relaxed),
+            done: self.is_done(),
+        }
+    }
+}
+
+/// Point-in-time copy of a [`GtsLoadProgress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GtsLoadProgressSnapshot {
+    /// Files found so far; 0 while files are still being discovered
+    pub files_total: usize,
+    pub files_loaded: usize,
+    pub files_failed: usize,
+    pub done: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,11 +146,110 @@ pub struct GtsStoreQueryResult {
     pub count: usize,
     pub limit: usize,
     pub results: Vec<Value>,
+    /// Why each result matched, in the order of `results` (explained queries only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub explanations: Vec<GtsQueryMatchExplanation>,
+}
+
+/// A query pattern segment and the ID token it matched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GtsSegmentMatch {
+    pub pattern: String,
+    /// The matched part of the entity ID; for a wildcard, everything it covers
+    pub token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// An attribute filter of the query and the value that satisfied it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GtsFilterMatch {
+    pub key: String,
+    pub expected: String,
+    pub actual: Value,
+}
+
+/// Why an entity matched a query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GtsQueryMatchExplanation {
+    pub gts_id: String,
+    pub segments: Vec<GtsSegmentMatch>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<GtsFilterMatch>,
+}
+
+/// A file whose name does not match the GTS ID embedded in its content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GtsFileNameMismatch {
+    pub path: String,
+    /// The ID implied by the file name (suffix stripped and, where the
+    /// encoding allows, decoded)
+    pub file_name_id: String,
+    /// The ID declared by the entity's `$id`/`id` field
+    pub entity_id: String,
+    /// The file name the entity should have under the checked encoding
+    #[serde(default)]
+    pub expected_file_name: String,
+}
+
+impl GtsFileNameMismatch {
+    /// Returns a mismatch if `entity` was loaded from a single-entity
+    /// `*.schema.json` / `*.instance.json` file whose name is not its GTS ID
+    /// under any of `encodings`; the first one is reported as expected.
+    fn check(entity: &GtsEntity, encodings: &[GtsFileNameEncoding]) -> Option<Self> {
+        let file = entity.file.as_ref()?;
+        let gts_id = entity.gts_id.as_ref()?;
+        if entity.list_sequence.is_some() {
+            return None;
+        }
+        let (stem, suffix) = [SCHEMA_FILE_SUFFIX, INSTANCE_FILE_SUFFIX]
+            .into_iter()
+            .find_map(|suffix| Some((file.name.strip_suffix(suffix)?, suffix)))?;
+        if encodings.iter().any(|e| e.matches(stem, &gts_id.id)) {
+            return None;
+        }
+        let encoding = encodings.first().copied().unwrap_or_default();
+        Some(Self {
+            path: file.path.clone(),
+            file_name_id: encoding.decode(stem).unwrap_or_else(|| stem.to_owned()),
+            entity_id: gts_id.id.clone(),
+            expected_file_name: format!("{}{suffix}", encoding.encode(&gts_id.id)),
+        })
+    }
+}
+
+/// Approximate heap bytes owned by a JSON value (excluding the value itself).
+fn value_heap_size(value: &Value) -> usize {
+    match value {
+        Value::String(s) => s.capacity(),
+        Value::Array(items) => items
+            .iter()
+            .map(|v| std::mem::size_of::<Value>() + value_heap_size(v))
+            .sum(),
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| k.capacity() + std::mem::size_of::<Value>() + value_heap_size(v))
+            .sum(),
+        Value::Null | Value::Bool(_) | Value::Number(_) => 0,
+    }
 }
 
 pub struct GtsStore {
     by_id: HashMap<String, GtsEntity>,
     reader: Option<Box<dyn GtsReader>>,
+    require_wildcard_matches: bool,
+    last_loaded_at: Option<SystemTime>,
+    load_errors: Vec<GtsLoadError>,
+    load_progress: Option<Arc<GtsLoadProgress>>,
+    /// Registries added with `add_retired_ids`
+    configured_retired_ids: GtsRetiredIds,
+    /// `configured_retired_ids` plus the registry instance
+    retired_ids: GtsRetiredIds,
+    keyword_handlers: GtsKeywordHandlers,
+    migrations: GtsMigrations,
+    /// Persistent backend registered entities are written to and missing
+    /// ones fetched from
+    storage: Option<Box<dyn GtsStorage>>,
 }
 
 impl GtsStore {
@@ -115,6 +257,15 @@ impl GtsStore {
         let mut store = GtsStore {
             by_id: HashMap::new(),
             reader,
+            require_wildcard_matches: false,
+            last_loaded_at: None,
+            load_errors: Vec::new(),
+            load_progress: None,
+            configured_retired_ids: GtsRetiredIds::new(),
+            retired_ids: GtsRetiredIds::new(),
+            keyword_handlers: GtsKeywordHandlers::new(),
+            migrations: GtsMigrations::new(),
+            storage: None,
         };
 
         if store.reader.is_some() {
@@ -125,35 +276,312 @@ impl GtsStore {
         store
     }
 
+    /// Empty store standing in while a background load runs; its health
+    /// reports `progress`.
+    #[must_use]
+    pub fn loading(progress: Arc<GtsLoadProgress>) -> Self {
+        let mut store = Self::new(None);
+        store.load_progress = Some(progress);
+        store
+    }
+
+    /// Populate a store from `reader` without blocking the async runtime, see
+    /// [`GtsFileReader::load_async`](crate::GtsFileReader::load_async).
+    #[cfg(feature = "async")]
+    pub async fn load_async(
+        mut reader: crate::GtsFileReader,
+        concurrency: usize,
+        progress: Arc<GtsLoadProgress>,
+    ) -> Self {
+        let entities = reader.load_async(concurrency, &progress).await;
+        let mut store = Self::loading(progress);
+        for entity in entities {
+            Self::index_loaded(&mut store.by_id, entity);
+        }
+        store.load_errors = reader.load_errors();
+        store.last_loaded_at = Some(SystemTime::now());
+        store.reader = Some(Box::new(reader));
+        store.refresh_retired_ids();
+        tracing::info!("Populated GtsStore with {} entities", store.by_id.len());
+        store
+    }
+
+    /// Empty store over `storage`: entities are fetched from it on demand,
+    /// see [`crate::storage`].
+    #[must_use]
+    pub fn with_storage(storage: Box<dyn GtsStorage>) -> Self {
+        let mut store = Self::new(None);
+        store.storage = Some(storage);
+        store
+    }
+
+    /// Write registered entities through to `storage` and fetch missing ones
+    /// from it. Entities already in the store are not written; see
+    /// [`persist`](Self::persist).
+    pub fn set_storage(&mut self, storage: Box<dyn GtsStorage>) {
+        self.storage = Some(storage);
+    }
+
+    /// Write every entity of the store to its storage and flush it, returning
+    /// the number written; 0 without storage.
+    ///
+    /// # Errors
+    /// Returns `StoreError::Storage` if the backend fails.
+    pub fn persist(&mut self) -> Result<usize, StoreError> {
+        let Some(storage) = self.storage.as_mut() else {
+            return Ok(0);
+        };
+        for (id, entity) in &self.by_id {
+            storage.put(id, encode_entity(entity))?;
+        }
+        storage.flush()?;
+        Ok(self.by_id.len())
+    }
+
+    /// Fetch the stored entities whose ID starts with `prefix` that the store
+    /// does not hold yet, returning how many were added.
+    ///
+    /// # Errors
+    /// Returns `StoreError::Storage` if the backend fails or an entity is corrupt.
+    pub fn load_prefix(&mut self, prefix: &str) -> Result<usize, StoreError> {
+        let Some(storage) = self.storage.as_ref() else {
+            return Ok(0);
+        };
+        let mut added = 0;
+        for (id, bytes) in storage.scan_prefix(prefix)? {
+            if self.by_id.contains_key(&id) || self.retired_ids.get(&id).is_some() {
+                continue;
+            }
+            self.by_id.insert(id.clone(), decode_entity(&id, &bytes)?);
+            added += 1;
+        }
+        Ok(added)
+    }
+
+    /// Progress of the background load this store came from, if any.
+    #[must_use]
+    pub fn load_progress(&self) -> Option<&Arc<GtsLoadProgress>> {
+        self.load_progress.as_ref()
+    }
+
     fn populate_from_reader(&mut self) {
         if let Some(ref mut reader) = self.reader {
             for entity in reader.iter() {
-                // Use effective_id() which handles both GTS IDs and anonymous instance IDs
-                if let Some(id) = entity.effective_id() {
-                    self.by_id.insert(id, entity);
-                }
+                Self::index_loaded(&mut self.by_id, entity);
+            }
+            self.load_errors = reader.load_errors();
+            self.last_loaded_at = Some(SystemTime::now());
+            self.refresh_retired_ids();
+        }
+    }
+
+    /// Retired IDs: the [`RETIRED_IDS_INSTANCE_ID`] instance's registry and
+    /// those added with [`add_retired_ids`](Self::add_retired_ids).
+    #[must_use]
+    pub fn retired_ids(&self) -> &GtsRetiredIds {
+        &self.retired_ids
+    }
+
+    /// Retired IDs added with [`add_retired_ids`](Self::add_retired_ids),
+    /// e.g. to keep them in a store this one replaces.
+    #[must_use]
+    pub fn configured_retired_ids(&self) -> &GtsRetiredIds {
+        &self.configured_retired_ids
+    }
+
+    /// Also treat the IDs of `registry` (e.g. a configured file) as retired;
+    /// loaded entities with such IDs are dropped and reported as load errors.
+    pub fn add_retired_ids(&mut self, registry: GtsRetiredIds) {
+        self.configured_retired_ids.extend(registry);
+        self.refresh_retired_ids();
+    }
+
+    /// Validate the custom schema keyword `keyword` with `handler` whenever a
+    /// schema or instance is validated, see [`crate::keywords`].
+    pub fn register_keyword<F>(&mut self, keyword: impl Into<String>, handler: F)
+    where
+        F: Fn(&GtsKeywordContext<'_>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.keyword_handlers.register(keyword, handler);
+    }
+
+    #[must_use]
+    pub fn keyword_handlers(&self) -> &GtsKeywordHandlers {
+        &self.keyword_handlers
+    }
+
+    /// Replace the custom keyword handlers, e.g. to keep those of a store
+    /// this one replaces.
+    pub fn set_keyword_handlers(&mut self, handlers: GtsKeywordHandlers) {
+        self.keyword_handlers = handlers;
+    }
+
+    /// Run `migration` on instances cast from schema `from` to schema `to`,
+    /// before the automatic cast steps, see [`crate::migrations`].
+    pub fn register_migration<F>(
+        &mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        migration: F,
+    ) where
+        F: Fn(&mut serde_json::Map<String, Value>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.migrations.register(from, to, migration);
+    }
+
+    #[must_use]
+    pub fn migrations(&self) -> &GtsMigrations {
+        &self.migrations
+    }
+
+    /// Replace the migrations, e.g. to keep those of a store this one
+    /// replaces.
+    pub fn set_migrations(&mut self, migrations: GtsMigrations) {
+        self.migrations = migrations;
+    }
+
+    /// Recompute the retired IDs and drop entities that use one, reporting
+    /// each as a load error. An invalid registry instance is reported too and
+    /// leaves only the configured registries in effect.
+    pub(crate) fn refresh_retired_ids(&mut self) {
+        let mut retired = self.configured_retired_ids.clone();
+        if let Some(entity) = self.by_id.get(RETIRED_IDS_INSTANCE_ID) {
+            match RetiredIdsDocument::from_value(&entity.content).and_then(|doc| doc.registry()) {
+                Ok(registry) => retired.extend(registry),
+                Err(e) => self.load_errors.push(GtsLoadError {
+                    path: Self::entity_path(entity, RETIRED_IDS_INSTANCE_ID),
+                    message: e.to_string(),
+                }),
+            }
+        }
+        let reused: Vec<String> = self
+            .by_id
+            .keys()
+            .filter(|id| retired.get(id).is_some())
+            .cloned()
+            .collect();
+        for id in reused {
+            if let (Some(entity), Err(e)) = (self.by_id.remove(&id), retired.check(&id)) {
+                tracing::warn!("Dropping entity with retired ID: {e}");
+                self.load_errors.push(GtsLoadError {
+                    path: Self::entity_path(&entity, &id),
+                    message: e.to_string(),
+                });
+            }
+        }
+        self.retired_ids = retired;
+    }
+
+    /// The file `entity` was loaded from, or `id` for entities without one.
+    fn entity_path(entity: &GtsEntity, id: &str) -> String {
+        entity
+            .file
+            .as_ref()
+            .map_or_else(|| id.to_owned(), |file| file.path.clone())
+    }
+
+    fn index_loaded(by_id: &mut HashMap<String, GtsEntity>, entity: GtsEntity) {
+        // Use effective_id() which handles both GTS IDs and anonymous instance IDs
+        if let Some(id) = entity.effective_id() {
+            // Files written with any encoding are consistent until linted
+            if let Some(m) = GtsFileNameMismatch::check(&entity, &GtsFileNameEncoding::ALL) {
+                tracing::warn!(
+                    "File name '{}' does not match its GTS ID '{}' ({})",
+                    m.file_name_id,
+                    m.entity_id,
+                    m.path
+                );
             }
+            by_id.insert(id, entity);
+        }
+    }
+
+    /// Counts, last load time, load errors and a memory estimate of the store.
+    #[must_use]
+    pub fn health(&self) -> GtsStoreHealth {
+        let schemas = self.by_id.values().filter(|e| e.is_schema).count();
+        let vendors: HashSet<&str> = self
+            .by_id
+            .values()
+            .filter_map(|e| e.gts_id.as_ref()?.gts_id_segments.first())
+            .map(|seg| seg.vendor.as_str())
+            .collect();
+        let index_memory_estimate_bytes = self
+            .by_id
+            .iter()
+            .map(|(id, entity)| {
+                id.capacity() + std::mem::size_of::<GtsEntity>() + value_heap_size(&entity.content)
+            })
+            .sum();
+        GtsStoreHealth {
+            schemas,
+            instances: self.by_id.len() - schemas,
+            vendors: vendors.len(),
+            last_load_unix_secs: self
+                .last_loaded_at
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+            load_errors: self.load_errors.clone(),
+            index_memory_estimate_bytes,
+            loading: self.load_progress.as_ref().map(|p| p.snapshot()),
         }
     }
 
+    /// Returns all loaded entities whose file name does not match their GTS ID,
+    /// sorted by file path.
+    #[must_use]
+    pub fn check_file_names(&self) -> Vec<GtsFileNameMismatch> {
+        self.check_file_names_with(GtsFileNameEncoding::Raw)
+    }
+
+    /// [`check_file_names`](Self::check_file_names) for file names written
+    /// with `encoding`.
+    #[must_use]
+    pub fn check_file_names_with(&self, encoding: GtsFileNameEncoding) -> Vec<GtsFileNameMismatch> {
+        let mut mismatches: Vec<GtsFileNameMismatch> = self
+            .by_id
+            .values()
+            .filter_map(|entity| GtsFileNameMismatch::check(entity, &[encoding]))
+            .collect();
+        mismatches.sort_by(|a, b| a.path.cmp(&b.path));
+        mismatches
+    }
+
     /// Registers an entity in the store.
     ///
     /// # Errors
-    /// Returns `StoreError::InvalidEntity` if the entity has no effective ID.
+    /// Returns `StoreError::InvalidEntity` if the entity has no effective ID,
+    /// `StoreError::RetiredIds` if the ID is retired or the entity is an
+    /// invalid retired-IDs registry, and `StoreError::Storage` if it cannot
+    /// be written to the store's storage.
     pub fn register(&mut self, entity: GtsEntity) -> Result<(), StoreError> {
         let id = entity.effective_id().ok_or(StoreError::InvalidEntity)?;
+        self.retired_ids.check(&id)?;
+        let is_registry = id == RETIRED_IDS_INSTANCE_ID;
+        if is_registry {
+            RetiredIdsDocument::from_value(&entity.content)?.registry()?;
+        }
+        if let Some(storage) = self.storage.as_mut() {
+            storage.put(&id, encode_entity(&entity))?;
+        }
         self.by_id.insert(id, entity);
+        if is_registry {
+            self.refresh_retired_ids();
+        }
         Ok(())
     }
 
     /// Registers a schema in the store.
     ///
     /// # Errors
-    /// Returns `StoreError::InvalidSchemaId` if the `type_id` doesn't end with '~'.
+    /// Returns `StoreError::InvalidSchemaId` if the `type_id` doesn't end with '~',
+    /// `StoreError::RetiredIds` if it is retired, and `StoreError::Storage` if
+    /// it cannot be written to the store's storage.
     pub fn register_schema(&mut self, type_id: &str, schema: &Value) -> Result<(), StoreError> {
         if !type_id.ends_with('~') {
             return Err(StoreError::InvalidSchemaId);
         }
+        self.retired_ids.check(type_id)?;
 
         let gts_id = GtsID::new(type_id).map_err(|_| StoreError::InvalidSchemaId)?;
         let entity = GtsEntity::new(
@@ -167,24 +595,65 @@ impl GtsStore {
             None,
             None,
         );
+        if let Some(storage) = self.storage.as_mut() {
+            storage.put(type_id, encode_entity(&entity))?;
+        }
         self.by_id.insert(type_id.to_owned(), entity);
         Ok(())
     }
 
+    /// Put `entity` under `entity_id`, or remove the entity there if `None`,
+    /// returning the entity it replaces. Unlike [`register`](Self::register)
+    /// nothing is checked or written to storage, so a dry run can restore
+    /// the store with the returned entity afterwards.
+    pub(crate) fn swap_entity(
+        &mut self,
+        entity_id: &str,
+        entity: Option<GtsEntity>,
+    ) -> Option<GtsEntity> {
+        match entity {
+            Some(entity) => self.by_id.insert(entity_id.to_owned(), entity),
+            None => self.by_id.remove(entity_id),
+        }
+    }
+
+    /// The storage registered entities are written to, if any.
+    pub(crate) fn storage_mut(&mut self) -> Option<&mut (dyn GtsStorage + 'static)> {
+        self.storage.as_deref_mut()
+    }
+
     pub fn get(&mut self, entity_id: &str) -> Option<&GtsEntity> {
         // Check cache first
         if self.by_id.contains_key(entity_id) {
             return self.by_id.get(entity_id);
         }
 
-        // Try to fetch from reader
+        // Try to fetch from reader; retired IDs stay out of the store
         if let Some(ref reader) = self.reader
+            && self.retired_ids.get(entity_id).is_none()
             && let Some(entity) = reader.read_by_id(entity_id)
         {
             self.by_id.insert(entity_id.to_owned(), entity);
             return self.by_id.get(entity_id);
         }
 
+        // Then from storage; a failing backend reads as a missing entity
+        if let Some(ref storage) = self.storage
+            && self.retired_ids.get(entity_id).is_none()
+        {
+            match storage
+                .get(entity_id)
+                .and_then(|bytes| bytes.map(|b| decode_entity(entity_id, &b)).transpose())
+            {
+                Ok(Some(entity)) => {
+                    self.by_id.insert(entity_id.to_owned(), entity);
+                    return self.by_id.get(entity_id);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Cannot load '{entity_id}' from storage: {e}"),
+            }
+        }
+
         None
     }
 
@@ -203,6 +672,37 @@ impl GtsStore {
         self.by_id.iter()
     }
 
+    /// When enabled, schema validation fails if a wildcard `x-gts-ref`
+    /// (e.g. `gts.x.core.events.topic.v1~*`) matches no entity in the store.
+    /// Disabled by default.
+    pub fn set_require_wildcard_matches(&mut self, require: bool) {
+        self.require_wildcard_matches = require;
+    }
+
+    /// Expands a GTS pattern into all concrete IDs in the store that match it,
+    /// sorted by ID. A pattern without `*` matches with minor-version flexibility,
+    /// the same way as in queries. The `gts://` prefix is accepted.
+    ///
+    /// # Errors
+    /// Returns `StoreError::InvalidPattern` if the pattern cannot be parsed.
+    pub fn expand_pattern(&self, pattern: &str) -> Result<Vec<GtsID>, StoreError> {
+        let pattern = pattern.trim();
+        let pattern = pattern.strip_prefix(GTS_URI_PREFIX).unwrap_or(pattern);
+        let wildcard =
+            GtsWildcard::new(pattern).map_err(|e| StoreError::InvalidPattern(e.to_string()))?;
+
+        let mut ids: Vec<GtsID> = self
+            .by_id
+            .values()
+            .filter_map(|entity| entity.gts_id.as_ref())
+            .filter(|gts_id| gts_id.wildcard_match(&wildcard))
+            .cloned()
+            .collect();
+        ids.sort_by(|a, b| a.id.cmp(&b.id));
+        ids.dedup_by(|a, b| a.id == b.id);
+        Ok(ids)
+    }
+
     /// Resolve all `$ref` references in a JSON Schema by inlining the referenced schemas.
     ///
     /// This method recursively traverses the schema, finds all `$ref` references,
@@ -553,6 +1053,8 @@ impl GtsStore {
         // Validate x-gts-ref constraints in the schema
         let validator = crate::x_gts_ref::XGtsRefValidator::new();
         let x_gts_ref_errors = validator.validate_schema(&schema_entity.content, "", None);
+        let mut patterns = Vec::new();
+        Self::collect_x_gts_ref_wildcards(&schema_entity.content, &mut patterns);
 
         if !x_gts_ref_errors.is_empty() {
             let error_messages: Vec<String> = x_gts_ref_errors
@@ -570,9 +1072,45 @@ impl GtsStore {
             return Err(StoreError::ValidationError(error_message));
         }
 
+        if self.require_wildcard_matches {
+            for pattern in patterns {
+                if self.expand_pattern(&pattern)?.is_empty() {
+                    return Err(StoreError::NoPatternMatches(pattern));
+                }
+            }
+        }
+
         Ok(())
     }
 
+    /// Collects absolute wildcard `x-gts-ref` values (e.g. `gts.x.core.*`) from a schema.
+    /// The catch-all `gts.*` is skipped since it places no constraint on the store.
+    fn collect_x_gts_ref_wildcards(schema: &Value, patterns: &mut Vec<String>) {
+        match schema {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if key == "x-gts-ref" {
+                        if let Some(pattern) = value.as_str()
+                            && pattern.starts_with("gts.")
+                            && pattern.contains('*')
+                            && pattern != "gts.*"
+                        {
+                            patterns.push(pattern.to_owned());
+                        }
+                    } else {
+                        Self::collect_x_gts_ref_wildcards(value, patterns);
+                    }
+                }
+            }
+            Value::Array(arr) => {
+                for item in arr {
+                    Self::collect_x_gts_ref_wildcards(item, patterns);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Validates all `$ref` values in a schema.
     ///
     /// Rules:
@@ -713,7 +1251,52 @@ impl GtsStore {
             gts_id
         );
 
-        Ok(())
+        // 4. Run the custom keyword handlers
+        self.validate_keywords(gts_id, gts_id)
+    }
+
+    /// Runs the custom keyword handlers on every schema of the chain of
+    /// `schema_id` for the entity `entity_id`.
+    fn validate_keywords(&mut self, entity_id: &str, schema_id: &str) -> Result<(), StoreError> {
+        if self.keyword_handlers.is_empty() {
+            return Ok(());
+        }
+        let gid = GtsID::new(schema_id)
+            .map_err(|e| StoreError::ValidationError(format!("Invalid GTS ID: {e}")))?;
+        let chain: Vec<String> = (1..=gid.gts_id_segments.len())
+            .map(|n| {
+                let prefix: String = gid.gts_id_segments[..n]
+                    .iter()
+                    .map(|s| s.segment.as_str())
+                    .collect();
+                format!("gts.{prefix}")
+            })
+            .collect();
+        // Fetch the chain and the entity into the cache; missing chain
+        // schemas are reported by the other checks
+        for id in chain.iter().map(String::as_str).chain([entity_id]) {
+            let _ = self.get(id);
+        }
+        let Some(entity) = self.by_id.get(entity_id) else {
+            return Err(StoreError::EntityNotFound(entity_id.to_owned()));
+        };
+        let violations: Vec<String> = chain
+            .iter()
+            .filter_map(|id| self.by_id.get(id).map(|schema| (id, schema)))
+            .flat_map(|(id, schema)| {
+                self.keyword_handlers
+                    .check(id, &schema.content, entity, &chain)
+            })
+            .map(|v| v.to_string())
+            .collect();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(StoreError::ValidationError(format!(
+                "Keyword validation failed: {}",
+                violations.join("; ")
+            )))
+        }
     }
 
     /// Validates a chained schema ID by checking each derived schema against its base.
@@ -1012,6 +1595,239 @@ impl GtsStore {
         Ok(())
     }
 
+    /// OP#13: Resolves the effective traits of a schema.
+    ///
+    /// `x-gts-traits` values are shallow-merged along the chain (rightmost
+    /// wins) and missing values are filled from trait schema defaults.  The
+    /// result is not validated; [`Self::validate_schema`] does that.
+    ///
+    /// # Errors
+    /// Returns `StoreError` if the ID is not a schema ID, a schema in the chain
+    /// is missing, or a trait schema contains an unresolvable `$ref`.
+    pub fn resolve_traits(
+        &mut self,
+        schema_id: &str,
+    ) -> Result<serde_json::Map<String, Value>, StoreError> {
+        let gid = GtsID::new(schema_id)
+            .map_err(|e| StoreError::ValidationError(format!("Invalid GTS ID: {e}")))?;
+        if !gid.is_type() {
+            return Err(StoreError::InvalidSchemaId);
+        }
+
+        let mut trait_schemas: Vec<Value> = Vec::new();
+        let mut merged_traits = serde_json::Map::new();
+        let mut chain_id = String::from("gts.");
+        for segment in &gid.gts_id_segments {
+            chain_id.push_str(&segment.segment);
+            let content = self.get_schema_content(&chain_id)?;
+            crate::schema_traits::collect_trait_schema_from_value(&content, &mut trait_schemas);
+            crate::schema_traits::collect_traits_from_value(&content, &mut merged_traits);
+        }
+
+        let mut resolved_trait_schemas = Vec::with_capacity(trait_schemas.len());
+        for ts in &trait_schemas {
+            let resolved = self.resolve_schema_refs_checked(ts).map_err(|e| {
+                StoreError::ValidationError(format!("Schema '{schema_id}' trait schema has {e}"))
+            })?;
+            resolved_trait_schemas.push(resolved);
+        }
+
+        Ok(crate::schema_traits::effective_traits(
+            &resolved_trait_schemas,
+            &Value::Object(merged_traits),
+        ))
+    }
+
+    /// Merges the whole `~` chain of a schema into a single schema.
+    ///
+    /// Each level contributes what it declares itself, base first. Conflicts
+    /// between levels (incompatible property types, contradictory
+    /// `additionalProperties`, duplicate `required` entries) are returned as
+    /// diagnostics next to the merged schema, which keeps the base declaration
+    /// wherever a conflict was found.
+    ///
+    /// # Errors
+    /// Returns `StoreError` if the ID is not a schema ID, a schema in the chain
+    /// is missing, or a schema contains a circular `$ref`.
+    pub fn flatten_chain(
+        &mut self,
+        schema_id: &str,
+    ) -> Result<crate::schema_flatten::FlattenedSchema, StoreError> {
+        let gid = GtsID::new(schema_id)
+            .map_err(|e| StoreError::ValidationError(format!("Invalid GTS ID: {e}")))?;
+        if !gid.is_type() {
+            return Err(StoreError::InvalidSchemaId);
+        }
+
+        let mut contents = Vec::with_capacity(gid.gts_id_segments.len());
+        let mut chain_id = String::from("gts.");
+        for segment in &gid.gts_id_segments {
+            chain_id.push_str(&segment.segment);
+            contents.push((chain_id.clone(), self.get_schema_content(&chain_id)?));
+        }
+
+        let resolve = |v: &Value| self.resolve_schema_refs_checked(v);
+        let mut levels = Vec::with_capacity(contents.len());
+        for (i, (level_id, content)) in contents.iter().enumerate() {
+            let ancestors: Vec<String> = contents[..i].iter().map(|(id, _)| id.clone()).collect();
+            let mut fragments = Vec::new();
+            crate::schema_flatten::level_fragments(content, &ancestors, &resolve, &mut fragments)
+                .map_err(|e| StoreError::ValidationError(format!("Schema '{level_id}' has {e}")))?;
+            levels.push((level_id.clone(), fragments));
+        }
+        Ok(crate::schema_flatten::merge_levels(&levels))
+    }
+
+    /// Generates `n` random instances that validate against a schema.
+    ///
+    /// Values honour `type`, `enum`, `const`, `default`, `required`, bounds
+    /// and the common string formats, and are checked like
+    /// [`Self::validate_instance`] does; candidates failing a constraint the
+    /// generator cannot construct (e.g. `pattern`) are retried. The same
+    /// `seed` always yields the same examples.
+    ///
+    /// # Errors
+    /// Returns `StoreError` if the schema cannot be flattened or compiled, or
+    /// no valid example is found within the retry budget.
+    pub fn generate_examples(
+        &mut self,
+        schema_id: &str,
+        n: usize,
+        seed: u64,
+    ) -> Result<Vec<Value>, StoreError> {
+        const MAX_ATTEMPTS: usize = 100;
+
+        let (flat, is_valid) = self.example_checker(schema_id)?;
+        let mut generator = crate::example_gen::ExampleGenerator::new(schema_id, seed);
+        let mut examples = Vec::with_capacity(n);
+        for _ in 0..n {
+            let example = (0..MAX_ATTEMPTS)
+                .map(|_| generator.generate(&flat))
+                .find(|candidate| is_valid(candidate))
+                .ok_or_else(|| {
+                    StoreError::ValidationError(format!(
+                        "Could not generate a valid example of '{schema_id}' in {MAX_ATTEMPTS} attempts"
+                    ))
+                })?;
+            examples.push(example);
+        }
+        Ok(examples)
+    }
+
+    /// Generates `n` negative examples of a schema for error-path tests.
+    ///
+    /// Each is a valid generated instance with one schema-aware mutation
+    /// (a required property dropped, an enum or `const` violated, an
+    /// `x-gts-ref` broken, a type, bound or length violated, an unexpected
+    /// property added), annotated with the violation it is expected to cause.
+    /// Only mutations that actually fail validation are returned.
+    ///
+    /// # Errors
+    /// Returns `StoreError` if the schema cannot be flattened or compiled, it
+    /// has no constraint to violate, or `n` examples cannot be produced within
+    /// the retry budget.
+    pub fn generate_invalid_examples(
+        &mut self,
+        schema_id: &str,
+        n: usize,
+        seed: u64,
+    ) -> Result<Vec<crate::example_gen::InvalidExample>, StoreError> {
+        const MAX_ATTEMPTS_PER_EXAMPLE: usize = 20;
+
+        let (flat, is_valid) = self.example_checker(schema_id)?;
+        let mut generator = crate::example_gen::ExampleGenerator::new(schema_id, seed);
+        let mut examples = Vec::with_capacity(n);
+        for _ in 0..n.saturating_mul(MAX_ATTEMPTS_PER_EXAMPLE) {
+            if examples.len() == n {
+                break;
+            }
+            let valid = generator.generate(&flat);
+            if !is_valid(&valid) {
+                continue;
+            }
+            let invalid = generator
+                .mutate(&flat, &valid, examples.len())
+                .ok_or_else(|| {
+                    StoreError::ValidationError(format!(
+                        "Schema '{schema_id}' has no constraint to violate"
+                    ))
+                })?;
+            if !is_valid(&invalid.instance) {
+                examples.push(invalid);
+            }
+        }
+        if examples.len() < n {
+            return Err(StoreError::ValidationError(format!(
+                "Could only generate {} of {n} invalid examples of '{schema_id}'",
+                examples.len()
+            )));
+        }
+        Ok(examples)
+    }
+
+    /// The flattened schema of `schema_id` for example generation, and a
+    /// check that a value validates the way [`Self::validate_instance`] does.
+    fn example_checker(
+        &mut self,
+        schema_id: &str,
+    ) -> Result<(Value, impl Fn(&Value) -> bool + use<>), StoreError> {
+        let flat = self.flatten_chain(schema_id)?.schema;
+        let raw = self.get_schema_content(schema_id)?;
+        let validator = self.instance_validator(&raw)?;
+        let ref_validator = crate::x_gts_ref::XGtsRefValidator::new();
+        let ref_schema = flat.clone();
+        let is_valid = move |value: &Value| {
+            validator.is_valid(value)
+                && ref_validator
+                    .validate_instance(value, &ref_schema, "")
+                    .is_empty()
+        };
+        Ok((flat, is_valid))
+    }
+
+    /// Returns a single resolved trait value of a schema, see [`Self::resolve_traits`].
+    ///
+    /// # Errors
+    /// Returns `StoreError` if the traits cannot be resolved.
+    pub fn trait_value(
+        &mut self,
+        schema_id: &str,
+        name: &str,
+    ) -> Result<Option<Value>, StoreError> {
+        Ok(self.resolve_traits(schema_id)?.remove(name))
+    }
+
+    /// A JSON Schema validator of instances of `schema`, as
+    /// [`Self::validate_instance`] runs it: internal and `gts://` references
+    /// are resolved from the store, and `x-gts-ref` fields, checked separately
+    /// by [`XGtsRefValidator`](crate::x_gts_ref::XGtsRefValidator), are left out.
+    ///
+    /// # Errors
+    /// Returns `StoreError::ValidationError` if the schema does not compile.
+    pub fn instance_validator(&self, schema: &Value) -> Result<jsonschema::Validator, StoreError> {
+        // Resolve internal #/ references (like #/$defs/GtsInstanceId) by inlining them
+        let resolved = self.resolve_schema_refs(schema);
+        // x-gts-ref is a GTS extension unknown to the jsonschema crate; leaving it
+        // inside oneOf/anyOf/allOf branches would cause those branches to be treated
+        // as empty match-everything schemas, breaking combinator semantics.
+        let resolved = Self::remove_x_gts_ref_fields(&resolved);
+        tracing::debug!(
+            "Schema for validation: {}",
+            serde_json::to_string_pretty(&resolved).unwrap_or_default()
+        );
+        // The retriever resolves any remaining $ref to gts:// URIs
+        jsonschema::options()
+            .with_retriever(GtsRefResolver::from_store(self))
+            .build(&resolved)
+            .map_err(|e| {
+                tracing::error!("Schema compilation error: {}", e);
+                StoreError::ValidationError(format!(
+                    "Invalid schema: {e}\nSchema: {}",
+                    serde_json::to_string_pretty(&resolved).unwrap_or_default()
+                ))
+            })
+    }
+
     /// Validates an instance against its schema.
     ///
     /// # Errors
@@ -1044,40 +1860,7 @@ impl GtsStore {
             schema_id
         );
 
-        // Resolve internal #/ references (like #/$defs/GtsInstanceId) by inlining them
-        // This handles the compile-time inlining of GtsInstanceId and GtsSchemaId
-        let schema_with_internal_refs_resolved = self.resolve_schema_refs(&schema);
-
-        // Remove x-gts-ref fields before jsonschema validation.
-        // x-gts-ref is a GTS extension unknown to the jsonschema crate; leaving it
-        // inside oneOf/anyOf/allOf branches would cause those branches to be treated
-        // as empty match-everything schemas, breaking combinator semantics.
-        let schema_with_internal_refs_resolved =
-            Self::remove_x_gts_ref_fields(&schema_with_internal_refs_resolved);
-
-        tracing::debug!(
-            "Schema for validation: {}",
-            serde_json::to_string_pretty(&schema_with_internal_refs_resolved).unwrap_or_default()
-        );
-
-        // Create custom retriever for gts:// URI resolution
-        let retriever = GtsRetriever::new(&self.by_id);
-
-        // Build validator with custom retriever to handle gts:// references
-        // Internal #/ references have already been resolved by resolve_schema_refs
-        // The retriever will resolve any $ref to gts:// URIs automatically
-        let validator = jsonschema::options()
-            .with_retriever(retriever)
-            .build(&schema_with_internal_refs_resolved)
-            .map_err(|e| {
-                tracing::error!("Schema compilation error: {}", e);
-                StoreError::ValidationError(format!(
-                    "Invalid schema: {e}\nContent: {}\nSchema: {}",
-                    serde_json::to_string_pretty(&obj.content).unwrap_or_default(),
-                    serde_json::to_string_pretty(&schema_with_internal_refs_resolved)
-                        .unwrap_or_default()
-                ))
-            })?;
+        let validator = self.instance_validator(&schema)?;
 
         validator.validate(&obj.content).map_err(|_| {
             let errors: Vec<String> = validator
@@ -1107,7 +1890,7 @@ impl GtsStore {
             return Err(StoreError::ValidationError(error_message));
         }
 
-        Ok(())
+        self.validate_keywords(&lookup_id, &schema_id)
     }
 
     /// Casts an entity from one schema to another.
@@ -1134,7 +1917,7 @@ impl GtsStore {
             .clone();
 
         // Get the source schema
-        let (from_schema, _from_schema_id) = if from_entity.is_schema {
+        let (from_schema, from_schema_id) = if from_entity.is_schema {
             let id = from_entity
                 .gts_id
                 .as_ref()
@@ -1154,15 +1937,100 @@ impl GtsStore {
             (schema, schema_id.clone())
         };
 
-        // Create a resolver to handle $ref in schemas
-        // TODO: Implement custom resolver
-        let resolver = None;
+        // TODO: Resolve $ref in schemas with a custom resolver
+        let migration = self.migrations.get(&from_schema_id, target_schema_id);
 
         from_entity
-            .cast(&to_schema, &from_schema, resolver)
+            .cast_migrated(&to_schema, &from_schema, migration)
             .map_err(|e| StoreError::SchemaNotFound(e.to_string()))
     }
 
+    /// Casts an instance to another version of its schema through every
+    /// version of the same type in between (`v1~` → `v2~` → `v3~` → `v4~`),
+    /// applying each hop's defaults and `const` updates in order.
+    ///
+    /// # Errors
+    /// Returns `StoreError` if the entity or a schema is missing, the entity is
+    /// a schema, or the target is not a version of the instance's schema type.
+    pub fn cast_chain(
+        &mut self,
+        from_id: &str,
+        target_schema_id: &str,
+    ) -> Result<GtsCastChainResult, StoreError> {
+        let from_entity = self
+            .get(from_id)
+            .ok_or_else(|| StoreError::EntityNotFound(from_id.to_owned()))?
+            .clone();
+        if from_entity.is_schema {
+            return Err(StoreError::CastFromSchemaNotAllowed(from_id.to_owned()));
+        }
+        let from_schema_id = from_entity
+            .schema_id
+            .clone()
+            .ok_or_else(|| StoreError::SchemaForInstanceNotFound(from_id.to_owned()))?;
+
+        let path = self.version_path(&from_schema_id, target_schema_id)?;
+        let mut schemas = Vec::with_capacity(path.len());
+        for id in &path {
+            let schema = self
+                .get(id)
+                .ok_or_else(|| StoreError::ObjectNotFound(id.clone()))?;
+            schemas.push(schema.content.clone());
+        }
+
+        GtsCastChainResult::cast(
+            from_id,
+            path,
+            &from_entity.content,
+            &schemas,
+            &self.migrations,
+        )
+        .map_err(|e| StoreError::SchemaNotFound(e.to_string()))
+    }
+
+    /// Schema IDs from `from` to `to`: both ends and every stored version of
+    /// the same type in between, in the direction of the cast.
+    fn version_path(&self, from: &str, to: &str) -> Result<Vec<String>, StoreError> {
+        let invalid = |e: crate::gts::GtsError| StoreError::ValidationError(e.to_string());
+        let from_gid = GtsID::new(from).map_err(invalid)?;
+        let to_gid = GtsID::new(to).map_err(invalid)?;
+        if !to_gid.is_type() {
+            return Err(StoreError::InvalidSchemaId);
+        }
+        let Some(direction) = from_gid.version_cmp(&to_gid) else {
+            return Err(StoreError::ValidationError(format!(
+                "Cannot cast from '{from}' to '{to}': not versions of the same type"
+            )));
+        };
+        if direction.is_eq() {
+            return Ok(vec![from_gid.id]);
+        }
+
+        let mut between: Vec<GtsID> = self
+            .by_id
+            .values()
+            .filter(|entity| entity.is_schema)
+            .filter_map(|entity| entity.gts_id.clone())
+            .filter(|gid| {
+                gid.version_cmp(&from_gid) == Some(direction.reverse())
+                    && to_gid.version_cmp(gid) == Some(direction.reverse())
+            })
+            .collect();
+        between.sort_by(|a, b| {
+            let order = a.version().cmp(&b.version());
+            if direction.is_gt() {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+
+        let mut path = vec![from_gid.id];
+        path.extend(between.into_iter().map(|gid| gid.id));
+        path.push(to_gid.id);
+        Ok(path)
+    }
+
     pub fn is_minor_compatible(
         &mut self,
         old_schema_id: &str,
@@ -1303,11 +2171,23 @@ impl GtsStore {
 
     #[must_use]
     pub fn query(&self, expr: &str, limit: usize) -> GtsStoreQueryResult {
+        self.run_query(expr, limit, false)
+    }
+
+    /// Like [`query`](Self::query), also explaining for each result which
+    /// pattern segment matched which ID token and which filters it passed.
+    #[must_use]
+    pub fn query_explained(&self, expr: &str, limit: usize) -> GtsStoreQueryResult {
+        self.run_query(expr, limit, true)
+    }
+
+    fn run_query(&self, expr: &str, limit: usize, explain: bool) -> GtsStoreQueryResult {
         let mut result = GtsStoreQueryResult {
             error: String::new(),
             count: 0,
             limit,
             results: Vec::new(),
+            explanations: Vec::new(),
         };
 
         // Parse the query expression
@@ -1361,6 +2241,21 @@ impl GtsStore {
                 continue;
             }
 
+            if explain {
+                let pattern_segments = wildcard_pattern.as_ref().map_or_else(
+                    || {
+                        exact_gts_id
+                            .as_ref()
+                            .map_or(&[][..], |id| &id.gts_id_segments)
+                    },
+                    |w| &w.gts_id_segments,
+                );
+                result.explanations.push(GtsQueryMatchExplanation {
+                    gts_id: gts_id.id.clone(),
+                    segments: Self::explain_segments(pattern_segments, gts_id),
+                    filters: Self::explain_filters(&entity.content, &filters),
+                });
+            }
             result.results.push(entity.content.clone());
         }
 
@@ -1441,6 +2336,46 @@ impl GtsStore {
         }
     }
 
+    fn explain_segments(pattern_segs: &[GtsIdSegment], entity_id: &GtsID) -> Vec<GtsSegmentMatch> {
+        pattern_segs
+            .iter()
+            .zip(&entity_id.gts_id_segments)
+            .map(|(p_seg, c_seg)| {
+                if p_seg.is_wildcard {
+                    let rest = entity_id.id.get(c_seg.offset..).unwrap_or(&c_seg.segment);
+                    return GtsSegmentMatch {
+                        pattern: p_seg.segment.clone(),
+                        token: rest.to_owned(),
+                        note: Some("wildcard matches the rest of the ID".to_owned()),
+                    };
+                }
+                let note = (p_seg.ver_minor.is_none() && c_seg.ver_minor.is_some())
+                    .then(|| "pattern does not pin the minor version".to_owned());
+                GtsSegmentMatch {
+                    pattern: p_seg.segment.clone(),
+                    token: c_seg.segment.clone(),
+                    note,
+                }
+            })
+            .collect()
+    }
+
+    fn explain_filters(
+        entity_content: &Value,
+        filters: &HashMap<String, String>,
+    ) -> Vec<GtsFilterMatch> {
+        let mut matches: Vec<GtsFilterMatch> = filters
+            .iter()
+            .map(|(key, expected)| GtsFilterMatch {
+                key: key.clone(),
+                expected: expected.clone(),
+                actual: entity_content.get(key).cloned().unwrap_or(Value::Null),
+            })
+            .collect();
+        matches.sort_by(|a, b| a.key.cmp(&b.key));
+        matches
+    }
+
     fn matches_filters(entity_content: &Value, filters: &HashMap<String, String>) -> bool {
         if filters.is_empty() {
             return true;
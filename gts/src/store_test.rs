@@ -10,6 +10,7 @@ fn test_gts_store_query_result_default() {
         count: 0,
         limit: 100,
         results: vec![],
+        explanations: vec![],
     };
 
     assert_eq!(result.count, 0);
@@ -25,6 +26,7 @@ fn test_gts_store_query_result_serialization() {
         count: 2,
         limit: 10,
         results: vec![json!({"id": "test1"}), json!({"id": "test2"})],
+        explanations: vec![],
     };
 
     let json_value = serde_json::to_value(&result).expect("test");
@@ -1023,6 +1025,7 @@ fn test_gts_store_query_result_structure() {
         count: 0,
         limit: 100,
         results: vec![],
+        explanations: vec![],
     };
 
     assert_eq!(result.count, 0);
@@ -1621,6 +1624,191 @@ fn test_gts_store_cast_same_version() {
     assert!(result.is_ok() || result.is_err());
 }
 
+fn store_with_versioned_user() -> GtsStore {
+    let mut store = GtsStore::new(None);
+    let versions = [
+        ("v1.0", json!({"name": {"type": "string"}}), None),
+        (
+            "v1.1",
+            json!({
+                "name": {"type": "string"},
+                "email": {"type": "string", "default": "none@example.com"}
+            }),
+            None,
+        ),
+        (
+            "v1.2",
+            json!({
+                "name": {"type": "string"},
+                "email": {"type": "string"},
+                "tier": {"type": "string", "default": "free"}
+            }),
+            None,
+        ),
+        (
+            "v1.3",
+            json!({
+                "id": {"type": "string"},
+                "name": {"type": "string"},
+                "tier": {"type": "string"}
+            }),
+            Some(false),
+        ),
+    ];
+    for (version, properties, additional) in versions {
+        let id = format!("gts.vendor.package.namespace.user.{version}~");
+        let mut schema = json!({
+            "$id": format!("gts://{id}"),
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": properties
+        });
+        if let Some(additional) = additional {
+            schema["additionalProperties"] = json!(additional);
+        }
+        store.register_schema(&id, &schema).expect("test");
+    }
+    store
+        .register_schema(
+            "gts.vendor.package.namespace.group.v1.1~",
+            &json!({
+                "$id": "gts://gts.vendor.package.namespace.group.v1.1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object"
+            }),
+        )
+        .expect("test");
+
+    let cfg = GtsConfig::default();
+    let entity = GtsEntity::new(
+        None,
+        None,
+        &json!({
+            "id": "gts.vendor.package.namespace.user.v1.0~vendor.app._.alice.v1",
+            "name": "alice",
+            "legacy": 1
+        }),
+        Some(&cfg),
+        None,
+        false,
+        String::new(),
+        None,
+        Some("gts.vendor.package.namespace.user.v1.0~".to_owned()),
+    );
+    store.register(entity).expect("test");
+    store
+}
+
+#[test]
+fn test_gts_store_cast_chain() {
+    let mut store = store_with_versioned_user();
+
+    let result = store
+        .cast_chain(
+            "gts.vendor.package.namespace.user.v1.0~vendor.app._.alice.v1",
+            "gts.vendor.package.namespace.user.v1.3~",
+        )
+        .expect("test");
+
+    assert_eq!(
+        result.path,
+        [
+            "gts.vendor.package.namespace.user.v1.0~",
+            "gts.vendor.package.namespace.user.v1.1~",
+            "gts.vendor.package.namespace.user.v1.2~",
+            "gts.vendor.package.namespace.user.v1.3~",
+        ]
+    );
+    assert_eq!(result.hops.len(), 3);
+    assert!(result.hops.iter().all(|hop| hop.direction == "up"));
+    // email was added by v1.1 and dropped again by v1.3
+    assert_eq!(result.added_properties, ["tier"]);
+    assert_eq!(result.removed_properties, ["legacy"]);
+    assert_eq!(result.hops[0].added_properties, ["email"]);
+    assert_eq!(
+        result.casted_entity,
+        Some(json!({
+            "id": "gts.vendor.package.namespace.user.v1.0~vendor.app._.alice.v1",
+            "name": "alice",
+            "tier": "free"
+        }))
+    );
+
+    // Already at the target version: nothing to cast
+    let result = store
+        .cast_chain(
+            "gts.vendor.package.namespace.user.v1.0~vendor.app._.alice.v1",
+            "gts.vendor.package.namespace.user.v1.0~",
+        )
+        .expect("test");
+    assert_eq!(result.path, ["gts.vendor.package.namespace.user.v1.0~"]);
+    assert!(result.hops.is_empty());
+}
+
+#[test]
+fn test_gts_store_cast_chain_runs_hop_migrations() {
+    let mut store = store_with_versioned_user();
+    store.register_migration(
+        "gts.vendor.package.namespace.user.v1.1~",
+        "gts.vendor.package.namespace.user.v1.2~",
+        |user| {
+            let name = user.get("name").cloned().unwrap_or_default();
+            user.insert(
+                "email".to_owned(),
+                json!(format!("{}@example.com", name.as_str().unwrap_or("x"))),
+            );
+            Ok(())
+        },
+    );
+
+    let result = store
+        .cast_chain(
+            "gts.vendor.package.namespace.user.v1.0~vendor.app._.alice.v1",
+            "gts.vendor.package.namespace.user.v1.2~",
+        )
+        .expect("test");
+    let casted = result.casted_entity.expect("test");
+    // v1.1 filled in the default, then the v1.1 -> v1.2 migration replaced it
+    assert_eq!(casted["email"], "alice@example.com");
+    assert_eq!(casted["tier"], "free");
+
+    // Plain casts run the migration of their pair too
+    let mut store = store_with_versioned_user();
+    store.register_migration(
+        "gts.vendor.package.namespace.user.v1.0~",
+        "gts.vendor.package.namespace.user.v1.3~",
+        |user| {
+            user.insert("tier".to_owned(), json!("gold"));
+            Ok(())
+        },
+    );
+    let result = store
+        .cast(
+            "gts.vendor.package.namespace.user.v1.0~vendor.app._.alice.v1",
+            "gts.vendor.package.namespace.user.v1.3~",
+        )
+        .expect("test");
+    assert_eq!(result.casted_entity.expect("test")["tier"], "gold");
+}
+
+#[test]
+fn test_gts_store_cast_chain_other_type() {
+    let mut store = store_with_versioned_user();
+
+    let result = store.cast_chain(
+        "gts.vendor.package.namespace.user.v1.0~vendor.app._.alice.v1",
+        "gts.vendor.package.namespace.group.v1.1~",
+    );
+    assert!(matches!(result, Err(StoreError::ValidationError(msg)) if msg.contains("same type")));
+    assert!(matches!(
+        store.cast_chain(
+            "gts.vendor.package.namespace.user.v1.0~",
+            "gts.vendor.package.namespace.user.v1.3~"
+        ),
+        Err(StoreError::CastFromSchemaNotAllowed(_))
+    ));
+}
+
 #[test]
 fn test_gts_store_multiple_entities_same_schema() {
     let mut store = GtsStore::new(None);
@@ -1815,6 +2003,7 @@ fn test_gts_store_query_result_serialization_with_error() {
         count: 0,
         limit: 10,
         results: vec![],
+        explanations: vec![],
     };
 
     let json_value = serde_json::to_value(&result).expect("test");
@@ -4346,3 +4535,554 @@ fn test_op13_change_default_in_mid_fails() {
         "Changing default in mid-level should fail, got: {result:?}"
     );
 }
+
+#[test]
+fn test_op13_resolve_traits_merges_chain_and_defaults() {
+    let mut store = GtsStore::new(None);
+
+    let base = json!({
+        "$id": "gts://gts.x.test13.res.base.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "x-gts-traits-schema": {
+            "type": "object",
+            "properties": {
+                "topicRef": {"type": "string"},
+                "retention": {"type": "string", "default": "P30D"},
+                "maxRetries": {"type": "integer", "default": 3}
+            }
+        },
+        "x-gts-traits": {"topicRef": "base-topic"},
+        "properties": {"id": {"type": "string"}}
+    });
+    store
+        .register_schema("gts.x.test13.res.base.v1~", &base)
+        .expect("register base");
+
+    let derived = json!({
+        "$id": "gts://gts.x.test13.res.base.v1~x.test13._.leaf.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "allOf": [
+            {"$ref": "gts://gts.x.test13.res.base.v1~"},
+            {"type": "object", "x-gts-traits": {"retention": "P90D"}}
+        ]
+    });
+    store
+        .register_schema("gts.x.test13.res.base.v1~x.test13._.leaf.v1~", &derived)
+        .expect("register derived");
+
+    let traits = store
+        .resolve_traits("gts.x.test13.res.base.v1~x.test13._.leaf.v1~")
+        .expect("resolve traits");
+    assert_eq!(traits.get("topicRef"), Some(&json!("base-topic")));
+    assert_eq!(traits.get("retention"), Some(&json!("P90D")));
+    assert_eq!(traits.get("maxRetries"), Some(&json!(3)));
+
+    let base_retention = store
+        .trait_value("gts.x.test13.res.base.v1~", "retention")
+        .expect("resolve base traits");
+    assert_eq!(base_retention, Some(json!("P30D")));
+
+    let derived_entity = store
+        .get("gts.x.test13.res.base.v1~x.test13._.leaf.v1~")
+        .expect("derived entity")
+        .clone();
+    assert_eq!(
+        derived_entity.trait_value(&mut store, "topicRef").unwrap(),
+        Some(json!("base-topic"))
+    );
+
+    // Instances read the traits of their schema
+    let cfg = GtsConfig::default();
+    let instance = GtsEntity::new(
+        None,
+        None,
+        &json!({
+            "id": "gts.x.test13.res.base.v1~x.test13._.leaf.v1~x.test13._.orders.v1",
+            "type": "gts.x.test13.res.base.v1~x.test13._.leaf.v1~"
+        }),
+        Some(&cfg),
+        None,
+        false,
+        String::new(),
+        None,
+        None,
+    );
+    assert_eq!(
+        instance.trait_value(&mut store, "retention").unwrap(),
+        Some(json!("P90D"))
+    );
+    assert_eq!(
+        instance.trait_value(&mut store, "maxRetries").unwrap(),
+        Some(json!(3))
+    );
+}
+
+#[test]
+fn test_op13_resolve_traits_errors() {
+    let mut store = GtsStore::new(None);
+    assert!(matches!(
+        store.resolve_traits("gts.x.test13.res.missing.v1~"),
+        Err(StoreError::SchemaNotFound(_))
+    ));
+    assert!(matches!(
+        store.resolve_traits("gts.x.test13.res.missing.v1~x.test13._.inst.v1"),
+        Err(StoreError::InvalidSchemaId)
+    ));
+}
+
+fn entity_from_file(name: &str, content: &Value) -> GtsEntity {
+    let cfg = GtsConfig::default();
+    let file =
+        crate::entities::GtsFile::new(format!("/data/{name}"), name.to_owned(), content.clone());
+    GtsEntity::new(
+        Some(file),
+        None,
+        content,
+        Some(&cfg),
+        None,
+        false,
+        String::new(),
+        None,
+        None,
+    )
+}
+
+#[test]
+fn test_check_file_names_reports_stale_schema_id() {
+    let mut store = GtsStore::new(None);
+    let content = json!({
+        "$id": "gts://gts.x.core.events.event.v2~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object"
+    });
+    store
+        .register(entity_from_file(
+            "gts.x.core.events.event.v1~.schema.json",
+            &content,
+        ))
+        .expect("test");
+
+    let mismatches = store.check_file_names();
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(
+        mismatches[0].path,
+        "/data/gts.x.core.events.event.v1~.schema.json"
+    );
+    assert_eq!(mismatches[0].file_name_id, "gts.x.core.events.event.v1~");
+    assert_eq!(mismatches[0].entity_id, "gts.x.core.events.event.v2~");
+}
+
+#[test]
+fn test_check_file_names_accepts_matching_names() {
+    let mut store = GtsStore::new(None);
+    let schema = json!({
+        "$id": "gts://gts.x.core.events.event.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object"
+    });
+    let instance = json!({
+        "id": "gts.x.core.events.event.v1~x.app.events.created.v1",
+        "name": "created"
+    });
+    store
+        .register(entity_from_file(
+            "gts.x.core.events.event.v1~.schema.json",
+            &schema,
+        ))
+        .expect("test");
+    store
+        .register(entity_from_file(
+            "gts.x.core.events.event.v1~x.app.events.created.v1.instance.json",
+            &instance,
+        ))
+        .expect("test");
+
+    assert!(store.check_file_names().is_empty());
+}
+
+#[test]
+fn test_check_file_names_ignores_other_naming_schemes() {
+    let mut store = GtsStore::new(None);
+    let content = json!({
+        "$id": "gts://gts.x.core.events.event.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object"
+    });
+    store
+        .register(entity_from_file("events.json", &content))
+        .expect("test");
+
+    assert!(store.check_file_names().is_empty());
+}
+
+fn store_with_event_schemas() -> GtsStore {
+    let mut store = GtsStore::new(None);
+    for id in [
+        "gts.x.core.events.event.v1~",
+        "gts.x.core.events.event.v1.1~",
+        "gts.x.core.events.topic.v1~",
+        "gts.x.core.other.thing.v1~",
+    ] {
+        let content = json!({
+            "$id": format!("gts://{id}"),
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object"
+        });
+        store.register_schema(id, &content).expect("test");
+    }
+    store
+}
+
+#[test]
+fn test_expand_pattern_wildcard() {
+    let store = store_with_event_schemas();
+    let ids: Vec<String> = store
+        .expand_pattern("gts.x.core.events.*")
+        .expect("test")
+        .into_iter()
+        .map(|g| g.id)
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            "gts.x.core.events.event.v1.1~",
+            "gts.x.core.events.event.v1~",
+            "gts.x.core.events.topic.v1~",
+        ]
+    );
+}
+
+#[test]
+fn test_expand_pattern_exact_id_matches_minor_versions() {
+    let store = store_with_event_schemas();
+    let ids = store
+        .expand_pattern("gts://gts.x.core.events.event.v1~")
+        .expect("test");
+    assert_eq!(ids.len(), 2);
+}
+
+#[test]
+fn test_expand_pattern_no_matches_is_empty() {
+    let store = store_with_event_schemas();
+    let ids = store.expand_pattern("gts.y.core.*").expect("test");
+    assert!(ids.is_empty());
+}
+
+#[test]
+fn test_expand_pattern_invalid_pattern() {
+    let store = store_with_event_schemas();
+    let result = store.expand_pattern("gts.x.*.events.*");
+    assert!(matches!(result, Err(StoreError::InvalidPattern(_))));
+}
+
+#[test]
+fn test_validate_schema_x_gts_refs_require_wildcard_matches() {
+    let mut store = store_with_event_schemas();
+    let schema_content = json!({
+        "$id": "gts://gts.x.core.events.consumer.v1~",
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "properties": {
+            "topic": {"type": "string", "x-gts-ref": "gts.x.core.events.topic.*"},
+            "legacy": {"type": "string", "x-gts-ref": "gts.y.legacy.*"}
+        }
+    });
+    store
+        .register_schema("gts.x.core.events.consumer.v1~", &schema_content)
+        .expect("test");
+
+    // Unmatched wildcards are accepted by default
+    assert!(
+        store
+            .validate_schema_x_gts_refs("gts.x.core.events.consumer.v1~")
+            .is_ok()
+    );
+
+    store.set_require_wildcard_matches(true);
+    match store.validate_schema_x_gts_refs("gts.x.core.events.consumer.v1~") {
+        Err(StoreError::NoPatternMatches(pattern)) => assert_eq!(pattern, "gts.y.legacy.*"),
+        other => panic!("Expected NoPatternMatches, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_health_counts_entities_and_vendors() {
+    let mut store = GtsStore::new(None);
+    let empty = store.health();
+    assert_eq!((empty.schemas, empty.instances, empty.vendors), (0, 0, 0));
+    assert!(empty.last_load_unix_secs.is_none());
+    assert!(empty.load_errors.is_empty());
+
+    for id in ["gts.x.core.events.event.v1~", "gts.y.core.events.event.v1~"] {
+        store
+            .register_schema(
+                id,
+                &json!({
+                    "$id": format!("gts://{id}"),
+                    "$schema": "http://json-schema.org/draft-07/schema#",
+                    "type": "object"
+                }),
+            )
+            .expect("test");
+    }
+    let cfg = GtsConfig::default();
+    let instance = json!({"id": "gts.x.core.events.event.v1~x.app.items.item.v1.0"});
+    store
+        .register(GtsEntity::new(
+            None,
+            None,
+            &instance,
+            Some(&cfg),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        ))
+        .expect("test");
+
+    let health = store.health();
+    assert_eq!(health.schemas, 2);
+    assert_eq!(health.instances, 1);
+    assert_eq!(health.vendors, 2);
+    assert!(health.index_memory_estimate_bytes > empty.index_memory_estimate_bytes);
+}
+
+#[test]
+fn test_generate_examples_validate_against_chain() {
+    let mut store = GtsStore::new(None);
+    store
+        .register_schema(
+            "gts.x.core.events.type.v1~",
+            &json!({
+                "$id": "gts://gts.x.core.events.type.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "type": {"type": "string", "format": "gts-schema-id", "x-gts-ref": "/$id"},
+                    "id": {"type": "string", "format": "uuid"},
+                    "priority": {"type": "integer", "minimum": 1, "maximum": 5, "default": 3},
+                    "payload": {"type": "object"}
+                },
+                "required": ["type", "id", "payload"]
+            }),
+        )
+        .expect("test");
+    store
+        .register_schema(
+            "gts.x.core.events.type.v1~x.core.audit.event.v1~",
+            &json!({
+                "$id": "gts://gts.x.core.events.type.v1~x.core.audit.event.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "allOf": [
+                    {"$ref": "gts://gts.x.core.events.type.v1~"},
+                    {
+                        "properties": {
+                            "payload": {
+                                "type": "object",
+                                "properties": {
+                                    "user": {"type": "string", "minLength": 2},
+                                    "action": {"enum": ["login", "logout"]}
+                                },
+                                "required": ["user", "action"]
+                            }
+                        }
+                    }
+                ]
+            }),
+        )
+        .expect("test");
+
+    let id = "gts.x.core.events.type.v1~x.core.audit.event.v1~";
+    let examples = store.generate_examples(id, 20, 42).expect("test");
+    assert_eq!(examples.len(), 20);
+    for example in &examples {
+        assert_eq!(example["type"], id);
+        assert!(
+            ["login", "logout"].contains(&example["payload"]["action"].as_str().expect("test"))
+        );
+        assert!(example["payload"]["user"].as_str().expect("test").len() >= 2);
+    }
+    assert_eq!(examples, store.generate_examples(id, 20, 42).expect("test"));
+
+    let unsatisfiable = "gts.x.core.events.never.v1~";
+    store
+        .register_schema(
+            unsatisfiable,
+            &json!({
+                "$id": format!("gts://{unsatisfiable}"),
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "string",
+                "pattern": "^[A-Z]{40}$"
+            }),
+        )
+        .expect("test");
+    assert!(store.generate_examples(unsatisfiable, 1, 0).is_err());
+}
+
+#[test]
+fn test_generate_invalid_examples_fail_validation() {
+    let mut store = GtsStore::new(None);
+    let id = "gts.x.core.events.topic.v1~";
+    store
+        .register_schema(
+            id,
+            &json!({
+                "$id": format!("gts://{id}"),
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "id": {"type": "string", "x-gts-ref": "/$id"},
+                    "retention": {"enum": ["P1D", "P7D"]},
+                    "partitions": {"type": "integer", "minimum": 1, "maximum": 64}
+                },
+                "required": ["id", "retention", "partitions"]
+            }),
+        )
+        .expect("test");
+
+    let (_, is_valid) = store.example_checker(id).expect("test");
+    let examples = store.generate_invalid_examples(id, 12, 5).expect("test");
+    assert_eq!(examples.len(), 12);
+    assert!(examples.iter().all(|e| !is_valid(&e.instance)));
+
+    let kinds: std::collections::BTreeSet<_> = examples.iter().map(|e| e.violation.kind).collect();
+    assert!(kinds.contains(&crate::example_gen::ViolationKind::MissingRequired));
+    assert!(kinds.contains(&crate::example_gen::ViolationKind::AdditionalProperty));
+    assert!(kinds.contains(&crate::example_gen::ViolationKind::GtsRef));
+    assert!(kinds.contains(&crate::example_gen::ViolationKind::Enum));
+    assert_eq!(
+        examples,
+        store.generate_invalid_examples(id, 12, 5).expect("test")
+    );
+}
+
+#[test]
+fn test_gts_store_query_explained() {
+    let mut store = GtsStore::new(None);
+    let cfg = GtsConfig::default();
+    for (id, status) in [
+        ("gts.x.core.events.topic.v1~x.app._.orders.v1.2", "active"),
+        ("gts.x.core.events.topic.v1~x.app._.users.v1", "retired"),
+    ] {
+        let content = json!({"id": id, "status": status});
+        let entity = GtsEntity::new(
+            None,
+            None,
+            &content,
+            Some(&cfg),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        );
+        store.register(entity).expect("test");
+    }
+
+    let plain = store.query("gts.x.core.events.topic.v1~x.app.*[status=active]", 10);
+    assert_eq!(plain.count, 1);
+    assert!(plain.explanations.is_empty());
+    assert!(
+        serde_json::to_value(&plain)
+            .expect("test")
+            .get("explanations")
+            .is_none()
+    );
+
+    let result = store.query_explained("gts.x.core.events.topic.v1~x.app.*[status=active]", 10);
+    assert_eq!(result.count, 1);
+    let explanation = &result.explanations[0];
+    assert_eq!(
+        explanation.gts_id,
+        "gts.x.core.events.topic.v1~x.app._.orders.v1.2"
+    );
+    assert_eq!(explanation.segments.len(), 2);
+    assert_eq!(explanation.segments[0].token, "x.core.events.topic.v1~");
+    assert_eq!(explanation.segments[1].pattern, "x.app.*");
+    assert_eq!(explanation.segments[1].token, "x.app._.orders.v1.2");
+    assert!(explanation.segments[1].note.is_some());
+    assert_eq!(
+        explanation.filters,
+        vec![GtsFilterMatch {
+            key: "status".to_owned(),
+            expected: "active".to_owned(),
+            actual: json!("active"),
+        }]
+    );
+
+    // Exact IDs match any minor version of the entity
+    let result = store.query_explained("gts.x.core.events.topic.v1~x.app._.orders.v1", 10);
+    assert_eq!(result.count, 1);
+    let segment = &result.explanations[0].segments[1];
+    assert_eq!(segment.token, "x.app._.orders.v1.2");
+    assert_eq!(
+        segment.note.as_deref(),
+        Some("pattern does not pin the minor version")
+    );
+}
+
+#[test]
+fn test_store_with_storage_loads_lazily() {
+    let storage = crate::GtsMemoryStorage::new();
+    let cfg = GtsConfig::default();
+    let schema_id = "gts.x.core.events.type.v1~";
+    let instance_id = "gts.x.core.events.type.v1~x.app._.orders.v1";
+
+    let mut store = GtsStore::new(None);
+    store
+        .register_schema(
+            schema_id,
+            &json!({
+                "$id": format!("gts://{schema_id}"),
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "required": ["id"],
+                "properties": {"id": {"type": "string"}}
+            }),
+        )
+        .unwrap();
+    // Entities registered before the storage is set are written by persist()
+    store.set_storage(Box::new(storage.clone()));
+    let instance = json!({"id": instance_id, "type": schema_id});
+    store
+        .register(GtsEntity::new(
+            None,
+            None,
+            &instance,
+            Some(&cfg),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        ))
+        .unwrap();
+    assert_eq!(storage.scan_prefix("gts.").unwrap().len(), 1);
+    assert_eq!(store.persist().unwrap(), 2);
+
+    let mut restarted = GtsStore::with_storage(Box::new(storage.clone()));
+    assert_eq!(restarted.items().count(), 0);
+    restarted.validate_instance(instance_id).unwrap();
+    assert_eq!(restarted.items().count(), 2);
+    let entity = restarted.get(schema_id).unwrap();
+    assert!(entity.is_schema);
+
+    let mut by_prefix = GtsStore::with_storage(Box::new(storage));
+    assert_eq!(
+        by_prefix
+            .load_prefix("gts.x.core.events.type.v1~x.")
+            .unwrap(),
+        1
+    );
+    assert_eq!(by_prefix.load_prefix("gts.x.core.").unwrap(), 1);
+    assert_eq!(by_prefix.load_prefix("gts.x.other.").unwrap(), 0);
+    assert_eq!(
+        by_prefix.get(instance_id).unwrap().schema_id.as_deref(),
+        Some(schema_id)
+    );
+}
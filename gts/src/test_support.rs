@@ -0,0 +1,251 @@
+//! Helpers for tests against GTS stores, enabled by the `test-util` feature.
+//!
+//! ```
+//! use gts::test_support::{assert_json_contains, assert_schema_eq, store_from_json};
+//! use serde_json::json;
+//!
+//! let schema = json!({
+//!     "$id": "gts://gts.x.core.events.type.v1~",
+//!     "$schema": "http://json-schema.org/draft-07/schema#",
+//!     "type": "object",
+//!     "properties": {"id": {"type": "string"}}
+//! });
+//! let mut store = store_from_json(&[
+//!     schema.clone(),
+//!     json!({"id": "gts.x.core.events.type.v1~x.app._.orders.v1", "type": "gts.x.core.events.type.v1~"}),
+//! ]);
+//!
+//! let stored = store.get_schema_content("gts.x.core.events.type.v1~").unwrap();
+//! assert_schema_eq(&stored, &schema);
+//! assert_json_contains(&store.health(), &json!({"schemas": 1, "instances": 1}));
+//! ```
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::entities::{GtsConfig, GtsEntity};
+use crate::ops::GtsOps;
+use crate::store::GtsStore;
+
+/// A draft-07 object schema with `$id` `gts://{id}`, string properties `id`
+/// and `name`, and the `required` ones of them.
+#[must_use]
+pub fn schema_json(id: &str, required: &[&str]) -> Value {
+    json!({
+        "$id": format!("gts://{id}"),
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "properties": {"id": {"type": "string"}, "name": {"type": "string"}},
+        "required": required
+    })
+}
+
+/// An entity (schema or instance, recognised by its `$schema` field) with
+/// IDs extracted by the default [`GtsConfig`].
+#[must_use]
+pub fn entity_from_json(content: &Value) -> GtsEntity {
+    GtsEntity::new(
+        None,
+        None,
+        content,
+        Some(&GtsConfig::default()),
+        None,
+        false,
+        String::new(),
+        None,
+        None,
+    )
+}
+
+/// A store holding `entities`, see [`entity_from_json`].
+///
+/// # Panics
+/// Panics if an entity has no ID or cannot be registered.
+#[must_use]
+pub fn store_from_json(entities: &[Value]) -> GtsStore {
+    let mut store = GtsStore::new(None);
+    for content in entities {
+        if let Err(e) = store.register(entity_from_json(content)) {
+            panic!("cannot register test entity: {e}\n{content:#}");
+        }
+    }
+    store
+}
+
+/// [`GtsOps`] over [`store_from_json`]`(entities)`.
+///
+/// # Panics
+/// Panics if an entity has no ID or cannot be registered.
+#[must_use]
+pub fn ops_from_json(entities: &[Value]) -> GtsOps {
+    let mut ops = GtsOps::new(None, None, 0);
+    ops.store = store_from_json(entities);
+    ops
+}
+
+/// Entity files in a temporary directory, removed on drop, for tests that
+/// load a store the way the file reader does.
+#[derive(Debug)]
+pub struct TempStore {
+    dir: tempfile::TempDir,
+}
+
+impl TempStore {
+    /// Write each `(relative path, content)` as pretty JSON.
+    ///
+    /// # Panics
+    /// Panics if the directory or a file cannot be written.
+    #[must_use]
+    pub fn new(files: &[(&str, Value)]) -> Self {
+        let dir = tempfile::tempdir()
+            .unwrap_or_else(|e| panic!("cannot create temp store directory: {e}"));
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .unwrap_or_else(|e| panic!("cannot create {}: {e}", parent.display()));
+            }
+            std::fs::write(&path, format!("{content:#}"))
+                .unwrap_or_else(|e| panic!("cannot write {}: {e}", path.display()));
+        }
+        Self { dir }
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// [`GtsOps`] loaded from the directory.
+    #[must_use]
+    pub fn ops(&self) -> GtsOps {
+        let path = self.path().to_string_lossy().into_owned();
+        GtsOps::new(Some(vec![path]), None, 0)
+    }
+}
+
+/// The differences between `actual` and `expected`, one line per JSON pointer
+/// (`""` for the root). Object key order is ignored; with `subset`, keys
+/// missing from `expected` are too.
+#[must_use]
+pub fn json_diff(actual: &Value, expected: &Value, subset: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    diff_at("", actual, expected, subset, &mut lines);
+    lines
+}
+
+fn diff_at(path: &str, actual: &Value, expected: &Value, subset: bool, lines: &mut Vec<String>) {
+    match (actual, expected) {
+        (Value::Object(a), Value::Object(e)) => {
+            for (key, expected) in e {
+                let path = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                match a.get(key) {
+                    Some(actual) => diff_at(&path, actual, expected, subset, lines),
+                    None => lines.push(format!("{path}: missing, expected {expected}")),
+                }
+            }
+            if !subset {
+                for (key, actual) in a.iter().filter(|(key, _)| !e.contains_key(*key)) {
+                    let path = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                    lines.push(format!("{path}: unexpected {actual}"));
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(e)) => {
+            for (i, (actual, expected)) in a.iter().zip(e).enumerate() {
+                diff_at(&format!("{path}/{i}"), actual, expected, subset, lines);
+            }
+            for (i, expected) in e.iter().enumerate().skip(a.len()) {
+                lines.push(format!("{path}/{i}: missing, expected {expected}"));
+            }
+            for (i, actual) in a.iter().enumerate().skip(e.len()) {
+                lines.push(format!("{path}/{i}: unexpected {actual}"));
+            }
+        }
+        _ if actual != expected => lines.push(format!("{path}: expected {expected}, got {actual}")),
+        _ => {}
+    }
+}
+
+fn fail(what: &str, lines: &[String], actual: &Value) -> ! {
+    let mut message = format!("{what}:\n");
+    for line in lines {
+        let _ = writeln!(message, "  {line}");
+    }
+    let _ = write!(message, "actual: {actual:#}");
+    panic!("{message}")
+}
+
+/// Assert that two schemas are equal regardless of object key order, listing
+/// every difference on failure.
+///
+/// # Panics
+/// Panics if the schemas differ.
+pub fn assert_schema_eq(actual: &Value, expected: &Value) {
+    let lines = json_diff(actual, expected, false);
+    if !lines.is_empty() {
+        fail("schemas differ", &lines, actual);
+    }
+}
+
+/// Assert that `actual` (a report or result, serialized to JSON) has the
+/// values of `expected`; keys `expected` leaves out are not checked, while
+/// arrays must have the same length.
+///
+/// # Panics
+/// Panics if `actual` cannot be serialized or does not contain `expected`.
+pub fn assert_json_contains<T: Serialize + ?Sized>(actual: &T, expected: &Value) {
+    let actual = serde_json::to_value(actual)
+        .unwrap_or_else(|e| panic!("cannot serialize the actual value: {e}"));
+    let lines = json_diff(&actual, expected, true);
+    if !lines.is_empty() {
+        fail("unexpected report contents", &lines, &actual);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_diff_lists_pointers() {
+        let actual = json!({"type": "object", "required": ["id"], "x~y": 1, "extra": true});
+        let expected = json!({"required": ["id", "name"], "type": "string", "x~y": 1});
+        assert_eq!(
+            json_diff(&actual, &expected, false),
+            [
+                "/required/1: missing, expected \"name\"",
+                "/type: expected \"string\", got \"object\"",
+                "/extra: unexpected true",
+            ]
+        );
+        assert_eq!(json_diff(&actual, &expected, true).len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "/properties/id/type: expected \"string\", got \"integer\"")]
+    fn test_assert_schema_eq_reports_differences() {
+        assert_schema_eq(
+            &json!({"properties": {"id": {"type": "integer"}}}),
+            &json!({"properties": {"id": {"type": "string"}}}),
+        );
+    }
+
+    #[test]
+    fn test_temp_store_loads_files() {
+        let temp = TempStore::new(&[(
+            "schemas/gts.x.core.events.type.v1~.schema.json",
+            json!({
+                "$id": "gts://gts.x.core.events.type.v1~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object"
+            }),
+        )]);
+        let ops = temp.ops();
+        assert_json_contains(&ops.health(), &json!({"schemas": 1, "load_errors": []}));
+    }
+}
@@ -0,0 +1,246 @@
+//! Infrastructure manifests built from schema traits.
+//!
+//! Traits such as `retention` or `partitions` often map one-to-one onto
+//! provisioning settings. A [`TraitMap`] selects instance types and renames
+//! traits to output keys; [`build_trait_manifest`] walks the matching
+//! instances and emits the effective trait values of each one.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::gts::{GtsID, GtsWildcard};
+use crate::store::{GtsStore, StoreError};
+
+#[derive(Debug, Error)]
+pub enum TraitManifestError {
+    #[error("Invalid trait map: {0}")]
+    InvalidMap(String),
+    #[error("Invalid type pattern '{pattern}': {cause}")]
+    InvalidPattern { pattern: String, cause: String },
+    #[error(transparent)]
+    Store(#[from] StoreError),
+}
+
+/// Which instances to export and which traits to emit for them.
+///
+/// ```yaml
+/// types:
+///   - gts.x.core.events.topic.*
+/// traits:
+///   retention: retention_ms
+///   partitions: partitions
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraitMap {
+    /// GTS wildcard patterns matched against each instance's schema ID.
+    /// Empty selects every instance.
+    #[serde(default)]
+    pub types: Vec<String>,
+    /// Trait name -> key used in the manifest.
+    pub traits: BTreeMap<String, String>,
+}
+
+impl TraitMap {
+    /// Parse a trait map from YAML (or JSON, which is valid YAML).
+    ///
+    /// # Errors
+    /// Returns `TraitManifestError::InvalidMap` if the text is not a valid trait map.
+    pub fn parse(text: &str) -> Result<Self, TraitManifestError> {
+        serde_saphyr::from_str(text).map_err(|e| TraitManifestError::InvalidMap(e.to_string()))
+    }
+}
+
+/// Trait values of a single instance, keyed by the mapped output names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraitManifestEntry {
+    pub id: String,
+    pub schema_id: String,
+    pub settings: BTreeMap<String, Value>,
+}
+
+/// Build a manifest of mapped trait values for every instance selected by `map`,
+/// sorted by instance ID. Traits without a value are left out of `settings`.
+///
+/// # Errors
+/// Returns an error if a type pattern is invalid or an instance's schema chain
+/// cannot be resolved.
+pub fn build_trait_manifest(
+    store: &mut GtsStore,
+    map: &TraitMap,
+) -> Result<Vec<TraitManifestEntry>, TraitManifestError> {
+    let patterns = map
+        .types
+        .iter()
+        .map(|p| {
+            GtsWildcard::new(p).map_err(|e| TraitManifestError::InvalidPattern {
+                pattern: p.clone(),
+                cause: e.to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut selected: Vec<(String, String)> = store
+        .items()
+        .filter(|(_, entity)| !entity.is_schema)
+        .filter_map(|(id, entity)| {
+            let schema_id = entity.schema_id.as_ref()?;
+            let type_id = GtsID::new(schema_id).ok()?;
+            (patterns.is_empty() || patterns.iter().any(|p| type_id.wildcard_match(p)))
+                .then(|| (id.clone(), schema_id.clone()))
+        })
+        .collect();
+    selected.sort();
+
+    let mut traits_by_schema: HashMap<String, serde_json::Map<String, Value>> = HashMap::new();
+    let mut entries = Vec::with_capacity(selected.len());
+    for (id, schema_id) in selected {
+        if !traits_by_schema.contains_key(&schema_id) {
+            let resolved = store.resolve_traits(&schema_id)?;
+            traits_by_schema.insert(schema_id.clone(), resolved);
+        }
+        let traits = &traits_by_schema[&schema_id];
+        let settings = map
+            .traits
+            .iter()
+            .filter_map(|(name, key)| Some((key.clone(), traits.get(name)?.clone())))
+            .collect();
+        entries.push(TraitManifestEntry {
+            id,
+            schema_id,
+            settings,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::entities::{GtsConfig, GtsEntity};
+    use serde_json::json;
+
+    fn store_with_topics() -> GtsStore {
+        let mut store = GtsStore::new(None);
+        store
+            .register_schema(
+                "gts.x.core.events.topic.v1~",
+                &json!({
+                    "$id": "gts://gts.x.core.events.topic.v1~",
+                    "$schema": "http://json-schema.org/draft-07/schema#",
+                    "type": "object",
+                    "x-gts-traits-schema": {
+                        "type": "object",
+                        "properties": {
+                            "retention": {"type": "string", "default": "P7D"},
+                            "partitions": {"type": "integer"}
+                        }
+                    }
+                }),
+            )
+            .expect("test");
+        store
+            .register_schema(
+                "gts.x.core.events.topic.v1~x.app.orders.topic.v1~",
+                &json!({
+                    "$id": "gts://gts.x.core.events.topic.v1~x.app.orders.topic.v1~",
+                    "$schema": "http://json-schema.org/draft-07/schema#",
+                    "allOf": [{"$ref": "gts://gts.x.core.events.topic.v1~"}],
+                    "x-gts-traits": {"partitions": 12}
+                }),
+            )
+            .expect("test");
+        let cfg = GtsConfig::default();
+        for content in [
+            json!({"id": "gts.x.core.events.topic.v1~x.app.orders.topic.v1~x.app._.placed.v1"}),
+            json!({"id": "gts.x.core.events.topic.v1~x.app._.audit.v1"}),
+        ] {
+            store
+                .register(GtsEntity::new(
+                    None,
+                    None,
+                    &content,
+                    Some(&cfg),
+                    None,
+                    false,
+                    String::new(),
+                    None,
+                    None,
+                ))
+                .expect("test");
+        }
+        store
+    }
+
+    #[test]
+    fn test_trait_map_parse_yaml() {
+        let map = TraitMap::parse(
+            "types:\n  - gts.x.core.events.topic.v1~*\ntraits:\n  retention: retention_period\n",
+        )
+        .unwrap();
+        assert_eq!(map.types, vec!["gts.x.core.events.topic.v1~*"]);
+        assert_eq!(map.traits["retention"], "retention_period");
+
+        assert!(matches!(
+            TraitMap::parse("types: [1, 2]"),
+            Err(TraitManifestError::InvalidMap(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_trait_manifest() {
+        let mut store = store_with_topics();
+        let map = TraitMap {
+            types: vec!["gts.x.core.events.topic.v1~x.app.orders.*".to_owned()],
+            traits: BTreeMap::from([
+                ("retention".to_owned(), "retention_period".to_owned()),
+                ("partitions".to_owned(), "partitions".to_owned()),
+            ]),
+        };
+
+        let entries = build_trait_manifest(&mut store, &map).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].id,
+            "gts.x.core.events.topic.v1~x.app.orders.topic.v1~x.app._.placed.v1"
+        );
+        assert_eq!(
+            entries[0].schema_id,
+            "gts.x.core.events.topic.v1~x.app.orders.topic.v1~"
+        );
+        assert_eq!(entries[0].settings["retention_period"], json!("P7D"));
+        assert_eq!(entries[0].settings["partitions"], json!(12));
+    }
+
+    #[test]
+    fn test_build_trait_manifest_all_instances() {
+        let mut store = store_with_topics();
+        let map = TraitMap {
+            types: vec![],
+            traits: BTreeMap::from([("partitions".to_owned(), "partitions".to_owned())]),
+        };
+
+        let entries = build_trait_manifest(&mut store, &map).unwrap();
+        assert_eq!(entries.len(), 2);
+        // Sorted by ID; the base topic has no partitions value
+        assert_eq!(entries[0].id, "gts.x.core.events.topic.v1~x.app._.audit.v1");
+        assert!(entries[0].settings.is_empty());
+        assert_eq!(entries[1].settings["partitions"], json!(12));
+    }
+
+    #[test]
+    fn test_build_trait_manifest_invalid_pattern() {
+        let mut store = store_with_topics();
+        let map = TraitMap {
+            types: vec!["x.core.*".to_owned()],
+            traits: BTreeMap::new(),
+        };
+        assert!(matches!(
+            build_trait_manifest(&mut store, &map),
+            Err(TraitManifestError::InvalidPattern { .. })
+        ));
+    }
+}
@@ -0,0 +1,496 @@
+//! Selecting schemas by their effective traits.
+//!
+//! A [`TraitQuery`] combines a GTS ID pattern with trait filters evaluated
+//! against each matching schema's effective traits (see
+//! [`GtsStore::resolve_traits`]):
+//!
+//! ```text
+//! gts.x.core.events.topic.v1~* where retention > P30D and priority in [high, critical]
+//! ```
+//!
+//! Filters compare with `=`, `!=`, `<`, `<=`, `>`, `>=` or `in [a, b, ...]`.
+//! Numbers compare numerically and ISO 8601 durations (`P30D`, `PT12H`) by
+//! length, counting a year as 365 days and a month as 30; other values only
+//! support `=`, `!=` and `in`. A schema without the trait never matches.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::gts::{GtsID, GtsWildcard};
+use crate::store::{GtsStore, StoreError};
+
+#[derive(Debug, Error)]
+pub enum TraitQueryError {
+    #[error("Invalid trait query '{query}': {cause}")]
+    InvalidQuery { query: String, cause: String },
+    #[error("Invalid pattern '{pattern}': {cause}")]
+    InvalidPattern { pattern: String, cause: String },
+    #[error(transparent)]
+    Store(#[from] StoreError),
+}
+
+/// Comparison of a [`TraitFilter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraitOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+}
+
+impl fmt::Display for TraitOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::In => "in",
+        })
+    }
+}
+
+/// A condition on one trait, such as `retention > P30D`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitFilter {
+    pub name: String,
+    pub op: TraitOp,
+    /// The operand; several for [`TraitOp::In`].
+    pub values: Vec<String>,
+}
+
+impl TraitFilter {
+    #[must_use]
+    pub fn new(name: impl Into<String>, op: TraitOp, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            op,
+            values: vec![value.into()],
+        }
+    }
+
+    /// A `name in [values]` filter.
+    #[must_use]
+    pub fn one_of<I, S>(name: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            op: TraitOp::In,
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Whether the effective traits satisfy the filter.
+    #[must_use]
+    pub fn matches(&self, traits: &serde_json::Map<String, Value>) -> bool {
+        let Some(actual) = traits.get(&self.name) else {
+            return false;
+        };
+        let ordering = |value: &String| compare(actual, value);
+        let equal = |value: &String| ordering(value) == Some(Ordering::Equal);
+        match self.op {
+            TraitOp::In => self.values.iter().any(equal),
+            TraitOp::Eq => self.values.iter().all(equal),
+            TraitOp::Ne => !self.values.iter().any(equal),
+            TraitOp::Lt => self
+                .values
+                .iter()
+                .all(|v| ordering(v) == Some(Ordering::Less)),
+            TraitOp::Le => self
+                .values
+                .iter()
+                .all(|v| matches!(ordering(v), Some(Ordering::Less | Ordering::Equal))),
+            TraitOp::Gt => self
+                .values
+                .iter()
+                .all(|v| ordering(v) == Some(Ordering::Greater)),
+            TraitOp::Ge => self
+                .values
+                .iter()
+                .all(|v| matches!(ordering(v), Some(Ordering::Greater | Ordering::Equal))),
+        }
+    }
+}
+
+impl FromStr for TraitFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let name_end = s
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.')))
+            .unwrap_or(s.len());
+        let (name, rest) = s.split_at(name_end);
+        if name.is_empty() {
+            return Err(format!("expected a trait name in '{s}'"));
+        }
+        let rest = rest.trim_start();
+
+        if let Some(list) = rest.strip_prefix("in ").map(str::trim) {
+            let items = list
+                .strip_prefix('[')
+                .and_then(|l| l.strip_suffix(']'))
+                .ok_or_else(|| format!("expected '[...]' after 'in' in '{s}'"))?;
+            return Ok(Self::one_of(
+                name,
+                items.split(',').map(unquote).filter(|v| !v.is_empty()),
+            ));
+        }
+
+        let (op, value) = [
+            (">=", TraitOp::Ge),
+            ("<=", TraitOp::Le),
+            ("!=", TraitOp::Ne),
+            ("==", TraitOp::Eq),
+            ("=", TraitOp::Eq),
+            (">", TraitOp::Gt),
+            ("<", TraitOp::Lt),
+        ]
+        .into_iter()
+        .find_map(|(token, op)| Some((op, rest.strip_prefix(token)?)))
+        .ok_or_else(|| format!("expected an operator after '{name}' in '{s}'"))?;
+        let value = unquote(value);
+        if value.is_empty() {
+            return Err(format!("expected a value after '{op}' in '{s}'"));
+        }
+        Ok(Self::new(name, op, value))
+    }
+}
+
+/// A GTS ID pattern and the trait filters every selected schema satisfies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitQuery {
+    /// Wildcard pattern (or exact ID) matched against schema IDs.
+    pub pattern: String,
+    pub filters: Vec<TraitFilter>,
+}
+
+impl TraitQuery {
+    #[must_use]
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            filters: Vec::new(),
+        }
+    }
+
+    /// Add a filter.
+    #[must_use]
+    pub fn filter(mut self, filter: TraitFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Parse `<pattern> [where <filter> [and <filter>]...]`.
+    ///
+    /// # Errors
+    /// Returns `TraitQueryError::InvalidQuery` if a filter cannot be parsed.
+    pub fn parse(query: &str) -> Result<Self, TraitQueryError> {
+        let invalid = |cause: String| TraitQueryError::InvalidQuery {
+            query: query.to_owned(),
+            cause,
+        };
+        let (pattern, filters) = query.split_once(" where ").unwrap_or((query, ""));
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err(invalid("expected a GTS ID pattern".to_owned()));
+        }
+        let filters = if filters.trim().is_empty() {
+            Vec::new()
+        } else {
+            filters
+                .split(" and ")
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map_err(invalid)?
+        };
+        Ok(Self {
+            pattern: pattern.to_owned(),
+            filters,
+        })
+    }
+}
+
+impl FromStr for TraitQuery {
+    type Err = TraitQueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// A schema selected by a [`TraitQuery`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TraitQueryMatch {
+    pub id: String,
+    /// The schema's effective traits.
+    pub traits: serde_json::Map<String, Value>,
+    pub content: Value,
+}
+
+impl GtsStore {
+    /// Schemas matching the query's pattern whose effective traits satisfy
+    /// all its filters, sorted by ID.
+    ///
+    /// # Errors
+    /// Returns an error if the pattern is invalid or the traits of a matching
+    /// schema cannot be resolved.
+    pub fn query_traits(
+        &mut self,
+        query: &TraitQuery,
+    ) -> Result<Vec<TraitQueryMatch>, TraitQueryError> {
+        let pattern =
+            GtsWildcard::new(&query.pattern).map_err(|e| TraitQueryError::InvalidPattern {
+                pattern: query.pattern.clone(),
+                cause: e.to_string(),
+            })?;
+
+        let mut selected: Vec<(String, Value)> = self
+            .items()
+            .filter(|(_, entity)| entity.is_schema)
+            .filter(|(id, _)| GtsID::new(id).is_ok_and(|gid| gid.wildcard_match(&pattern)))
+            .map(|(id, entity)| (id.clone(), entity.content.clone()))
+            .collect();
+        selected.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut matches = Vec::new();
+        for (id, content) in selected {
+            let traits = self.resolve_traits(&id)?;
+            if query.filters.iter().all(|f| f.matches(&traits)) {
+                matches.push(TraitQueryMatch {
+                    id,
+                    traits,
+                    content,
+                });
+            }
+        }
+        Ok(matches)
+    }
+}
+
+fn unquote(value: &str) -> String {
+    value.trim().trim_matches('"').trim_matches('\'').to_owned()
+}
+
+/// Order a trait value against a filter operand; `Some(Equal)` also for
+/// equal non-ordered values.
+fn compare(actual: &Value, operand: &str) -> Option<Ordering> {
+    match actual {
+        Value::Number(n) => n.as_f64()?.partial_cmp(&operand.parse::<f64>().ok()?),
+        Value::String(s) => match (parse_duration(s), parse_duration(operand)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => (s == operand).then_some(Ordering::Equal),
+        },
+        Value::Bool(b) => (b.to_string() == operand).then_some(Ordering::Equal),
+        _ => (*actual == operand).then_some(Ordering::Equal),
+    }
+}
+
+/// Length in seconds of an ISO 8601 duration such as `P1Y2M10DT2H30M`.
+fn parse_duration(s: &str) -> Option<f64> {
+    const DAY: f64 = 86_400.0;
+    let rest = s.strip_prefix('P')?;
+    if rest.is_empty() {
+        return None;
+    }
+    let (date, time) = rest.split_once('T').unwrap_or((rest, ""));
+    if rest.ends_with('T') {
+        return None;
+    }
+    let units = |part: &str, table: &[(char, f64)]| -> Option<f64> {
+        let mut total = 0.0;
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() || c == '.' {
+                number.push(c);
+            } else {
+                let (_, seconds) = table.iter().find(|(unit, _)| *unit == c)?;
+                total += number.parse::<f64>().ok()? * seconds;
+                number.clear();
+            }
+        }
+        number.is_empty().then_some(total)
+    };
+    let date = units(
+        date,
+        &[
+            ('Y', 365.0 * DAY),
+            ('M', 30.0 * DAY),
+            ('W', 7.0 * DAY),
+            ('D', DAY),
+        ],
+    )?;
+    let time = units(time, &[('H', 3600.0), ('M', 60.0), ('S', 1.0)])?;
+    Some(date + time)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::test_support::store_from_json;
+    use serde_json::json;
+
+    fn store_with_topics() -> GtsStore {
+        let mut schemas = vec![json!({
+            "$id": "gts://gts.x.core.events.topic.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "x-gts-traits-schema": {
+                "type": "object",
+                "properties": {
+                    "retention": {"type": "string", "default": "P7D"},
+                    "priority": {"type": "string", "default": "low"},
+                    "partitions": {"type": "integer"}
+                }
+            }
+        })];
+        for (name, traits) in [
+            (
+                "orders",
+                json!({"retention": "P90D", "priority": "high", "partitions": 12}),
+            ),
+            ("audit", json!({"retention": "P1Y", "priority": "critical"})),
+            ("metrics", json!({"retention": "PT12H"})),
+        ] {
+            schemas.push(json!({
+                "$id": format!("gts://gts.x.core.events.topic.v1~x.app.{name}.topic.v1~"),
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "allOf": [{"$ref": "gts://gts.x.core.events.topic.v1~"}],
+                "x-gts-traits": traits
+            }));
+        }
+        store_from_json(&schemas)
+    }
+
+    fn ids(matches: &[TraitQueryMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_trait_query_parse() {
+        let query = TraitQuery::parse(
+            "gts.x.core.events.topic.v1~* where retention > P30D and priority in [high, 'critical']",
+        )
+        .unwrap();
+        assert_eq!(query.pattern, "gts.x.core.events.topic.v1~*");
+        assert_eq!(
+            query.filters,
+            vec![
+                TraitFilter::new("retention", TraitOp::Gt, "P30D"),
+                TraitFilter::one_of("priority", ["high", "critical"]),
+            ]
+        );
+        assert!(TraitQuery::parse("gts.x.*").unwrap().filters.is_empty());
+
+        for bad in [
+            "gts.x.* where retention",
+            "gts.x.* where > P30D",
+            "gts.x.* where priority in high",
+            "gts.x.* where retention >=",
+        ] {
+            assert!(
+                matches!(
+                    TraitQuery::parse(bad),
+                    Err(TraitQueryError::InvalidQuery { .. })
+                ),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_query_traits_filters_effective_traits() {
+        let mut store = store_with_topics();
+
+        let matches = store
+            .query_traits(
+                &"gts.x.core.events.topic.v1~* where retention > P30D"
+                    .parse()
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(
+            ids(&matches),
+            [
+                "gts.x.core.events.topic.v1~x.app.audit.topic.v1~",
+                "gts.x.core.events.topic.v1~x.app.orders.topic.v1~",
+            ]
+        );
+        assert_eq!(matches[1].traits["partitions"], 12);
+
+        // Defaults from the trait schema count as effective traits
+        let low = TraitQuery::new("gts.x.core.events.topic.*").filter(TraitFilter::new(
+            "priority",
+            TraitOp::Eq,
+            "low",
+        ));
+        assert_eq!(
+            ids(&store.query_traits(&low).unwrap()),
+            [
+                "gts.x.core.events.topic.v1~",
+                "gts.x.core.events.topic.v1~x.app.metrics.topic.v1~",
+            ]
+        );
+
+        let query = TraitQuery::parse(
+            "gts.x.core.events.topic.v1~* where priority in [high, critical] and partitions >= 10",
+        )
+        .unwrap();
+        assert_eq!(
+            ids(&store.query_traits(&query).unwrap()),
+            ["gts.x.core.events.topic.v1~x.app.orders.topic.v1~"]
+        );
+    }
+
+    #[test]
+    fn test_query_traits_invalid_pattern() {
+        let mut store = store_with_topics();
+        assert!(matches!(
+            store.query_traits(&TraitQuery::new("not-a-pattern")),
+            Err(TraitQueryError::InvalidPattern { .. })
+        ));
+    }
+
+    #[test]
+    fn test_filter_comparisons() {
+        let traits = json!({"retention": "P1DT12H", "partitions": 8, "compacted": true})
+            .as_object()
+            .unwrap()
+            .clone();
+        let holds = |filter: &str| filter.parse::<TraitFilter>().unwrap().matches(&traits);
+
+        assert!(holds("retention > PT24H"));
+        assert!(holds("retention <= P1.5D"));
+        assert!(holds("retention != P2D"));
+        assert!(holds("partitions < 10"));
+        assert!(holds("partitions = 8"));
+        assert!(holds("compacted = true"));
+        assert!(!holds("partitions > many"));
+        assert!(!holds("missing != 1"));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("P30D"), Some(30.0 * 86_400.0));
+        assert_eq!(parse_duration("PT1H30M"), Some(5400.0));
+        assert_eq!(parse_duration("P1W"), Some(7.0 * 86_400.0));
+        assert_eq!(parse_duration("P1M"), Some(30.0 * 86_400.0));
+        for bad in ["P", "PT", "30D", "P30", "P1X", "high"] {
+            assert_eq!(parse_duration(bad), None, "{bad}");
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Transactional multi-entity updates of a [`GtsStore`].
+//!
+//! Entities registered one by one are validated against a store that holds
+//! only part of the update: a new schema version is checked before the
+//! instances moved to it exist, and an instance failing halfway leaves the
+//! store with the schema but not the rest. A [`StoreTransaction`] stages the
+//! whole update, validates every staged entity against the store as it would
+//! be after the update, and applies all of it or nothing:
+//!
+//! ```
+//! use gts::{GtsConfig, GtsEntity, GtsStore};
+//! use serde_json::json;
+//!
+//! let entity = |content: serde_json::Value| {
+//!     let cfg = GtsConfig::default();
+//!     GtsEntity::new(None, None, &content, Some(&cfg), None, false, String::new(), None, None)
+//! };
+//! let mut store = GtsStore::new(None);
+//! let ids = store
+//!     .transaction()
+//!     .put(entity(json!({
+//!         "$id": "gts://gts.x.core.events.type.v1~",
+//!         "$schema": "http://json-schema.org/draft-07/schema#",
+//!         "type": "object",
+//!         "required": ["id", "name"]
+//!     })))
+//!     .put(entity(json!({"id": "gts.x.core.events.type.v1~x.app._.orders.v1", "name": "orders"})))
+//!     .commit()
+//!     .unwrap();
+//! assert_eq!(ids.len(), 2);
+//!
+//! // The second instance misses `name`: neither instance is added
+//! let result = store
+//!     .transaction()
+//!     .put(entity(json!({"id": "gts.x.core.events.type.v1~x.app._.users.v1", "name": "users"})))
+//!     .put(entity(json!({"id": "gts.x.core.events.type.v1~x.app._.carts.v1"})))
+//!     .commit();
+//! assert!(result.is_err());
+//! assert!(store.get("gts.x.core.events.type.v1~x.app._.users.v1").is_none());
+//! ```
+
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::entities::GtsEntity;
+use crate::retired::{RETIRED_IDS_INSTANCE_ID, RetiredIdsDocument};
+use crate::storage::encode_entity;
+use crate::store::{GtsStore, StoreError};
+
+#[derive(Debug, Error)]
+pub enum TransactionError {
+    #[error("Entity '{0}' is staged more than once in the transaction")]
+    DuplicateEntity(String),
+    #[error("Transaction rolled back, '{id}' is invalid: {source}")]
+    Invalid { id: String, source: StoreError },
+    #[error(transparent)]
+    Store(#[from] StoreError),
+}
+
+/// Entities to add to or replace in a store at once, see [`crate::transaction`].
+#[must_use = "a transaction does nothing until committed"]
+pub struct StoreTransaction<'a> {
+    store: &'a mut GtsStore,
+    staged: Vec<GtsEntity>,
+}
+
+impl GtsStore {
+    /// Start a transaction on this store.
+    pub fn transaction(&mut self) -> StoreTransaction<'_> {
+        StoreTransaction {
+            store: self,
+            staged: Vec::new(),
+        }
+    }
+}
+
+impl StoreTransaction<'_> {
+    /// Stage `entity`, replacing the store's entity with the same ID on commit.
+    pub fn put(mut self, entity: GtsEntity) -> Self {
+        self.staged.push(entity);
+        self
+    }
+
+    /// The number of staged entities.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.staged.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.staged.is_empty()
+    }
+
+    /// Validate the staged entities against the store as updated with all of
+    /// them and, if every one is valid, keep the update and write it to the
+    /// store's storage. Returns the IDs of the staged entities in order.
+    ///
+    /// Schemas are validated as by [`GtsOps::validate_schema`](crate::GtsOps::validate_schema)
+    /// (meta-schema, refs, chain and traits) and instances against their
+    /// schema chain. Entities of the store the transaction does not stage are
+    /// not validated again.
+    ///
+    /// # Errors
+    /// Leaves the store as it was and returns:
+    /// - `TransactionError::Store` with `StoreError::InvalidEntity` if an
+    ///   entity has no ID, or `StoreError::RetiredIds` if an ID is retired
+    ///   (also by a staged retired-IDs registry) or a staged registry is
+    ///   invalid
+    /// - `TransactionError::DuplicateEntity` if an ID is staged twice
+    /// - `TransactionError::Invalid` with the first entity failing validation
+    /// - `TransactionError::Store` with `StoreError::Storage` if the update
+    ///   cannot be written to the storage
+    pub fn commit(self) -> Result<Vec<String>, TransactionError> {
+        let Self { store, staged } = self;
+        let ids = check_ids(store, &staged)?;
+
+        let mut previous = Vec::with_capacity(staged.len());
+        for (id, entity) in ids.iter().zip(staged) {
+            // Fetch the entity being replaced from the reader or storage, so
+            // a rollback restores it rather than dropping it
+            store.get(id);
+            previous.push(store.swap_entity(id, Some(entity)));
+        }
+        if let Err(e) = validate_staged(store, &ids) {
+            restore(store, &ids, previous);
+            return Err(e);
+        }
+        if let Err(e) = write_staged(store, &ids, &previous) {
+            restore(store, &ids, previous);
+            return Err(e.into());
+        }
+        if ids.iter().any(|id| id == RETIRED_IDS_INSTANCE_ID) {
+            store.refresh_retired_ids();
+        }
+        Ok(ids)
+    }
+}
+
+/// The IDs of `staged`, checked to be present, unique and not retired.
+fn check_ids(store: &GtsStore, staged: &[GtsEntity]) -> Result<Vec<String>, TransactionError> {
+    let mut retired = store.retired_ids().clone();
+    if let Some(registry) = staged
+        .iter()
+        .find(|entity| entity.effective_id().as_deref() == Some(RETIRED_IDS_INSTANCE_ID))
+    {
+        let registry = RetiredIdsDocument::from_value(&registry.content)
+            .and_then(|doc| doc.registry())
+            .map_err(StoreError::from)?;
+        retired.extend(registry);
+    }
+    let mut ids = Vec::with_capacity(staged.len());
+    let mut seen = HashSet::new();
+    for entity in staged {
+        let id = entity.effective_id().ok_or(StoreError::InvalidEntity)?;
+        if !seen.insert(id.clone()) {
+            return Err(TransactionError::DuplicateEntity(id));
+        }
+        retired.check(&id).map_err(StoreError::from)?;
+        ids.push(id);
+    }
+    Ok(ids)
+}
+
+fn validate_staged(store: &mut GtsStore, ids: &[String]) -> Result<(), TransactionError> {
+    for id in ids {
+        let is_schema = store.get(id).is_some_and(|entity| entity.is_schema);
+        let result = if is_schema {
+            store
+                .validate_schema(id)
+                .and_then(|()| store.validate_schema_chain(id))
+                .and_then(|()| store.validate_schema_traits(id))
+        } else {
+            store.validate_instance(id)
+        };
+        result.map_err(|source| TransactionError::Invalid {
+            id: id.clone(),
+            source,
+        })?;
+    }
+    Ok(())
+}
+
+/// Write the staged entities to the store's storage; on failure, put the
+/// entries written so far back as they were, as far as the backend lets us.
+fn write_staged(
+    store: &mut GtsStore,
+    ids: &[String],
+    previous: &[Option<GtsEntity>],
+) -> Result<(), StoreError> {
+    let encoded: Vec<Vec<u8>> = ids
+        .iter()
+        .filter_map(|id| store.get(id).map(encode_entity))
+        .collect();
+    let Some(storage) = store.storage_mut() else {
+        return Ok(());
+    };
+    for (written, (id, bytes)) in ids.iter().zip(encoded).enumerate() {
+        if let Err(e) = storage.put(id, bytes) {
+            for (id, entity) in ids.iter().zip(previous).take(written) {
+                let undone = match entity {
+                    Some(entity) => storage.put(id, encode_entity(entity)),
+                    None => storage.remove(id),
+                };
+                if let Err(e) = undone {
+                    tracing::warn!("Cannot roll back '{id}' in storage: {e}");
+                }
+            }
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+fn restore(store: &mut GtsStore, ids: &[String], previous: Vec<Option<GtsEntity>>) {
+    for (id, entity) in ids.iter().zip(previous) {
+        store.swap_entity(id, entity);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::storage::{GtsMemoryStorage, GtsStorage};
+    use crate::test_support::{entity_from_json, schema_json as schema, store_from_json};
+    use serde_json::json;
+
+    const SCHEMA_V1: &str = "gts.x.core.events.type.v1~";
+    const SCHEMA_V1_1: &str = "gts.x.core.events.type.v1.1~";
+    const ORDERS: &str = "gts.x.core.events.type.v1.1~x.app._.orders.v1";
+
+    fn store() -> (GtsStore, GtsMemoryStorage) {
+        let storage = GtsMemoryStorage::new();
+        let mut store = store_from_json(&[schema(SCHEMA_V1, &["id"])]);
+        store.set_storage(Box::new(storage.clone()));
+        (store, storage)
+    }
+
+    #[test]
+    fn test_commit_validates_post_state() {
+        let (mut store, storage) = store();
+        // The instance is only valid once the new schema version is in
+        let ids = store
+            .transaction()
+            .put(entity_from_json(&json!({"id": ORDERS, "name": "orders"})))
+            .put(entity_from_json(&schema(SCHEMA_V1_1, &["id", "name"])))
+            .commit()
+            .unwrap();
+        assert_eq!(ids, [ORDERS, SCHEMA_V1_1]);
+        assert!(store.get(ORDERS).is_some());
+        assert!(storage.get(SCHEMA_V1_1).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_invalid_entity_rolls_back_everything() {
+        let (mut store, storage) = store();
+        store
+            .transaction()
+            .put(entity_from_json(&schema(SCHEMA_V1_1, &["id"])))
+            .put(entity_from_json(&json!({"id": ORDERS})))
+            .commit()
+            .unwrap();
+
+        // Tightening the schema invalidates the instance replaced with it
+        let err = store
+            .transaction()
+            .put(entity_from_json(&schema(SCHEMA_V1_1, &["id", "name"])))
+            .put(entity_from_json(&json!({"id": ORDERS, "note": "no name"})))
+            .commit()
+            .unwrap_err();
+        assert!(matches!(&err, TransactionError::Invalid { id, .. } if id == ORDERS));
+        assert_eq!(store.get(ORDERS).unwrap().content, json!({"id": ORDERS}));
+        assert_eq!(
+            store.get_schema_content(SCHEMA_V1_1).unwrap()["required"],
+            json!(["id"])
+        );
+        let stored = storage.get(ORDERS).unwrap().unwrap();
+        assert!(!String::from_utf8_lossy(&stored).contains("no name"));
+    }
+
+    #[test]
+    fn test_commit_rejects_duplicate_and_missing_ids() {
+        let (mut store, _) = store();
+        let err = store
+            .transaction()
+            .put(entity_from_json(&schema(SCHEMA_V1_1, &["id"])))
+            .put(entity_from_json(&schema(SCHEMA_V1_1, &["id", "name"])))
+            .commit()
+            .unwrap_err();
+        assert!(matches!(err, TransactionError::DuplicateEntity(id) if id == SCHEMA_V1_1));
+
+        let err = store
+            .transaction()
+            .put(entity_from_json(&schema(SCHEMA_V1_1, &["id"])))
+            .put(entity_from_json(&json!({"name": "anonymous"})))
+            .commit()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            TransactionError::Store(StoreError::InvalidEntity)
+        ));
+        assert!(store.get(SCHEMA_V1_1).is_none());
+    }
+}
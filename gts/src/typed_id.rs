@@ -0,0 +1,103 @@
+//! GTS ID newtypes restricted to one type family.
+//!
+//! `#[derive(GtsTypedId)]` (from `gts-macros`) turns a `struct TopicId(GtsID);`
+//! into an ID type that only accepts IDs matching a wildcard pattern such as
+//! `gts.x.core.events.topic.v1~*`, so APIs can ask for "a topic instance ID"
+//! instead of any string. This module holds the runtime side of the derive.
+
+use thiserror::Error;
+
+use crate::gts::{GtsError, GtsID, GtsWildcard};
+
+/// Why a string was rejected by a [`GtsTypedId`] constructor.
+#[derive(Debug, Error)]
+pub enum GtsTypedIdError {
+    /// The string is not a valid GTS ID.
+    #[error(transparent)]
+    Invalid(#[from] GtsError),
+
+    /// The ID is valid but does not match the family pattern of the type.
+    #[error("GTS ID '{id}' does not match '{pattern}'")]
+    OutsideFamily { id: String, pattern: &'static str },
+}
+
+/// A GTS ID newtype whose values all match [`Self::PATTERN`].
+///
+/// Usually derived:
+///
+/// ```ignore
+/// use gts::{GtsID, GtsTypedId};
+/// use gts_macros::GtsTypedId;
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash, GtsTypedId)]
+/// #[gts_typed_id(pattern = "gts.x.core.events.topic.v1~*")]
+/// pub struct TopicId(GtsID);
+///
+/// let topic = TopicId::new("gts.x.core.events.topic.v1~x.commerce._.orders.v1")?;
+/// assert!(TopicId::new("gts.x.core.events.type.v1~").is_err());
+/// ```
+pub trait GtsTypedId: Sized {
+    /// Wildcard pattern (or exact ID) every value matches.
+    const PATTERN: &'static str;
+
+    /// Wrap an ID already known to match [`Self::PATTERN`].
+    fn from_gts_id_unchecked(id: GtsID) -> Self;
+
+    /// The wrapped ID.
+    fn gts_id(&self) -> &GtsID;
+
+    /// Parse `id`, checking that it matches [`Self::PATTERN`].
+    ///
+    /// # Errors
+    /// Returns [`GtsTypedIdError::Invalid`] for a malformed ID and
+    /// [`GtsTypedIdError::OutsideFamily`] for an ID of another family.
+    fn parse_typed(id: &str) -> Result<Self, GtsTypedIdError> {
+        parse_typed_id(id, Self::PATTERN).map(Self::from_gts_id_unchecked)
+    }
+}
+
+/// Parse `id` and check that it matches `pattern`.
+///
+/// # Errors
+/// See [`GtsTypedId::parse_typed`]. A malformed `pattern` is reported as
+/// [`GtsTypedIdError::Invalid`]; derived types validate theirs at compile time.
+pub fn parse_typed_id(id: &str, pattern: &'static str) -> Result<GtsID, GtsTypedIdError> {
+    let gts_id = GtsID::new(id)?;
+    let wildcard = GtsWildcard::new(pattern)?;
+    if gts_id.wildcard_match(&wildcard) {
+        Ok(gts_id)
+    } else {
+        Err(GtsTypedIdError::OutsideFamily {
+            id: gts_id.id,
+            pattern,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_typed_id_checks_family() {
+        let pattern = "gts.x.core.events.topic.v1~*";
+        let id =
+            parse_typed_id("gts.x.core.events.topic.v1~x.commerce._.orders.v1", pattern).unwrap();
+        assert!(!id.is_type());
+
+        assert!(matches!(
+            parse_typed_id("gts.x.core.events.type.v1~x.commerce._.orders.v1", pattern),
+            Err(GtsTypedIdError::OutsideFamily { .. })
+        ));
+        assert!(matches!(
+            parse_typed_id("gts.x.core.events", pattern),
+            Err(GtsTypedIdError::Invalid(_))
+        ));
+
+        // Without a wildcard the pattern names exactly one ID
+        assert!(
+            parse_typed_id("gts.x.core.events.topic.v1~", "gts.x.core.events.topic.v1~").is_ok()
+        );
+    }
+}
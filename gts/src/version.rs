@@ -0,0 +1,182 @@
+//! Version-aware comparison of GTS IDs.
+//!
+//! Two IDs are of the *same type* when they differ only in the version of
+//! their [defining segment](GtsID::defining_segment): `gts.x.app.entities.user.v1~`
+//! and `gts.x.app.entities.user.v1.2~` are, `…user.v1~` and `…order.v1~` are
+//! not. Only IDs of the same type have a version order.
+//!
+//! Following the GTS versioning rules, a newer minor version of the same
+//! major is a compatible upgrade; a new major version is not.
+//!
+//! ```
+//! use gts::GtsID;
+//! use gts::version::{is_compatible_upgrade, latest_of};
+//!
+//! let current = GtsID::new("gts.x.app.entities.user.v1.1~").unwrap();
+//! let found = ["v1~", "v1.3~", "v2~", "v1.2~"]
+//!     .map(|v| GtsID::new(&format!("gts.x.app.entities.user.{v}")).unwrap());
+//!
+//! let newest = latest_of(found.iter().filter(|id| is_compatible_upgrade(&current, id)));
+//! assert_eq!(newest.unwrap().id, "gts.x.app.entities.user.v1.3~");
+//! assert_eq!(latest_of(&found).unwrap().id, "gts.x.app.entities.user.v2~");
+//! ```
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+use crate::gts::{GtsID, GtsIdSegment};
+
+impl GtsID {
+    /// Whether `other` differs from this ID at most in the version of the
+    /// defining segment.
+    #[must_use]
+    pub fn same_type_as(&self, other: &GtsID) -> bool {
+        self.is_type() == other.is_type()
+            && self.gts_id_segments.len() == other.gts_id_segments.len()
+            && self.defining_index() == other.defining_index()
+            && self
+                .gts_id_segments
+                .iter()
+                .zip(&other.gts_id_segments)
+                .enumerate()
+                .all(|(i, (a, b))| {
+                    if i == self.defining_index() {
+                        same_name(a, b)
+                    } else {
+                        a.segment == b.segment
+                    }
+                })
+    }
+
+    /// Order by version against an ID of the [same type](Self::same_type_as);
+    /// `None` for IDs of another type.
+    #[must_use]
+    pub fn version_cmp(&self, other: &GtsID) -> Option<Ordering> {
+        self.same_type_as(other)
+            .then(|| self.version().cmp(&other.version()))
+    }
+
+    fn defining_index(&self) -> usize {
+        self.gts_id_segments
+            .iter()
+            .rposition(|s| !s.is_uuid_tail)
+            .unwrap_or(0)
+    }
+}
+
+fn same_name(a: &GtsIdSegment, b: &GtsIdSegment) -> bool {
+    a.vendor == b.vendor
+        && a.package == b.package
+        && a.namespace == b.namespace
+        && a.type_name == b.type_name
+}
+
+/// The newest of `ids` of the same type as the first one; IDs of other types
+/// are ignored. `None` for no IDs.
+pub fn latest_of<I, T>(ids: I) -> Option<T>
+where
+    I: IntoIterator<Item = T>,
+    T: Borrow<GtsID>,
+{
+    let mut ids = ids.into_iter();
+    let mut latest = ids.next()?;
+    for id in ids {
+        if latest.borrow().version_cmp(id.borrow()) == Some(Ordering::Less) {
+            latest = id;
+        }
+    }
+    Some(latest)
+}
+
+/// Whether moving from `from` to `to` keeps compatibility: the same type and
+/// major version, and a minor version no older than `from`'s (`v1~` counts as
+/// `v1.0~`).
+#[must_use]
+pub fn is_compatible_upgrade(from: &GtsID, to: &GtsID) -> bool {
+    let (from_version, to_version) = (from.version(), to.version());
+    from.same_type_as(to)
+        && from_version.major == to_version.major
+        && from_version.minor.unwrap_or(0) <= to_version.minor.unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(id: &str) -> GtsID {
+        GtsID::new(id).unwrap()
+    }
+
+    #[test]
+    fn test_same_type_ignores_only_the_defining_version() {
+        let user = id("gts.x.app.entities.user.v1~");
+        assert!(user.same_type_as(&id("gts.x.app.entities.user.v2.3~")));
+        assert!(!user.same_type_as(&id("gts.x.app.entities.order.v1~")));
+        assert!(!user.same_type_as(&id("gts.x.app.entities.user.v1~x.app._.admin.v1~")));
+
+        let admin = id("gts.x.app.entities.user.v1~x.app._.admin.v1~");
+        assert!(admin.same_type_as(&id("gts.x.app.entities.user.v1~x.app._.admin.v1.1~")));
+        // The base type is part of the type, its version included
+        assert!(!admin.same_type_as(&id("gts.x.app.entities.user.v2~x.app._.admin.v1~")));
+
+        let instance = id("gts.x.core.events.topic.v1~x.commerce._.orders.v1");
+        assert!(!instance.same_type_as(&id("gts.x.core.events.topic.v1~x.commerce._.orders.v1~")));
+    }
+
+    #[test]
+    fn test_version_cmp() {
+        let v1_1 = id("gts.x.app.entities.user.v1.1~");
+        assert_eq!(
+            v1_1.version_cmp(&id("gts.x.app.entities.user.v1.2~")),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            v1_1.version_cmp(&id("gts.x.app.entities.user.v1~")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(v1_1.version_cmp(&v1_1), Some(Ordering::Equal));
+        assert_eq!(v1_1.version_cmp(&id("gts.x.app.entities.order.v2~")), None);
+    }
+
+    #[test]
+    fn test_latest_of() {
+        let ids = [
+            id("gts.x.app.entities.user.v1.1~"),
+            id("gts.x.app.entities.order.v9~"),
+            id("gts.x.app.entities.user.v2~"),
+            id("gts.x.app.entities.user.v1.4~"),
+        ];
+        assert_eq!(latest_of(&ids).unwrap().id, "gts.x.app.entities.user.v2~");
+        assert_eq!(
+            latest_of(ids.iter().skip(1)).unwrap().id,
+            "gts.x.app.entities.order.v9~"
+        );
+        assert!(latest_of(Vec::<GtsID>::new()).is_none());
+    }
+
+    #[test]
+    fn test_is_compatible_upgrade() {
+        let v1_1 = id("gts.x.app.entities.user.v1.1~");
+        assert!(is_compatible_upgrade(
+            &v1_1,
+            &id("gts.x.app.entities.user.v1.3~")
+        ));
+        assert!(is_compatible_upgrade(&v1_1, &v1_1));
+        assert!(!is_compatible_upgrade(
+            &v1_1,
+            &id("gts.x.app.entities.user.v1~")
+        ));
+        assert!(!is_compatible_upgrade(
+            &v1_1,
+            &id("gts.x.app.entities.user.v2~")
+        ));
+        assert!(!is_compatible_upgrade(
+            &v1_1,
+            &id("gts.x.app.entities.order.v1.3~")
+        ));
+        assert!(is_compatible_upgrade(
+            &id("gts.x.app.entities.user.v1~"),
+            &id("gts.x.app.entities.user.v1.0~")
+        ));
+    }
+}
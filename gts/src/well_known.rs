@@ -0,0 +1,171 @@
+//! Well-known instances embedded in code.
+//!
+//! `#[gts_well_known_instance]` (from `gts-macros`) checks the ID and JSON
+//! body of a constant at compile time and turns it into a
+//! [`GtsWellKnownInstance`], so instances every deployment needs (topics,
+//! default settings) ship with the code instead of as separate files:
+//!
+//! ```ignore
+//! use gts::GtsWellKnownInstance;
+//! use gts_macros::gts_well_known_instance;
+//!
+//! #[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.orders.v1")]
+//! pub const ORDERS_TOPIC: &str = r#"{"name": "orders", "retention": "P30D"}"#;
+//!
+//! assert_eq!(ORDERS_TOPIC.schema_id(), Some("gts.x.core.events.topic.v1~"));
+//! ops.add_entity(&ORDERS_TOPIC.value()?, true);
+//! ```
+//!
+//! Instances built with loops or shared constants come from a function
+//! returning `serde_json::Value`; only the ID is checked at compile time, the
+//! body when [`GtsWellKnownInstance::value`] calls the function:
+//!
+//! ```ignore
+//! #[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.payments.v1")]
+//! pub fn payments_topic() -> serde_json::Value {
+//!     serde_json::json!({"name": "payments", "retention": DEFAULT_RETENTION})
+//! }
+//!
+//! // The function is kept; the instance is `PAYMENTS_TOPIC`
+//! ops.add_entity(&PAYMENTS_TOPIC.value()?, true);
+//! ```
+//!
+//! A constant can also hold a `gts_instance_json!` body in `json!` syntax,
+//! kept as JSON and checked at compile time when it only holds literals:
+//!
+//! ```ignore
+//! #[gts_well_known_instance(id = "gts.x.core.events.topic.v1~x.commerce._.refunds.v1")]
+//! pub const REFUNDS_TOPIC: GtsWellKnownBody = gts_instance_json! {
+//!     "name": "refunds",
+//!     "retention": DEFAULT_RETENTION
+//! };
+//! ```
+
+use serde_json::Value;
+use thiserror::Error;
+
+/// Why the body of a [`GtsWellKnownInstance`] could not be turned into an
+/// instance.
+#[derive(Debug, Error)]
+pub enum GtsWellKnownInstanceError {
+    #[error("Invalid JSON in well-known instance '{id}': {source}")]
+    InvalidJson {
+        id: &'static str,
+        source: serde_json::Error,
+    },
+    #[error("Well-known instance '{id}' is not a JSON object")]
+    NotAnObject { id: &'static str },
+    #[error("Well-known instance '{id}' has a different id: {found}")]
+    IdMismatch { id: &'static str, found: Value },
+}
+
+/// Where the JSON of a [`GtsWellKnownInstance`] comes from.
+#[derive(Debug, Clone, Copy)]
+pub enum GtsWellKnownBody {
+    /// A JSON object as written, checked at compile time
+    Json(&'static str),
+    /// A function building the JSON object, checked when called
+    Build(fn() -> Value),
+}
+
+/// An instance whose ID is fixed at compile time.
+#[derive(Debug, Clone, Copy)]
+pub struct GtsWellKnownInstance {
+    /// Instance ID, e.g. `gts.x.core.events.topic.v1~x.commerce._.orders.v1`
+    pub id: &'static str,
+    /// The JSON object, with or without its `id`
+    pub body: GtsWellKnownBody,
+}
+
+impl GtsWellKnownInstance {
+    /// The schema ID of the instance (its ID up to the last `~`), or `None`
+    /// for a single-segment ID.
+    #[must_use]
+    pub fn schema_id(&self) -> Option<&'static str> {
+        self.id.rfind('~').map(|end| &self.id[..=end])
+    }
+
+    /// The instance as JSON, with `id` set to [`Self::id`].
+    ///
+    /// # Errors
+    /// Returns an error if the body is not a JSON object or has another
+    /// `id`. The macro rules this out at compile time for JSON literals.
+    pub fn value(&self) -> Result<Value, GtsWellKnownInstanceError> {
+        let id = self.id;
+        let value = match self.body {
+            GtsWellKnownBody::Json(json) => serde_json::from_str(json)
+                .map_err(|source| GtsWellKnownInstanceError::InvalidJson { id, source })?,
+            GtsWellKnownBody::Build(build) => build(),
+        };
+        let Value::Object(mut map) = value else {
+            return Err(GtsWellKnownInstanceError::NotAnObject { id });
+        };
+        match map.get("id") {
+            Some(Value::String(found)) if found == id => {}
+            Some(found) => {
+                return Err(GtsWellKnownInstanceError::IdMismatch {
+                    id,
+                    found: found.clone(),
+                });
+            }
+            None => {
+                map.insert("id".to_owned(), Value::String(id.to_owned()));
+            }
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ORDERS_TOPIC: GtsWellKnownInstance = GtsWellKnownInstance {
+        id: "gts.x.core.events.topic.v1~x.commerce._.orders.v1",
+        body: GtsWellKnownBody::Json(r#"{"name": "orders"}"#),
+    };
+
+    #[test]
+    fn test_value_carries_the_id() {
+        assert_eq!(
+            ORDERS_TOPIC.value().unwrap(),
+            json!({
+                "id": "gts.x.core.events.topic.v1~x.commerce._.orders.v1",
+                "name": "orders"
+            })
+        );
+        assert_eq!(
+            ORDERS_TOPIC.schema_id(),
+            Some("gts.x.core.events.topic.v1~")
+        );
+
+        let single = GtsWellKnownInstance {
+            id: "gts.x.core.settings.defaults.v1",
+            body: GtsWellKnownBody::Json("{}"),
+        };
+        assert_eq!(single.schema_id(), None);
+    }
+
+    #[test]
+    fn test_built_value_is_checked() {
+        let built = |build| GtsWellKnownInstance {
+            id: "gts.x.core.events.topic.v1~x.commerce._.orders.v1",
+            body: GtsWellKnownBody::Build(build),
+        };
+        assert_eq!(
+            built(|| json!({"name": "orders"})).value().unwrap()["id"],
+            "gts.x.core.events.topic.v1~x.commerce._.orders.v1"
+        );
+        assert!(matches!(
+            built(|| json!(["orders"])).value(),
+            Err(GtsWellKnownInstanceError::NotAnObject { .. })
+        ));
+        let err = built(|| json!({"id": "gts.x.core.events.topic.v1~x.commerce._.payments.v1"}))
+            .value()
+            .unwrap_err();
+        assert!(matches!(err, GtsWellKnownInstanceError::IdMismatch { .. }));
+        assert!(err.to_string().contains("payments"), "{err}");
+    }
+}